@@ -1,33 +1,186 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = env!("CARGO_BIN_NAME"))]
 #[command(about = "A Git extension tool")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 pub struct Cli {
+    /// Answer yes to all confirmation prompts (also via XGIT_ASSUME_YES or `git config xgit.assumeYes`)
+    #[arg(short = 'y', long, global = true)]
+    pub yes: bool,
+
+    /// Show what would happen without changing the repository or remotes
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Print where the command spent its time in git and GitHub operations to stderr
+    #[arg(long, global = true)]
+    pub profile: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Stage files (passthrough to git add unless --interactive) (alias: a)
+    #[command(alias = "a")]
+    Add {
+        /// Pick files to stage from a fuzzy-filtered list with a diff preview
+        #[arg(short, long)]
+        interactive: bool,
+        /// Arguments to pass to git add
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Branch operations (alias: b)
     #[command(alias = "b")]
     Branch {
+        /// Create and switch to a branch named by AI after a short task description, or after
+        /// the staged changes when none is given
+        #[arg(
+            long,
+            value_name = "TASK",
+            num_args = 0..=1,
+            default_missing_value = "",
+            conflicts_with_all = ["prune_merged", "stats", "porcelain", "format"]
+        )]
+        new: Option<String>,
         /// Clean up local branches that have been merged and deleted remotely
         #[arg(long)]
         prune_merged: bool,
+        /// With --prune-merged, also delete each pruned branch's upstream branch on its remote
+        #[arg(long, requires = "prune_merged")]
+        remote: bool,
         /// Show current branch and associated GitHub PR information
         #[arg(long)]
         stats: bool,
-        /// Show what would be pruned without actually deleting branches
+        /// Print stable tab-separated output for scripts instead of the interactive/styled view
+        #[arg(long)]
+        porcelain: bool,
+        /// Print each branch using a template such as "{name}\t{ahead}/{behind}\t{pr.state}"
+        #[arg(long, conflicts_with_all = ["prune_merged", "porcelain", "watch"])]
+        format: Option<String>,
+        /// Keep --stats on screen, refreshing on repository changes
+        #[arg(long, requires = "stats")]
+        watch: bool,
+        /// Seconds between forced refreshes of remote PR information in --watch mode
+        #[arg(long, default_value = "30", requires = "watch")]
+        interval: u64,
+        /// With --stats or --format, ask GitHub again instead of using cached PR information
+        #[arg(long, conflicts_with = "prune_merged")]
+        refresh: bool,
+    },
+    /// Switch branches, creating or tracking remote branches as needed (alias: sw)
+    #[command(alias = "sw")]
+    Switch {
+        /// Branch to switch to, `-` for the previous one (with --detach, the commit to detach at)
+        #[arg(required_unless_present_any = ["detach", "orphan"])]
+        branch: Option<String>,
+        /// Commit or remote branch to create the branch from (with -c), e.g. origin/feature
+        start_point: Option<String>,
+        /// Create the branch before switching
+        #[arg(short = 'c', long)]
+        create: bool,
+        /// Check out a commit without a branch (passes through to git switch)
+        #[arg(long, conflicts_with_all = ["create", "start_point", "autostash"])]
+        detach: bool,
+        /// Start a new branch with no commits (passes through to git switch)
+        #[arg(long, value_name = "BRANCH", conflicts_with_all = ["branch", "create", "detach", "autostash"])]
+        orphan: Option<String>,
+        /// Discard uncommitted changes to tracked files
+        #[arg(short, long, conflicts_with = "autostash")]
+        force: bool,
+        /// Stash uncommitted changes and re-apply them after switching (also via `git config xgit.switch.autostash`)
+        #[arg(long)]
+        autostash: bool,
+    },
+    /// Replay the current branch's commits on top of another branch (its upstream by default;
+    /// options xg doesn't handle pass through to git rebase)
+    #[command(group(clap::ArgGroup::new("action").args(["continue_", "skip", "abort"])))]
+    Rebase {
+        /// Edit the commits after the new base in a terminal list: reorder, reword, squash, fixup
+        /// or drop them
+        #[arg(short, long, conflicts_with = "action")]
+        interactive: bool,
+        /// Commit the resolved conflicts and replay the remaining commits
+        #[arg(long = "continue")]
+        continue_: bool,
+        /// Drop the commit that stopped the rebase and replay the remaining commits
+        #[arg(long)]
+        skip: bool,
+        /// Stop the rebase and restore the branch as it was before
         #[arg(long)]
-        dry_run: bool,
+        abort: bool,
+        /// Branch or revision to rebase onto, e.g. main or origin/main, or arguments to pass
+        /// to git rebase
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Apply a commit on top of HEAD, keeping its author and message (anything but a single
+    /// commit passes through to git cherry-pick)
+    CherryPick {
+        /// Commit to pick, e.g. a SHA, a branch name or feature~2, or arguments to pass to
+        /// git cherry-pick
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Fetch and prune every remote, fast-forward the current branch and summarize each branch
+    Sync {
+        /// Also delete local branches that were merged (like `xg branch --prune-merged`)
+        #[arg(long)]
+        prune_merged: bool,
     },
-    /// Create a commit (passthrough to git commit) (alias: c)
+    /// Set uncommitted changes aside and bring them back later (saves when no subcommand is
+    /// given; other subcommands and options pass through to git stash)
+    #[command(args_conflicts_with_subcommands = true)]
+    Stash {
+        #[command(subcommand)]
+        command: Option<StashCommand>,
+        /// Options to pass to git stash, e.g. -u or --keep-index
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Create, delete, list and push tags (lists when no subcommand is given; other arguments
+    /// pass through to git tag)
+    #[command(args_conflicts_with_subcommands = true)]
+    Tag {
+        #[command(subcommand)]
+        command: Option<TagCommand>,
+        /// Arguments to pass to git tag, e.g. -a v1.2.0 -m "Release" or -l "v1.*"
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Create a commit, with an AI-suggested message when none is given; flags xg doesn't
+    /// handle pass through to git commit. `--issue` links an assigned GitHub issue (alias: c)
     #[command(alias = "c")]
     Commit {
-        /// Arguments to pass to git commit
+        /// Options such as -m, --amend or --issue, or arguments to pass to git commit
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Push to a remote (passthrough to git push), setting the upstream of a branch pushed
+    /// for the first time
+    Push {
+        /// Arguments to pass to git push
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Fetch the current branch's upstream and merge or rebase it in
+    #[command(group(clap::ArgGroup::new("strategy").args(["rebase", "no_rebase", "ff_only"])))]
+    Pull {
+        /// Replay local commits on top of the remote branch instead of merging
+        #[arg(short, long)]
+        rebase: bool,
+        /// Merge even when `pull.rebase` is set
+        #[arg(long)]
+        no_rebase: bool,
+        /// Only fast-forward; fail when the branches have diverged
+        #[arg(long)]
+        ff_only: bool,
+        /// Remote to pull from and remote branch to pull (default: the upstream, then origin
+        /// and the current branch's name), or arguments to pass to git pull
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
@@ -37,10 +190,486 @@ pub enum Commands {
         #[arg(long, value_names = ["PR_NUMBER", "COMMIT_SHA"], num_args = 2)]
         repair: Option<Vec<String>>,
     },
+    /// Work with the current branch's pull request
+    Pr {
+        #[command(subcommand)]
+        command: PrCommand,
+    },
+    /// Stacked branches: chains of branches that each build on the one below, with one pull
+    /// request per branch
+    Stack {
+        #[command(subcommand)]
+        command: StackCommand,
+    },
+    /// Switch to the branch on top of the current one in its stack
+    Up {
+        /// How many levels to move
+        #[arg(default_value = "1")]
+        steps: usize,
+    },
+    /// Switch to the branch the current one is stacked on
+    Down {
+        /// How many levels to move
+        #[arg(default_value = "1")]
+        steps: usize,
+    },
+    /// Open the current branch's pull request, the branch, or a file on GitHub in the browser
+    Open {
+        /// File to open at the current commit, with an optional line or range: PATH[:LINE[-END]]
+        target: Option<String>,
+        /// Open the branch even when it has a pull request
+        #[arg(long, conflicts_with = "target")]
+        branch: bool,
+        /// Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Have AI review the staged changes for potential bugs, missing tests and style issues
+    Review {
+        /// Review everything the branch changed since BASE (default: the trunk branch)
+        /// instead of the staged changes
+        #[arg(
+            long,
+            value_name = "BASE",
+            num_args = 0..=1,
+            default_missing_value = ""
+        )]
+        branch: Option<String>,
+        /// Only report findings at least this severe
+        #[arg(long, default_value = "low", value_parser = ["low", "medium", "high"])]
+        min_severity: String,
+        /// Print the findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show staged changes with syntax highlighting (alias: sd)
+    #[command(alias = "sd")]
+    ShowDiff {
+        /// Show the changes introduced by this commit instead of staged changes
+        rev: Option<String>,
+        /// Render old and new versions side by side
+        #[arg(long)]
+        side_by_side: bool,
+        /// Disable word-level highlighting within modified lines
+        #[arg(long)]
+        no_word_diff: bool,
+    },
+    /// Show commit history (passthrough to git log unless --format or --interactive)
+    #[command(group(clap::ArgGroup::new("view").args(["format", "interactive"])))]
+    Log {
+        /// Print each commit using a template such as "{short} {date} {subject}"
+        #[arg(long)]
+        format: Option<String>,
+        /// Browse the history in the terminal beside a commit graph
+        #[arg(short, long)]
+        interactive: bool,
+        /// Limit the number of commits shown
+        #[arg(short = 'n', long)]
+        max_count: Option<usize>,
+        /// Skip this many commits before showing the rest
+        #[arg(long)]
+        skip: Option<usize>,
+        /// Only commits whose author name or email contains this, ignoring case
+        #[arg(long)]
+        author: Option<String>,
+        /// Only commits committed on or after this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only commits committed on or before this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        until: Option<String>,
+        /// Only commits that changed this file or directory; repeat for several
+        #[arg(long = "path", value_name = "PATH")]
+        paths: Vec<String>,
+        /// Show the commits that changed the path given after it (or after --), following
+        /// renames, with each commit's diff
+        #[arg(long, conflicts_with = "view")]
+        follow: bool,
+        /// Revision or range (A..B, A...B) to show with --format or --interactive, or
+        /// arguments to pass to git log
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Show working tree status (passthrough to git status unless --watch)
+    #[command(alias = "st")]
+    Status {
+        /// Redraw the status whenever files or refs change
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between forced refreshes in --watch mode
+        #[arg(long, default_value = "30", requires = "watch")]
+        interval: u64,
+        /// Arguments to pass to git status
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Local productivity statistics computed from repository history
+    Stats {
+        #[command(subcommand)]
+        command: StatsCommand,
+    },
+    /// Run status, fetch and branch stats across several repositories (alias: ws)
+    #[command(alias = "ws")]
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommand,
+    },
+    /// Generate a .gitignore from bundled templates
+    Ignore {
+        #[command(subcommand)]
+        command: IgnoreCommand,
+    },
+    /// Install git hooks that run xg
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommand,
+    },
+    /// Check commit messages against the `[commitlint]` rules in .xgit.toml, failing when any
+    /// breaks them
+    LintCommits {
+        /// Commits to check, as in `origin/main..HEAD`
+        range: String,
+    },
+    /// Manage remotes (lists them when no subcommand is given; other subcommands and options
+    /// pass through to git remote)
+    #[command(args_conflicts_with_subcommands = true)]
+    Remote {
+        #[command(subcommand)]
+        command: Option<RemoteCommand>,
+        /// Options to pass to git remote, e.g. -v
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Maintain CHANGELOG.md from Conventional Commits
+    Changelog {
+        #[command(subcommand)]
+        command: ChangelogCommand,
+    },
+    /// Release versioning driven by Conventional Commits (runs `git version` when no subcommand
+    /// is given)
+    Version {
+        #[command(subcommand)]
+        command: Option<VersionCommand>,
+    },
+    /// Show who last changed each line of a file, with the PR that brought it in (other git
+    /// blame options pass through to git blame)
+    Blame {
+        /// Blame the file as of this revision instead of HEAD
+        #[arg(long)]
+        rev: Option<String>,
+        /// Skip looking up PR numbers on GitHub
+        #[arg(long)]
+        no_pr: bool,
+        /// File to blame, or arguments to pass to git blame, e.g. -L 10,20 src/lib.rs
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Share of current lines each author last touched, from blame, to find reviewers and
+    /// bus-factor risks
+    Owners {
+        /// File or directory to analyze (defaults to the current directory)
+        path: Option<String>,
+        /// Number of authors to list
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
+    /// Check git, GitHub and AI tooling setup and suggest fixes
+    Doctor,
+    /// Browse the log of state-mutating xg operations
+    History {
+        /// Maximum number of entries to show, newest first
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: usize,
+    },
+    /// Revert the last operation in the HEAD reflog (a commit, merge, reset, rebase or
+    /// checkout) after confirming
+    Undo,
+    /// Run newline-delimited xg commands (or a JSON array) read from stdin in one process
+    Batch {
+        /// Report per-command results on stderr as JSON lines
+        #[arg(long)]
+        json: bool,
+        /// Stop at the first failing command instead of running the rest
+        #[arg(long)]
+        stop_on_error: bool,
+    },
     /// Explicit git passthrough command (e.g. xg git diff)
     Git {
         /// Git arguments where first arg is the git subcommand
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Run an `xg-<command>` plugin from PATH, falling back to git passthrough
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum StatsCommand {
+    /// Commit activity, streaks and commit sizes for the configured author
+    Me {
+        /// Author email to report on instead of `user.email`
+        #[arg(long)]
+        author: Option<String>,
+        /// Number of weeks to chart
+        #[arg(long, default_value = "12")]
+        weeks: usize,
+    },
+    /// Contributors, commit frequency, churn hotspots and PR merge times for the repository
+    Repo {
+        /// Number of weeks to chart
+        #[arg(long, default_value = "12")]
+        weeks: usize,
+        /// Number of contributors and hotspot files to list
+        #[arg(long, default_value = "10")]
+        top: usize,
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StashCommand {
+    /// Stash staged and unstaged changes, leaving a clean working tree
+    Save {
+        /// Describe the stashed changes
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Stash untracked files too
+        #[arg(short = 'u', long)]
+        include_untracked: bool,
+    },
+    /// List stash entries, newest first
+    List,
+    /// Apply an entry and drop it if it applied without conflicts
+    Pop {
+        /// Entry to pop, N in stash@{N}
+        #[arg(default_value = "0")]
+        index: usize,
+    },
+    /// Apply an entry, keeping it in the stash
+    Apply {
+        /// Entry to apply, N in stash@{N}
+        #[arg(default_value = "0")]
+        index: usize,
+    },
+    /// Delete an entry without applying it
+    Drop {
+        /// Entry to drop, N in stash@{N}
+        #[arg(default_value = "0")]
+        index: usize,
+    },
+    /// Any other git stash subcommand, such as push, show or clear
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum TagCommand {
+    /// List tags with their commit and message
+    List,
+    /// Create a tag, annotated when a message is given
+    Create {
+        /// Tag name, e.g. v1.2.0
+        name: String,
+        /// Commit to tag
+        #[arg(default_value = "HEAD")]
+        target: String,
+        /// Create an annotated tag with this message
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+    /// Delete local tags
+    Delete {
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+    /// Push tags to a remote (all local tags when none are named)
+    Push {
+        names: Vec<String>,
+        /// Remote to push to
+        #[arg(long, default_value = "origin")]
+        remote: String,
+    },
+    /// Anything else is passed to git tag, e.g. `xg tag v1.2.0` for a lightweight tag
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum RemoteCommand {
+    /// List remotes with their URL and number of remote-tracking branches
+    List,
+    /// Add a remote
+    Add { name: String, url: String },
+    /// Remove a remote and its remote-tracking branches (alias: rm)
+    #[command(alias = "rm")]
+    Remove { name: String },
+    /// Rename a remote, moving its remote-tracking branches and branch upstreams
+    Rename { old_name: String, new_name: String },
+    /// Any other git remote subcommand, such as set-url, show or prune
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub enum PrCommand {
+    /// Push the current branch and open a pull request, with an AI-drafted title and
+    /// description to edit first
+    Create {
+        /// Branch to merge into (defaults to the repository's default branch)
+        #[arg(long)]
+        base: Option<String>,
+        /// Open the pull request as a draft
+        #[arg(long)]
+        draft: bool,
+    },
+    /// List open pull requests with their CI status, then pick one to check out
+    List {
+        /// Only pull requests you opened
+        #[arg(long)]
+        mine: bool,
+        /// Only pull requests waiting on your review
+        #[arg(long)]
+        review_requested: bool,
+        /// Only pull requests with this label; repeat for several
+        #[arg(long = "label", value_name = "LABEL")]
+        labels: Vec<String>,
+    },
+    /// Merge the current branch's pull request, then delete the branch locally and on the
+    /// remote and switch back to the base branch
+    Merge {
+        /// How to land the commits on the base branch
+        #[arg(long, default_value = "merge", value_parser = ["merge", "squash", "rebase"])]
+        strategy: String,
+        /// Wait for pending checks to finish first, and give up if any fail
+        #[arg(long)]
+        wait: bool,
+        /// Keep the branch and stay on it after merging
+        #[arg(long)]
+        keep_branch: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StackCommand {
+    /// Create a branch on top of the current one and switch to it
+    Create {
+        /// Name of the new branch
+        name: String,
+    },
+    /// Show every stack as a tree, with pull requests and branches that need restacking
+    List,
+    /// Rebase each branch of the current stack onto its parent after the parent changed
+    Restack,
+    /// Push the current stack and open or update one pull request per branch, each based on
+    /// its parent and linking to the others
+    Submit {
+        /// Open new pull requests as drafts
+        #[arg(long)]
+        draft: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IgnoreCommand {
+    /// Add the patterns of one or more templates (rust, node, python, macos, ...) to
+    /// .gitignore, then offer to untrack files they ignore
+    Init {
+        /// Template names; see `xg ignore list`
+        #[arg(required = true)]
+        templates: Vec<String>,
+    },
+    /// List the bundled templates
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum HooksCommand {
+    /// Install prepare-commit-msg and commit-msg hooks so plain `git commit` gets an
+    /// AI-suggested message and a Conventional Commits check
+    InstallAi {
+        /// Replace existing hooks that xg did not install
+        #[arg(long)]
+        force: bool,
+    },
+    /// Run by the installed prepare-commit-msg hook
+    #[command(hide = true)]
+    PrepareCommitMsg {
+        file: PathBuf,
+        source: Option<String>,
+        sha: Option<String>,
+    },
+    /// Run by the installed commit-msg hook
+    #[command(hide = true)]
+    CommitMsg { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+pub enum ChangelogCommand {
+    /// Write the section for the next release from the commits since the last version tag
+    /// and stage CHANGELOG.md for the release commit
+    Update {
+        /// Title the section [Unreleased] instead of the next version
+        #[arg(long)]
+        unreleased: bool,
+    },
+    /// Write a changelog section for the commits between two refs, polished by AI
+    Generate {
+        /// Start of the range, exclusive (defaults to the latest version tag)
+        from: Option<String>,
+        /// End of the range; a version tag titles the section with its version
+        #[arg(default_value = "HEAD")]
+        to: String,
+        /// File with an example section or instructions to follow instead of Keep a Changelog
+        #[arg(long)]
+        template: Option<PathBuf>,
+        /// Write the section from the commits alone, without AI
+        #[arg(long)]
+        no_ai: bool,
+        /// Print the section instead of writing it to CHANGELOG.md
+        #[arg(long)]
+        stdout: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VersionCommand {
+    /// Bump the version from the commits since the last version tag, update Cargo.toml and
+    /// package.json, commit them and create an annotated tag
+    Bump {
+        /// Bump the major version regardless of the commits
+        #[arg(long, group = "level")]
+        major: bool,
+        /// Bump the minor version regardless of the commits
+        #[arg(long, group = "level")]
+        minor: bool,
+        /// Bump the patch version regardless of the commits
+        #[arg(long, group = "level")]
+        patch: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommand {
+    /// Current branch, upstream divergence and uncommitted changes per repository
+    Status,
+    /// Fetch all remotes of every repository concurrently
+    Fetch,
+    /// Local branches with upstream divergence and merge status per repository
+    Stats,
+    /// Add a repository to the workspace (stored in global git config)
+    Add {
+        /// Path to the repository
+        #[arg(default_value = ".")]
+        path: String,
+    },
+    /// Remove a repository from the workspace
+    Remove {
+        /// Path to the repository
+        path: String,
+    },
+    /// List workspace repositories
+    List,
 }
@@ -0,0 +1,127 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::commits::staging::{WorkdirChange, WorkdirChangeKind};
+use crate::i18n::t;
+use crate::tui::emoji::icon;
+use crate::{
+    git::GitRepo,
+    tui::{confirm, dry_run, file_picker},
+};
+use anyhow::Context;
+use console::style;
+use inquire::Confirm;
+use std::fmt;
+
+/// Stage files, either interactively or by passing the arguments through to `git add`
+pub fn handle_add(interactive: bool, args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    if interactive {
+        interactive_add(dry_run)
+    } else {
+        git_passthrough("add", args, dry_run)
+    }
+}
+
+/// Picker entry showing the change kind and size next to the path
+struct ChangeOption<'a>(&'a WorkdirChange);
+
+impl fmt::Display for ChangeOption<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = self.0;
+        let marker = match change.kind {
+            WorkdirChangeKind::Modified => "M",
+            WorkdirChangeKind::Untracked => "?",
+            WorkdirChangeKind::Deleted => "D",
+            WorkdirChangeKind::Typechange => "T",
+        };
+        write!(
+            f,
+            "{marker} {}  +{} -{}",
+            change.path, change.insertions, change.deletions
+        )
+    }
+}
+
+fn interactive_add(dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let changes = repo.get_unstaged_changes()?;
+
+    if changes.is_empty() {
+        println!("{} Nothing to stage", style(icon("✨")).green().bold());
+        return Ok(());
+    }
+
+    // Type to fuzzy-filter, space to toggle, right arrow to select all; the pane below the
+    // list shows the diff of the file under the cursor
+    let labels = changes
+        .iter()
+        .map(|change| ChangeOption(change).to_string())
+        .collect();
+    let Some(selected) = file_picker::pick("Select files to stage:", labels, |index| {
+        change_preview(&repo, &changes[index].path)
+    })?
+    else {
+        println!("{} {}", style("ℹ").blue().bold(), t("common.aborted"));
+        return Ok(());
+    };
+
+    if selected.is_empty() {
+        println!("{} No files selected", style("ℹ").blue().bold());
+        return Ok(());
+    }
+
+    let paths: Vec<&str> = selected
+        .iter()
+        .map(|&index| changes[index].path.as_str())
+        .collect();
+
+    if dry_run {
+        dry_run::print_header();
+        for path in &paths {
+            dry_run::print_operation(&format!("stage {path}"));
+        }
+        return Ok(());
+    }
+
+    if !confirm::assume_yes()
+        && !Confirm::new(&format!("Stage {} files?", paths.len()))
+            .with_default(true)
+            .prompt()?
+    {
+        println!("{} {}", style("ℹ").blue().bold(), t("common.aborted"));
+        return Ok(());
+    }
+
+    repo.add_paths(&paths)?;
+    println!("{} Staged {} files", style("✓").green().bold(), paths.len());
+
+    Ok(())
+}
+
+/// The preview pane of `xg add --interactive`: the hunks of the file's unstaged changes
+fn change_preview(repo: &GitRepo, path: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let printed = repo
+        .get_unstaged_file_diff(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|diff| {
+            diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+                let content = String::from_utf8_lossy(line.content());
+                let content = content.trim_end().replace('\t', "    ");
+                match line.origin() {
+                    // The file header repeats the path the list already shows
+                    'F' => {}
+                    '+' => lines.push(style(format!("+{content}")).green().to_string()),
+                    '-' => lines.push(style(format!("-{content}")).red().to_string()),
+                    ' ' => lines.push(format!(" {content}")),
+                    'H' => lines.push(style(content).cyan().to_string()),
+                    _ => lines.push(style(content).dim().to_string()),
+                }
+                true
+            })
+            .context(format!("Failed to diff '{path}'"))
+        });
+    if let Err(err) = printed {
+        lines.push(format!("{} {err:#}", style("⚠").yellow()));
+    }
+    lines
+}
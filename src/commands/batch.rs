@@ -0,0 +1,200 @@
+use crate::error::GitxError;
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io::Read;
+
+/// One entry of a JSON batch: either a command line or a pre-split argument list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonCommand {
+    Line(String),
+    Args(Vec<String>),
+}
+
+/// Outcome of one batch command, reported on stderr
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BatchResult {
+    pub command: String,
+    pub success: bool,
+    pub exit_code: i32,
+    pub error: Option<String>,
+}
+
+/// Read commands from stdin and run each with `run`, reporting a result per command.
+///
+/// Command output goes to stdout as usual; results go to stderr (as JSON lines with
+/// `json`) so the two streams can be consumed separately.
+pub async fn handle_batch<F, Fut>(
+    json: bool,
+    stop_on_error: bool,
+    mut run: F,
+) -> Result<(), GitxError>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<(), GitxError>>,
+{
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| anyhow::anyhow!("Failed to read commands from stdin: {e}"))?;
+    let commands = parse_batch_input(&input)?;
+
+    let total = commands.len();
+    let mut failed = 0;
+    for (idx, args) in commands.into_iter().enumerate() {
+        let command = args.join(" ");
+        let result = run(args).await;
+        let result = BatchResult {
+            command,
+            success: result.is_ok(),
+            exit_code: result.as_ref().map_or_else(GitxError::exit_code, |_| 0),
+            error: result.err().map(|e| e.to_string()),
+        };
+        report(&result, idx + 1, total, json);
+
+        if !result.success {
+            failed += 1;
+            if stop_on_error {
+                break;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(GitxError::Other(anyhow::anyhow!(
+            "{failed} of {total} batch commands failed"
+        )));
+    }
+    Ok(())
+}
+
+fn report(result: &BatchResult, position: usize, total: usize, json: bool) {
+    if json {
+        if let Ok(line) = serde_json::to_string(result) {
+            eprintln!("{line}");
+        }
+        return;
+    }
+
+    let progress = style(format!("[{position}/{total}]")).dim();
+    match &result.error {
+        None => eprintln!(
+            "{} {progress} xg {}",
+            style("✓").green().bold(),
+            result.command
+        ),
+        Some(error) => eprintln!(
+            "{} {progress} xg {}: {}",
+            style("✗").red().bold(),
+            result.command,
+            style(error).red()
+        ),
+    }
+}
+
+/// Parse newline-delimited command lines, or a JSON array of command lines / argument arrays.
+///
+/// Commands are given without the leading `xg`. Blank lines and `#` comments are skipped.
+pub fn parse_batch_input(input: &str) -> Result<Vec<Vec<String>>, GitxError> {
+    let trimmed = input.trim_start();
+    if trimmed.starts_with('[') {
+        let entries: Vec<JsonCommand> = serde_json::from_str(trimmed)
+            .map_err(|e| GitxError::Usage(format!("Invalid JSON batch: {e}")))?;
+        return entries
+            .into_iter()
+            .map(|entry| match entry {
+                JsonCommand::Line(line) => split_command_line(&line),
+                JsonCommand::Args(args) => Ok(args),
+            })
+            .filter(|args| args.as_ref().map_or(true, |args| !args.is_empty()))
+            .collect();
+    }
+
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(split_command_line)
+        .collect()
+}
+
+/// Split a command line into arguments, honouring single quotes, double quotes and backslashes
+fn split_command_line(line: &str) -> Result<Vec<String>, GitxError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => match chars.next() {
+                Some(escaped) => current.push(escaped),
+                None => current.push('\\'),
+            },
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(GitxError::Usage(format!("Unterminated quote in: {line}")));
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_batch_input, split_command_line};
+
+    #[test]
+    fn split_command_line_handles_quotes_and_escapes() {
+        assert_eq!(
+            split_command_line(r#"log --format "{short} {subject}" -n 3"#).unwrap(),
+            vec!["log", "--format", "{short} {subject}", "-n", "3"]
+        );
+        assert_eq!(
+            split_command_line(r"commit -m 'it\s' a\ b").unwrap(),
+            vec!["commit", "-m", r"it\s", "a b"]
+        );
+        assert_eq!(split_command_line(r#"x """#).unwrap(), vec!["x", ""]);
+        assert!(split_command_line("log 'open").is_err());
+    }
+
+    #[test]
+    fn parse_batch_input_accepts_lines_and_json() {
+        let lines = "# refresh\nbranch --porcelain\n\n  status --short  \n";
+        assert_eq!(
+            parse_batch_input(lines).unwrap(),
+            vec![vec!["branch", "--porcelain"], vec!["status", "--short"]]
+        );
+
+        let json = r#"["branch --porcelain", ["log", "--format", "{short}"]]"#;
+        assert_eq!(
+            parse_batch_input(json).unwrap(),
+            vec![
+                vec!["branch", "--porcelain"],
+                vec!["log", "--format", "{short}"]
+            ]
+        );
+        assert!(parse_batch_input("[1, 2]").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use super::changelog::split_pr_suffix;
+use super::git_passthrough::git_passthrough;
+use super::log::{format_date, repo_relative_path};
+use crate::error::GitxError;
+use crate::git::commits::blame::BlameLine;
+use crate::git::GitRepo;
+use crate::github::GitHubPrMatcher;
+
+use console::style;
+use std::collections::{HashMap, HashSet};
+
+const AUTHOR_WIDTH: usize = 20;
+
+/// Show who last changed each line of the file in `args`, with the PR that brought each change
+/// in. Options such as `-L`, `-w` or `-C` hand the command to `git blame`.
+pub async fn handle_blame(
+    args: &[String],
+    rev: Option<&str>,
+    no_pr: bool,
+) -> Result<(), GitxError> {
+    let path = match args {
+        [path] if !path.starts_with('-') => path,
+        _ => {
+            let git_args: Vec<String> = rev
+                .into_iter()
+                .map(str::to_string)
+                .chain(args.iter().cloned())
+                .collect();
+            return git_passthrough("blame", &git_args, false);
+        }
+    };
+    let repo = GitRepo::open(".")?;
+    let path = repo_relative_path(&repo, path)?;
+    let lines = repo.blame(&path, rev)?;
+    let prs = if no_pr {
+        HashMap::new()
+    } else {
+        pr_numbers(&repo, &lines).await
+    };
+    for line in render_blame(&lines, &prs) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// PR number per blamed commit: from a squash-merge subject when it has one, otherwise from
+/// GitHub. Stops asking GitHub after the first failed lookup.
+async fn pr_numbers(repo: &GitRepo, lines: &[BlameLine]) -> HashMap<String, u64> {
+    let mut matcher = GitHubPrMatcher::new(repo).ok();
+    let mut prs = HashMap::new();
+    let mut seen = HashSet::new();
+    for line in lines {
+        let sha = &line.commit_id;
+        if !seen.insert(sha.as_str()) {
+            continue;
+        }
+        let subject = repo.get_commit_subject(sha).unwrap_or_default();
+        if let (_, Some(number)) = split_pr_suffix(&subject) {
+            prs.insert(sha.clone(), number);
+            continue;
+        }
+        let Some(active) = &matcher else {
+            continue;
+        };
+        match active.service().find_pr_by_commit(sha).await {
+            Ok(Some(record)) => {
+                prs.insert(sha.clone(), record.pr_number);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!(
+                    "{} Could not look up PRs on GitHub: {err}",
+                    style("⚠").yellow()
+                );
+                matcher = None;
+            }
+        }
+    }
+    prs
+}
+
+/// One output line per blamed line. The commit, author, date and PR are shown on the first
+/// line of each run of lines from the same commit.
+fn render_blame(lines: &[BlameLine], prs: &HashMap<String, u64>) -> Vec<String> {
+    let author_width = lines
+        .iter()
+        .map(|line| line.author_name.chars().count().min(AUTHOR_WIDTH))
+        .max()
+        .unwrap_or(0);
+    let pr_width = prs
+        .values()
+        .map(|number| format!("#{number}").len())
+        .max()
+        .unwrap_or(0);
+    let number_width = lines.len().to_string().len();
+
+    // Sha, author and date, plus the PR column when any line has one
+    let meta_width = 7 + 1 + author_width + 1 + 10 + if pr_width > 0 { pr_width + 1 } else { 0 };
+
+    let mut previous: Option<&str> = None;
+    lines
+        .iter()
+        .map(|line| {
+            let first = previous != Some(line.commit_id.as_str());
+            previous = Some(line.commit_id.as_str());
+            let meta = if first {
+                let author: String = line.author_name.chars().take(AUTHOR_WIDTH).collect();
+                let mut meta = format!(
+                    "{} {} {}",
+                    style(&line.commit_id[..7]).yellow(),
+                    style(format!("{author:<author_width$}")).cyan(),
+                    style(format_date(line.time, line.offset_minutes)).dim()
+                );
+                if pr_width > 0 {
+                    let pr = prs
+                        .get(&line.commit_id)
+                        .map(|number| format!("#{number}"))
+                        .unwrap_or_default();
+                    meta.push_str(&format!(" {}", style(format!("{pr:<pr_width$}")).green()));
+                }
+                meta
+            } else {
+                " ".repeat(meta_width)
+            };
+            format!(
+                "{meta} {} {}",
+                style(format!("{:>number_width$} │", line.line)).dim(),
+                line.content
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_blame;
+    use crate::git::commits::blame::BlameLine;
+    use std::collections::HashMap;
+
+    fn line(number: usize, content: &str, commit: &str, author: &str) -> BlameLine {
+        BlameLine {
+            line: number,
+            content: content.to_string(),
+            commit_id: commit.repeat(40),
+            author_name: author.to_string(),
+            author_email: String::new(),
+            time: 1_700_000_000,
+            offset_minutes: 0,
+            original_path: None,
+        }
+    }
+
+    #[test]
+    fn hunks_are_labelled_once_with_their_pr() {
+        let lines = [
+            line(1, "fn main() {", "a", "Ada"),
+            line(2, "    run();", "b", "Grace Hopper"),
+            line(3, "    stop();", "b", "Grace Hopper"),
+            line(4, "}", "a", "Ada"),
+        ];
+        let prs = HashMap::from([("b".repeat(40), 42)]);
+        assert_eq!(
+            render_blame(&lines, &prs),
+            vec![
+                "aaaaaaa Ada          2023-11-14     1 │ fn main() {",
+                "bbbbbbb Grace Hopper 2023-11-14 #42 2 │     run();",
+                "                                    3 │     stop();",
+                "aaaaaaa Ada          2023-11-14     4 │ }",
+            ]
+        );
+    }
+}
@@ -1,64 +1,98 @@
 use super::branch_prune::prune_merged_branches;
 use super::branch_stats::show_branch_stats;
-use crate::git::GitRepo;
+use crate::error::GitxError;
+use crate::i18n::{t, tf};
+use crate::{
+    git::GitRepo,
+    tui::{dry_run, porcelain},
+};
 use console::style;
 use inquire::Select;
+use std::time::Duration;
 
 pub async fn handle_branch(
     prune_merged: bool,
+    remote: bool,
     stats: bool,
+    porcelain: bool,
+    watch_interval: Option<Duration>,
+    refresh: bool,
     dry_run: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), GitxError> {
     if prune_merged {
-        return prune_merged_branches(dry_run).await;
+        return prune_merged_branches(porcelain, remote, dry_run).await;
     }
 
     if stats {
-        return show_branch_stats().await;
+        return show_branch_stats(porcelain, watch_interval, refresh).await;
     }
     let repo = GitRepo::open(".")?;
 
-    match repo.get_all_branches() {
+    if porcelain {
+        let current_branch = repo.get_current_branch().ok();
+        for branch in repo.read_backend()?.list_branches()? {
+            let is_current = current_branch.as_deref() == Some(branch.as_str());
+            println!("{}", porcelain::branch_line(&branch, is_current));
+        }
+        return Ok(());
+    }
+
+    match repo.read_backend()?.list_branches() {
         Ok(branches) => {
             if branches.is_empty() {
-                println!("No branches found");
+                println!("{}", t("branch.none_found"));
                 return Ok(());
             }
 
-            let selection = Select::new("Select a branch:", branches).prompt();
+            let selection = Select::new(t("branch.select_prompt"), branches).prompt();
 
             match selection {
+                Ok(chosen_branch) if dry_run => {
+                    dry_run::print_header();
+                    dry_run::print_operation(&format!("checkout branch '{chosen_branch}'"));
+                }
                 Ok(chosen_branch) => match repo.checkout_branch(&chosen_branch) {
                     Ok(_) => {
                         println!(
-                            "{} Switched to branch: {}",
+                            "{} {}",
                             style("✓").green().bold(),
-                            style(&chosen_branch).cyan()
+                            tf(
+                                "branch.switched",
+                                &[("branch", &style(&chosen_branch).cyan())]
+                            )
                         );
                     }
                     Err(e) => {
                         eprintln!(
-                            "{} Error switching to branch '{}': {}",
+                            "{} {}",
                             style("✗").red().bold(),
-                            style(&chosen_branch).yellow(),
-                            style(e).red()
+                            tf(
+                                "branch.switch_failed",
+                                &[
+                                    ("branch", &style(&chosen_branch).yellow()),
+                                    ("error", &style(e).red())
+                                ]
+                            )
                         );
                     }
                 },
                 Err(err) => {
                     eprintln!(
-                        "{} Selection cancelled: {}",
+                        "{} {}",
                         style("⚠").yellow().bold(),
-                        style(err).yellow()
+                        tf(
+                            "branch.selection_cancelled",
+                            &[("error", &style(err).yellow())]
+                        )
                     );
                 }
             }
         }
         Err(e) => {
             eprintln!(
-                "{} Error getting branches: {}",
+                "{} {}",
                 style("✗").red().bold(),
-                style(e).red()
+                tf("branch.list_failed", &[("error", &style(e).red())])
             );
         }
     }
@@ -0,0 +1,134 @@
+use super::commit::{AI_DIFF_MAX_BYTES, AI_DIFF_MAX_FILE_BYTES};
+use crate::ai::{self, BranchNameSuggestion};
+use crate::config::{AiProvider, XgitConfig};
+use crate::error::GitxError;
+use crate::git::branches::naming::{infer_branch_type, render_branch_name, slugify};
+use crate::git::commits::diff_text::DiffTextOptions;
+use crate::git::GitRepo;
+use crate::tui::emoji::icon;
+use crate::tui::{confirm, dry_run};
+use console::style;
+use inquire::Text;
+
+/// Create and check out a branch for `task`, or for the staged changes when no task is given.
+/// The AI suggests a type and description that fill the `branches.pattern` setting, and the
+/// name can be edited before the branch is created.
+pub fn handle_branch_new(task: Option<&str>, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let config = repo.xgit_config()?;
+    let task = task.map(str::trim).filter(|task| !task.is_empty());
+
+    let diff_text = match task {
+        Some(_) => String::new(),
+        None => repo.diff_to_string_with(
+            &repo.get_staged_diff()?,
+            &DiffTextOptions {
+                max_bytes: Some(AI_DIFF_MAX_BYTES),
+                max_file_bytes: Some(AI_DIFF_MAX_FILE_BYTES),
+                ..DiffTextOptions::default()
+            },
+        )?,
+    };
+    if task.is_none() && diff_text.is_empty() {
+        return Err(GitxError::Usage(
+            "Describe the task, as in `xg branch --new \"fix login redirect\"`, or stage the changes to name the branch after"
+                .to_string(),
+        ));
+    }
+
+    let suggestion = match suggest_name(&config, task, &diff_text)? {
+        Some(suggestion) => suggestion,
+        None => match task {
+            Some(task) => fallback_name(task),
+            None => {
+                return Err(GitxError::Usage(
+                    "No branch name could be suggested from the staged changes; describe the task instead"
+                        .to_string(),
+                ))
+            }
+        },
+    };
+    let name = render_branch_name(
+        config.branch_pattern(),
+        &suggestion.kind,
+        &suggestion.description,
+    )?;
+    let name = review_name(&name)?;
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("create branch '{name}' and switch to it"));
+        return Ok(());
+    }
+
+    repo.create_and_checkout_branch(&name)?;
+    println!(
+        "{} Created and switched to {}",
+        style("✓").green().bold(),
+        style(&name).cyan()
+    );
+    Ok(())
+}
+
+/// Ask the AI for a type and description, or `None` when it gives none or the AI provider is
+/// turned off
+fn suggest_name(
+    config: &XgitConfig,
+    task: Option<&str>,
+    diff_text: &str,
+) -> Result<Option<BranchNameSuggestion>, GitxError> {
+    if config.ai_provider() == AiProvider::Disabled {
+        return Ok(None);
+    }
+
+    eprintln!(
+        "{} Suggesting a branch name",
+        style(icon("🤖")).cyan().bold()
+    );
+    Ok(ai::generate_branch_name(task, diff_text)?)
+}
+
+/// Type and description without the AI: a leading type word, then the task in kebab-case
+fn fallback_name(task: &str) -> BranchNameSuggestion {
+    let (kind, description) = infer_branch_type(task);
+    BranchNameSuggestion {
+        kind: kind.to_string(),
+        description: slugify(description),
+    }
+}
+
+/// Let the user edit the suggested name. `--yes` accepts it as it is.
+fn review_name(proposed: &str) -> Result<String, GitxError> {
+    if confirm::assume_yes() {
+        return Ok(proposed.to_string());
+    }
+    if !console::user_attended() {
+        return Err(GitxError::Usage(format!(
+            "No terminal to review the branch name; pass --yes to accept '{proposed}'"
+        )));
+    }
+
+    let name = Text::new("Branch name:")
+        .with_initial_value(proposed)
+        .with_help_message("enter to create, esc to cancel")
+        .prompt()?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(GitxError::Usage(
+            "Aborting due to empty branch name".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fallback_name;
+
+    #[test]
+    fn fallback_names_come_from_the_task() {
+        let suggestion = fallback_name("Fix: crash when the config is empty");
+        assert_eq!(suggestion.kind, "fix");
+        assert_eq!(suggestion.description, "crash-when-the-config-is-empty");
+    }
+}
@@ -1,24 +1,94 @@
-use crate::{git::GitRepo, github::GitHubPrMatcher};
+use crate::error::GitxError;
+use crate::i18n::{t, tf};
+use crate::tui::emoji::icon;
+use crate::{
+    git::GitRepo,
+    github::GitHubPrMatcher,
+    tui::confirm::{self, confirm, confirm_or_abort},
+    tui::porcelain::{self, PruneStatus},
+};
 use console::style;
 use inquire::MultiSelect;
+use std::io::{self, Write};
 
 #[derive(Debug, Clone)]
 struct PruneCandidate {
     branch: String,
-    reason: String,
+    reason: PruneReason,
+    /// The branch's upstream as `(remote, branch)`, when it still exists on the remote
+    remote_branch: Option<(String, String)>,
+}
+
+impl PruneCandidate {
+    fn new(repo: &GitRepo, branch: String, reason: PruneReason) -> Self {
+        let remote_branch = repo
+            .get_upstream_remote_branch(&branch)
+            .ok()
+            .flatten()
+            .filter(|(remote, name)| {
+                repo.remote_tracking_branch_exists(&format!("{remote}/{name}"))
+            });
+        PruneCandidate {
+            branch,
+            reason,
+            remote_branch,
+        }
+    }
+
+    fn remote_label(&self) -> Option<String> {
+        self.remote_branch
+            .as_ref()
+            .map(|(remote, name)| format!("{remote}/{name}"))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PruneReason {
+    MergedIntoTrunk,
+    PullRequestMerged { pr_number: u64, trunk: String },
+}
+
+impl PruneReason {
+    fn describe(&self) -> String {
+        match self {
+            PruneReason::MergedIntoTrunk => t("prune.reason_merged_local").to_string(),
+            PruneReason::PullRequestMerged { pr_number, trunk } => tf(
+                "prune.reason_merged_pr",
+                &[("number", pr_number), ("trunk", trunk)],
+            ),
+        }
+    }
+
+    fn porcelain_fields(&self) -> (&'static str, Option<u64>) {
+        match self {
+            PruneReason::MergedIntoTrunk => ("merged", None),
+            PruneReason::PullRequestMerged { pr_number, .. } => ("pr-merged", Some(*pr_number)),
+        }
+    }
 }
 
 /// Prune local branches that have either been merged into trunk or merged via GitHub and deleted remotely.
-pub async fn prune_merged_branches(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+///
+/// With `remote`, each pruned branch's upstream branch is deleted on its remote too.
+pub async fn prune_merged_branches(
+    porcelain: bool,
+    remote: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
     let repo = GitRepo::open(".")?;
 
+    if porcelain {
+        let branches_to_prune = find_branches_to_prune(&repo).await?;
+        return prune_branches_porcelain(&repo, &branches_to_prune, remote, dry_run);
+    }
+
     println!(
         "{} {}",
-        style("🔍").blue().bold(),
+        style(icon("🔍")).blue().bold(),
         if dry_run {
-            "Finding branches that would be pruned (dry run)..."
+            t("prune.finding_dry_run")
         } else {
-            "Finding merged branches to prune..."
+            t("prune.finding")
         }
     );
     println!();
@@ -27,67 +97,80 @@ pub async fn prune_merged_branches(dry_run: bool) -> Result<(), Box<dyn std::err
 
     if branches_to_prune.is_empty() {
         println!(
-            "{} No merged branches found to prune",
-            style("✨").green().bold()
+            "{} {}",
+            style(icon("✨")).green().bold(),
+            t("prune.none_found")
         );
         return Ok(());
     }
 
     if dry_run {
-        show_dry_run_results(&branches_to_prune);
+        show_dry_run_results(&mut io::stdout().lock(), &branches_to_prune, remote)?;
     } else {
-        prune_branches(&repo, &branches_to_prune)?;
+        prune_branches(&repo, &branches_to_prune, remote)?;
     }
 
     Ok(())
 }
 
-async fn find_branches_to_prune(
-    repo: &GitRepo,
-) -> Result<Vec<PruneCandidate>, Box<dyn std::error::Error>> {
+async fn find_branches_to_prune(repo: &GitRepo) -> Result<Vec<PruneCandidate>, GitxError> {
     let all_branches = repo.get_all_branches()?;
     let current_branch = repo.get_current_branch()?;
     let mut branches_to_prune = Vec::new();
-    let protected_branches = ["main", "master", "develop"];
+    let protected_branches = repo.protected_branches()?;
 
     let github_matcher = GitHubPrMatcher::new(repo).ok();
     let mut trunk_branch = None;
     if let Some(ref matcher) = github_matcher {
-        let fetch_result = repo.fetch_prune(matcher.remote_name(), None);
+        let fetch_result = repo.fetch_prune_async(matcher.remote_name(), None).await;
         if let Err(err) = fetch_result {
-            println!(
-                "{} Warning: Failed to refresh remote-tracking branches before squash-merge checks: {}",
+            eprintln!(
+                "{} {}",
                 style("⚠").yellow(),
-                err
+                tf("prune.fetch_warning", &[("error", &err)])
             );
         } else if let Ok(resolved_trunk) = matcher.service().resolve_trunk_base_branch(repo).await {
             trunk_branch = Some(resolved_trunk);
         }
     }
 
+    // One walk of trunk's history answers the merged check for every branch; anything it
+    // could not answer falls back to a per-branch check, which reports its own error
+    let cache = repo.merge_base_cache()?;
+    let merged = repo
+        .branches_merged_to_main(&cache, &all_branches)
+        .unwrap_or_default();
+
     for branch in all_branches {
         if branch == current_branch {
             continue;
         }
-        if protected_branches.contains(&branch.as_str()) {
+        if protected_branches.is_protected(&branch) {
             continue;
         }
 
-        match repo.is_branch_merged_to_main(&branch) {
+        let is_merged = match merged.get(&branch) {
+            Some(is_merged) => Ok(*is_merged),
+            None => repo.is_branch_merged_to_main(&branch),
+        };
+        match is_merged {
             Ok(true) => {
-                branches_to_prune.push(PruneCandidate {
+                branches_to_prune.push(PruneCandidate::new(
+                    repo,
                     branch,
-                    reason: "merged into local trunk".to_string(),
-                });
+                    PruneReason::MergedIntoTrunk,
+                ));
                 continue;
             }
             Ok(false) => {}
             Err(err) => {
-                println!(
-                    "{} Warning: Could not determine merge status for '{}': {}",
+                eprintln!(
+                    "{} {}",
                     style("⚠").yellow(),
-                    style(&branch).cyan(),
-                    err
+                    tf(
+                        "prune.merge_status_warning",
+                        &[("branch", &style(&branch).cyan()), ("error", &err)]
+                    )
                 );
             }
         }
@@ -106,61 +189,139 @@ async fn find_branches_to_prune(
                         resolved_pr.record.head_ref
                     )) =>
             {
-                branches_to_prune.push(PruneCandidate {
+                branches_to_prune.push(PruneCandidate::new(
+                    repo,
                     branch,
-                    reason: format!(
-                        "PR #{} merged to {} and remote head deleted",
-                        resolved_pr.record.pr_number, trunk_branch
-                    ),
-                });
+                    PruneReason::PullRequestMerged {
+                        pr_number: resolved_pr.record.pr_number,
+                        trunk: trunk_branch.to_string(),
+                    },
+                ));
             }
             Ok(_) => {}
             Err(err) => {
-                println!(
-                    "{} Warning: Could not refresh PR state for '{}': {}",
+                eprintln!(
+                    "{} {}",
                     style("⚠").yellow(),
-                    style(&branch).cyan(),
-                    err
+                    tf(
+                        "prune.pr_state_warning",
+                        &[("branch", &style(&branch).cyan()), ("error", &err)]
+                    )
                 );
             }
         }
     }
 
+    cache.save()?;
     Ok(branches_to_prune)
 }
 
-fn show_dry_run_results(branches_to_prune: &[PruneCandidate]) {
-    println!(
-        "{} The following {} branches would be deleted:",
-        style("📋").cyan().bold(),
-        branches_to_prune.len()
-    );
-    println!();
+/// Script-friendly pruning: no selection prompt, one porcelain record per candidate, plus one
+/// named `<remote>/<branch>` per remote branch when `remote` is set
+fn prune_branches_porcelain(
+    repo: &GitRepo,
+    branches_to_prune: &[PruneCandidate],
+    remote: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let print_record = |status: PruneStatus, name: &str, candidate: &PruneCandidate| {
+        let (reason, pr_number) = candidate.reason.porcelain_fields();
+        println!("{}", porcelain::prune_line(status, name, reason, pr_number));
+    };
+
+    let asked = !dry_run && !branches_to_prune.is_empty();
+    let confirmed = asked && confirm(&confirm_prompt(branches_to_prune.len(), remote))?;
 
     for candidate in branches_to_prune {
-        println!(
+        let status = if dry_run {
+            PruneStatus::WouldDelete
+        } else if !confirmed {
+            PruneStatus::Skipped
+        } else if repo.delete_branch_forced(&candidate.branch).is_ok() {
+            PruneStatus::Deleted
+        } else {
+            PruneStatus::Failed
+        };
+        print_record(status, &candidate.branch, candidate);
+
+        let Some((remote_name, branch)) = candidate.remote_branch.as_ref().filter(|_| remote)
+        else {
+            continue;
+        };
+        let remote_status = match status {
+            PruneStatus::Deleted if repo.delete_remote_branch(remote_name, branch).is_ok() => {
+                PruneStatus::Deleted
+            }
+            PruneStatus::Deleted => PruneStatus::Failed,
+            other => other,
+        };
+        print_record(remote_status, &format!("{remote_name}/{branch}"), candidate);
+    }
+
+    // Declined: every candidate was reported as skipped
+    if asked && !confirmed {
+        return Err(GitxError::UserAbort(t("common.aborted").to_string()));
+    }
+    Ok(())
+}
+
+fn confirm_prompt(count: usize, remote: bool) -> String {
+    let key = if remote {
+        "prune.confirm_delete_remote"
+    } else {
+        "prune.confirm_delete"
+    };
+    tf(key, &[("count", &count)])
+}
+
+fn show_dry_run_results(
+    out: &mut impl Write,
+    branches_to_prune: &[PruneCandidate],
+    remote: bool,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "{} {}",
+        style(icon("📋")).cyan().bold(),
+        tf("prune.would_delete", &[("count", &branches_to_prune.len())])
+    )?;
+    writeln!(out)?;
+
+    for candidate in branches_to_prune {
+        writeln!(
+            out,
             "  {} {} {}",
-            style("🗑").red(),
+            style(icon("🗑")).red(),
             style(&candidate.branch).cyan().bold(),
-            style(format!("({})", candidate.reason)).dim()
-        );
+            style(format!("({})", candidate.reason.describe())).dim()
+        )?;
+        if let Some(label) = candidate.remote_label().filter(|_| remote) {
+            writeln!(
+                out,
+                "    {}",
+                style(tf("prune.also_remote", &[("branch", &label)])).red()
+            )?;
+        }
     }
 
-    println!();
-    println!(
-        "{} Run without --dry-run to actually delete these branches",
-        style("💡").blue()
-    );
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} {}",
+        style(icon("💡")).blue(),
+        t("prune.dry_run_hint")
+    )
 }
 
 fn prune_branches(
     repo: &GitRepo,
     branches_to_prune: &[PruneCandidate],
-) -> Result<(), Box<dyn std::error::Error>> {
+    remote: bool,
+) -> Result<(), GitxError> {
     println!(
-        "{} Found {} merged branches. Select which ones to delete:",
-        style("🗑").red().bold(),
-        branches_to_prune.len()
+        "{} {}",
+        style(icon("🗑")).red().bold(),
+        tf("prune.found", &[("count", &branches_to_prune.len())])
     );
     println!();
 
@@ -169,7 +330,7 @@ fn prune_branches(
             "  {} {} {}",
             style("•").dim(),
             style(&candidate.branch).cyan().bold(),
-            style(format!("({})", candidate.reason)).dim()
+            style(format!("({})", candidate.reason.describe())).dim()
         );
     }
     println!();
@@ -178,42 +339,81 @@ fn prune_branches(
         .iter()
         .map(|candidate| candidate.branch.as_str())
         .collect();
-    let branches_to_delete = MultiSelect::new("Select branches to delete:", options).prompt()?;
+    // With --yes there is nobody to pick from the list, so every candidate is selected
+    let branches_to_delete = if confirm::assume_yes() {
+        options
+    } else {
+        MultiSelect::new(t("prune.select_prompt"), options).prompt()?
+    };
 
     if branches_to_delete.is_empty() {
-        println!(
-            "{} No branches selected for deletion",
-            style("ℹ").blue().bold()
-        );
+        println!("{} {}", style("ℹ").blue().bold(), t("prune.none_selected"));
         return Ok(());
     }
 
+    confirm_or_abort(
+        &confirm_prompt(branches_to_delete.len(), remote),
+        t("common.aborted"),
+    )?;
+
     println!(
-        "{} Deleting {} selected branches:",
-        style("🗑").red().bold(),
-        branches_to_delete.len()
+        "{} {}",
+        style(icon("🗑")).red().bold(),
+        tf("prune.deleting", &[("count", &branches_to_delete.len())])
     );
     println!();
 
     let mut deleted_count = 0;
     let mut failed_count = 0;
 
-    for branch in branches_to_delete {
-        match repo.delete_branch(branch) {
+    for candidate in branches_to_prune
+        .iter()
+        .filter(|candidate| branches_to_delete.contains(&candidate.branch.as_str()))
+    {
+        let branch = &candidate.branch;
+        match repo.delete_branch_forced(branch) {
             Ok(()) => {
                 println!(
-                    "  {} Deleted {}",
+                    "  {} {}",
                     style("✓").green().bold(),
-                    style(branch).cyan()
+                    tf("prune.deleted_branch", &[("branch", &style(branch).cyan())])
                 );
                 deleted_count += 1;
             }
             Err(err) => {
                 println!(
-                    "  {} Failed to delete {}: {}",
+                    "  {} {}",
+                    style("✗").red().bold(),
+                    tf(
+                        "prune.delete_failed",
+                        &[("branch", &style(branch).cyan()), ("error", &err)]
+                    )
+                );
+                failed_count += 1;
+                continue;
+            }
+        }
+
+        let Some((remote_name, remote_branch)) =
+            candidate.remote_branch.as_ref().filter(|_| remote)
+        else {
+            continue;
+        };
+        let label = style(format!("{remote_name}/{remote_branch}")).cyan();
+        match repo.delete_remote_branch(remote_name, remote_branch) {
+            Ok(()) => println!(
+                "  {} {}",
+                style("✓").green().bold(),
+                tf("prune.deleted_remote_branch", &[("branch", &label)])
+            ),
+            Err(err) => {
+                println!(
+                    "  {} {}",
                     style("✗").red().bold(),
-                    style(branch).cyan(),
-                    err
+                    tf(
+                        "prune.delete_remote_failed",
+                        &[("branch", &label), ("error", &err)]
+                    )
                 );
                 failed_count += 1;
             }
@@ -222,11 +422,11 @@ fn prune_branches(
 
     println!();
     println!(
-        "{} Deleted {} branches{}",
-        style("✨").green().bold(),
-        deleted_count,
+        "{} {}{}",
+        style(icon("✨")).green().bold(),
+        tf("prune.summary", &[("count", &deleted_count)]),
         if failed_count > 0 {
-            format!(", {failed_count} failed")
+            tf("prune.summary_failed", &[("count", &failed_count)])
         } else {
             String::new()
         }
@@ -234,3 +434,50 @@ fn prune_branches(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{show_dry_run_results, PruneCandidate, PruneReason};
+    use crate::tui::snapshot::{normalize, render};
+
+    #[test]
+    fn dry_run_lists_each_candidate_with_its_reason() {
+        let candidates = [
+            PruneCandidate {
+                branch: "feature/done".to_string(),
+                reason: PruneReason::MergedIntoTrunk,
+                remote_branch: None,
+            },
+            PruneCandidate {
+                branch: "fix/squashed".to_string(),
+                reason: PruneReason::PullRequestMerged {
+                    pr_number: 42,
+                    trunk: "main".to_string(),
+                },
+                remote_branch: None,
+            },
+        ];
+
+        let output = render(|out| show_dry_run_results(out, &candidates, false));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+
+    #[test]
+    fn dry_run_with_remote_lists_the_remote_branches_too() {
+        let candidates = [
+            PruneCandidate {
+                branch: "feature/done".to_string(),
+                reason: PruneReason::MergedIntoTrunk,
+                remote_branch: Some(("origin".to_string(), "feature/done".to_string())),
+            },
+            PruneCandidate {
+                branch: "local-only".to_string(),
+                reason: PruneReason::MergedIntoTrunk,
+                remote_branch: None,
+            },
+        ];
+
+        let output = render(|out| show_dry_run_results(out, &candidates, true));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
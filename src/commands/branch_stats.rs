@@ -1,54 +1,405 @@
+use crate::error::GitxError;
 use crate::{
+    git::branches::merge_cache::MergeBaseCache,
     git::GitRepo,
-    github::GitHubPrMatcher,
+    github::{
+        types::{PullRequestStatus, ResolvedPullRequest},
+        GitHubPrMatcher,
+    },
     tui::branch_display::{self, BranchInfo, MergeStatus},
+    tui::porcelain,
+    tui::template::Template,
+    tui::watch,
 };
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::Semaphore;
 
-/// Show statistics for all local branches
-pub async fn show_branch_stats() -> Result<(), Box<dyn std::error::Error>> {
+/// Most PR lookups `xg branch --stats` runs at once
+const PR_LOOKUP_CONCURRENCY: usize = 8;
+
+/// Show statistics for all local branches, optionally refreshing them as the repository changes.
+/// With `refresh`, PR information is fetched again rather than read from the cache.
+pub async fn show_branch_stats(
+    porcelain: bool,
+    watch_interval: Option<Duration>,
+    refresh: bool,
+) -> Result<(), GitxError> {
     let repo = GitRepo::open(".")?;
-    let branch_infos = gather_branch_data(&repo).await?;
-    branch_display::display_branch_stats(&branch_infos);
+
+    let Some(interval) = watch_interval else {
+        return render_branch_stats(&repo, porcelain, refresh).await;
+    };
+
+    let repo = &repo;
+    watch::watch(
+        repo.path(),
+        repo.git_dir(),
+        interval,
+        |path| repo.is_path_ignored(path),
+        move || render_branch_stats(repo, porcelain, refresh),
+    )
+    .await
+}
+
+async fn render_branch_stats(
+    repo: &GitRepo,
+    porcelain: bool,
+    refresh: bool,
+) -> Result<(), GitxError> {
+    let cache = repo.merge_base_cache()?;
+    let branch_infos = gather_branch_data(repo, &cache, pr_matcher(repo, refresh)).await?;
+    cache.save()?;
+    if porcelain {
+        for branch_info in &branch_infos {
+            println!("{}", porcelain::branch_stats_line(branch_info));
+        }
+    } else {
+        branch_display::display_branch_stats(&branch_infos)?;
+    }
     Ok(())
 }
 
-/// Gather all branch data from the git repository with GitHub PR information
-async fn gather_branch_data(repo: &GitRepo) -> Result<Vec<BranchInfo>, Box<dyn std::error::Error>> {
-    let branches = repo.get_all_branches()?;
-    let current_branch = repo.get_current_branch()?;
+/// Fields available to `xg branch --format`
+pub const BRANCH_FORMAT_FIELDS: &[&str] = &[
+    "name",
+    "current",
+    "sha",
+    "subject",
+    "upstream",
+    "ahead",
+    "behind",
+    "merged",
+    "pr.number",
+    "pr.state",
+    "pr.title",
+    "pr.url",
+    "pr.checks",
+    "pr.review",
+];
 
-    // Try to initialize GitHub PR matcher (optional - will gracefully fail if not a GitHub repo)
-    let github_matcher = GitHubPrMatcher::new(repo).ok();
+/// Print one line per local branch rendered through a `--format` template
+pub async fn show_branch_format(format: &str, refresh: bool) -> Result<(), GitxError> {
+    let template = Template::parse(format, BRANCH_FORMAT_FIELDS)
+        .map_err(|e| GitxError::Usage(e.to_string()))?;
+    let repo = GitRepo::open(".")?;
+    let cache = repo.merge_base_cache()?;
 
-    let mut branch_infos = Vec::new();
+    // PR lookups can hit the network, so only do them when the template asks for PR fields
+    let branch_infos = if template.uses_field_prefix("pr.") {
+        gather_branch_data(&repo, &cache, pr_matcher(&repo, refresh)).await?
+    } else {
+        gather_local_branch_data(&repo, &cache).await?
+    };
 
-    for branch in branches {
-        // Look up GitHub PR information if matcher is available
-        let pull_request = if let Some(ref matcher) = github_matcher {
-            matcher.find_pr_for_branch(repo, &branch).await
-        } else {
-            None
-        };
+    for branch_info in &branch_infos {
+        println!(
+            "{}",
+            template.render(|field| branch_field(branch_info, field))
+        );
+    }
+    cache.save()?;
+    Ok(())
+}
 
-        let branch_info = BranchInfo {
-            name: branch.clone(),
-            is_current: branch == current_branch,
-            commit_info: repo.get_branch_commit_info(&branch).ok(),
-            merge_status: get_merge_status(repo, &branch),
-            remote_tracking: repo.get_remote_tracking_info(&branch).ok(),
-            pull_request,
-        };
+/// PR matcher for the repository's GitHub remote; `None` when it has none
+fn pr_matcher(repo: &GitRepo, refresh: bool) -> Option<Arc<GitHubPrMatcher>> {
+    let matcher = GitHubPrMatcher::new(repo).ok()?;
+    Some(Arc::new(if refresh {
+        matcher.refreshing()
+    } else {
+        matcher
+    }))
+}
+
+fn branch_field(branch: &BranchInfo, field: &str) -> Option<String> {
+    let commit = branch
+        .commit_info
+        .as_deref()
+        .and_then(|info| info.split_once(' '));
+    let pr = branch.pull_request.as_ref().map(|pr| &pr.record);
+
+    match field {
+        "name" => Some(branch.name.clone()),
+        "current" => Some(if branch.is_current { "*" } else { " " }.to_string()),
+        "sha" => commit.map(|(sha, _)| sha.to_string()),
+        "subject" => commit.map(|(_, subject)| subject.to_string()),
+        "upstream" => branch.remote_tracking.clone(),
+        "ahead" => branch.ahead_behind.map(|(ahead, _)| ahead.to_string()),
+        "behind" => branch.ahead_behind.map(|(_, behind)| behind.to_string()),
+        "merged" => match branch.merge_status {
+            MergeStatus::Merged => Some("true".to_string()),
+            MergeStatus::NotMerged => Some("false".to_string()),
+            MergeStatus::Unknown => None,
+        },
+        "pr.number" => pr.map(|pr| pr.pr_number.to_string()),
+        "pr.state" => pr.map(|pr| porcelain::pr_state(pr).to_string()),
+        "pr.title" => pr.map(|pr| pr.title.clone()),
+        "pr.url" => pr.map(|pr| pr.url.clone()),
+        "pr.checks" => pr
+            .and_then(|pr| pr.checks)
+            .map(|checks| porcelain::checks_state(checks).to_string()),
+        "pr.review" => pr
+            .and_then(|pr| pr.review_decision)
+            .map(|review| porcelain::review_decision(review).to_string()),
+        _ => None,
+    }
+}
+
+/// Gather branch data available without contacting GitHub, one blocking task per branch.
+///
+/// Merged status for every branch comes from a single walk of main's history up front.
+async fn gather_local_branch_data(
+    repo: &GitRepo,
+    cache: &MergeBaseCache,
+) -> Result<Vec<BranchInfo>, GitxError> {
+    let current_branch = repo.get_current_branch().ok();
+    let pool = Arc::new(repo.pool()?);
+    let branches = repo.get_all_branches()?;
+    let merged = repo
+        .branches_merged_to_main(cache, &branches)
+        .unwrap_or_default();
+
+    let tasks: Vec<_> = branches
+        .into_iter()
+        .map(|branch| {
+            let pool = Arc::clone(&pool);
+            let is_current = current_branch.as_deref() == Some(branch.as_str());
+            let merge_status = match merged.get(&branch) {
+                Some(true) => MergeStatus::Merged,
+                Some(false) => MergeStatus::NotMerged,
+                None => MergeStatus::Unknown,
+            };
+            tokio::task::spawn_blocking(move || {
+                pool.with(|repo| Ok(local_branch_info(repo, branch, is_current, merge_status)))
+            })
+        })
+        .collect();
+
+    let mut branch_infos = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let mut branch_info = task
+            .await
+            .map_err(|e| anyhow::anyhow!("Branch stats task failed: {e}"))??;
+        branch_info.ahead_behind = branch_info.remote_tracking.as_deref().and_then(|upstream| {
+            repo.ahead_behind_cached(cache, &branch_info.name, upstream)
+                .ok()
+        });
         branch_infos.push(branch_info);
     }
+    Ok(branch_infos)
+}
+
+fn local_branch_info(
+    repo: &GitRepo,
+    branch: String,
+    is_current: bool,
+    merge_status: MergeStatus,
+) -> BranchInfo {
+    BranchInfo {
+        is_current,
+        commit_info: repo.get_branch_commit_info(&branch).ok(),
+        merge_status,
+        remote_tracking: repo.get_remote_tracking_info(&branch).ok(),
+        // Filled in from the merge-base cache once every branch is gathered
+        ahead_behind: None,
+        pull_request: None,
+        name: branch,
+    }
+}
+
+/// Gather all branch data from the git repository, with PR information when `matcher` is given
+async fn gather_branch_data(
+    repo: &GitRepo,
+    cache: &MergeBaseCache,
+    matcher: Option<Arc<GitHubPrMatcher>>,
+) -> Result<Vec<BranchInfo>, GitxError> {
+    let mut branch_infos = gather_local_branch_data(repo, cache).await?;
+
+    if let Some(matcher) = matcher {
+        let branches = branch_infos.iter().map(|info| info.name.clone()).collect();
+        let pull_requests = lookup_pull_requests(repo, matcher, branches).await?;
+        for (branch_info, pull_request) in branch_infos.iter_mut().zip(pull_requests) {
+            branch_info.pull_request = pull_request;
+        }
+    }
 
     Ok(branch_infos)
 }
 
-/// Determine the merge status of a branch
-fn get_merge_status(repo: &GitRepo, branch: &str) -> MergeStatus {
-    match repo.is_branch_merged_to_main(branch) {
-        Ok(true) => MergeStatus::Merged,
-        Ok(false) => MergeStatus::NotMerged,
-        Err(_) => MergeStatus::Unknown,
+/// Look up the PR of every branch, in order, with at most [`PR_LOOKUP_CONCURRENCY`] lookups
+/// running at once.
+///
+/// Lookups run on the blocking pool because the `gh` backend waits on a child process.
+async fn lookup_pull_requests(
+    repo: &GitRepo,
+    matcher: Arc<GitHubPrMatcher>,
+    branches: Vec<String>,
+) -> Result<Vec<Option<ResolvedPullRequest>>, GitxError> {
+    let pool = Arc::new(repo.pool()?);
+    let permits = Arc::new(Semaphore::new(PR_LOOKUP_CONCURRENCY));
+    let runtime = Handle::current();
+
+    let mut tasks = Vec::with_capacity(branches.len());
+    for branch in branches {
+        let permit = Arc::clone(&permits)
+            .acquire_owned()
+            .await
+            .map_err(|e| anyhow::anyhow!("PR lookup was cancelled: {e}"))?;
+        let pool = Arc::clone(&pool);
+        let matcher = Arc::clone(&matcher);
+        let runtime = runtime.clone();
+        tasks.push(tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            pool.with(|repo| Ok(runtime.block_on(pull_request_for_branch(&matcher, repo, &branch))))
+        }));
+    }
+
+    let mut pull_requests = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let pull_request = task
+            .await
+            .map_err(|e| anyhow::anyhow!("PR lookup task failed: {e}"))??;
+        pull_requests.push(pull_request);
+    }
+    Ok(pull_requests)
+}
+
+/// The PR for `branch`, with the CI and review state of open ones
+async fn pull_request_for_branch(
+    matcher: &GitHubPrMatcher,
+    repo: &GitRepo,
+    branch: &str,
+) -> Option<ResolvedPullRequest> {
+    let mut pull_request = matcher.find_pr_for_branch(repo, branch).await;
+    // Checks are extra detail: keep the PR without them when they cannot be fetched
+    if let Some(pr) = pull_request.as_mut().filter(|pr| !pr.is_stale) {
+        if pr.record.status == PullRequestStatus::Open {
+            if let Ok(record) = matcher.service().load_checks(&pr.record).await {
+                pr.record = record;
+            }
+        }
+    }
+    pull_request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        branch_field, gather_branch_data, gather_local_branch_data, BranchInfo, MergeStatus,
+    };
+    use crate::github::types::{ChecksState, PullRequestStatus, ReviewDecision};
+    use crate::github::GitHubPrMatcher;
+    use crate::test_utils::{
+        create_test_bare_repo, create_test_repo, MockForge, RepoTestOperations,
+    };
+    use crate::tui::branch_display::write_branch_stats;
+    use crate::tui::snapshot::{normalize, render};
+    use std::sync::Arc;
+
+    #[test]
+    fn branch_field_splits_commit_info_and_leaves_missing_values_empty() {
+        let mut branch = BranchInfo {
+            name: "feature".to_string(),
+            is_current: true,
+            commit_info: Some("abc1234 Add feature".to_string()),
+            merge_status: MergeStatus::Unknown,
+            remote_tracking: None,
+            ahead_behind: Some((2, 1)),
+            pull_request: None,
+        };
+
+        assert_eq!(branch_field(&branch, "sha").as_deref(), Some("abc1234"));
+        assert_eq!(
+            branch_field(&branch, "subject").as_deref(),
+            Some("Add feature")
+        );
+        assert_eq!(branch_field(&branch, "current").as_deref(), Some("*"));
+        assert_eq!(branch_field(&branch, "behind").as_deref(), Some("1"));
+
+        assert_eq!(branch_field(&branch, "merged"), None);
+        assert_eq!(branch_field(&branch, "pr.state"), None);
+
+        branch.ahead_behind = None;
+        assert_eq!(branch_field(&branch, "ahead"), None);
+    }
+
+    #[tokio::test]
+    async fn branch_stats_for_a_repository() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature", "Add feature")?;
+
+        let branch_infos = gather_local_branch_data(&repo, &repo.merge_base_cache()?).await?;
+        let output = render(|out| write_branch_stats(out, &branch_infos));
+        insta::assert_snapshot!(normalize(&output, Some(repo.path())));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn branch_stats_count_commits_ahead_and_behind_upstream(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_remote_dir, remote) = create_test_bare_repo();
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature", "Add feature")?;
+        repo.add_local_remote("origin", &remote)?;
+        repo.push("origin", "feature")?;
+        git2::Repository::open(repo.path())?
+            .find_branch("feature", git2::BranchType::Local)?
+            .set_upstream(Some("origin/feature"))?;
+        repo.add_file_and_commit("more.txt", "more", "Add more")?;
+
+        let branch_infos = gather_local_branch_data(&repo, &repo.merge_base_cache()?).await?;
+        let counts: Vec<_> = branch_infos
+            .iter()
+            .map(|branch| (branch.name.as_str(), branch.ahead_behind))
+            .collect();
+        assert_eq!(counts, vec![("feature", Some((1, 0))), ("master", None)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn branch_stats_attach_pull_requests_from_the_forge(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature", "Add feature")?;
+        repo.add_remote("origin", "https://github.com/octo/repo.git")?;
+        let mut pull_request = MockForge::pull_request(12, "feature", PullRequestStatus::Open);
+        pull_request.checks = Some(ChecksState::Passing);
+        pull_request.review_decision = Some(ReviewDecision::ChangesRequested);
+        let forge = MockForge::new().with_pr(pull_request);
+        let matcher = Arc::new(GitHubPrMatcher::with_forge(&repo, Box::new(forge.clone()))?);
+
+        let branch_infos =
+            gather_branch_data(&repo, &repo.merge_base_cache()?, Some(matcher)).await?;
+        let pr_numbers: Vec<_> = branch_infos
+            .iter()
+            .map(|branch| {
+                let pr = branch.pull_request.as_ref();
+                (branch.name.as_str(), pr.map(|pr| pr.record.pr_number))
+            })
+            .collect();
+        assert_eq!(pr_numbers, vec![("feature", Some(12)), ("master", None)]);
+
+        let feature = &branch_infos[0];
+        assert_eq!(
+            branch_field(feature, "pr.checks").as_deref(),
+            Some("passing")
+        );
+        assert_eq!(
+            branch_field(feature, "pr.review").as_deref(),
+            Some("changes-requested")
+        );
+        assert!(forge
+            .calls()
+            .iter()
+            .any(|call| call.starts_with("get_pr_checks 12")));
+        Ok(())
     }
 }
@@ -0,0 +1,497 @@
+use super::log::format_date;
+use super::version::{latest_version_tag, PendingRelease, Version};
+use crate::ai;
+use crate::config::AiProvider;
+use crate::error::GitxError;
+use crate::git::commits::conventional::{is_generated_message, ConventionalCommit};
+use crate::git::repository::core::CommitInfo;
+use crate::git::GitRepo;
+use crate::github::GitHubPrMatcher;
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use console::style;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+const CHANGELOG_HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+/// How the AI is told to format a section when no template is given
+const KEEP_A_CHANGELOG_FORMAT: &str = "Keep a Changelog (https://keepachangelog.com/en/1.1.0/): \
+a `## [version] - date` heading, then `### Added`, `### Changed`, `### Deprecated`, \
+`### Removed`, `### Fixed` and `### Security` subsections as needed, each a bullet list.";
+
+/// Keep a Changelog groups in the order they are written, with the commit types that go in
+/// each; other types only appear when they are breaking changes, under "Changed"
+const GROUPS: &[(&str, &[&str])] = &[
+    ("Added", &["feat"]),
+    ("Changed", &["refactor", "perf", "revert"]),
+    ("Fixed", &["fix"]),
+];
+
+/// One changelog line
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    group: &'static str,
+    scope: Option<String>,
+    description: String,
+    breaking: bool,
+    /// Pull request the commit came from, by number
+    pr_number: Option<u64>,
+    sha: String,
+}
+
+/// Write a Keep a Changelog section for the next release (or `[Unreleased]`) from the
+/// Conventional Commits since the last version tag, then stage `CHANGELOG.md`.
+///
+/// An existing section for the same release or `[Unreleased]` is regenerated; otherwise the
+/// section goes above the previous releases. PR numbers from squash-merge subjects and the
+/// local PR index are linked through the forge.
+pub async fn handle_changelog_update(unreleased: bool, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| GitxError::Usage("`xg changelog update` needs a working tree".to_string()))?
+        .to_path_buf();
+
+    let pending = PendingRelease::load(&repo, &workdir)?;
+    let mut entries = collect_entries(&pending.commits);
+    if entries.is_empty() {
+        println!(
+            "{} No feat, fix, refactor, perf or breaking commits since {}; nothing to add",
+            style(icon("✨")).green().bold(),
+            pending.since()
+        );
+        return Ok(());
+    }
+
+    let pr_urls = link_pull_requests(&repo, &mut entries).await;
+
+    let heading = match pending.next_version() {
+        Some(version) if !unreleased => {
+            let offset_minutes = pending
+                .commits
+                .first()
+                .map_or(0, |commit| commit.offset_minutes);
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs() as i64)
+                .unwrap_or_default();
+            format!("[{version}] - {}", format_date(now, offset_minutes))
+        }
+        _ => "[Unreleased]".to_string(),
+    };
+    let section = render_section(&heading, &entries, &pr_urls);
+
+    let path = workdir.join(CHANGELOG_FILE);
+    let existing = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let updated = insert_section(&existing, &heading, &section);
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "write this section to {CHANGELOG_FILE} and stage it:"
+        ));
+        for line in section.lines() {
+            println!("      {line}");
+        }
+        return Ok(());
+    }
+
+    fs::write(&path, updated)?;
+    repo.add(&[CHANGELOG_FILE])?;
+    println!(
+        "{} Wrote {} with {} entr{} to {CHANGELOG_FILE} and staged it",
+        style("✓").green().bold(),
+        style(format!("## {heading}")).cyan(),
+        entries.len(),
+        if entries.len() == 1 { "y" } else { "ies" }
+    );
+    Ok(())
+}
+
+/// Write a changelog section for the commits in `from..to`, drafted from their Conventional
+/// Commits and polished by the AI in Keep a Changelog format, or in the format `template`
+/// describes. `from` defaults to the latest version tag; a version tag as `to` titles the
+/// section with its version and date, anything else with `[Unreleased]`.
+///
+/// The section goes into `CHANGELOG.md` the same way `xg changelog update` puts it there, or
+/// to stdout with `stdout`.
+pub async fn handle_changelog_generate(
+    from: Option<&str>,
+    to: &str,
+    template: Option<&Path>,
+    no_ai: bool,
+    stdout: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let range = match from {
+        Some(from) => format!("{from}..{to}"),
+        None => match latest_version_tag(&repo)? {
+            Some((_, tag)) if tag != to => format!("{tag}..{to}"),
+            _ => to.to_string(),
+        },
+    };
+    let commits = repo.list_commits_range(&range, 0, None)?;
+    let mut entries = collect_entries(&commits);
+    if entries.is_empty() {
+        println!(
+            "{} No feat, fix, refactor, perf or breaking commits in {range}; nothing to write",
+            style(icon("✨")).green().bold()
+        );
+        return Ok(());
+    }
+    let pr_urls = link_pull_requests(&repo, &mut entries).await;
+
+    let heading = match (
+        Version::parse(to),
+        repo.list_commits_range(to, 0, Some(1))?.first(),
+    ) {
+        (Some(version), Some(tip)) => {
+            format!(
+                "[{version}] - {}",
+                format_date(tip.time, tip.offset_minutes)
+            )
+        }
+        _ => "[Unreleased]".to_string(),
+    };
+    let draft = render_section(&heading, &entries, &pr_urls);
+    let format = match template {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|err| GitxError::Usage(format!("Failed to read {}: {err}", path.display())))?,
+        None => KEEP_A_CHANGELOG_FORMAT.to_string(),
+    };
+    let section = match polish_section(&repo, &draft, &format, no_ai)? {
+        Some(section) => section,
+        None => {
+            if template.is_some() {
+                eprintln!(
+                    "{} No AI available to follow the template; using Keep a Changelog",
+                    style("⚠").yellow().bold()
+                );
+            }
+            draft
+        }
+    };
+
+    if stdout {
+        print!("{section}");
+        return Ok(());
+    }
+
+    let workdir = repo.workdir().ok_or_else(|| {
+        GitxError::Usage(
+            "`xg changelog generate` needs a working tree; pass --stdout to print the section"
+                .to_string(),
+        )
+    })?;
+    let path = workdir.join(CHANGELOG_FILE);
+    let existing = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("write this section to {CHANGELOG_FILE}:"));
+        for line in section.lines() {
+            println!("      {line}");
+        }
+        return Ok(());
+    }
+
+    fs::write(&path, insert_section(&existing, &heading, &section))?;
+    println!(
+        "{} Wrote {} for {} to {CHANGELOG_FILE}",
+        style("✓").green().bold(),
+        style(format!("## {heading}")).cyan(),
+        style(&range).dim()
+    );
+    Ok(())
+}
+
+/// Ask the AI to polish `draft`, or `None` when it gives nothing back, `no_ai` is set or the
+/// AI provider is turned off
+fn polish_section(
+    repo: &GitRepo,
+    draft: &str,
+    format: &str,
+    no_ai: bool,
+) -> Result<Option<String>, GitxError> {
+    if no_ai || repo.xgit_config()?.ai_provider() == AiProvider::Disabled {
+        return Ok(None);
+    }
+
+    eprintln!(
+        "{} Polishing the changelog section",
+        style(icon("🤖")).cyan().bold()
+    );
+    Ok(ai::generate_changelog_section(draft, format)?)
+}
+
+/// Fill in each entry's PR number from the local PR index and look up the PR URLs through
+/// the forge, by number. Without a GitHub remote nothing is linked.
+async fn link_pull_requests(repo: &GitRepo, entries: &mut [Entry]) -> HashMap<u64, String> {
+    let mut pr_urls: HashMap<u64, String> = HashMap::new();
+    let Ok(matcher) = GitHubPrMatcher::new(repo) else {
+        return pr_urls;
+    };
+    for entry in entries {
+        if entry.pr_number.is_none() {
+            entry.pr_number = matcher
+                .service()
+                .get_cached_by_commit(&entry.sha)
+                .ok()
+                .flatten()
+                .map(|record| record.pr_number);
+        }
+        let Some(number) = entry.pr_number else {
+            continue;
+        };
+        if pr_urls.contains_key(&number) {
+            continue;
+        }
+        let record = match matcher.service().get_cached_pr(number).ok().flatten() {
+            Some(record) => Some(record),
+            None => matcher.service().get_pr(number).await.ok(),
+        };
+        if let Some(record) = record {
+            pr_urls.insert(number, record.url);
+        }
+    }
+    pr_urls
+}
+
+/// Changelog entries for `commits` (newest first), oldest first within the changelog
+fn collect_entries(commits: &[CommitInfo]) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for commit in commits.iter().rev() {
+        if is_generated_message(&commit.message) {
+            continue;
+        }
+        let Ok(parsed) = ConventionalCommit::parse(&commit.message) else {
+            continue;
+        };
+        let group = GROUPS
+            .iter()
+            .find(|(_, types)| types.contains(&parsed.commit_type.as_str()))
+            .map(|(group, _)| *group);
+        let Some(group) = group.or_else(|| parsed.breaking.then(|| "Changed")) else {
+            continue;
+        };
+        let (description, pr_number) = split_pr_suffix(&parsed.description);
+        entries.push(Entry {
+            group,
+            scope: parsed.scope,
+            description: description.to_string(),
+            breaking: parsed.breaking,
+            pr_number,
+            sha: commit.hash.clone(),
+        });
+    }
+    entries
+}
+
+/// Split the ` (#123)` GitHub appends to squash-merged subjects off a description
+pub(crate) fn split_pr_suffix(description: &str) -> (&str, Option<u64>) {
+    let number = description
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" (#"))
+        .and_then(|(text, number)| Some((text, number.parse::<u64>().ok()?)));
+    match number {
+        Some((text, number)) => (text.trim_end(), Some(number)),
+        None => (description, None),
+    }
+}
+
+fn render_section(heading: &str, entries: &[Entry], pr_urls: &HashMap<u64, String>) -> String {
+    let mut section = format!("## {heading}\n");
+    for (group, _) in GROUPS {
+        let lines: Vec<String> = entries
+            .iter()
+            .filter(|entry| entry.group == *group)
+            .map(|entry| {
+                let mut line = String::from("- ");
+                if entry.breaking {
+                    line.push_str("**Breaking:** ");
+                }
+                if let Some(scope) = &entry.scope {
+                    line.push_str(&format!("**{scope}:** "));
+                }
+                line.push_str(&entry.description);
+                match (
+                    entry.pr_number,
+                    entry.pr_number.and_then(|n| pr_urls.get(&n)),
+                ) {
+                    (Some(number), Some(url)) => line.push_str(&format!(" ([#{number}]({url}))")),
+                    (Some(number), None) => line.push_str(&format!(" (#{number})")),
+                    _ => line.push_str(&format!(" ({})", &entry.sha[..7.min(entry.sha.len())])),
+                }
+                line
+            })
+            .collect();
+        if !lines.is_empty() {
+            section.push_str(&format!("\n### {group}\n\n{}\n", lines.join("\n")));
+        }
+    }
+    section
+}
+
+/// Put `section` into `existing`: in place of the section with the same heading or
+/// `[Unreleased]`, else above the first release section, else at the end
+fn insert_section(existing: &str, heading: &str, section: &str) -> String {
+    if existing.trim().is_empty() {
+        return format!("{CHANGELOG_HEADER}\n{section}");
+    }
+
+    let version = heading.split(" - ").next().unwrap_or(heading);
+    let mut offset = 0;
+    let mut first_section = None;
+    let mut replace = None;
+    for line in existing.split_inclusive('\n') {
+        if let Some(title) = line.trim_end().strip_prefix("## ") {
+            if let Some(start) = replace {
+                // End of the section being replaced
+                return format!("{}{section}\n{}", &existing[..start], &existing[offset..]);
+            }
+            first_section.get_or_insert(offset);
+            if title == "[Unreleased]" || title.split(" - ").next() == Some(version) {
+                replace = Some(offset);
+            }
+        }
+        offset += line.len();
+    }
+
+    match (replace, first_section) {
+        (Some(start), _) => format!("{}{section}", &existing[..start]),
+        (None, Some(start)) => format!("{}{section}\n{}", &existing[..start], &existing[start..]),
+        (None, None) => format!("{}\n\n{section}", existing.trim_end()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        collect_entries, insert_section, render_section, split_pr_suffix, CHANGELOG_HEADER,
+    };
+    use crate::git::repository::core::CommitInfo;
+    use std::collections::HashMap;
+
+    fn commit(hash: &str, message: &str) -> CommitInfo {
+        CommitInfo {
+            hash: hash.repeat(40),
+            message: message.to_string(),
+            author_name: "Test".to_string(),
+            author_email: "test@example.com".to_string(),
+            time: 0,
+            offset_minutes: 0,
+            parent_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn split_pr_suffix_reads_squash_merge_numbers() {
+        assert_eq!(split_pr_suffix("Add hooks (#42)"), ("Add hooks", Some(42)));
+        assert_eq!(
+            split_pr_suffix("Add hooks (draft)"),
+            ("Add hooks (draft)", None)
+        );
+        assert_eq!(split_pr_suffix("Add hooks"), ("Add hooks", None));
+    }
+
+    #[test]
+    fn entries_are_grouped_and_linked() {
+        // Newest first, as git lists them
+        let commits = [
+            commit("d", "chore: Bump deps"),
+            commit("c", "fix(cli): Handle empty diffs (#7)"),
+            commit("b", "docs!: Drop the old config section"),
+            commit("a", "feat: Add hooks"),
+            commit("e", "Merge branch 'topic'"),
+        ];
+        let entries = collect_entries(&commits);
+        let urls = HashMap::from([(7, "https://github.com/o/r/pull/7".to_string())]);
+
+        assert_eq!(
+            render_section("[0.2.0] - 2026-10-15", &entries, &urls),
+            "## [0.2.0] - 2026-10-15
+
+### Added
+
+- Add hooks (aaaaaaa)
+
+### Changed
+
+- **Breaking:** Drop the old config section (bbbbbbb)
+
+### Fixed
+
+- **cli:** Handle empty diffs ([#7](https://github.com/o/r/pull/7))
+"
+        );
+    }
+
+    #[test]
+    fn insert_section_creates_prepends_and_regenerates() {
+        let first = insert_section("", "[Unreleased]", "## [Unreleased]\n\n- one\n");
+        assert_eq!(
+            first,
+            format!("{CHANGELOG_HEADER}\n## [Unreleased]\n\n- one\n")
+        );
+
+        // The release replaces [Unreleased]
+        let released = insert_section(
+            &first,
+            "[0.2.0] - 2026-10-15",
+            "## [0.2.0] - 2026-10-15\n\n- one\n",
+        );
+        assert_eq!(
+            released,
+            format!("{CHANGELOG_HEADER}\n## [0.2.0] - 2026-10-15\n\n- one\n")
+        );
+
+        // A later run on another day regenerates the same release
+        let regenerated = insert_section(
+            &released,
+            "[0.2.0] - 2026-10-16",
+            "## [0.2.0] - 2026-10-16\n\n- two\n",
+        );
+        assert_eq!(
+            regenerated,
+            format!("{CHANGELOG_HEADER}\n## [0.2.0] - 2026-10-16\n\n- two\n")
+        );
+
+        let next = insert_section(
+            &regenerated,
+            "[0.3.0] - 2026-11-01",
+            "## [0.3.0] - 2026-11-01\n\n- three\n",
+        );
+        assert_eq!(
+            next,
+            format!(
+                "{CHANGELOG_HEADER}\n## [0.3.0] - 2026-11-01\n\n- three\n\n## [0.2.0] - 2026-10-16\n\n- two\n"
+            )
+        );
+        assert_eq!(
+            insert_section(&next, "[0.3.0] - 2026-11-02", "## [0.3.0] - 2026-11-02\n\n- four\n"),
+            format!(
+                "{CHANGELOG_HEADER}\n## [0.3.0] - 2026-11-02\n\n- four\n\n## [0.2.0] - 2026-10-16\n\n- two\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::commits::cherry_pick::CherryPickOutcome;
+use crate::git::GitRepo;
+use crate::tui::dry_run;
+use console::style;
+
+/// Apply a commit on top of HEAD with its original author and message. Anything in `args` but
+/// a single commit, such as options, ranges or several commits, is handed to `git cherry-pick`.
+///
+/// A conflicting pick exits with the conflict code and lists the files to resolve; the pick
+/// stays in progress so `git commit` or `git cherry-pick --abort` can finish it.
+pub fn handle_cherry_pick(args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let commit = match args {
+        [commit] if !commit.starts_with('-') && !commit.contains("..") => commit.as_str(),
+        _ => return git_passthrough("cherry-pick", args, dry_run),
+    };
+    let repo = GitRepo::open(".")?;
+    if dry_run {
+        dry_run::print_header();
+        let branch = repo
+            .get_current_branch()
+            .unwrap_or_else(|_| "HEAD".to_string());
+        dry_run::print_operation(&format!("cherry-pick '{commit}' onto '{branch}'"));
+        return Ok(());
+    }
+
+    match repo.cherry_pick(commit)? {
+        CherryPickOutcome::Picked {
+            id,
+            original,
+            summary,
+        } => {
+            println!(
+                "{} Picked {} as {} {summary}",
+                style("✓").green().bold(),
+                style(&original[..7]).yellow(),
+                style(&id[..7]).yellow()
+            );
+            Ok(())
+        }
+        CherryPickOutcome::Conflicted {
+            original,
+            summary,
+            paths,
+        } => {
+            let files: String = paths.iter().map(|path| format!("\n    {path}")).collect();
+            Err(GitxError::Conflict(anyhow::anyhow!(
+                "{} {summary} conflicts with HEAD in:{files}\n  Resolve the conflicts and stage them with `xg add`, then run `xg commit --no-edit`; or `git cherry-pick --abort` to stop",
+                &original[..7]
+            )))
+        }
+    }
+}
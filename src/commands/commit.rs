@@ -1,44 +1,151 @@
 use super::git_passthrough::git_passthrough;
-use crate::{ai, git::GitRepo};
+use super::large_files::check_staged_sizes;
+use super::lint_commits::{describe_problems, has_errors};
+use super::pre_commit::run_pre_commit_checks;
+use crate::config::{AiProvider, CommitLintMode};
+use crate::error::GitxError;
+use crate::git::commits::builder::CommitOptions;
+use crate::git::commits::commitlint::lint_message;
+use crate::git::commits::diff_text::DiffTextOptions;
+use crate::github::client::GitHubClient;
+use crate::github::issues::{
+    append_issue_link, Issue, IssueCache, IssueKeyword, IssueLink, DEFAULT_ISSUE_CACHE_TTL_SECS,
+};
+use crate::{ai, git::GitRepo, tui::confirm, tui::diff_display, tui::dry_run};
 use console::style;
-use std::fs;
-use std::process::Command;
+use inquire::{InquireError, Select, Text};
+use std::fmt;
 
-pub fn handle_commit(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
-    // Check if user provided commit message or other flags that should bypass interactive mode
-    let has_message_flag = args.iter().any(|arg| {
-        arg == "-m"
-            || arg == "--message"
-            || arg == "-F"
-            || arg == "--file"
-            || arg == "-C"
-            || arg == "--reuse-message"
-            || arg == "-c"
-            || arg == "--reedit-message"
-            || arg == "--fixup"
-            || arg == "--squash"
-            || arg.starts_with("-m=")
-            || arg.starts_with("--message=")
-    });
+pub(crate) const AI_DIFF_MAX_BYTES: usize = 100_000;
+pub(crate) const AI_DIFF_MAX_FILE_BYTES: usize = 20_000;
 
-    // If user provided message flags or other args, use passthrough mode
-    if has_message_flag || !args.is_empty() {
-        return passthrough_commit(args);
+pub async fn handle_commit(args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let native = NativeCommitArgs::parse(args);
+    if native.is_none() && args.iter().any(|arg| arg.starts_with("--issue")) {
+        return Err(GitxError::Usage(
+            "--issue only combines with -m, --amend, --no-edit, --no-verify, --signoff, --gpg-sign and --allow-empty"
+                .to_string(),
+        ));
     }
 
-    // Otherwise, use AI-assisted commit
-    ai_commit()
+    // --no-verify skips xg's size guard and pre-commit checks as well as git's hooks
+    let no_verify = match &native {
+        Some(native) => native.no_verify,
+        None => args.iter().any(|arg| arg == "--no-verify" || arg == "-n"),
+    };
+    if !no_verify {
+        let repo = GitRepo::open(".")?;
+        check_staged_sizes(&repo, !dry_run)?;
+        if !dry_run {
+            run_pre_commit_checks(&repo)?;
+        }
+    }
+
+    match native {
+        Some(native) => native_commit(&native, dry_run).await,
+        // Flags the native path does not understand go to git as they are
+        None => passthrough_commit(args, dry_run),
+    }
 }
 
-fn passthrough_commit(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
-    git_passthrough("commit", args)
+/// The `git commit` flags xg commits with itself; any other flag is passed through to git
+#[derive(Debug, Default, PartialEq, Eq)]
+struct NativeCommitArgs {
+    /// Each `-m` is a paragraph of the message; none means the message is generated
+    messages: Vec<String>,
+    no_verify: bool,
+    allow_empty: bool,
+    signoff: bool,
+    gpg_sign: bool,
+    /// Rewrite the tip commit instead of adding one
+    amend: bool,
+    /// Keep the amended commit's message
+    no_edit: bool,
+    /// Link the commit to an issue
+    issue: Option<IssueArg>,
+    /// Link the issue with `Refs` rather than `Closes`
+    refs: bool,
 }
 
-fn ai_commit() -> Result<(), Box<dyn std::error::Error>> {
-    // Check if there are staged changes
-    let git_repo = GitRepo::open(".")?;
+/// Which issue `--issue` links
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IssueArg {
+    /// `--issue`: pick one of the open issues assigned to the user
+    Pick,
+    /// `--issue=N`
+    Number(u64),
+}
+
+impl NativeCommitArgs {
+    fn parse(args: &[String]) -> Option<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-m" | "--message" => parsed.messages.push(args.next()?.clone()),
+                "-n" | "--no-verify" => parsed.no_verify = true,
+                "--allow-empty" => parsed.allow_empty = true,
+                "-s" | "--signoff" => parsed.signoff = true,
+                "-S" | "--gpg-sign" => parsed.gpg_sign = true,
+                "--amend" => parsed.amend = true,
+                "--no-edit" => parsed.no_edit = true,
+                "--issue" => parsed.issue = Some(IssueArg::Pick),
+                "--refs" => parsed.refs = true,
+                _ => {
+                    if let Some(number) = arg.strip_prefix("--issue=") {
+                        let number = number.trim_start_matches('#').parse().ok()?;
+                        parsed.issue = Some(IssueArg::Number(number));
+                        continue;
+                    }
+                    let message = arg.strip_prefix("--message=")?;
+                    parsed.messages.push(message.to_string());
+                }
+            }
+        }
+        // --no-edit alone finishes a stopped merge or cherry-pick, which git does; an amend
+        // only takes a message
+        let amend_flags =
+            parsed.no_verify || parsed.allow_empty || parsed.signoff || parsed.gpg_sign;
+        if (parsed.no_edit && !parsed.amend)
+            || (parsed.amend && amend_flags)
+            || (parsed.refs && parsed.issue.is_none())
+        {
+            return None;
+        }
+        Some(parsed)
+    }
+
+    fn options(&self, message: String) -> CommitOptions {
+        CommitOptions {
+            run_hooks: true,
+            no_verify: self.no_verify,
+            allow_empty: self.allow_empty,
+            signoff: self.signoff,
+            gpg_sign: self.gpg_sign,
+            ..CommitOptions::new(message)
+        }
+    }
+}
 
-    if !git_repo.has_staged_changes()? {
+fn passthrough_commit(args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    if dry_run {
+        // git commit has a native dry run that lists what would be committed
+        let mut args = args.to_vec();
+        args.push("--dry-run".to_string());
+        return git_passthrough("commit", &args, false);
+    }
+    git_passthrough("commit", args, false)
+}
+
+/// Commit the index through [`GitRepo::commit_with`], generating the message with the AI
+/// and letting the user edit it when no `-m` is given
+async fn native_commit(args: &NativeCommitArgs, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    if args.amend {
+        return amend_commit(&repo, args, dry_run).await;
+    }
+
+    if !args.allow_empty && !repo.has_staged_changes()? {
         eprintln!(
             "{} No changes staged for commit.",
             style("⚠").yellow().bold()
@@ -46,43 +153,483 @@ fn ai_commit() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Get the diff for AI processing
-    let diff_text = git_repo.diff_staged()?;
+    let message = if args.messages.is_empty() {
+        let staged_diff = repo.get_staged_diff()?;
+        diff_display::display_diffstat(&repo.diff_stats(&staged_diff)?);
+        let diff_text = ai_diff_text(&repo, &staged_diff)?;
+        let count = repo.xgit_config()?.ai_suggestions();
+        let suggestions = suggest_messages(&repo, &diff_text, count, false)?;
+        if dry_run {
+            dry_run::print_header();
+            match suggestions.first() {
+                Some(message) => dry_run::print_operation(&format!(
+                    "commit staged changes with message:\n\n{}\n",
+                    link_issue(&repo, message, args, dry_run).await?
+                )),
+                None => dry_run::print_operation("commit staged changes with a message you enter"),
+            }
+            return Ok(());
+        }
+        choose_message(
+            &repo,
+            &diff_text,
+            suggestions,
+            repo.commit_template()?.as_deref(),
+        )?
+    } else {
+        args.messages.join("\n\n")
+    };
+    let message = link_issue(&repo, &message, args, dry_run).await?;
+    if !args.no_verify {
+        lint_commit_message(&repo, &message)?;
+    }
 
-    // Try to generate commit message with Claude
-    let generated_message = ai::generate_commit_message(&diff_text)?;
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "commit staged changes with message:\n\n{message}\n"
+        ));
+        return Ok(());
+    }
+
+    let id = repo.commit_with(&args.options(message.clone()))?;
+    println!(
+        "{} Committed {} {}",
+        style("✓").green().bold(),
+        style(&id[..7]).yellow(),
+        message.lines().next().unwrap_or_default()
+    );
+    Ok(())
+}
 
-    if let Some(message) = generated_message {
-        // Write generated message to a temporary file with comment
-        let temp_file = "/tmp/gitx_commit_template";
-        let template_content = format!(
-            "{message}\n\n# Generated by gitx with Claude AI\n# Edit the message above and save to commit"
+/// Rewrite the tip commit with the index. Without `-m` or `--no-edit` the AI suggests a
+/// message for the combined changes, falling back to the current one.
+async fn amend_commit(
+    repo: &GitRepo,
+    args: &NativeCommitArgs,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let current = repo
+        .list_commits_range("HEAD", 0, Some(1))?
+        .into_iter()
+        .next()
+        .map(|commit| commit.message)
+        .ok_or_else(|| GitxError::Usage("No commit to amend".to_string()))?;
+    let message = if !args.messages.is_empty() {
+        Some(args.messages.join("\n\n"))
+    } else if args.no_edit {
+        None
+    } else {
+        let combined_diff = repo.get_amend_diff()?;
+        diff_display::display_diffstat(&repo.diff_stats(&combined_diff)?);
+        let generated = generate_message(repo, &combined_diff)?;
+        if dry_run {
+            dry_run::print_header();
+            dry_run::print_operation(&format!(
+                "amend HEAD with staged changes and message:\n\n{}\n",
+                generated.as_deref().unwrap_or(current.trim_end())
+            ));
+            return Ok(());
+        }
+        Some(review_message(
+            Some(generated.as_deref().unwrap_or(current.trim_end())),
+            None,
+        )?)
+    };
+    let message = match message {
+        Some(message) => Some(link_issue(repo, &message, args, dry_run).await?),
+        None if args.issue.is_some() => Some(link_issue(repo, &current, args, dry_run).await?),
+        None => None,
+    };
+    if let Some(message) = message.as_deref().filter(|_| !args.no_verify) {
+        lint_commit_message(repo, message)?;
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "amend HEAD with staged changes and message:\n\n{}\n",
+            message.as_deref().unwrap_or(current.trim_end())
+        ));
+        return Ok(());
+    }
+
+    let id = repo.commit_amend(message.as_deref(), true)?;
+    println!(
+        "{} Amended {} {}",
+        style("✓").green().bold(),
+        style(&id[..7]).yellow(),
+        message
+            .as_deref()
+            .unwrap_or(&current)
+            .lines()
+            .next()
+            .unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Check `message` against the `[commitlint]` rules when they are configured: errors refuse
+/// the commit, or are only printed with `on-commit = "warn"`
+fn lint_commit_message(repo: &GitRepo, message: &str) -> Result<(), GitxError> {
+    let config = repo.xgit_config()?;
+    if config.commit_lint_mode() == CommitLintMode::Off {
+        return Ok(());
+    }
+    let problems = lint_message(message, config.commit_lint());
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    if config.commit_lint_mode() == CommitLintMode::Error && has_errors(&problems) {
+        return Err(GitxError::Usage(format!(
+            "Commit message rejected:\n{}  Fix the message, or commit with --no-verify to skip the check",
+            describe_problems(&problems)
+        )));
+    }
+    eprint!(
+        "{} Commit message problems:\n{}",
+        style("⚠").yellow().bold(),
+        describe_problems(&problems)
+    );
+    Ok(())
+}
+
+/// Let the user edit the summary line of the `proposed` message, or type one starting from
+/// `template` (`commit.template`) when there is none. `--yes` accepts a proposal as it is.
+fn review_message(proposed: Option<&str>, template: Option<&str>) -> Result<String, GitxError> {
+    if let Some(message) = proposed.filter(|_| confirm::assume_yes()) {
+        eprintln!(
+            "{} Using the suggested message {}",
+            style("✓").green().bold(),
+            style("(--yes)").dim()
         );
-        fs::write(temp_file, &template_content)?;
+        return Ok(message.to_string());
+    }
+    if !console::user_attended() {
+        return Err(GitxError::Usage(
+            "No terminal to review the commit message; pass -m, or --yes to accept the suggestion"
+                .to_string(),
+        ));
+    }
 
-        // Use git commit with template
-        let mut cmd = Command::new("git");
-        cmd.arg("commit");
-        cmd.arg("-t");
-        cmd.arg(temp_file);
-        cmd.arg("--allow-empty-message");
+    let (summary, body) = match proposed.or(template) {
+        Some(message) => message.split_once('\n').unwrap_or((message, "")),
+        None => ("", ""),
+    };
+    let body = body.trim();
+    if !body.is_empty() {
+        println!("{}", style(body).dim());
+    }
+    let summary = Text::new("Commit message:")
+        .with_initial_value(summary)
+        .with_help_message("enter to commit, esc to cancel")
+        .prompt()?;
+    if summary.trim().is_empty() {
+        return Err(GitxError::Usage(
+            "Aborting commit due to empty commit message".to_string(),
+        ));
+    }
+    Ok(if body.is_empty() {
+        summary.trim().to_string()
+    } else {
+        format!("{}\n\n{body}", summary.trim())
+    })
+}
 
-        let status = cmd.status()?;
+/// One of the AI's suggestions, or a way to get a message other than picking one
+enum MessageChoice {
+    Suggested(String),
+    Regenerate,
+    Write,
+}
 
-        // Clean up
-        let _ = fs::remove_file(temp_file);
+impl std::fmt::Display for MessageChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageChoice::Suggested(message) => match message.split_once('\n') {
+                Some((summary, _)) => write!(f, "{summary} (+ body)"),
+                None => write!(f, "{message}"),
+            },
+            MessageChoice::Regenerate => write!(f, "↻ Regenerate suggestions"),
+            MessageChoice::Write => write!(f, "✎ Write the message myself"),
+        }
+    }
+}
+
+/// Let the user pick one of several `suggestions`, ask the AI for new ones or write the
+/// message themselves. With one suggestion or none, `--yes` or no terminal, this is
+/// [`review_message`].
+fn choose_message(
+    repo: &GitRepo,
+    diff_text: &str,
+    mut suggestions: Vec<String>,
+    template: Option<&str>,
+) -> Result<String, GitxError> {
+    loop {
+        if suggestions.len() < 2 || confirm::assume_yes() || !console::user_attended() {
+            return review_message(suggestions.first().map(String::as_str), template);
+        }
+
+        let count = suggestions.len();
+        let mut choices: Vec<MessageChoice> = suggestions
+            .into_iter()
+            .map(MessageChoice::Suggested)
+            .collect();
+        choices.extend([MessageChoice::Regenerate, MessageChoice::Write]);
+        match Select::new("Commit message:", choices)
+            .with_help_message("enter to commit, esc to cancel")
+            .prompt()?
+        {
+            MessageChoice::Suggested(message) => return Ok(message),
+            MessageChoice::Regenerate => {
+                suggestions = suggest_messages(repo, diff_text, count, true)?;
+            }
+            MessageChoice::Write => return review_message(None, template),
+        }
+    }
+}
+
+/// Ask the AI for a message describing `staged_diff`, or `None` when it gives none or the
+/// AI provider is turned off
+pub fn generate_message(
+    repo: &GitRepo,
+    staged_diff: &git2::Diff<'_>,
+) -> Result<Option<String>, GitxError> {
+    let diff_text = ai_diff_text(repo, staged_diff)?;
+    Ok(suggest_messages(repo, &diff_text, 1, false)?
+        .into_iter()
+        .next())
+}
+
+/// Up to `count` AI messages for `diff_text`. The ones last suggested for the same diff are
+/// reused unless `regenerate` asks for new ones.
+fn suggest_messages(
+    repo: &GitRepo,
+    diff_text: &str,
+    count: usize,
+    regenerate: bool,
+) -> Result<Vec<String>, GitxError> {
+    if repo.xgit_config()?.ai_provider() == AiProvider::Disabled {
+        return Ok(Vec::new());
+    }
 
-        if !status.success() {
-            std::process::exit(status.code().unwrap_or(1));
+    let cache = ai::CommitMessageCache::for_repo(repo);
+    if let Some(cached) = (!regenerate)
+        .then(|| cache.get(diff_text))
+        .flatten()
+        .filter(|cached| cached.len() >= count)
+    {
+        return Ok(cached);
+    }
+
+    // Try to generate commit messages with Claude
+    let messages = ai::generate_commit_messages(diff_text, count)?;
+    if !messages.is_empty() {
+        // The cache only saves a model call; failing to write it shouldn't stop the commit
+        let _ = cache.store(diff_text, &messages);
+    }
+    Ok(messages)
+}
+
+/// `message` with a `Closes #N` (or, with `--refs`, `Refs #N`) line for the issue `--issue`
+/// names or the user picks from their assigned issues; `message` as it is without `--issue`.
+/// A dry run links a picked issue as `#?` instead of asking.
+async fn link_issue(
+    repo: &GitRepo,
+    message: &str,
+    args: &NativeCommitArgs,
+    dry_run: bool,
+) -> Result<String, GitxError> {
+    let keyword = if args.refs {
+        IssueKeyword::Refs
+    } else {
+        IssueKeyword::Closes
+    };
+    let number = match args.issue {
+        None => return Ok(message.to_string()),
+        Some(IssueArg::Number(number)) => number,
+        Some(IssueArg::Pick) if dry_run => {
+            return Ok(format!("{}\n\n{keyword} #?", message.trim_end()));
+        }
+        Some(IssueArg::Pick) => {
+            let Some((number, keyword)) = pick_issue(repo, args.refs).await? else {
+                return Ok(message.to_string());
+            };
+            return Ok(append_issue_link(message, IssueLink { keyword, number }));
         }
+    };
+    Ok(append_issue_link(message, IssueLink { keyword, number }))
+}
+
+/// Let the user pick one of their assigned issues and, unless `refs` settles it, whether the
+/// commit closes it. `None` when they have no open issues.
+async fn pick_issue(repo: &GitRepo, refs: bool) -> Result<Option<(u64, IssueKeyword)>, GitxError> {
+    if !console::user_attended() {
+        return Err(GitxError::Usage(
+            "No terminal to pick an issue; pass --issue=<number>".to_string(),
+        ));
+    }
+    let issues = assigned_issues(repo).await?;
+    if issues.is_empty() {
+        eprintln!(
+            "{} No open issues are assigned to you; committing without one",
+            style("⚠").yellow().bold()
+        );
+        return Ok(None);
+    }
+
+    let choices: Vec<IssueChoice> = issues.into_iter().map(IssueChoice).collect();
+    let issue = match Select::new("Link an issue:", choices)
+        .with_page_size(15)
+        .with_help_message("enter to link, esc to commit without one")
+        .prompt()
+    {
+        Ok(IssueChoice(issue)) => issue,
+        Err(InquireError::OperationCanceled) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let keyword = if refs {
+        IssueKeyword::Refs
+    } else if confirm::assume_yes() {
+        IssueKeyword::Closes
     } else {
-        // Fallback to normal git commit
-        let status = Command::new("git").arg("commit").status()?;
+        Select::new(
+            &format!("Link #{} with:", issue.number),
+            vec![IssueKeyword::Closes, IssueKeyword::Refs],
+        )
+        .with_help_message("Closes closes the issue when the commit lands on the default branch")
+        .prompt()?
+    };
+    Ok(Some((issue.number, keyword)))
+}
+
+/// The open issues assigned to the signed-in user in the repository's GitHub remote, reused
+/// from `.git/xgit/issue-cache.json` for a few minutes
+async fn assigned_issues(repo: &GitRepo) -> Result<Vec<Issue>, GitxError> {
+    let remote = repo.github_remote()?;
+    let slug = remote.repo.slug();
+    let cache = IssueCache::new(repo.git_dir().join("xgit").join("issue-cache.json"));
+    if let Some(issues) = cache.get(&slug, DEFAULT_ISSUE_CACHE_TTL_SECS) {
+        return Ok(issues);
+    }
+
+    let client = GitHubClient::authenticated(
+        &remote.repo.host,
+        remote.repo.owner.clone(),
+        remote.repo.repo.clone(),
+    )?;
+    let issues = client.list_assigned_issues().await?;
+    // The cache only saves a request; failing to write it shouldn't stop the commit
+    let _ = cache.put(&slug, &issues);
+    Ok(issues)
+}
 
-        if !status.success() {
-            std::process::exit(status.code().unwrap_or(1));
+/// An issue in the `--issue` picker
+struct IssueChoice(Issue);
+
+impl fmt::Display for IssueChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let issue = &self.0;
+        write!(f, "#{} {}", issue.number, issue.title)?;
+        if !issue.labels.is_empty() {
+            write!(f, " [{}]", issue.labels.join(", "))?;
         }
+        Ok(())
     }
+}
 
-    Ok(())
+/// The diff text sent to the AI, capped so huge changes aren't sent whole
+fn ai_diff_text(repo: &GitRepo, diff: &git2::Diff<'_>) -> Result<String, GitxError> {
+    Ok(repo.diff_to_string_with(
+        diff,
+        &DiffTextOptions {
+            max_bytes: Some(AI_DIFF_MAX_BYTES),
+            max_file_bytes: Some(AI_DIFF_MAX_FILE_BYTES),
+            word_diff: true,
+            ..DiffTextOptions::default()
+        },
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{suggest_messages, IssueArg, MessageChoice, NativeCommitArgs};
+    use crate::ai::CommitMessageCache;
+    use crate::test_utils::create_test_repo;
+
+    fn parse(args: &[&str]) -> Option<NativeCommitArgs> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        NativeCommitArgs::parse(&args)
+    }
+
+    #[test]
+    fn parse_collects_messages_and_supported_flags() {
+        assert_eq!(parse(&[]), Some(NativeCommitArgs::default()));
+        assert_eq!(
+            parse(&[
+                "-m",
+                "Subject",
+                "--message=Body",
+                "-n",
+                "-s",
+                "--allow-empty"
+            ]),
+            Some(NativeCommitArgs {
+                messages: vec!["Subject".to_string(), "Body".to_string()],
+                no_verify: true,
+                allow_empty: true,
+                signoff: true,
+                ..NativeCommitArgs::default()
+            })
+        );
+        assert_eq!(
+            parse(&["--amend", "--no-edit"]),
+            Some(NativeCommitArgs {
+                amend: true,
+                no_edit: true,
+                ..NativeCommitArgs::default()
+            })
+        );
+        assert_eq!(
+            parse(&["-m", "Subject", "--issue=#12", "--refs"]),
+            Some(NativeCommitArgs {
+                messages: vec!["Subject".to_string()],
+                issue: Some(IssueArg::Number(12)),
+                refs: true,
+                ..NativeCommitArgs::default()
+            })
+        );
+        assert_eq!(
+            parse(&["--issue"]).and_then(|args| args.issue),
+            Some(IssueArg::Pick)
+        );
+    }
+
+    #[test]
+    fn parse_rejects_flags_left_to_git() {
+        assert_eq!(parse(&["--no-edit"]), None);
+        assert_eq!(parse(&["--amend", "--signoff"]), None);
+        assert_eq!(parse(&["--reset-author"]), None);
+        assert_eq!(parse(&["-am", "Subject"]), None);
+        assert_eq!(parse(&["-m"]), None);
+        // git reads `-m=Subject` as the message "=Subject"
+        assert_eq!(parse(&["-m=Subject"]), None);
+        assert_eq!(parse(&["--issue=twelve"]), None);
+        assert_eq!(parse(&["--refs"]), None);
+    }
+
+    #[test]
+    fn cached_suggestions_are_reused_for_the_same_diff() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let cached = vec!["feat: Add a".to_string(), "chore: Touch a".to_string()];
+        CommitMessageCache::for_repo(&repo).store("+a", &cached)?;
+
+        assert_eq!(suggest_messages(&repo, "+a", 2, false)?, cached);
+        assert_eq!(
+            MessageChoice::Suggested("feat: Add a\n\nWhy".to_string()).to_string(),
+            "feat: Add a (+ body)"
+        );
+        Ok(())
+    }
 }
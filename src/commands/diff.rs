@@ -1,5 +1,7 @@
+use crate::error::GitxError;
 use crate::git::GitRepo;
 use crate::github::pr_service::GitHubPrService;
+use crate::tui::{confirm::confirm_or_abort, dry_run};
 use anyhow::{Context, Error};
 use console::style;
 use inquire::MultiSelect;
@@ -28,19 +30,31 @@ struct SyncRow {
     url: String,
 }
 
-pub async fn handle_diff(repair: &Option<Vec<String>>) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn handle_diff(repair: &Option<Vec<String>>, dry_run: bool) -> Result<(), GitxError> {
     let repo = GitRepo::open(".")?;
     ensure_clean_worktree(&repo)?;
 
-    let remote = detect_github_remote(&repo)?;
-    let (owner, repo_name) = parse_github_url(&remote.url)?;
-
-    let github = GitHubPrService::new(repo.path(), owner, repo_name)?;
+    let remote = repo.forge_remote()?;
+    let github = GitHubPrService::new(repo.path(), &remote)?;
     github.ensure_ready()?;
 
     let trunk_base = github.resolve_trunk_base_branch(&repo).await?;
     let trunk_range = resolve_trunk_range_ref(&repo, &remote.name, &trunk_base)?;
 
+    if dry_run {
+        dry_run::print_header();
+        if let Some(repair_args) = repair {
+            dry_run::print_operation(&format!(
+                "rewrite stack from commit {} to attach {TRAILER_KEY}: #{}",
+                repair_args.get(1).map(String::as_str).unwrap_or_default(),
+                repair_args.first().map(String::as_str).unwrap_or_default()
+            ));
+        }
+        let stack = collect_stack(&repo, &trunk_range)?;
+        print_sync_plan(&remote.name, &trunk_base, &stack);
+        return Ok(());
+    }
+
     if let Some(repair_args) = repair {
         run_repair(&repo, &trunk_range, repair_args)?;
         let repaired_stack = collect_stack(&repo, &trunk_range)?;
@@ -108,6 +122,14 @@ async fn sync_stack(
             return Ok(());
         }
 
+        confirm_or_abort(
+            &format!(
+                "Force-push {} PR head branches to {remote_name}?",
+                selected_prs.len()
+            ),
+            "Sync aborted",
+        )?;
+
         let rows =
             sync_existing_prs(repo, github, remote_name, trunk_base, &stack, &selected_prs).await?;
         print_summary(&rows);
@@ -144,6 +166,13 @@ fn run_repair(repo: &GitRepo, trunk_range: &str, repair_args: &[String]) -> Resu
     };
 
     let suffix = &stack[target_idx..];
+    confirm_or_abort(
+        &format!(
+            "Rewrite {} commits to attach PR #{pr_number}?",
+            suffix.len()
+        ),
+        "Repair aborted",
+    )?;
     replay_suffix_with_optional_trailer(repo, &base_ref, suffix, Some((&suffix[0].sha, pr_number)))
         .context("Failed to apply repair rewrite")?;
 
@@ -197,7 +226,8 @@ Run `git rebase -i {trunk_base}` and reword commits, then rerun xg diff."
         let suffix = timestamp_suffix(idx as u64);
         let temp_branch = format!("xgit/new-{}-{suffix}", short_sha(&commit.sha));
 
-        repo.force_push_commit_to_branch(remote_name, &commit.sha, &temp_branch)
+        repo.force_push_commit_to_branch_async(remote_name, &commit.sha, &temp_branch)
+            .await
             .context("Failed to push temporary PR head branch")?;
 
         let body = format!("Synced by xg diff from commit {}", commit.sha);
@@ -222,6 +252,18 @@ Run `git rebase -i {trunk_base}` and reword commits, then rerun xg diff."
         github
             .attach_commit(created.pr_number, &commit.sha)
             .context("Failed to persist commit to PR mapping")?;
+        let diffstat = repo
+            .get_commit_diff(&commit.sha)
+            .and_then(|diff| repo.diff_stats(&diff))
+            .map(|stats| format!(" ({})", stats.summary()))
+            .unwrap_or_default();
+        println!(
+            "{} Created PR #{} for {}{}",
+            style("✓").green().bold(),
+            style(created.pr_number).cyan(),
+            style(short_sha(&commit.sha)).cyan(),
+            style(diffstat).dim()
+        );
         assigned.push((commit.sha.clone(), created.pr_number));
         base_branch = created.head_ref;
     }
@@ -270,7 +312,8 @@ Repair this commit with `xg diff --repair <pr_number> <commit_sha>`.",
             ));
         }
 
-        repo.force_push_commit_to_branch(remote_name, &commit.sha, &pr.head_ref)
+        repo.force_push_commit_to_branch_async(remote_name, &commit.sha, &pr.head_ref)
+            .await
             .with_context(|| {
                 format!(
                     "Failed to force-push commit '{}' to PR head branch '{}'",
@@ -348,57 +391,6 @@ fn prompt_pr_selection(stack: &[StackCommit]) -> Result<HashSet<u64>, Error> {
     Ok(result)
 }
 
-#[derive(Debug, Clone)]
-struct GitHubRemote {
-    name: String,
-    url: String,
-}
-
-fn detect_github_remote(repo: &GitRepo) -> Result<GitHubRemote, Error> {
-    for preferred in ["origin", "upstream"] {
-        if let Ok(url) = repo.get_remote_url(preferred) {
-            if url.contains("github.com") {
-                return Ok(GitHubRemote {
-                    name: preferred.to_string(),
-                    url,
-                });
-            }
-        }
-    }
-
-    let remotes = repo.get_remotes()?;
-    for remote in remotes {
-        if remote.url.contains("github.com") {
-            return Ok(GitHubRemote {
-                name: remote.name,
-                url: remote.url,
-            });
-        }
-    }
-
-    Err(anyhow::anyhow!("No GitHub remote found"))
-}
-
-fn parse_github_url(url: &str) -> Result<(String, String), Error> {
-    if let Some(ssh_part) = url.strip_prefix("git@github.com:") {
-        let repo_part = ssh_part.strip_suffix(".git").unwrap_or(ssh_part);
-        let parts: Vec<&str> = repo_part.split('/').collect();
-        if parts.len() == 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
-        }
-    }
-
-    if let Some(https_part) = url.strip_prefix("https://github.com/") {
-        let repo_part = https_part.strip_suffix(".git").unwrap_or(https_part);
-        let parts: Vec<&str> = repo_part.split('/').collect();
-        if parts.len() == 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
-        }
-    }
-
-    Err(anyhow::anyhow!("Invalid GitHub URL format: {url}"))
-}
-
 fn resolve_trunk_range_ref(
     repo: &GitRepo,
     remote_name: &str,
@@ -611,9 +603,11 @@ fn amend_head_message(repo_path: &std::path::Path, message: &str) -> Result<(),
 
 fn ensure_clean_worktree(repo: &GitRepo) -> Result<(), Error> {
     if !repo.is_working_tree_clean()? {
-        return Err(anyhow::anyhow!(
+        return Err(GitxError::DirtyWorktree(
             "Working tree is not clean. Commit or stash your changes before running xg diff."
-        ));
+                .to_string(),
+        )
+        .into());
     }
     Ok(())
 }
@@ -657,6 +651,39 @@ fn git_output(repo_path: &std::path::Path, args: &[&str]) -> Result<String, Erro
     String::from_utf8(output.stdout).context("Invalid UTF-8 git output")
 }
 
+fn print_sync_plan(remote_name: &str, trunk_base: &str, stack: &[StackCommit]) {
+    if stack.is_empty() {
+        dry_run::print_operation(&format!(
+            "nothing to sync: no commits ahead of {trunk_base}"
+        ));
+        return;
+    }
+
+    let mut missing = 0;
+    for commit in stack {
+        match commit.pr_number {
+            Some(pr_number) => dry_run::print_operation(&format!(
+                "force-push {} to the head branch of PR #{pr_number} on {remote_name}",
+                short_sha(&commit.sha)
+            )),
+            None => {
+                missing += 1;
+                dry_run::print_operation(&format!(
+                    "push {} to a new branch on {remote_name} and open PR \"{}\"",
+                    short_sha(&commit.sha),
+                    commit.subject
+                ));
+            }
+        }
+    }
+
+    if missing > 0 {
+        dry_run::print_operation(&format!(
+            "rewrite {missing} commits to add {TRAILER_KEY} trailers"
+        ));
+    }
+}
+
 fn print_summary(rows: &[SyncRow]) {
     println!(
         "{} Synced {} stacked PRs",
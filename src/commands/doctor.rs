@@ -0,0 +1,383 @@
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use crate::github::{auth, client};
+use crate::tui::emoji::icon;
+use console::style;
+use std::process::Command;
+
+/// Oldest git release whose CLI behaviour xg relies on (`git switch`, `--force-with-lease`)
+const MIN_GIT_VERSION: (u32, u32) = (2, 23);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+struct CheckResult {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Check the local environment for everything xg depends on and explain how to fix problems
+pub async fn handle_doctor() -> Result<(), GitxError> {
+    let repo = GitRepo::open(".").ok();
+
+    let results = vec![
+        check_git_binary(),
+        check_libgit2(),
+        check_user_identity(repo.as_ref()),
+        check_claude_cli(),
+        check_github_auth(repo.as_ref()).await,
+        check_hooks(repo.as_ref()),
+    ];
+
+    println!("{} xg doctor", style(icon("🩺")).cyan().bold());
+    println!();
+    for result in &results {
+        display_result(result);
+    }
+
+    let failures = results
+        .iter()
+        .filter(|result| result.status == CheckStatus::Fail)
+        .count();
+    let warnings = results
+        .iter()
+        .filter(|result| result.status == CheckStatus::Warn)
+        .count();
+
+    println!();
+    if failures > 0 {
+        return Err(GitxError::Other(anyhow::anyhow!(
+            "{failures} check(s) failed, {warnings} warning(s)"
+        )));
+    }
+    println!(
+        "{} All checks passed{}",
+        style(icon("✨")).green().bold(),
+        if warnings > 0 {
+            format!(" ({warnings} warning(s))")
+        } else {
+            String::new()
+        }
+    );
+    Ok(())
+}
+
+fn display_result(result: &CheckResult) {
+    let marker = match result.status {
+        CheckStatus::Pass => style("✓").green().bold(),
+        CheckStatus::Warn => style("⚠").yellow().bold(),
+        CheckStatus::Fail => style("✗").red().bold(),
+    };
+    println!(
+        "{} {:<16} {}",
+        marker,
+        style(result.name).bold(),
+        style(&result.detail).dim()
+    );
+    if let Some(fix) = &result.fix {
+        println!("  {} {}", style("→").cyan(), fix);
+    }
+}
+
+/// Run a command and return its trimmed stdout if it exited successfully
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn check_git_binary() -> CheckResult {
+    const NAME: &str = "git binary";
+    let Some(version_output) = command_output("git", &["--version"]) else {
+        return CheckResult::fail(
+            NAME,
+            "git was not found on PATH",
+            "Install git from https://git-scm.com/downloads",
+        );
+    };
+
+    match parse_git_version(&version_output) {
+        Some(version) if version >= MIN_GIT_VERSION => CheckResult::pass(NAME, version_output),
+        Some(_) => CheckResult::warn(
+            NAME,
+            version_output,
+            format!(
+                "Upgrade git to {}.{} or newer",
+                MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+            ),
+        ),
+        None => CheckResult::warn(
+            NAME,
+            format!("Unrecognized version output: {version_output}"),
+            "Make sure `git` on PATH is the official git CLI",
+        ),
+    }
+}
+
+/// Extract `(major, minor)` from `git --version` output such as `git version 2.39.3 (Apple Git-146)`
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.strip_prefix("git version ")?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn check_libgit2() -> CheckResult {
+    let version = git2::Version::get();
+    libgit2_result(
+        version.libgit2_version(),
+        version.https(),
+        version.ssh(),
+        version.threads(),
+    )
+}
+
+/// Fetch, push and tag push go through libgit2's own transports, so a build without https or
+/// ssh cannot reach remotes using those URLs
+fn libgit2_result(
+    (major, minor, patch): (u32, u32, u32),
+    https: bool,
+    ssh: bool,
+    threads: bool,
+) -> CheckResult {
+    const NAME: &str = "libgit2";
+    let feature = |enabled: bool, name: &str| {
+        if enabled {
+            format!("+{name}")
+        } else {
+            format!("-{name}")
+        }
+    };
+    let detail = format!(
+        "{major}.{minor}.{patch} ({} {} {})",
+        feature(https, "https"),
+        feature(ssh, "ssh"),
+        feature(threads, "threads")
+    );
+
+    let missing: Vec<&str> = [("https", https), ("ssh", ssh)]
+        .into_iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|(name, _)| name)
+        .collect();
+    if missing.is_empty() {
+        CheckResult::pass(NAME, format!("{detail}; fetch and push use libgit2"))
+    } else {
+        CheckResult::fail(
+            NAME,
+            format!(
+                "{detail}; fetch and push cannot reach {} remotes",
+                missing.join(" or ")
+            ),
+            "Reinstall xg from a build with git2's `https` and `ssh` features enabled",
+        )
+    }
+}
+
+fn check_user_identity(repo: Option<&GitRepo>) -> CheckResult {
+    const NAME: &str = "git identity";
+    let read = |key: &str| match repo {
+        Some(repo) => repo.get_config_string(key).ok().flatten(),
+        None => command_output("git", &["config", "--global", key]),
+    };
+
+    match (read("user.name"), read("user.email")) {
+        (Some(name), Some(email)) => CheckResult::pass(NAME, format!("{name} <{email}>")),
+        (name, email) => {
+            let missing: Vec<&str> = [("user.name", name), ("user.email", email)]
+                .into_iter()
+                .filter(|(_, value)| value.is_none())
+                .map(|(key, _)| key)
+                .collect();
+            CheckResult::fail(
+                NAME,
+                format!("{} not set", missing.join(" and ")),
+                "Run `git config --global user.name \"Your Name\"` and `git config --global user.email you@example.com`",
+            )
+        }
+    }
+}
+
+fn check_claude_cli() -> CheckResult {
+    const NAME: &str = "claude CLI";
+    match command_output("claude", &["--version"]) {
+        Some(version) => CheckResult::pass(NAME, version),
+        None => CheckResult::warn(
+            NAME,
+            "not found; AI commit messages are unavailable",
+            "Install Claude Code: https://docs.anthropic.com/en/docs/claude-code",
+        ),
+    }
+}
+
+async fn check_github_auth(repo: Option<&GitRepo>) -> CheckResult {
+    const NAME: &str = "GitHub auth";
+    let api_backend = std::env::var("XGIT_GITHUB_BACKEND").ok().as_deref() == Some("api");
+    let host = repo
+        .and_then(|repo| repo.github_remote().ok())
+        .map_or_else(|| auth::GITHUB_HOST.to_string(), |remote| remote.repo.host);
+
+    if api_backend {
+        let Some(token) = auth::resolve_token(&host) else {
+            return CheckResult::fail(
+                NAME,
+                format!("XGIT_GITHUB_BACKEND=api but no GitHub token was found for {host}"),
+                "Export a token with `export GITHUB_TOKEN=<token>`, or run `gh auth login`",
+            );
+        };
+        return match client::validate_token(&host, &token).await {
+            Ok(login) => {
+                CheckResult::pass(NAME, format!("API token for {login} from {}", token.source))
+            }
+            Err(err) => CheckResult::fail(
+                NAME,
+                format!("{err:#} (token from {})", token.source),
+                "Create a new token with `repo` scope and export it as GITHUB_TOKEN",
+            ),
+        };
+    }
+
+    match Command::new("gh")
+        .args(["auth", "status", "--hostname", &host])
+        .output()
+    {
+        Ok(output) if output.status.success() => CheckResult::pass(NAME, "gh CLI is authenticated"),
+        Ok(_) => CheckResult::warn(
+            NAME,
+            "gh CLI is not logged in; PR features are unavailable",
+            "Run `gh auth login`",
+        ),
+        Err(_) => CheckResult::warn(
+            NAME,
+            "gh CLI not found; PR features are unavailable",
+            "Install the GitHub CLI from https://cli.github.com",
+        ),
+    }
+}
+
+fn check_hooks(repo: Option<&GitRepo>) -> CheckResult {
+    const NAME: &str = "git hooks";
+    let Some(repo) = repo else {
+        return CheckResult::pass(NAME, "not inside a repository; skipped");
+    };
+
+    let hooks_dir = repo.hooks_dir();
+
+    if !hooks_dir.is_dir() {
+        return CheckResult::warn(
+            NAME,
+            format!("hooks directory {} does not exist", hooks_dir.display()),
+            "Create the directory or unset core.hooksPath",
+        );
+    }
+
+    let mut active: Vec<String> = std::fs::read_dir(&hooks_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.ends_with(".sample"))
+                .collect()
+        })
+        .unwrap_or_default();
+    active.sort();
+
+    if active.is_empty() {
+        CheckResult::pass(NAME, format!("none installed in {}", hooks_dir.display()))
+    } else {
+        CheckResult::pass(
+            NAME,
+            format!("{} in {}", active.join(", "), hooks_dir.display()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_hooks, check_user_identity, libgit2_result, parse_git_version, CheckStatus};
+    use crate::test_utils::create_test_repo;
+
+    #[test]
+    fn parse_git_version_handles_vendor_suffixes() {
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-146)"),
+            Some((2, 39))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.45.2.windows.1"),
+            Some((2, 45))
+        );
+        assert_eq!(parse_git_version("hub version 2.14"), None);
+    }
+
+    #[test]
+    fn libgit2_without_network_transports_fails() {
+        assert_eq!(
+            libgit2_result((1, 9, 1), true, true, true).status,
+            CheckStatus::Pass
+        );
+        let result = libgit2_result((1, 9, 1), false, false, true);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.detail.contains("(-https -ssh +threads)"));
+        assert!(result.detail.contains("https or ssh remotes"));
+    }
+
+    #[test]
+    fn check_user_identity_passes_with_repo_config() {
+        let (_temp_dir, repo) = create_test_repo();
+        let result = check_user_identity(Some(&repo));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.detail.contains("test@example.com"));
+    }
+
+    #[test]
+    fn check_hooks_lists_active_hooks() {
+        let (_temp_dir, repo) = create_test_repo();
+        let hooks_dir = repo.git_dir().join("hooks");
+        std::fs::create_dir_all(&hooks_dir).unwrap();
+        std::fs::write(hooks_dir.join("pre-commit"), "#!/bin/sh\n").unwrap();
+
+        let result = check_hooks(Some(&repo));
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert!(result.detail.starts_with("pre-commit in"));
+    }
+}
@@ -1,31 +1,136 @@
-use console::style;
+use crate::error::GitxError;
+use crate::i18n::t;
+use crate::tui::{confirm::confirm_or_abort, dry_run};
 use std::process::Command;
 
 /// Helper function to passthrough commands to git
-pub fn git_passthrough(
-    subcommand: &str,
-    args: &[String],
-) -> Result<(), Box<dyn std::error::Error>> {
+pub fn git_passthrough(subcommand: &str, args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    if dry_run && !is_read_only(subcommand, args) {
+        dry_run::print_header();
+        dry_run::print_operation(format!("git {subcommand} {}", args.join(" ")).trim_end());
+        return Ok(());
+    }
+
+    if let Some(operation) = destructive_operation(subcommand, args) {
+        confirm_or_abort(
+            &format!("Run `git {subcommand}`? This will {operation}."),
+            t("common.aborted"),
+        )?;
+    }
+
     let mut cmd = Command::new("git");
     cmd.arg(subcommand);
     cmd.args(args);
 
-    match cmd.status() {
-        Ok(status) => {
-            if !status.success() {
-                std::process::exit(status.code().unwrap_or(1));
-            }
+    let status = cmd
+        .status()
+        .map_err(|e| anyhow::anyhow!("Error running git {subcommand}: {e}"))?;
+    if !status.success() {
+        return Err(GitxError::Subprocess {
+            program: format!("git {subcommand}"),
+            code: status.code().unwrap_or(1),
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether a passthrough git command leaves the repository untouched, so it can run under --dry-run
+pub(crate) fn is_read_only(subcommand: &str, args: &[String]) -> bool {
+    let first = args.first().map(String::as_str);
+    match subcommand {
+        "status" | "log" | "diff" | "show" | "blame" | "grep" | "ls-files" | "describe"
+        | "help" | "version" => true,
+        "reflog" => matches!(first, None | Some("show")),
+        "remote" => matches!(first, None | Some("-v" | "--verbose" | "show" | "get-url")),
+        "stash" => matches!(first, Some("list" | "show")),
+        "tag" => args.is_empty() || args.iter().any(|arg| arg == "-l" || arg == "--list"),
+        "config" => args
+            .iter()
+            .any(|arg| matches!(arg.as_str(), "--get" | "--get-all" | "-l" | "--list")),
+        _ => false,
+    }
+}
+
+/// Describe what a passthrough git command would destroy, if it is destructive
+fn destructive_operation(subcommand: &str, args: &[String]) -> Option<&'static str> {
+    let has_arg = |candidates: &[&str]| {
+        args.iter()
+            .any(|arg| candidates.iter().any(|candidate| arg == candidate))
+    };
+
+    match subcommand {
+        "reset" if has_arg(&["--hard"]) => Some("discard all uncommitted changes"),
+        "clean" if !has_arg(&["-n", "--dry-run"]) => Some("permanently delete untracked files"),
+        "push"
+            if has_arg(&["-f", "--force", "--force-with-lease", "--mirror"])
+                || args
+                    .iter()
+                    .any(|arg| arg.starts_with("--force-with-lease=") || arg.starts_with('+')) =>
+        {
+            Some("overwrite history on the remote")
         }
-        Err(e) => {
-            eprintln!(
-                "{} Error running git {}: {}",
-                style("✗").red().bold(),
-                style(subcommand).cyan(),
-                style(e).red()
-            );
-            std::process::exit(1);
+        "push" if has_arg(&["-d", "--delete"]) => Some("delete branches on the remote"),
+        "tag" if has_arg(&["-d", "--delete"]) => Some("delete tags"),
+        "stash" if matches!(args.first().map(String::as_str), Some("drop" | "clear")) => {
+            Some("delete stashed changes")
         }
+        "remote" if matches!(args.first().map(String::as_str), Some("remove" | "rm")) => {
+            Some("remove the remote and its remote-tracking branches")
+        }
+        _ => None,
     }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::{destructive_operation, git_passthrough, is_read_only};
+    use crate::error::GitxError;
+    use crate::tui::confirm;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn destructive_operation_detects_dangerous_commands() {
+        assert!(destructive_operation("reset", &args(&["--hard", "HEAD~1"])).is_some());
+        assert!(destructive_operation("clean", &args(&["-fd"])).is_some());
+        assert!(destructive_operation("push", &args(&["--force", "origin"])).is_some());
+        assert!(destructive_operation("push", &args(&["origin", "+main"])).is_some());
+        assert!(destructive_operation("remote", &args(&["remove", "origin"])).is_some());
+        assert!(destructive_operation("stash", &args(&["clear"])).is_some());
+        assert!(destructive_operation("tag", &args(&["-d", "v1"])).is_some());
+    }
+
+    #[test]
+    fn is_read_only_distinguishes_queries_from_mutations() {
+        assert!(is_read_only("status", &[]));
+        assert!(is_read_only("remote", &args(&["-v"])));
+        assert!(is_read_only("tag", &[]));
+        assert!(is_read_only("config", &args(&["--get", "user.name"])));
+        assert!(!is_read_only("remote", &args(&["add", "origin", "url"])));
+        assert!(!is_read_only("tag", &args(&["v1.0.0"])));
+        assert!(!is_read_only("push", &args(&["origin", "main"])));
+        assert!(!is_read_only("stash", &[]));
+    }
+
+    #[test]
+    fn destructive_operation_ignores_safe_commands() {
+        assert!(destructive_operation("reset", &args(&["--soft", "HEAD~1"])).is_none());
+        assert!(destructive_operation("clean", &args(&["-n"])).is_none());
+        assert!(destructive_operation("push", &args(&["origin", "main"])).is_none());
+        assert!(destructive_operation("remote", &args(&["add", "origin", "url"])).is_none());
+        assert!(destructive_operation("stash", &args(&["push", "-m", "wip"])).is_none());
+        assert!(destructive_operation("status", &[]).is_none());
+    }
+
+    #[test]
+    fn declining_a_destructive_command_aborts_with_exit_code_130() {
+        confirm::answer_in_tests(false);
+        let err =
+            git_passthrough("remote", &args(&["remove", "xg-missing-remote"]), false).unwrap_err();
+        assert!(matches!(err, GitxError::UserAbort(_)), "{err:?}");
+        assert_eq!(err.exit_code(), 130);
+    }
 }
@@ -0,0 +1,88 @@
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use crate::github::types::now_timestamp;
+use crate::history::{HistoryEntry, HistoryLog};
+use crate::tui::emoji::icon;
+use console::style;
+
+/// Show the most recent state-mutating operations recorded for this repository
+pub fn handle_history(limit: usize) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let entries = HistoryLog::for_repo(&repo).entries()?;
+
+    if entries.is_empty() {
+        println!("{} No operations recorded yet", style("ℹ").blue().bold());
+        return Ok(());
+    }
+
+    println!("{} Operation history", style(icon("🕘")).cyan().bold());
+    println!();
+
+    let now = now_timestamp();
+    for entry in entries.iter().rev().take(limit) {
+        display_entry(entry, now);
+    }
+
+    Ok(())
+}
+
+fn display_entry(entry: &HistoryEntry, now: u64) {
+    let status = if entry.success {
+        style("✓").green().bold()
+    } else {
+        style("✗").red().bold()
+    };
+    println!(
+        "{} {} {}",
+        status,
+        style(format!("xg {}", entry.command.join(" ")))
+            .cyan()
+            .bold(),
+        style(format_age(now.saturating_sub(entry.timestamp))).dim()
+    );
+
+    if entry.ref_changes.is_empty() {
+        println!("    {}", style("no refs changed").dim());
+    }
+    for change in &entry.ref_changes {
+        println!(
+            "    {} {} {} {}",
+            style(&change.name).yellow(),
+            format_sha(change.before.as_deref()),
+            style("→").dim(),
+            format_sha(change.after.as_deref())
+        );
+    }
+    println!();
+}
+
+fn format_sha(sha: Option<&str>) -> String {
+    match sha {
+        Some(sha) => sha.chars().take(7).collect(),
+        None => "(none)".to_string(),
+    }
+}
+
+pub(crate) fn format_age(seconds: u64) -> String {
+    let (value, unit) = match seconds {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (seconds / 60, "minute"),
+        3_600..=86_399 => (seconds / 3_600, "hour"),
+        _ => (seconds / 86_400, "day"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    format!("{value} {unit}{plural} ago")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_age;
+
+    #[test]
+    fn format_age_picks_largest_unit() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(60), "1 minute ago");
+        assert_eq!(format_age(7_200), "2 hours ago");
+        assert_eq!(format_age(3 * 86_400), "3 days ago");
+    }
+}
@@ -0,0 +1,199 @@
+use super::commit::generate_message;
+use super::lint_commits::{describe_problems, has_errors};
+use crate::config::XgitConfig;
+use crate::error::GitxError;
+use crate::git::commits::commitlint::lint_message;
+use crate::git::GitRepo;
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use console::style;
+use std::fs;
+use std::path::Path;
+
+/// Written into every hook `xg hooks install-ai` installs, to tell them from other hooks
+const HOOK_MARKER: &str = "Installed by `xg hooks install-ai`";
+/// The hooks `xg hooks install-ai` installs, each running the `xg hooks` subcommand of the
+/// same name
+const AI_HOOKS: &[&str] = &["prepare-commit-msg", "commit-msg"];
+
+/// Install `prepare-commit-msg` and `commit-msg` hooks that run xg, so plain `git commit`
+/// gets an AI-suggested message and the Conventional Commits check.
+///
+/// Hooks that xg did not write are left alone unless `force` is set.
+pub fn handle_hooks_install_ai(force: bool, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let hooks_dir = repo.hooks_dir();
+    let exe = std::env::current_exe()?;
+
+    if !force {
+        let foreign: Vec<&str> = AI_HOOKS
+            .iter()
+            .copied()
+            .filter(|hook| {
+                fs::read_to_string(hooks_dir.join(hook))
+                    .map_or(false, |script| !script.contains(HOOK_MARKER))
+            })
+            .collect();
+        if !foreign.is_empty() {
+            return Err(GitxError::Usage(format!(
+                "{} already installed in {}; rerun with --force to replace",
+                foreign.join(", "),
+                hooks_dir.display()
+            )));
+        }
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        for hook in AI_HOOKS {
+            dry_run::print_operation(&format!("write {}", hooks_dir.join(hook).display()));
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(&hooks_dir)?;
+    for hook in AI_HOOKS {
+        let path = hooks_dir.join(hook);
+        fs::write(&path, hook_script(&exe, hook))?;
+        make_executable(&path)?;
+        println!(
+            "{} Installed {}",
+            style("✓").green().bold(),
+            style(path.display()).cyan()
+        );
+    }
+    println!(
+        "  {}",
+        style("`git commit` now suggests a message and checks it against Conventional Commits")
+            .dim()
+    );
+    Ok(())
+}
+
+/// `prepare-commit-msg` hook: pre-fill the message of a plain `git commit` with an AI
+/// suggestion.
+///
+/// Commits whose message comes from somewhere else (`-m`, a template, a merge, ...) are left
+/// alone, and failing to generate a message never stops the commit.
+pub fn handle_prepare_commit_msg(file: &Path, source: Option<&str>) -> Result<(), GitxError> {
+    if source.is_some() {
+        return Ok(());
+    }
+    let repo = GitRepo::open(".")?;
+    if !repo.has_staged_changes()? {
+        return Ok(());
+    }
+
+    eprintln!(
+        "{} Generating commit message",
+        style(icon("🤖")).cyan().bold()
+    );
+    let message = match generate_message(&repo, &repo.get_staged_diff()?) {
+        Ok(Some(message)) => message,
+        Ok(None) => return Ok(()),
+        Err(err) => {
+            eprintln!(
+                "{} Could not generate a commit message: {err}",
+                style("⚠").yellow().bold()
+            );
+            return Ok(());
+        }
+    };
+
+    let existing = fs::read_to_string(file)?;
+    fs::write(file, format!("{message}\n{existing}"))?;
+    Ok(())
+}
+
+/// `commit-msg` hook: reject messages that are not Conventional Commits or break the other
+/// `[commitlint]` rules, printing warnings without rejecting.
+///
+/// Merge, revert, `fixup!` and `squash!` messages written by git are accepted as they are,
+/// and git aborts empty commits itself.
+pub fn handle_commit_msg(file: &Path) -> Result<(), GitxError> {
+    let message = strip_comments(&fs::read_to_string(file)?);
+    let repo = GitRepo::open(".").ok();
+    let config = XgitConfig::load(repo.as_ref())?;
+    let problems = lint_message(&message, config.commit_lint());
+
+    if has_errors(&problems) {
+        return Err(GitxError::Usage(format!(
+            "Commit message rejected:\n{}  e.g. `feat(cli): Add hooks command`; commit with --no-verify to skip",
+            describe_problems(&problems)
+        )));
+    }
+    if !problems.is_empty() {
+        eprint!(
+            "{} Commit message problems:\n{}",
+            style("⚠").yellow().bold(),
+            describe_problems(&problems)
+        );
+    }
+    Ok(())
+}
+
+fn hook_script(exe: &Path, hook: &str) -> String {
+    format!(
+        "#!/bin/sh\n# {HOOK_MARKER}\nexec \"{}\" hooks {hook} \"$@\"\n",
+        exe.display()
+    )
+}
+
+/// The message git will record: lines before the `git commit -v` scissors line, without
+/// `#` comments
+fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .take_while(|line| !line.starts_with("# ------------------------ >8"))
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), GitxError> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), GitxError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handle_commit_msg, hook_script, strip_comments, HOOK_MARKER};
+    use std::path::Path;
+
+    #[test]
+    fn hook_script_runs_xg_with_hook_arguments() {
+        let script = hook_script(Path::new("/usr/local/bin/xg"), "commit-msg");
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(HOOK_MARKER));
+        assert!(script.ends_with("exec \"/usr/local/bin/xg\" hooks commit-msg \"$@\"\n"));
+    }
+
+    #[test]
+    fn strip_comments_drops_comments_and_verbose_diff() {
+        let message = "feat: Add hooks\n\nBody\n# Please enter the commit message\n# ------------------------ >8 ------------------------\ndiff --git a/x b/x\n";
+        assert_eq!(strip_comments(message), "feat: Add hooks\n\nBody");
+    }
+
+    #[test]
+    fn handle_commit_msg_checks_conventional_format() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("COMMIT_EDITMSG");
+        let check = |message: &str| {
+            std::fs::write(&file, message).unwrap();
+            handle_commit_msg(&file)
+        };
+
+        assert!(check("feat(cli): Add hooks\n# comment\n").is_ok());
+        assert!(check("Merge branch 'main'\n").is_ok());
+        assert!(check("# only comments\n").is_ok());
+        let err = check("Add hooks\n").unwrap_err().to_string();
+        assert!(err.contains("does not match"), "{err}");
+    }
+}
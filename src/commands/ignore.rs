@@ -0,0 +1,281 @@
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use crate::tui::emoji::icon;
+use crate::tui::{confirm, dry_run};
+use console::style;
+use std::fs;
+
+/// Bundled templates as `(name, aliases, contents)`
+const TEMPLATES: &[(&str, &[&str], &str)] = &[
+    (
+        "go",
+        &["golang"],
+        include_str!("ignore_templates/go.gitignore"),
+    ),
+    (
+        "java",
+        &["gradle", "maven"],
+        include_str!("ignore_templates/java.gitignore"),
+    ),
+    (
+        "jetbrains",
+        &["idea", "intellij"],
+        include_str!("ignore_templates/jetbrains.gitignore"),
+    ),
+    (
+        "linux",
+        &[],
+        include_str!("ignore_templates/linux.gitignore"),
+    ),
+    (
+        "macos",
+        &["osx", "mac"],
+        include_str!("ignore_templates/macos.gitignore"),
+    ),
+    (
+        "node",
+        &["nodejs", "javascript", "typescript", "js", "ts"],
+        include_str!("ignore_templates/node.gitignore"),
+    ),
+    (
+        "python",
+        &["py"],
+        include_str!("ignore_templates/python.gitignore"),
+    ),
+    (
+        "rust",
+        &["cargo"],
+        include_str!("ignore_templates/rust.gitignore"),
+    ),
+    (
+        "vscode",
+        &["code"],
+        include_str!("ignore_templates/vscode.gitignore"),
+    ),
+    (
+        "windows",
+        &["win"],
+        include_str!("ignore_templates/windows.gitignore"),
+    ),
+];
+
+/// List the bundled .gitignore templates
+pub fn handle_ignore_list() -> Result<(), GitxError> {
+    for (name, aliases, _) in TEMPLATES {
+        if aliases.is_empty() {
+            println!("{name}");
+        } else {
+            println!(
+                "{name} {}",
+                style(format!("({})", aliases.join(", "))).dim()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Add the patterns of the named templates to `.gitignore`, skipping ones it already has, then
+/// offer to untrack files the new rules ignore. Without a terminal (and no `--yes`) the files
+/// are only listed.
+pub fn handle_ignore_init(names: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let templates = names
+        .iter()
+        .map(|name| find_template(name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let repo = GitRepo::open(".")?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| GitxError::Usage("`xg ignore` needs a working tree".to_string()))?;
+    let path = workdir.join(".gitignore");
+    let existing = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    let (merged, added) = merge_templates(&existing, &templates);
+    if added == 0 {
+        println!(
+            "{} .gitignore already has every pattern from {}",
+            style(icon("✨")).green().bold(),
+            names.join(", ")
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("add {added} pattern(s) to .gitignore:"));
+        for line in merged[existing.len()..].lines() {
+            println!("      {line}");
+        }
+        return Ok(());
+    }
+
+    fs::write(&path, &merged)?;
+    println!(
+        "{} Added {} pattern(s) to .gitignore",
+        style("✓").green().bold(),
+        added
+    );
+
+    let ignored = repo.get_ignored_tracked_files()?;
+    if ignored.is_empty() {
+        return Ok(());
+    }
+    println!();
+    println!(
+        "{} {} tracked file(s) are now ignored:",
+        style("⚠").yellow().bold(),
+        ignored.len()
+    );
+    for file in &ignored {
+        println!("  {}", style(file).dim());
+    }
+    // Without anyone to ask, the patterns stay added and the files stay tracked
+    if !console::user_attended() && !confirm::assume_yes() {
+        println!(
+            "{} Left them tracked; rerun with --yes or `git rm --cached` them to stop tracking",
+            style("⚠").yellow().bold()
+        );
+        return Ok(());
+    }
+    confirm::confirm_or_abort(
+        "Stop tracking them (files stay on disk)?",
+        "Nothing untracked",
+    )?;
+    let paths: Vec<&str> = ignored.iter().map(String::as_str).collect();
+    repo.untrack(&paths)?;
+    println!(
+        "{} Untracked {} file(s); commit to record the removal",
+        style("✓").green().bold(),
+        paths.len()
+    );
+    Ok(())
+}
+
+fn find_template(name: &str) -> Result<(&'static str, &'static str), GitxError> {
+    let wanted = name.to_lowercase();
+    TEMPLATES
+        .iter()
+        .find(|(template, aliases, _)| *template == wanted || aliases.contains(&wanted.as_str()))
+        .map(|(template, _, contents)| (*template, *contents))
+        .ok_or_else(|| {
+            let available: Vec<&str> = TEMPLATES.iter().map(|(template, _, _)| *template).collect();
+            GitxError::Usage(format!(
+                "Unknown .gitignore template '{name}' (available: {})",
+                available.join(", ")
+            ))
+        })
+}
+
+/// Append each template's patterns that `existing` lacks under a `### <name>` heading.
+///
+/// Templates are made of blank-line separated groups; a group's comments are kept only when
+/// at least one of its patterns is added. Returns the new contents and the number of
+/// patterns added.
+fn merge_templates(existing: &str, templates: &[(&str, &str)]) -> (String, usize) {
+    let mut known: Vec<String> = existing.lines().map(normalize_pattern).collect();
+    let mut merged = existing.to_string();
+    let mut added = 0;
+
+    for (name, contents) in templates {
+        let mut groups: Vec<Vec<&str>> = Vec::new();
+        for group in contents.split("\n\n") {
+            let mut has_new = false;
+            let lines: Vec<&str> = group
+                .lines()
+                .filter(|line| {
+                    let pattern = normalize_pattern(line);
+                    if pattern.is_empty() {
+                        return !line.trim().is_empty();
+                    }
+                    if known.contains(&pattern) {
+                        return false;
+                    }
+                    known.push(pattern);
+                    has_new = true;
+                    added += 1;
+                    true
+                })
+                .collect();
+            if has_new {
+                groups.push(lines);
+            }
+        }
+        if groups.is_empty() {
+            continue;
+        }
+
+        if !merged.is_empty() {
+            if !merged.ends_with('\n') {
+                merged.push('\n');
+            }
+            merged.push('\n');
+        }
+        merged.push_str(&format!("### {name} (added by xg ignore init)\n"));
+        let blocks: Vec<String> = groups.iter().map(|lines| lines.join("\n")).collect();
+        merged.push_str(&blocks.join("\n\n"));
+        merged.push('\n');
+    }
+    (merged, added)
+}
+
+/// A pattern compared without surrounding whitespace or a leading `/`, so `target/` and
+/// `/target/` count as the same rule
+fn normalize_pattern(line: &str) -> String {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return String::new();
+    }
+    line.trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_template, merge_templates, TEMPLATES};
+
+    #[test]
+    fn find_template_accepts_aliases_case_insensitively() {
+        assert_eq!(find_template("Rust").unwrap().0, "rust");
+        assert_eq!(find_template("typescript").unwrap().0, "node");
+        assert_eq!(find_template("OSX").unwrap().0, "macos");
+        let err = find_template("cobol").unwrap_err().to_string();
+        assert!(err.contains("available: go, java"), "{err}");
+    }
+
+    #[test]
+    fn every_template_has_patterns() {
+        for (name, _, contents) in TEMPLATES {
+            let (merged, added) = merge_templates("", &[(name, contents)]);
+            assert!(added > 0, "{name}");
+            assert!(merged.starts_with(&format!("### {name} ")), "{merged}");
+        }
+    }
+
+    #[test]
+    fn merge_templates_skips_existing_patterns_and_keeps_user_lines() {
+        let existing = "# mine\nsecrets.txt\ntarget/";
+        let template = "# Build output\n/target/\n\n# Backups\n**/*.rs.bk\n";
+
+        let (merged, added) = merge_templates(existing, &[("rust", template)]);
+
+        assert_eq!(added, 1);
+        assert_eq!(
+            merged,
+            "# mine\nsecrets.txt\ntarget/\n\n### rust (added by xg ignore init)\n# Backups\n**/*.rs.bk\n"
+        );
+        assert_eq!(merge_templates(&merged, &[("rust", template)]).1, 0);
+    }
+
+    #[test]
+    fn merge_templates_does_not_repeat_patterns_shared_by_templates() {
+        let (merged, added) = merge_templates(
+            "",
+            &[("java", "build/\n*.class\n"), ("node", "build/\ndist/\n")],
+        );
+        assert_eq!(added, 3);
+        assert_eq!(merged.matches("build/").count(), 1);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::error::GitxError;
+use crate::git::commits::staging::StagedFileSize;
+use crate::git::GitRepo;
+use crate::tui::confirm;
+use console::style;
+
+const MAX_FILE_SIZE_KEY: &str = "xgit.commit.maxFileSize";
+const MAX_NEW_FILES_KEY: &str = "xgit.commit.maxNewFiles";
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_NEW_FILES: usize = 500;
+/// Offending files or directories listed before the rest are summarised
+const MAX_LISTED: usize = 5;
+
+/// What the staged changes trip, if anything
+#[derive(Debug, Clone, PartialEq)]
+struct GuardReport {
+    /// Files over the size limit, largest first
+    large_files: Vec<StagedFileSize>,
+    /// Number of files the commit adds, when over the limit
+    new_files: Option<usize>,
+    /// Top-level directories holding most of the new files, most first
+    busiest_dirs: Vec<(String, usize)>,
+}
+
+/// Warn before committing staged files over `xgit.commit.maxFileSize` (default 10 MB) or
+/// more new files than `xgit.commit.maxNewFiles` (default 500), suggesting Git LFS or
+/// `.gitignore` fixes. Either limit is turned off by setting it to 0.
+///
+/// With `prompt`, asks whether to commit anyway when attached to a terminal (or with `--yes`)
+/// and aborts on "no"; otherwise only warns.
+pub fn check_staged_sizes(repo: &GitRepo, prompt: bool) -> Result<(), GitxError> {
+    let max_file_size = match repo.get_config_string(MAX_FILE_SIZE_KEY)? {
+        Some(value) => parse_size(&value).ok_or_else(|| {
+            GitxError::Usage(format!(
+                "Invalid {MAX_FILE_SIZE_KEY} '{value}' (expected a size such as 500k or 10m)"
+            ))
+        })?,
+        None => DEFAULT_MAX_FILE_SIZE,
+    };
+    let max_new_files = match repo.get_config_string(MAX_NEW_FILES_KEY)? {
+        Some(value) => value.trim().parse().map_err(|_| {
+            GitxError::Usage(format!(
+                "Invalid {MAX_NEW_FILES_KEY} '{value}' (expected a number of files)"
+            ))
+        })?,
+        None => DEFAULT_MAX_NEW_FILES,
+    };
+
+    let files = repo.get_staged_file_sizes()?;
+    let Some(report) = evaluate(files, max_file_size, max_new_files) else {
+        return Ok(());
+    };
+    display_report(&report, max_file_size);
+
+    if prompt
+        && (console::user_attended() || confirm::assume_yes())
+        && !confirm::confirm("Commit anyway?")?
+    {
+        return Err(GitxError::UserAbort("Commit aborted".to_string()));
+    }
+    Ok(())
+}
+
+fn evaluate(
+    files: Vec<StagedFileSize>,
+    max_file_size: u64,
+    max_new_files: usize,
+) -> Option<GuardReport> {
+    let added: Vec<&str> = files
+        .iter()
+        .filter(|file| file.added)
+        .map(|file| file.path.as_str())
+        .collect();
+    let new_files = (max_new_files > 0 && added.len() > max_new_files).then(|| added.len());
+
+    let mut busiest_dirs: Vec<(String, usize)> = Vec::new();
+    if new_files.is_some() {
+        for path in &added {
+            let dir = match path.split_once('/') {
+                Some((dir, _)) => format!("{dir}/"),
+                None => continue,
+            };
+            match busiest_dirs.iter_mut().find(|(name, _)| *name == dir) {
+                Some((_, count)) => *count += 1,
+                None => busiest_dirs.push((dir, 1)),
+            }
+        }
+        busiest_dirs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        busiest_dirs.truncate(MAX_LISTED);
+    }
+
+    let mut large_files: Vec<StagedFileSize> = files
+        .into_iter()
+        .filter(|file| max_file_size > 0 && file.size > max_file_size)
+        .collect();
+    large_files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    if large_files.is_empty() && new_files.is_none() {
+        return None;
+    }
+    Some(GuardReport {
+        large_files,
+        new_files,
+        busiest_dirs,
+    })
+}
+
+fn display_report(report: &GuardReport, max_file_size: u64) {
+    if !report.large_files.is_empty() {
+        println!(
+            "{} {} staged file(s) over {}:",
+            style("⚠").yellow().bold(),
+            report.large_files.len(),
+            format_size(max_file_size)
+        );
+        for file in report.large_files.iter().take(MAX_LISTED) {
+            println!(
+                "    {}  {}",
+                style(format!("{:>9}", format_size(file.size))).yellow(),
+                file.path
+            );
+        }
+        if report.large_files.len() > MAX_LISTED {
+            println!(
+                "    {}",
+                style(format!(
+                    "… and {} more",
+                    report.large_files.len() - MAX_LISTED
+                ))
+                .dim()
+            );
+        }
+        let example = &report.large_files[0].path;
+        println!("  {}", style("Consider:").dim());
+        println!(
+            "    {}",
+            style(format!("git lfs track \"{}\"", lfs_pattern(example))).cyan()
+        );
+        println!(
+            "    {}",
+            style(format!(
+                "echo \"{example}\" >> .gitignore && git rm --cached \"{example}\""
+            ))
+            .cyan()
+        );
+        println!();
+    }
+
+    if let Some(count) = report.new_files {
+        println!(
+            "{} This commit adds {} new files",
+            style("⚠").yellow().bold(),
+            count
+        );
+        for (dir, files) in &report.busiest_dirs {
+            println!("    {}  {}", style(format!("{files:>9}")).yellow(), dir);
+        }
+        println!(
+            "  {}",
+            style(
+                "Build output or dependencies? Add them to .gitignore (see `xg ignore list`) and unstage them with `git rm -r --cached <dir>`"
+            )
+            .dim()
+        );
+        println!();
+    }
+}
+
+/// Git LFS pattern for a path: its extension when it has one, else the path itself
+fn lfs_pattern(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.is_empty() => {
+            format!("*.{extension}")
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Parse a size like git config does: a number with an optional `k`, `m` or `g` suffix
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let (number, multiplier) = match value.chars().last()? {
+        'k' => (&value[..value.len() - 1], 1024),
+        'm' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value.as_str(), 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, format_size, lfs_pattern, parse_size};
+    use crate::git::commits::staging::StagedFileSize;
+
+    fn file(path: &str, size: u64, added: bool) -> StagedFileSize {
+        StagedFileSize {
+            path: path.to_string(),
+            size,
+            added,
+        }
+    }
+
+    #[test]
+    fn parse_size_accepts_git_style_suffixes() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("500k"), Some(500 * 1024));
+        assert_eq!(parse_size(" 10M "), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("m"), None);
+        assert_eq!(parse_size("ten"), None);
+    }
+
+    #[test]
+    fn format_size_picks_a_readable_unit() {
+        assert_eq!(format_size(900), "900 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(10 * 1024 * 1024), "10.0 MB");
+    }
+
+    #[test]
+    fn lfs_pattern_uses_the_extension() {
+        assert_eq!(lfs_pattern("assets/video.mp4"), "*.mp4");
+        assert_eq!(lfs_pattern("data/blob"), "data/blob");
+        assert_eq!(lfs_pattern(".env"), ".env");
+    }
+
+    #[test]
+    fn evaluate_flags_large_files_largest_first() {
+        let report = evaluate(
+            vec![
+                file("small.txt", 10, true),
+                file("a.bin", 200, false),
+                file("b.bin", 300, true),
+            ],
+            100,
+            0,
+        )
+        .unwrap();
+        let paths: Vec<&str> = report
+            .large_files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["b.bin", "a.bin"]);
+        assert_eq!(report.new_files, None);
+
+        assert_eq!(evaluate(vec![file("a.bin", 200, true)], 0, 0), None);
+    }
+
+    #[test]
+    fn evaluate_flags_many_new_files_by_directory() {
+        let files = vec![
+            file("node_modules/a/index.js", 1, true),
+            file("node_modules/b/index.js", 1, true),
+            file("dist/app.js", 1, true),
+            file("README.md", 1, true),
+            file("src/main.js", 1, false),
+        ];
+        assert_eq!(evaluate(files.clone(), 100, 4), None);
+
+        let report = evaluate(files, 100, 3).unwrap();
+        assert_eq!(report.new_files, Some(4));
+        assert_eq!(
+            report.busiest_dirs,
+            vec![("node_modules/".to_string(), 2), ("dist/".to_string(), 1)]
+        );
+        assert!(report.large_files.is_empty());
+    }
+}
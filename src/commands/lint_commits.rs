@@ -0,0 +1,148 @@
+use crate::error::GitxError;
+use crate::git::commits::commitlint::{lint_message, LintLevel, LintProblem};
+use crate::git::GitRepo;
+use crate::tui::emoji::icon;
+use console::style;
+use std::io::{self, Write};
+
+/// A commit whose message breaks the rules
+struct CommitReport {
+    short_id: String,
+    subject: String,
+    problems: Vec<LintProblem>,
+}
+
+/// Check the message of every commit in `range` against the `[commitlint]` rules, for CI.
+/// Fails when any message has an error; warnings are only reported.
+pub fn handle_lint_commits(range: &str) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let rules = repo.xgit_config()?.commit_lint().clone();
+    let commits = repo.list_commits_range(range, 0, None)?;
+
+    let reports: Vec<CommitReport> = commits
+        .iter()
+        .filter_map(|commit| {
+            let problems = lint_message(&commit.message, &rules);
+            (!problems.is_empty()).then(|| CommitReport {
+                short_id: commit.hash.chars().take(7).collect(),
+                subject: commit
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                problems,
+            })
+        })
+        .collect();
+    write_report(&mut io::stdout().lock(), commits.len(), &reports)?;
+
+    let rejected = reports
+        .iter()
+        .filter(|report| has_errors(&report.problems))
+        .count();
+    if rejected > 0 {
+        return Err(GitxError::Usage(format!(
+            "{rejected} commit message{} in {range} break{} the commit message rules",
+            if rejected == 1 { "" } else { "s" },
+            if rejected == 1 { "s" } else { "" }
+        )));
+    }
+    Ok(())
+}
+
+/// Whether any of `problems` rejects the message rather than warning about it
+pub(crate) fn has_errors(problems: &[LintProblem]) -> bool {
+    problems
+        .iter()
+        .any(|problem| problem.level == LintLevel::Error)
+}
+
+/// `problems` as indented plain lines, for error messages
+pub(crate) fn describe_problems(problems: &[LintProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| {
+            let level = match problem.level {
+                LintLevel::Error => "error",
+                LintLevel::Warning => "warning",
+            };
+            format!("  {level} ({}): {}\n", problem.rule, problem.message)
+        })
+        .collect()
+}
+
+fn write_report(out: &mut impl Write, checked: usize, reports: &[CommitReport]) -> io::Result<()> {
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    if reports.is_empty() {
+        return writeln!(
+            out,
+            "{} {checked} commit{} checked, no problems",
+            style(icon("✨")).green().bold(),
+            plural(checked)
+        );
+    }
+
+    for report in reports {
+        let marker = if has_errors(&report.problems) {
+            style("✗").red().bold()
+        } else {
+            style("⚠").yellow().bold()
+        };
+        writeln!(
+            out,
+            "{marker} {} {}",
+            style(&report.short_id).yellow(),
+            report.subject
+        )?;
+        for problem in &report.problems {
+            let level = match problem.level {
+                LintLevel::Error => style(format!("{:<8}", "error")).red(),
+                LintLevel::Warning => style(format!("{:<8}", "warning")).yellow(),
+            };
+            writeln!(
+                out,
+                "    {level} {:<15} {}",
+                style(problem.rule).dim(),
+                problem.message
+            )?;
+        }
+    }
+
+    let rejected = reports
+        .iter()
+        .filter(|report| has_errors(&report.problems))
+        .count();
+    writeln!(
+        out,
+        "\n{checked} commit{} checked: {rejected} with errors, {} with warnings only",
+        plural(checked),
+        reports.len() - rejected
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_report, CommitReport};
+    use crate::git::commits::commitlint::{lint_message, LintRules};
+    use crate::tui::snapshot::{normalize, render};
+
+    #[test]
+    fn report_lists_commits_with_problems() {
+        let rules = LintRules::default();
+        let reports: Vec<CommitReport> = [
+            ("1a2b3c4", "Add lint-commits"),
+            ("5d6e7f8", "fix: Fixed empty diffs"),
+        ]
+        .iter()
+        .map(|(short_id, message)| CommitReport {
+            short_id: short_id.to_string(),
+            subject: message.to_string(),
+            problems: lint_message(message, &rules),
+        })
+        .collect();
+
+        let output = render(|out| write_report(out, 4, &reports));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
@@ -0,0 +1,473 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::commits::builder::CommitTime;
+use crate::git::commits::log::LogOptions;
+use crate::git::{repository::core::CommitInfo, GitRepo};
+use crate::tui::diff_display::{self, DiffDisplayOptions};
+use crate::tui::log_browser::{self, LogFilter};
+use crate::tui::template::Template;
+use console::style;
+use std::path::Path;
+
+/// Fields available to `xg log --format`
+pub const LOG_FORMAT_FIELDS: &[&str] = &[
+    "hash",
+    "short",
+    "subject",
+    "body",
+    "author",
+    "email",
+    "date",
+    "timestamp",
+    "parents",
+];
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Which commits `xg log --format` and `xg log --interactive` show
+#[derive(Debug, Default)]
+pub struct LogFilters<'a> {
+    pub max_count: Option<usize>,
+    pub skip: usize,
+    pub author: Option<&'a str>,
+    pub since: Option<&'a str>,
+    pub until: Option<&'a str>,
+    /// Only commits that changed one of these paths
+    pub paths: &'a [String],
+}
+
+impl LogFilters<'_> {
+    /// The walk for `rev`
+    fn options(&self, rev: &str) -> Result<LogOptions, GitxError> {
+        Ok(LogOptions {
+            rev: Some(rev.to_string()),
+            max_count: self.max_count,
+            skip: self.skip,
+            author: self.author.map(str::to_string),
+            paths: self.paths.to_vec(),
+            since: self.since.map(parse_log_date).transpose()?,
+            until: self.until.map(parse_log_date).transpose()?,
+        })
+    }
+
+    /// `args` for `git log` with the filters given on the command line in front
+    fn git_log_args(&self, args: &[String]) -> Vec<String> {
+        let mut git_args = Vec::new();
+        if let Some(max_count) = self.max_count {
+            git_args.push(format!("--max-count={max_count}"));
+        }
+        if self.skip > 0 {
+            git_args.push(format!("--skip={}", self.skip));
+        }
+        let matching = [
+            ("--author", self.author),
+            ("--since", self.since),
+            ("--until", self.until),
+        ];
+        for (flag, value) in matching {
+            if let Some(value) = value {
+                git_args.push(format!("{flag}={value}"));
+            }
+        }
+        git_args.extend(args.iter().cloned());
+        if !self.paths.is_empty() {
+            if !args.iter().any(|arg| arg == "--") {
+                git_args.push("--".to_string());
+            }
+            git_args.extend(self.paths.iter().cloned());
+        }
+        git_args
+    }
+
+    fn is_set(&self) -> bool {
+        self.max_count.is_some() || self.skip > 0 || self.filters_commits()
+    }
+
+    fn filters_commits(&self) -> bool {
+        self.author.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+            || !self.paths.is_empty()
+    }
+}
+
+/// Show commit history, rendered through a template when `format` is given
+pub fn handle_log(
+    format: Option<&str>,
+    filters: &LogFilters<'_>,
+    args: &[String],
+) -> Result<(), GitxError> {
+    let Some(format) = format else {
+        return git_passthrough("log", &filters.git_log_args(args), false);
+    };
+
+    let template =
+        Template::parse(format, LOG_FORMAT_FIELDS).map_err(|e| GitxError::Usage(e.to_string()))?;
+    let rev = rev_arg("--format", args)?;
+
+    let repo = GitRepo::open(".")?;
+    // The read backend only pages; filtering walks with libgit2
+    let commits = if filters.filters_commits() {
+        repo.list_commits_with(&filters.options(rev)?)?
+    } else {
+        repo.read_backend()?
+            .log(rev, filters.skip, filters.max_count)?
+    };
+    for commit in &commits {
+        println!("{}", template.render(|field| commit_field(commit, field)));
+    }
+    Ok(())
+}
+
+/// Browse the history in the terminal beside its commit graph, with a detail pane and author
+/// and path filters that can be changed while browsing
+pub fn handle_log_interactive(filters: &LogFilters<'_>, args: &[String]) -> Result<(), GitxError> {
+    if !console::user_attended() {
+        return Err(GitxError::Usage(
+            "`xg log --interactive` needs a terminal; use --format to print the history"
+                .to_string(),
+        ));
+    }
+    let rev = rev_arg("--interactive", args)?;
+    let repo = GitRepo::open(".")?;
+    let filter = LogFilter {
+        author: filters.author.map(str::to_string),
+        paths: filters.paths.to_vec(),
+    };
+    let load = |filter: &LogFilter| -> anyhow::Result<Vec<CommitInfo>> {
+        let mut options = filters.options(rev)?;
+        options.author = filter.author.clone();
+        options.paths = filter.paths.clone();
+        Ok(repo.list_commits_with(&options)?)
+    };
+    let commits = load(&filter)?;
+    let width = usize::from(console::Term::stdout().size().1);
+    log_browser::browse(rev, commits, filter, load, |commit| {
+        commit_details(&repo, commit, width)
+    })?;
+    Ok(())
+}
+
+/// The detail pane of `xg log --interactive`: header, full message and `--stat` of the changes
+fn commit_details(repo: &GitRepo, commit: &CommitInfo, width: usize) -> Vec<String> {
+    let parents: Vec<String> = commit
+        .parent_ids
+        .iter()
+        .map(|id| id.chars().take(7).collect())
+        .collect();
+    let mut lines = vec![
+        format!(
+            "{} {}",
+            style("commit").yellow(),
+            style(&commit.hash).yellow()
+        ),
+        format!("Author:  {} <{}>", commit.author_name, commit.author_email),
+        format!(
+            "Date:    {}",
+            format_date(commit.time, commit.offset_minutes)
+        ),
+    ];
+    if parents.len() > 1 {
+        lines.push(format!("Merge:   {}", parents.join(" ")));
+    }
+    lines.push(String::new());
+    lines.extend(
+        commit
+            .message
+            .trim_end()
+            .lines()
+            .map(|line| format!("    {line}")),
+    );
+    let stat = repo
+        .get_commit_diff(&commit.hash)
+        .and_then(|diff| repo.diff_summary(&diff));
+    match stat {
+        Ok(summary) if !summary.files.is_empty() => {
+            lines.push(String::new());
+            lines.extend(summary.render_stat(width).lines().map(str::to_string));
+        }
+        Ok(_) => {}
+        Err(err) => lines.push(format!("{} {err:#}", style("⚠").yellow())),
+    }
+    lines
+}
+
+/// The revision or range in `args`, HEAD when there is none
+fn rev_arg<'a>(option: &str, args: &'a [String]) -> Result<&'a str, GitxError> {
+    match args {
+        [] => Ok("HEAD"),
+        [rev] => Ok(rev.as_str()),
+        _ => Err(GitxError::Usage(format!(
+            "{option} accepts at most one revision or range; use `xg git log` for other options"
+        ))),
+    }
+}
+
+/// The path `xg log --follow` shows itself: `args` is just `<path>` or `-- <path>`
+fn follow_path(args: &[String]) -> Option<&str> {
+    let path = match args {
+        [path] => path,
+        [separator, path] if separator == "--" => path,
+        _ => return None,
+    };
+    (!path.starts_with('-')).then(|| path.as_str())
+}
+
+/// Show every commit that changed the path in `args`, following renames, each with its diff of
+/// that file. Anything more than a path is handed to `git log --follow`.
+pub fn handle_log_follow(filters: &LogFilters<'_>, args: &[String]) -> Result<(), GitxError> {
+    let path = match follow_path(args) {
+        Some(path) if !filters.is_set() => path,
+        _ => {
+            let mut git_args = vec!["--follow".to_string()];
+            git_args.extend(filters.git_log_args(args));
+            return git_passthrough("log", &git_args, false);
+        }
+    };
+
+    let repo = GitRepo::open(".")?;
+    let repo_path = repo_relative_path(&repo, path)?;
+    let history = repo.file_history(&repo_path)?;
+    if history.is_empty() {
+        return Err(GitxError::Usage(format!(
+            "No commits found for '{repo_path}'"
+        )));
+    }
+
+    let options = DiffDisplayOptions {
+        side_by_side: false,
+        word_diff: true,
+        highlight: console::colors_enabled(),
+    };
+    for entry in &history {
+        let subject = entry.commit.message.lines().next().unwrap_or("").trim();
+        println!(
+            "{} {} {}",
+            style(entry.commit.hash.chars().take(7).collect::<String>())
+                .yellow()
+                .bold(),
+            subject,
+            style(format!(
+                "({}, {})",
+                entry.commit.author_name,
+                format_date(entry.commit.time, entry.commit.offset_minutes)
+            ))
+            .dim()
+        );
+        // Renames show up in the diff header as `old → new`
+        diff_display::display_diff(&repo.get_file_history_diff(entry)?, &options)?;
+    }
+    Ok(())
+}
+
+/// `path` as given on the command line (relative to the current directory) relative to the
+/// repository root
+pub fn repo_relative_path(repo: &GitRepo, path: &str) -> Result<String, GitxError> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| GitxError::Usage("Paths need a working tree".to_string()))?;
+    let absolute = std::env::current_dir()?.join(path);
+    let relative = strip_workdir(&absolute, workdir)
+        .or_else(|| strip_workdir(&absolute, &workdir.canonicalize().ok()?))
+        .ok_or_else(|| GitxError::Usage(format!("'{path}' is outside the repository")))?;
+    Ok(relative)
+}
+
+/// Path below `workdir`, with `.` and `..` components resolved and `/` separators
+fn strip_workdir(path: &Path, workdir: &Path) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.strip_prefix(workdir).ok()?.components() {
+        match component {
+            std::path::Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            std::path::Component::ParentDir => {
+                parts.pop()?;
+            }
+            _ => {}
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn commit_field(commit: &CommitInfo, field: &str) -> Option<String> {
+    let (subject, body) = match commit.message.split_once('\n') {
+        Some((subject, body)) => (subject, body.trim()),
+        None => (commit.message.as_str(), ""),
+    };
+
+    match field {
+        "hash" => Some(commit.hash.clone()),
+        "short" => Some(commit.hash.chars().take(7).collect()),
+        "subject" => Some(subject.trim().to_string()),
+        "body" => Some(body.to_string()),
+        "author" => Some(commit.author_name.clone()),
+        "email" => Some(commit.author_email.clone()),
+        "date" => Some(format_date(commit.time, commit.offset_minutes)),
+        "timestamp" => Some(commit.time.to_string()),
+        "parents" => Some(
+            commit
+                .parent_ids
+                .iter()
+                .map(|id| id.chars().take(7).collect::<String>())
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+/// Seconds since the Unix epoch of a `--since`/`--until` value: any date git accepts for
+/// commits, or a bare `YYYY-MM-DD` meaning midnight UTC
+fn parse_log_date(value: &str) -> Result<i64, GitxError> {
+    CommitTime::parse(value)
+        .or_else(|| CommitTime::parse(&format!("{}T00:00:00Z", value.trim())))
+        .map(|time| time.seconds)
+        .ok_or_else(|| GitxError::Usage(format!("Invalid date '{value}'; use YYYY-MM-DD")))
+}
+
+/// `YYYY-MM-DD` of a timestamp in the author's timezone
+pub fn format_date(time: i64, offset_minutes: i32) -> String {
+    let days = (time + i64::from(offset_minutes) * 60).div_euclid(SECONDS_PER_DAY);
+
+    // Civil-from-days conversion for the proleptic Gregorian calendar
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        commit_field, follow_path, format_date, parse_log_date, rev_arg, strip_workdir, LogFilters,
+    };
+    use crate::git::repository::core::CommitInfo;
+
+    #[test]
+    fn format_date_uses_author_timezone() {
+        assert_eq!(format_date(0, 0), "1970-01-01");
+        // 2024-02-29 23:30 UTC is already March 1st at UTC+1
+        assert_eq!(format_date(1_709_249_400, 0), "2024-02-29");
+        assert_eq!(format_date(1_709_249_400, 60), "2024-03-01");
+        assert_eq!(format_date(-1, 0), "1969-12-31");
+    }
+
+    #[test]
+    fn rev_arg_defaults_to_head() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(rev_arg("--format", &[]).unwrap(), "HEAD");
+        assert_eq!(
+            rev_arg("--format", &args(&["main..feature"])).unwrap(),
+            "main..feature"
+        );
+        assert!(rev_arg("--format", &args(&["main", "feature"])).is_err());
+    }
+
+    #[test]
+    fn passthrough_forwards_filters_to_git_log() {
+        let filters = LogFilters {
+            max_count: Some(3),
+            skip: 2,
+            author: Some("ada"),
+            until: Some("2024-01-01"),
+            ..LogFilters::default()
+        };
+        assert_eq!(
+            filters.git_log_args(&["--oneline".to_string()]),
+            [
+                "--max-count=3",
+                "--skip=2",
+                "--author=ada",
+                "--until=2024-01-01",
+                "--oneline"
+            ]
+        );
+        assert_eq!(
+            LogFilters::default().git_log_args(&["-p".to_string()]),
+            ["-p"]
+        );
+
+        // Paths go after the `--` that ends the revisions, adding one when there is none
+        let paths = ["src".to_string()];
+        let filters = LogFilters {
+            paths: &paths,
+            ..LogFilters::default()
+        };
+        assert_eq!(
+            filters.git_log_args(&["main".to_string()]),
+            ["main", "--", "src"]
+        );
+        assert_eq!(
+            filters.git_log_args(&["--".to_string(), "README.md".to_string()]),
+            ["--", "README.md", "src"]
+        );
+    }
+
+    #[test]
+    fn follow_takes_a_lone_path_the_way_git_does() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(follow_path(&args(&["src/lib.rs"])), Some("src/lib.rs"));
+        assert_eq!(
+            follow_path(&args(&["--", "src/lib.rs"])),
+            Some("src/lib.rs")
+        );
+        assert_eq!(follow_path(&args(&["-p", "--", "src/lib.rs"])), None);
+        assert_eq!(follow_path(&args(&["--stat"])), None);
+        assert_eq!(follow_path(&[]), None);
+    }
+
+    #[test]
+    fn parse_log_date_accepts_bare_dates() {
+        assert_eq!(parse_log_date("1970-01-02").unwrap(), 86_400);
+        assert_eq!(parse_log_date("1970-01-02T01:00:00+01:00").unwrap(), 86_400);
+        assert!(parse_log_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn commit_field_splits_subject_and_body() {
+        let commit = CommitInfo {
+            hash: "0123456789abcdef".to_string(),
+            message: "Add feature\n\nLonger explanation\n".to_string(),
+            author_name: "Test User".to_string(),
+            author_email: "test@example.com".to_string(),
+            time: 0,
+            offset_minutes: 0,
+            parent_ids: Vec::new(),
+        };
+
+        assert_eq!(commit_field(&commit, "short").as_deref(), Some("0123456"));
+        assert_eq!(
+            commit_field(&commit, "subject").as_deref(),
+            Some("Add feature")
+        );
+        assert_eq!(
+            commit_field(&commit, "body").as_deref(),
+            Some("Longer explanation")
+        );
+    }
+
+    #[test]
+    fn strip_workdir_resolves_relative_components() {
+        let workdir = std::path::Path::new("/repo");
+        assert_eq!(
+            strip_workdir(&workdir.join("src/./nested/../lib.rs"), workdir).as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(strip_workdir(&workdir.join("../elsewhere"), workdir), None);
+        assert_eq!(
+            strip_workdir(std::path::Path::new("/other/file"), workdir),
+            None
+        );
+    }
+}
@@ -1,6 +1,40 @@
+pub mod add;
+pub mod batch;
+pub mod blame;
 pub mod branch;
+pub mod branch_new;
 pub mod branch_prune;
 pub mod branch_stats;
+pub mod changelog;
+pub mod cherry_pick;
 pub mod commit;
 pub mod diff;
+pub mod doctor;
 pub mod git_passthrough;
+pub mod history;
+pub mod hooks;
+pub mod ignore;
+pub mod large_files;
+pub mod lint_commits;
+pub mod log;
+pub mod open;
+pub mod owners;
+pub mod plugin;
+pub mod pr;
+pub mod pre_commit;
+pub mod pull;
+pub mod push;
+pub mod rebase;
+pub mod remote;
+pub mod review;
+pub mod show_diff;
+pub mod stack;
+pub mod stash;
+pub mod stats;
+pub mod status;
+pub mod switch;
+pub mod sync;
+pub mod tag;
+pub mod undo;
+pub mod version;
+pub mod workspace;
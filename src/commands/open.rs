@@ -0,0 +1,158 @@
+use super::log::repo_relative_path;
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use crate::github::GitHubPrMatcher;
+use crate::tui::dry_run;
+use console::style;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// A file to open and the lines to highlight, from `path`, `path:42` or `path:42-50`
+#[derive(Debug, PartialEq, Eq)]
+struct FileTarget<'a> {
+    path: &'a str,
+    lines: Option<(u32, u32)>,
+}
+
+/// Open the forge's page for `target` in the browser: a file, or lines of one, as of the
+/// current commit. Without a target, open the current branch's pull request, or the branch
+/// itself when it has none or `branch` is set. `print` writes the URL instead.
+pub async fn handle_open(
+    target: Option<&str>,
+    branch: bool,
+    print: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let remote = repo.forge_remote()?;
+    let head = repo
+        .list_commits_range("HEAD", 0, Some(1))?
+        .into_iter()
+        .next()
+        .map(|commit| commit.hash)
+        .ok_or_else(|| GitxError::Usage("No commits yet; nothing to open".to_string()))?;
+
+    let url = match target {
+        Some(target) => {
+            let target = parse_target(target);
+            if !Path::new(target.path).exists() {
+                return Err(GitxError::Usage(format!("'{}' not found", target.path)));
+            }
+            let path = repo_relative_path(&repo, target.path)?;
+            remote.blob_url(&head, &path, target.lines)
+        }
+        // A detached HEAD opens the tree at the commit
+        None => match repo.get_current_branch() {
+            Ok(current) => {
+                let pull_request = if branch {
+                    None
+                } else {
+                    pull_request_url(&repo, &current).await
+                };
+                pull_request.unwrap_or_else(|| remote.branch_url(&current))
+            }
+            Err(_) => remote.branch_url(&head),
+        },
+    };
+
+    if print {
+        println!("{url}");
+        return Ok(());
+    }
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("open {url} in the browser"));
+        return Ok(());
+    }
+    open_in_browser(&url)?;
+    println!("{} Opened {}", style("✓").green().bold(), style(&url).dim());
+    Ok(())
+}
+
+/// The URL of `branch`'s pull request, or `None` when it has none or the forge cannot be asked
+async fn pull_request_url(repo: &GitRepo, branch: &str) -> Option<String> {
+    let matcher = GitHubPrMatcher::new(repo).ok()?;
+    matcher
+        .find_pr_for_branch(repo, branch)
+        .await
+        .map(|pr| pr.record.url)
+}
+
+/// Split a trailing `:line` or `:first-last` off `target`; anything else is all path
+fn parse_target(target: &str) -> FileTarget<'_> {
+    let whole = FileTarget {
+        path: target,
+        lines: None,
+    };
+    let Some((path, lines)) = target.rsplit_once(':') else {
+        return whole;
+    };
+    let (first, last) = lines.split_once('-').unwrap_or((lines, lines));
+    match (first.parse::<u32>(), last.parse::<u32>()) {
+        (Ok(first), Ok(last)) if !path.is_empty() && first > 0 && last >= first => FileTarget {
+            path,
+            lines: Some((first, last)),
+        },
+        _ => whole,
+    }
+}
+
+/// Launch `$BROWSER` on `url`, or the platform's opener when it is not set
+fn open_in_browser(url: &str) -> Result<(), GitxError> {
+    let browser = std::env::var("BROWSER")
+        .ok()
+        .filter(|browser| !browser.trim().is_empty());
+    let mut command = match &browser {
+        Some(browser) => {
+            let mut words = browser.split_whitespace();
+            let mut command = Command::new(words.next().unwrap_or_default());
+            command.args(words);
+            command
+        }
+        None if cfg!(target_os = "macos") => Command::new("open"),
+        None if cfg!(windows) => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        None => Command::new("xdg-open"),
+    };
+    let launched = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(false, |status| status.success());
+    if !launched {
+        return Err(GitxError::Other(anyhow::anyhow!(
+            "Could not launch a browser; set $BROWSER or visit {url}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_target, FileTarget};
+
+    #[test]
+    fn parse_target_reads_lines_and_ranges() {
+        let target = |path, lines| FileTarget { path, lines };
+        assert_eq!(parse_target("src/lib.rs"), target("src/lib.rs", None));
+        assert_eq!(
+            parse_target("src/lib.rs:42"),
+            target("src/lib.rs", Some((42, 42)))
+        );
+        assert_eq!(
+            parse_target("src/lib.rs:10-20"),
+            target("src/lib.rs", Some((10, 20)))
+        );
+        assert_eq!(
+            parse_target("src/lib.rs:20-10"),
+            target("src/lib.rs:20-10", None)
+        );
+        assert_eq!(parse_target("notes:todo"), target("notes:todo", None));
+        assert_eq!(parse_target("src/lib.rs:0"), target("src/lib.rs:0", None));
+    }
+}
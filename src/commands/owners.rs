@@ -0,0 +1,245 @@
+use super::log::repo_relative_path;
+use crate::error::GitxError;
+use crate::git::commits::ownership::{AuthorLines, FileOwnership};
+use crate::git::GitRepo;
+use crate::tui::emoji::icon;
+use console::style;
+use std::collections::HashMap;
+
+const BAR_WIDTH: usize = 20;
+
+/// Show who last touched the current lines under `path`, overall and per directory entry
+pub fn handle_owners(path: Option<&str>, top: usize) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let prefix = repo_relative_path(&repo, path.unwrap_or("."))?;
+    let files = repo.get_line_ownership(&prefix)?;
+    let label = if prefix.is_empty() { "." } else { &prefix };
+    if files.is_empty() {
+        return Err(GitxError::Usage(format!(
+            "No tracked text files under '{label}'"
+        )));
+    }
+
+    let authors = merge_authors(files.iter().flat_map(|file| file.authors.iter()));
+    let total: usize = authors.iter().map(|author| author.lines).sum();
+    println!(
+        "{} Ownership of {} ({} lines in {} {})",
+        style(icon("👥")).cyan().bold(),
+        style(label).cyan().bold(),
+        total,
+        files.len(),
+        if files.len() == 1 { "file" } else { "files" }
+    );
+    println!();
+
+    let width = authors
+        .iter()
+        .take(top)
+        .map(|author| author_label(author).chars().count())
+        .max()
+        .unwrap_or(0);
+    for author in authors.iter().take(top) {
+        println!(
+            "  {}  {}  {}",
+            style(format!("{:<width$}", author_label(author))).cyan(),
+            share_bar(author.lines, total),
+            style(format!(
+                "{:>5.1}%  {} lines",
+                percent(author.lines, total),
+                author.lines
+            ))
+            .dim()
+        );
+    }
+    if authors.len() > top {
+        println!(
+            "  {}",
+            style(format!("… and {} more", authors.len() - top)).dim()
+        );
+    }
+    println!();
+    println!(
+        "  {} {} {}",
+        style("Bus factor:").bold(),
+        style(bus_factor(&authors)).yellow().bold(),
+        style("(authors who together last touched over half the lines)").dim()
+    );
+
+    let children = group_by_child(&files, &prefix);
+    if children.len() > 1 {
+        println!();
+        println!("  {}", style("By path").bold());
+        let width = children
+            .iter()
+            .map(|(child, _)| child.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (child, authors) in &children {
+            let total: usize = authors.iter().map(|author| author.lines).sum();
+            let owner = &authors[0];
+            println!(
+                "    {}  {}  {} {}",
+                style(format!("{child:<width$}")).cyan(),
+                share_bar(owner.lines, total),
+                owner.name,
+                style(format!(
+                    "{:.0}%, bus factor {}",
+                    percent(owner.lines, total),
+                    bus_factor(authors)
+                ))
+                .dim()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Sum line counts per author (by case-insensitive email), most lines first
+fn merge_authors<'a>(authors: impl Iterator<Item = &'a AuthorLines>) -> Vec<AuthorLines> {
+    let mut merged: Vec<AuthorLines> = Vec::new();
+    let mut index_by_email: HashMap<String, usize> = HashMap::new();
+    for author in authors {
+        let index = *index_by_email
+            .entry(author.email.to_lowercase())
+            .or_insert_with(|| {
+                merged.push(AuthorLines {
+                    lines: 0,
+                    ..author.clone()
+                });
+                merged.len() - 1
+            });
+        merged[index].lines += author.lines;
+    }
+    merged.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.name.cmp(&b.name)));
+    merged
+}
+
+/// Fewest authors who together own more than half of the lines
+fn bus_factor(authors: &[AuthorLines]) -> usize {
+    let total: usize = authors.iter().map(|author| author.lines).sum();
+    let mut owned = 0;
+    for (count, author) in authors.iter().enumerate() {
+        owned += author.lines;
+        if owned * 2 > total {
+            return count + 1;
+        }
+    }
+    authors.len()
+}
+
+/// Ownership per entry directly below `prefix`: a directory (with a trailing `/`) or a file
+fn group_by_child(files: &[FileOwnership], prefix: &str) -> Vec<(String, Vec<AuthorLines>)> {
+    let mut children: Vec<(String, Vec<&AuthorLines>)> = Vec::new();
+    for file in files {
+        let rest = if prefix.is_empty() {
+            file.path.as_str()
+        } else {
+            file.path
+                .strip_prefix(prefix)
+                .map_or(file.path.as_str(), |rest| rest.trim_start_matches('/'))
+        };
+        let child = match rest.split_once('/') {
+            Some((dir, _)) => format!("{dir}/"),
+            None => rest.to_string(),
+        };
+        match children.iter_mut().find(|(name, _)| *name == child) {
+            Some((_, authors)) => authors.extend(&file.authors),
+            None => children.push((child, file.authors.iter().collect())),
+        }
+    }
+
+    children
+        .into_iter()
+        .map(|(child, authors)| (child, merge_authors(authors.into_iter())))
+        .filter(|(_, authors)| !authors.is_empty())
+        .collect()
+}
+
+fn author_label(author: &AuthorLines) -> String {
+    format!("{} <{}>", author.name, author.email)
+}
+
+fn percent(lines: usize, total: usize) -> f64 {
+    lines as f64 * 100.0 / total.max(1) as f64
+}
+
+fn share_bar(lines: usize, total: usize) -> String {
+    let filled = (lines * BAR_WIDTH + total / 2) / total.max(1);
+    format!(
+        "{}{}",
+        style("█".repeat(filled)).green(),
+        style("░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH))).dim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bus_factor, group_by_child, merge_authors};
+    use crate::git::commits::ownership::{AuthorLines, FileOwnership};
+
+    fn lines(email: &str, lines: usize) -> AuthorLines {
+        AuthorLines {
+            name: email.split('@').next().unwrap_or_default().to_string(),
+            email: email.to_string(),
+            lines,
+        }
+    }
+
+    fn file(path: &str, authors: Vec<AuthorLines>) -> FileOwnership {
+        FileOwnership {
+            path: path.to_string(),
+            authors,
+        }
+    }
+
+    #[test]
+    fn merge_authors_sums_by_email_ignoring_case() {
+        let authors = [
+            lines("ada@example.com", 3),
+            lines("bob@example.com", 5),
+            lines("ADA@example.com", 4),
+        ];
+        let merged = merge_authors(authors.iter());
+        assert_eq!(
+            merged,
+            vec![lines("ada@example.com", 7), lines("bob@example.com", 5)]
+        );
+    }
+
+    #[test]
+    fn bus_factor_counts_authors_owning_over_half() {
+        assert_eq!(bus_factor(&[lines("a@x", 60), lines("b@x", 40)]), 1);
+        assert_eq!(bus_factor(&[lines("a@x", 50), lines("b@x", 50)]), 2);
+        assert_eq!(
+            bus_factor(&[lines("a@x", 40), lines("b@x", 30), lines("c@x", 30)]),
+            2
+        );
+        assert_eq!(bus_factor(&[]), 0);
+    }
+
+    #[test]
+    fn group_by_child_splits_directories_and_files() {
+        let files = [
+            file("src/cli.rs", vec![lines("ada@example.com", 10)]),
+            file("src/tui/a.rs", vec![lines("bob@example.com", 4)]),
+            file(
+                "src/tui/b.rs",
+                vec![lines("ada@example.com", 1), lines("bob@example.com", 2)],
+            ),
+        ];
+
+        let children = group_by_child(&files, "src");
+        assert_eq!(
+            children,
+            vec![
+                ("cli.rs".to_string(), vec![lines("ada@example.com", 10)]),
+                (
+                    "tui/".to_string(),
+                    vec![lines("bob@example.com", 6), lines("ada@example.com", 1)]
+                ),
+            ]
+        );
+        assert_eq!(group_by_child(&files, "")[0].0, "src/");
+    }
+}
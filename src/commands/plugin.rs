@@ -0,0 +1,137 @@
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use std::env;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of external plugin executables, e.g. `xg-foo` provides `xg foo`
+const PLUGIN_PREFIX: &str = "xg-";
+
+/// Find an `xg-<name>` plugin executable on PATH
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    let path_var = env::var_os("PATH")?;
+    find_plugin_in(name, &path_var)
+}
+
+fn find_plugin_in(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    // Reject names that would escape the PATH directories
+    if name.is_empty() || name.contains(std::path::is_separator) {
+        return None;
+    }
+
+    let file_name = format!("{PLUGIN_PREFIX}{name}{}", env::consts::EXE_SUFFIX);
+    env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Run a plugin executable, exposing the repository context through `XGIT_*` environment variables
+pub fn run_plugin(plugin: &Path, args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let mut cmd = Command::new(plugin);
+    cmd.args(args);
+    cmd.env("XGIT_VERSION", env!("CARGO_PKG_VERSION"));
+    if dry_run {
+        cmd.env("XGIT_DRY_RUN", "1");
+    }
+
+    // Plugins may run outside a repository, so context is best-effort
+    if let Ok(repo) = GitRepo::open(".") {
+        for (key, value) in plugin_env(&repo) {
+            cmd.env(key, value);
+        }
+    }
+
+    let status = cmd
+        .status()
+        .map_err(|e| anyhow::anyhow!("Error running plugin {}: {e}", plugin.display()))?;
+    if !status.success() {
+        return Err(GitxError::Subprocess {
+            program: plugin.display().to_string(),
+            code: status.code().unwrap_or(1),
+        });
+    }
+
+    Ok(())
+}
+
+fn plugin_env(repo: &GitRepo) -> Vec<(&'static str, String)> {
+    let mut vars = vec![
+        ("XGIT_REPO_PATH", repo.path().display().to_string()),
+        ("XGIT_GIT_DIR", repo.git_dir().display().to_string()),
+    ];
+
+    if let Ok(branch) = repo.get_current_branch() {
+        vars.push(("XGIT_CURRENT_BRANCH", branch));
+    }
+
+    let config: serde_json::Map<String, serde_json::Value> = repo
+        .get_config_entries("xgit.")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| (name, serde_json::Value::String(value)))
+        .collect();
+    vars.push(("XGIT_CONFIG", serde_json::Value::Object(config).to_string()));
+
+    vars
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{find_plugin_in, plugin_env};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn find_plugin_in_only_returns_executables() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let plugin_path = temp_dir.path().join("xg-hello");
+        std::fs::write(&plugin_path, "#!/bin/sh\necho hello\n").unwrap();
+
+        let path_var = temp_dir.path().as_os_str();
+        assert_eq!(find_plugin_in("hello", path_var), None);
+
+        std::fs::set_permissions(&plugin_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(find_plugin_in("hello", path_var), Some(plugin_path));
+        assert_eq!(find_plugin_in("missing", path_var), None);
+        assert_eq!(find_plugin_in("../hello", path_var), None);
+    }
+
+    #[test]
+    fn plugin_env_exposes_branch_and_config() {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+        repo.create_and_checkout_branch("feature").unwrap();
+        std::process::Command::new("git")
+            .args(["config", "xgit.theme", "dark"])
+            .current_dir(repo.path())
+            .output()
+            .unwrap();
+
+        let vars = plugin_env(&repo);
+        let get = |key: &str| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.clone())
+        };
+
+        assert_eq!(get("XGIT_CURRENT_BRANCH").as_deref(), Some("feature"));
+        let config: serde_json::Value = serde_json::from_str(&get("XGIT_CONFIG").unwrap()).unwrap();
+        assert_eq!(config["xgit.theme"], "dark");
+    }
+}
@@ -0,0 +1,652 @@
+use super::commit::{AI_DIFF_MAX_BYTES, AI_DIFF_MAX_FILE_BYTES};
+use crate::ai::{self, PrDescription};
+use crate::config::AiProvider;
+use crate::error::GitxError;
+use crate::forge;
+use crate::git::commits::diff_text::DiffTextOptions;
+use crate::git::merge::pull::PullStrategy;
+use crate::git::GitRepo;
+use crate::github::issues::{append_issue_link, issue_links};
+use crate::github::pr_service::GitHubPrService;
+use crate::github::types::{
+    ChecksState, MergeMethod, PrListFilter, PullRequestListing, PullRequestRecord,
+    PullRequestStatus,
+};
+use crate::github::GitHubPrMatcher;
+use crate::tui::emoji::icon;
+use crate::tui::{confirm, dry_run};
+use console::style;
+use inquire::{Editor, InquireError, Select, Text};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// How often `xg pr merge --wait` asks for the pull request's checks
+const CHECKS_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long `xg pr merge --wait` waits for pending checks before giving up
+const CHECKS_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Push the current branch and open a pull request into `base` (the repository's default
+/// branch unless given), with a title and description drafted by the AI from the branch's
+/// commits and diff, which the user edits before anything is sent
+pub async fn handle_pr_create(
+    base: Option<&str>,
+    draft: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let branch = repo.get_current_branch()?;
+    let remote = repo.forge_remote()?;
+    let github = GitHubPrService::with_forge(
+        repo.path(),
+        remote.repo.owner.clone(),
+        remote.repo.repo.clone(),
+        forge::authenticated_provider(&remote)?,
+    )?;
+
+    let base = match base {
+        Some(base) => base.to_string(),
+        None => github.resolve_trunk_base_branch(&repo).await?,
+    };
+    if branch == base {
+        return Err(GitxError::Usage(format!(
+            "'{branch}' is the base branch; switch to the branch you want to open a pull request for"
+        )));
+    }
+
+    // Compare against the remote's base when it has been fetched, as the forge will
+    let (base_ref, commits) = [format!("{}/{base}", remote.name), base.clone()]
+        .into_iter()
+        .find_map(|base_ref| {
+            let commits = repo.list_commits_between(&base_ref, "HEAD").ok()?;
+            Some((base_ref, commits))
+        })
+        .ok_or_else(|| GitxError::Usage(format!("Base branch '{base}' not found")))?;
+    if commits.is_empty() {
+        return Err(GitxError::Usage(format!(
+            "No commits ahead of {base_ref}; nothing to open a pull request for"
+        )));
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("push '{branch}' to '{}'", remote.name));
+        dry_run::print_operation(&format!(
+            "open a {}pull request from '{branch}' into '{base}' for {} commit{}",
+            if draft { "draft " } else { "" },
+            commits.len(),
+            if commits.len() == 1 { "" } else { "s" }
+        ));
+        return Ok(());
+    }
+
+    if let Some(existing) = github
+        .find_pr_by_head(&branch)
+        .await?
+        .filter(|pr| !pr.is_closed_or_merged())
+    {
+        println!(
+            "{} {} already has PR #{}: {}",
+            style("✓").green().bold(),
+            style(&branch).cyan(),
+            style(existing.pr_number).cyan(),
+            style(&existing.url).dim()
+        );
+        return Ok(());
+    }
+
+    let subjects = commits
+        .iter()
+        .map(|sha| repo.get_commit_subject(sha))
+        .collect::<Result<Vec<_>, _>>()?;
+    let diff_text = repo.diff_to_string_with(
+        &repo.get_range_diff(&base_ref, "HEAD")?,
+        &DiffTextOptions {
+            max_bytes: Some(AI_DIFF_MAX_BYTES),
+            max_file_bytes: Some(AI_DIFF_MAX_FILE_BYTES),
+            ..DiffTextOptions::default()
+        },
+    )?;
+    let mut proposed = match generate_description(&repo, &subjects, &diff_text)? {
+        Some(description) => description,
+        None => fallback_description(&branch, &subjects),
+    };
+    // Carry the commits' `Closes #N` and `Refs #N` lines over, so merging closes the issues
+    let messages = commits
+        .iter()
+        .map(|sha| repo.get_commit_message(sha))
+        .collect::<Result<Vec<_>, _>>()?;
+    for link in issue_links(&messages.join("\n")) {
+        proposed.body = append_issue_link(&proposed.body, link);
+    }
+    let description = review_description(proposed)?;
+
+    repo.push_async(&remote.name, &branch).await?;
+    if repo.get_upstream_remote_branch(&branch)?.is_none() {
+        repo.set_upstream(&branch, &remote.name, &branch)?;
+    }
+
+    let pr = github
+        .create_pr(
+            &description.title,
+            Some(&description.body),
+            &branch,
+            &base,
+            draft,
+        )
+        .await?;
+    github.attach_branch(pr.pr_number, &branch)?;
+    println!(
+        "{} Opened {}PR #{} into {}: {}",
+        style("✓").green().bold(),
+        if draft { "draft " } else { "" },
+        style(pr.pr_number).cyan(),
+        style(&base).cyan(),
+        style(&pr.url).dim()
+    );
+    Ok(())
+}
+
+/// List the open pull requests passing `filter`. In a terminal the list is a picker that checks
+/// out the chosen pull request's branch.
+pub async fn handle_pr_list(filter: &PrListFilter, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let remote = repo.forge_remote()?;
+    let github = GitHubPrService::new(repo.path(), &remote)?;
+    github.ensure_ready()?;
+    let listings = github.list_open_prs(filter).await?;
+
+    if listings.is_empty() || confirm::assume_yes() || !console::user_attended() {
+        write_pr_list(&mut io::stdout().lock(), &listings)?;
+        return Ok(());
+    }
+    let choices: Vec<String> = listings
+        .iter()
+        .map(|listing| {
+            let checks = listing.record.checks.map_or("·", checks_icon);
+            format!(
+                "#{} {checks} {}",
+                listing.record.pr_number,
+                describe(listing)
+            )
+        })
+        .collect();
+    let choice = match Select::new("Check out a pull request:", choices)
+        .with_page_size(15)
+        .with_help_message("enter to check out, esc to leave")
+        .raw_prompt()
+    {
+        Ok(choice) => choice,
+        Err(InquireError::OperationCanceled) => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    check_out_pull_request(&repo, &remote.name, &listings[choice.index], dry_run).await
+}
+
+/// Fetch the pull request's head branch from `remote` and switch to it, creating a local
+/// branch tracking it when there is none
+async fn check_out_pull_request(
+    repo: &GitRepo,
+    remote: &str,
+    listing: &PullRequestListing,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let pr = &listing.record;
+    if listing.is_cross_repository {
+        return Err(GitxError::Usage(format!(
+            "PR #{} comes from a fork; check it out with `gh pr checkout {}`",
+            pr.pr_number, pr.pr_number
+        )));
+    }
+
+    let branch = &pr.head_ref;
+    if !dry_run {
+        repo.fetch_async(remote, Some(branch)).await?;
+    }
+    let start_point = if repo.get_all_branches()?.contains(branch) {
+        None
+    } else {
+        Some(format!("{remote}/{branch}"))
+    };
+    super::switch::handle_switch(
+        branch,
+        start_point.as_deref(),
+        start_point.is_some(),
+        false,
+        false,
+        dry_run,
+    )
+}
+
+/// Merge the current branch's pull request with `strategy` (`merge`, `squash` or `rebase`),
+/// after its pending checks pass when `wait`. Unless `keep_branch`, then switch to the base
+/// branch, fast-forward it and delete the merged branch locally and on the remote.
+pub async fn handle_pr_merge(
+    strategy: &str,
+    wait: bool,
+    keep_branch: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let method = MergeMethod::parse(strategy).ok_or_else(|| {
+        GitxError::Usage(format!(
+            "Unknown merge strategy '{strategy}'; use merge, squash or rebase"
+        ))
+    })?;
+    let repo = GitRepo::open(".")?;
+    let remote = repo.forge_remote()?;
+    let matcher =
+        GitHubPrMatcher::with_forge(&repo, forge::authenticated_provider(&remote)?)?.refreshing();
+    let (branch, pr) = pull_request_to_merge(&repo, &matcher).await?;
+    let base = pr.base_ref.clone();
+
+    if dry_run {
+        dry_run::print_header();
+        if wait {
+            dry_run::print_operation(&format!(
+                "wait for the checks on PR #{} to pass",
+                pr.pr_number
+            ));
+        }
+        dry_run::print_operation(&format!(
+            "merge PR #{} into '{base}' ({})",
+            pr.pr_number,
+            method.as_str()
+        ));
+        if !keep_branch {
+            dry_run::print_operation(&format!(
+                "switch to '{base}' and fast-forward it from '{}'",
+                remote.name
+            ));
+            dry_run::print_operation(&format!(
+                "delete '{branch}' locally and on '{}'",
+                remote.name
+            ));
+        }
+        return Ok(());
+    }
+
+    // Check before merging, so a dirty worktree cannot strand the cleanup halfway
+    if !keep_branch && !repo.is_working_tree_clean()? {
+        return Err(GitxError::DirtyWorktree(format!(
+            "Commit or stash your changes before merging; '{branch}' is deleted afterwards"
+        )));
+    }
+    confirm::confirm_or_abort(
+        &format!(
+            "Merge PR #{} \"{}\" into '{base}' ({})?",
+            pr.pr_number,
+            pr.title,
+            method.as_str()
+        ),
+        "Merge aborted",
+    )?;
+    if wait {
+        wait_for_checks(matcher.service(), &pr).await?;
+    }
+
+    let merged = matcher
+        .service()
+        .merge_pr(pr.pr_number, method, &pr.head_sha)
+        .await?;
+    println!(
+        "{} Merged PR #{} into {}: {}",
+        style("✓").green().bold(),
+        style(merged.pr_number).cyan(),
+        style(&base).cyan(),
+        style(&merged.url).dim()
+    );
+    if keep_branch {
+        return Ok(());
+    }
+    clean_up_merged_branch(&repo, &remote.name, &branch, &pr).await
+}
+
+/// The current branch and its open pull request, which must point at the branch's local head
+/// so nothing unpushed is left out of the merge
+async fn pull_request_to_merge(
+    repo: &GitRepo,
+    matcher: &GitHubPrMatcher,
+) -> Result<(String, PullRequestRecord), GitxError> {
+    let branch = repo.get_current_branch()?;
+    let pr = matcher
+        .refresh_pr_for_branch(repo, &branch)
+        .await?
+        .map(|resolved| resolved.record)
+        .filter(|pr| pr.status == PullRequestStatus::Open)
+        .ok_or_else(|| {
+            GitxError::Usage(format!(
+                "'{branch}' has no open pull request; open one with `xg pr create`"
+            ))
+        })?;
+    if pr.draft {
+        return Err(GitxError::Usage(format!(
+            "PR #{} is a draft; mark it ready for review before merging",
+            pr.pr_number
+        )));
+    }
+
+    let head = repo
+        .list_commits_range("HEAD", 0, Some(1))?
+        .into_iter()
+        .next()
+        .map(|commit| commit.hash)
+        .unwrap_or_default();
+    // Bitbucket abbreviates head commits
+    if pr.head_sha.is_empty() || !head.starts_with(&pr.head_sha) {
+        return Err(GitxError::Usage(format!(
+            "'{branch}' and PR #{} point at different commits; push or pull before merging",
+            pr.pr_number
+        )));
+    }
+    Ok((branch, pr))
+}
+
+/// Poll the checks on the pull request's head until none are pending, failing when any fail
+/// or they are still running after [`CHECKS_TIMEOUT`]
+async fn wait_for_checks(
+    github: &GitHubPrService,
+    pr: &PullRequestRecord,
+) -> Result<(), GitxError> {
+    let started = Instant::now();
+    let mut announced = false;
+    loop {
+        match github.load_checks(pr).await?.checks {
+            Some(ChecksState::Passing) | None => return Ok(()),
+            Some(ChecksState::Failing) => {
+                return Err(GitxError::Other(anyhow::anyhow!(
+                    "Checks failed on PR #{}; not merging",
+                    pr.pr_number
+                )))
+            }
+            Some(ChecksState::Pending) => {}
+        }
+        if started.elapsed() >= CHECKS_TIMEOUT {
+            return Err(GitxError::Other(anyhow::anyhow!(
+                "Checks on PR #{} were still running after {} minutes; not merging",
+                pr.pr_number,
+                CHECKS_TIMEOUT.as_secs() / 60
+            )));
+        }
+        if !announced {
+            announced = true;
+            eprintln!(
+                "{} Waiting for the checks on PR #{}...",
+                style(icon("🚦")).yellow(),
+                style(pr.pr_number).cyan()
+            );
+        }
+        tokio::time::sleep(CHECKS_POLL_INTERVAL).await;
+    }
+}
+
+/// Switch from the merged `branch` to the pull request's base, fast-forward the base from
+/// `remote` and delete `branch` locally and the head branch on `remote`. GitHub may already
+/// have deleted the head branch, so failing to delete it is only a warning.
+async fn clean_up_merged_branch(
+    repo: &GitRepo,
+    remote: &str,
+    branch: &str,
+    pr: &PullRequestRecord,
+) -> Result<(), GitxError> {
+    let base = &pr.base_ref;
+    if repo.get_all_branches()?.contains(base) {
+        super::switch::handle_switch(base, None, false, false, false, false)?;
+        if let Err(err) = repo
+            .pull_with_async(remote, Some(base), PullStrategy::FfOnly)
+            .await
+        {
+            eprintln!(
+                "{} Could not fast-forward {}: {err:#}",
+                style("⚠").yellow(),
+                style(base).cyan()
+            );
+        }
+    } else {
+        repo.fetch_async(remote, Some(base)).await?;
+        let start_point = format!("{remote}/{base}");
+        super::switch::handle_switch(base, Some(&start_point), true, false, false, false)?;
+    }
+
+    // A squash or rebase merge leaves the branch looking unmerged, so force the delete
+    repo.delete_branch_forced(branch)?;
+    println!(
+        "{} Deleted branch {}",
+        style(icon("🗑")).red(),
+        style(branch).cyan()
+    );
+    match repo.delete_remote_branch(remote, &pr.head_ref) {
+        Ok(()) => println!(
+            "{} Deleted {}",
+            style(icon("🗑")).red(),
+            style(format!("{remote}/{}", pr.head_ref)).cyan()
+        ),
+        Err(err) => eprintln!(
+            "{} Could not delete {}: {err:#}",
+            style("⚠").yellow(),
+            style(format!("{remote}/{}", pr.head_ref)).cyan()
+        ),
+    }
+    Ok(())
+}
+
+/// `✓`, `✗` or `●` for passing, failing or pending checks
+fn checks_icon(checks: ChecksState) -> &'static str {
+    match checks {
+        ChecksState::Passing => "✓",
+        ChecksState::Failing => "✗",
+        ChecksState::Pending => "●",
+    }
+}
+
+/// Title, author, draft marker and labels
+fn describe(listing: &PullRequestListing) -> String {
+    let mut description = format!("{} @{}", listing.record.title, listing.author);
+    if listing.record.draft {
+        description.push_str(" [draft]");
+    }
+    if !listing.labels.is_empty() {
+        description.push_str(&format!(" ({})", listing.labels.join(", ")));
+    }
+    description
+}
+
+fn write_pr_list(out: &mut impl Write, listings: &[PullRequestListing]) -> io::Result<()> {
+    if listings.is_empty() {
+        return writeln!(
+            out,
+            "{} No open pull requests",
+            style(icon("✨")).green().bold()
+        );
+    }
+
+    let width = listings
+        .iter()
+        .map(|listing| listing.record.pr_number.to_string().len() + 1)
+        .max()
+        .unwrap_or_default();
+    for listing in listings {
+        let checks = match listing.record.checks {
+            Some(ChecksState::Passing) => style(checks_icon(ChecksState::Passing)).green(),
+            Some(ChecksState::Failing) => style(checks_icon(ChecksState::Failing)).red(),
+            Some(ChecksState::Pending) => style(checks_icon(ChecksState::Pending)).yellow(),
+            None => style("·").dim(),
+        };
+        writeln!(
+            out,
+            "{} {checks} {}",
+            style(format!(
+                "{:>width$}",
+                format!("#{}", listing.record.pr_number)
+            ))
+            .cyan(),
+            describe(listing)
+        )?;
+    }
+    Ok(())
+}
+
+/// Ask the AI for a title and description, or `None` when it gives none or the AI provider is
+/// turned off
+fn generate_description(
+    repo: &GitRepo,
+    subjects: &[String],
+    diff_text: &str,
+) -> Result<Option<PrDescription>, GitxError> {
+    if repo.xgit_config()?.ai_provider() == AiProvider::Disabled {
+        return Ok(None);
+    }
+
+    eprintln!(
+        "{} Drafting pull request description",
+        style(icon("🤖")).cyan().bold()
+    );
+    Ok(ai::generate_pr_description(subjects, diff_text)?)
+}
+
+/// Title and description without the AI: a lone commit's subject, or the branch name over a
+/// list of the commits
+pub(super) fn fallback_description(branch: &str, subjects: &[String]) -> PrDescription {
+    let title = match subjects {
+        [only] => only.clone(),
+        _ => branch.to_string(),
+    };
+    let changes: String = subjects
+        .iter()
+        .map(|subject| format!("- {subject}\n"))
+        .collect();
+    PrDescription {
+        title,
+        body: format!("## Changes\n\n{changes}"),
+    }
+}
+
+/// Let the user edit the title inline and the description in their editor. `--yes` accepts
+/// the proposal as it is.
+fn review_description(proposed: PrDescription) -> Result<PrDescription, GitxError> {
+    if confirm::assume_yes() {
+        eprintln!(
+            "{} Using the suggested title and description {}",
+            style("✓").green().bold(),
+            style("(--yes)").dim()
+        );
+        return Ok(proposed);
+    }
+    if !console::user_attended() {
+        return Err(GitxError::Usage(
+            "No terminal to review the pull request; pass --yes to accept the suggestion"
+                .to_string(),
+        ));
+    }
+
+    println!("{}", style(&proposed.body).dim());
+    let title = Text::new("Pull request title:")
+        .with_initial_value(&proposed.title)
+        .with_help_message("enter to continue, esc to cancel")
+        .prompt()?;
+    if title.trim().is_empty() {
+        return Err(GitxError::Usage(
+            "Aborting pull request due to empty title".to_string(),
+        ));
+    }
+    let body = Editor::new("Pull request description:")
+        .with_predefined_text(&proposed.body)
+        .with_file_extension(".md")
+        .with_help_message("e to edit in your editor, enter to open the pull request")
+        .prompt()?;
+    Ok(PrDescription {
+        title: title.trim().to_string(),
+        body: body.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fallback_description, pull_request_to_merge, write_pr_list};
+    use crate::error::GitxError;
+    use crate::github::types::{ChecksState, MergeMethod, PullRequestListing, PullRequestStatus};
+    use crate::github::GitHubPrMatcher;
+    use crate::test_utils::{create_test_repo, MockForge, RepoTestOperations};
+    use crate::tui::snapshot::{normalize, render};
+
+    #[test]
+    fn pr_list_shows_checks_authors_drafts_and_labels() {
+        let listing = |number, checks, draft, labels: &[&str]| {
+            let mut record = MockForge::pull_request(number, "feature", PullRequestStatus::Open);
+            record.title = format!("Change {number}");
+            record.checks = checks;
+            record.draft = draft;
+            PullRequestListing {
+                record,
+                author: "octocat".to_string(),
+                labels: labels.iter().map(|label| label.to_string()).collect(),
+                requested_reviewers: Vec::new(),
+                is_cross_repository: false,
+            }
+        };
+        let listings = [
+            listing(104, Some(ChecksState::Passing), false, &["bug", "ui"]),
+            listing(98, Some(ChecksState::Failing), true, &[]),
+            listing(7, None, false, &[]),
+        ];
+
+        let output = render(|out| write_pr_list(out, &listings));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+
+    #[tokio::test]
+    async fn merge_needs_an_open_pr_at_the_local_head() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature", "Add feature")?;
+        repo.add_remote("origin", "https://github.com/octo/repo.git")?;
+        let head = repo.list_commits_range("HEAD", 0, Some(1))?[0].hash.clone();
+
+        // The PR has not seen the local commit yet
+        let forge = MockForge::new().with_pr(MockForge::pull_request(
+            12,
+            "feature",
+            PullRequestStatus::Open,
+        ));
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(forge))?.refreshing();
+        assert!(matches!(
+            pull_request_to_merge(&repo, &matcher).await,
+            Err(GitxError::Usage(_))
+        ));
+
+        let mut pr = MockForge::pull_request(12, "feature", PullRequestStatus::Open);
+        pr.head_sha = head.clone();
+        let forge = MockForge::new().with_pr(pr);
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(forge.clone()))?.refreshing();
+        let (branch, pr) = pull_request_to_merge(&repo, &matcher).await?;
+        assert_eq!(branch, "feature");
+
+        let merged = matcher
+            .service()
+            .merge_pr(pr.pr_number, MergeMethod::Squash, &pr.head_sha)
+            .await?;
+        assert!(merged.is_merged());
+        assert!(forge
+            .calls()
+            .contains(&format!("merge_pr 12 squash {head}")));
+        assert!(matches!(
+            pull_request_to_merge(&repo, &matcher).await,
+            Err(GitxError::Usage(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_titles_single_commits_by_their_subject() {
+        let one = fallback_description("feature/login", &["Add login form".to_string()]);
+        assert_eq!(one.title, "Add login form");
+        assert_eq!(one.body, "## Changes\n\n- Add login form\n");
+
+        let two = fallback_description(
+            "feature/login",
+            &["Add login form".to_string(), "Validate email".to_string()],
+        );
+        assert_eq!(two.title, "feature/login");
+        assert!(two.body.ends_with("- Add login form\n- Validate email\n"));
+    }
+}
@@ -0,0 +1,170 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::merge::operations::MergeOutcome;
+use crate::git::merge::pull::PullStrategy;
+use crate::git::{GitError, GitRepo};
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use console::style;
+
+/// Fetch a remote branch and merge, rebase or fast-forward the current branch onto it. Without
+/// a strategy flag the one `pull.rebase` and `pull.ff` ask for is used.
+///
+/// `args` names the remote and branch; any other argument hands the pull to `git pull`.
+/// Conflicts exit with the conflict code and leave the merge or rebase in progress.
+pub async fn handle_pull(
+    args: &[String],
+    rebase: bool,
+    no_rebase: bool,
+    ff_only: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    if args.len() > 2 || args.iter().any(|arg| arg.starts_with('-')) {
+        let flags = [
+            ("--rebase", rebase),
+            ("--no-rebase", no_rebase),
+            ("--ff-only", ff_only),
+        ];
+        let git_args: Vec<String> = flags
+            .into_iter()
+            .filter(|(_, set)| *set)
+            .map(|(flag, _)| flag.to_string())
+            .chain(args.iter().cloned())
+            .collect();
+        return git_passthrough("pull", &git_args, dry_run);
+    }
+    let remote = args.first().map(String::as_str);
+    let branch = args.get(1).map(String::as_str);
+
+    let repo = GitRepo::open(".")?;
+    let current = repo.get_current_branch()?;
+    let (remote, branch) = pull_target(&repo, &current, remote, branch)?;
+    let strategy = if rebase {
+        PullStrategy::Rebase
+    } else if no_rebase {
+        PullStrategy::Merge
+    } else if ff_only {
+        PullStrategy::FfOnly
+    } else {
+        repo.pull_strategy()?
+    };
+
+    if dry_run {
+        dry_run::print_header();
+        let upstream = format!("'{remote}/{branch}'");
+        dry_run::print_operation(&match strategy {
+            PullStrategy::Merge => format!("fetch {upstream} and merge it into '{current}'"),
+            PullStrategy::Rebase => format!("fetch {upstream} and rebase '{current}' onto it"),
+            PullStrategy::FfOnly => format!("fetch {upstream} and fast-forward '{current}' to it"),
+        });
+        return Ok(());
+    }
+
+    let upstream = format!("{remote}/{branch}");
+    match repo
+        .pull_with_async(&remote, Some(&branch), strategy)
+        .await
+    {
+        Ok(MergeOutcome::UpToDate) => {
+            println!(
+                "{} Already up to date with {}",
+                style(icon("✨")).green().bold(),
+                style(&upstream).cyan()
+            );
+            Ok(())
+        }
+        Ok(MergeOutcome::FastForward(id)) => {
+            println!(
+                "{} Fast-forwarded {} to {}",
+                style("✓").green().bold(),
+                style(&current).cyan(),
+                style(&id[..7]).yellow()
+            );
+            Ok(())
+        }
+        Ok(MergeOutcome::MergeCommit(id)) => {
+            println!(
+                "{} Merged {} into {} as {}",
+                style("✓").green().bold(),
+                style(&upstream).cyan(),
+                style(&current).cyan(),
+                style(&id[..7]).yellow()
+            );
+            Ok(())
+        }
+        Ok(MergeOutcome::Rebased {
+            applied, skipped, ..
+        }) => {
+            println!(
+                "{} Rebased {} onto {} ({applied} commit{} replayed{})",
+                style("✓").green().bold(),
+                style(&current).cyan(),
+                style(&upstream).cyan(),
+                if applied == 1 { "" } else { "s" },
+                if skipped > 0 {
+                    format!(", {skipped} already upstream")
+                } else {
+                    String::new()
+                }
+            );
+            Ok(())
+        }
+        Ok(MergeOutcome::Conflicts(paths)) => {
+            let files: String = paths.iter().map(|path| format!("\n    {path}")).collect();
+            Err(GitxError::Conflict(anyhow::anyhow!(
+                "Merging {upstream} conflicts in:{files}\n  Resolve the conflicts and stage them with `xg add`, then run `git commit --no-edit`; or `git merge --abort` to stop"
+            )))
+        }
+        Err(GitError::Conflict(err)) => Err(GitxError::Conflict(anyhow::anyhow!(
+            "{err:#}\n  Resolve the conflicts and stage them with `xg add`, then run `xg rebase --continue`; or `xg rebase --skip` to drop this commit, `xg rebase --abort` to stop"
+        ))),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The remote and remote branch to pull into `current`. Unnamed ones come from its upstream,
+/// falling back to the default remote (normally `origin`) and a branch of the same name.
+fn pull_target(
+    repo: &GitRepo,
+    current: &str,
+    remote: Option<&str>,
+    branch: Option<&str>,
+) -> Result<(String, String), GitxError> {
+    let upstream = repo.get_upstream_remote_branch(current)?;
+    let remote = match (remote, &upstream) {
+        (Some(remote), _) => remote.to_string(),
+        (None, Some((upstream_remote, _))) => upstream_remote.clone(),
+        (None, None) => repo.xgit_config()?.default_remote().to_string(),
+    };
+    let branch = match (branch, upstream) {
+        (Some(branch), _) => branch.to_string(),
+        (None, Some((upstream_remote, upstream_branch))) if upstream_remote == remote => {
+            upstream_branch
+        }
+        _ => current.to_string(),
+    };
+    Ok((remote, branch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pull_target;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn pulls_from_the_upstream_unless_told_otherwise() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?;
+        let target = |remote, branch| pull_target(&repo, "feature", remote, branch).unwrap();
+        let pair = |remote: &str, branch: &str| (remote.to_string(), branch.to_string());
+
+        assert_eq!(target(None, None), pair("origin", "feature"));
+
+        repo.set_upstream("feature", "upstream", "main")?;
+        assert_eq!(target(None, None), pair("upstream", "main"));
+        assert_eq!(target(None, Some("dev")), pair("upstream", "dev"));
+        assert_eq!(target(Some("fork"), None), pair("fork", "feature"));
+        Ok(())
+    }
+}
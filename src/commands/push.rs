@@ -0,0 +1,131 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::GitRepo;
+
+/// Flags that choose what to push or how to track it, so xg leaves the upstream alone
+const EXPLICIT_FLAGS: &[&str] = &[
+    "-u",
+    "--set-upstream",
+    "--all",
+    "--branches",
+    "--mirror",
+    "--tags",
+    "-d",
+    "--delete",
+    "--prune",
+];
+
+/// Flags followed by a separate value, which would otherwise look like a remote or refspec
+const VALUE_FLAGS: &[&str] = &["-o", "--push-option", "--repo", "--receive-pack", "--exec"];
+
+/// `git push`, adding `--set-upstream` when it pushes the current branch and the branch has
+/// no upstream yet (unless `xgit.push.setUpstream` is false)
+pub fn handle_push(args: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let set_upstream = repo
+        .get_config_bool("xgit.push.setUpstream")?
+        .unwrap_or(true);
+    let target = if set_upstream {
+        upstream_target(&repo, args)?
+    } else {
+        None
+    };
+
+    match target {
+        Some((remote, branch)) => {
+            let mut push_args: Vec<String> = args
+                .iter()
+                .filter(|arg| arg.starts_with('-'))
+                .cloned()
+                .collect();
+            push_args.extend(["--set-upstream".to_string(), remote, branch]);
+            git_passthrough("push", &push_args, dry_run)
+        }
+        None => git_passthrough("push", args, dry_run),
+    }
+}
+
+/// The remote and branch to push with `--set-upstream`: the current branch, when `args` name
+/// at most a remote and the branch has no upstream
+fn upstream_target(repo: &GitRepo, args: &[String]) -> Result<Option<(String, String)>, GitxError> {
+    if args
+        .iter()
+        .any(|arg| EXPLICIT_FLAGS.contains(&arg.as_str()) || VALUE_FLAGS.contains(&arg.as_str()))
+    {
+        return Ok(None);
+    }
+    let positionals: Vec<&String> = args.iter().filter(|arg| !arg.starts_with('-')).collect();
+    if positionals.len() > 1 {
+        return Ok(None);
+    }
+    let Ok(branch) = repo.get_current_branch() else {
+        return Ok(None);
+    };
+    if repo.get_upstream_remote_branch(&branch)?.is_some() {
+        return Ok(None);
+    }
+
+    let remote = match positionals.first() {
+        Some(remote) => Some(remote.to_string()),
+        None => default_push_remote(repo, &branch)?,
+    };
+    Ok(remote.map(|remote| (remote, branch)))
+}
+
+/// Where git pushes `branch` by default: `branch.<name>.pushRemote`, then `remote.pushDefault`,
+/// then the configured default remote (normally `origin`) or the only remote
+fn default_push_remote(repo: &GitRepo, branch: &str) -> Result<Option<String>, GitxError> {
+    for key in [
+        format!("branch.{branch}.pushRemote"),
+        "remote.pushDefault".to_string(),
+    ] {
+        if let Some(remote) = repo.get_config_string(&key)? {
+            return Ok(Some(remote));
+        }
+    }
+    let default_remote = repo.xgit_config()?.default_remote().to_string();
+    let remotes = repo.get_remote_names()?;
+    if remotes.contains(&default_remote) {
+        return Ok(Some(default_remote));
+    }
+    Ok(match remotes.as_slice() {
+        [only] => Some(only.clone()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::upstream_target;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn plain_push_of_a_new_branch_sets_its_upstream() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?;
+        repo.add_remote("upstream", "https://example.com/repo.git")?;
+        let target = |values: &[&str]| upstream_target(&repo, &args(values)).unwrap();
+
+        let expected = Some(("upstream".to_string(), "feature".to_string()));
+        assert_eq!(target(&[]), expected);
+        assert_eq!(target(&["--force-with-lease"]), expected);
+        assert_eq!(
+            target(&["fork"]),
+            Some(("fork".to_string(), "feature".to_string()))
+        );
+
+        // Explicit refspecs and tracking choices are left to git
+        assert_eq!(target(&["upstream", "main"]), None);
+        assert_eq!(target(&["-u"]), None);
+        assert_eq!(target(&["--tags"]), None);
+
+        repo.set_upstream("feature", "upstream", "feature")?;
+        assert_eq!(target(&[]), None);
+        Ok(())
+    }
+}
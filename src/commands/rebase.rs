@@ -0,0 +1,184 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::rebase::interactive::TodoAction;
+use crate::git::rebase::operations::RebaseOutcome;
+use crate::git::{GitError, GitRepo};
+use crate::tui::emoji::icon;
+use crate::tui::{dry_run, rebase_editor};
+use console::style;
+
+/// Start, continue, skip or abort a rebase of the current branch with libgit2.
+///
+/// `args` is the branch to rebase onto, the current branch's upstream when empty; anything
+/// else is handed to `git rebase`. A rebase that stops on a conflict exits with the conflict
+/// code and leaves the rebase in progress for `--continue`, `--skip` or `--abort`, like
+/// `git rebase` does.
+pub fn handle_rebase(
+    args: &[String],
+    interactive: bool,
+    continue_: bool,
+    skip: bool,
+    abort: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let pass_to_git = || {
+        let flags = [
+            ("-i", interactive),
+            ("--continue", continue_),
+            ("--skip", skip),
+            ("--abort", abort),
+        ];
+        let git_args: Vec<String> = flags
+            .into_iter()
+            .filter(|(_, set)| *set)
+            .map(|(flag, _)| flag.to_string())
+            .chain(args.iter().cloned())
+            .collect();
+        git_passthrough("rebase", &git_args, dry_run)
+    };
+    let action = continue_ || skip || abort;
+    let onto = match args {
+        [] if action => String::new(),
+        [] => match upstream(&repo)? {
+            Some(upstream) => upstream,
+            None => return pass_to_git(),
+        },
+        [onto] if !action && !onto.starts_with('-') => onto.clone(),
+        _ => return pass_to_git(),
+    };
+    let onto = onto.as_str();
+    if interactive {
+        return interactive_rebase(&repo, onto, dry_run);
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        let operation = if continue_ {
+            "commit the resolved conflicts and replay the remaining commits".to_string()
+        } else if skip {
+            "drop the stopped commit and replay the remaining commits".to_string()
+        } else if abort {
+            "abort the rebase and restore the branch".to_string()
+        } else {
+            let branch = repo.get_current_branch()?;
+            let (ahead, _) = repo.ahead_behind(&branch, onto)?;
+            format!(
+                "rebase {ahead} commit{} of '{branch}' onto '{onto}'",
+                if ahead == 1 { "" } else { "s" }
+            )
+        };
+        dry_run::print_operation(&operation);
+        return Ok(());
+    }
+
+    if abort {
+        repo.rebase_abort()?;
+        println!("{} Aborted the rebase", style("✓").green().bold());
+        return Ok(());
+    }
+
+    let result = if continue_ {
+        repo.rebase_continue()
+    } else if skip {
+        repo.rebase_skip()
+    } else {
+        repo.rebase(onto)
+    };
+
+    match result {
+        Ok(RebaseOutcome::UpToDate) => {
+            println!(
+                "{} Already up to date with {}",
+                style(icon("✨")).green().bold(),
+                style(onto).cyan()
+            );
+            Ok(())
+        }
+        Ok(RebaseOutcome::Completed {
+            branch,
+            applied,
+            skipped,
+        }) => {
+            println!(
+                "{} Rebased {} ({applied} commit{} replayed{})",
+                style("✓").green().bold(),
+                style(&branch).cyan(),
+                if applied == 1 { "" } else { "s" },
+                if skipped > 0 {
+                    format!(", {skipped} already upstream or skipped")
+                } else {
+                    String::new()
+                }
+            );
+            Ok(())
+        }
+        Err(GitError::Conflict(err)) => Err(GitxError::Conflict(anyhow::anyhow!(
+            "{err:#}\n  Resolve the conflicts and stage them with `xg add`, then run `xg rebase --continue`; or `xg rebase --skip` to drop this commit, `xg rebase --abort` to stop"
+        ))),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The remote-tracking branch the current branch follows, e.g. `origin/main`
+fn upstream(repo: &GitRepo) -> Result<Option<String>, GitxError> {
+    let branch = repo.get_current_branch()?;
+    Ok(repo
+        .get_upstream_remote_branch(&branch)?
+        .map(|(remote, branch)| format!("{remote}/{branch}")))
+}
+
+/// Edit the commits after `base` in the rebase editor, then rewrite the branch to match
+fn interactive_rebase(repo: &GitRepo, base: &str, dry_run: bool) -> Result<(), GitxError> {
+    let todo = repo.rebase_todo(base)?;
+    if todo.is_empty() {
+        println!(
+            "{} No commits after {} to rebase",
+            style(icon("✨")).green().bold(),
+            style(base).cyan()
+        );
+        return Ok(());
+    }
+    if !console::user_attended() {
+        return Err(GitxError::Usage(
+            "`xg rebase -i` needs a terminal to edit the plan in".to_string(),
+        ));
+    }
+
+    let title = format!(
+        "Rebase {} commit{} onto {base}",
+        todo.len(),
+        if todo.len() == 1 { "" } else { "s" }
+    );
+    let Some(plan) = rebase_editor::edit_plan(&title, todo)? else {
+        println!("{} Rebase cancelled", style("ℹ").blue().bold());
+        return Ok(());
+    };
+
+    if dry_run {
+        dry_run::print_header();
+        for item in &plan {
+            let summary = match &item.action {
+                TodoAction::Reword(message) => message.as_str(),
+                _ => item.summary.as_str(),
+            };
+            dry_run::print_operation(&format!(
+                "{} {} {summary}",
+                item.action.name(),
+                &item.id[..7]
+            ));
+        }
+        return Ok(());
+    }
+
+    let outcome = repo.rebase_interactive(base, &plan)?;
+    println!(
+        "{} Rewrote {}: {} commit{} on top of {}",
+        style("✓").green().bold(),
+        style(&outcome.branch).cyan(),
+        outcome.commits,
+        if outcome.commits == 1 { "" } else { "s" },
+        style(base).cyan()
+    );
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use crate::error::GitxError;
+use crate::git::repository::core::RemoteInfo;
+use crate::git::GitRepo;
+use crate::tui::{confirm::confirm_or_abort, dry_run};
+use console::style;
+use std::io::{self, Write};
+
+/// List remotes with their URL and how many remote-tracking branches each has
+pub fn handle_remote_list() -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let remotes = repo.get_remotes()?;
+    if remotes.is_empty() {
+        println!(
+            "{} No remotes yet; add one with `xg remote add <name> <url>`",
+            style("ℹ").blue().bold()
+        );
+        return Ok(());
+    }
+
+    let branches = repo.get_remote_branches()?;
+    let counts: Vec<usize> = remotes
+        .iter()
+        .map(|remote| {
+            let prefix = format!("{}/", remote.name);
+            branches
+                .iter()
+                .filter(|branch| branch.starts_with(&prefix))
+                .count()
+        })
+        .collect();
+    write_remote_table(&mut io::stdout().lock(), &remotes, &counts)?;
+    Ok(())
+}
+
+fn write_remote_table(
+    out: &mut impl Write,
+    remotes: &[RemoteInfo],
+    branch_counts: &[usize],
+) -> io::Result<()> {
+    let name_width = remotes
+        .iter()
+        .map(|remote| remote.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let url_width = remotes
+        .iter()
+        .map(|remote| remote.url.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (remote, count) in remotes.iter().zip(branch_counts) {
+        writeln!(
+            out,
+            "{}  {:<url_width$}  {}",
+            style(format!("{:<name_width$}", remote.name)).cyan().bold(),
+            remote.url,
+            style(format!(
+                "{count} branch{}",
+                if *count == 1 { "" } else { "es" }
+            ))
+            .dim()
+        )?;
+    }
+    Ok(())
+}
+
+pub fn handle_remote_add(name: &str, url: &str, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    if repo.get_remote_names()?.iter().any(|remote| remote == name) {
+        return Err(GitxError::Usage(format!("Remote '{name}' already exists")));
+    }
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("add remote '{name}' for {url}"));
+        return Ok(());
+    }
+
+    repo.add_remote(name, url)?;
+    println!(
+        "{} Added remote {} {}",
+        style("✓").green().bold(),
+        style(name).cyan().bold(),
+        style(url).dim()
+    );
+    Ok(())
+}
+
+/// Remove a remote and its remote-tracking branches after confirming
+pub fn handle_remote_remove(name: &str, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    require_remote(&repo, name)?;
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "remove remote '{name}' and its remote-tracking branches"
+        ));
+        return Ok(());
+    }
+    confirm_or_abort(
+        &format!("Remove remote {name} and its remote-tracking branches?"),
+        "Nothing removed",
+    )?;
+
+    repo.remove_remote(name)?;
+    println!(
+        "{} Removed remote {}",
+        style("✓").green().bold(),
+        style(name).cyan().bold()
+    );
+    Ok(())
+}
+
+/// Rename a remote, warning about fetch refspecs git could not migrate
+pub fn handle_remote_rename(
+    old_name: &str,
+    new_name: &str,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    require_remote(&repo, old_name)?;
+    if repo
+        .get_remote_names()?
+        .iter()
+        .any(|remote| remote == new_name)
+    {
+        return Err(GitxError::Usage(format!(
+            "Remote '{new_name}' already exists"
+        )));
+    }
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("rename remote '{old_name}' to '{new_name}'"));
+        return Ok(());
+    }
+
+    let unmigrated = repo.rename_remote(old_name, new_name)?;
+    println!(
+        "{} Renamed remote {} to {}",
+        style("✓").green().bold(),
+        style(old_name).cyan(),
+        style(new_name).cyan().bold()
+    );
+    for refspec in unmigrated {
+        eprintln!(
+            "{} Fetch refspec {} was left unchanged; update it with `git config remote.{new_name}.fetch`",
+            style("⚠").yellow(),
+            style(refspec).yellow()
+        );
+    }
+    Ok(())
+}
+
+fn require_remote(repo: &GitRepo, name: &str) -> Result<(), GitxError> {
+    if repo.get_remote_names()?.iter().any(|remote| remote == name) {
+        Ok(())
+    } else {
+        Err(GitxError::Usage(format!("Remote '{name}' not found")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_remote_table;
+    use crate::git::repository::core::RemoteInfo;
+    use crate::tui::snapshot::{normalize, render};
+
+    #[test]
+    fn remote_table_aligns_names_urls_and_branch_counts() {
+        let remotes = [
+            RemoteInfo {
+                name: "origin".to_string(),
+                url: "https://github.com/octo/repo.git".to_string(),
+            },
+            RemoteInfo {
+                name: "me".to_string(),
+                url: "git@github.com:me/repo.git".to_string(),
+            },
+        ];
+
+        let output = render(|out| write_remote_table(out, &remotes, &[12, 1]));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
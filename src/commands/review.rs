@@ -0,0 +1,169 @@
+use super::commit::{AI_DIFF_MAX_BYTES, AI_DIFF_MAX_FILE_BYTES};
+use crate::ai::{self, ReviewFinding, Severity};
+use crate::config::AiProvider;
+use crate::error::GitxError;
+use crate::git::commits::diff_text::DiffTextOptions;
+use crate::git::GitRepo;
+use crate::tui::emoji::icon;
+use console::style;
+use std::io::{self, Write};
+
+/// Have the AI review the staged changes, or with `branch` everything the current branch
+/// changed since it left that base (the trunk branch when empty), and print its findings of
+/// at least `min_severity` by file and line, or as JSON
+pub fn handle_review(
+    branch: Option<&str>,
+    min_severity: &str,
+    json: bool,
+) -> Result<(), GitxError> {
+    let min_severity = Severity::parse(min_severity).ok_or_else(|| {
+        GitxError::Usage(format!(
+            "Unknown severity '{min_severity}'; use low, medium or high"
+        ))
+    })?;
+    let repo = GitRepo::open(".")?;
+    if repo.xgit_config()?.ai_provider() == AiProvider::Disabled {
+        return Err(GitxError::Usage(
+            "The AI provider is turned off; set ai.provider to review changes".to_string(),
+        ));
+    }
+
+    let (diff, reviewing) = match branch {
+        Some(base) => {
+            let base = match base {
+                "" => repo.trunk_branch()?,
+                base => base.to_string(),
+            };
+            let diff = repo.get_range_diff(&base, "HEAD")?;
+            (diff, format!("changes since {base}"))
+        }
+        None => (repo.get_staged_diff()?, "staged changes".to_string()),
+    };
+    let diff_text = repo.diff_to_string_with(
+        &diff,
+        &DiffTextOptions {
+            max_bytes: Some(AI_DIFF_MAX_BYTES),
+            max_file_bytes: Some(AI_DIFF_MAX_FILE_BYTES),
+            ..DiffTextOptions::default()
+        },
+    )?;
+    if diff_text.is_empty() {
+        return Err(GitxError::Usage(format!("No {reviewing} to review")));
+    }
+
+    if !json {
+        eprintln!("{} Reviewing {reviewing}", style(icon("🤖")).cyan().bold());
+    }
+    let findings = ai::review_diff(&diff_text)?.ok_or_else(|| {
+        GitxError::Other(anyhow::anyhow!(
+            "The AI gave no review; check that the claude CLI is installed and signed in"
+        ))
+    })?;
+    let findings = filter_findings(findings, min_severity);
+
+    if json {
+        let output = serde_json::to_string_pretty(&findings).map_err(anyhow::Error::from)?;
+        println!("{output}");
+        return Ok(());
+    }
+    write_findings(&mut io::stdout().lock(), &findings)?;
+    Ok(())
+}
+
+/// Findings of at least `min_severity`, by file, then line, most severe first on a line
+fn filter_findings(findings: Vec<ReviewFinding>, min_severity: Severity) -> Vec<ReviewFinding> {
+    let mut findings: Vec<ReviewFinding> = findings
+        .into_iter()
+        .filter(|finding| finding.severity >= min_severity)
+        .collect();
+    findings.sort_by(|a, b| (&a.file, a.line, b.severity).cmp(&(&b.file, b.line, a.severity)));
+    findings
+}
+
+fn write_findings(out: &mut impl Write, findings: &[ReviewFinding]) -> io::Result<()> {
+    if findings.is_empty() {
+        return writeln!(out, "{} No findings", style(icon("✨")).green().bold());
+    }
+
+    let mut file = None;
+    for finding in findings {
+        if file != Some(&finding.file) {
+            if file.is_some() {
+                writeln!(out)?;
+            }
+            writeln!(out, "{}", style(&finding.file).cyan().bold())?;
+            file = Some(&finding.file);
+        }
+        let severity = format!("{:<6}", finding.severity.as_str());
+        let severity = match finding.severity {
+            Severity::High => style(severity).red().bold(),
+            Severity::Medium => style(severity).yellow(),
+            Severity::Low => style(severity).dim(),
+        };
+        let line = finding
+            .line
+            .map(|line| format!("L{line}"))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "  {severity} {:<13} {:<6} {}",
+            finding.category.as_str(),
+            style(line).dim(),
+            finding.message
+        )?;
+    }
+
+    let count = |severity| {
+        findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    };
+    writeln!(
+        out,
+        "\n{} finding{}: {} high, {} medium, {} low",
+        findings.len(),
+        if findings.len() == 1 { "" } else { "s" },
+        count(Severity::High),
+        count(Severity::Medium),
+        count(Severity::Low)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_findings, write_findings};
+    use crate::ai::{FindingCategory, ReviewFinding, Severity};
+    use crate::tui::snapshot::{normalize, render};
+
+    fn finding(file: &str, line: Option<u32>, severity: Severity, message: &str) -> ReviewFinding {
+        ReviewFinding {
+            file: file.to_string(),
+            line,
+            severity,
+            category: FindingCategory::Bug,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn findings_are_filtered_sorted_and_grouped_by_file() {
+        let findings = filter_findings(
+            vec![
+                finding("src/b.rs", Some(3), Severity::Medium, "Unchecked index"),
+                finding("src/a.rs", Some(9), Severity::Low, "Long line"),
+                finding("src/a.rs", Some(2), Severity::Medium, "Ignored error"),
+                finding("src/a.rs", Some(2), Severity::High, "Panics on empty input"),
+            ],
+            Severity::Medium,
+        );
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["Panics on empty input", "Ignored error", "Unchecked index"]
+        );
+
+        let output = render(|out| write_findings(out, &findings));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
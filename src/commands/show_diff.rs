@@ -0,0 +1,43 @@
+use crate::error::GitxError;
+use crate::tui::emoji::icon;
+use crate::{
+    git::GitRepo,
+    tui::diff_display::{self, DiffDisplayOptions},
+};
+use console::style;
+
+/// Show staged changes (or the changes of a commit) with syntax highlighting
+pub fn handle_show_diff(
+    rev: Option<&str>,
+    side_by_side: bool,
+    no_word_diff: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+
+    let diff = match rev {
+        Some(rev) => repo.get_commit_diff(rev)?,
+        None => repo.get_staged_diff()?,
+    };
+
+    if diff.deltas().len() == 0 {
+        println!(
+            "{} {}",
+            style(icon("✨")).green().bold(),
+            if rev.is_some() {
+                "Commit has no changes"
+            } else {
+                "No staged changes to show"
+            }
+        );
+        return Ok(());
+    }
+
+    let options = DiffDisplayOptions {
+        side_by_side,
+        word_diff: !no_word_diff,
+        highlight: console::colors_enabled(),
+    };
+    diff_display::display_diff(&diff, &options)?;
+
+    Ok(())
+}
@@ -0,0 +1,494 @@
+use super::pr::fallback_description;
+use crate::error::GitxError;
+use crate::forge;
+use crate::git::branches::stack::{Stack, StackBranch};
+use crate::git::rebase::operations::RebaseOutcome;
+use crate::git::{GitError, GitRepo};
+use crate::github::pr_service::GitHubPrService;
+use crate::github::types::PullRequestRecord;
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use console::style;
+use inquire::{InquireError, Select};
+
+/// Create `name` on top of the current branch and switch to it
+pub fn handle_stack_create(name: &str, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let parent = repo.get_current_branch()?;
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "create '{name}' on top of '{parent}' and switch to it"
+        ));
+        return Ok(());
+    }
+
+    repo.create_stacked_branch(name)?;
+    println!(
+        "{} Created {} on top of {}",
+        style("✓").green().bold(),
+        style(name).cyan(),
+        style(&parent).cyan()
+    );
+    Ok(())
+}
+
+/// Print every stack as a tree, with each branch's pull request and whether it needs
+/// restacking
+pub fn handle_stack_list() -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let stacks = repo.stacks()?;
+    if stacks.is_empty() {
+        println!(
+            "{} No stacks yet; start one with `xg stack create <name>`",
+            style("ℹ").blue().bold()
+        );
+        return Ok(());
+    }
+
+    let current = repo.get_current_branch().ok();
+    for stack in &stacks {
+        print_stack(&repo, stack, current.as_deref());
+    }
+    Ok(())
+}
+
+/// Switch to the branch `steps` levels up the current stack, asking which one when a branch
+/// has several children
+pub fn handle_up(steps: usize, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let (current, stack) = current_stack(&repo)?;
+    let mut target = current.clone();
+    for _ in 0..steps.max(1) {
+        let children = stack.children(&target);
+        target = match children.as_slice() {
+            [] if target == current => {
+                return Err(GitxError::Usage(format!(
+                    "'{current}' is at the top of its stack"
+                )))
+            }
+            [] => break,
+            [only] => only.name.clone(),
+            _ => match pick_child(&target, &children)? {
+                Some(child) => child,
+                None => return Ok(()),
+            },
+        };
+    }
+    move_to(&repo, &stack, &target, dry_run)
+}
+
+/// Switch to the branch `steps` levels down the current stack, stopping at its base
+pub fn handle_down(steps: usize, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let (current, stack) = current_stack(&repo)?;
+    if current == stack.base {
+        return Err(GitxError::Usage(format!(
+            "'{current}' is the base of its stack"
+        )));
+    }
+    let mut target = current;
+    for _ in 0..steps.max(1) {
+        match stack.get(&target) {
+            Some(branch) => target = branch.parent.clone(),
+            None => break,
+        }
+    }
+    move_to(&repo, &stack, &target, dry_run)
+}
+
+/// Which of `parent`'s children to move up to; `None` when the user backs out
+fn pick_child(parent: &str, children: &[&StackBranch]) -> Result<Option<String>, GitxError> {
+    let names: Vec<String> = children.iter().map(|child| child.name.clone()).collect();
+    if !console::user_attended() {
+        return Err(GitxError::Usage(format!(
+            "'{parent}' has several branches on top: {}; switch to one with `xg switch`",
+            names.join(", ")
+        )));
+    }
+    match Select::new(&format!("Move up from {parent} to:"), names).prompt() {
+        Ok(child) => Ok(Some(child)),
+        Err(InquireError::OperationCanceled) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Switch to `branch`, then show where it sits in `stack`
+fn move_to(repo: &GitRepo, stack: &Stack, branch: &str, dry_run: bool) -> Result<(), GitxError> {
+    super::switch::handle_switch(branch, None, false, false, false, dry_run)?;
+    if dry_run {
+        return Ok(());
+    }
+
+    println!();
+    print_stack(repo, stack, Some(branch));
+    if let Some(stale) = stack.get(branch).filter(|b| b.needs_restack) {
+        println!(
+            "{} {} is not based on the latest {}; run `xg stack restack`",
+            style("⚠").yellow().bold(),
+            style(&stale.name).cyan(),
+            style(&stale.parent).cyan()
+        );
+    }
+    Ok(())
+}
+
+/// `stack` as a tree under its base, with each branch's pull request and whether it needs
+/// restacking, marking `current`
+fn print_stack(repo: &GitRepo, stack: &Stack, current: Option<&str>) {
+    // PR numbers come from the local PR index only, so this works offline
+    let github = repo
+        .forge_remote()
+        .ok()
+        .and_then(|remote| GitHubPrService::new(repo.path(), &remote).ok());
+    let cached_pr = |branch: &str| {
+        github
+            .as_ref()
+            .and_then(|github| github.get_cached_by_branch(branch).ok().flatten())
+            .filter(|pr| !pr.is_closed_or_merged())
+    };
+    let is_current = |branch: &str| current == Some(branch);
+
+    println!("{}", branch_label(&stack.base, 0, is_current(&stack.base)));
+    for branch in &stack.branches {
+        let mut line = branch_label(&branch.name, branch.depth, is_current(&branch.name));
+        if let Some(pr) = cached_pr(&branch.name) {
+            line.push_str(&format!(" {}", style(format!("#{}", pr.pr_number)).dim()));
+        }
+        if branch.needs_restack {
+            line.push_str(&format!(" {}", style("needs restack").yellow()));
+        }
+        println!("{line}");
+    }
+}
+
+/// Rebase every branch of the current branch's stack onto its parent, parents first, then
+/// switch back to the branch restacking started on
+pub fn handle_stack_restack(dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let (current, stack) = current_stack(&repo)?;
+
+    if dry_run {
+        dry_run::print_header();
+        let stale: Vec<_> = stack.branches.iter().filter(|b| b.needs_restack).collect();
+        if stale.is_empty() {
+            dry_run::print_operation("nothing to restack");
+        }
+        for branch in stale {
+            dry_run::print_operation(&format!(
+                "rebase '{}' onto '{}'",
+                branch.name, branch.parent
+            ));
+        }
+        return Ok(());
+    }
+
+    let mut restacked = 0;
+    for branch in &stack.branches {
+        match repo.restack_branch(&branch.name) {
+            Ok(RebaseOutcome::UpToDate) => {}
+            Ok(RebaseOutcome::Completed { applied, .. }) => {
+                restacked += 1;
+                println!(
+                    "{} Restacked {} onto {} ({applied} commit{} replayed)",
+                    style("✓").green().bold(),
+                    style(&branch.name).cyan(),
+                    style(repo.stack_parent(&branch.name)?.unwrap_or_default()).cyan(),
+                    if applied == 1 { "" } else { "s" }
+                );
+            }
+            Err(GitError::Conflict(err)) => {
+                return Err(GitxError::Conflict(anyhow::anyhow!(
+                    "{err:#}\n  Resolve the conflicts and stage them with `xg add`, then run `xg rebase --continue` and `xg stack restack` again; or `xg rebase --abort` to stop"
+                )));
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    repo.checkout_branch(&current)?;
+
+    if restacked == 0 {
+        println!(
+            "{} Every branch is up to date with its parent",
+            style(icon("✨")).green().bold()
+        );
+    }
+    Ok(())
+}
+
+/// Push every branch of the current branch's stack and open or update one pull request per
+/// branch, based on its parent branch, with links to the whole stack in each description
+pub async fn handle_stack_submit(draft: bool, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let (_, stack) = current_stack(&repo)?;
+    if let Some(stale) = stack.branches.iter().find(|b| b.needs_restack) {
+        return Err(GitxError::Usage(format!(
+            "'{}' is not based on the latest '{}'; run `xg stack restack` first",
+            stale.name, stale.parent
+        )));
+    }
+    let remote = repo.forge_remote()?;
+
+    if dry_run {
+        dry_run::print_header();
+        for branch in &stack.branches {
+            dry_run::print_operation(&format!(
+                "push '{}' to '{}' and open or update its pull request into '{}'",
+                branch.name, remote.name, branch.parent
+            ));
+        }
+        return Ok(());
+    }
+
+    let github = GitHubPrService::with_forge(
+        repo.path(),
+        remote.repo.owner.clone(),
+        remote.repo.repo.clone(),
+        forge::authenticated_provider(&remote)?,
+    )?;
+    submit_stack(&repo, &github, &remote.name, &stack, draft).await
+}
+
+/// Push each branch of `stack` to `remote`, open or retarget its pull request, then bring the
+/// stack section of every description up to date
+async fn submit_stack(
+    repo: &GitRepo,
+    github: &GitHubPrService,
+    remote: &str,
+    stack: &Stack,
+    draft: bool,
+) -> Result<(), GitxError> {
+    let mut submitted: Vec<(&StackBranch, PullRequestRecord)> = Vec::new();
+    for branch in &stack.branches {
+        let commits = repo.list_commits_between(&branch.parent, &branch.name)?;
+        let Some(tip) = commits.last() else {
+            eprintln!(
+                "{} Skipping {}: no commits ahead of {}",
+                style("⚠").yellow().bold(),
+                style(&branch.name).cyan(),
+                style(&branch.parent).cyan()
+            );
+            continue;
+        };
+
+        // Restacking rewrites branches, so the remote ones are replaced rather than merged
+        repo.force_push_commit_to_branch_async(remote, tip, &branch.name)
+            .await?;
+        if repo.get_upstream_remote_branch(&branch.name)?.is_none() {
+            repo.set_upstream(&branch.name, remote, &branch.name)?;
+        }
+
+        let subjects = commits
+            .iter()
+            .map(|sha| repo.get_commit_subject(sha))
+            .collect::<Result<Vec<_>, _>>()?;
+        let existing = github
+            .find_pr_by_head(&branch.name)
+            .await?
+            .filter(|pr| !pr.is_closed_or_merged());
+        let pr = match existing {
+            Some(pr) if pr.base_ref == branch.parent => pr,
+            Some(pr) => {
+                github
+                    .update_pr(pr.pr_number, Some(&branch.parent), None, None)
+                    .await?
+            }
+            None => {
+                let description = fallback_description(&branch.name, &subjects);
+                let pr = github
+                    .create_pr(
+                        &description.title,
+                        Some(&description.body),
+                        &branch.name,
+                        &branch.parent,
+                        draft,
+                    )
+                    .await?;
+                github.attach_branch(pr.pr_number, &branch.name)?;
+                pr
+            }
+        };
+        submitted.push((branch, pr));
+    }
+
+    // Every PR is known now, so each description can link to all of them
+    let links: Vec<(u64, String)> = submitted
+        .iter()
+        .map(|(_, pr)| (pr.pr_number, pr.url.clone()))
+        .collect();
+    for (branch, pr) in &submitted {
+        let body = github.get_pr_body(pr.pr_number).await?;
+        let updated =
+            replace_stack_section(&body, &stack_section(&stack.base, &links, pr.pr_number));
+        if updated != body {
+            github
+                .update_pr(pr.pr_number, None, None, Some(&updated))
+                .await?;
+        }
+        println!(
+            "{} {} → {}: PR #{} {}",
+            style("✓").green().bold(),
+            style(&branch.name).cyan(),
+            style(&branch.parent).cyan(),
+            style(pr.pr_number).cyan(),
+            style(&pr.url).dim()
+        );
+    }
+    Ok(())
+}
+
+/// The current branch and the stack it belongs to
+fn current_stack(repo: &GitRepo) -> Result<(String, Stack), GitxError> {
+    let current = repo.get_current_branch()?;
+    let stack = repo.stack_containing(&current)?.ok_or_else(|| {
+        GitxError::Usage(format!(
+            "'{current}' is not part of a stack; start one with `xg stack create <name>`"
+        ))
+    })?;
+    Ok((current, stack))
+}
+
+/// `branch` indented by its depth in the stack, marked and highlighted when checked out
+fn branch_label(branch: &str, depth: usize, current: bool) -> String {
+    let indent = "  ".repeat(depth);
+    if current {
+        format!(
+            "{} {indent}{}",
+            style("*").green().bold(),
+            style(branch).green().bold()
+        )
+    } else {
+        format!("  {indent}{branch}")
+    }
+}
+
+/// Marks the start of the section `xg stack submit` maintains in each pull request's description
+const STACK_SECTION_START: &str = "<!-- xg stack -->";
+/// Marks the end of that section
+const STACK_SECTION_END: &str = "<!-- /xg stack -->";
+
+/// The "Stack" section closing each pull request's description: the stack's PRs from the
+/// bottom up, pointing out the one it belongs to, between markers so later submits can find it
+fn stack_section(base: &str, prs: &[(u64, String)], this_pr: u64) -> String {
+    let mut section =
+        format!("{STACK_SECTION_START}\n## Stack\n\nOn `{base}`, from the bottom up:\n\n");
+    for (number, url) in prs {
+        if *number == this_pr {
+            section.push_str(&format!("- **#{number}** (this pull request)\n"));
+        } else {
+            section.push_str(&format!("- [#{number}]({url})\n"));
+        }
+    }
+    section.push_str(STACK_SECTION_END);
+    section
+}
+
+/// `body` with its stack section replaced by `section`, or `section` appended when it has none;
+/// everything else the author wrote is kept
+fn replace_stack_section(body: &str, section: &str) -> String {
+    let existing = body.find(STACK_SECTION_START).and_then(|start| {
+        let end = body[start..].find(STACK_SECTION_END)? + start + STACK_SECTION_END.len();
+        Some((start, end))
+    });
+    match existing {
+        Some((start, end)) => format!("{}{section}{}", &body[..start], &body[end..]),
+        None if body.trim().is_empty() => section.to_string(),
+        None => format!("{}\n\n{section}", body.trim_end()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_stack_section, stack_section, submit_stack};
+    use crate::github::pr_service::GitHubPrService;
+    use crate::github::types::PullRequestStatus;
+    use crate::test_utils::{
+        create_test_bare_repo, create_test_repo, MockForge, RepoTestOperations,
+    };
+
+    #[test]
+    fn stack_section_links_the_other_pull_requests() {
+        let prs = [
+            (4, "https://github.com/octo/repo/pull/4".to_string()),
+            (5, "https://github.com/octo/repo/pull/5".to_string()),
+        ];
+        assert_eq!(
+            stack_section("main", &prs, 5),
+            "<!-- xg stack -->\n## Stack\n\nOn `main`, from the bottom up:\n\n\
+             - [#4](https://github.com/octo/repo/pull/4)\n\
+             - **#5** (this pull request)\n<!-- /xg stack -->"
+        );
+    }
+
+    #[test]
+    fn replace_stack_section_keeps_the_rest_of_the_description() {
+        let old = stack_section("main", &[(4, "u4".to_string())], 4);
+        let new = stack_section("main", &[(4, "u4".to_string()), (5, "u5".to_string())], 4);
+
+        let appended = replace_stack_section("Adds the API.\n", &old);
+        assert_eq!(appended, format!("Adds the API.\n\n{old}"));
+        assert_eq!(replace_stack_section("", &old), old);
+
+        let edited = format!("Adds the API, reviewed.\n\n{old}\n\nThanks!");
+        assert_eq!(
+            replace_stack_section(&edited, &new),
+            format!("Adds the API, reviewed.\n\n{new}\n\nThanks!")
+        );
+    }
+
+    #[tokio::test]
+    async fn submit_opens_prs_on_parents_and_keeps_edited_descriptions(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_remote_dir, remote) = create_test_bare_repo();
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?;
+        repo.add_remote("origin", &remote.path().to_string_lossy())?;
+        repo.push("origin", "master")?;
+        repo.create_stacked_branch("api")?;
+        repo.add_file_and_commit("api.txt", "api\n", "Add api")?;
+        repo.create_stacked_branch("ui")?;
+        repo.add_file_and_commit("ui.txt", "ui\n", "Add ui")?;
+
+        // api already has a PR whose description the author wrote
+        let mut api_pr = MockForge::pull_request(7, "api", PullRequestStatus::Open);
+        api_pr.base_ref = "master".to_string();
+        let forge = MockForge::new()
+            .with_pr(api_pr)
+            .with_pr_body(7, "Adds the API.");
+        let github = GitHubPrService::with_forge(
+            repo.path(),
+            "octo".to_string(),
+            "repo".to_string(),
+            Box::new(forge.clone()),
+        )?;
+        let stack = repo.stack_containing("ui")?.unwrap();
+        submit_stack(&repo, &github, "origin", &stack, false).await?;
+
+        let prs = forge.pull_requests();
+        let ui_pr = prs.iter().find(|pr| pr.head_ref == "ui").unwrap();
+        assert_eq!(ui_pr.base_ref, "api");
+        assert!(remote.get_all_branches()?.contains(&"ui".to_string()));
+
+        let links = [
+            (7, "https://github.com/octo/repo/pull/7".to_string()),
+            (8, "https://github.com/octo/repo/pull/8".to_string()),
+        ];
+        assert_eq!(
+            forge.pr_body(7),
+            format!("Adds the API.\n\n{}", stack_section("master", &links, 7))
+        );
+        assert!(forge.pr_body(8).starts_with("## Changes\n\n- Add ui\n"));
+        assert!(forge
+            .pr_body(8)
+            .ends_with(&stack_section("master", &links, 8)));
+
+        // Submitting again only touches the stack section
+        let calls = forge.calls().len();
+        submit_stack(&repo, &github, "origin", &stack, false).await?;
+        assert!(!forge.calls()[calls..]
+            .iter()
+            .any(|call| call.starts_with("update_pr")));
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::commands::history::format_age;
+use crate::error::GitxError;
+use crate::git::stash::operations::StashEntry;
+use crate::git::{GitError, GitRepo};
+use crate::github::types::now_timestamp;
+use crate::tui::{confirm::confirm_or_abort, dry_run};
+use console::style;
+
+/// Stash uncommitted changes, optionally including untracked files
+pub fn handle_stash_save(
+    message: Option<&str>,
+    include_untracked: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "stash uncommitted changes{}",
+            if include_untracked {
+                " and untracked files"
+            } else {
+                ""
+            }
+        ));
+        return Ok(());
+    }
+
+    match repo.stash_save(message, include_untracked)? {
+        Some(_) => {
+            let entry = repo.stash_list()?.into_iter().next();
+            println!(
+                "{} Stashed changes as {}{}",
+                style("✓").green().bold(),
+                style("stash@{0}").cyan(),
+                entry
+                    .map(|entry| format!(": {}", entry.message))
+                    .unwrap_or_default()
+            );
+        }
+        None => println!("{} No local changes to stash", style("ℹ").blue().bold()),
+    }
+    Ok(())
+}
+
+/// List stash entries, newest first, with their age
+pub fn handle_stash_list() -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let entries = repo.stash_list()?;
+    if entries.is_empty() {
+        println!("{} The stash is empty", style("ℹ").blue().bold());
+        return Ok(());
+    }
+
+    let now = now_timestamp();
+    for entry in &entries {
+        display_entry(entry, now);
+    }
+    Ok(())
+}
+
+fn display_entry(entry: &StashEntry, now: u64) {
+    let age = now.saturating_sub(u64::try_from(entry.time).unwrap_or_default());
+    println!(
+        "{} {} {} {}",
+        style(format!("stash@{{{}}}", entry.index)).cyan().bold(),
+        style(&entry.id[..7]).yellow(),
+        entry.message,
+        style(format_age(age)).dim()
+    );
+}
+
+/// Re-apply a stash entry; `pop` also drops it once it applied cleanly
+pub fn handle_stash_apply(index: usize, pop: bool, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let name = format!("stash@{{{index}}}");
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!(
+            "apply {name}{}",
+            if pop { " and drop it" } else { "" }
+        ));
+        return Ok(());
+    }
+
+    let result = if pop {
+        repo.stash_pop(index)
+    } else {
+        repo.stash_apply(index)
+    };
+    match result {
+        Ok(()) => {
+            println!(
+                "{} {} {}",
+                style("✓").green().bold(),
+                if pop { "Popped" } else { "Applied" },
+                style(&name).cyan()
+            );
+            Ok(())
+        }
+        Err(GitError::Conflict(err)) => Err(GitxError::Conflict(anyhow::anyhow!(
+            "{err:#}\n  The entry is kept in the stash; drop it with `xg stash drop {index}` once resolved"
+        ))),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Delete a stash entry after confirming
+pub fn handle_stash_drop(index: usize, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let name = format!("stash@{{{index}}}");
+    let entry = repo
+        .stash_list()?
+        .into_iter()
+        .find(|entry| entry.index == index)
+        .ok_or_else(|| GitxError::Usage(format!("No stash entry {name}")))?;
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("drop {name}: {}", entry.message));
+        return Ok(());
+    }
+
+    confirm_or_abort(
+        &format!("Drop {name} ({})?", entry.message),
+        "Nothing dropped",
+    )?;
+    repo.stash_drop(index)?;
+    println!(
+        "{} Dropped {} ({})",
+        style("✓").green().bold(),
+        style(&name).cyan(),
+        style(&entry.id[..7]).yellow()
+    );
+    Ok(())
+}
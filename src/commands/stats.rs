@@ -0,0 +1,545 @@
+use crate::error::GitxError;
+use crate::git::commits::history::{CommitActivity, RepoCommit};
+use crate::github::{types::PullRequestRecord, GitHubPrMatcher};
+use crate::tui::emoji::icon;
+use crate::{git::GitRepo, tui::stats_display};
+use console::style;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const DAILY_CHART_DAYS: usize = 28;
+
+/// Aggregated activity for one author, computed purely from local history
+#[derive(Debug, PartialEq)]
+struct AuthorStats {
+    total_commits: usize,
+    files_touched: usize,
+    average_commit_size: f64,
+    current_streak: usize,
+    longest_streak: usize,
+    /// Commits per day, oldest first, ending today
+    daily: Vec<usize>,
+    /// Commits per 7-day window, oldest first, ending today
+    weekly: Vec<usize>,
+}
+
+/// Repository-wide statistics; also the `--json` output
+#[derive(Debug, PartialEq, Serialize)]
+struct RepoStats {
+    total_commits: usize,
+    /// Contributors with the most commits, most active first
+    contributors: Vec<Contributor>,
+    /// Commits per 7-day window, oldest first, ending today
+    weekly_commits: Vec<usize>,
+    /// Files changed by the most commits
+    hotspots: Vec<Hotspot>,
+    /// Mean time from opening to merging, over cached PRs that have both timestamps
+    average_pr_merge_secs: Option<u64>,
+    merged_prs_measured: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Contributor {
+    name: String,
+    email: String,
+    commits: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Hotspot {
+    path: String,
+    commits: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+/// Show local commit statistics for the configured author (or `--author`)
+pub fn handle_stats_me(author: Option<&str>, weeks: usize) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+
+    let email = match author {
+        Some(email) => email.to_string(),
+        None => repo.get_config_string("user.email")?.ok_or_else(|| {
+            GitxError::Usage(
+                "No user.email configured. Set it with `git config user.email` or pass --author"
+                    .to_string(),
+            )
+        })?,
+    };
+
+    let activity = repo.get_author_activity(&email)?;
+    if activity.is_empty() {
+        println!(
+            "{} No commits found for {}",
+            style("ℹ").blue().bold(),
+            style(&email).cyan()
+        );
+        return Ok(());
+    }
+
+    // Use the author's most recent timezone so "today" lines up with their commit days
+    let offset_minutes = activity[0].offset_minutes;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let stats = compute_author_stats(&activity, local_day(now, offset_minutes), weeks.max(1));
+
+    println!(
+        "{} Activity for {}",
+        style(icon("📈")).cyan().bold(),
+        style(&email).cyan().bold()
+    );
+    println!();
+    stats_display::display_chart(
+        &format!("Commits per day (last {DAILY_CHART_DAYS} days)"),
+        &stats.daily,
+    );
+    println!();
+    stats_display::display_chart(
+        &format!("Commits per week (last {} weeks)", stats.weekly.len()),
+        &stats.weekly,
+    );
+    println!();
+    stats_display::display_metric("Total commits", stats.total_commits);
+    stats_display::display_metric("Files touched", stats.files_touched);
+    stats_display::display_metric(
+        "Average commit size",
+        format!("{:.1} lines", stats.average_commit_size),
+    );
+    stats_display::display_metric("Current streak", format_days(stats.current_streak));
+    stats_display::display_metric("Longest streak", format_days(stats.longest_streak));
+
+    Ok(())
+}
+
+/// Show contributor, frequency, churn and PR merge-time statistics for the repository.
+///
+/// PR merge times come from the local PR index, so they only cover PRs xgit has already
+/// looked up and never trigger network calls.
+pub fn handle_stats_repo(weeks: usize, top: usize, json: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let commits = repo.get_repo_activity()?;
+    let pull_requests = GitHubPrMatcher::new(&repo)
+        .and_then(|matcher| matcher.service().cached_pull_requests())
+        .unwrap_or_default();
+
+    let offset_minutes = commits.first().map_or(0, |commit| commit.offset_minutes);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let stats = compute_repo_stats(
+        &commits,
+        &pull_requests,
+        local_day(now, offset_minutes),
+        weeks.max(1),
+        top,
+    );
+
+    if json {
+        let output = serde_json::to_string_pretty(&stats).map_err(anyhow::Error::from)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if commits.is_empty() {
+        println!("{} No commits found", style("ℹ").blue().bold());
+        return Ok(());
+    }
+
+    println!("{} Repository activity", style(icon("📈")).cyan().bold());
+    println!();
+    stats_display::display_chart(
+        &format!(
+            "Commits per week (last {} weeks)",
+            stats.weekly_commits.len()
+        ),
+        &stats.weekly_commits,
+    );
+    println!();
+    stats_display::display_ranking(
+        "Top contributors",
+        &stats
+            .contributors
+            .iter()
+            .map(|contributor| {
+                (
+                    format!("{} <{}>", contributor.name, contributor.email),
+                    format!(
+                        "{} commits, +{} -{}",
+                        contributor.commits, contributor.insertions, contributor.deletions
+                    ),
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    println!();
+    stats_display::display_ranking(
+        "Churn hotspots",
+        &stats
+            .hotspots
+            .iter()
+            .map(|hotspot| {
+                (
+                    hotspot.path.clone(),
+                    format!(
+                        "{} commits, +{} -{}",
+                        hotspot.commits, hotspot.insertions, hotspot.deletions
+                    ),
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    println!();
+    stats_display::display_metric("Total commits", stats.total_commits);
+    match stats.average_pr_merge_secs {
+        Some(seconds) => stats_display::display_metric(
+            "Average PR merge time",
+            format!(
+                "{} ({} merged PRs)",
+                format_duration(seconds),
+                stats.merged_prs_measured
+            ),
+        ),
+        None => stats_display::display_metric("Average PR merge time", "no merged PRs cached"),
+    }
+
+    Ok(())
+}
+
+fn compute_repo_stats(
+    commits: &[RepoCommit],
+    pull_requests: &[PullRequestRecord],
+    today: i64,
+    weeks: usize,
+    top: usize,
+) -> RepoStats {
+    let mut contributors: Vec<Contributor> = Vec::new();
+    let mut contributor_index: HashMap<String, usize> = HashMap::new();
+    let mut hotspots: Vec<Hotspot> = Vec::new();
+    let mut hotspot_index: HashMap<&str, usize> = HashMap::new();
+
+    // Commits arrive newest first, so each contributor keeps their most recent name
+    for commit in commits {
+        let insertions: usize = commit.files.iter().map(|file| file.insertions).sum();
+        let deletions: usize = commit.files.iter().map(|file| file.deletions).sum();
+        let index = *contributor_index
+            .entry(commit.author_email.to_lowercase())
+            .or_insert_with(|| {
+                contributors.push(Contributor {
+                    name: commit.author_name.clone(),
+                    email: commit.author_email.clone(),
+                    commits: 0,
+                    insertions: 0,
+                    deletions: 0,
+                });
+                contributors.len() - 1
+            });
+        let contributor = &mut contributors[index];
+        contributor.commits += 1;
+        contributor.insertions += insertions;
+        contributor.deletions += deletions;
+
+        for file in &commit.files {
+            let index = *hotspot_index.entry(&file.path).or_insert_with(|| {
+                hotspots.push(Hotspot {
+                    path: file.path.clone(),
+                    commits: 0,
+                    insertions: 0,
+                    deletions: 0,
+                });
+                hotspots.len() - 1
+            });
+            let hotspot = &mut hotspots[index];
+            hotspot.commits += 1;
+            hotspot.insertions += file.insertions;
+            hotspot.deletions += file.deletions;
+        }
+    }
+
+    contributors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+    contributors.truncate(top);
+    hotspots.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| (b.insertions + b.deletions).cmp(&(a.insertions + a.deletions)))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    hotspots.truncate(top);
+
+    let weekly_commits = (0..weeks as i64)
+        .rev()
+        .map(|weeks_ago| {
+            let newest = today - weeks_ago * 7;
+            commits
+                .iter()
+                .filter(|commit| {
+                    let day = local_day(commit.time, commit.offset_minutes);
+                    day <= newest && day > newest - 7
+                })
+                .count()
+        })
+        .collect();
+
+    let merge_times: Vec<u64> = pull_requests
+        .iter()
+        .filter(|pr| pr.is_merged())
+        .filter_map(|pr| pr.merged_at?.checked_sub(pr.created_at?))
+        .collect();
+    let average_pr_merge_secs = if merge_times.is_empty() {
+        None
+    } else {
+        Some(merge_times.iter().sum::<u64>() / merge_times.len() as u64)
+    };
+
+    RepoStats {
+        total_commits: commits.len(),
+        contributors,
+        weekly_commits,
+        hotspots,
+        average_pr_merge_secs,
+        merged_prs_measured: merge_times.len(),
+    }
+}
+
+fn compute_author_stats(activity: &[CommitActivity], today: i64, weeks: usize) -> AuthorStats {
+    let days: HashSet<i64> = activity
+        .iter()
+        .map(|commit| local_day(commit.time, commit.offset_minutes))
+        .collect();
+
+    let commits_on = |day: i64| {
+        activity
+            .iter()
+            .filter(|commit| local_day(commit.time, commit.offset_minutes) == day)
+            .count()
+    };
+    let daily = (0..DAILY_CHART_DAYS as i64)
+        .rev()
+        .map(|days_ago| commits_on(today - days_ago))
+        .collect();
+    let weekly = (0..weeks as i64)
+        .rev()
+        .map(|weeks_ago| {
+            (0..7)
+                .map(|day| commits_on(today - weeks_ago * 7 - day))
+                .sum()
+        })
+        .collect();
+
+    let files_touched = activity
+        .iter()
+        .flat_map(|commit| commit.files.iter())
+        .collect::<HashSet<_>>()
+        .len();
+    let total_lines: usize = activity
+        .iter()
+        .map(|commit| commit.insertions + commit.deletions)
+        .sum();
+
+    // A streak is still "current" if the last commit was yesterday and today is not over yet
+    let streak_end = if days.contains(&today) {
+        today
+    } else {
+        today - 1
+    };
+    let current_streak = (0..)
+        .take_while(|offset| days.contains(&(streak_end - offset)))
+        .count();
+
+    let mut sorted_days: Vec<i64> = days.iter().copied().collect();
+    sorted_days.sort_unstable();
+    let mut longest_streak = 0;
+    let mut run = 0;
+    for (index, day) in sorted_days.iter().enumerate() {
+        run = if index > 0 && sorted_days[index - 1] == day - 1 {
+            run + 1
+        } else {
+            1
+        };
+        longest_streak = longest_streak.max(run);
+    }
+
+    AuthorStats {
+        total_commits: activity.len(),
+        files_touched,
+        average_commit_size: total_lines as f64 / activity.len().max(1) as f64,
+        current_streak,
+        longest_streak,
+        daily,
+        weekly,
+    }
+}
+
+/// Day number since the Unix epoch in the given timezone
+fn local_day(time: i64, offset_minutes: i32) -> i64 {
+    (time + i64::from(offset_minutes) * 60).div_euclid(SECONDS_PER_DAY)
+}
+
+fn format_days(days: usize) -> String {
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{days} days")
+    }
+}
+
+/// Seconds as hours below two days, days above
+fn format_duration(seconds: u64) -> String {
+    let hours = seconds as f64 / 3_600.0;
+    if hours < 48.0 {
+        format!("{hours:.1} hours")
+    } else {
+        format!("{:.1} days", hours / 24.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_author_stats, compute_repo_stats, format_duration, local_day, SECONDS_PER_DAY,
+    };
+    use crate::git::commits::history::{CommitActivity, FileChurn, RepoCommit};
+    use crate::github::types::PullRequestStatus;
+    use crate::test_utils::MockForge;
+
+    fn commit_on(day: i64, insertions: usize, file: &str) -> CommitActivity {
+        CommitActivity {
+            hash: format!("{day}-{file}"),
+            time: day * SECONDS_PER_DAY + 3_600,
+            offset_minutes: 0,
+            insertions,
+            deletions: 0,
+            files: vec![file.to_string()],
+        }
+    }
+
+    #[test]
+    fn compute_author_stats_tracks_streaks_and_sizes() {
+        let today = 20_000;
+        let activity = vec![
+            commit_on(today - 1, 10, "a.rs"),
+            commit_on(today - 2, 20, "b.rs"),
+            commit_on(today - 2, 30, "a.rs"),
+            commit_on(today - 10, 40, "c.rs"),
+            commit_on(today - 11, 0, "c.rs"),
+            commit_on(today - 12, 0, "c.rs"),
+            commit_on(today - 13, 0, "c.rs"),
+        ];
+
+        let stats = compute_author_stats(&activity, today, 2);
+
+        assert_eq!(stats.total_commits, 7);
+        assert_eq!(stats.files_touched, 3);
+        assert_eq!(stats.average_commit_size, 100.0 / 7.0);
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(stats.longest_streak, 4);
+        assert_eq!(stats.daily.len(), 28);
+        assert_eq!(&stats.daily[25..], &[2, 1, 0]);
+        assert_eq!(stats.weekly, vec![4, 3]);
+    }
+
+    #[test]
+    fn current_streak_is_zero_after_a_gap() {
+        let today = 20_000;
+        let stats = compute_author_stats(&[commit_on(today - 3, 1, "a.rs")], today, 1);
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.longest_streak, 1);
+    }
+
+    #[test]
+    fn local_day_applies_timezone_offset() {
+        // 23:30 UTC is already the next day at UTC+1
+        let time = SECONDS_PER_DAY - 1_800;
+        assert_eq!(local_day(time, 0), 0);
+        assert_eq!(local_day(time, 60), 1);
+        assert_eq!(local_day(-1, 0), -1);
+    }
+
+    fn repo_commit(day: i64, email: &str, files: &[(&str, usize)]) -> RepoCommit {
+        RepoCommit {
+            hash: format!("{day}-{email}"),
+            author_name: email.split('@').next().unwrap_or_default().to_string(),
+            author_email: email.to_string(),
+            time: day * SECONDS_PER_DAY + 3_600,
+            offset_minutes: 0,
+            files: files
+                .iter()
+                .map(|(path, insertions)| FileChurn {
+                    path: path.to_string(),
+                    insertions: *insertions,
+                    deletions: 1,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn compute_repo_stats_ranks_contributors_and_hotspots() {
+        let today = 20_000;
+        let commits = vec![
+            repo_commit(today, "ada@example.com", &[("src/lib.rs", 5)]),
+            repo_commit(
+                today - 1,
+                "ADA@example.com",
+                &[("src/lib.rs", 3), ("README.md", 1)],
+            ),
+            repo_commit(today - 8, "bob@example.com", &[("src/main.rs", 10)]),
+            repo_commit(today - 30, "bob@example.com", &[("src/lib.rs", 1)]),
+            repo_commit(today - 31, "cy@example.com", &[]),
+        ];
+
+        let stats = compute_repo_stats(&commits, &[], today, 3, 2);
+
+        assert_eq!(stats.total_commits, 5);
+        let contributors: Vec<_> = stats
+            .contributors
+            .iter()
+            .map(|c| (c.email.as_str(), c.commits, c.insertions, c.deletions))
+            .collect();
+        assert_eq!(
+            contributors,
+            vec![("ada@example.com", 2, 9, 3), ("bob@example.com", 2, 11, 2)]
+        );
+        let hotspots: Vec<_> = stats
+            .hotspots
+            .iter()
+            .map(|h| (h.path.as_str(), h.commits))
+            .collect();
+        assert_eq!(hotspots, vec![("src/lib.rs", 3), ("src/main.rs", 1)]);
+        assert_eq!(stats.weekly_commits, vec![0, 1, 2]);
+        assert_eq!(stats.average_pr_merge_secs, None);
+    }
+
+    #[test]
+    fn compute_repo_stats_averages_merge_time_of_merged_prs() {
+        let pr = |number, status, created_at, merged_at| {
+            let mut record = MockForge::pull_request(number, "feature", status);
+            record.created_at = created_at;
+            record.merged_at = merged_at;
+            record
+        };
+        let pull_requests = vec![
+            pr(1, PullRequestStatus::Merged, Some(1_000), Some(4_600)),
+            pr(2, PullRequestStatus::Merged, Some(1_000), Some(8_200)),
+            pr(3, PullRequestStatus::Merged, None, Some(9_000)),
+            pr(4, PullRequestStatus::Open, Some(1_000), None),
+        ];
+
+        let stats = compute_repo_stats(&[], &pull_requests, 20_000, 1, 10);
+
+        assert_eq!(stats.average_pr_merge_secs, Some(5_400));
+        assert_eq!(stats.merged_prs_measured, 2);
+    }
+
+    #[test]
+    fn format_duration_switches_to_days() {
+        assert_eq!(format_duration(5_400), "1.5 hours");
+        assert_eq!(format_duration(3 * 86_400), "3.0 days");
+    }
+}
@@ -0,0 +1,34 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::{git::GitRepo, tui::watch};
+use std::process::Command;
+use std::time::Duration;
+
+/// Show `git status`, optionally redrawing it whenever the repository changes
+pub async fn handle_status(
+    args: &[String],
+    watch_interval: Option<Duration>,
+) -> Result<(), GitxError> {
+    let Some(interval) = watch_interval else {
+        return git_passthrough("status", args, false);
+    };
+
+    let repo = GitRepo::open(".")?;
+    let repo = &repo;
+    watch::watch(
+        repo.path(),
+        repo.git_dir(),
+        interval,
+        |path| repo.is_path_ignored(path),
+        move || async move {
+            // Output goes straight to the terminal so git keeps its colors
+            Command::new("git")
+                .arg("status")
+                .args(args)
+                .current_dir(repo.path())
+                .status()?;
+            Ok::<(), GitxError>(())
+        },
+    )
+    .await
+}
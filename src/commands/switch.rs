@@ -0,0 +1,117 @@
+use super::git_passthrough::git_passthrough;
+use crate::error::GitxError;
+use crate::git::branches::switch::{DirtyWorktreePolicy, SwitchOptions};
+use crate::git::GitRepo;
+use crate::i18n::tf;
+use crate::tui::dry_run;
+use console::style;
+
+/// Switch to (and optionally create) a branch without shelling out to `git switch`. `-` (or
+/// `@{-1}`) is the branch checked out before the current one.
+pub fn handle_switch(
+    branch: &str,
+    start_point: Option<&str>,
+    create: bool,
+    force: bool,
+    autostash: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let previous;
+    let branch = if !create && matches!(branch, "-" | "@{-1}") {
+        previous = repo
+            .previous_checkout()?
+            .ok_or_else(|| GitxError::Usage("No previous branch to switch back to".to_string()))?;
+        previous.as_str()
+    } else {
+        branch
+    };
+    let autostash = autostash
+        || (!force
+            && repo
+                .get_config_bool("xgit.switch.autostash")?
+                .unwrap_or(false));
+
+    if dry_run {
+        dry_run::print_header();
+        let operation = match (create, start_point) {
+            (true, Some(start)) => {
+                format!("create branch '{branch}' from '{start}' and switch to it")
+            }
+            (true, None) => format!("create branch '{branch}' and switch to it"),
+            (false, _) => format!("switch to branch '{branch}'"),
+        };
+        dry_run::print_operation(&operation);
+        return Ok(());
+    }
+
+    let options = SwitchOptions {
+        create,
+        start_point: start_point.map(str::to_string),
+        dirty: if force {
+            DirtyWorktreePolicy::Force
+        } else if autostash {
+            DirtyWorktreePolicy::Autostash
+        } else {
+            DirtyWorktreePolicy::Refuse
+        },
+    };
+    let outcome = repo.switch_branch(branch, &options)?;
+
+    if outcome.created {
+        println!(
+            "{} Created branch {}",
+            style("✓").green().bold(),
+            style(&outcome.branch).cyan()
+        );
+    }
+    if let Some(upstream) = &outcome.upstream {
+        println!(
+            "{} Tracking {}",
+            style("ℹ").blue().bold(),
+            style(upstream).cyan()
+        );
+    }
+    println!(
+        "{} {}",
+        style("✓").green().bold(),
+        tf(
+            "branch.switched",
+            &[("branch", &style(&outcome.branch).cyan())]
+        )
+    );
+    if outcome.stash_conflicted {
+        println!(
+            "{} Re-applying your stashed changes conflicted; they are kept in `git stash list`",
+            style("⚠").yellow().bold()
+        );
+    } else if outcome.stashed {
+        println!(
+            "{} Re-applied your uncommitted changes",
+            style("✓").green().bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// Detach HEAD at `target` or start the `orphan` branch, which leave no existing branch to
+/// switch to, through `git switch`
+pub fn handle_switch_without_branch(
+    target: Option<&str>,
+    orphan: Option<&str>,
+    force: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let mut args = match orphan {
+        Some(orphan) => vec!["--orphan".to_string(), orphan.to_string()],
+        None => std::iter::once("--detach")
+            .chain(target)
+            .map(str::to_string)
+            .collect(),
+    };
+    if force {
+        args.push("--force".to_string());
+    }
+    git_passthrough("switch", &args, dry_run)
+}
@@ -0,0 +1,160 @@
+use crate::commands::branch_prune::prune_merged_branches;
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use console::style;
+
+/// What happened to one local branch during a sync
+enum BranchSync {
+    FastForwarded(usize),
+    UpToDate,
+    Ahead(usize),
+    Behind(usize),
+    Diverged(usize, usize),
+    UpstreamGone,
+    NoUpstream,
+    Failed(String),
+}
+
+impl BranchSync {
+    fn describe(&self) -> String {
+        match self {
+            BranchSync::FastForwarded(count) => style(format!(
+                "fast-forwarded {count} commit{}",
+                if *count == 1 { "" } else { "s" }
+            ))
+            .green()
+            .to_string(),
+            BranchSync::UpToDate => style("up to date").dim().to_string(),
+            BranchSync::Ahead(ahead) => style(format!("↑{ahead} to push")).yellow().to_string(),
+            BranchSync::Behind(behind) => style(format!("↓{behind} behind")).yellow().to_string(),
+            BranchSync::Diverged(ahead, behind) => style(format!("diverged ↑{ahead} ↓{behind}"))
+                .red()
+                .to_string(),
+            BranchSync::UpstreamGone => style("upstream gone").red().to_string(),
+            BranchSync::NoUpstream => style("no upstream").dim().to_string(),
+            BranchSync::Failed(err) => style(format!("not updated: {err}")).red().to_string(),
+        }
+    }
+}
+
+/// Fetch every remote with pruning, fast-forward the current branch, optionally prune merged
+/// local branches, and summarize what changed per branch
+pub async fn handle_sync(prune_merged: bool, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let remotes = repo.get_remote_names()?;
+    if remotes.is_empty() {
+        return Err(GitxError::Usage(
+            "No remotes configured; add one with `git remote add`".to_string(),
+        ));
+    }
+    let current = repo.get_current_branch()?;
+
+    if dry_run {
+        dry_run::print_header();
+        for remote in &remotes {
+            dry_run::print_operation(&format!("fetch {remote} and prune deleted branches"));
+        }
+        dry_run::print_operation(&format!("fast-forward {current} to its upstream"));
+        if prune_merged {
+            dry_run::print_operation("prune merged local branches");
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{} Fetching {} remote{}...",
+        style(icon("🔄")).cyan(),
+        remotes.len(),
+        if remotes.len() == 1 { "" } else { "s" }
+    );
+    let mut pruned = Vec::new();
+    // The summary below is still worth showing when a remote is unreachable, but the command
+    // fails with the first fetch error once it is printed
+    let mut fetch_error = None;
+    for remote in &remotes {
+        match repo.fetch_prune_async(remote, None).await {
+            Ok(outcome) => pruned.extend_from_slice(outcome.pruned()),
+            Err(err) => {
+                eprintln!(
+                    "{} Failed to fetch {}: {err:#}",
+                    style("⚠").yellow(),
+                    style(remote).cyan()
+                );
+                fetch_error.get_or_insert(err);
+            }
+        }
+    }
+    let after = repo.get_remote_branches()?;
+
+    let mut rows = Vec::new();
+    for branch in repo.get_all_branches()? {
+        let upstream = repo.get_remote_tracking_info(&branch).ok();
+        let status = match &upstream {
+            None => BranchSync::NoUpstream,
+            Some(upstream) if !after.contains(upstream) => BranchSync::UpstreamGone,
+            Some(upstream) if branch == current => {
+                match repo.fast_forward_current_branch(upstream) {
+                    Ok(0) => sync_status(&repo, &branch, upstream),
+                    Ok(count) => BranchSync::FastForwarded(count),
+                    Err(err) => BranchSync::Failed(format!("{err:#}")),
+                }
+            }
+            Some(upstream) => sync_status(&repo, &branch, upstream),
+        };
+        rows.push((branch, upstream, status));
+    }
+
+    println!();
+    let width = rows
+        .iter()
+        .map(|(branch, _, _)| branch.len())
+        .max()
+        .unwrap_or_default();
+    for (branch, upstream, status) in &rows {
+        let marker = if *branch == current { "*" } else { " " };
+        println!(
+            "{marker} {}  {}  {}",
+            style(format!("{branch:width$}")).cyan(),
+            status.describe(),
+            style(upstream.as_deref().unwrap_or_default()).dim()
+        );
+    }
+
+    if !pruned.is_empty() {
+        println!();
+        println!(
+            "{} Pruned remote-tracking branches:",
+            style(icon("🧹")).cyan()
+        );
+        for name in pruned {
+            println!("  {}", style(name).dim());
+        }
+    }
+
+    if let Some(err) = fetch_error {
+        if prune_merged {
+            eprintln!(
+                "{} Skipped pruning merged branches because a fetch failed",
+                style("⚠").yellow()
+            );
+        }
+        return Err(err.into());
+    }
+    if prune_merged {
+        println!();
+        prune_merged_branches(false, false, false).await?;
+    }
+    Ok(())
+}
+
+fn sync_status(repo: &GitRepo, branch: &str, upstream: &str) -> BranchSync {
+    match repo.ahead_behind(branch, upstream) {
+        Ok((0, 0)) => BranchSync::UpToDate,
+        Ok((ahead, 0)) => BranchSync::Ahead(ahead),
+        Ok((0, behind)) => BranchSync::Behind(behind),
+        Ok((ahead, behind)) => BranchSync::Diverged(ahead, behind),
+        Err(err) => BranchSync::Failed(format!("{err:#}")),
+    }
+}
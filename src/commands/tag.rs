@@ -0,0 +1,125 @@
+use crate::error::GitxError;
+use crate::git::GitRepo;
+use crate::tui::{confirm::confirm_or_abort, dry_run};
+use console::style;
+
+/// List tags with the commit they point at and their message
+pub fn handle_tag_list() -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let tags = repo.list_tags()?;
+    if tags.is_empty() {
+        println!("{} No tags yet", style("ℹ").blue().bold());
+        return Ok(());
+    }
+
+    let width = tags.iter().map(|tag| tag.name.len()).max().unwrap_or(0);
+    for tag in &tags {
+        let description = match &tag.message {
+            Some(message) => style(message.lines().next().unwrap_or_default().to_string()),
+            None => style("(lightweight)".to_string()).dim(),
+        };
+        println!(
+            "{} {} {description}",
+            style(format!("{:<width$}", tag.name)).cyan().bold(),
+            style(&tag.commit[..7]).yellow()
+        );
+    }
+    Ok(())
+}
+
+/// Create a tag at `target`, annotated when a message is given
+pub fn handle_tag_create(
+    name: &str,
+    target: &str,
+    message: Option<&str>,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let kind = if message.is_some() {
+        "annotated"
+    } else {
+        "lightweight"
+    };
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("create {kind} tag '{name}' at '{target}'"));
+        return Ok(());
+    }
+
+    repo.create_tag(name, target, message)?;
+    println!(
+        "{} Created {kind} tag {} at {}",
+        style("✓").green().bold(),
+        style(name).cyan().bold(),
+        style(target).yellow()
+    );
+    Ok(())
+}
+
+/// Delete local tags after confirming
+pub fn handle_tag_delete(names: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let tags = repo.list_tags()?;
+    if let Some(missing) = names
+        .iter()
+        .find(|name| !tags.iter().any(|tag| &tag.name == *name))
+    {
+        return Err(GitxError::Usage(format!("Tag '{missing}' not found")));
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        for name in names {
+            dry_run::print_operation(&format!("delete tag '{name}'"));
+        }
+        return Ok(());
+    }
+
+    let prompt = match names {
+        [name] => format!("Delete tag {name}?"),
+        _ => format!("Delete {} tags?", names.len()),
+    };
+    confirm_or_abort(&prompt, "Nothing deleted")?;
+    for name in names {
+        repo.delete_tag(name)?;
+        println!(
+            "{} Deleted tag {}",
+            style("✓").green().bold(),
+            style(name).cyan().bold()
+        );
+    }
+    Ok(())
+}
+
+/// Push `names` to `remote`, or every local tag when none are given
+pub fn handle_tag_push(remote: &str, names: &[String], dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let names: Vec<String> = if names.is_empty() {
+        repo.list_tags()?.into_iter().map(|tag| tag.name).collect()
+    } else {
+        names.to_vec()
+    };
+    if names.is_empty() {
+        println!("{} No tags to push", style("ℹ").blue().bold());
+        return Ok(());
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        for name in &names {
+            dry_run::print_operation(&format!("push tag '{name}' to '{remote}'"));
+        }
+        return Ok(());
+    }
+
+    let refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    repo.push_tags(remote, &refs)?;
+    println!(
+        "{} Pushed {} tag{} to {}",
+        style("✓").green().bold(),
+        names.len(),
+        if names.len() == 1 { "" } else { "s" },
+        style(remote).cyan()
+    );
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::error::GitxError;
+use crate::git::branches::switch::SwitchOptions;
+use crate::git::repository::reflog::UndoPlan;
+use crate::git::GitRepo;
+use crate::tui::{confirm::confirm_or_abort, dry_run};
+use console::style;
+
+/// Revert the last operation in the HEAD reflog after confirming
+pub fn handle_undo(dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let Some(plan) = repo.plan_undo()? else {
+        println!("{} Nothing to undo", style("ℹ").blue().bold());
+        return Ok(());
+    };
+
+    let description = describe(&plan);
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&description);
+        return Ok(());
+    }
+    confirm_or_abort(&format!("Undo: {description}?"), "Undo aborted")?;
+
+    match &plan {
+        UndoPlan::SwitchBack { branch, .. } => {
+            repo.switch_branch(branch, &SwitchOptions::default())?;
+        }
+        UndoPlan::Reset { to_id, .. } => repo.reset_hard(to_id)?,
+    }
+    println!("{} Undid: {description}", style("✓").green().bold());
+    Ok(())
+}
+
+fn describe(plan: &UndoPlan) -> String {
+    match plan {
+        UndoPlan::SwitchBack { branch, from } => {
+            format!("switch from '{from}' back to '{branch}'")
+        }
+        UndoPlan::Reset {
+            branch,
+            operation,
+            from_id,
+            to_id,
+        } => format!(
+            "{operation}, moving {} back from {} to {}",
+            branch.as_deref().unwrap_or("HEAD"),
+            &from_id[..7],
+            &to_id[..7]
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe;
+    use crate::git::repository::reflog::UndoPlan;
+
+    #[test]
+    fn describes_what_undo_will_do() {
+        let reset = UndoPlan::Reset {
+            branch: Some("main".to_string()),
+            operation: "merge feature".to_string(),
+            from_id: "a".repeat(40),
+            to_id: "b".repeat(40),
+        };
+        assert_eq!(
+            describe(&reset),
+            "merge feature, moving main back from aaaaaaa to bbbbbbb"
+        );
+        let switch = UndoPlan::SwitchBack {
+            branch: "main".to_string(),
+            from: "feature".to_string(),
+        };
+        assert_eq!(describe(&switch), "switch from 'feature' back to 'main'");
+    }
+}
@@ -0,0 +1,621 @@
+use super::changelog::CHANGELOG_FILE;
+use crate::error::GitxError;
+use crate::git::commits::builder::CommitOptions;
+use crate::git::commits::conventional::{is_generated_message, ConventionalCommit};
+use crate::git::repository::core::CommitInfo;
+use crate::git::GitRepo;
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use console::style;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Part of a `major.minor.patch` version to increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
+
+/// A `major.minor.patch` release version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl Version {
+    /// Parse `1.2.3` or `v1.2.3`; pre-release and build suffixes are not supported
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let value = value.strip_prefix('v').unwrap_or(value);
+        let mut parts = value.split('.').map(|part| part.parse::<u64>().ok());
+        let version = Version {
+            major: parts.next()??,
+            minor: parts.next()??,
+            patch: parts.next()??,
+        };
+        parts.next().is_none().then(|| version)
+    }
+
+    fn bump(self, bump: Bump) -> Self {
+        match bump {
+            Bump::Major => Version {
+                major: self.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            Bump::Minor => Version {
+                minor: self.minor + 1,
+                patch: 0,
+                ..self
+            },
+            Bump::Patch => Version {
+                patch: self.patch + 1,
+                ..self
+            },
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A file at the repository root that records the project version
+trait VersionFile {
+    fn path(&self) -> &'static str;
+    /// The version the file declares, if any
+    fn read_version(&self, contents: &str) -> Option<String>;
+    /// `contents` with the declared version replaced by `version`, formatting untouched
+    fn write_version(&self, contents: &str, version: &str) -> Option<String>;
+}
+
+/// `version` in the `[package]` (or `[workspace.package]`) table of `Cargo.toml`
+struct CargoToml;
+
+impl CargoToml {
+    /// Byte range of the quoted `key` value in the package table
+    fn find_string(contents: &str, key: &str) -> Option<(usize, usize)> {
+        let mut in_package = false;
+        let mut offset = 0;
+        for line in contents.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_package = trimmed == "[package]" || trimmed == "[workspace.package]";
+            } else if in_package {
+                if let Some(rest) = trimmed.strip_prefix(key) {
+                    if let Some(value) = rest.trim_start().strip_prefix('=') {
+                        let value = value.trim_start().strip_prefix('"')?;
+                        let end = value.find('"')?;
+                        let start = offset + line.find(value)?;
+                        return Some((start, start + end));
+                    }
+                }
+            }
+            offset += line.len();
+        }
+        None
+    }
+
+    /// `name` of the `[package]` table; a `[workspace.package]` has none
+    fn package_name(contents: &str) -> Option<&str> {
+        let (start, end) = Self::find_string(contents, "name")?;
+        Some(&contents[start..end])
+    }
+}
+
+impl VersionFile for CargoToml {
+    fn path(&self) -> &'static str {
+        "Cargo.toml"
+    }
+
+    fn read_version(&self, contents: &str) -> Option<String> {
+        let (start, end) = Self::find_string(contents, "version")?;
+        Some(contents[start..end].to_string())
+    }
+
+    fn write_version(&self, contents: &str, version: &str) -> Option<String> {
+        let (start, end) = Self::find_string(contents, "version")?;
+        Some(format!(
+            "{}{version}{}",
+            &contents[..start],
+            &contents[end..]
+        ))
+    }
+}
+
+/// Top-level `"version"` of `package.json`
+struct PackageJson;
+
+impl VersionFile for PackageJson {
+    fn path(&self) -> &'static str {
+        "package.json"
+    }
+
+    fn read_version(&self, contents: &str) -> Option<String> {
+        let json: serde_json::Value = serde_json::from_str(contents).ok()?;
+        Some(json.get("version")?.as_str()?.to_string())
+    }
+
+    fn write_version(&self, contents: &str, version: &str) -> Option<String> {
+        let current = self.read_version(contents)?;
+        // The first `"version": "<current>"` pair; nested objects rarely repeat it
+        let mut search = 0;
+        while let Some(found) = contents[search..].find("\"version\"") {
+            let key_end = search + found + "\"version\"".len();
+            let rest = contents[key_end..].trim_start().strip_prefix(':');
+            if let Some(value) = rest.map(str::trim_start) {
+                if let Some(value) = value.strip_prefix(&format!("\"{current}\"")) {
+                    let end = contents.len() - value.len() - 1;
+                    let start = end - current.len();
+                    return Some(format!(
+                        "{}{version}{}",
+                        &contents[..start],
+                        &contents[end..]
+                    ));
+                }
+            }
+            search = key_end;
+        }
+        None
+    }
+}
+
+const VERSION_FILES: &[&dyn VersionFile] = &[&CargoToml, &PackageJson];
+
+const CARGO_LOCK: &str = "Cargo.lock";
+
+/// `Cargo.lock` with the entries of the workspace's own packages (those without a `source`)
+/// moved from version `from` to `to`, limited to `name` when the manifest has one. `None`
+/// when no entry matches.
+fn update_cargo_lock(contents: &str, name: Option<&str>, from: &str, to: &str) -> Option<String> {
+    let mut updated = String::with_capacity(contents.len());
+    let mut changed = false;
+    let mut rest = contents;
+    while !rest.is_empty() {
+        // Each entry runs from its `[[package]]` header to the next one
+        let next = rest[1..]
+            .find("[[package]]")
+            .map_or(rest.len(), |found| found + 1);
+        let (entry, tail) = rest.split_at(next);
+        rest = tail;
+
+        let lines: Vec<&str> = entry.lines().collect();
+        let is_ours = entry.starts_with("[[package]]")
+            && !lines.iter().any(|line| line.starts_with("source = "))
+            && name.map_or(true, |name| {
+                lines.contains(&format!("name = \"{name}\"").as_str())
+            });
+        let old = format!("\nversion = \"{from}\"\n");
+        if is_ours && entry.contains(&old) {
+            updated.push_str(&entry.replacen(&old, &format!("\nversion = \"{to}\"\n"), 1));
+            changed = true;
+        } else {
+            updated.push_str(entry);
+        }
+    }
+    changed.then(|| updated)
+}
+
+/// Commits since the last release, by the bump they call for
+#[derive(Debug, Default, PartialEq)]
+struct ReleaseSummary {
+    breaking: usize,
+    features: usize,
+    fixes: usize,
+    other: usize,
+}
+
+impl ReleaseSummary {
+    fn from_commits(commits: &[CommitInfo]) -> Self {
+        let mut summary = ReleaseSummary::default();
+        for commit in commits {
+            if is_generated_message(&commit.message) {
+                continue;
+            }
+            match ConventionalCommit::parse(&commit.message) {
+                Ok(parsed) if parsed.breaking => summary.breaking += 1,
+                Ok(parsed) if parsed.commit_type == "feat" => summary.features += 1,
+                Ok(parsed) if parsed.commit_type == "fix" || parsed.commit_type == "perf" => {
+                    summary.fixes += 1
+                }
+                _ => summary.other += 1,
+            }
+        }
+        summary
+    }
+
+    /// The bump these commits call for; breaking changes only bump the minor version
+    /// before 1.0.0
+    fn bump(&self, current: Version) -> Option<Bump> {
+        if self.breaking > 0 {
+            Some(if current.major == 0 {
+                Bump::Minor
+            } else {
+                Bump::Major
+            })
+        } else if self.features > 0 {
+            Some(Bump::Minor)
+        } else if self.fixes > 0 {
+            Some(Bump::Patch)
+        } else {
+            None
+        }
+    }
+}
+
+/// A [`VersionFile`] present at the repository root
+struct FoundVersionFile {
+    file: &'static dyn VersionFile,
+    path: PathBuf,
+    contents: String,
+    /// The version the file currently declares
+    version: String,
+}
+
+fn find_version_files(workdir: &Path) -> Result<Vec<FoundVersionFile>, GitxError> {
+    let mut found = Vec::new();
+    for file in VERSION_FILES {
+        let path = workdir.join(file.path());
+        if path.is_file() {
+            let contents = fs::read_to_string(&path)?;
+            if let Some(version) = file.read_version(&contents) {
+                found.push(FoundVersionFile {
+                    file: *file,
+                    path,
+                    contents,
+                    version,
+                });
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// The commits since the latest version tag, which the next release will contain
+pub struct PendingRelease {
+    /// Latest `vX.Y.Z` tag reachable from HEAD
+    pub last_tag: Option<String>,
+    /// Version of that tag, or the version files' version before the first release
+    pub current: Version,
+    /// Commits since the tag, newest first
+    pub commits: Vec<CommitInfo>,
+    tag_prefix: &'static str,
+}
+
+impl PendingRelease {
+    pub fn load(repo: &GitRepo, workdir: &Path) -> Result<Self, GitxError> {
+        Ok(match latest_version_tag(repo)? {
+            Some((current, name)) => PendingRelease {
+                current,
+                tag_prefix: if name.starts_with('v') { "v" } else { "" },
+                commits: repo.list_commits_range(&format!("{name}..HEAD"), 0, None)?,
+                last_tag: Some(name),
+            },
+            None => PendingRelease {
+                current: find_version_files(workdir)?
+                    .iter()
+                    .find_map(|found| Version::parse(&found.version))
+                    .unwrap_or(Version {
+                        major: 0,
+                        minor: 0,
+                        patch: 0,
+                    }),
+                tag_prefix: "v",
+                commits: repo.list_commits()?,
+                last_tag: None,
+            },
+        })
+    }
+
+    /// The version the commits call for, if any of them is a feat, fix or breaking change
+    pub fn next_version(&self) -> Option<Version> {
+        ReleaseSummary::from_commits(&self.commits)
+            .bump(self.current)
+            .map(|bump| self.current.bump(bump))
+    }
+
+    /// Tag name for `version`, following the previous tag's `v` prefix
+    pub fn tag_name(&self, version: Version) -> String {
+        format!("{}{version}", self.tag_prefix)
+    }
+
+    /// Where the pending commits start, for messages
+    pub fn since(&self) -> &str {
+        self.last_tag.as_deref().unwrap_or("the first commit")
+    }
+}
+
+/// The highest `vX.Y.Z` (or `X.Y.Z`) tag reachable from HEAD, with its version
+pub fn latest_version_tag(repo: &GitRepo) -> Result<Option<(Version, String)>, GitxError> {
+    Ok(repo
+        .reachable_tags()?
+        .into_iter()
+        .filter_map(|tag| Version::parse(&tag.name).map(|version| (version, tag.name)))
+        .max())
+}
+
+/// Bump the version from the Conventional Commits since the last version tag (or by `level`),
+/// update the version files at the repository root, commit them and create an annotated tag.
+///
+/// A staged `CHANGELOG.md`, as left by `xg changelog update`, goes into the release commit.
+pub fn handle_version_bump(level: Option<Bump>, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(".")?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| GitxError::Usage("`xg version bump` needs a working tree".to_string()))?
+        .to_path_buf();
+    let counts = repo.change_counts()?;
+    let staged: Vec<String> = repo
+        .get_staged_file_sizes()?
+        .into_iter()
+        .map(|file| file.path)
+        .collect();
+    let changelog_staged = staged == [CHANGELOG_FILE] && counts.staged == 1;
+    if counts.unstaged + counts.conflicted > 0 || (counts.staged > 0 && !changelog_staged) {
+        return Err(GitxError::DirtyWorktree(
+            "Commit or stash your changes before bumping the version".to_string(),
+        ));
+    }
+
+    let files = find_version_files(&workdir)?;
+    let pending = PendingRelease::load(&repo, &workdir)?;
+    let current = pending.current;
+    let since = pending.since();
+    let commits = &pending.commits;
+
+    let summary = ReleaseSummary::from_commits(commits);
+    let Some(bump) = level.or_else(|| summary.bump(current)) else {
+        println!(
+            "{} No feat, fix or breaking commits since {since}; nothing to release",
+            style(icon("✨")).green().bold()
+        );
+        return Ok(());
+    };
+    let next = current.bump(bump);
+    let tag = pending.tag_name(next);
+
+    println!(
+        "{} {} → {} ({bump}) from {} commit(s) since {since}",
+        style(icon("🏷")).cyan().bold(),
+        current,
+        style(next).green().bold(),
+        commits.len()
+    );
+    println!(
+        "  {}",
+        style(format!(
+            "{} breaking, {} feat, {} fix, {} other",
+            summary.breaking, summary.features, summary.fixes, summary.other
+        ))
+        .dim()
+    );
+
+    // Cargo.lock repeats the package version; an ignored lock file is cargo's to refresh
+    let lock_path = workdir.join(CARGO_LOCK);
+    let lock = match files.iter().find(|found| found.file.path() == "Cargo.toml") {
+        Some(manifest) if lock_path.is_file() && !repo.is_path_ignored(&lock_path) => {
+            update_cargo_lock(
+                &fs::read_to_string(&lock_path)?,
+                CargoToml::package_name(&manifest.contents),
+                &manifest.version,
+                &next.to_string(),
+            )
+        }
+        _ => None,
+    };
+
+    let message = format!("chore(release): {tag}");
+    let mut paths: Vec<&str> = files.iter().map(|found| found.file.path()).collect();
+    if lock.is_some() {
+        paths.push(CARGO_LOCK);
+    }
+    if changelog_staged {
+        paths.push(CHANGELOG_FILE);
+    }
+    if dry_run {
+        dry_run::print_header();
+        for found in &files {
+            dry_run::print_operation(&format!(
+                "set version in {} from {} to {next}",
+                found.file.path(),
+                found.version
+            ));
+        }
+        if lock.is_some() {
+            dry_run::print_operation(&format!("set version in {CARGO_LOCK} to {next}"));
+        }
+        if !paths.is_empty() {
+            dry_run::print_operation(&format!("commit {} as \"{message}\"", paths.join(", ")));
+        }
+        dry_run::print_operation(&format!("create annotated tag {tag}"));
+        return Ok(());
+    }
+
+    if !paths.is_empty() {
+        for found in &files {
+            let updated = found
+                .file
+                .write_version(&found.contents, &next.to_string())
+                .ok_or_else(|| {
+                    GitxError::Other(anyhow::anyhow!(
+                        "Could not update the version in {}",
+                        found.file.path()
+                    ))
+                })?;
+            fs::write(&found.path, updated)?;
+        }
+        if let Some(lock) = &lock {
+            fs::write(&lock_path, lock)?;
+        }
+        repo.add(&paths)?;
+        repo.commit_with(&CommitOptions::new(&message))?;
+        println!(
+            "{} Committed {} as \"{message}\"",
+            style("✓").green().bold(),
+            paths.join(", ")
+        );
+    }
+
+    repo.create_annotated_tag(&tag, &tag_message(&tag, commits))?;
+    println!(
+        "{} Tagged {}; publish with `git push --follow-tags`",
+        style("✓").green().bold(),
+        style(&tag).cyan()
+    );
+    Ok(())
+}
+
+/// `Release <tag>` followed by the subjects of the released commits
+fn tag_message(tag: &str, commits: &[CommitInfo]) -> String {
+    let mut message = format!("Release {tag}\n");
+    let subjects: Vec<&str> = commits
+        .iter()
+        .filter(|commit| !is_generated_message(&commit.message))
+        .filter_map(|commit| commit.message.lines().next())
+        .collect();
+    if !subjects.is_empty() {
+        message.push('\n');
+        for subject in subjects {
+            message.push_str(&format!("- {subject}\n"));
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        tag_message, update_cargo_lock, Bump, CargoToml, PackageJson, ReleaseSummary, Version,
+        VersionFile,
+    };
+    use crate::git::repository::core::CommitInfo;
+
+    fn commit(message: &str) -> CommitInfo {
+        CommitInfo {
+            hash: "0".repeat(40),
+            message: message.to_string(),
+            author_name: "Test".to_string(),
+            author_email: "test@example.com".to_string(),
+            time: 0,
+            offset_minutes: 0,
+            parent_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn version_parses_and_bumps() {
+        let version = Version::parse("v1.2.3").unwrap();
+        assert_eq!(version.bump(Bump::Patch).to_string(), "1.2.4");
+        assert_eq!(version.bump(Bump::Minor).to_string(), "1.3.0");
+        assert_eq!(version.bump(Bump::Major).to_string(), "2.0.0");
+        assert_eq!(Version::parse("1.2"), None);
+        assert_eq!(Version::parse("1.2.3-rc.1"), None);
+        assert_eq!(Version::parse("release-1"), None);
+    }
+
+    #[test]
+    fn release_summary_picks_the_largest_bump() {
+        let one = Version::parse("1.0.0").unwrap();
+        let zero = Version::parse("0.4.0").unwrap();
+
+        let summary = ReleaseSummary::from_commits(&[
+            commit("fix: Handle empty diffs"),
+            commit("docs: Explain hooks"),
+            commit("Merge branch 'topic'"),
+        ]);
+        assert_eq!(summary.fixes, 1);
+        assert_eq!(summary.other, 1);
+        assert_eq!(summary.bump(one), Some(Bump::Patch));
+
+        let summary = ReleaseSummary::from_commits(&[
+            commit("feat(cli): Add version bump"),
+            commit("refactor!: Rename the config keys"),
+        ]);
+        assert_eq!(summary.bump(one), Some(Bump::Major));
+        assert_eq!(summary.bump(zero), Some(Bump::Minor));
+
+        let summary = ReleaseSummary::from_commits(&[commit("chore: Tidy up")]);
+        assert_eq!(summary.bump(one), None);
+    }
+
+    #[test]
+    fn cargo_toml_updates_only_the_package_version() {
+        let contents = "[package]\nname = \"xgit\"\nversion = \"0.1.0\"\n\n[dependencies]\nclap = { version = \"4\" }\n";
+        assert_eq!(CargoToml.read_version(contents).as_deref(), Some("0.1.0"));
+        assert_eq!(
+            CargoToml.write_version(contents, "0.2.0").unwrap(),
+            contents.replace("\"0.1.0\"", "\"0.2.0\"")
+        );
+
+        let workspace =
+            "[workspace]\nmembers = [\"a\"]\n\n[workspace.package]\nversion=\"1.0.0\"\n";
+        assert_eq!(
+            CargoToml.write_version(workspace, "1.1.0").unwrap(),
+            workspace.replace("1.0.0", "1.1.0")
+        );
+        assert_eq!(
+            CargoToml.read_version("[package]\nversion.workspace = true\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn cargo_lock_updates_only_the_workspace_package() {
+        let lock = "version = 4\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\ndependencies = [\n \"log\",\n]\n\n[[package]]\nname = \"log\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n";
+        assert_eq!(
+            update_cargo_lock(lock, Some("demo"), "0.1.0", "0.2.0").unwrap(),
+            lock.replacen("\"0.1.0\"", "\"0.2.0\"", 1)
+        );
+        assert_eq!(
+            update_cargo_lock(lock, None, "0.1.0", "0.2.0").unwrap(),
+            lock.replacen("\"0.1.0\"", "\"0.2.0\"", 1)
+        );
+        assert_eq!(
+            update_cargo_lock(lock, Some("other"), "0.1.0", "0.2.0"),
+            None
+        );
+        assert_eq!(CargoToml::package_name(lock), None);
+        assert_eq!(
+            CargoToml::package_name("[package]\nname = \"demo\"\n"),
+            Some("demo")
+        );
+    }
+
+    #[test]
+    fn package_json_updates_the_top_level_version() {
+        let contents =
+            "{\n  \"name\": \"app\",\n  \"version\" : \"1.4.2\",\n  \"dependencies\": {}\n}\n";
+        assert_eq!(PackageJson.read_version(contents).as_deref(), Some("1.4.2"));
+        assert_eq!(
+            PackageJson.write_version(contents, "1.5.0").unwrap(),
+            contents.replace("1.4.2", "1.5.0")
+        );
+        assert_eq!(PackageJson.write_version("{}", "1.0.0"), None);
+    }
+
+    #[test]
+    fn tag_message_lists_released_subjects() {
+        let message = tag_message(
+            "v1.1.0",
+            &[commit("feat: Add bump\n\nBody"), commit("Merge branch 'x'")],
+        );
+        assert_eq!(message, "Release v1.1.0\n\n- feat: Add bump\n");
+    }
+}
@@ -0,0 +1,475 @@
+use crate::error::GitxError;
+use crate::git::commits::staging::ChangeCounts;
+use crate::git::GitRepo;
+use crate::tui::dry_run;
+use crate::tui::emoji::icon;
+use anyhow::Context;
+use console::style;
+use std::path::{Path, PathBuf};
+
+/// Multi-valued global git config key listing the workspace repositories
+const WORKSPACE_CONFIG_KEY: &str = "xgit.workspace.repo";
+
+/// Snapshot of one workspace repository
+#[derive(Debug)]
+struct RepoSummary {
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead_behind: Option<(usize, usize)>,
+    changes: ChangeCounts,
+    branches: Vec<BranchSummary>,
+}
+
+#[derive(Debug)]
+struct BranchSummary {
+    name: String,
+    ahead_behind: Option<(usize, usize)>,
+    merged: bool,
+}
+
+pub fn handle_workspace_list() -> Result<(), GitxError> {
+    let repos = workspace_repos()?;
+    if repos.is_empty() {
+        print_empty_hint();
+        return Ok(());
+    }
+    for repo in repos {
+        println!("{}", repo.display());
+    }
+    Ok(())
+}
+
+pub fn handle_workspace_add(path: &str, dry_run: bool) -> Result<(), GitxError> {
+    let repo = GitRepo::open(path)?;
+    let repo_path = repo.path().to_path_buf();
+    if workspace_repos()?.contains(&repo_path) {
+        println!(
+            "{} {} is already in the workspace",
+            style("ℹ").blue().bold(),
+            repo_path.display()
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("add {} to the workspace", repo_path.display()));
+        return Ok(());
+    }
+
+    // A value regex that matches no existing entry appends, like `git config --add`
+    global_config()?
+        .set_multivar(WORKSPACE_CONFIG_KEY, "^$", &repo_path.to_string_lossy())
+        .context("Failed to update global git config")?;
+    println!(
+        "{} Added {} to the workspace",
+        style("✓").green().bold(),
+        style(repo_path.display()).cyan()
+    );
+    Ok(())
+}
+
+pub fn handle_workspace_remove(path: &str, dry_run: bool) -> Result<(), GitxError> {
+    // Allow removing repositories that no longer exist on disk
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    if !workspace_repos()?.contains(&target) {
+        return Err(GitxError::Usage(format!(
+            "{} is not in the workspace",
+            target.display()
+        )));
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        dry_run::print_operation(&format!("remove {} from the workspace", target.display()));
+        return Ok(());
+    }
+
+    let pattern = format!("^{}$", escape_regex(&target.to_string_lossy()));
+    global_config()?
+        .remove_multivar(WORKSPACE_CONFIG_KEY, &pattern)
+        .context("Failed to update global git config")?;
+    println!(
+        "{} Removed {} from the workspace",
+        style("✓").green().bold(),
+        style(target.display()).cyan()
+    );
+    Ok(())
+}
+
+/// Show branch, upstream divergence and uncommitted changes for every workspace repository
+pub async fn handle_workspace_status() -> Result<(), GitxError> {
+    let results = collect(|path| summarize(path, false)).await?;
+    let Some(results) = results else {
+        return Ok(());
+    };
+
+    println!("{} Workspace Status", style(icon("🗂")).cyan().bold());
+    println!();
+    let mut dirty = 0;
+    for (path, result) in &results {
+        match result {
+            Ok(summary) => {
+                if !summary.changes.is_clean() {
+                    dirty += 1;
+                }
+                print_status_line(path, summary);
+            }
+            Err(err) => print_error_line(path, err),
+        }
+    }
+
+    println!();
+    println!(
+        "{} repositories, {} with uncommitted changes, {} failed",
+        results.len(),
+        dirty,
+        results.iter().filter(|(_, result)| result.is_err()).count()
+    );
+    finish(&results)
+}
+
+/// Run `git fetch --all --prune` in every workspace repository at once
+pub async fn handle_workspace_fetch(dry_run: bool) -> Result<(), GitxError> {
+    let repos = workspace_repos()?;
+    if repos.is_empty() {
+        print_empty_hint();
+        return Ok(());
+    }
+
+    if dry_run {
+        dry_run::print_header();
+        for repo in &repos {
+            dry_run::print_operation(&format!("git fetch --all --prune in {}", repo.display()));
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{} Fetching {} repositories...",
+        style(icon("🔄")).cyan(),
+        repos.len()
+    );
+    let fetches = repos.into_iter().map(|path| async move {
+        let output = tokio::process::Command::new("git")
+            .args(["fetch", "--all", "--prune", "--quiet"])
+            .current_dir(&path)
+            .output()
+            .await;
+        let result = match output {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => Err(anyhow::anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(err) => Err(anyhow::anyhow!("Failed to run git fetch: {err}")),
+        };
+        (path, result)
+    });
+    let results: Vec<(PathBuf, Result<(), anyhow::Error>)> = join_all(fetches).await;
+
+    for (path, result) in &results {
+        match result {
+            Ok(()) => println!(
+                "{} {}",
+                style("✓").green().bold(),
+                style(repo_name(path)).cyan()
+            ),
+            Err(err) => print_error_line(path, err),
+        }
+    }
+    finish(&results)
+}
+
+/// List local branches with upstream divergence and merge status for every workspace repository
+pub async fn handle_workspace_stats() -> Result<(), GitxError> {
+    let results = collect(|path| summarize(path, true)).await?;
+    let Some(results) = results else {
+        return Ok(());
+    };
+
+    println!(
+        "{} Workspace Branch Statistics",
+        style(icon("📊")).cyan().bold()
+    );
+    for (path, result) in &results {
+        println!();
+        match result {
+            Ok(summary) => {
+                println!(
+                    "{} {}",
+                    style(repo_name(path)).bold(),
+                    style(path.display()).dim()
+                );
+                for branch in &summary.branches {
+                    let marker = if summary.branch.as_deref() == Some(branch.name.as_str()) {
+                        style("●").green().bold()
+                    } else {
+                        style("○").dim()
+                    };
+                    let merged = if branch.merged {
+                        format!(" {}", style("merged").green())
+                    } else {
+                        String::new()
+                    };
+                    println!(
+                        "  {marker} {}{}{merged}",
+                        style(&branch.name).cyan(),
+                        format_divergence(branch.ahead_behind)
+                    );
+                }
+            }
+            Err(err) => print_error_line(path, err),
+        }
+    }
+    finish(&results)
+}
+
+/// Summarize every workspace repository concurrently; `None` when the workspace is empty
+async fn collect<F>(
+    summarize: F,
+) -> Result<Option<Vec<(PathBuf, Result<RepoSummary, anyhow::Error>)>>, GitxError>
+where
+    F: Fn(&Path) -> Result<RepoSummary, anyhow::Error> + Copy + Send + 'static,
+{
+    let repos = workspace_repos()?;
+    if repos.is_empty() {
+        print_empty_hint();
+        return Ok(None);
+    }
+
+    // git2 repositories cannot be shared between threads, so each task opens its own
+    let tasks = repos.into_iter().map(|path| async move {
+        let task_path = path.clone();
+        let result = tokio::task::spawn_blocking(move || summarize(&task_path))
+            .await
+            .unwrap_or_else(|err| Err(anyhow::anyhow!("Task failed: {err}")));
+        (path, result)
+    });
+    Ok(Some(join_all(tasks).await))
+}
+
+/// Await futures that were started together, preserving their order
+async fn join_all<T, Fut>(futures: impl Iterator<Item = Fut>) -> Vec<T>
+where
+    Fut: std::future::Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let handles: Vec<_> = futures.map(tokio::spawn).collect();
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(result) = handle.await {
+            results.push(result);
+        }
+    }
+    results
+}
+
+fn summarize(path: &Path, include_branches: bool) -> Result<RepoSummary, anyhow::Error> {
+    let repo = GitRepo::open(path)?;
+    let branch = repo.get_current_branch().ok();
+    let upstream = branch
+        .as_deref()
+        .and_then(|branch| repo.get_remote_tracking_info(branch).ok());
+    let ahead_behind = match (&branch, &upstream) {
+        (Some(branch), Some(upstream)) => repo.ahead_behind(branch, upstream).ok(),
+        _ => None,
+    };
+
+    let backend = repo.read_backend()?;
+    let branches = if include_branches {
+        backend
+            .list_branches()?
+            .into_iter()
+            .map(|name| BranchSummary {
+                ahead_behind: repo
+                    .get_remote_tracking_info(&name)
+                    .ok()
+                    .and_then(|upstream| repo.ahead_behind(&name, &upstream).ok()),
+                merged: repo.is_branch_merged_to_main(&name).unwrap_or(false),
+                name,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(RepoSummary {
+        branch,
+        upstream,
+        ahead_behind,
+        changes: backend.change_counts()?,
+        branches,
+    })
+}
+
+fn print_status_line(path: &Path, summary: &RepoSummary) {
+    let marker = if summary.changes.is_clean() {
+        style("✓").green().bold()
+    } else {
+        style("●").yellow().bold()
+    };
+    let branch = summary.branch.as_deref().unwrap_or("(detached)");
+    let upstream = match &summary.upstream {
+        Some(upstream) => format!(" → {}", style(upstream).dim()),
+        None => String::new(),
+    };
+    println!(
+        "{marker} {:<20} {}{}{}  {}",
+        style(repo_name(path)).bold(),
+        style(branch).cyan(),
+        upstream,
+        format_divergence(summary.ahead_behind),
+        format_changes(&summary.changes)
+    );
+}
+
+fn print_error_line(path: &Path, err: &anyhow::Error) {
+    println!(
+        "{} {:<20} {}",
+        style("✗").red().bold(),
+        style(repo_name(path)).bold(),
+        style(format!("{err:#}")).red()
+    );
+}
+
+fn format_divergence(ahead_behind: Option<(usize, usize)>) -> String {
+    match ahead_behind {
+        Some((0, 0)) | None => String::new(),
+        Some((ahead, behind)) => {
+            let mut parts = Vec::new();
+            if ahead > 0 {
+                parts.push(format!("↑{ahead}"));
+            }
+            if behind > 0 {
+                parts.push(format!("↓{behind}"));
+            }
+            format!(" {}", style(parts.join(" ")).yellow())
+        }
+    }
+}
+
+fn format_changes(changes: &ChangeCounts) -> String {
+    if changes.is_clean() {
+        return style("clean").dim().to_string();
+    }
+    [
+        (changes.conflicted, "conflicted"),
+        (changes.staged, "staged"),
+        (changes.unstaged, "modified"),
+        (changes.untracked, "untracked"),
+    ]
+    .iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+fn finish<T>(results: &[(PathBuf, Result<T, anyhow::Error>)]) -> Result<(), GitxError> {
+    let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+    if failed > 0 {
+        return Err(GitxError::Other(anyhow::anyhow!(
+            "{failed} of {} repositories failed",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+fn repo_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn print_empty_hint() {
+    println!(
+        "{} No workspace repositories configured. Add one with `xg ws add <path>`.",
+        style("ℹ").blue().bold()
+    );
+}
+
+fn global_config() -> Result<git2::Config, GitxError> {
+    Ok(git2::Config::open_default()
+        .and_then(|config| config.open_level(git2::ConfigLevel::Global))
+        .context("Failed to open global git config")?)
+}
+
+/// Workspace repositories from `xgit.workspace.repo`, in config order
+fn workspace_repos() -> Result<Vec<PathBuf>, GitxError> {
+    let config = git2::Config::open_default().context("Failed to open git config")?;
+    let mut repos = Vec::new();
+    // A missing key is reported as an error by libgit2; treat it as an empty workspace
+    if let Ok(mut entries) = config.multivar(WORKSPACE_CONFIG_KEY, None) {
+        while let Some(entry) = entries.next() {
+            let entry = entry.context("Failed to read workspace config")?;
+            if let Some(value) = entry.value() {
+                let path = expand_home(value);
+                if !repos.contains(&path) {
+                    repos.push(path);
+                }
+            }
+        }
+    }
+    Ok(repos)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn escape_regex(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(|c| {
+            let escape = "\\.^$|?*+()[]{}".contains(c);
+            escape.then(|| '\\').into_iter().chain(std::iter::once(c))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_regex, format_changes, summarize};
+    use crate::git::commits::staging::ChangeCounts;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn escape_regex_escapes_path_metacharacters() {
+        assert_eq!(
+            escape_regex("/home/me/repo.v2 (old)"),
+            r"/home/me/repo\.v2 \(old\)"
+        );
+    }
+
+    #[test]
+    fn format_changes_lists_non_zero_counts() {
+        console::set_colors_enabled(false);
+        assert_eq!(format_changes(&ChangeCounts::default()), "clean");
+        let changes = ChangeCounts {
+            staged: 2,
+            untracked: 1,
+            ..ChangeCounts::default()
+        };
+        assert_eq!(format_changes(&changes), "2 staged, 1 untracked");
+    }
+
+    #[test]
+    fn summarize_reports_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?
+            .create_and_checkout_branch("feature")?;
+        repo.add_file("b.txt", "b")?;
+
+        let summary = summarize(repo.path(), true)?;
+        assert_eq!(summary.branch.as_deref(), Some("feature"));
+        assert_eq!(summary.changes.untracked, 1);
+        let names: Vec<&str> = summary.branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["feature", "master"]);
+        Ok(())
+    }
+}
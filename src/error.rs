@@ -0,0 +1,295 @@
+use crate::git::GitError;
+use thiserror::Error;
+
+/// Top-level error returned by command handlers.
+///
+/// Each variant maps to a documented process exit code so scripts can branch on the
+/// kind of failure:
+///
+/// | Code | Variant         |
+/// |------|-----------------|
+/// | 1    | `Other`         |
+/// | 2    | `Usage`         |
+/// | 3    | `NotARepo`      |
+/// | 4    | `Conflict`      |
+/// | 5    | `DirtyWorktree` |
+/// | 6    | `Network`       |
+/// | 7    | `Auth`          |
+/// | 8    | `Locked`        |
+/// | 130  | `UserAbort`     |
+///
+/// `Subprocess` carries the exit code of a passthrough git command or plugin, which has
+/// already reported its own error.
+#[derive(Debug, Error)]
+pub enum GitxError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("{0:#}")]
+    NotARepo(anyhow::Error),
+    #[error("{0:#}")]
+    Conflict(anyhow::Error),
+    #[error("{0}")]
+    DirtyWorktree(String),
+    #[error("{0:#}")]
+    Network(anyhow::Error),
+    #[error("{0:#}")]
+    Auth(anyhow::Error),
+    #[error("{0:#}")]
+    Locked(anyhow::Error),
+    #[error("{0}")]
+    UserAbort(String),
+    #[error("`{program}` exited with status {code}")]
+    Subprocess { program: String, code: i32 },
+    #[error("{0:#}")]
+    Other(anyhow::Error),
+}
+
+impl GitxError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GitxError::Other(_) => 1,
+            GitxError::Usage(_) => 2,
+            GitxError::NotARepo(_) => 3,
+            GitxError::Conflict(_) => 4,
+            GitxError::DirtyWorktree(_) => 5,
+            GitxError::Network(_) => 6,
+            GitxError::Auth(_) => 7,
+            GitxError::Locked(_) => 8,
+            GitxError::UserAbort(_) => 130,
+            GitxError::Subprocess { code, .. } => *code,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Category {
+    NotARepo,
+    Conflict,
+    DirtyWorktree,
+    Network,
+    Auth,
+    Locked,
+    BareRepo,
+    UserAbort,
+}
+
+impl From<anyhow::Error> for GitxError {
+    fn from(err: anyhow::Error) -> Self {
+        // Internals that already know the failure kind can raise a GitxError through anyhow
+        let err = match err.downcast::<GitxError>() {
+            Ok(gitx_error) => return gitx_error,
+            Err(err) => err,
+        };
+
+        match categorize(&err) {
+            Some(Category::NotARepo) => GitxError::NotARepo(err),
+            Some(Category::Conflict) => GitxError::Conflict(err),
+            Some(Category::DirtyWorktree) => GitxError::DirtyWorktree(dirty_worktree_message(&err)),
+            Some(Category::Network) => GitxError::Network(err),
+            Some(Category::Auth) => GitxError::Auth(err),
+            Some(Category::Locked) => GitxError::Locked(err),
+            Some(Category::BareRepo) => GitxError::Usage(format!("{err:#}")),
+            Some(Category::UserAbort) => GitxError::UserAbort(format!("{err:#}")),
+            None => GitxError::Other(err),
+        }
+    }
+}
+
+impl From<GitError> for GitxError {
+    fn from(err: GitError) -> Self {
+        match err {
+            GitError::NotARepo(err) => GitxError::NotARepo(err),
+            GitError::Conflict(err) => GitxError::Conflict(err),
+            GitError::DirtyWorktree(message) => GitxError::DirtyWorktree(message),
+            GitError::AuthRequired(err) => GitxError::Auth(err),
+            GitError::Network(err) => GitxError::Network(err),
+            GitError::Locked(err) => GitxError::Locked(err),
+            GitError::UnsupportedOnBareRepo(err) => GitxError::Usage(format!("{err:#}")),
+            GitError::NotFound(err) | GitError::UnbornBranch(err) => GitxError::Other(err),
+            GitError::Other(err) => err.into(),
+        }
+    }
+}
+
+impl From<git2::Error> for GitxError {
+    fn from(err: git2::Error) -> Self {
+        anyhow::Error::new(err).into()
+    }
+}
+
+impl From<std::io::Error> for GitxError {
+    fn from(err: std::io::Error) -> Self {
+        GitxError::Other(err.into())
+    }
+}
+
+impl From<inquire::InquireError> for GitxError {
+    fn from(err: inquire::InquireError) -> Self {
+        anyhow::Error::new(err).into()
+    }
+}
+
+/// Find the most specific failure category anywhere in the error chain
+fn categorize(err: &anyhow::Error) -> Option<Category> {
+    err.chain().find_map(|cause| {
+        if let Some(git_error) = cause.downcast_ref::<GitError>() {
+            return categorize_git_error(git_error);
+        }
+        if let Some(git_error) = cause.downcast_ref::<git2::Error>() {
+            return categorize_git2(git_error);
+        }
+        if let Some(prompt_error) = cause.downcast_ref::<inquire::InquireError>() {
+            return match prompt_error {
+                inquire::InquireError::OperationCanceled
+                | inquire::InquireError::OperationInterrupted => Some(Category::UserAbort),
+                _ => None,
+            };
+        }
+        if let Some(github_error) = cause.downcast_ref::<octocrab::Error>() {
+            return match github_error {
+                octocrab::Error::GitHub { source, .. }
+                    if matches!(source.status_code.as_u16(), 401 | 403) =>
+                {
+                    Some(Category::Auth)
+                }
+                octocrab::Error::GitHub { .. } => None,
+                _ => Some(Category::Network),
+            };
+        }
+        None
+    })
+}
+
+/// Dirty-worktree refusals already explain how to proceed, so show just that explanation
+/// (or libgit2's message without its class and code suffix) rather than the full chain
+fn dirty_worktree_message(err: &anyhow::Error) -> String {
+    err.chain()
+        .find_map(|cause| match cause.downcast_ref::<GitError>() {
+            Some(GitError::DirtyWorktree(message)) => Some(message.clone()),
+            _ => cause
+                .downcast_ref::<git2::Error>()
+                .map(|git_error| git_error.message().to_string()),
+        })
+        .unwrap_or_else(|| format!("{err:#}"))
+}
+
+fn categorize_git_error(err: &GitError) -> Option<Category> {
+    match err {
+        GitError::NotARepo(_) => Some(Category::NotARepo),
+        GitError::Conflict(_) => Some(Category::Conflict),
+        GitError::DirtyWorktree(_) => Some(Category::DirtyWorktree),
+        GitError::AuthRequired(_) => Some(Category::Auth),
+        GitError::Network(_) => Some(Category::Network),
+        GitError::Locked(_) => Some(Category::Locked),
+        GitError::UnsupportedOnBareRepo(_) => Some(Category::BareRepo),
+        GitError::NotFound(_) | GitError::UnbornBranch(_) | GitError::Other(_) => None,
+    }
+}
+
+fn categorize_git2(err: &git2::Error) -> Option<Category> {
+    use git2::{ErrorClass, ErrorCode};
+
+    match (err.code(), err.class()) {
+        (ErrorCode::Auth, _) | (ErrorCode::Certificate, _) => Some(Category::Auth),
+        (ErrorCode::Conflict | ErrorCode::MergeConflict | ErrorCode::Unmerged, _) => {
+            Some(Category::Conflict)
+        }
+        (ErrorCode::Uncommitted | ErrorCode::Modified | ErrorCode::IndexDirty, _) => {
+            Some(Category::DirtyWorktree)
+        }
+        (ErrorCode::Locked, _) => Some(Category::Locked),
+        (ErrorCode::BareRepo, _) => Some(Category::BareRepo),
+        (ErrorCode::NotFound, ErrorClass::Repository) => Some(Category::NotARepo),
+        (_, ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssh | ErrorClass::Ssl) => {
+            Some(Category::Network)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitxError;
+    use crate::git::{GitError, GitRepo};
+
+    #[test]
+    fn opening_outside_a_repo_maps_to_not_a_repo() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let err: GitxError = GitRepo::open(temp_dir.path()).err().unwrap().into();
+
+        assert!(matches!(err, GitxError::NotARepo(_)));
+        assert_eq!(err.exit_code(), 3);
+    }
+
+    #[test]
+    fn git2_codes_map_to_categories() {
+        let auth = git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Ssh,
+            "authentication required",
+        );
+        assert!(matches!(GitxError::from(auth), GitxError::Auth(_)));
+
+        let network = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "failed to resolve address",
+        );
+        assert!(matches!(GitxError::from(network), GitxError::Network(_)));
+
+        let conflict = git2::Error::new(
+            git2::ErrorCode::MergeConflict,
+            git2::ErrorClass::Merge,
+            "conflicts",
+        );
+        assert_eq!(GitxError::from(conflict).exit_code(), 4);
+
+        let locked = git2::Error::new(
+            git2::ErrorCode::Locked,
+            git2::ErrorClass::Index,
+            "the index is locked",
+        );
+        assert_eq!(GitxError::from(locked).exit_code(), 8);
+
+        let dirty = git2::Error::new(
+            git2::ErrorCode::Uncommitted,
+            git2::ErrorClass::Checkout,
+            "Commit your changes first",
+        );
+        match GitxError::from(dirty) {
+            GitxError::DirtyWorktree(message) => assert_eq!(message, "Commit your changes first"),
+            other => panic!("expected DirtyWorktree, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn git_layer_errors_keep_their_category_through_context() {
+        let dirty = GitError::DirtyWorktree("Commit your changes first".to_string());
+        let err: GitxError = anyhow::Error::new(dirty).context("Failed to switch").into();
+        match err {
+            GitxError::DirtyWorktree(message) => assert_eq!(message, "Commit your changes first"),
+            other => panic!("expected DirtyWorktree, got {other:?}"),
+        }
+
+        let conflict = GitError::Conflict(anyhow::anyhow!("conflicts"));
+        assert_eq!(GitxError::from(conflict).exit_code(), 4);
+
+        let bare = GitError::UnsupportedOnBareRepo(anyhow::anyhow!("no working tree"));
+        let err: GitxError = anyhow::Error::new(bare).context("Failed to add").into();
+        assert_eq!(err.exit_code(), 2);
+    }
+
+    #[test]
+    fn typed_errors_survive_a_round_trip_through_anyhow() {
+        let err: anyhow::Error = GitxError::UserAbort("Aborted".to_string()).into();
+        let err: GitxError = err.context("Outer context").into();
+
+        assert!(matches!(err, GitxError::UserAbort(_)));
+    }
+
+    #[test]
+    fn cancelled_prompts_map_to_user_abort() {
+        let err: GitxError = inquire::InquireError::OperationCanceled.into();
+        assert_eq!(err.exit_code(), 130);
+    }
+}
@@ -1,2 +0,0 @@
-pub mod operations;
-pub mod tracking;
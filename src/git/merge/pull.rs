@@ -1,3 +0,0 @@
-// Pull functionality is implemented in remotes/sync.rs
-// This module exists for domain organization purposes
-// Pull operations combine remote sync (fetch) with merge strategies
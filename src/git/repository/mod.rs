@@ -1,2 +0,0 @@
-pub mod core;
-pub mod signature;
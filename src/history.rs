@@ -0,0 +1,199 @@
+//! Audit log of state-mutating `xg` operations, stored as JSON lines in `.git/xgit/history.log`.
+
+use crate::git::GitRepo;
+use crate::github::types::now_timestamp;
+use anyhow::{Context, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    /// Command line arguments after `xg`
+    pub command: Vec<String>,
+    pub success: bool,
+    pub ref_changes: Vec<RefChange>,
+}
+
+/// A reference that moved during an operation; `None` means it did not exist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefChange {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn for_repo(repo: &GitRepo) -> Self {
+        Self::new(repo.git_dir().join("xgit").join("history.log"))
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create history directory '{}'",
+                parent.display()
+            ))?;
+        }
+
+        let mut line = serde_json::to_string(entry).context("Failed to serialize history entry")?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .context(format!(
+                "Failed to write history log '{}'",
+                self.path.display()
+            ))
+    }
+
+    /// All entries, oldest first. Lines that fail to parse are skipped.
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>, Error> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&self.path).context(format!(
+            "Failed to read history log '{}'",
+            self.path.display()
+        ))?;
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    pub fn last_entry(&self) -> Result<Option<HistoryEntry>, Error> {
+        Ok(self.entries()?.pop())
+    }
+}
+
+/// Captures refs before a mutating command runs so the change can be logged afterwards
+pub struct OperationRecorder {
+    repo: GitRepo,
+    command: Vec<String>,
+    before: BTreeMap<String, String>,
+}
+
+impl OperationRecorder {
+    /// Start recording, or `None` outside a repository
+    pub fn begin(command: Vec<String>) -> Option<Self> {
+        let repo = GitRepo::open(".").ok()?;
+        let before = repo.snapshot_refs().ok()?;
+        Some(Self {
+            repo,
+            command,
+            before,
+        })
+    }
+
+    pub fn finish(self, success: bool) -> Result<(), Error> {
+        let after = self.repo.snapshot_refs()?;
+        let entry = HistoryEntry {
+            timestamp: now_timestamp(),
+            command: self.command,
+            success,
+            ref_changes: diff_refs(&self.before, &after),
+        };
+        HistoryLog::for_repo(&self.repo).append(&entry)
+    }
+}
+
+pub fn diff_refs(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> Vec<RefChange> {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| before.get(*name) != after.get(*name))
+        .map(|name| RefChange {
+            name: name.clone(),
+            before: before.get(name).cloned(),
+            after: after.get(name).cloned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_refs, HistoryEntry, HistoryLog, RefChange};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn diff_refs_reports_created_moved_and_deleted_refs() {
+        let before = BTreeMap::from([
+            ("refs/heads/main".to_string(), "aaa".to_string()),
+            ("refs/heads/old".to_string(), "bbb".to_string()),
+            ("refs/heads/same".to_string(), "ccc".to_string()),
+        ]);
+        let after = BTreeMap::from([
+            ("refs/heads/main".to_string(), "ddd".to_string()),
+            ("refs/heads/new".to_string(), "eee".to_string()),
+            ("refs/heads/same".to_string(), "ccc".to_string()),
+        ]);
+
+        let names: Vec<(String, Option<String>, Option<String>)> = diff_refs(&before, &after)
+            .into_iter()
+            .map(|change| (change.name, change.before, change.after))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (
+                    "refs/heads/main".to_string(),
+                    Some("aaa".to_string()),
+                    Some("ddd".to_string())
+                ),
+                ("refs/heads/new".to_string(), None, Some("eee".to_string())),
+                ("refs/heads/old".to_string(), Some("bbb".to_string()), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn history_log_appends_and_reads_entries() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let log = HistoryLog::new(temp_dir.path().join("xgit").join("history.log"));
+        assert!(log.entries().unwrap().is_empty());
+
+        let entry = HistoryEntry {
+            timestamp: 1,
+            command: vec!["branch".to_string(), "--prune-merged".to_string()],
+            success: true,
+            ref_changes: vec![RefChange {
+                name: "refs/heads/old".to_string(),
+                before: Some("abc".to_string()),
+                after: None,
+            }],
+        };
+        log.append(&entry).unwrap();
+        log.append(&HistoryEntry {
+            timestamp: 2,
+            ..entry.clone()
+        })
+        .unwrap();
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry);
+        assert_eq!(log.last_entry().unwrap().unwrap().timestamp, 2);
+    }
+}
@@ -0,0 +1,81 @@
+//! English catalog. Every message key must be defined here; other locales fall back to it.
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("common.aborted", "Aborted"),
+    ("cli.no_command", "No command provided"),
+    (
+        "cli.command_not_allowed",
+        "Command '{command}' is not allowed. Use 'git {command}' directly if needed.",
+    ),
+    ("dry_run.header", "Dry run - no changes will be made"),
+    (
+        "confirm.non_interactive",
+        "Cannot confirm \"{prompt}\" without an interactive terminal. Re-run with --yes to proceed.",
+    ),
+    (
+        "confirm.cancelled",
+        "Confirmation prompt was cancelled or failed",
+    ),
+    ("branch.none_found", "No branches found"),
+    ("branch.select_prompt", "Select a branch:"),
+    ("branch.switched", "Switched to branch: {branch}"),
+    (
+        "branch.switch_failed",
+        "Error switching to branch '{branch}': {error}",
+    ),
+    ("branch.selection_cancelled", "Selection cancelled: {error}"),
+    ("branch.list_failed", "Error getting branches: {error}"),
+    ("prune.finding", "Finding merged branches to prune..."),
+    (
+        "prune.finding_dry_run",
+        "Finding branches that would be pruned (dry run)...",
+    ),
+    ("prune.none_found", "No merged branches found to prune"),
+    (
+        "prune.fetch_warning",
+        "Warning: Failed to refresh remote-tracking branches before squash-merge checks: {error}",
+    ),
+    (
+        "prune.merge_status_warning",
+        "Warning: Could not determine merge status for '{branch}': {error}",
+    ),
+    (
+        "prune.pr_state_warning",
+        "Warning: Could not refresh PR state for '{branch}': {error}",
+    ),
+    ("prune.reason_merged_local", "merged into local trunk"),
+    (
+        "prune.reason_merged_pr",
+        "PR #{number} merged to {trunk} and remote head deleted",
+    ),
+    (
+        "prune.would_delete",
+        "The following {count} branches would be deleted:",
+    ),
+    (
+        "prune.dry_run_hint",
+        "Run without --dry-run to actually delete these branches",
+    ),
+    (
+        "prune.found",
+        "Found {count} merged branches. Select which ones to delete:",
+    ),
+    ("prune.select_prompt", "Select branches to delete:"),
+    ("prune.none_selected", "No branches selected for deletion"),
+    ("prune.confirm_delete", "Delete {count} local branches?"),
+    (
+        "prune.confirm_delete_remote",
+        "Delete {count} local branches and their remote branches?",
+    ),
+    ("prune.deleting", "Deleting {count} selected branches:"),
+    ("prune.deleted_branch", "Deleted {branch}"),
+    ("prune.delete_failed", "Failed to delete {branch}: {error}"),
+    ("prune.also_remote", "and {branch}"),
+    ("prune.deleted_remote_branch", "Deleted remote branch {branch}"),
+    (
+        "prune.delete_remote_failed",
+        "Failed to delete remote branch {branch}: {error}",
+    ),
+    ("prune.summary", "Deleted {count} branches"),
+    ("prune.summary_failed", ", {count} failed"),
+];
@@ -0,0 +1,140 @@
+//! Message catalog for user-facing CLI strings.
+//!
+//! Messages are looked up by key in the active locale's catalog and fall back to
+//! English when a translation is missing. Placeholders use `{name}` syntax.
+
+mod en;
+mod zh_cn;
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    /// Parse a locale identifier such as `zh_CN.UTF-8`, `zh-CN` or `en_US`.
+    ///
+    /// Returns `None` for locales without a catalog so callers can keep looking.
+    pub fn parse(value: &str) -> Option<Locale> {
+        let language = value
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .replace('-', "_");
+
+        match language.as_str() {
+            "c" | "posix" => Some(Locale::En),
+            lang if lang == "en" || lang.starts_with("en_") => Some(Locale::En),
+            lang if lang == "zh" || lang.starts_with("zh_cn") || lang.starts_with("zh_hans") => {
+                Some(Locale::ZhCn)
+            }
+            _ => None,
+        }
+    }
+
+    fn messages(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::ZhCn => zh_cn::MESSAGES,
+        }
+    }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        value if value == Locale::ZhCn as u8 => Locale::ZhCn,
+        _ => Locale::En,
+    }
+}
+
+/// Look up a message in the active locale
+pub fn t(key: &'static str) -> &'static str {
+    lookup(locale(), key)
+}
+
+/// Look up a message in the active locale and fill in its `{name}` placeholders
+pub fn tf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    format_message(t(key), args)
+}
+
+fn lookup(locale: Locale, key: &'static str) -> &'static str {
+    find(locale.messages(), key)
+        .or_else(|| find(Locale::En.messages(), key))
+        .unwrap_or(key)
+}
+
+fn find(messages: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    messages
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, message)| *message)
+}
+
+fn format_message(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{en, format_message, lookup, zh_cn, Locale};
+
+    fn placeholders(message: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = message
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split('}').next())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn parse_recognizes_common_locale_formats() {
+        assert_eq!(Locale::parse("zh_CN.UTF-8"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("en_US.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::parse("C"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr_FR.UTF-8"), None);
+    }
+
+    #[test]
+    fn translations_match_english_keys_and_placeholders() {
+        for (key, message) in zh_cn::MESSAGES {
+            let english = en::MESSAGES
+                .iter()
+                .find(|(name, _)| name == key)
+                .unwrap_or_else(|| panic!("'{key}' is missing from the English catalog"));
+            assert_eq!(placeholders(message), placeholders(english.1), "{key}");
+        }
+    }
+
+    #[test]
+    fn lookup_falls_back_to_english_then_key() {
+        assert_eq!(lookup(Locale::ZhCn, "common.aborted"), "已取消");
+        assert_eq!(lookup(Locale::En, "common.aborted"), "Aborted");
+        assert_eq!(lookup(Locale::ZhCn, "missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn format_message_replaces_placeholders() {
+        let message = format_message(
+            "Delete {count} local branches from {remote}?",
+            &[("count", &3), ("remote", &"origin")],
+        );
+        assert_eq!(message, "Delete 3 local branches from origin?");
+    }
+}
@@ -0,0 +1,75 @@
+//! Simplified Chinese catalog. Keys missing here fall back to English.
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("common.aborted", "已取消"),
+    ("cli.no_command", "未提供命令"),
+    (
+        "cli.command_not_allowed",
+        "不允许使用命令 '{command}'。如有需要，请直接使用 'git {command}'。",
+    ),
+    ("dry_run.header", "试运行 - 不会进行任何更改"),
+    (
+        "confirm.non_interactive",
+        "没有交互式终端，无法确认 \"{prompt}\"。请使用 --yes 重新运行以继续。",
+    ),
+    ("confirm.cancelled", "确认提示已取消或失败"),
+    ("branch.none_found", "未找到任何分支"),
+    ("branch.select_prompt", "选择一个分支："),
+    ("branch.switched", "已切换到分支：{branch}"),
+    (
+        "branch.switch_failed",
+        "切换到分支 '{branch}' 时出错：{error}",
+    ),
+    ("branch.selection_cancelled", "已取消选择：{error}"),
+    ("branch.list_failed", "获取分支列表时出错：{error}"),
+    ("prune.finding", "正在查找可清理的已合并分支..."),
+    (
+        "prune.finding_dry_run",
+        "正在查找将被清理的分支（试运行）...",
+    ),
+    ("prune.none_found", "没有找到可清理的已合并分支"),
+    (
+        "prune.fetch_warning",
+        "警告：在检查 squash 合并之前刷新远程跟踪分支失败：{error}",
+    ),
+    (
+        "prune.merge_status_warning",
+        "警告：无法确定 '{branch}' 的合并状态：{error}",
+    ),
+    (
+        "prune.pr_state_warning",
+        "警告：无法刷新 '{branch}' 的 PR 状态：{error}",
+    ),
+    ("prune.reason_merged_local", "已合并到本地主干"),
+    (
+        "prune.reason_merged_pr",
+        "PR #{number} 已合并到 {trunk}，且远程分支已删除",
+    ),
+    ("prune.would_delete", "以下 {count} 个分支将被删除："),
+    (
+        "prune.dry_run_hint",
+        "去掉 --dry-run 重新运行即可真正删除这些分支",
+    ),
+    (
+        "prune.found",
+        "找到 {count} 个已合并分支。请选择要删除的分支：",
+    ),
+    ("prune.select_prompt", "选择要删除的分支："),
+    ("prune.none_selected", "未选择要删除的分支"),
+    ("prune.confirm_delete", "删除 {count} 个本地分支？"),
+    (
+        "prune.confirm_delete_remote",
+        "删除 {count} 个本地分支及其远程分支？",
+    ),
+    ("prune.deleting", "正在删除选中的 {count} 个分支："),
+    ("prune.deleted_branch", "已删除 {branch}"),
+    ("prune.delete_failed", "删除 {branch} 失败：{error}"),
+    ("prune.also_remote", "以及 {branch}"),
+    ("prune.deleted_remote_branch", "已删除远程分支 {branch}"),
+    (
+        "prune.delete_remote_failed",
+        "删除远程分支 {branch} 失败：{error}",
+    ),
+    ("prune.summary", "已删除 {count} 个分支"),
+    ("prune.summary_failed", "，{count} 个失败"),
+];
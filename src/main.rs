@@ -1,48 +1,591 @@
 #![allow(dead_code)]
 
-mod ai;
 mod cli;
 mod commands;
-mod git;
-mod github;
+mod error;
+mod history;
+mod i18n;
+mod profile;
 mod tui;
 
 #[cfg(test)]
-mod test_utils;
+use xgit_core::test_utils;
+use xgit_core::{ai, config, forge, git, github};
 
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{
+    ChangelogCommand, Cli, Commands, HooksCommand, IgnoreCommand, PrCommand, RemoteCommand,
+    StackCommand, StashCommand, StatsCommand, TagCommand, VersionCommand, WorkspaceCommand,
+};
 use console::style;
+use error::GitxError;
+use git::GitRepo;
+use github::types::PrListFilter;
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    let profiler = cli.profile.then(profile::Profiler::install);
+    i18n::set_locale(locale_from_env_or_config());
+    let assume_yes = cli.yes || assume_yes_from_env_or_config();
+    tui::confirm::set_assume_yes(assume_yes);
+    tui::emoji::set_enabled(load_xgit_config().emoji());
 
     let result = match &cli.command {
+        Commands::Batch {
+            json,
+            stop_on_error,
+        } => {
+            commands::batch::handle_batch(*json, *stop_on_error, |args| {
+                run_batch_command(args, assume_yes, cli.dry_run)
+            })
+            .await
+        }
+        _ => execute(&cli, std::env::args().skip(1).collect()).await,
+    };
+
+    if let Some(profiler) = &profiler {
+        profiler.report();
+    }
+    if let Err(e) = result {
+        // Passthrough commands and plugins have already reported their own failure
+        if !matches!(e, GitxError::Subprocess { .. }) {
+            eprintln!("{} {}", style("✗").red().bold(), style(&e).red());
+        }
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Parse and run one `xg batch` line; the batch's own `--yes`/`--dry-run` apply to every line
+async fn run_batch_command(
+    args: Vec<String>,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<(), GitxError> {
+    let mut cli = Cli::try_parse_from(std::iter::once("xg".to_string()).chain(args.clone()))
+        .map_err(|e| GitxError::Usage(e.to_string().trim_end().to_string()))?;
+    cli.dry_run |= dry_run;
+    tui::confirm::set_assume_yes(assume_yes || cli.yes);
+    execute(&cli, args).await
+}
+
+/// Run a single parsed command, recording it in the operation history if it mutates the repo
+async fn execute(cli: &Cli, invocation: Vec<String>) -> Result<(), GitxError> {
+    let recorder = if !cli.dry_run && is_mutating(&cli.command) {
+        history::OperationRecorder::begin(invocation)
+    } else {
+        None
+    };
+    let started = std::time::Instant::now();
+
+    let result = match &cli.command {
+        Commands::Batch { .. } => Err(GitxError::Usage(
+            "`xg batch` cannot be nested inside a batch".to_string(),
+        )),
+        Commands::Add { interactive, args } => {
+            commands::add::handle_add(*interactive, args, cli.dry_run)
+        }
+        Commands::Branch {
+            new: Some(task), ..
+        } => commands::branch_new::handle_branch_new(Some(task), cli.dry_run),
         Commands::Branch {
+            format: Some(format),
+            refresh,
+            ..
+        } => commands::branch_stats::show_branch_format(format, *refresh).await,
+        Commands::Branch {
+            new: None,
             prune_merged,
+            remote,
             stats,
-            dry_run,
-        } => commands::branch::handle_branch(*prune_merged, *stats, *dry_run).await,
-        Commands::Commit { args } => commands::commit::handle_commit(args),
-        Commands::Diff { repair } => commands::diff::handle_diff(repair).await,
-        Commands::Git { args } => handle_external_command(args),
+            porcelain,
+            format: None,
+            watch,
+            interval,
+            refresh,
+        } => {
+            commands::branch::handle_branch(
+                *prune_merged,
+                *remote,
+                *stats,
+                *porcelain,
+                watch_interval(*watch, *interval),
+                *refresh,
+                cli.dry_run,
+            )
+            .await
+        }
+        Commands::Switch {
+            branch,
+            start_point,
+            create,
+            detach,
+            orphan,
+            force,
+            autostash,
+        } => match (branch, orphan) {
+            (Some(branch), None) if !*detach => commands::switch::handle_switch(
+                branch,
+                start_point.as_deref(),
+                *create,
+                *force,
+                *autostash,
+                cli.dry_run,
+            ),
+            _ => commands::switch::handle_switch_without_branch(
+                branch.as_deref(),
+                orphan.as_deref(),
+                *force,
+                cli.dry_run,
+            ),
+        },
+        Commands::Rebase {
+            interactive,
+            continue_,
+            skip,
+            abort,
+            args,
+        } => commands::rebase::handle_rebase(
+            args,
+            *interactive,
+            *continue_,
+            *skip,
+            *abort,
+            cli.dry_run,
+        ),
+        Commands::CherryPick { args } => {
+            commands::cherry_pick::handle_cherry_pick(args, cli.dry_run)
+        }
+        Commands::Sync { prune_merged } => {
+            commands::sync::handle_sync(*prune_merged, cli.dry_run).await
+        }
+        Commands::Stash { command, args } => match command {
+            None if !args.is_empty() => {
+                commands::git_passthrough::git_passthrough("stash", args, cli.dry_run)
+            }
+            None => commands::stash::handle_stash_save(None, false, cli.dry_run),
+            Some(StashCommand::Save {
+                message,
+                include_untracked,
+            }) => commands::stash::handle_stash_save(
+                message.as_deref(),
+                *include_untracked,
+                cli.dry_run,
+            ),
+            Some(StashCommand::List) => commands::stash::handle_stash_list(),
+            Some(StashCommand::Pop { index }) => {
+                commands::stash::handle_stash_apply(*index, true, cli.dry_run)
+            }
+            Some(StashCommand::Apply { index }) => {
+                commands::stash::handle_stash_apply(*index, false, cli.dry_run)
+            }
+            Some(StashCommand::Drop { index }) => {
+                commands::stash::handle_stash_drop(*index, cli.dry_run)
+            }
+            Some(StashCommand::External(args)) => {
+                commands::git_passthrough::git_passthrough("stash", args, cli.dry_run)
+            }
+        },
+        Commands::Tag { command, args } => match command {
+            None if !args.is_empty() => {
+                commands::git_passthrough::git_passthrough("tag", args, cli.dry_run)
+            }
+            None | Some(TagCommand::List) => commands::tag::handle_tag_list(),
+            Some(TagCommand::Create {
+                name,
+                target,
+                message,
+            }) => commands::tag::handle_tag_create(name, target, message.as_deref(), cli.dry_run),
+            Some(TagCommand::Delete { names }) => {
+                commands::tag::handle_tag_delete(names, cli.dry_run)
+            }
+            Some(TagCommand::Push { names, remote }) => {
+                commands::tag::handle_tag_push(remote, names, cli.dry_run)
+            }
+            Some(TagCommand::External(args)) => {
+                commands::git_passthrough::git_passthrough("tag", args, cli.dry_run)
+            }
+        },
+        Commands::Remote { command, args } => match command {
+            None if !args.is_empty() => {
+                commands::git_passthrough::git_passthrough("remote", args, cli.dry_run)
+            }
+            None | Some(RemoteCommand::List) => commands::remote::handle_remote_list(),
+            Some(RemoteCommand::Add { name, url }) => {
+                commands::remote::handle_remote_add(name, url, cli.dry_run)
+            }
+            Some(RemoteCommand::Remove { name }) => {
+                commands::remote::handle_remote_remove(name, cli.dry_run)
+            }
+            Some(RemoteCommand::Rename { old_name, new_name }) => {
+                commands::remote::handle_remote_rename(old_name, new_name, cli.dry_run)
+            }
+            Some(RemoteCommand::External(args)) => {
+                commands::git_passthrough::git_passthrough("remote", args, cli.dry_run)
+            }
+        },
+        Commands::Commit { args } => commands::commit::handle_commit(args, cli.dry_run).await,
+        Commands::Push { args } => commands::push::handle_push(args, cli.dry_run),
+        Commands::Pull {
+            rebase,
+            no_rebase,
+            ff_only,
+            args,
+        } => commands::pull::handle_pull(args, *rebase, *no_rebase, *ff_only, cli.dry_run).await,
+        Commands::Diff { repair } => commands::diff::handle_diff(repair, cli.dry_run).await,
+        Commands::Pr { command } => match command {
+            PrCommand::Create { base, draft } => {
+                commands::pr::handle_pr_create(base.as_deref(), *draft, cli.dry_run).await
+            }
+            PrCommand::List {
+                mine,
+                review_requested,
+                labels,
+            } => {
+                let filter = PrListFilter {
+                    mine: *mine,
+                    review_requested: *review_requested,
+                    labels: labels.clone(),
+                };
+                commands::pr::handle_pr_list(&filter, cli.dry_run).await
+            }
+            PrCommand::Merge {
+                strategy,
+                wait,
+                keep_branch,
+            } => commands::pr::handle_pr_merge(strategy, *wait, *keep_branch, cli.dry_run).await,
+        },
+        Commands::Open {
+            target,
+            branch,
+            print,
+        } => commands::open::handle_open(target.as_deref(), *branch, *print, cli.dry_run).await,
+        Commands::Review {
+            branch,
+            min_severity,
+            json,
+        } => commands::review::handle_review(branch.as_deref(), min_severity, *json),
+        Commands::ShowDiff {
+            rev,
+            side_by_side,
+            no_word_diff,
+        } => commands::show_diff::handle_show_diff(rev.as_deref(), *side_by_side, *no_word_diff),
+        Commands::Log {
+            format,
+            max_count,
+            skip,
+            author,
+            since,
+            until,
+            paths,
+            interactive,
+            follow,
+            args,
+        } => {
+            let filters = commands::log::LogFilters {
+                max_count: *max_count,
+                skip: skip.unwrap_or(0),
+                author: author.as_deref(),
+                since: since.as_deref(),
+                until: until.as_deref(),
+                paths,
+            };
+            if *follow {
+                commands::log::handle_log_follow(&filters, args)
+            } else if *interactive {
+                commands::log::handle_log_interactive(&filters, args)
+            } else {
+                commands::log::handle_log(format.as_deref(), &filters, args)
+            }
+        }
+        Commands::Status {
+            watch,
+            interval,
+            args,
+        } => commands::status::handle_status(args, watch_interval(*watch, *interval)).await,
+        Commands::Stats { command } => match command {
+            StatsCommand::Me { author, weeks } => {
+                commands::stats::handle_stats_me(author.as_deref(), *weeks)
+            }
+            StatsCommand::Repo { weeks, top, json } => {
+                commands::stats::handle_stats_repo(*weeks, *top, *json)
+            }
+        },
+        Commands::Stack { command } => match command {
+            StackCommand::Create { name } => {
+                commands::stack::handle_stack_create(name, cli.dry_run)
+            }
+            StackCommand::List => commands::stack::handle_stack_list(),
+            StackCommand::Restack => commands::stack::handle_stack_restack(cli.dry_run),
+            StackCommand::Submit { draft } => {
+                commands::stack::handle_stack_submit(*draft, cli.dry_run).await
+            }
+        },
+        Commands::Up { steps } => commands::stack::handle_up(*steps, cli.dry_run),
+        Commands::Down { steps } => commands::stack::handle_down(*steps, cli.dry_run),
+        Commands::Workspace { command } => match command {
+            WorkspaceCommand::Status => commands::workspace::handle_workspace_status().await,
+            WorkspaceCommand::Fetch => {
+                commands::workspace::handle_workspace_fetch(cli.dry_run).await
+            }
+            WorkspaceCommand::Stats => commands::workspace::handle_workspace_stats().await,
+            WorkspaceCommand::Add { path } => {
+                commands::workspace::handle_workspace_add(path, cli.dry_run)
+            }
+            WorkspaceCommand::Remove { path } => {
+                commands::workspace::handle_workspace_remove(path, cli.dry_run)
+            }
+            WorkspaceCommand::List => commands::workspace::handle_workspace_list(),
+        },
+        Commands::Ignore { command } => match command {
+            IgnoreCommand::Init { templates } => {
+                commands::ignore::handle_ignore_init(templates, cli.dry_run)
+            }
+            IgnoreCommand::List => commands::ignore::handle_ignore_list(),
+        },
+        Commands::Hooks { command } => match command {
+            HooksCommand::InstallAi { force } => {
+                commands::hooks::handle_hooks_install_ai(*force, cli.dry_run)
+            }
+            HooksCommand::PrepareCommitMsg { file, source, .. } => {
+                commands::hooks::handle_prepare_commit_msg(file, source.as_deref())
+            }
+            HooksCommand::CommitMsg { file } => commands::hooks::handle_commit_msg(file),
+        },
+        Commands::LintCommits { range } => commands::lint_commits::handle_lint_commits(range),
+        Commands::Changelog { command } => match command {
+            ChangelogCommand::Update { unreleased } => {
+                commands::changelog::handle_changelog_update(*unreleased, cli.dry_run).await
+            }
+            ChangelogCommand::Generate {
+                from,
+                to,
+                template,
+                no_ai,
+                stdout,
+            } => {
+                commands::changelog::handle_changelog_generate(
+                    from.as_deref(),
+                    to,
+                    template.as_deref(),
+                    *no_ai,
+                    *stdout,
+                    cli.dry_run,
+                )
+                .await
+            }
+        },
+        Commands::Version { command } => match command {
+            None => commands::git_passthrough::git_passthrough("version", &[], cli.dry_run),
+            Some(VersionCommand::Bump {
+                major,
+                minor,
+                patch,
+            }) => {
+                let level = if *major {
+                    Some(commands::version::Bump::Major)
+                } else if *minor {
+                    Some(commands::version::Bump::Minor)
+                } else if *patch {
+                    Some(commands::version::Bump::Patch)
+                } else {
+                    None
+                };
+                commands::version::handle_version_bump(level, cli.dry_run)
+            }
+        },
+        Commands::Blame { rev, no_pr, args } => {
+            commands::blame::handle_blame(args, rev.as_deref(), *no_pr).await
+        }
+        Commands::Owners { path, top } => commands::owners::handle_owners(path.as_deref(), *top),
+        Commands::Doctor => commands::doctor::handle_doctor().await,
+        Commands::History { limit } => commands::history::handle_history(*limit),
+        Commands::Undo => commands::undo::handle_undo(cli.dry_run),
+        Commands::Git { args } => handle_external_command(args, cli.dry_run),
+        Commands::External(args) => match commands::plugin::find_plugin(&args[0]) {
+            Some(plugin) => commands::plugin::run_plugin(&plugin, &args[1..], cli.dry_run),
+            None => handle_external_command(args, cli.dry_run),
+        },
     };
 
-    if let Err(e) = result {
-        eprintln!(
-            "{} {}",
-            style("✗").red().bold(),
-            style(format!("{e:#}")).red()
-        );
-        std::process::exit(1);
+    if let Some(recorder) = recorder {
+        // The audit log is best-effort and must never change the command's outcome
+        let _ = recorder.finish(result.is_ok());
     }
+
+    if let Some(operation) = (!cli.dry_run)
+        .then(|| network_operation(&cli.command))
+        .flatten()
+    {
+        let elapsed = started.elapsed();
+        if tui::desktop_notify::NotifySettings::from_env_or_config().should_notify(elapsed) {
+            tui::desktop_notify::notify_finished(operation, result.is_ok(), elapsed);
+        }
+    }
+
+    result
+}
+
+/// Commands that talk to a remote and may take long enough to warrant a desktop notification
+fn network_operation(command: &Commands) -> Option<&str> {
+    match command {
+        Commands::Diff { .. } => Some("diff"),
+        Commands::Pr {
+            command: PrCommand::Create { .. },
+        } => Some("pr create"),
+        Commands::Pr {
+            command: PrCommand::Merge { .. },
+        } => Some("pr merge"),
+        Commands::Stack {
+            command: StackCommand::Submit { .. },
+        } => Some("stack submit"),
+        Commands::Workspace {
+            command: WorkspaceCommand::Fetch,
+        } => Some("ws fetch"),
+        Commands::Sync { .. } => Some("sync"),
+        Commands::Push { .. } => Some("push"),
+        Commands::Pull { .. } => Some("pull"),
+        Commands::Tag {
+            command: Some(TagCommand::Push { .. }),
+            ..
+        } => Some("tag push"),
+        Commands::Git { args } | Commands::External(args) => args
+            .first()
+            .map(String::as_str)
+            .filter(|subcommand| matches!(*subcommand, "push" | "fetch")),
+        _ => None,
+    }
+}
+
+fn watch_interval(watch: bool, interval_secs: u64) -> Option<std::time::Duration> {
+    watch.then(|| std::time::Duration::from_secs(interval_secs.max(1)))
+}
+
+/// Commands that can change refs, the index or the working tree, and so get recorded in history
+fn is_mutating(command: &Commands) -> bool {
+    match command {
+        Commands::Branch {
+            prune_merged,
+            stats,
+            porcelain,
+            format,
+            ..
+        } => *prune_merged || !(*stats || *porcelain || format.is_some()),
+        Commands::Add { .. }
+        | Commands::Switch { .. }
+        | Commands::Up { .. }
+        | Commands::Down { .. }
+        | Commands::Rebase { .. }
+        | Commands::CherryPick { .. }
+        | Commands::Sync { .. }
+        | Commands::Commit { .. }
+        | Commands::Push { .. }
+        | Commands::Pull { .. }
+        | Commands::Diff { .. }
+        | Commands::Undo => true,
+        Commands::Ignore { command } => matches!(command, IgnoreCommand::Init { .. }),
+        Commands::Hooks { command } => matches!(command, HooksCommand::InstallAi { .. }),
+        Commands::Stash { command, .. } => match command {
+            Some(StashCommand::List) => false,
+            Some(StashCommand::External(args)) => {
+                !commands::git_passthrough::is_read_only("stash", args)
+            }
+            _ => true,
+        },
+        Commands::Tag { command, args } => match command {
+            None => !commands::git_passthrough::is_read_only("tag", args),
+            Some(TagCommand::List) => false,
+            Some(TagCommand::External(args)) => {
+                !commands::git_passthrough::is_read_only("tag", args)
+            }
+            _ => true,
+        },
+        Commands::Remote { command, args } => match command {
+            None => !commands::git_passthrough::is_read_only("remote", args),
+            Some(RemoteCommand::List) => false,
+            Some(RemoteCommand::External(args)) => {
+                !commands::git_passthrough::is_read_only("remote", args)
+            }
+            _ => true,
+        },
+        Commands::Changelog { command } => {
+            !matches!(command, ChangelogCommand::Generate { stdout: true, .. })
+        }
+        Commands::Version { command } => command.is_some(),
+        Commands::Stack { command } => !matches!(command, StackCommand::List),
+        // Creating pushes the branch; checking out the chosen pull request switches branches;
+        // merging deletes the branch
+        Commands::Pr { command } => matches!(
+            command,
+            PrCommand::Create { .. } | PrCommand::List { .. } | PrCommand::Merge { .. }
+        ),
+        Commands::ShowDiff { .. }
+        | Commands::Review { .. }
+        | Commands::LintCommits { .. }
+        | Commands::Log { .. }
+        | Commands::Status { .. }
+        | Commands::Stats { .. }
+        | Commands::Workspace { .. }
+        | Commands::Blame { .. }
+        | Commands::Open { .. }
+        | Commands::Owners { .. }
+        | Commands::Doctor
+        | Commands::History { .. }
+        | Commands::Batch { .. } => false,
+        Commands::Git { args } | Commands::External(args) => {
+            args.split_first().map_or(false, |(subcommand, rest)| {
+                !commands::git_passthrough::is_read_only(subcommand, rest)
+            })
+        }
+    }
+}
+
+/// Non-interactive environments can opt into `--yes` via env var or git config
+fn assume_yes_from_env_or_config() -> bool {
+    if let Ok(value) = std::env::var("XGIT_ASSUME_YES") {
+        return matches!(value.trim(), "1" | "true" | "yes" | "on");
+    }
+
+    GitRepo::open(".")
+        .ok()
+        .and_then(|repo| repo.get_config_bool("xgit.assumeYes").ok().flatten())
+        .unwrap_or(false)
+}
+
+/// xgit's layered settings; a broken config file is reported but doesn't stop the command
+fn load_xgit_config() -> config::XgitConfig {
+    let repo = GitRepo::open(".").ok();
+    config::XgitConfig::load(repo.as_ref()).unwrap_or_else(|e| {
+        eprintln!("{} {:#}", style("⚠").yellow().bold(), style(e).yellow());
+        config::XgitConfig::default()
+    })
+}
+
+/// `XGIT_LOCALE` wins over `xgit.locale`, which wins over the usual `LC_*`/`LANG` variables
+fn locale_from_env_or_config() -> i18n::Locale {
+    let from_env = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| i18n::Locale::parse(&v))
+    };
+    let from_config = || {
+        GitRepo::open(".")
+            .ok()
+            .and_then(|repo| repo.get_config_string("xgit.locale").ok().flatten())
+            .and_then(|value| i18n::Locale::parse(&value))
+    };
+
+    from_env("XGIT_LOCALE")
+        .or_else(from_config)
+        .or_else(|| from_env("LC_ALL"))
+        .or_else(|| from_env("LC_MESSAGES"))
+        .or_else(|| from_env("LANG"))
+        .unwrap_or(i18n::Locale::En)
 }
 
-fn handle_external_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+fn handle_external_command(args: &[String], dry_run: bool) -> Result<(), GitxError> {
     if args.is_empty() {
-        eprintln!("{} No command provided", style("✗").red().bold());
-        std::process::exit(1);
+        return Err(GitxError::Usage(i18n::t("cli.no_command").to_string()));
     }
 
     let subcommand = &args[0];
@@ -55,24 +598,16 @@ fn handle_external_command(args: &[String]) -> Result<(), Box<dyn std::error::Er
         "log",
         "diff",
         "show",
-        "remote",
         "fetch",
-        "pull",
         "push",
         "checkout",
-        "switch",
         "merge",
-        "rebase",
         "reset",
         "clean",
-        "stash",
-        "tag",
-        "blame",
         "grep",
         "ls-files",
         "describe",
         "reflog",
-        "cherry-pick",
         "revert",
         "bisect",
         "submodule",
@@ -83,14 +618,11 @@ fn handle_external_command(args: &[String]) -> Result<(), Box<dyn std::error::Er
     ];
 
     if ALLOWED_COMMANDS.contains(&subcommand.as_str()) {
-        commands::git_passthrough::git_passthrough(subcommand, remaining_args)
+        commands::git_passthrough::git_passthrough(subcommand, remaining_args, dry_run)
     } else {
-        eprintln!(
-            "{} Command '{}' is not allowed. Use '{}' directly if needed.",
-            style("✗").red().bold(),
-            style(subcommand).yellow(),
-            style(format!("git {subcommand}")).cyan()
-        );
-        std::process::exit(1);
+        Err(GitxError::Usage(i18n::tf(
+            "cli.command_not_allowed",
+            &[("command", subcommand)],
+        )))
     }
 }
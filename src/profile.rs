@@ -0,0 +1,249 @@
+//! `xg --profile`: time every instrumented `GitRepo` and `GitHubClient` call and print where
+//! the invocation spent its time.
+//!
+//! The repository and GitHub layers open a `tracing` span per method; this layer times each
+//! span from creation to close, subtracts the time spent in nested spans to get self time, and
+//! keeps the arguments of the slowest call of every method.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Rows shown in the report; the rest are summarised in one line
+const MAX_ROWS: usize = 25;
+
+#[derive(Debug, Default, Clone)]
+struct SpanStats {
+    calls: usize,
+    total: Duration,
+    self_time: Duration,
+    slowest: Duration,
+    slowest_args: String,
+}
+
+/// Per-span state kept in the registry while the span is open
+struct Timing {
+    started: Instant,
+    children: Duration,
+    args: String,
+}
+
+/// Aggregated span timings for one invocation
+#[derive(Clone, Default)]
+pub struct Profiler {
+    started: Option<Instant>,
+    stats: Arc<Mutex<HashMap<&'static str, SpanStats>>>,
+}
+
+impl Profiler {
+    /// Install the profiler as the global `tracing` subscriber
+    pub fn install() -> Self {
+        let profiler = Self {
+            started: Some(Instant::now()),
+            ..Self::default()
+        };
+        let subscriber = tracing_subscriber::registry().with(profiler.clone());
+        // Only fails if a subscriber is already installed, in which case there is nothing to report
+        let _ = tracing::subscriber::set_global_default(subscriber);
+        profiler
+    }
+
+    /// Print the report to stderr
+    pub fn report(&self) {
+        let elapsed = self.started.map(|started| started.elapsed());
+        eprint!("{}", self.render(elapsed));
+    }
+
+    fn render(&self, elapsed: Option<Duration>) -> String {
+        let mut rows: Vec<(&'static str, SpanStats)> = match self.stats.lock() {
+            Ok(stats) => stats
+                .iter()
+                .map(|(name, stats)| (*name, stats.clone()))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        rows.sort_by(|a, b| b.1.self_time.cmp(&a.1.self_time).then(a.0.cmp(b.0)));
+
+        let calls: usize = rows.iter().map(|(_, stats)| stats.calls).sum();
+        let mut out = String::new();
+        let _ = write!(out, "\nProfile: {calls} instrumented calls");
+        if let Some(elapsed) = elapsed {
+            let _ = write!(out, " in {} wall clock", format_duration(elapsed));
+        }
+        out.push('\n');
+        if rows.is_empty() {
+            return out;
+        }
+
+        let _ = writeln!(
+            out,
+            "{:>9} {:>9} {:>6} {:>9}  operation (slowest call)",
+            "self", "total", "calls", "slowest"
+        );
+        for (name, stats) in rows.iter().take(MAX_ROWS) {
+            let _ = write!(
+                out,
+                "{:>9} {:>9} {:>6} {:>9}  {name}",
+                format_duration(stats.self_time),
+                format_duration(stats.total),
+                stats.calls,
+                format_duration(stats.slowest),
+            );
+            if !stats.slowest_args.is_empty() {
+                let _ = write!(out, " ({})", stats.slowest_args);
+            }
+            out.push('\n');
+        }
+        if rows.len() > MAX_ROWS {
+            let rest = &rows[MAX_ROWS..];
+            let self_time: Duration = rest.iter().map(|(_, stats)| stats.self_time).sum();
+            let _ = writeln!(
+                out,
+                "{:>9} {:>9} {:>6} {:>9}  {} more operations",
+                format_duration(self_time),
+                "",
+                rest.iter().map(|(_, stats)| stats.calls).sum::<usize>(),
+                "",
+                rest.len()
+            );
+        }
+        out
+    }
+}
+
+impl<S> Layer<S> for Profiler
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !attrs.metadata().target().starts_with("xgit") {
+            return;
+        }
+        let mut args = ArgsVisitor(String::new());
+        attrs.record(&mut args);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Timing {
+                started: Instant::now(),
+                children: Duration::ZERO,
+                args: args.0,
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<Timing>() else {
+            return;
+        };
+        let elapsed = timing.started.elapsed();
+        if let Some(parent) = span.parent() {
+            if let Some(parent_timing) = parent.extensions_mut().get_mut::<Timing>() {
+                parent_timing.children += elapsed;
+            }
+        }
+
+        if let Ok(mut stats) = self.stats.lock() {
+            let entry = stats.entry(span.metadata().name()).or_default();
+            entry.calls += 1;
+            entry.total += elapsed;
+            // Concurrent children can add up to more than the parent's wall time
+            entry.self_time += elapsed.saturating_sub(timing.children);
+            if elapsed >= entry.slowest {
+                entry.slowest = elapsed;
+                entry.slowest_args = timing.args;
+            }
+        }
+    }
+}
+
+/// Formats span fields as `name=value, ...`
+struct ArgsVisitor(String);
+
+impl ArgsVisitor {
+    fn separator(&mut self) {
+        if !self.0.is_empty() {
+            self.0.push_str(", ");
+        }
+    }
+}
+
+impl Visit for ArgsVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.separator();
+        let _ = write!(self.0, "{}={value}", field.name());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.separator();
+        let _ = write!(self.0, "{}={value:?}", field.name());
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_secs_f64() * 1000.0;
+    if millis >= 1000.0 {
+        format!("{:.2}s", millis / 1000.0)
+    } else if millis >= 1.0 {
+        format!("{millis:.1}ms")
+    } else {
+        format!("{:.0}µs", millis * 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_duration, Profiler};
+    use std::time::Duration;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn nested_spans_are_aggregated_with_self_time_and_arguments() {
+        let profiler = Profiler::default();
+        let subscriber = tracing_subscriber::registry().with(profiler.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let _outer = tracing::debug_span!(target: "xgit_core::git", "branch_stats").entered();
+            for branch in ["main", "feature"] {
+                let _inner =
+                    tracing::debug_span!(target: "xgit_core::git", "merge_base", branch).entered();
+                std::thread::sleep(Duration::from_millis(2));
+            }
+            // Spans from other crates are ignored
+            let _foreign = tracing::debug_span!(target: "hyper", "request").entered();
+        });
+
+        let stats = profiler.stats.lock().unwrap();
+        assert_eq!(stats.len(), 2);
+        let outer = &stats["branch_stats"];
+        let inner = &stats["merge_base"];
+        assert_eq!((outer.calls, inner.calls), (1, 2));
+        assert!(outer.total >= inner.total);
+        assert!(outer.self_time < outer.total);
+        assert!(
+            inner.slowest_args.starts_with("branch="),
+            "{}",
+            inner.slowest_args
+        );
+        drop(stats);
+
+        let report = profiler.render(None);
+        assert!(report.contains("3 instrumented calls"), "{report}");
+        assert!(report.contains("merge_base (branch="), "{report}");
+    }
+
+    #[test]
+    fn durations_are_formatted_by_magnitude() {
+        assert_eq!(format_duration(Duration::from_micros(250)), "250µs");
+        assert_eq!(format_duration(Duration::from_micros(12_340)), "12.3ms");
+        assert_eq!(format_duration(Duration::from_millis(1_420)), "1.42s");
+    }
+}
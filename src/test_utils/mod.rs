@@ -1,13 +0,0 @@
-#[cfg(test)]
-pub mod git_repo_test_decorator;
-
-#[cfg(test)]
-pub mod repo_extensions;
-
-#[cfg(test)]
-pub use git_repo_test_decorator::GitRepoTestDecorator;
-
-#[cfg(test)]
-pub use repo_extensions::{
-    create_test_bare_repo, create_test_repo, RepoAssertions, RepoTestOperations,
-};
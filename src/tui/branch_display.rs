@@ -1,5 +1,7 @@
-use crate::github::types::{PullRequestStatus, ResolvedPullRequest};
+use crate::github::types::{ChecksState, PullRequestStatus, ResolvedPullRequest, ReviewDecision};
+use crate::tui::emoji::icon;
 use console::style;
+use std::io::{self, Write};
 
 /// Information about a single branch
 #[derive(Debug)]
@@ -9,6 +11,8 @@ pub struct BranchInfo {
     pub commit_info: Option<String>,
     pub merge_status: MergeStatus,
     pub remote_tracking: Option<String>,
+    /// Commits ahead of and behind `remote_tracking`
+    pub ahead_behind: Option<(usize, usize)>,
     pub pull_request: Option<ResolvedPullRequest>,
 }
 
@@ -21,22 +25,28 @@ pub enum MergeStatus {
 }
 
 /// Display branch statistics in a formatted way
-pub fn display_branch_stats(branches: &[BranchInfo]) {
-    println!("{} Branch Statistics", style("📊").cyan().bold());
-    println!();
+pub fn display_branch_stats(branches: &[BranchInfo]) -> io::Result<()> {
+    write_branch_stats(&mut io::stdout().lock(), branches)
+}
+
+/// Write branch statistics to `out`
+pub fn write_branch_stats(out: &mut impl Write, branches: &[BranchInfo]) -> io::Result<()> {
+    writeln!(out, "{} Branch Statistics", style(icon("📊")).cyan().bold())?;
+    writeln!(out)?;
 
     if branches.is_empty() {
-        println!("{} No branches found", style("⚠").yellow());
-        return;
+        writeln!(out, "{} No branches found", style("⚠").yellow())?;
+        return Ok(());
     }
 
     for branch in branches {
-        display_single_branch(branch);
+        display_single_branch(out, branch)?;
     }
+    Ok(())
 }
 
 /// Display information for a single branch
-fn display_single_branch(branch: &BranchInfo) {
+fn display_single_branch(out: &mut impl Write, branch: &BranchInfo) -> io::Result<()> {
     // Mark current branch
     let branch_marker = if branch.is_current {
         style("● ").green().bold()
@@ -44,44 +54,59 @@ fn display_single_branch(branch: &BranchInfo) {
         style("  ").dim()
     };
 
-    println!("{}{}", branch_marker, style(&branch.name).cyan().bold());
+    writeln!(
+        out,
+        "{}{}",
+        branch_marker,
+        style(&branch.name).cyan().bold()
+    )?;
 
     // Display commit info
     if let Some(commit_info) = &branch.commit_info {
-        println!("  {} {}", style("📝").blue(), style(commit_info).dim());
+        writeln!(
+            out,
+            "  {} {}",
+            style(icon("📝")).blue(),
+            style(commit_info).dim()
+        )?;
     }
 
     // Show merge status to main
-    display_merge_status(&branch.merge_status);
+    display_merge_status(out, &branch.merge_status)?;
 
     // Display GitHub PR information
-    display_pull_request_info(&branch.pull_request);
+    display_pull_request_info(out, &branch.pull_request)?;
 
     // Display remote tracking info
-    display_remote_tracking_info(&branch.remote_tracking);
+    display_remote_tracking_info(out, &branch.remote_tracking, branch.ahead_behind)?;
 
-    println!(); // Empty line between branches
+    writeln!(out) // Empty line between branches
 }
 
 /// Display merge status for a branch
-fn display_merge_status(status: &MergeStatus) {
+fn display_merge_status(out: &mut impl Write, status: &MergeStatus) -> io::Result<()> {
     match status {
-        MergeStatus::Merged => println!(
+        MergeStatus::Merged => writeln!(
+            out,
             "  {} {}",
             style("✅").green(),
             style("Merged to main").green()
         ),
-        MergeStatus::NotMerged => println!(
+        MergeStatus::NotMerged => writeln!(
+            out,
             "  {} {}",
-            style("🔄").yellow(),
+            style(icon("🔄")).yellow(),
             style("Not merged to main").yellow()
         ),
-        MergeStatus::Unknown => {} // Skip if we can't determine merge status
+        MergeStatus::Unknown => Ok(()), // Skip if we can't determine merge status
     }
 }
 
 /// Display GitHub pull request information for a branch
-fn display_pull_request_info(pull_request: &Option<ResolvedPullRequest>) {
+fn display_pull_request_info(
+    out: &mut impl Write,
+    pull_request: &Option<ResolvedPullRequest>,
+) -> io::Result<()> {
     if let Some(pr) = pull_request {
         let state_display = match pr.record.status {
             PullRequestStatus::Open => {
@@ -100,32 +125,179 @@ fn display_pull_request_info(pull_request: &Option<ResolvedPullRequest>) {
             String::new()
         };
 
-        println!(
+        writeln!(
+            out,
             "  {} PR #{} {} {}{}",
-            style("🔗").yellow(),
+            style(icon("🔗")).yellow(),
             style(pr.record.pr_number).cyan().bold(),
             state_display,
             style(&pr.record.title).dim(),
             stale_display
-        );
+        )?;
+        display_pull_request_checks(out, pr)
     } else {
-        println!(
+        writeln!(
+            out,
             "  {} {}",
-            style("🔗").yellow(),
+            style(icon("🔗")).yellow(),
             style("No GitHub PR found").dim()
-        );
+        )
+    }
+}
+
+/// Display CI and review state for an open pull request, when known
+fn display_pull_request_checks(out: &mut impl Write, pr: &ResolvedPullRequest) -> io::Result<()> {
+    if pr.record.status != PullRequestStatus::Open {
+        return Ok(());
     }
+
+    let checks = pr.record.checks.map(|checks| match checks {
+        ChecksState::Passing => style("✓ Checks passing").green(),
+        ChecksState::Failing => style("✗ Checks failing").red(),
+        ChecksState::Pending => style("● Checks pending").yellow(),
+    });
+    let review = pr.record.review_decision.map(|review| match review {
+        ReviewDecision::Approved => style("✓ Approved").green(),
+        ReviewDecision::ChangesRequested => style("✗ Changes requested").red(),
+        ReviewDecision::ReviewRequired => style("● Review required").yellow(),
+    });
+    let parts: Vec<String> = checks
+        .into_iter()
+        .chain(review)
+        .map(|part| part.to_string())
+        .collect();
+    if parts.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "  {} {}", style(icon("🚦")).yellow(), parts.join("  "))
 }
 
 /// Display remote tracking information for a branch
-fn display_remote_tracking_info(remote_tracking: &Option<String>) {
+fn display_remote_tracking_info(
+    out: &mut impl Write,
+    remote_tracking: &Option<String>,
+    ahead_behind: Option<(usize, usize)>,
+) -> io::Result<()> {
     if let Some(remote_info) = remote_tracking {
-        println!("  {} {}", style("📡").blue(), style(remote_info).cyan());
+        let divergence = match ahead_behind {
+            Some((0, 0)) => format!(" {}", style("(up to date)").green()),
+            Some((ahead, behind)) => {
+                let mut parts = Vec::new();
+                if ahead > 0 {
+                    parts.push(style(format!("↑{ahead}")).green().to_string());
+                }
+                if behind > 0 {
+                    parts.push(style(format!("↓{behind}")).red().to_string());
+                }
+                format!(" {}", parts.join(" "))
+            }
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "  {} {}{divergence}",
+            style(icon("📡")).blue(),
+            style(remote_info).cyan()
+        )
     } else {
-        println!(
+        writeln!(
+            out,
             "  {} {}",
-            style("📡").blue(),
+            style(icon("📡")).blue(),
             style("No remote tracking").yellow()
-        );
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_branch_stats, BranchInfo, MergeStatus};
+    use crate::github::types::{
+        ChecksState, PullRequestRecord, PullRequestStatus, ResolvedPullRequest, ReviewDecision,
+    };
+    use crate::tui::snapshot::{normalize, render};
+
+    fn branch(name: &str, merge_status: MergeStatus) -> BranchInfo {
+        BranchInfo {
+            name: name.to_string(),
+            is_current: false,
+            commit_info: Some(format!("1a2b3c4 Work on {name}")),
+            merge_status,
+            remote_tracking: Some(format!("origin/{name}")),
+            ahead_behind: Some((0, 0)),
+            pull_request: None,
+        }
+    }
+
+    fn pull_request(number: u64, status: PullRequestStatus, is_stale: bool) -> ResolvedPullRequest {
+        ResolvedPullRequest {
+            record: PullRequestRecord {
+                repo_slug: "octo/repo".to_string(),
+                pr_number: number,
+                title: format!("PR {number}"),
+                url: format!("https://github.com/octo/repo/pull/{number}"),
+                base_ref: "main".to_string(),
+                head_ref: format!("feature-{number}"),
+                head_sha: String::new(),
+                draft: false,
+                status,
+                branch_names: Vec::new(),
+                remote_head_names: Vec::new(),
+                commit_shas: Vec::new(),
+                last_refreshed_at: None,
+                updated_at: 0,
+                created_at: None,
+                merged_at: None,
+                checks: None,
+                review_decision: None,
+                checks_refreshed_at: None,
+            },
+            is_stale,
+        }
+    }
+
+    #[test]
+    fn branch_stats_cover_every_status() {
+        let branches = [
+            BranchInfo {
+                is_current: true,
+                ..branch("main", MergeStatus::Unknown)
+            },
+            BranchInfo {
+                pull_request: Some(pull_request(7, PullRequestStatus::Merged, false)),
+                ..branch("feature/merged", MergeStatus::Merged)
+            },
+            BranchInfo {
+                pull_request: Some(pull_request(12, PullRequestStatus::Open, true)),
+                ahead_behind: Some((2, 1)),
+                ..branch("feature/open", MergeStatus::NotMerged)
+            },
+            BranchInfo {
+                pull_request: Some(ResolvedPullRequest {
+                    record: PullRequestRecord {
+                        checks: Some(ChecksState::Failing),
+                        review_decision: Some(ReviewDecision::Approved),
+                        ..pull_request(15, PullRequestStatus::Open, false).record
+                    },
+                    is_stale: false,
+                }),
+                ..branch("feature/checked", MergeStatus::NotMerged)
+            },
+            BranchInfo {
+                commit_info: None,
+                remote_tracking: None,
+                ahead_behind: None,
+                ..branch("local-only", MergeStatus::NotMerged)
+            },
+        ];
+
+        let output = render(|out| write_branch_stats(out, &branches));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+
+    #[test]
+    fn branch_stats_without_branches() {
+        let output = render(|out| write_branch_stats(out, &[]));
+        insta::assert_snapshot!(normalize(&output, None));
     }
 }
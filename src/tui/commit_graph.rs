@@ -0,0 +1,217 @@
+//! Branch and merge topology drawn beside a commit list, like `git log --graph`.
+//!
+//! Every commit sits in a lane (a column of the graph). A lane runs down to the commit's first
+//! parent; a merge opens or joins a lane for each further parent, and lanes waiting for the
+//! same commit join into its lane when it is reached.
+
+use crate::git::repository::core::CommitInfo;
+
+/// The graph beside one commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphRow {
+    /// The commit's own line, `●` in its lane
+    pub node: String,
+    /// A line below a merge commit that branches out to its other parents
+    pub fork: Option<String>,
+}
+
+/// Graph rows for `commits`, which must be listed children before parents (as a revwalk
+/// returns them). Parents outside the list keep their lane open to the bottom.
+pub fn layout(commits: &[CommitInfo]) -> Vec<GraphRow> {
+    // The commit each lane is waiting for; `None` for a free lane
+    let mut lanes: Vec<Option<&str>> = Vec::new();
+    let mut rows = Vec::with_capacity(commits.len());
+
+    for commit in commits {
+        let id = commit.hash.as_str();
+        let column = match lanes.iter().position(|lane| *lane == Some(id)) {
+            Some(column) => column,
+            None => free_lane(&mut lanes),
+        };
+        let joining: Vec<usize> = (0..lanes.len())
+            .filter(|&lane| lane != column && lanes[lane] == Some(id))
+            .collect();
+
+        let mut node = Cells::new(&lanes);
+        node.set(column, '●');
+        for &lane in &joining {
+            node.connect(column, lane, '╯', '╰');
+            lanes[lane] = None;
+        }
+
+        let mut parents = commit.parent_ids.iter().map(String::as_str);
+        lanes[column] = parents.next();
+        let mut fork = None;
+        let mut targets = Vec::new();
+        for parent in parents {
+            match lanes.iter().position(|lane| *lane == Some(parent)) {
+                Some(lane) => targets.push((lane, false)),
+                None => {
+                    let lane = free_lane(&mut lanes);
+                    lanes[lane] = Some(parent);
+                    targets.push((lane, true));
+                }
+            }
+        }
+        if !targets.is_empty() {
+            let mut cells = Cells::new(&lanes);
+            for (lane, opened) in targets {
+                if opened {
+                    cells.connect(column, lane, '╮', '╭');
+                } else {
+                    cells.connect(column, lane, '┤', '├');
+                }
+            }
+            fork = Some(cells.finish());
+        }
+
+        while lanes.last().map_or(false, Option::is_none) {
+            lanes.pop();
+        }
+        rows.push(GraphRow {
+            node: node.finish(),
+            fork,
+        });
+    }
+    rows
+}
+
+/// The leftmost free lane, adding one on the right when every lane is taken
+fn free_lane(lanes: &mut Vec<Option<&str>>) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+/// One line of the graph: a cell per lane with a spacer cell after each
+struct Cells(Vec<char>);
+
+impl Cells {
+    /// `│` for every lane that is waiting for a commit
+    fn new(lanes: &[Option<&str>]) -> Self {
+        let mut cells = vec![' '; lanes.len() * 2];
+        for (lane, waiting) in lanes.iter().enumerate() {
+            if waiting.is_some() {
+                cells[lane * 2] = '│';
+            }
+        }
+        Cells(cells)
+    }
+
+    fn set(&mut self, lane: usize, glyph: char) {
+        if self.0.len() < lane * 2 + 2 {
+            self.0.resize(lane * 2 + 2, ' ');
+        }
+        self.0[lane * 2] = glyph;
+    }
+
+    /// Draw a horizontal line from `from` to `to`, ending in `right` or `left` depending on
+    /// which side of `from` the target lane lies
+    fn connect(&mut self, from: usize, to: usize, right: char, left: char) {
+        let (start, end) = (from.min(to) * 2, from.max(to) * 2);
+        self.set(to, if to > from { right } else { left });
+        for cell in start + 1..end {
+            self.0[cell] = match self.0[cell] {
+                '│' | '┼' => '┼',
+                ' ' | '─' => '─',
+                glyph => glyph,
+            };
+        }
+        let joined = match (self.0[from * 2], to > from) {
+            ('●', _) => '●',
+            ('├', false) | ('┤', true) | ('┼', _) => '┼',
+            (_, true) => '├',
+            (_, false) => '┤',
+        };
+        self.0[from * 2] = joined;
+    }
+
+    fn finish(self) -> String {
+        self.0
+            .into_iter()
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::layout;
+    use crate::git::repository::core::CommitInfo;
+
+    fn commit(hash: &str, parents: &[&str]) -> CommitInfo {
+        CommitInfo {
+            hash: hash.to_string(),
+            message: hash.to_string(),
+            author_name: String::new(),
+            author_email: String::new(),
+            time: 0,
+            offset_minutes: 0,
+            parent_ids: parents.iter().map(|parent| parent.to_string()).collect(),
+        }
+    }
+
+    fn draw(commits: &[CommitInfo]) -> String {
+        commits
+            .iter()
+            .zip(layout(commits))
+            .flat_map(|(commit, row)| {
+                let mut lines = vec![format!("{} {}", row.node, commit.hash)];
+                lines.extend(row.fork);
+                lines
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn linear_history_is_one_lane() {
+        let commits = [commit("c", &["b"]), commit("b", &["a"]), commit("a", &[])];
+        assert_eq!(draw(&commits), "● c\n● b\n● a");
+    }
+
+    #[test]
+    fn merge_forks_and_branches_join_at_the_base() {
+        let commits = [
+            commit("m", &["b", "f"]),
+            commit("f", &["a"]),
+            commit("b", &["a"]),
+            commit("a", &[]),
+        ];
+        assert_eq!(
+            draw(&commits),
+            ["● m", "├─╮", "│ ● f", "● │ b", "●─╯ a"].join("\n")
+        );
+    }
+
+    #[test]
+    fn unmerged_branches_keep_their_own_lane() {
+        // Two branch tips listed together, as a symmetric range `x...y` walks them
+        let commits = [
+            commit("x", &["b"]),
+            commit("y", &["b"]),
+            commit("b", &["a"]),
+            commit("a", &[]),
+        ];
+        assert_eq!(draw(&commits), ["● x", "│ ● y", "●─╯ b", "● a"].join("\n"));
+    }
+
+    #[test]
+    fn merges_into_an_existing_lane_cross_the_lanes_between() {
+        let commits = [
+            commit("t1", &["m"]),
+            commit("t2", &["y"]),
+            commit("t3", &["d"]),
+            commit("m", &["b", "d"]),
+        ];
+        assert_eq!(
+            draw(&commits),
+            ["● t1", "│ ● t2", "│ │ ● t3", "● │ │ m", "├─┼─┤"].join("\n")
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::error::GitxError;
+use crate::i18n::{t, tf};
+use anyhow::{Context, Error};
+use console::style;
+use inquire::Confirm;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+#[cfg(test)]
+thread_local! {
+    /// What [`confirm`] answers in unit tests, which have no one to ask
+    static TEST_ANSWER: std::cell::Cell<Option<bool>> = const { std::cell::Cell::new(None) };
+}
+
+/// Answer this thread's confirmation prompts with `answer` instead of asking
+#[cfg(test)]
+pub fn answer_in_tests(answer: bool) {
+    TEST_ANSWER.with(|cell| cell.set(Some(answer)));
+}
+
+/// Answer every confirmation prompt with "yes" (set from `--yes` or config)
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// Ask the user to confirm a destructive operation.
+///
+/// Returns `Ok(true)` immediately when `--yes` is in effect, and fails instead of
+/// blocking when no user is attached to the terminal.
+pub fn confirm(prompt: &str) -> Result<bool, Error> {
+    if assume_yes() {
+        // Written to stderr so it never interleaves with porcelain output on stdout
+        eprintln!(
+            "{} {} {}",
+            style("✓").green().bold(),
+            prompt,
+            style("(--yes)").dim()
+        );
+        return Ok(true);
+    }
+
+    #[cfg(test)]
+    if let Some(answer) = TEST_ANSWER.with(std::cell::Cell::get) {
+        return Ok(answer);
+    }
+
+    if !console::user_attended() {
+        return Err(anyhow::anyhow!(tf(
+            "confirm.non_interactive",
+            &[("prompt", &prompt)]
+        )));
+    }
+
+    Confirm::new(prompt)
+        .with_default(false)
+        .prompt()
+        .context(t("confirm.cancelled"))
+}
+
+/// [`confirm`], failing with [`GitxError::UserAbort`] (exit code 130) and `aborted` as the
+/// message when the user says no
+pub fn confirm_or_abort(prompt: &str, aborted: &str) -> Result<(), GitxError> {
+    if confirm(prompt)? {
+        Ok(())
+    } else {
+        Err(GitxError::UserAbort(aborted.to_string()))
+    }
+}
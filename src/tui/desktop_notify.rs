@@ -0,0 +1,84 @@
+//! Opt-in desktop notifications when long-running network operations finish.
+//!
+//! Enabled with `git config xgit.notify.enabled true` (or `XGIT_NOTIFY=1`); only operations
+//! taking at least `xgit.notify.minSeconds` (default 10) notify, so quick commands stay quiet.
+
+use crate::git::GitRepo;
+use std::time::Duration;
+
+const DEFAULT_MIN_SECONDS: u64 = 10;
+
+/// Whether and after how long to notify
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotifySettings {
+    pub enabled: bool,
+    pub min_duration: Duration,
+}
+
+impl NotifySettings {
+    /// `XGIT_NOTIFY` wins over `xgit.notify.enabled`; notifications are off by default
+    pub fn from_env_or_config() -> Self {
+        let repo = GitRepo::open(".").ok();
+        let enabled = match std::env::var("XGIT_NOTIFY") {
+            Ok(value) => matches!(value.trim(), "1" | "true" | "yes" | "on"),
+            Err(_) => repo
+                .as_ref()
+                .and_then(|repo| repo.get_config_bool("xgit.notify.enabled").ok().flatten())
+                .unwrap_or(false),
+        };
+        let min_seconds = repo
+            .as_ref()
+            .and_then(|repo| {
+                repo.get_config_string("xgit.notify.minSeconds")
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MIN_SECONDS);
+
+        Self {
+            enabled,
+            min_duration: Duration::from_secs(min_seconds),
+        }
+    }
+
+    pub fn should_notify(&self, elapsed: Duration) -> bool {
+        self.enabled && elapsed >= self.min_duration
+    }
+}
+
+/// Show a notification that `operation` finished; failures to reach the desktop are ignored
+pub fn notify_finished(operation: &str, success: bool, elapsed: Duration) {
+    let summary = if success {
+        format!("xg {operation} finished")
+    } else {
+        format!("xg {operation} failed")
+    };
+    let _ = notify_rust::Notification::new()
+        .appname("xg")
+        .summary(&summary)
+        .body(&format!("Took {}s", elapsed.as_secs()))
+        .show();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NotifySettings;
+    use std::time::Duration;
+
+    #[test]
+    fn should_notify_requires_opt_in_and_minimum_duration() {
+        let settings = NotifySettings {
+            enabled: true,
+            min_duration: Duration::from_secs(10),
+        };
+        assert!(settings.should_notify(Duration::from_secs(12)));
+        assert!(!settings.should_notify(Duration::from_secs(3)));
+
+        let disabled = NotifySettings {
+            enabled: false,
+            ..settings
+        };
+        assert!(!disabled.should_notify(Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,557 @@
+use crate::git::commits::diff_stats::DiffStats;
+use crate::git::commits::word_diff::changed_ranges;
+use crate::tui::emoji::icon;
+use anyhow::{Context, Error};
+use console::{style, Term};
+use std::ops::Range;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style as SyntaxStyle, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+const DEFAULT_THEME: &str = "base16-ocean.dark";
+const TAB_WIDTH: usize = 4;
+const LINE_NUMBER_WIDTH: usize = 4;
+
+const ADDED_BG: (u8, u8, u8) = (0x1d, 0x3b, 0x24);
+const ADDED_EMPHASIS_BG: (u8, u8, u8) = (0x2c, 0x6e, 0x3a);
+const REMOVED_BG: (u8, u8, u8) = (0x45, 0x1e, 0x22);
+const REMOVED_EMPHASIS_BG: (u8, u8, u8) = (0x8a, 0x2d, 0x36);
+
+/// Options controlling how a diff is rendered
+#[derive(Debug, Clone)]
+pub struct DiffDisplayOptions {
+    /// Render old and new versions in two columns instead of a unified view
+    pub side_by_side: bool,
+    /// Emphasize the changed words within modified lines
+    pub word_diff: bool,
+    /// Apply syntax highlighting based on the file extension
+    pub highlight: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone)]
+struct DiffLine {
+    kind: LineKind,
+    content: String,
+    old_lineno: Option<u32>,
+    new_lineno: Option<u32>,
+}
+
+#[derive(Debug, Default)]
+struct Hunk {
+    header: String,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Default)]
+struct FileDiff {
+    path: String,
+    old_path: Option<String>,
+    binary: bool,
+    hunks: Vec<Hunk>,
+}
+
+/// A removed/added line pair rendered next to each other, with the changed byte ranges
+struct LinePair<'a> {
+    old: Option<(&'a DiffLine, Vec<Range<usize>>)>,
+    new: Option<(&'a DiffLine, Vec<Range<usize>>)>,
+}
+
+/// Display a diff with syntax highlighting and intra-line change emphasis
+pub fn display_diff(diff: &git2::Diff, options: &DiffDisplayOptions) -> Result<(), Error> {
+    let files = collect_files(diff)?;
+    let renderer = Renderer::new(options);
+
+    for file in &files {
+        renderer.display_file(file);
+    }
+
+    Ok(())
+}
+
+/// Print a `git diff --stat` style header: one line per file, then the totals
+pub fn display_diffstat(stats: &DiffStats) {
+    let width = stats
+        .files
+        .iter()
+        .map(|file| file.path.chars().count())
+        .max()
+        .unwrap_or(0);
+    for file in &stats.files {
+        let counts = if file.binary {
+            style("binary".to_string()).dim()
+        } else {
+            style(format!("+{} -{}", file.insertions, file.deletions)).dim()
+        };
+        println!(" {:<width$} | {counts}", file.path);
+    }
+    println!(" {}", style(stats.summary()).bold());
+}
+
+fn collect_files(diff: &git2::Diff) -> Result<Vec<FileDiff>, Error> {
+    let mut files: Vec<FileDiff> = Vec::new();
+
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        let content =
+            expand_tabs(String::from_utf8_lossy(line.content()).trim_end_matches(['\n', '\r']));
+
+        match line.origin() {
+            'F' => files.push(file_from_delta(&delta)),
+            'H' => {
+                if let Some(file) = files.last_mut() {
+                    file.hunks.push(Hunk {
+                        header: content,
+                        lines: Vec::new(),
+                    });
+                }
+            }
+            'B' => {
+                if let Some(file) = files.last_mut() {
+                    file.binary = true;
+                }
+            }
+            origin @ ('+' | '-' | ' ') => {
+                let kind = match origin {
+                    '+' => LineKind::Added,
+                    '-' => LineKind::Removed,
+                    _ => LineKind::Context,
+                };
+                if let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) {
+                    hunk.lines.push(DiffLine {
+                        kind,
+                        content,
+                        old_lineno: line.old_lineno(),
+                        new_lineno: line.new_lineno(),
+                    });
+                }
+            }
+            _ => {} // End-of-file newline markers are not rendered
+        }
+        true
+    })
+    .context("Failed to walk diff")?;
+
+    Ok(files)
+}
+
+fn file_from_delta(delta: &git2::DiffDelta) -> FileDiff {
+    let path_of = |file: git2::DiffFile| {
+        file.path()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let old_path = path_of(delta.old_file());
+    let new_path = path_of(delta.new_file());
+
+    let (path, old_path) = match delta.status() {
+        git2::Delta::Deleted => (old_path, None),
+        git2::Delta::Renamed | git2::Delta::Copied if old_path != new_path => {
+            (new_path, Some(old_path))
+        }
+        _ => (new_path, None),
+    };
+
+    FileDiff {
+        path,
+        old_path,
+        binary: delta.flags().is_binary(),
+        hunks: Vec::new(),
+    }
+}
+
+struct Renderer<'a> {
+    options: &'a DiffDisplayOptions,
+    syntax_set: SyntaxSet,
+    theme: Option<Theme>,
+    column_width: usize,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(options: &'a DiffDisplayOptions) -> Self {
+        let theme = if options.highlight {
+            ThemeSet::load_defaults().themes.remove(DEFAULT_THEME)
+        } else {
+            None
+        };
+        let (_, term_width) = Term::stdout().size();
+        let gutter = LINE_NUMBER_WIDTH + 3;
+        let column_width = (term_width as usize).saturating_sub(3) / 2;
+
+        Self {
+            options,
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme,
+            column_width: column_width.saturating_sub(gutter).max(20),
+        }
+    }
+
+    fn display_file(&self, file: &FileDiff) {
+        match &file.old_path {
+            Some(old_path) => println!(
+                "{} {} {} {}",
+                style(icon("📄")).blue(),
+                style(old_path).cyan(),
+                style("→").dim(),
+                style(&file.path).cyan().bold()
+            ),
+            None => println!(
+                "{} {}",
+                style(icon("📄")).blue(),
+                style(&file.path).cyan().bold()
+            ),
+        }
+
+        if file.binary {
+            println!("  {}", style("Binary file differs").dim());
+            println!();
+            return;
+        }
+
+        let syntax = self.syntax_for(&file.path);
+        let mut old_highlighter = self.highlighter(syntax);
+        let mut new_highlighter = self.highlighter(syntax);
+
+        for hunk in &file.hunks {
+            println!("{}", style(&hunk.header).magenta());
+            for pair in pair_lines(&hunk.lines, self.options.word_diff) {
+                if self.options.side_by_side {
+                    self.print_side_by_side(&pair, &mut old_highlighter, &mut new_highlighter);
+                } else {
+                    self.print_unified(&pair, &mut old_highlighter, &mut new_highlighter);
+                }
+            }
+        }
+        println!();
+    }
+
+    fn print_unified(
+        &self,
+        pair: &LinePair,
+        old_highlighter: &mut Option<HighlightLines>,
+        new_highlighter: &mut Option<HighlightLines>,
+    ) {
+        match (&pair.old, &pair.new) {
+            (Some((old, _)), Some((new, _))) if old.kind == LineKind::Context => {
+                // Feed the old-side highlighter too so its parse state stays in sync
+                let _ = self.render_content(&old.content, old.kind, &[], old_highlighter);
+                let rendered = self.render_content(&new.content, new.kind, &[], new_highlighter);
+                println!(
+                    "{} {}",
+                    self.gutter(old.old_lineno, new.new_lineno),
+                    rendered
+                );
+            }
+            _ => {
+                if let Some((old, ranges)) = &pair.old {
+                    let rendered =
+                        self.render_content(&old.content, old.kind, ranges, old_highlighter);
+                    println!("{} {}", self.gutter(old.old_lineno, None), rendered);
+                }
+                if let Some((new, ranges)) = &pair.new {
+                    let rendered =
+                        self.render_content(&new.content, new.kind, ranges, new_highlighter);
+                    println!("{} {}", self.gutter(None, new.new_lineno), rendered);
+                }
+            }
+        }
+    }
+
+    fn print_side_by_side(
+        &self,
+        pair: &LinePair,
+        old_highlighter: &mut Option<HighlightLines>,
+        new_highlighter: &mut Option<HighlightLines>,
+    ) {
+        let left = match &pair.old {
+            Some((line, ranges)) => {
+                self.side_column(line, line.old_lineno, ranges, old_highlighter)
+            }
+            None => " ".repeat(self.column_width + LINE_NUMBER_WIDTH + 2),
+        };
+        let right = match &pair.new {
+            Some((line, ranges)) => {
+                self.side_column(line, line.new_lineno, ranges, new_highlighter)
+            }
+            None => String::new(),
+        };
+        println!("{left} {} {right}", style("│").dim());
+    }
+
+    fn side_column(
+        &self,
+        line: &DiffLine,
+        lineno: Option<u32>,
+        ranges: &[Range<usize>],
+        highlighter: &mut Option<HighlightLines>,
+    ) -> String {
+        let content = truncate_to_width(&line.content, self.column_width);
+        let ranges = clip_ranges(ranges, content.len());
+        let rendered = self.render_content(content, line.kind, &ranges, highlighter);
+        let padding = self.column_width - content.chars().count();
+
+        format!(
+            "{} {}{}",
+            style(format_lineno(lineno)).dim(),
+            rendered,
+            " ".repeat(padding)
+        )
+    }
+
+    fn gutter(&self, old_lineno: Option<u32>, new_lineno: Option<u32>) -> String {
+        format!(
+            "{} {} {}",
+            style(format_lineno(old_lineno)).dim(),
+            style(format_lineno(new_lineno)).dim(),
+            style("│").dim()
+        )
+    }
+
+    fn syntax_for(&self, path: &str) -> &SyntaxReference {
+        std::path::Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.syntax_set.find_syntax_by_extension(ext))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text())
+    }
+
+    fn highlighter(&self, syntax: &SyntaxReference) -> Option<HighlightLines<'_>> {
+        self.theme
+            .as_ref()
+            .map(|theme| HighlightLines::new(syntax, theme))
+    }
+
+    fn render_content(
+        &self,
+        content: &str,
+        kind: LineKind,
+        emphasis: &[Range<usize>],
+        highlighter: &mut Option<HighlightLines>,
+    ) -> String {
+        let sign = match kind {
+            LineKind::Added => "+",
+            LineKind::Removed => "-",
+            LineKind::Context => " ",
+        };
+
+        let line = format!("{content}\n");
+        let highlighted = highlighter
+            .as_mut()
+            .and_then(|highlighter| highlighter.highlight_line(&line, &self.syntax_set).ok());
+
+        match highlighted {
+            Some(ranges) => render_highlighted(sign, &ranges, kind, emphasis),
+            None => render_plain(sign, content, kind, emphasis),
+        }
+    }
+}
+
+fn render_highlighted(
+    sign: &str,
+    ranges: &[(SyntaxStyle, &str)],
+    kind: LineKind,
+    emphasis: &[Range<usize>],
+) -> String {
+    let (line_bg, emphasis_bg) = match kind {
+        LineKind::Added => (Some(ADDED_BG), Some(ADDED_EMPHASIS_BG)),
+        LineKind::Removed => (Some(REMOVED_BG), Some(REMOVED_EMPHASIS_BG)),
+        LineKind::Context => (None, None),
+    };
+
+    let mut output = String::new();
+    push_escape(&mut output, None, line_bg);
+    output.push_str(sign);
+
+    let mut offset = 0;
+    for (syntax_style, text) in ranges {
+        let text = text.trim_end_matches('\n');
+        let fg = (
+            syntax_style.foreground.r,
+            syntax_style.foreground.g,
+            syntax_style.foreground.b,
+        );
+        for (segment, emphasized) in split_by_ranges(text, offset, emphasis) {
+            let bg = if emphasized { emphasis_bg } else { line_bg };
+            push_escape(&mut output, Some(fg), bg);
+            output.push_str(segment);
+        }
+        offset += text.len();
+    }
+
+    output.push_str("\x1b[0m");
+    output
+}
+
+fn render_plain(sign: &str, content: &str, kind: LineKind, emphasis: &[Range<usize>]) -> String {
+    let colorize = |text: &str, emphasized: bool| {
+        let styled = match kind {
+            LineKind::Added => style(text).green(),
+            LineKind::Removed => style(text).red(),
+            LineKind::Context => style(text),
+        };
+        if emphasized {
+            styled.reverse().to_string()
+        } else {
+            styled.to_string()
+        }
+    };
+
+    let mut output = colorize(sign, false);
+    for (segment, emphasized) in split_by_ranges(content, 0, emphasis) {
+        output.push_str(&colorize(segment, emphasized));
+    }
+    output
+}
+
+fn push_escape(output: &mut String, fg: Option<(u8, u8, u8)>, bg: Option<(u8, u8, u8)>) {
+    output.push_str("\x1b[0m");
+    if let Some((r, g, b)) = fg {
+        output.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+    }
+    if let Some((r, g, b)) = bg {
+        output.push_str(&format!("\x1b[48;2;{r};{g};{b}m"));
+    }
+}
+
+/// Split `text` (starting at byte `offset` of its line) into segments flagged as emphasized or not
+fn split_by_ranges<'t>(
+    text: &'t str,
+    offset: usize,
+    ranges: &[Range<usize>],
+) -> Vec<(&'t str, bool)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+
+    while start < text.len() {
+        let absolute = offset + start;
+        let active = ranges
+            .iter()
+            .find(|range| range.start <= absolute && absolute < range.end);
+        let boundary = match active {
+            Some(range) => range.end - offset,
+            None => ranges
+                .iter()
+                .filter(|range| range.start > absolute)
+                .map(|range| range.start - offset)
+                .min()
+                .unwrap_or(text.len()),
+        }
+        .min(text.len());
+
+        segments.push((&text[start..boundary], active.is_some()));
+        start = boundary;
+    }
+
+    segments
+}
+
+/// Pair removed lines with the added lines that replace them, computing word-level changes
+fn pair_lines(lines: &[DiffLine], word_diff: bool) -> Vec<LinePair<'_>> {
+    let mut pairs = Vec::new();
+    let mut idx = 0;
+
+    while idx < lines.len() {
+        if lines[idx].kind == LineKind::Context {
+            pairs.push(LinePair {
+                old: Some((&lines[idx], Vec::new())),
+                new: Some((&lines[idx], Vec::new())),
+            });
+            idx += 1;
+            continue;
+        }
+
+        let removed_start = idx;
+        while idx < lines.len() && lines[idx].kind == LineKind::Removed {
+            idx += 1;
+        }
+        let added_start = idx;
+        while idx < lines.len() && lines[idx].kind == LineKind::Added {
+            idx += 1;
+        }
+
+        let removed = &lines[removed_start..added_start];
+        let added = &lines[added_start..idx];
+        for offset in 0..removed.len().max(added.len()) {
+            let old = removed.get(offset);
+            let new = added.get(offset);
+            let (old_ranges, new_ranges) = match (old, new) {
+                (Some(old), Some(new)) if word_diff => changed_ranges(&old.content, &new.content),
+                _ => (Vec::new(), Vec::new()),
+            };
+            pairs.push(LinePair {
+                old: old.map(|line| (line, old_ranges)),
+                new: new.map(|line| (line, new_ranges)),
+            });
+        }
+    }
+
+    pairs
+}
+
+fn clip_ranges(ranges: &[Range<usize>], len: usize) -> Vec<Range<usize>> {
+    ranges
+        .iter()
+        .filter(|range| range.start < len)
+        .map(|range| range.start..range.end.min(len))
+        .collect()
+}
+
+fn truncate_to_width(content: &str, width: usize) -> &str {
+    match content.char_indices().nth(width) {
+        Some((idx, _)) => &content[..idx],
+        None => content,
+    }
+}
+
+fn expand_tabs(content: &str) -> String {
+    content.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn format_lineno(lineno: Option<u32>) -> String {
+    match lineno {
+        Some(lineno) => format!("{lineno:>LINE_NUMBER_WIDTH$}"),
+        None => " ".repeat(LINE_NUMBER_WIDTH),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pair_lines, split_by_ranges, DiffLine, LineKind};
+    use std::ops::Range;
+
+    fn line(kind: LineKind, content: &str) -> DiffLine {
+        DiffLine {
+            kind,
+            content: content.to_string(),
+            old_lineno: None,
+            new_lineno: None,
+        }
+    }
+
+    #[test]
+    fn pair_lines_pairs_removed_with_added_lines() {
+        let lines = vec![
+            line(LineKind::Context, "fn main() {"),
+            line(LineKind::Removed, "    old();"),
+            line(LineKind::Added, "    new();"),
+            line(LineKind::Added, "    extra();"),
+        ];
+
+        let pairs = pair_lines(&lines, true);
+        assert_eq!(pairs.len(), 3);
+        assert!(pairs[1].old.is_some() && pairs[1].new.is_some());
+        assert_eq!(pairs[1].new.as_ref().unwrap().1, vec![4..7]);
+        assert!(pairs[2].old.is_none());
+    }
+
+    #[test]
+    fn split_by_ranges_respects_segment_offset() {
+        let segments = split_by_ranges("value = 42", 4, &[Range { start: 12, end: 14 }]);
+        assert_eq!(segments, vec![("value = ", false), ("42", true)]);
+    }
+}
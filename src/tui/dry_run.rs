@@ -0,0 +1,17 @@
+use crate::i18n::t;
+use crate::tui::emoji::icon;
+use console::style;
+
+/// Print the banner shown before listing dry-run operations
+pub fn print_header() {
+    println!(
+        "{} {}",
+        style(icon("🔍")).blue().bold(),
+        style(t("dry_run.header")).bold()
+    );
+}
+
+/// Print a single operation that would be performed without --dry-run
+pub fn print_operation(operation: &str) {
+    println!("  {} {}", style("→").cyan().bold(), operation);
+}
@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Decorate output with emoji, or with plain symbols (set from `ui.emoji` or `XGIT_EMOJI`)
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// `emoji`, or a plain symbol standing in for it when emoji are turned off
+pub fn icon(emoji: &'static str) -> &'static str {
+    if ENABLED.load(Ordering::Relaxed) {
+        emoji
+    } else {
+        plain(emoji)
+    }
+}
+
+fn plain(emoji: &str) -> &'static str {
+    match emoji {
+        "✨" => "✓",
+        "🗑" => "✗",
+        "💡" => "ℹ",
+        "🔄" => "↻",
+        _ => "•",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plain;
+
+    #[test]
+    fn emoji_have_plain_stand_ins() {
+        assert_eq!(plain("✨"), "✓");
+        assert_eq!(plain("🗑"), "✗");
+        assert_eq!(plain("📊"), "•");
+    }
+}
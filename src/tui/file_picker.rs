@@ -0,0 +1,294 @@
+//! Terminal picker for choosing several entries from a list, such as files to stage: type to
+//! narrow the list down, toggle entries in and out of the selection and preview the one under
+//! the cursor.
+
+use console::{style, Key, Term};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+const HELP: &str =
+    "type to filter  ↑/↓ move  space toggle  → all  ← none  enter confirm  esc cancel";
+
+/// Lines around the list and preview: title, blank line, separator, blank line, help, and the
+/// empty line the cursor rests on, so a frame never scrolls the terminal
+const CHROME_LINES: usize = 6;
+
+/// What a key press asks the picker loop to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Redraw,
+    Done,
+    Cancel,
+}
+
+struct FilePicker {
+    title: String,
+    labels: Vec<String>,
+    chosen: Vec<bool>,
+    query: String,
+    /// Indices of the labels matching the query, in list order
+    matches: Vec<usize>,
+    /// Position in `matches`
+    cursor: usize,
+    /// First match shown
+    offset: usize,
+    /// Terminal size in rows and columns
+    size: (usize, usize),
+    /// Preview of the entry under the cursor, filled in by the picker loop
+    preview: Vec<String>,
+}
+
+impl FilePicker {
+    fn new(title: String, labels: Vec<String>) -> Self {
+        FilePicker {
+            title,
+            chosen: vec![false; labels.len()],
+            matches: (0..labels.len()).collect(),
+            labels,
+            query: String::new(),
+            cursor: 0,
+            offset: 0,
+            size: (24, 80),
+            preview: Vec::new(),
+        }
+    }
+
+    /// Index of the label under the cursor
+    fn current(&self) -> Option<usize> {
+        self.matches.get(self.cursor).copied()
+    }
+
+    /// Rows of the list: the matches, up to a third of the terminal
+    fn list_height(&self) -> usize {
+        self.matches.len().clamp(1, (self.size.0 / 3).max(1))
+    }
+
+    /// Rows of the preview pane: whatever the list and chrome leave
+    fn preview_height(&self) -> usize {
+        self.size
+            .0
+            .saturating_sub(CHROME_LINES + self.list_height())
+    }
+
+    fn handle_key(&mut self, key: Key) -> Step {
+        let last = self.matches.len().saturating_sub(1);
+        let page = self.list_height();
+        match key {
+            Key::ArrowUp => self.cursor = self.cursor.saturating_sub(1),
+            Key::ArrowDown => self.cursor = (self.cursor + 1).min(last),
+            Key::PageUp => self.cursor = self.cursor.saturating_sub(page),
+            Key::PageDown => self.cursor = (self.cursor + page).min(last),
+            Key::Char(' ') => {
+                if let Some(index) = self.current() {
+                    self.chosen[index] = !self.chosen[index];
+                }
+            }
+            Key::ArrowRight => self.set_matches_chosen(true),
+            Key::ArrowLeft => self.set_matches_chosen(false),
+            Key::Backspace => {
+                self.query.pop();
+                self.filter();
+            }
+            Key::Char(c) if !c.is_control() => {
+                self.query.push(c);
+                self.filter();
+            }
+            Key::Enter => return Step::Done,
+            Key::Escape => return Step::Cancel,
+            _ => {}
+        }
+        self.scroll_to_cursor();
+        Step::Redraw
+    }
+
+    fn set_matches_chosen(&mut self, chosen: bool) {
+        for &index in &self.matches {
+            self.chosen[index] = chosen;
+        }
+    }
+
+    /// Keep the labels containing the query's characters in order, ignoring case
+    fn filter(&mut self) {
+        let query = self.query.to_lowercase();
+        self.matches = (0..self.labels.len())
+            .filter(|&index| is_subsequence(&query, &self.labels[index].to_lowercase()))
+            .collect();
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let height = self.list_height();
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + height {
+            self.offset = self.cursor + 1 - height;
+        }
+    }
+
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        let width = self.size.1;
+        let count = self.chosen.iter().filter(|chosen| **chosen).count();
+        writeln!(
+            out,
+            "{} {} {}  {}",
+            style("?").green().bold(),
+            style(&self.title).bold(),
+            style(&self.query).cyan(),
+            style(format!("({count} selected)")).dim()
+        )?;
+        writeln!(out)?;
+
+        if self.matches.is_empty() {
+            writeln!(out, "  {}", style("No files match").dim())?;
+        }
+        for (position, &index) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(self.list_height())
+        {
+            let marker = if position == self.cursor { "❯" } else { " " };
+            let check = if self.chosen[index] {
+                style("[x]").green()
+            } else {
+                style("[ ]").dim()
+            };
+            let label = if position == self.cursor {
+                style(self.labels[index].as_str()).bold()
+            } else {
+                style(self.labels[index].as_str())
+            };
+            let line = format!("{} {check} {label}", style(marker).cyan().bold());
+            writeln!(out, "{}", fit(&line, width))?;
+        }
+
+        writeln!(out, "{}", style("─".repeat(width)).dim())?;
+        for line in self.preview.iter().take(self.preview_height()) {
+            writeln!(out, "{}", fit(line, width))?;
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", style(HELP).dim())
+    }
+}
+
+fn is_subsequence(query: &str, text: &str) -> bool {
+    let mut text = text.chars();
+    query.chars().all(|wanted| text.any(|c| c == wanted))
+}
+
+/// `line` cut to `width` columns with an ellipsis
+fn fit(line: &str, width: usize) -> Cow<'_, str> {
+    if console::measure_text_width(line) <= width {
+        Cow::Borrowed(line)
+    } else {
+        console::truncate_str(line, width, "…")
+    }
+}
+
+/// Let the user choose any number of `labels`, showing `preview` of the one under the cursor
+/// below the list. Returns the chosen indices in list order, or `None` when cancelled.
+pub fn pick(
+    title: &str,
+    labels: Vec<String>,
+    mut preview: impl FnMut(usize) -> Vec<String>,
+) -> anyhow::Result<Option<Vec<usize>>> {
+    let term = Term::stdout();
+    let mut picker = FilePicker::new(title.to_string(), labels);
+    let mut drawn = 0;
+    // The entry whose preview is loaded, to only compute it when the cursor moves
+    let mut preview_of = None;
+    term.hide_cursor()?;
+    let result = loop {
+        let (rows, columns) = term.size();
+        picker.size = (usize::from(rows), usize::from(columns));
+        picker.scroll_to_cursor();
+        let current = picker.current();
+        if current != preview_of {
+            picker.preview = current.map(&mut preview).unwrap_or_default();
+            preview_of = current;
+        }
+
+        let mut frame = Vec::new();
+        picker.render(&mut frame)?;
+        term.clear_last_lines(drawn)?;
+        let frame = String::from_utf8_lossy(&frame);
+        term.write_str(&frame)?;
+        drawn = frame.lines().count();
+
+        match picker.handle_key(term.read_key()?) {
+            Step::Redraw => {}
+            Step::Done => {
+                break Ok(Some(
+                    (0..picker.labels.len())
+                        .filter(|&index| picker.chosen[index])
+                        .collect(),
+                ))
+            }
+            Step::Cancel => break Ok(None),
+        }
+    };
+    term.clear_last_lines(drawn)?;
+    term.show_cursor()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilePicker, Step};
+    use crate::tui::snapshot::{normalize, render};
+    use console::Key;
+
+    fn picker() -> FilePicker {
+        let labels = ["M src/main.rs", "M src/cli.rs", "? notes.txt", "D old.rs"]
+            .iter()
+            .map(|label| label.to_string())
+            .collect();
+        let mut picker = FilePicker::new("Select files to stage:".to_string(), labels);
+        picker.size = (14, 40);
+        picker
+    }
+
+    #[test]
+    fn keys_filter_toggle_and_finish() {
+        let mut picker = picker();
+        assert_eq!(picker.list_height(), 4);
+        picker.handle_key(Key::ArrowDown);
+        picker.handle_key(Key::Char(' '));
+        assert_eq!(picker.chosen, [false, true, false, false]);
+
+        // Typing narrows the list to fuzzy matches and moves the cursor to the first
+        for c in "srcrs".chars() {
+            picker.handle_key(Key::Char(c));
+        }
+        assert_eq!(picker.matches, [0, 1]);
+        assert_eq!(picker.current(), Some(0));
+        picker.handle_key(Key::ArrowRight);
+        assert_eq!(picker.chosen, [true, true, false, false]);
+
+        for _ in 0.."srcrs".len() {
+            picker.handle_key(Key::Backspace);
+        }
+        assert_eq!(picker.matches, [0, 1, 2, 3]);
+        picker.handle_key(Key::ArrowLeft);
+        assert_eq!(picker.chosen, [false; 4]);
+        assert_eq!(picker.handle_key(Key::Enter), Step::Done);
+        assert_eq!(picker.handle_key(Key::Escape), Step::Cancel);
+    }
+
+    #[test]
+    fn render_shows_selection_and_preview() {
+        let mut picker = picker();
+        picker.handle_key(Key::Char(' '));
+        picker.handle_key(Key::ArrowDown);
+        picker.preview = vec![
+            "@@ -1,2 +1,2 @@".to_string(),
+            "-use clap::Parser;".to_string(),
+            "+use clap::{Parser, Subcommand};".to_string(),
+        ];
+
+        let output = render(|out| picker.render(out));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
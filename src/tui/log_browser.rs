@@ -0,0 +1,391 @@
+//! Terminal browser for commit history: scroll through the commits beside their graph, open
+//! the details of one, and narrow the list down by author or path.
+
+use crate::commands::log::format_date;
+use crate::git::repository::core::CommitInfo;
+use crate::tui::commit_graph::{self, GraphRow};
+use crate::tui::emoji::icon;
+use console::{style, Key, Term};
+use inquire::Text;
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+const HELP: &str = "↑/↓ move  pgup/pgdn page  enter details  a author  p path  c clear  q quit";
+
+/// Lines around the commit list: title, two blank lines, help, and the empty line the cursor
+/// rests on, so a frame never scrolls the terminal
+const CHROME_LINES: usize = 5;
+
+/// What narrows the history down; applied by the caller when the browser asks to reload
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    pub author: Option<String>,
+    pub paths: Vec<String>,
+}
+
+impl LogFilter {
+    fn describe(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(author) = &self.author {
+            parts.push(format!("author: {author}"));
+        }
+        if !self.paths.is_empty() {
+            parts.push(format!("path: {}", self.paths.join(" ")));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterField {
+    Author,
+    Path,
+}
+
+/// What a key press asks the browser loop to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Redraw,
+    /// Ask for a new value of one filter, then reload
+    Prompt(FilterField),
+    /// Load the commits again with the current filter
+    Reload,
+    Quit,
+}
+
+struct LogBrowser {
+    title: String,
+    commits: Vec<CommitInfo>,
+    graph: Vec<GraphRow>,
+    filter: LogFilter,
+    cursor: usize,
+    /// First commit shown
+    offset: usize,
+    /// Terminal size in rows and columns
+    size: (usize, usize),
+    show_detail: bool,
+    /// Details of the commit under the cursor, filled in by the browser loop
+    detail: Vec<String>,
+}
+
+impl LogBrowser {
+    fn new(title: String, commits: Vec<CommitInfo>, filter: LogFilter) -> Self {
+        let mut browser = LogBrowser {
+            title,
+            commits: Vec::new(),
+            graph: Vec::new(),
+            filter,
+            cursor: 0,
+            offset: 0,
+            size: (24, 80),
+            show_detail: false,
+            detail: Vec::new(),
+        };
+        browser.set_commits(commits);
+        browser
+    }
+
+    fn set_commits(&mut self, commits: Vec<CommitInfo>) {
+        self.graph = commit_graph::layout(&commits);
+        self.commits = commits;
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    fn selected(&self) -> Option<&CommitInfo> {
+        self.commits.get(self.cursor)
+    }
+
+    /// Rows of the detail pane, a third of the terminal, separator included
+    fn detail_height(&self) -> usize {
+        if self.show_detail {
+            self.size.0 / 3
+        } else {
+            0
+        }
+    }
+
+    /// Rows available to the commit list
+    fn list_height(&self) -> usize {
+        self.size
+            .0
+            .saturating_sub(CHROME_LINES + self.detail_height())
+            .max(1)
+    }
+
+    /// Lines commit `index` takes up: its own and a fork line below a merge
+    fn lines_of(&self, index: usize) -> usize {
+        1 + usize::from(self.graph[index].fork.is_some())
+    }
+
+    fn handle_key(&mut self, key: Key) -> Step {
+        let last = self.commits.len().saturating_sub(1);
+        let page = self.list_height().saturating_sub(1).max(1);
+        match key {
+            Key::ArrowUp | Key::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            Key::ArrowDown | Key::Char('j') => self.cursor = (self.cursor + 1).min(last),
+            Key::PageUp => self.cursor = self.cursor.saturating_sub(page),
+            Key::PageDown | Key::Char(' ') => self.cursor = (self.cursor + page).min(last),
+            Key::Home | Key::Char('g') => self.cursor = 0,
+            Key::End | Key::Char('G') => self.cursor = last,
+            Key::Enter => self.show_detail = !self.show_detail,
+            Key::Char('a') => return Step::Prompt(FilterField::Author),
+            Key::Char('p') => return Step::Prompt(FilterField::Path),
+            Key::Char('c') if self.filter != LogFilter::default() => {
+                self.filter = LogFilter::default();
+                return Step::Reload;
+            }
+            Key::Escape | Key::Char('q') => return Step::Quit,
+            _ => {}
+        }
+        self.scroll_to_cursor();
+        Step::Redraw
+    }
+
+    /// Move the visible window so the cursor's commit is fully on screen
+    fn scroll_to_cursor(&mut self) {
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        }
+        let height = self.list_height();
+        while self.offset < self.cursor
+            && (self.offset..=self.cursor)
+                .map(|index| self.lines_of(index))
+                .sum::<usize>()
+                > height
+        {
+            self.offset += 1;
+        }
+    }
+
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        let width = self.size.1;
+        let filter = match self.filter.describe() {
+            Some(filter) => format!("  {}", style(format!("({filter})")).dim()),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "{} {}{filter}",
+            style(icon("📜")).cyan().bold(),
+            style(&self.title).bold()
+        )?;
+        writeln!(out)?;
+
+        let height = self.list_height();
+        let mut drawn = 0;
+        if self.commits.is_empty() {
+            writeln!(out, "  {}", style("No commits match").dim())?;
+            drawn += 1;
+        }
+        for (index, commit) in self.commits.iter().enumerate().skip(self.offset) {
+            if drawn + self.lines_of(index) > height {
+                break;
+            }
+            let row = &self.graph[index];
+            let selected = index == self.cursor;
+            let marker = if selected { "❯" } else { " " };
+            let subject = commit.message.lines().next().unwrap_or_default().trim();
+            let subject = if selected {
+                style(subject).bold()
+            } else {
+                style(subject)
+            };
+            let line = format!(
+                "{} {} {} {subject} {}",
+                style(marker).cyan().bold(),
+                style(&row.node).magenta(),
+                style(&commit.hash[..7.min(commit.hash.len())]).yellow(),
+                style(format!(
+                    "({}, {})",
+                    commit.author_name,
+                    format_date(commit.time, commit.offset_minutes)
+                ))
+                .dim()
+            );
+            writeln!(out, "{}", fit(&line, width))?;
+            if let Some(fork) = &row.fork {
+                writeln!(out, "  {}", style(fork).magenta())?;
+            }
+            drawn += self.lines_of(index);
+        }
+
+        if self.show_detail {
+            writeln!(out, "{}", style("─".repeat(width)).dim())?;
+            for line in self
+                .detail
+                .iter()
+                .take(self.detail_height().saturating_sub(1))
+            {
+                writeln!(out, "{}", fit(line, width))?;
+            }
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", style(HELP).dim())
+    }
+}
+
+/// `line` cut to `width` columns with an ellipsis. `truncate_str` alone also cuts lines that
+/// fill the width exactly.
+fn fit(line: &str, width: usize) -> Cow<'_, str> {
+    if console::measure_text_width(line) <= width {
+        Cow::Borrowed(line)
+    } else {
+        console::truncate_str(line, width, "…")
+    }
+}
+
+/// Browse `commits`, listed children before parents, until the user quits.
+///
+/// `reload` lists the commits again whenever the user changes the filter; `details` gives the
+/// lines of the detail pane for a commit.
+pub fn browse(
+    title: &str,
+    commits: Vec<CommitInfo>,
+    filter: LogFilter,
+    mut reload: impl FnMut(&LogFilter) -> anyhow::Result<Vec<CommitInfo>>,
+    mut details: impl FnMut(&CommitInfo) -> Vec<String>,
+) -> anyhow::Result<()> {
+    let term = Term::stdout();
+    let mut browser = LogBrowser::new(title.to_string(), commits, filter);
+    let mut drawn = 0;
+    // The commit whose details are loaded, to only compute them when the cursor moves
+    let mut detail_of = None;
+    term.hide_cursor()?;
+    let result = loop {
+        let (rows, columns) = term.size();
+        browser.size = (usize::from(rows), usize::from(columns));
+        browser.scroll_to_cursor();
+        if browser.show_detail {
+            let selected = browser.selected().map(|commit| commit.hash.clone());
+            if selected != detail_of {
+                browser.detail = browser.selected().map(&mut details).unwrap_or_default();
+                detail_of = selected;
+            }
+        }
+
+        let mut frame = Vec::new();
+        browser.render(&mut frame)?;
+        term.clear_last_lines(drawn)?;
+        let frame = String::from_utf8_lossy(&frame);
+        term.write_str(&frame)?;
+        drawn = frame.lines().count();
+
+        let step = match browser.handle_key(term.read_key()?) {
+            Step::Prompt(field) => {
+                let (label, initial) = match field {
+                    FilterField::Author => ("Author:", browser.filter.author.clone()),
+                    FilterField::Path => ("Paths:", Some(browser.filter.paths.join(" "))),
+                };
+                term.show_cursor()?;
+                let value = Text::new(label)
+                    .with_initial_value(initial.as_deref().unwrap_or_default())
+                    .prompt_skippable()?;
+                term.hide_cursor()?;
+                // The prompt's own line sits below the frame
+                drawn += 1;
+                match (field, value) {
+                    (_, None) => Step::Redraw,
+                    (FilterField::Author, Some(value)) => {
+                        browser.filter.author =
+                            Some(value.trim().to_string()).filter(|author| !author.is_empty());
+                        Step::Reload
+                    }
+                    (FilterField::Path, Some(value)) => {
+                        browser.filter.paths =
+                            value.split_whitespace().map(str::to_string).collect();
+                        Step::Reload
+                    }
+                }
+            }
+            step => step,
+        };
+        match step {
+            Step::Reload => {
+                let commits = reload(&browser.filter);
+                browser.set_commits(commits?);
+                detail_of = None;
+            }
+            Step::Quit => break Ok(()),
+            Step::Redraw | Step::Prompt(_) => {}
+        }
+    };
+    term.clear_last_lines(drawn)?;
+    term.show_cursor()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilterField, LogBrowser, LogFilter, Step};
+    use crate::git::repository::core::CommitInfo;
+    use crate::tui::snapshot::{normalize, render};
+    use console::Key;
+
+    fn commit(index: usize, parents: &[usize]) -> CommitInfo {
+        CommitInfo {
+            hash: format!("{index}").repeat(40),
+            message: format!("Commit {index}\n\nBody of {index}\n"),
+            author_name: "Ada".to_string(),
+            author_email: "ada@example.com".to_string(),
+            time: 1_700_000_000 - index as i64 * 86_400,
+            offset_minutes: 0,
+            parent_ids: parents
+                .iter()
+                .map(|parent| format!("{parent}").repeat(40))
+                .collect(),
+        }
+    }
+
+    fn browser() -> LogBrowser {
+        let commits = vec![
+            commit(1, &[2, 3]),
+            commit(3, &[4]),
+            commit(2, &[4]),
+            commit(4, &[5]),
+            commit(5, &[6]),
+            commit(6, &[7]),
+            commit(7, &[]),
+        ];
+        let mut browser = LogBrowser::new("main".to_string(), commits, LogFilter::default());
+        browser.size = (11, 60);
+        browser
+    }
+
+    #[test]
+    fn keys_move_scroll_and_ask_for_filters() {
+        let mut browser = browser();
+        assert_eq!(browser.list_height(), 6);
+        assert_eq!(browser.handle_key(Key::End), Step::Redraw);
+        assert_eq!(browser.cursor, 6);
+        // The merge and its fork line scroll out, leaving room for the other six commits
+        assert_eq!(browser.offset, 1);
+        browser.handle_key(Key::Char('g'));
+        assert_eq!((browser.cursor, browser.offset), (0, 0));
+
+        assert_eq!(
+            browser.handle_key(Key::Char('a')),
+            Step::Prompt(FilterField::Author)
+        );
+        assert_eq!(browser.handle_key(Key::Char('c')), Step::Redraw);
+        browser.filter.author = Some("ada".to_string());
+        assert_eq!(browser.handle_key(Key::Char('c')), Step::Reload);
+        assert_eq!(browser.filter, LogFilter::default());
+        assert_eq!(browser.handle_key(Key::Char('q')), Step::Quit);
+    }
+
+    #[test]
+    fn render_shows_graph_window_and_details() {
+        let mut browser = browser();
+        browser.filter.paths = vec!["src".to_string()];
+        browser.handle_key(Key::ArrowDown);
+        browser.handle_key(Key::Enter);
+        browser.detail = vec![
+            "commit 3333333333333333333333333333333333333333".to_string(),
+            "Author: Ada <ada@example.com>".to_string(),
+        ];
+
+        let output = render(|out| browser.render(out));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
@@ -1 +1,16 @@
 pub mod branch_display;
+pub mod commit_graph;
+pub mod confirm;
+pub mod desktop_notify;
+pub mod diff_display;
+pub mod dry_run;
+pub mod emoji;
+pub mod file_picker;
+pub mod log_browser;
+pub mod porcelain;
+pub mod rebase_editor;
+#[cfg(test)]
+pub mod snapshot;
+pub mod stats_display;
+pub mod template;
+pub mod watch;
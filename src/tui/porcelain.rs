@@ -0,0 +1,220 @@
+//! Machine-readable output for `--porcelain`.
+//!
+//! Every record is one line of tab-separated fields. The field order and values below
+//! are a stable contract: new fields may only ever be appended, and existing ones never
+//! change meaning. Missing values are written as `-`.
+//!
+//! - `xg branch --porcelain`: `current name`
+//! - `xg branch --stats --porcelain`:
+//!   `current name sha merge-status upstream pr-number pr-state subject ahead behind pr-checks
+//!   pr-review`
+//! - `xg branch --prune-merged --porcelain`: `status name reason pr-number`
+//!
+//! `current` is `*` for the checked-out branch and `-` otherwise. `merge-status` is one of
+//! `merged`, `not-merged`, `unknown`; `pr-state` is one of `open`, `draft`, `closed`,
+//! `merged`; `pr-checks` is one of `passing`, `failing`, `pending` and `pr-review` one of
+//! `approved`, `changes-requested`, `review-required`, both only known for open PRs. Prune `status` is one of `would-delete`, `deleted`, `failed`, `skipped` and
+//! `reason` is `merged` (merged into trunk) or `pr-merged` (squash/rebase merged on GitHub).
+
+use crate::github::types::{ChecksState, PullRequestRecord, PullRequestStatus, ReviewDecision};
+use crate::tui::branch_display::{BranchInfo, MergeStatus};
+
+const MISSING: &str = "-";
+
+/// Outcome of a single prune candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneStatus {
+    WouldDelete,
+    Deleted,
+    Failed,
+    Skipped,
+}
+
+impl PruneStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            PruneStatus::WouldDelete => "would-delete",
+            PruneStatus::Deleted => "deleted",
+            PruneStatus::Failed => "failed",
+            PruneStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// Stable name of a PR's state, distinguishing drafts from other open PRs
+pub fn pr_state(record: &PullRequestRecord) -> &'static str {
+    match record.status {
+        PullRequestStatus::Open if record.draft => "draft",
+        PullRequestStatus::Open => "open",
+        PullRequestStatus::Closed => "closed",
+        PullRequestStatus::Merged => "merged",
+    }
+}
+
+/// Stable name of a PR's combined check state
+pub fn checks_state(checks: ChecksState) -> &'static str {
+    match checks {
+        ChecksState::Passing => "passing",
+        ChecksState::Failing => "failing",
+        ChecksState::Pending => "pending",
+    }
+}
+
+/// Stable name of a PR's review decision
+pub fn review_decision(review: ReviewDecision) -> &'static str {
+    match review {
+        ReviewDecision::Approved => "approved",
+        ReviewDecision::ChangesRequested => "changes-requested",
+        ReviewDecision::ReviewRequired => "review-required",
+    }
+}
+
+pub fn branch_line(name: &str, is_current: bool) -> String {
+    join(&[current_marker(is_current), name])
+}
+
+pub fn branch_stats_line(branch: &BranchInfo) -> String {
+    let (sha, subject) = branch
+        .commit_info
+        .as_deref()
+        .and_then(|info| info.split_once(' '))
+        .unwrap_or((MISSING, MISSING));
+    let merge_status = match branch.merge_status {
+        MergeStatus::Merged => "merged",
+        MergeStatus::NotMerged => "not-merged",
+        MergeStatus::Unknown => "unknown",
+    };
+    let upstream = branch.remote_tracking.as_deref().unwrap_or(MISSING);
+    let (ahead, behind) = branch.ahead_behind.map_or_else(
+        || (MISSING.to_string(), MISSING.to_string()),
+        |(ahead, behind)| (ahead.to_string(), behind.to_string()),
+    );
+    let (pr_number, pr_state) = match &branch.pull_request {
+        Some(pr) => (pr.record.pr_number.to_string(), pr_state(&pr.record)),
+        None => (MISSING.to_string(), MISSING),
+    };
+    let record = branch.pull_request.as_ref().map(|pr| &pr.record);
+    let pr_checks = record
+        .and_then(|record| record.checks)
+        .map_or(MISSING, checks_state);
+    let pr_review = record
+        .and_then(|record| record.review_decision)
+        .map_or(MISSING, review_decision);
+
+    join(&[
+        current_marker(branch.is_current),
+        &branch.name,
+        sha,
+        merge_status,
+        upstream,
+        &pr_number,
+        pr_state,
+        subject,
+        &ahead,
+        &behind,
+        pr_checks,
+        pr_review,
+    ])
+}
+
+pub fn prune_line(status: PruneStatus, name: &str, reason: &str, pr_number: Option<u64>) -> String {
+    let pr_number = pr_number.map_or_else(|| MISSING.to_string(), |number| number.to_string());
+    join(&[status.as_str(), name, reason, &pr_number])
+}
+
+fn current_marker(is_current: bool) -> &'static str {
+    if is_current {
+        "*"
+    } else {
+        MISSING
+    }
+}
+
+/// Join fields with tabs, flattening any tabs or newlines inside a field so each record stays on one line
+fn join(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{branch_line, branch_stats_line, prune_line, PruneStatus};
+    use crate::github::types::{
+        ChecksState, PullRequestRecord, PullRequestSnapshot, PullRequestStatus, ResolvedPullRequest,
+    };
+    use crate::tui::branch_display::{BranchInfo, MergeStatus};
+
+    #[test]
+    fn branch_line_marks_current_branch() {
+        assert_eq!(branch_line("main", true), "*\tmain");
+        assert_eq!(branch_line("feature", false), "-\tfeature");
+    }
+
+    #[test]
+    fn branch_stats_line_has_stable_field_order() {
+        let mut record = PullRequestRecord::from_snapshot(PullRequestSnapshot {
+            repo_slug: "owner/repo".to_string(),
+            pr_number: 42,
+            title: "Feature".to_string(),
+            url: "https://example.com/42".to_string(),
+            base_ref: "main".to_string(),
+            head_ref: "feature".to_string(),
+            head_sha: "sha-1".to_string(),
+            draft: true,
+            status: PullRequestStatus::Open,
+            created_at: None,
+            merged_at: None,
+        });
+        record.checks = Some(ChecksState::Pending);
+        let branch = BranchInfo {
+            name: "feature".to_string(),
+            is_current: false,
+            commit_info: Some("abc1234 Add\tfeature".to_string()),
+            merge_status: MergeStatus::NotMerged,
+            remote_tracking: Some("origin/feature".to_string()),
+            ahead_behind: Some((2, 0)),
+            pull_request: Some(ResolvedPullRequest {
+                record,
+                is_stale: false,
+            }),
+        };
+
+        assert_eq!(
+            branch_stats_line(&branch),
+            "-\tfeature\tabc1234\tnot-merged\torigin/feature\t42\tdraft\tAdd feature\t2\t0\tpending\t-"
+        );
+    }
+
+    #[test]
+    fn branch_stats_line_uses_placeholders_for_missing_values() {
+        let branch = BranchInfo {
+            name: "main".to_string(),
+            is_current: true,
+            commit_info: None,
+            merge_status: MergeStatus::Unknown,
+            remote_tracking: None,
+            ahead_behind: None,
+            pull_request: None,
+        };
+
+        assert_eq!(
+            branch_stats_line(&branch),
+            "*\tmain\t-\tunknown\t-\t-\t-\t-\t-\t-\t-\t-"
+        );
+    }
+
+    #[test]
+    fn prune_line_formats_status_and_reason() {
+        assert_eq!(
+            prune_line(PruneStatus::WouldDelete, "old", "merged", None),
+            "would-delete\told\tmerged\t-"
+        );
+        assert_eq!(
+            prune_line(PruneStatus::Deleted, "feature", "pr-merged", Some(7)),
+            "deleted\tfeature\tpr-merged\t7"
+        );
+    }
+}
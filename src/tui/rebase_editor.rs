@@ -0,0 +1,248 @@
+//! Terminal editor for an interactive rebase plan: move through the commits, reorder them
+//! and pick what happens to each one, like the todo file of `git rebase -i`.
+
+use crate::git::rebase::interactive::{TodoAction, TodoItem};
+use console::{style, Key, Term};
+use inquire::Text;
+use std::io::{self, Write};
+
+const HELP: &str =
+    "↑/↓ move  J/K reorder  p pick  r reword  s squash  f fixup  d drop  enter run  esc cancel";
+
+/// What a key press asks the editor loop to do next
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Redraw,
+    /// Ask for a new message for the commit under the cursor
+    Reword,
+    Done,
+    Cancel,
+}
+
+struct RebaseEditor {
+    title: String,
+    items: Vec<TodoItem>,
+    cursor: usize,
+    /// Why the plan cannot run yet, shown until the next key press
+    problem: Option<String>,
+}
+
+impl RebaseEditor {
+    fn new(title: String, items: Vec<TodoItem>) -> Self {
+        RebaseEditor {
+            title,
+            items,
+            cursor: 0,
+            problem: None,
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> Step {
+        self.problem = None;
+        let last = self.items.len().saturating_sub(1);
+        match key {
+            Key::ArrowUp | Key::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            Key::ArrowDown | Key::Char('j') => self.cursor = (self.cursor + 1).min(last),
+            Key::Char('K') if self.cursor > 0 => {
+                self.items.swap(self.cursor, self.cursor - 1);
+                self.cursor -= 1;
+            }
+            Key::Char('J') if self.cursor < last => {
+                self.items.swap(self.cursor, self.cursor + 1);
+                self.cursor += 1;
+            }
+            Key::Char('p') => self.set_action(TodoAction::Pick),
+            Key::Char('s') => self.set_action(TodoAction::Squash),
+            Key::Char('f') => self.set_action(TodoAction::Fixup),
+            Key::Char('d') => self.set_action(TodoAction::Drop),
+            Key::Char('r') => return Step::Reword,
+            Key::Enter => match self.validate() {
+                Ok(()) => return Step::Done,
+                Err(problem) => self.problem = Some(problem),
+            },
+            Key::Escape | Key::Char('q') => return Step::Cancel,
+            _ => {}
+        }
+        Step::Redraw
+    }
+
+    fn set_action(&mut self, action: TodoAction) {
+        if let Some(item) = self.items.get_mut(self.cursor) {
+            item.action = action;
+        }
+    }
+
+    /// Squash and fixup need a kept commit before them to fold into
+    fn validate(&self) -> Result<(), String> {
+        let first = self
+            .items
+            .iter()
+            .find(|item| item.action != TodoAction::Drop);
+        match first {
+            None => Err("Every commit is dropped; press esc to cancel instead".to_string()),
+            Some(item) if matches!(item.action, TodoAction::Squash | TodoAction::Fixup) => {
+                Err(format!(
+                    "The first commit cannot be a {}; there is nothing before it to fold into",
+                    item.action.name()
+                ))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{} {}",
+            style("✏").cyan().bold(),
+            style(&self.title).bold()
+        )?;
+        writeln!(out)?;
+        for (index, item) in self.items.iter().enumerate() {
+            let marker = if index == self.cursor { "❯" } else { " " };
+            let action = format!("{:<6}", item.action.name());
+            let action = match item.action {
+                TodoAction::Pick => style(action).green(),
+                TodoAction::Reword(_) => style(action).yellow(),
+                TodoAction::Squash | TodoAction::Fixup => style(action).cyan(),
+                TodoAction::Drop => style(action).red(),
+            };
+            let summary = match &item.action {
+                TodoAction::Reword(message) => message.lines().next().unwrap_or_default(),
+                _ => item.summary.as_str(),
+            };
+            let summary = match item.action {
+                TodoAction::Drop => style(summary).dim().strikethrough(),
+                _ => style(summary),
+            };
+            writeln!(
+                out,
+                "{} {action} {} {summary}",
+                style(marker).cyan().bold(),
+                style(&item.id[..7.min(item.id.len())]).yellow()
+            )?;
+        }
+        writeln!(out)?;
+        match &self.problem {
+            Some(problem) => writeln!(out, "{} {}", style("⚠").yellow().bold(), problem),
+            None => writeln!(out, "{}", style(HELP).dim()),
+        }
+    }
+}
+
+/// Let the user edit `items` in the terminal; `None` when they cancel
+pub fn edit_plan(title: &str, items: Vec<TodoItem>) -> anyhow::Result<Option<Vec<TodoItem>>> {
+    let term = Term::stdout();
+    let mut editor = RebaseEditor::new(title.to_string(), items);
+    let mut drawn = 0;
+    term.hide_cursor()?;
+    let result = loop {
+        let mut frame = Vec::new();
+        editor.render(&mut frame)?;
+        term.clear_last_lines(drawn)?;
+        let frame = String::from_utf8_lossy(&frame);
+        term.write_str(&frame)?;
+        drawn = frame.lines().count();
+
+        match editor.handle_key(term.read_key()?) {
+            Step::Redraw => {}
+            Step::Reword => {
+                let item = &editor.items[editor.cursor];
+                let initial = match &item.action {
+                    TodoAction::Reword(message) => message.clone(),
+                    _ => item.summary.clone(),
+                };
+                term.show_cursor()?;
+                let message = Text::new(&format!("New message for {}:", &item.id[..7]))
+                    .with_initial_value(&initial)
+                    .prompt_skippable()?;
+                term.hide_cursor()?;
+                // The prompt's own line sits below the frame
+                drawn += 1;
+                if let Some(message) = message.filter(|message| !message.trim().is_empty()) {
+                    editor.set_action(TodoAction::Reword(message));
+                }
+            }
+            Step::Done => break Some(editor.items),
+            Step::Cancel => break None,
+        }
+    };
+    term.show_cursor()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RebaseEditor, Step};
+    use crate::git::rebase::interactive::{TodoAction, TodoItem};
+    use crate::tui::snapshot::{normalize, render};
+    use console::Key;
+
+    fn editor() -> RebaseEditor {
+        let items = ["Add a", "Add b", "Add c"]
+            .iter()
+            .enumerate()
+            .map(|(index, summary)| TodoItem {
+                id: format!("{index}").repeat(40),
+                summary: summary.to_string(),
+                action: TodoAction::Pick,
+            })
+            .collect();
+        RebaseEditor::new("Rebase 3 commits onto main".to_string(), items)
+    }
+
+    #[test]
+    fn keys_reorder_and_change_actions() {
+        let mut editor = editor();
+        for key in [
+            Key::ArrowDown,
+            Key::Char('J'),
+            Key::Char('f'),
+            Key::Char('k'),
+        ] {
+            assert_eq!(editor.handle_key(key), Step::Redraw);
+        }
+        editor.set_action(TodoAction::Reword("Add c, better".to_string()));
+        editor.handle_key(Key::ArrowDown);
+        editor.handle_key(Key::ArrowDown);
+        editor.handle_key(Key::ArrowDown);
+        editor.handle_key(Key::Char('d'));
+
+        let plan: Vec<(&str, &str)> = editor
+            .items
+            .iter()
+            .map(|item| (item.summary.as_str(), item.action.name()))
+            .collect();
+        assert_eq!(
+            plan,
+            vec![("Add a", "pick"), ("Add c", "reword"), ("Add b", "drop")]
+        );
+        assert_eq!(editor.handle_key(Key::Enter), Step::Done);
+        assert_eq!(editor.handle_key(Key::Escape), Step::Cancel);
+    }
+
+    #[test]
+    fn first_commit_cannot_be_folded_away() {
+        let mut editor = editor();
+        editor.handle_key(Key::Char('d'));
+        editor.handle_key(Key::ArrowDown);
+        editor.handle_key(Key::Char('s'));
+
+        assert_eq!(editor.handle_key(Key::Enter), Step::Redraw);
+        assert!(editor.problem.as_deref().unwrap().contains("squash"));
+        editor.handle_key(Key::Char('p'));
+        assert_eq!(editor.handle_key(Key::Enter), Step::Done);
+    }
+
+    #[test]
+    fn render_shows_actions_and_help() {
+        let mut editor = editor();
+        editor.handle_key(Key::ArrowDown);
+        editor.handle_key(Key::Char('s'));
+        editor.handle_key(Key::ArrowDown);
+        editor.handle_key(Key::Char('d'));
+
+        let output = render(|out| editor.render(out));
+        insta::assert_snapshot!(normalize(&output, None));
+    }
+}
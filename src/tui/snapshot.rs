@@ -0,0 +1,74 @@
+//! Snapshot testing of command output.
+//!
+//! Presentation code writes to any `io::Write`; tests render into a buffer and snapshot the
+//! result with `insta` after [`normalize`] has removed what changes between runs: ANSI
+//! colors, temporary repository paths and commit hashes.
+
+use std::io;
+use std::path::Path;
+
+/// Run `write` against an in-memory buffer and return what it wrote
+pub fn render(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+    let mut buffer = Vec::new();
+    write(&mut buffer).expect("writing to a buffer cannot fail");
+    String::from_utf8(buffer).expect("output is UTF-8")
+}
+
+/// Strip colors, replace `repo` with `[REPO]` and commit hashes with `[SHA]`, and drop
+/// trailing whitespace
+pub fn normalize(output: &str, repo: Option<&Path>) -> String {
+    let mut output = console::strip_ansi_codes(output).into_owned();
+    if let Some(repo) = repo {
+        let canonical = repo.canonicalize().ok();
+        for path in canonical.as_deref().into_iter().chain([repo]) {
+            output = output.replace(&*path.to_string_lossy(), "[REPO]");
+        }
+    }
+
+    output
+        .lines()
+        .map(|line| redact_hashes(line.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Replace runs of 7 to 40 hex digits that contain at least one digit
+fn redact_hashes(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, result: &mut String| {
+        let is_hash = (7..=40).contains(&word.len())
+            && word.chars().any(|c| c.is_ascii_digit())
+            && word.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'));
+        result.push_str(if is_hash { "[SHA]" } else { word });
+        word.clear();
+    };
+    for c in line.chars() {
+        if c.is_ascii_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+    use std::path::Path;
+
+    #[test]
+    fn normalize_strips_colors_paths_and_hashes() {
+        let output = format!(
+            "{} at /tmp/repo/src\nHEAD 1a2b3c4d5e6f plus deadbeef and 1234   \n",
+            console::style("feature").cyan().bold().force_styling(true)
+        );
+        assert_eq!(
+            normalize(&output, Some(Path::new("/tmp/repo"))),
+            "feature at [REPO]/src\nHEAD [SHA] plus deadbeef and 1234"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use console::style;
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Render values as a one-line bar chart scaled to the largest value.
+///
+/// Zero renders as a blank so quiet days stand out; any non-zero value gets at least the
+/// lowest bar.
+pub fn sparkline(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| {
+            if value == 0 {
+                ' '
+            } else {
+                let levels = SPARK_LEVELS.len();
+                let level = (value * levels + max - 1) / max;
+                SPARK_LEVELS[level.clamp(1, levels) - 1]
+            }
+        })
+        .collect()
+}
+
+/// Print a labelled sparkline with its total and peak
+pub fn display_chart(label: &str, values: &[usize]) {
+    let total: usize = values.iter().sum();
+    let peak = values.iter().copied().max().unwrap_or(0);
+    println!("  {}", style(label).bold());
+    println!(
+        "  {}{}{} {}",
+        style("│").dim(),
+        style(sparkline(values)).green(),
+        style("│").dim(),
+        style(format!("total {total}, peak {peak}")).dim()
+    );
+}
+
+/// Print a single `label: value` statistic
+pub fn display_metric(label: &str, value: impl std::fmt::Display) {
+    println!(
+        "  {:<22} {}",
+        style(format!("{label}:")).dim(),
+        style(value).cyan()
+    );
+}
+
+/// Print a titled list of `name  detail` rows with the names aligned
+pub fn display_ranking(label: &str, rows: &[(String, String)]) {
+    println!("  {}", style(label).bold());
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, detail) in rows {
+        println!(
+            "    {}  {}",
+            style(format!("{name:<width$}")).cyan(),
+            style(detail).dim()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sparkline;
+
+    #[test]
+    fn sparkline_scales_to_peak_and_blanks_zero() {
+        assert_eq!(sparkline(&[0, 1, 4, 8]), " ▁▄█");
+        assert_eq!(sparkline(&[3, 3]), "██");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
@@ -0,0 +1,146 @@
+//! `--format` templates such as `"{name}\t{ahead}/{behind}\t{pr.state}"`.
+//!
+//! `{field}` is replaced with the field's value (empty when unavailable). `{{` and `}}`
+//! produce literal braces, and `\t`, `\n` and `\\` are unescaped so templates can be typed
+//! in a shell without `$'...'` quoting.
+
+use anyhow::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse a template, rejecting fields that are not in `known_fields`
+    pub fn parse(source: &str, known_fields: &[&str]) -> Result<Self, Error> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(anyhow::anyhow!(
+                                    "Unclosed '{{' in format template: {source}"
+                                ))
+                            }
+                        }
+                    }
+                    let name = name.trim().to_string();
+                    if !known_fields.contains(&name.as_str()) {
+                        return Err(anyhow::anyhow!(
+                            "Unknown format field '{{{name}}}'. Available fields: {}",
+                            known_fields.join(", ")
+                        ));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(name));
+                }
+                '}' => {
+                    return Err(anyhow::anyhow!(
+                        "Unmatched '}}' in format template (use '}}}}' for a literal brace)"
+                    ))
+                }
+                '\\' => match chars.peek() {
+                    Some('t') => {
+                        chars.next();
+                        literal.push('\t');
+                    }
+                    Some('n') => {
+                        chars.next();
+                        literal.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        literal.push('\\');
+                    }
+                    _ => literal.push('\\'),
+                },
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Whether any field in the template starts with `prefix`, to skip fetching unused data
+    pub fn uses_field_prefix(&self, prefix: &str) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Field(name) if name.starts_with(prefix)))
+    }
+
+    /// Render the template, looking up each field's value with `field`
+    pub fn render(&self, field: impl Fn(&str) -> Option<String>) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Field(name) => field(name).unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+
+    const FIELDS: &[&str] = &["name", "ahead", "behind", "pr.state"];
+
+    #[test]
+    fn render_substitutes_fields_and_escapes() {
+        let template = Template::parse(r"{name}\t{ahead}/{behind} {{{pr.state}}}", FIELDS).unwrap();
+        let rendered = template.render(|field| match field {
+            "name" => Some("feature".to_string()),
+            "ahead" => Some("2".to_string()),
+            "behind" => Some("0".to_string()),
+            _ => None,
+        });
+        assert_eq!(rendered, "feature\t2/0 {}");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_and_unclosed_fields() {
+        let err = Template::parse("{nme}", FIELDS).unwrap_err();
+        assert!(err.to_string().contains("Available fields: name, ahead"));
+        assert!(Template::parse("{name", FIELDS).is_err());
+        assert!(Template::parse("name}", FIELDS).is_err());
+    }
+
+    #[test]
+    fn uses_field_prefix_detects_pr_fields() {
+        assert!(Template::parse("{pr.state}", FIELDS)
+            .unwrap()
+            .uses_field_prefix("pr."));
+        assert!(!Template::parse("{name}", FIELDS)
+            .unwrap()
+            .uses_field_prefix("pr."));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::tui::emoji::icon;
+use anyhow::{Context, Error};
+use console::{style, Term};
+use notify::{RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How long to keep collecting filesystem events before redrawing, so one git
+/// operation touching many files causes a single refresh
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Paths under the git directory whose changes never affect what we display
+/// (object writes, and xg's own caches which a refresh itself may update)
+const IGNORED_GIT_DIRS: &[&str] = &["objects", "logs", "xgit"];
+
+/// Redraw `render` whenever the repository changes on disk, and at least every
+/// `poll_interval` so remote information (PRs, CI) is refreshed too. Runs until interrupted.
+pub async fn watch<F, Fut, E>(
+    workdir: &Path,
+    git_dir: &Path,
+    poll_interval: Duration,
+    mut is_ignored: impl FnMut(&Path) -> bool,
+    mut render: F,
+) -> Result<(), E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+    E: From<Error>,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            for path in event.paths {
+                let _ = tx.send(path);
+            }
+        }
+    })
+    .context("Failed to start filesystem watcher")?;
+    watcher
+        .watch(workdir, RecursiveMode::Recursive)
+        .context(format!("Failed to watch '{}'", workdir.display()))?;
+    if !git_dir.starts_with(workdir) {
+        watcher
+            .watch(git_dir, RecursiveMode::Recursive)
+            .context(format!("Failed to watch '{}'", git_dir.display()))?;
+    }
+
+    let term = Term::stdout();
+    loop {
+        let _ = term.clear_screen();
+        render().await?;
+        println!();
+        println!(
+            "{} {}",
+            style(icon("👀")).cyan(),
+            style(format!(
+                "Watching for changes (refresh every {}s) - press Ctrl-C to exit",
+                poll_interval.as_secs()
+            ))
+            .dim()
+        );
+
+        // Rendering itself can touch the repository (e.g. `git status` refreshing the index),
+        // so discard those events instead of redrawing in a loop
+        tokio::time::sleep(DEBOUNCE).await;
+        while rx.try_recv().is_ok() {}
+
+        // Wait for a relevant change or the poll interval, whichever comes first
+        let deadline = tokio::time::Instant::now() + poll_interval;
+        loop {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Err(_) => break,
+                Ok(None) => {
+                    // Watcher has gone away; fall back to plain polling
+                    tokio::time::sleep_until(deadline).await;
+                    break;
+                }
+                Ok(Some(path)) => {
+                    if is_relevant(&path, git_dir) && !is_ignored(&path) {
+                        tokio::time::sleep(DEBOUNCE).await;
+                        while rx.try_recv().is_ok() {}
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_relevant(path: &Path, git_dir: &Path) -> bool {
+    match path.strip_prefix(git_dir) {
+        Ok(relative) => {
+            let in_ignored_dir = IGNORED_GIT_DIRS.iter().any(|dir| relative.starts_with(dir));
+            let is_lock = path.extension().map_or(false, |ext| ext == "lock");
+            !in_ignored_dir && !is_lock
+        }
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_relevant;
+    use std::path::Path;
+
+    #[test]
+    fn is_relevant_skips_git_internals_that_do_not_change_output() {
+        let git_dir = Path::new("/repo/.git");
+        assert!(is_relevant(Path::new("/repo/src/main.rs"), git_dir));
+        assert!(is_relevant(Path::new("/repo/.git/HEAD"), git_dir));
+        assert!(is_relevant(
+            Path::new("/repo/.git/refs/heads/main"),
+            git_dir
+        ));
+        assert!(!is_relevant(
+            Path::new("/repo/.git/objects/ab/cdef"),
+            git_dir
+        ));
+        assert!(!is_relevant(
+            Path::new("/repo/.git/xgit/pr-index.json"),
+            git_dir
+        ));
+        assert!(!is_relevant(Path::new("/repo/.git/index.lock"), git_dir));
+    }
+}
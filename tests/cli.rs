@@ -0,0 +1,830 @@
+//! End-to-end scenarios that run the built `xg` binary against throwaway repositories.
+//!
+//! Each scenario gets its own home directory, global git config and `PATH`, so nothing from
+//! the developer's machine leaks in. The `claude` CLI is replaced by a script that always
+//! suggests the same commit message.
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+const STUB_COMMIT_MESSAGE: &str = "feat: Add greeting";
+
+struct Scenario {
+    _root: TempDir,
+    home: PathBuf,
+    bin: PathBuf,
+    repo: PathBuf,
+}
+
+impl Scenario {
+    fn new() -> Self {
+        let root = TempDir::new().unwrap();
+        let home = root.path().join("home");
+        let bin = root.path().join("bin");
+        let repo = root.path().join("repo");
+        for dir in [&home, &bin, &repo] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        write_executable(
+            &bin.join("claude"),
+            &format!("#!/bin/sh\necho '{{\"result\": \"{STUB_COMMIT_MESSAGE}\"}}'\n"),
+        );
+
+        Self {
+            _root: root,
+            home,
+            bin,
+            repo,
+        }
+    }
+
+    /// `xg` with `args`, run in the scenario's repository
+    fn xg(&self, args: &[&str]) -> Command {
+        let mut command = Command::cargo_bin("xg").unwrap();
+        command.args(args).current_dir(&self.repo);
+        self.isolate(&mut command);
+        command
+    }
+
+    /// Plain `git`, for setup and for checking what `xg` did
+    fn git(&self, args: &[&str]) -> String {
+        let mut command = Command::new("git");
+        command.args(args).current_dir(&self.repo);
+        self.isolate(&mut command);
+        let output = command.output().unwrap();
+        assert!(output.status.success(), "git {args:?} failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn isolate(&self, command: &mut Command) {
+        let path = format!(
+            "{}:{}",
+            self.bin.display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        command
+            .env("HOME", &self.home)
+            .env("XDG_CONFIG_HOME", self.home.join(".config"))
+            .env("GIT_CONFIG_GLOBAL", self.home.join(".gitconfig"))
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_EDITOR", "true")
+            .env("PATH", path)
+            .env("XGIT_LOCALE", "en")
+            .env_remove("XGIT_ASSUME_YES")
+            .env_remove("XGIT_GITHUB_BACKEND")
+            .env_remove("GITHUB_TOKEN")
+            .env_remove("GH_TOKEN")
+            .env_remove("GIT_DIR")
+            .env_remove("GIT_WORK_TREE");
+        for role in ["AUTHOR", "COMMITTER"] {
+            for field in ["NAME", "EMAIL", "DATE"] {
+                command.env_remove(format!("GIT_{role}_{field}"));
+            }
+        }
+    }
+
+    fn write(&self, file: &str, content: &str) {
+        fs::write(self.repo.join(file), content).unwrap();
+    }
+
+    fn stdout(&self, command: &mut Command) -> String {
+        let output = command.assert().success().get_output().stdout.clone();
+        normalize(&String::from_utf8(output).unwrap(), &self.repo)
+    }
+}
+
+fn write_executable(path: &Path, script: &str) {
+    fs::write(path, script).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Drop colors and the temporary repository path so output is stable between runs
+fn normalize(output: &str, repo: &Path) -> String {
+    let output = console::strip_ansi_codes(output).replace(&*repo.to_string_lossy(), "[REPO]");
+    output
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn branch_commit_with_ai_and_prune() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+
+    scenario.write("README.md", "hello\n");
+    scenario.xg(&["add", "README.md"]).assert().success();
+    scenario
+        .xg(&["commit", "-m", "Initial commit"])
+        .assert()
+        .success();
+
+    scenario.xg(&["switch", "-c", "feature"]).assert().success();
+    scenario.write("greeting.txt", "hi\n");
+    scenario.xg(&["add", "greeting.txt"]).assert().success();
+
+    // A dry run shows the suggested message without committing
+    let preview = scenario.stdout(&mut scenario.xg(&["--dry-run", "commit"]));
+    assert!(preview.contains(STUB_COMMIT_MESSAGE), "{preview}");
+    assert_eq!(scenario.git(&["rev-list", "--count", "HEAD"]).trim(), "1");
+
+    // Reviewing the suggestion needs a terminal, unless --yes accepts it as it is
+    scenario.xg(&["commit"]).assert().failure().code(2);
+    assert_eq!(scenario.git(&["rev-list", "--count", "HEAD"]).trim(), "1");
+    scenario.xg(&["-y", "commit"]).assert().success();
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        STUB_COMMIT_MESSAGE
+    );
+
+    scenario.xg(&["switch", "main"]).assert().success();
+    scenario.git(&["merge", "--ff-only", "feature"]);
+
+    // --dry-run lists the merged branch but keeps it
+    let dry_run = scenario.stdout(&mut scenario.xg(&["--dry-run", "branch", "--prune-merged"]));
+    insta::assert_snapshot!("prune_dry_run", dry_run);
+    assert!(scenario
+        .git(&["branch", "--list", "feature"])
+        .contains("feature"));
+
+    // Without --dry-run the deletion needs confirming, which fails without a terminal
+    scenario
+        .xg(&["branch", "--prune-merged", "--porcelain"])
+        .assert()
+        .failure();
+    assert!(scenario
+        .git(&["branch", "--list", "feature"])
+        .contains("feature"));
+
+    let pruned =
+        scenario.stdout(&mut scenario.xg(&["-y", "branch", "--prune-merged", "--porcelain"]));
+    insta::assert_snapshot!("prune_porcelain", pruned);
+    assert_eq!(scenario.git(&["branch", "--list", "feature"]), "");
+}
+
+#[test]
+fn usage_errors_exit_with_code_two() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+
+    let output = scenario
+        .xg(&["branch", "--format", "{no-such-field}"])
+        .assert()
+        .code(2)
+        .get_output()
+        .stderr
+        .clone();
+    let stderr = normalize(&String::from_utf8(output).unwrap(), &scenario.repo);
+    assert!(stderr.contains("no-such-field"), "{stderr}");
+}
+
+#[test]
+fn ignore_init_without_a_terminal_keeps_ignored_files_tracked() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    fs::create_dir(scenario.repo.join("target")).unwrap();
+    scenario.write("target/out.txt", "built\n");
+    scenario.git(&["add", "target/out.txt"]);
+    scenario.git(&["commit", "-m", "chore: Initial commit"]);
+
+    let output = scenario.stdout(&mut scenario.xg(&["ignore", "init", "rust"]));
+    assert!(
+        output.contains("1 tracked file(s) are now ignored"),
+        "{output}"
+    );
+    assert!(output.contains("Left them tracked"), "{output}");
+    assert!(fs::read_to_string(scenario.repo.join(".gitignore"))
+        .unwrap()
+        .contains("target"));
+    assert_eq!(
+        scenario.git(&["ls-files", "target"]).trim(),
+        "target/out.txt"
+    );
+
+    // --yes answers for the missing terminal
+    scenario
+        .xg(&["-y", "ignore", "init", "node"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["ls-files", "target"]), "");
+}
+
+#[test]
+fn version_bump_updates_manifest_commits_and_tags() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+
+    scenario.write(
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+    );
+    scenario.write(
+        "Cargo.lock",
+        "version = 4\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+    );
+    scenario.git(&["add", "Cargo.toml", "Cargo.lock"]);
+    scenario.git(&["commit", "-m", "chore: Initial commit"]);
+    scenario.git(&["tag", "v0.1.0"]);
+    scenario.write("lib.rs", "\n");
+    scenario.git(&["add", "lib.rs"]);
+    scenario.git(&["commit", "-m", "feat: Add library"]);
+
+    let preview = scenario.stdout(&mut scenario.xg(&["--dry-run", "version", "bump"]));
+    assert!(preview.contains("0.1.0 → 0.2.0 (minor)"), "{preview}");
+    assert_eq!(scenario.git(&["tag", "--list"]).trim(), "v0.1.0");
+
+    scenario.xg(&["version", "bump"]).assert().success();
+    assert_eq!(
+        fs::read_to_string(scenario.repo.join("Cargo.toml")).unwrap(),
+        "[package]\nname = \"demo\"\nversion = \"0.2.0\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(scenario.repo.join("Cargo.lock")).unwrap(),
+        "version = 4\n\n[[package]]\nname = \"demo\"\nversion = \"0.2.0\"\n"
+    );
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        "chore(release): v0.2.0"
+    );
+    assert_eq!(
+        scenario
+            .git(&["show", "--name-only", "--format=", "HEAD"])
+            .trim(),
+        "Cargo.lock\nCargo.toml"
+    );
+    assert!(scenario.git(&["status", "--porcelain"]).is_empty());
+    assert_eq!(
+        scenario
+            .git(&[
+                "tag",
+                "--list",
+                "v0.2.0",
+                "--format=%(objecttype) %(subject)"
+            ])
+            .trim(),
+        "tag Release v0.2.0"
+    );
+
+    // Only chores since the release: nothing to do
+    let nothing = scenario.stdout(&mut scenario.xg(&["version", "bump"]));
+    assert!(nothing.contains("nothing to release"), "{nothing}");
+    scenario
+        .xg(&["version", "bump", "--patch"])
+        .assert()
+        .success();
+    assert!(scenario.git(&["tag", "--list"]).contains("v0.2.1"));
+
+    // Without a subcommand it is still `git version`
+    let version = scenario.stdout(&mut scenario.xg(&["version"]));
+    assert!(version.starts_with("git version"), "{version}");
+}
+
+#[test]
+fn changelog_update_is_committed_with_the_release() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+
+    scenario.write("README.md", "demo\n");
+    scenario.git(&["add", "README.md"]);
+    scenario.git(&["commit", "-m", "chore: Initial commit"]);
+    scenario.git(&["tag", "v1.0.0"]);
+    scenario.write("README.md", "demo\nmore\n");
+    scenario.git(&["commit", "-am", "fix(readme): Describe the demo (#3)"]);
+
+    scenario.xg(&["changelog", "update"]).assert().success();
+    let changelog = fs::read_to_string(scenario.repo.join("CHANGELOG.md")).unwrap();
+    assert!(changelog.starts_with("# Changelog\n"), "{changelog}");
+    assert!(changelog.contains("## [1.0.1] - "), "{changelog}");
+    assert!(
+        changelog.contains("### Fixed\n\n- **readme:** Describe the demo (#3)\n"),
+        "{changelog}"
+    );
+    assert_eq!(
+        scenario.git(&["diff", "--cached", "--name-only"]).trim(),
+        "CHANGELOG.md"
+    );
+
+    scenario.xg(&["version", "bump"]).assert().success();
+    assert_eq!(
+        scenario
+            .git(&["show", "--name-only", "--format=%s", "v1.0.1"])
+            .lines()
+            .rfind(|line| !line.is_empty()),
+        Some("CHANGELOG.md")
+    );
+    assert_eq!(scenario.git(&["status", "--porcelain"]), "");
+}
+
+#[test]
+fn rebase_stops_on_conflict_and_continues() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+
+    scenario.write("notes.txt", "base\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.git(&["commit", "-m", "Initial commit"]);
+    scenario.git(&["switch", "-c", "feature"]);
+    scenario.write("notes.txt", "feature\n");
+    scenario.git(&["commit", "-am", "Edit notes on feature"]);
+    scenario.git(&["switch", "main"]);
+    scenario.write("notes.txt", "main\n");
+    scenario.git(&["commit", "-am", "Edit notes on main"]);
+    scenario.git(&["switch", "feature"]);
+
+    let preview = scenario.stdout(&mut scenario.xg(&["--dry-run", "rebase", "main"]));
+    assert!(
+        preview.contains("rebase 1 commit of 'feature' onto 'main'"),
+        "{preview}"
+    );
+
+    let output = scenario.xg(&["rebase", "main"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Edit notes on feature"), "{stderr}");
+    assert!(stderr.contains("xg rebase --continue"), "{stderr}");
+
+    scenario.write("notes.txt", "both\n");
+    scenario.git(&["add", "notes.txt"]);
+    let continued = scenario.stdout(&mut scenario.xg(&["rebase", "--continue"]));
+    assert!(continued.contains("Rebased feature"), "{continued}");
+    assert_eq!(
+        scenario.git(&["log", "--format=%s"]).trim(),
+        "Edit notes on feature\nEdit notes on main\nInitial commit"
+    );
+    assert_eq!(scenario.git(&["status", "--porcelain"]), "");
+
+    // Without a branch, the rebase goes onto the upstream
+    let origin = scenario.repo.to_string_lossy().to_string();
+    scenario.git(&["remote", "add", "origin", &origin]);
+    scenario.git(&["switch", "main"]);
+    scenario.write("other.txt", "main\n");
+    scenario.git(&["add", "other.txt"]);
+    scenario.git(&["commit", "-m", "Add other on main"]);
+    scenario.git(&["fetch", "origin"]);
+    scenario.git(&["switch", "feature"]);
+    scenario.git(&["branch", "--set-upstream-to", "origin/main"]);
+    let rebased = scenario.stdout(&mut scenario.xg(&["rebase"]));
+    assert!(rebased.contains("Rebased feature"), "{rebased}");
+
+    // Options xg doesn't handle go to git rebase
+    scenario
+        .xg(&["rebase", "--onto", "main~1", "main"])
+        .assert()
+        .success();
+    assert_eq!(
+        scenario.git(&["log", "--format=%s", "-1", "HEAD~1"]).trim(),
+        "Edit notes on main"
+    );
+}
+
+#[test]
+fn stash_save_list_pop_and_drop() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+
+    scenario.write("notes.txt", "base\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.git(&["commit", "-m", "Initial commit"]);
+
+    let empty = scenario.stdout(&mut scenario.xg(&["stash"]));
+    assert!(empty.contains("No local changes to stash"), "{empty}");
+
+    scenario.write("notes.txt", "first\n");
+    let saved = scenario.stdout(&mut scenario.xg(&["stash", "save", "-m", "first try"]));
+    assert!(saved.contains("stash@{0}: On main: first try"), "{saved}");
+    scenario.write("notes.txt", "second\n");
+    scenario.xg(&["stash"]).assert().success();
+    assert_eq!(scenario.git(&["status", "--porcelain"]), "");
+
+    let list = scenario.stdout(&mut scenario.xg(&["stash", "list"]));
+    assert!(list.contains("stash@{0}"), "{list}");
+    assert!(list.contains("stash@{1}"), "{list}");
+    assert!(list.contains("On main: first try"), "{list}");
+
+    let popped = scenario.stdout(&mut scenario.xg(&["stash", "pop", "1"]));
+    assert!(popped.contains("Popped stash@{1}"), "{popped}");
+    assert_eq!(
+        fs::read_to_string(scenario.repo.join("notes.txt")).unwrap(),
+        "first\n"
+    );
+
+    // Dropping needs confirmation, which fails without a terminal
+    scenario.xg(&["stash", "drop"]).assert().failure();
+    scenario.xg(&["stash", "drop", "-y"]).assert().success();
+    let list = scenario.stdout(&mut scenario.xg(&["stash", "list"]));
+    assert!(list.contains("The stash is empty"), "{list}");
+
+    // Subcommands and options xg doesn't model go to git stash
+    scenario.write("untracked.txt", "new\n");
+    scenario.xg(&["stash", "-u"]).assert().success();
+    assert_eq!(scenario.git(&["status", "--porcelain"]), "");
+    scenario.xg(&["stash", "pop"]).assert().success();
+    scenario
+        .xg(&["stash", "push", "-m", "wip"])
+        .assert()
+        .success();
+    let list = scenario.stdout(&mut scenario.xg(&["stash", "list"]));
+    assert!(list.contains("On main: wip"), "{list}");
+}
+
+#[test]
+fn cherry_pick_reports_conflicts_and_finishes_with_commit() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+
+    scenario.write("notes.txt", "base\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.git(&["commit", "-m", "Initial commit"]);
+    scenario.git(&["switch", "-c", "feature"]);
+    scenario.write("feature.txt", "feature\n");
+    scenario.git(&["add", "feature.txt"]);
+    scenario.git(&[
+        "commit",
+        "-m",
+        "Add feature",
+        "--author",
+        "Feature Author <feature@example.com>",
+    ]);
+    scenario.write("notes.txt", "feature\n");
+    scenario.git(&["commit", "-am", "Edit notes on feature"]);
+    scenario.git(&["switch", "main"]);
+    scenario.write("notes.txt", "main\n");
+    scenario.git(&["commit", "-am", "Edit notes on main"]);
+
+    let picked = scenario.stdout(&mut scenario.xg(&["cherry-pick", "feature~1"]));
+    assert!(picked.contains("Add feature"), "{picked}");
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%an|%s"]).trim(),
+        "Feature Author|Add feature"
+    );
+
+    let output = scenario.xg(&["cherry-pick", "feature"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("notes.txt"), "{stderr}");
+    assert!(stderr.contains("xg commit --no-edit"), "{stderr}");
+
+    scenario.write("notes.txt", "both\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.xg(&["commit", "--no-edit"]).assert().success();
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        "Edit notes on feature"
+    );
+    assert_eq!(scenario.git(&["status", "--porcelain"]), "");
+
+    // Options and several commits go to git cherry-pick
+    scenario.git(&["switch", "-c", "other", "main~3"]);
+    scenario
+        .xg(&["cherry-pick", "-x", "feature~1"])
+        .assert()
+        .success();
+    assert!(scenario
+        .git(&["log", "-1", "--format=%b"])
+        .contains("cherry picked from commit"));
+    scenario
+        .xg(&["cherry-pick", "main~2", "main"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["rev-list", "--count", "HEAD"]).trim(), "4");
+}
+
+#[test]
+fn commit_amend_rewrites_the_tip() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    scenario.write("README.md", "hello\n");
+    scenario.git(&["add", "README.md"]);
+    scenario
+        .xg(&["commit", "-m", "Initial commit"])
+        .assert()
+        .success();
+
+    scenario.write("notes.txt", "notes\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario
+        .xg(&["commit", "--amend", "--no-edit"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["rev-list", "--count", "HEAD"]).trim(), "1");
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        "Initial commit"
+    );
+    assert!(scenario
+        .git(&["ls-tree", "--name-only", "HEAD"])
+        .contains("notes.txt"));
+
+    // Without a message the AI suggests one for the combined changes
+    scenario.xg(&["-y", "commit", "--amend"]).assert().success();
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        STUB_COMMIT_MESSAGE
+    );
+
+    scenario
+        .xg(&["commit", "--amend", "-m", "docs: Add readme and notes"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["rev-list", "--count", "HEAD"]).trim(), "1");
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        "docs: Add readme and notes"
+    );
+}
+
+#[test]
+fn sync_fast_forwards_and_reports_pruned_branches() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    let remote = scenario.repo.parent().unwrap().join("remote.git");
+    scenario.git(&["init", "--bare", remote.to_str().unwrap()]);
+    scenario.git(&["remote", "add", "origin", remote.to_str().unwrap()]);
+
+    scenario.write("notes.txt", "base\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.git(&["commit", "-m", "Initial commit"]);
+    scenario.git(&["push", "-u", "origin", "main"]);
+    scenario.git(&["branch", "feature"]);
+    scenario.git(&["push", "-u", "origin", "feature"]);
+
+    // Someone else pushes to main and deletes feature
+    let other = scenario.repo.parent().unwrap().join("other");
+    let other = other.to_str().unwrap();
+    scenario.git(&["clone", "--branch", "main", remote.to_str().unwrap(), other]);
+    scenario.git(&[
+        "-C",
+        other,
+        "-c",
+        "user.name=Other",
+        "-c",
+        "user.email=other@example.com",
+        "commit",
+        "--allow-empty",
+        "-m",
+        "Upstream change",
+    ]);
+    scenario.git(&["-C", other, "push", "origin", "main", ":feature"]);
+
+    scenario.xg(&["--dry-run", "sync"]).assert().success();
+    assert_eq!(scenario.git(&["rev-list", "--count", "HEAD"]).trim(), "1");
+
+    let output = scenario.stdout(&mut scenario.xg(&["sync"]));
+    assert!(output.contains("fast-forwarded 1 commit"), "{output}");
+    assert!(output.contains("upstream gone"), "{output}");
+    assert!(output.contains("origin/feature"), "{output}");
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        "Upstream change"
+    );
+
+    let again = scenario.stdout(&mut scenario.xg(&["sync"]));
+    assert!(again.contains("up to date"), "{again}");
+    assert!(!again.contains("Pruned"), "{again}");
+}
+
+#[test]
+fn tag_create_list_push_and_delete() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    let remote = scenario.repo.parent().unwrap().join("remote.git");
+    scenario.git(&["init", "--bare", remote.to_str().unwrap()]);
+    scenario.git(&["remote", "add", "origin", remote.to_str().unwrap()]);
+
+    scenario.write("notes.txt", "base\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.git(&["commit", "-m", "Initial commit"]);
+
+    scenario
+        .xg(&["tag", "create", "v1.0.0", "-m", "First release"])
+        .assert()
+        .success();
+    scenario.xg(&["tag", "create", "base"]).assert().success();
+    let list = scenario.stdout(&mut scenario.xg(&["tag"]));
+    assert!(list.contains("base"), "{list}");
+    assert!(list.contains("(lightweight)"), "{list}");
+    assert!(list.contains("v1.0.0"), "{list}");
+    assert!(list.contains("First release"), "{list}");
+
+    let pushed = scenario.stdout(&mut scenario.xg(&["tag", "push", "v1.0.0"]));
+    assert!(pushed.contains("Pushed 1 tag to origin"), "{pushed}");
+    assert_eq!(
+        scenario
+            .git(&["ls-remote", "--tags", "origin"])
+            .lines()
+            .count(),
+        2,
+        "the annotated tag and its peeled commit"
+    );
+
+    scenario.xg(&["tag", "delete", "base"]).assert().failure();
+    scenario
+        .xg(&["-y", "tag", "delete", "base"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["tag", "--list"]).trim(), "v1.0.0");
+    scenario
+        .xg(&["-y", "tag", "delete", "missing"])
+        .assert()
+        .failure()
+        .code(2);
+
+    // Plain git tag arguments pass through
+    scenario
+        .xg(&["tag", "-a", "v1.1.0", "-m", "Second release"])
+        .assert()
+        .success();
+    scenario.xg(&["tag", "v2.0.0"]).assert().success();
+    let listed = scenario.stdout(&mut scenario.xg(&["tag", "-l", "v1.*"]));
+    assert_eq!(listed, "v1.0.0\nv1.1.0");
+}
+
+#[test]
+fn sync_fails_when_a_remote_cannot_be_fetched() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    scenario.git(&["commit", "--allow-empty", "-m", "chore: Initial commit"]);
+    scenario.git(&["remote", "add", "origin", "/nonexistent/repo.git"]);
+
+    let output = scenario
+        .xg(&["sync"])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stderr = normalize(&String::from_utf8(output.stderr).unwrap(), &scenario.repo);
+    assert!(stderr.contains("Failed to fetch origin"), "{stderr}");
+}
+
+#[test]
+fn log_without_a_view_passes_its_flags_to_git_log() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    for subject in ["first", "second", "third"] {
+        scenario.write("notes.txt", subject);
+        scenario.git(&["add", "notes.txt"]);
+        scenario.git(&["commit", "-m", subject]);
+    }
+
+    let latest = scenario.stdout(&mut scenario.xg(&["log", "-n", "1", "--pretty=%s"]));
+    assert_eq!(latest, "third");
+    let paged =
+        scenario.stdout(&mut scenario.xg(&["log", "--skip", "1", "-n", "1", "--pretty=%s"]));
+    assert_eq!(paged, "second");
+    let authored = scenario.stdout(&mut scenario.xg(&["log", "--author", "Nobody", "--pretty=%s"]));
+    assert_eq!(authored, "");
+    let touched =
+        scenario.stdout(&mut scenario.xg(&["log", "--path", "missing.txt", "--pretty=%s"]));
+    assert_eq!(touched, "");
+
+    // A lone path after `--` gets xg's own file history; more options go to git log --follow
+    let history = scenario.stdout(&mut scenario.xg(&["log", "--follow", "--", "notes.txt"]));
+    assert!(history.contains("third"), "{history}");
+    let patched = scenario.stdout(&mut scenario.xg(&[
+        "log",
+        "--follow",
+        "-p",
+        "--pretty=%s",
+        "--",
+        "notes.txt",
+    ]));
+    assert!(patched.starts_with("third\n"), "{patched}");
+    assert!(patched.contains("+second"), "{patched}");
+}
+
+#[test]
+fn remote_passes_unmodelled_subcommands_to_git_remote() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario
+        .xg(&["remote", "add", "origin", "https://example.com/a.git"])
+        .assert()
+        .success();
+
+    scenario
+        .xg(&["remote", "set-url", "origin", "https://example.com/b.git"])
+        .assert()
+        .success();
+    let verbose = scenario.stdout(&mut scenario.xg(&["remote", "-v"]));
+    assert!(
+        verbose.contains("origin\thttps://example.com/b.git (fetch)"),
+        "{verbose}"
+    );
+}
+
+#[test]
+fn pull_passes_unmodelled_options_to_git_pull() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    let remote = scenario.repo.parent().unwrap().join("remote.git");
+    scenario.git(&["init", "--bare", remote.to_str().unwrap()]);
+    scenario.git(&["remote", "add", "origin", remote.to_str().unwrap()]);
+    scenario.git(&["commit", "--allow-empty", "-m", "Initial commit"]);
+    scenario.git(&["push", "-u", "origin", "main"]);
+
+    let other = scenario.repo.parent().unwrap().join("other");
+    let other = other.to_str().unwrap();
+    scenario.git(&["clone", "--branch", "main", remote.to_str().unwrap(), other]);
+    scenario.git(&[
+        "-C",
+        other,
+        "-c",
+        "user.name=Other",
+        "-c",
+        "user.email=other@example.com",
+        "commit",
+        "--allow-empty",
+        "-m",
+        "Upstream change",
+    ]);
+    scenario.git(&["-C", other, "tag", "v1.0.0"]);
+    scenario.git(&["-C", other, "push", "origin", "main", "v1.0.0"]);
+
+    scenario
+        .xg(&["pull", "--ff-only", "--tags", "--no-edit"])
+        .assert()
+        .success();
+    assert_eq!(
+        scenario.git(&["log", "-1", "--format=%s"]).trim(),
+        "Upstream change"
+    );
+    assert_eq!(scenario.git(&["tag", "--list"]).trim(), "v1.0.0");
+}
+
+#[test]
+fn switch_back_detach_and_orphan() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    scenario.git(&["commit", "--allow-empty", "-m", "Initial commit"]);
+
+    scenario.xg(&["switch", "-c", "feature"]).assert().success();
+    scenario.xg(&["switch", "-"]).assert().success();
+    assert_eq!(scenario.git(&["branch", "--show-current"]).trim(), "main");
+    scenario.xg(&["switch", "-"]).assert().success();
+    assert_eq!(
+        scenario.git(&["branch", "--show-current"]).trim(),
+        "feature"
+    );
+
+    scenario
+        .xg(&["switch", "--detach", "main"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["branch", "--show-current"]).trim(), "");
+    scenario
+        .xg(&["switch", "--orphan", "fresh"])
+        .assert()
+        .success();
+    assert_eq!(scenario.git(&["branch", "--show-current"]).trim(), "fresh");
+}
+
+#[test]
+fn blame_passes_git_blame_options_through() {
+    let scenario = Scenario::new();
+    scenario.git(&["init", "--initial-branch", "main"]);
+    scenario.git(&["config", "user.name", "Test User"]);
+    scenario.git(&["config", "user.email", "test@example.com"]);
+    scenario.write("notes.txt", "one\ntwo\nthree\n");
+    scenario.git(&["add", "notes.txt"]);
+    scenario.git(&["commit", "-m", "Initial commit"]);
+
+    let native = scenario.stdout(&mut scenario.xg(&["blame", "--no-pr", "notes.txt"]));
+    assert!(native.contains("Test User"), "{native}");
+    let lines = scenario.stdout(&mut scenario.xg(&["blame", "-L", "2,3", "-w", "notes.txt"]));
+    assert_eq!(lines.lines().count(), 2, "{lines}");
+    assert!(lines.contains("two"), "{lines}");
+    assert!(!lines.contains("one"), "{lines}");
+}
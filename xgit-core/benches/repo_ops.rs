@@ -0,0 +1,244 @@
+//! Benchmarks for the repository layer on synthetic repositories of several sizes.
+//!
+//! Run with `cargo bench -p xgit-core`; pass a filter such as `cargo bench -p xgit-core merge`
+//! to run one group.
+
+use std::fs;
+
+use assert_fs::TempDir;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use git2::Repository;
+use xgit_core::{GitRepo, MergeBaseCache};
+
+/// (commits on master, branches) for each synthetic repository
+const SIZES: &[(usize, usize)] = &[(100, 10), (1_000, 50)];
+/// Files the commits rotate through, so diffs touch a realistic spread of paths
+const FILES: usize = 50;
+
+struct SyntheticRepo {
+    _dir: TempDir,
+    repo: GitRepo,
+    branches: Vec<String>,
+}
+
+/// Build a repository with `commits` linear commits on master and `branches` branches forked
+/// at evenly spaced points; every other branch carries one extra, unmerged commit.
+fn synthetic_repo(commits: usize, branches: usize) -> SyntheticRepo {
+    let dir = TempDir::new().expect("create temp dir");
+    let repo = GitRepo::init(dir.path()).expect("init repo");
+    repo.set_user_config("Bench User", "bench@example.com")
+        .expect("set identity");
+
+    for i in 0..commits {
+        let path = dir.path().join(format!("file_{}.txt", i % FILES));
+        let mut contents = fs::read_to_string(&path).unwrap_or_default();
+        contents.push_str(&format!("line {i}\n"));
+        fs::write(&path, contents).expect("write file");
+        repo.add(&["."]).expect("stage");
+        repo.commit(&format!("Commit {i}")).expect("commit");
+    }
+
+    let raw = Repository::open(dir.path()).expect("open repo");
+    let signature = git2::Signature::now("Bench User", "bench@example.com").expect("signature");
+    let head = raw
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .expect("head");
+    let mut names = Vec::new();
+    for b in 0..branches {
+        let mut fork = head.clone();
+        for _ in 0..(b * commits / branches.max(1)).min(commits - 1) {
+            fork = fork.parent(0).expect("parent");
+        }
+        let name = format!("branch-{b}");
+        let tip = if b % 2 == 0 {
+            fork.id()
+        } else {
+            let tree = fork.tree().expect("tree");
+            raw.commit(None, &signature, &signature, &name, &tree, &[&fork])
+                .expect("branch commit")
+        };
+        raw.reference(&format!("refs/heads/{name}"), tip, true, "bench branch")
+            .expect("create branch");
+        names.push(name);
+    }
+
+    SyntheticRepo {
+        _dir: dir,
+        repo,
+        branches: names,
+    }
+}
+
+/// A single-commit repository with `files` files spread over directories of 100, with one
+/// file modified and staged.
+///
+/// The index is rebuilt from scratch, as after a reset or by tools that do not maintain
+/// git's cached trees, so nothing about the index's tree shape is known up front.
+fn wide_repo(files: usize) -> TempDir {
+    let dir = TempDir::new().expect("create temp dir");
+    let repo = GitRepo::init(dir.path()).expect("init repo");
+    repo.set_user_config("Bench User", "bench@example.com")
+        .expect("set identity");
+    for i in 0..files {
+        let subdir = dir.path().join(format!("dir_{}", i / 100));
+        fs::create_dir_all(&subdir).expect("create dir");
+        fs::write(subdir.join(format!("file_{i}.txt")), format!("{i}\n")).expect("write file");
+    }
+    repo.add(&["."]).expect("stage");
+    repo.commit("Add files").expect("commit");
+
+    fs::write(dir.path().join("dir_0").join("file_0.txt"), "changed\n").expect("write file");
+
+    let raw = Repository::open(dir.path()).expect("open repo");
+    let mut index = raw.index().expect("read index");
+    index.clear().expect("clear index");
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .expect("stage");
+    index.write().expect("write index");
+    dir
+}
+
+fn label(commits: usize, branches: usize) -> String {
+    format!("{commits}c/{branches}b")
+}
+
+fn bench_list_commits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_commits");
+    for &(commits, branches) in SIZES {
+        let synthetic = synthetic_repo(commits, branches);
+        group.bench_function(BenchmarkId::new("all", label(commits, branches)), |b| {
+            b.iter(|| synthetic.repo.list_commits_range("HEAD", 0, None).unwrap())
+        });
+        group.bench_function(
+            BenchmarkId::new("first_20", label(commits, branches)),
+            |b| {
+                b.iter(|| {
+                    synthetic
+                        .repo
+                        .list_commits_range("HEAD", 0, Some(20))
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_branch_stats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("branch_stats");
+    for &(commits, branches) in SIZES {
+        let synthetic = synthetic_repo(commits, branches);
+        let repo = &synthetic.repo;
+        group.bench_function(BenchmarkId::from_parameter(label(commits, branches)), |b| {
+            b.iter(|| {
+                let cache = MergeBaseCache::in_memory();
+                let names = repo.get_all_branches().unwrap();
+                let merged = repo.branches_merged_to_main(&cache, &names).unwrap();
+                for name in &names {
+                    repo.get_branch_commit_info(name).unwrap();
+                    repo.ahead_behind_cached(&cache, name, "master").unwrap();
+                }
+                merged
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_merge_base(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merge_base");
+    for &(commits, branches) in SIZES {
+        let synthetic = synthetic_repo(commits, branches);
+        let repo = &synthetic.repo;
+        group.bench_function(
+            BenchmarkId::new("per_branch", label(commits, branches)),
+            |b| {
+                b.iter(|| {
+                    for name in &synthetic.branches {
+                        repo.is_branch_merged_to_main(name).unwrap();
+                    }
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("single_walk", label(commits, branches)),
+            |b| {
+                b.iter(|| {
+                    repo.branches_merged_to_main(&MergeBaseCache::in_memory(), &synthetic.branches)
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_diff(c: &mut Criterion) {
+    let mut group = c.benchmark_group("diff");
+    for &(commits, branches) in SIZES {
+        let synthetic = synthetic_repo(commits, branches);
+        let repo = &synthetic.repo;
+        // A commit that rewrites every file, so the diff size grows with the history
+        for i in 0..FILES {
+            let path = repo.path().join(format!("file_{i}.txt"));
+            let contents = fs::read_to_string(&path).unwrap_or_default();
+            fs::write(&path, contents.replace("line", "row")).unwrap();
+        }
+        repo.add(&["."]).unwrap();
+        repo.commit("Rewrite every file").unwrap();
+
+        group.bench_function(
+            BenchmarkId::new("to_string", label(commits, branches)),
+            |b| {
+                b.iter(|| {
+                    let diff = repo.get_commit_diff("HEAD").unwrap();
+                    repo.diff_to_string(&diff).unwrap()
+                })
+            },
+        );
+        group.bench_function(BenchmarkId::new("stats", label(commits, branches)), |b| {
+            b.iter(|| {
+                let diff = repo.get_commit_diff("HEAD").unwrap();
+                repo.diff_stats(&diff).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_staged_changes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("staged_changes");
+    for files in [1_000, 20_000] {
+        // Each iteration opens the repository afresh, like a new `xg commit` would
+        let dir = wide_repo(files);
+        group.bench_function(BenchmarkId::new("has_staged_changes", files), |b| {
+            b.iter(|| {
+                assert!(GitRepo::open(dir.path())
+                    .unwrap()
+                    .has_staged_changes()
+                    .unwrap())
+            })
+        });
+        // The previous implementation: write the index out as a tree and compare with HEAD's
+        group.bench_function(BenchmarkId::new("write_tree", files), |b| {
+            b.iter(|| {
+                let raw = Repository::open(dir.path()).unwrap();
+                let head_tree = raw.head().unwrap().peel_to_tree().unwrap().id();
+                assert_ne!(raw.index().unwrap().write_tree().unwrap(), head_tree)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_list_commits,
+    bench_branch_stats,
+    bench_merge_base,
+    bench_diff,
+    bench_staged_changes
+);
+criterion_main!(benches);
@@ -0,0 +1,500 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::git::branches::naming::{infer_branch_type, slugify, BRANCH_TYPES};
+use crate::git::GitRepo;
+
+/// Line that separates alternative commit messages in a response
+const MESSAGE_SEPARATOR: &str = "---";
+
+/// Generate a commit message from a git diff using Claude AI
+pub fn generate_commit_message(diff_text: &str) -> Result<Option<String>, anyhow::Error> {
+    Ok(generate_commit_messages(diff_text, 1)?.into_iter().next())
+}
+
+/// Generate up to `count` alternative commit messages from a git diff using Claude AI; none
+/// when the diff is empty or Claude is unavailable
+pub fn generate_commit_messages(
+    diff_text: &str,
+    count: usize,
+) -> Result<Vec<String>, anyhow::Error> {
+    if diff_text.is_empty() || count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let respond_with = if count == 1 {
+        "Respond with ONLY the commit message, no additional text or formatting.".to_string()
+    } else {
+        format!(
+            "Write {count} different commit messages, each taking a different angle on the change.
+Respond with ONLY the commit messages, separated by a line containing only {MESSAGE_SEPARATOR}, no additional text or formatting."
+        )
+    };
+
+    // Prepare the prompt for Claude
+    let prompt = format!(
+        "Based on the following git diff, generate a conventional commit message.
+
+The message should follow this format:
+<type>[optional scope]: <description>
+
+[optional body]
+
+Choose type from: feat, fix, docs, style, refactor, test, chore
+Keep the description under 50 characters, use imperative mood, and capitalize the first letter.
+
+{respond_with}
+
+Git diff:
+{diff_text}"
+    );
+
+    let mut messages = ask_claude(&prompt)
+        .map(|response| split_messages(&response))
+        .unwrap_or_default();
+    messages.truncate(count);
+    Ok(messages)
+}
+
+/// Split a response into its messages, dropping empty ones and repeats
+fn split_messages(response: &str) -> Vec<String> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for line in response.lines().chain(std::iter::once(MESSAGE_SEPARATOR)) {
+        if line.trim() != MESSAGE_SEPARATOR {
+            current.push_str(line);
+            current.push('\n');
+            continue;
+        }
+        let message = current.trim().to_string();
+        if !message.is_empty() && !messages.contains(&message) {
+            messages.push(message);
+        }
+        current.clear();
+    }
+    messages
+}
+
+/// The commit messages last suggested, kept in `.git/xgit/ai-commit-cache.json` with a hash
+/// of the diff they describe so asking again for the same changes doesn't call the model
+#[derive(Debug, Clone)]
+pub struct CommitMessageCache {
+    path: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedMessages {
+    diff_hash: String,
+    messages: Vec<String>,
+}
+
+impl CommitMessageCache {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn for_repo(repo: &GitRepo) -> Self {
+        Self::new(repo.git_dir().join("xgit").join("ai-commit-cache.json"))
+    }
+
+    /// The messages cached for `diff_text`, if it is the diff they were suggested for
+    pub fn get(&self, diff_text: &str) -> Option<Vec<String>> {
+        let contents = fs::read(&self.path).ok()?;
+        let cached: CachedMessages = serde_json::from_slice(&contents).ok()?;
+        (cached.diff_hash == diff_hash(diff_text)).then(|| cached.messages)
+    }
+
+    /// Remember `messages` as the suggestions for `diff_text`, replacing what was cached
+    pub fn store(&self, diff_text: &str, messages: &[String]) -> Result<(), anyhow::Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create cache directory '{}'",
+                parent.display()
+            ))?;
+        }
+        let cached = CachedMessages {
+            diff_hash: diff_hash(diff_text),
+            messages: messages.to_vec(),
+        };
+        fs::write(&self.path, serde_json::to_vec(&cached)?).context(format!(
+            "Failed to write commit message cache '{}'",
+            self.path.display()
+        ))
+    }
+}
+
+/// Stable hash of a diff: its git blob id
+fn diff_hash(diff_text: &str) -> String {
+    git2::Oid::hash_object(git2::ObjectType::Blob, diff_text.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default()
+}
+
+/// A pull request title and markdown description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrDescription {
+    pub title: String,
+    pub body: String,
+}
+
+/// Draft a pull request title and description from the branch's commit subjects and its diff
+/// against the base branch using Claude AI
+pub fn generate_pr_description(
+    commits: &[String],
+    diff_text: &str,
+) -> Result<Option<PrDescription>, anyhow::Error> {
+    if diff_text.is_empty() {
+        return Ok(None);
+    }
+
+    let commit_list: String = commits
+        .iter()
+        .map(|subject| format!("- {subject}\n"))
+        .collect();
+    let prompt = format!(
+        "Based on the following commits and git diff, write a pull request title and description.
+
+The first line is the title: under 72 characters, imperative mood, no trailing period.
+After a blank line comes the description in GitHub markdown with these sections:
+## Summary
+One or two sentences on what the change does and why.
+## Changes
+A bullet list of the notable changes.
+## Testing
+How the change was or should be tested.
+
+Respond with ONLY the title and description, no additional text or code fences.
+
+Commits:
+{commit_list}
+Git diff:
+{diff_text}"
+    );
+
+    Ok(ask_claude(&prompt).and_then(|response| parse_pr_description(&response)))
+}
+
+/// Split a response into its title line and the body after it
+fn parse_pr_description(response: &str) -> Option<PrDescription> {
+    let response = strip_code_fence(response);
+    let (title, body) = response.split_once('\n').unwrap_or((response, ""));
+    let title = title.trim().trim_start_matches('#').trim();
+    let title = title.strip_prefix("Title:").unwrap_or(title).trim();
+    if title.is_empty() {
+        return None;
+    }
+    Some(PrDescription {
+        title: title.to_string(),
+        body: body.trim().to_string(),
+    })
+}
+
+/// Polish a changelog section drafted from Conventional Commits, following `format` (a
+/// description of the changelog style or an example section), using Claude AI
+pub fn generate_changelog_section(
+    draft: &str,
+    format: &str,
+) -> Result<Option<String>, anyhow::Error> {
+    if draft.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let prompt = format!(
+        "Rewrite the following draft changelog section so it reads well for the project's users.
+
+Keep the first heading line exactly as it is. Merge entries that describe the same change,
+reword commit-style descriptions into clear user-facing ones, and keep every link and PR
+reference. Do not invent changes that are not in the draft.
+
+Follow this format:
+{format}
+
+Respond with ONLY the changelog section in markdown, no additional text or code fences.
+
+Draft:
+{draft}"
+    );
+
+    Ok(ask_claude(&prompt).map(|section| format!("{}\n", strip_code_fence(&section))))
+}
+
+/// How much a review finding matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "low" => Some(Severity::Low),
+            "medium" => Some(Severity::Medium),
+            "high" => Some(Severity::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        }
+    }
+}
+
+/// What kind of problem a review finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FindingCategory {
+    Bug,
+    MissingTests,
+    Style,
+    #[serde(other)]
+    Other,
+}
+
+impl FindingCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FindingCategory::Bug => "bug",
+            FindingCategory::MissingTests => "missing-tests",
+            FindingCategory::Style => "style",
+            FindingCategory::Other => "other",
+        }
+    }
+}
+
+/// One problem the AI found in a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewFinding {
+    pub file: String,
+    /// Line in the new version of the file, when the finding is about a specific hunk
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub severity: Severity,
+    pub category: FindingCategory,
+    pub message: String,
+}
+
+/// Review a git diff for potential bugs, missing tests and style issues using Claude AI.
+/// `None` when Claude is unavailable or its answer can't be read; an empty list when it found
+/// nothing.
+pub fn review_diff(diff_text: &str) -> Result<Option<Vec<ReviewFinding>>, anyhow::Error> {
+    if diff_text.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+
+    let prompt = format!(
+        "Review the following git diff like a careful senior engineer.
+
+Look for potential bugs, missing tests for new or changed behavior, and style issues.
+Only report real problems in the changed lines; do not praise or summarize.
+
+Respond with ONLY a JSON array, no additional text or code fences. Each element is an object:
+{{\"file\": path, \"line\": line number in the new file or null, \"severity\": \"low\" | \"medium\" | \"high\", \"category\": \"bug\" | \"missing-tests\" | \"style\", \"message\": one or two sentences}}
+Respond with [] when there is nothing to report.
+
+Git diff:
+{diff_text}"
+    );
+
+    Ok(ask_claude(&prompt).and_then(|response| parse_findings(&response)))
+}
+
+fn parse_findings(response: &str) -> Option<Vec<ReviewFinding>> {
+    let response = strip_code_fence(response);
+    let response = response.strip_prefix("json").unwrap_or(response).trim();
+    serde_json::from_str(response).ok()
+}
+
+/// A branch type (`feat`, `fix`, ...) and kebab-case description to build a branch name from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchNameSuggestion {
+    pub kind: String,
+    pub description: String,
+}
+
+/// Suggest a branch type and short description for a task, described by the user or inferred
+/// from `diff_text`, using Claude AI
+pub fn generate_branch_name(
+    task: Option<&str>,
+    diff_text: &str,
+) -> Result<Option<BranchNameSuggestion>, anyhow::Error> {
+    let context = match task {
+        Some(task) => format!("Task description:\n{task}"),
+        None if diff_text.is_empty() => return Ok(None),
+        None => format!("Git diff of the work so far:\n{diff_text}"),
+    };
+    let prompt = format!(
+        "Suggest a git branch name for the following work.
+
+Respond with ONLY <type>/<description>, for example fix/login-redirect.
+Choose type from: {types}
+The description is kebab-case, lowercase, at most five words.
+
+{context}",
+        types = BRANCH_TYPES.join(", ")
+    );
+
+    Ok(ask_claude(&prompt).and_then(|response| parse_branch_suggestion(&response)))
+}
+
+/// Read `type/description` from the first line of a response
+fn parse_branch_suggestion(response: &str) -> Option<BranchNameSuggestion> {
+    let line = response
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_matches(|c| c == '`' || c == '"' || c == '\'')
+        })
+        .find(|line| !line.is_empty())?;
+    let (kind, description) = match line.split_once('/') {
+        Some((kind, description)) if BRANCH_TYPES.contains(&kind) => (kind, description),
+        _ => infer_branch_type(line),
+    };
+    let description = slugify(description);
+    (!description.is_empty()).then(|| BranchNameSuggestion {
+        kind: kind.to_string(),
+        description,
+    })
+}
+
+/// `text` without the markdown code fence the model sometimes wraps answers in
+fn strip_code_fence(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix("```markdown")
+        .or_else(|| text.strip_prefix("```"))
+        .and_then(|inner| inner.strip_suffix("```"))
+        .unwrap_or(text)
+        .trim()
+}
+
+/// Send `prompt` to the Claude CLI and return its answer, or `None` when it is unavailable,
+/// fails or answers with nothing
+fn ask_claude(prompt: &str) -> Option<String> {
+    // Call Claude CLI with JSON output
+    let output = Command::new("claude")
+        .arg("--print")
+        .arg("--output-format")
+        .arg("json")
+        .arg(prompt)
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let response = String::from_utf8_lossy(&output.stdout);
+
+            // Parse Claude CLI JSON response and extract the result field
+            let json = serde_json::from_str::<serde_json::Value>(&response).ok()?;
+            let message = json.get("result").and_then(|r| r.as_str())?.trim();
+            (!message.is_empty()).then(|| message.to_string())
+        }
+        _ => None, // Silently ignore errors to maintain graceful fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_branch_suggestion, parse_findings, parse_pr_description, split_messages,
+        BranchNameSuggestion, CommitMessageCache, FindingCategory, PrDescription, ReviewFinding,
+        Severity,
+    };
+
+    #[test]
+    fn findings_parse_from_json_with_unknown_categories() {
+        let findings = parse_findings(
+            r#"```json
+[{"file": "src/lib.rs", "line": 12, "severity": "high", "category": "bug", "message": "Off by one"},
+ {"file": "README.md", "severity": "low", "category": "typo", "message": "Misspelled"}]
+```"#,
+        );
+        assert_eq!(
+            findings,
+            Some(vec![
+                ReviewFinding {
+                    file: "src/lib.rs".to_string(),
+                    line: Some(12),
+                    severity: Severity::High,
+                    category: FindingCategory::Bug,
+                    message: "Off by one".to_string(),
+                },
+                ReviewFinding {
+                    file: "README.md".to_string(),
+                    line: None,
+                    severity: Severity::Low,
+                    category: FindingCategory::Other,
+                    message: "Misspelled".to_string(),
+                },
+            ])
+        );
+        assert_eq!(parse_findings("Looks good to me!"), None);
+        assert!(Severity::High > Severity::Medium);
+    }
+
+    #[test]
+    fn responses_split_into_distinct_messages() {
+        assert_eq!(
+            split_messages("feat: Add x\n\nBody\n---\nfix: Repair y\n---\n\n---\nfix: Repair y"),
+            ["feat: Add x\n\nBody", "fix: Repair y"]
+        );
+        assert_eq!(split_messages("docs: Explain z\n"), ["docs: Explain z"]);
+    }
+
+    #[test]
+    fn cache_only_answers_for_the_same_diff() -> Result<(), anyhow::Error> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let cache = CommitMessageCache::new(temp_dir.path().join("xgit").join("cache.json"));
+        assert_eq!(cache.get("+a"), None);
+
+        cache.store("+a", &["feat: Add a".to_string()])?;
+        assert_eq!(cache.get("+a"), Some(vec!["feat: Add a".to_string()]));
+        assert_eq!(cache.get("+b"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn branch_suggestion_reads_type_and_description() {
+        let suggestion = |kind: &str, description: &str| {
+            Some(BranchNameSuggestion {
+                kind: kind.to_string(),
+                description: description.to_string(),
+            })
+        };
+        assert_eq!(
+            parse_branch_suggestion("`fix/login-redirect`\n"),
+            suggestion("fix", "login-redirect")
+        );
+        assert_eq!(
+            parse_branch_suggestion("Docs update README"),
+            suggestion("docs", "update-readme")
+        );
+        assert_eq!(parse_branch_suggestion("\n"), None);
+    }
+
+    #[test]
+    fn pr_description_splits_title_from_body() {
+        assert_eq!(
+            parse_pr_description("Title: Add pr create\n\n## Summary\nDrafts PRs.\n"),
+            Some(PrDescription {
+                title: "Add pr create".to_string(),
+                body: "## Summary\nDrafts PRs.".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_pr_description("```\n# Fix login\n```"),
+            Some(PrDescription {
+                title: "Fix login".to_string(),
+                body: String::new(),
+            })
+        );
+        assert_eq!(parse_pr_description("  \n"), None);
+    }
+}
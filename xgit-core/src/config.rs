@@ -0,0 +1,570 @@
+//! xgit's own settings, layered like git's: built-in defaults, then the user's
+//! `~/.config/xgit/config.toml`, then the repository's `.xgit.toml`, then `XGIT_*`
+//! environment variables. Later layers override earlier ones, except protected branches
+//! and forge hosts, which every layer adds to.
+//!
+//! Forge hosts decide where the user's tokens are sent, so `.xgit.toml`, which arrives with
+//! a clone, cannot add them; `github.hosts` and `gitlab.hosts` are read from the user's file,
+//! the `xgit.githubHosts` and `xgit.gitlabHosts` git config keys and `XGIT_GITHUB_HOSTS` and
+//! `XGIT_GITLAB_HOSTS` only.
+//!
+//! ```toml
+//! [ai]
+//! provider = "claude"        # or "none" to turn AI suggestions off
+//! suggestions = 3            # commit messages to choose from
+//!
+//! [branches]
+//! default = "trunk"          # trunk branch, when it is not main or master
+//! protected = ["release/*"]
+//! pattern = "lx/{type}/{description}"  # names suggested by `xg branch --new`
+//!
+//! [commitlint]              # checked by `xg commit`, the commit-msg hook and `xg lint-commits`
+//! types = ["feat", "fix", "docs", "chore"]
+//! scopes = ["cli", "core"]   # any scope when left out
+//! require-scope = true
+//! max-subject-length = 72
+//! imperative = true          # warn about `Added` or `Fixes`
+//! on-commit = "warn"         # "error" (the default with this table), "warn" or "off"
+//!
+//! [github]
+//! hosts = ["github.example.com"]  # GitHub Enterprise hosts, besides github.com
+//!
+//! [gitlab]
+//! hosts = ["gitlab.example.com"]  # self-managed GitLab hosts, besides gitlab.com
+//!
+//! [remote]
+//! default = "upstream"
+//!
+//! [ui]
+//! emoji = false
+//! ```
+
+use anyhow::{Context, Error};
+use std::path::{Path, PathBuf};
+
+use crate::forge::gitlab::GITLAB_HOST;
+use crate::git::branches::naming::DEFAULT_BRANCH_PATTERN;
+use crate::git::branches::protected::DEFAULT_PROTECTED_BRANCHES;
+use crate::git::commits::commitlint::LintRules;
+use crate::git::GitRepo;
+use crate::github::auth::GITHUB_HOST;
+
+/// File at the root of the working tree that shares xgit settings with everyone on a project
+pub const REPO_CONFIG_FILE: &str = ".xgit.toml";
+
+/// Where AI suggestions come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiProvider {
+    /// The `claude` CLI
+    Claude,
+    /// No AI suggestions
+    Disabled,
+}
+
+impl AiProvider {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "claude" => Some(AiProvider::Claude),
+            "none" | "off" | "disabled" => Some(AiProvider::Disabled),
+            _ => None,
+        }
+    }
+}
+
+/// What `xg commit` does with a message that breaks the commit lint rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitLintMode {
+    /// Refuse to commit
+    Error,
+    /// Commit after printing the problems
+    Warn,
+    /// Do not check
+    Off,
+}
+
+impl CommitLintMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(CommitLintMode::Error),
+            "warn" | "warning" => Some(CommitLintMode::Warn),
+            "off" | "none" => Some(CommitLintMode::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Where a TOML layer comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    /// The user's own config file
+    User,
+    /// `.xgit.toml`, which comes with the repository
+    Repo,
+}
+
+/// Commit messages `xg commit` offers to choose from unless `ai.suggestions` says otherwise
+pub const DEFAULT_AI_SUGGESTIONS: usize = 3;
+
+/// Settings resolved from every layer; see the [module docs](self)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XgitConfig {
+    ai_provider: AiProvider,
+    ai_suggestions: usize,
+    protected_branches: Vec<String>,
+    default_branch: Option<String>,
+    branch_pattern: String,
+    default_remote: String,
+    github_hosts: Vec<String>,
+    gitlab_hosts: Vec<String>,
+    emoji: bool,
+    commit_lint: LintRules,
+    commit_lint_mode: CommitLintMode,
+}
+
+impl Default for XgitConfig {
+    fn default() -> Self {
+        Self {
+            ai_provider: AiProvider::Claude,
+            ai_suggestions: DEFAULT_AI_SUGGESTIONS,
+            protected_branches: DEFAULT_PROTECTED_BRANCHES
+                .iter()
+                .map(|branch| branch.to_string())
+                .collect(),
+            default_branch: None,
+            branch_pattern: DEFAULT_BRANCH_PATTERN.to_string(),
+            default_remote: "origin".to_string(),
+            github_hosts: vec![GITHUB_HOST.to_string()],
+            gitlab_hosts: vec![GITLAB_HOST.to_string()],
+            emoji: true,
+            commit_lint: LintRules::default(),
+            commit_lint_mode: CommitLintMode::Off,
+        }
+    }
+}
+
+impl XgitConfig {
+    /// Resolve the settings for `repo`, or for no repository (skipping `.xgit.toml`)
+    pub fn load(repo: Option<&GitRepo>) -> Result<Self, Error> {
+        let mut config = Self::default();
+        if let Some(path) = Self::user_config_path() {
+            config.apply_file(&path, Layer::User)?;
+        }
+        if let Some(repo) = repo {
+            if let Some(workdir) = repo.repo().workdir() {
+                config.apply_file(&workdir.join(REPO_CONFIG_FILE), Layer::Repo)?;
+            }
+            config.apply_git_config(repo)?;
+        }
+        config.apply_env(|name| std::env::var(name).ok())?;
+        Ok(config)
+    }
+
+    /// `$XDG_CONFIG_HOME/xgit/config.toml`, falling back to `~/.config/xgit/config.toml`
+    pub fn user_config_path() -> Option<PathBuf> {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|config| config.join("xgit").join("config.toml"))
+    }
+
+    pub fn ai_provider(&self) -> AiProvider {
+        self.ai_provider
+    }
+
+    /// How many commit messages to ask the AI for, at least one
+    pub fn ai_suggestions(&self) -> usize {
+        self.ai_suggestions
+    }
+
+    /// Branch patterns cleanup commands never delete; see
+    /// [`GitRepo::protected_branches`], which adds the git config ones
+    pub fn protected_branches(&self) -> &[String] {
+        &self.protected_branches
+    }
+
+    /// The trunk branch when configured; otherwise xgit looks for main, then master
+    pub fn default_branch(&self) -> Option<&str> {
+        self.default_branch.as_deref()
+    }
+
+    /// Pattern for new branch names, with `{type}` and `{description}` placeholders
+    pub fn branch_pattern(&self) -> &str {
+        &self.branch_pattern
+    }
+
+    /// Remote to use when a command is not told which one and the branch has no upstream
+    pub fn default_remote(&self) -> &str {
+        &self.default_remote
+    }
+
+    /// Hosts whose remotes are GitHub repositories: github.com and any GitHub Enterprise hosts
+    pub fn github_hosts(&self) -> &[String] {
+        &self.github_hosts
+    }
+
+    /// Hosts whose remotes are GitLab projects: gitlab.com and any self-managed instances
+    pub fn gitlab_hosts(&self) -> &[String] {
+        &self.gitlab_hosts
+    }
+
+    /// Whether to decorate output with emoji
+    pub fn emoji(&self) -> bool {
+        self.emoji
+    }
+
+    /// Rules commit messages are checked against
+    pub fn commit_lint(&self) -> &LintRules {
+        &self.commit_lint
+    }
+
+    /// Whether `xg commit` checks messages against [`commit_lint`](Self::commit_lint): off
+    /// unless a `[commitlint]` table is configured
+    pub fn commit_lint_mode(&self) -> CommitLintMode {
+        self.commit_lint_mode
+    }
+
+    /// Apply a TOML layer; a missing file is not an error
+    fn apply_file(&mut self, path: &Path, layer: Layer) -> Result<(), Error> {
+        if !path.is_file() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.apply_toml(&content, layer)
+            .with_context(|| format!("Invalid settings in {}", path.display()))
+    }
+
+    fn apply_toml(&mut self, content: &str, layer: Layer) -> Result<(), Error> {
+        let document: toml_edit::DocumentMut = content.parse()?;
+        let trusted = |key: &str| {
+            if layer == Layer::Repo && key_present(&document, key) {
+                tracing::warn!(
+                    "Ignoring {key} in {REPO_CONFIG_FILE}; set it in the user config, git config or environment"
+                );
+            }
+            layer == Layer::User
+        };
+        let get = |table: &str, key: &str| document.get(table).and_then(|table| table.get(key));
+        let string = |table: &str, key: &str| -> Result<Option<String>, Error> {
+            match get(table, key) {
+                None => Ok(None),
+                Some(item) => item
+                    .as_str()
+                    .map(|value| Some(value.to_string()))
+                    .with_context(|| format!("{table}.{key} must be a string")),
+            }
+        };
+
+        if let Some(provider) = string("ai", "provider")? {
+            self.ai_provider = AiProvider::parse(&provider).with_context(|| {
+                format!("ai.provider must be \"claude\" or \"none\", not \"{provider}\"")
+            })?;
+        }
+        if let Some(suggestions) = get("ai", "suggestions") {
+            self.ai_suggestions = suggestions
+                .as_integer()
+                .filter(|count| (1..=10).contains(count))
+                .context("ai.suggestions must be a number from 1 to 10")?
+                as usize;
+        }
+        if let Some(protected) = get("branches", "protected") {
+            let patterns = protected
+                .as_array()
+                .context("branches.protected must be an array of branch patterns")?;
+            self.add_protected_branches(patterns.iter().filter_map(|value| value.as_str()));
+        }
+        if let Some(branch) = string("branches", "default")? {
+            self.default_branch = Some(branch);
+        }
+        if let Some(pattern) = string("branches", "pattern")? {
+            self.branch_pattern = pattern;
+        }
+        if let Some(hosts) = get("github", "hosts").filter(|_| trusted("github.hosts")) {
+            let hosts = hosts
+                .as_array()
+                .context("github.hosts must be an array of host names")?;
+            add_hosts(
+                &mut self.github_hosts,
+                hosts.iter().filter_map(|value| value.as_str()),
+            );
+        }
+        if let Some(hosts) = get("gitlab", "hosts").filter(|_| trusted("gitlab.hosts")) {
+            let hosts = hosts
+                .as_array()
+                .context("gitlab.hosts must be an array of host names")?;
+            add_hosts(
+                &mut self.gitlab_hosts,
+                hosts.iter().filter_map(|value| value.as_str()),
+            );
+        }
+        if let Some(remote) = string("remote", "default")? {
+            self.default_remote = remote;
+        }
+        if let Some(emoji) = get("ui", "emoji") {
+            self.emoji = emoji.as_bool().context("ui.emoji must be true or false")?;
+        }
+        if document.contains_key("commitlint") {
+            self.apply_commit_lint(&document)?;
+        }
+        Ok(())
+    }
+
+    fn apply_commit_lint(&mut self, document: &toml_edit::DocumentMut) -> Result<(), Error> {
+        let get = |key: &str| document.get("commitlint").and_then(|table| table.get(key));
+        let strings = |key: &str| -> Result<Option<Vec<String>>, Error> {
+            get(key)
+                .map(|item| {
+                    item.as_array()
+                        .and_then(|array| {
+                            array
+                                .iter()
+                                .map(|value| value.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .with_context(|| format!("commitlint.{key} must be an array of strings"))
+                })
+                .transpose()
+        };
+        let flag = |key: &str| -> Result<Option<bool>, Error> {
+            get(key)
+                .map(|item| {
+                    item.as_bool()
+                        .with_context(|| format!("commitlint.{key} must be true or false"))
+                })
+                .transpose()
+        };
+
+        self.commit_lint_mode = CommitLintMode::Error;
+        if let Some(types) = strings("types")? {
+            self.commit_lint.types = types;
+        }
+        if let Some(scopes) = strings("scopes")? {
+            self.commit_lint.scopes = scopes;
+        }
+        if let Some(require_scope) = flag("require-scope")? {
+            self.commit_lint.require_scope = require_scope;
+        }
+        if let Some(imperative) = flag("imperative")? {
+            self.commit_lint.imperative = imperative;
+        }
+        if let Some(length) = get("max-subject-length") {
+            self.commit_lint.max_subject_length = length
+                .as_integer()
+                .filter(|length| *length > 0)
+                .context(
+                "commitlint.max-subject-length must be a positive number",
+            )? as usize;
+        }
+        if let Some(mode) = get("on-commit") {
+            self.commit_lint_mode = mode
+                .as_str()
+                .and_then(CommitLintMode::parse)
+                .context("commitlint.on-commit must be \"error\", \"warn\" or \"off\"")?;
+        }
+        Ok(())
+    }
+
+    /// Apply the `xgit.githubHosts` and `xgit.gitlabHosts` git config keys (comma-separated,
+    /// may repeat)
+    fn apply_git_config(&mut self, repo: &GitRepo) -> Result<(), Error> {
+        for (name, value) in repo.get_config_entries("xgit.")? {
+            let hosts = if name.eq_ignore_ascii_case("xgit.githubHosts") {
+                &mut self.github_hosts
+            } else if name.eq_ignore_ascii_case("xgit.gitlabHosts") {
+                &mut self.gitlab_hosts
+            } else {
+                continue;
+            };
+            add_hosts(hosts, value.split(',').map(str::trim));
+        }
+        Ok(())
+    }
+
+    /// Apply `XGIT_AI_PROVIDER`, `XGIT_AI_SUGGESTIONS`, `XGIT_PROTECTED_BRANCHES`
+    /// (comma-separated), `XGIT_DEFAULT_BRANCH`, `XGIT_BRANCH_PATTERN`, `XGIT_DEFAULT_REMOTE`,
+    /// `XGIT_GITHUB_HOSTS`, `XGIT_GITLAB_HOSTS` (both comma-separated) and `XGIT_EMOJI`
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), Error> {
+        if let Some(provider) = var("XGIT_AI_PROVIDER") {
+            self.ai_provider = AiProvider::parse(&provider).with_context(|| {
+                format!("XGIT_AI_PROVIDER must be \"claude\" or \"none\", not \"{provider}\"")
+            })?;
+        }
+        if let Some(suggestions) = var("XGIT_AI_SUGGESTIONS") {
+            self.ai_suggestions = suggestions
+                .trim()
+                .parse()
+                .ok()
+                .filter(|count| (1..=10).contains(count))
+                .context("XGIT_AI_SUGGESTIONS must be a number from 1 to 10")?;
+        }
+        if let Some(protected) = var("XGIT_PROTECTED_BRANCHES") {
+            self.add_protected_branches(protected.split(',').map(str::trim));
+        }
+        if let Some(branch) = var("XGIT_DEFAULT_BRANCH").filter(|branch| !branch.is_empty()) {
+            self.default_branch = Some(branch);
+        }
+        if let Some(pattern) = var("XGIT_BRANCH_PATTERN").filter(|pattern| !pattern.is_empty()) {
+            self.branch_pattern = pattern;
+        }
+        if let Some(remote) = var("XGIT_DEFAULT_REMOTE").filter(|remote| !remote.is_empty()) {
+            self.default_remote = remote;
+        }
+        if let Some(hosts) = var("XGIT_GITHUB_HOSTS") {
+            add_hosts(&mut self.github_hosts, hosts.split(',').map(str::trim));
+        }
+        if let Some(hosts) = var("XGIT_GITLAB_HOSTS") {
+            add_hosts(&mut self.gitlab_hosts, hosts.split(',').map(str::trim));
+        }
+        if let Some(emoji) = var("XGIT_EMOJI") {
+            self.emoji = match emoji.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => true,
+                "0" | "false" | "no" | "off" => false,
+                _ => return Err(anyhow::anyhow!("XGIT_EMOJI must be true or false")),
+            };
+        }
+        Ok(())
+    }
+
+    fn add_protected_branches<'a>(&mut self, patterns: impl Iterator<Item = &'a str>) {
+        for pattern in patterns {
+            if !pattern.is_empty() && !self.protected_branches.iter().any(|p| p == pattern) {
+                self.protected_branches.push(pattern.to_string());
+            }
+        }
+    }
+}
+
+/// Whether `document` sets the dotted `table.key`
+fn key_present(document: &toml_edit::DocumentMut, key: &str) -> bool {
+    let (table, key) = key.split_once('.').unwrap_or((key, ""));
+    document
+        .get(table)
+        .map_or(false, |table| table.get(key).is_some())
+}
+
+/// Add `hosts` to `known`, lowercased and without duplicates
+fn add_hosts<'a>(known: &mut Vec<String>, hosts: impl Iterator<Item = &'a str>) {
+    for host in hosts {
+        let host = host.trim_end_matches('/').to_ascii_lowercase();
+        if !host.is_empty() && !known.contains(&host) {
+            known.push(host);
+        }
+    }
+}
+
+impl GitRepo {
+    /// xgit's settings for this repository; see [`XgitConfig`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn xgit_config(&self) -> Result<XgitConfig, Error> {
+        XgitConfig::load(Some(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AiProvider, CommitLintMode, Layer, XgitConfig};
+    use std::collections::HashMap;
+
+    #[test]
+    fn later_layers_override_earlier_ones() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = XgitConfig::default();
+        assert_eq!(config.ai_provider(), AiProvider::Claude);
+        assert_eq!(config.default_remote(), "origin");
+        assert_eq!(config.default_branch(), None);
+        assert!(config.emoji());
+
+        // User file, then repository file
+        config.apply_toml(
+            "[remote]\ndefault = \"upstream\"\n[ui]\nemoji = false\n[branches]\nprotected = [\"release/*\"]\n",
+            Layer::User,
+        )?;
+        config.apply_toml(
+            "[branches]\ndefault = \"trunk\"\nprotected = [\"hotfix/**\"]\npattern = \"{type}-{description}\"\n",
+            Layer::Repo,
+        )?;
+        let env = HashMap::from([
+            ("XGIT_AI_PROVIDER", "none"),
+            ("XGIT_AI_SUGGESTIONS", "2"),
+            ("XGIT_EMOJI", "1"),
+            ("XGIT_PROTECTED_BRANCHES", "staging, release/*"),
+            ("XGIT_GITHUB_HOSTS", "GHE.example.com"),
+            ("XGIT_GITLAB_HOSTS", "gitlab.example.com/"),
+        ]);
+        config.apply_env(|name| env.get(name).map(|value| value.to_string()))?;
+
+        assert_eq!(config.ai_provider(), AiProvider::Disabled);
+        assert_eq!(config.ai_suggestions(), 2);
+        assert_eq!(config.default_remote(), "upstream");
+        assert_eq!(config.default_branch(), Some("trunk"));
+        assert_eq!(config.branch_pattern(), "{type}-{description}");
+        assert_eq!(config.github_hosts(), ["github.com", "ghe.example.com"]);
+        assert_eq!(config.gitlab_hosts(), ["gitlab.com", "gitlab.example.com"]);
+        assert!(config.emoji());
+        assert_eq!(
+            config.protected_branches(),
+            [
+                "main",
+                "master",
+                "develop",
+                "release/*",
+                "hotfix/**",
+                "staging"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repository_file_cannot_add_forge_hosts() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = XgitConfig::default();
+        config.apply_toml(
+            "[github]\nhosts = [\"evil.example\"]\n[gitlab]\nhosts = [\"evil.example\"]\n[remote]\ndefault = \"upstream\"\n",
+            Layer::Repo,
+        )?;
+        assert_eq!(config.github_hosts(), ["github.com"]);
+        assert_eq!(config.gitlab_hosts(), ["gitlab.com"]);
+        assert_eq!(config.default_remote(), "upstream");
+
+        config.apply_toml("[github]\nhosts = [\"ghe.example.com\"]\n", Layer::User)?;
+        assert_eq!(config.github_hosts(), ["github.com", "ghe.example.com"]);
+        Ok(())
+    }
+
+    #[test]
+    fn commitlint_table_turns_on_the_commit_check() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = XgitConfig::default();
+        assert_eq!(config.commit_lint_mode(), CommitLintMode::Off);
+
+        config.apply_toml(
+            "[commitlint]\ntypes = [\"feat\", \"fix\"]\nrequire-scope = true\nmax-subject-length = 50\n",
+            Layer::User,
+        )?;
+        assert_eq!(config.commit_lint_mode(), CommitLintMode::Error);
+        assert_eq!(config.commit_lint().types, ["feat", "fix"]);
+        assert!(config.commit_lint().require_scope);
+        assert_eq!(config.commit_lint().max_subject_length, 50);
+        assert!(config.commit_lint().imperative);
+
+        config.apply_toml(
+            "[commitlint]\non-commit = \"warn\"\nimperative = false\n",
+            Layer::User,
+        )?;
+        assert_eq!(config.commit_lint_mode(), CommitLintMode::Warn);
+        assert_eq!(config.commit_lint().types, ["feat", "fix"]);
+        assert!(!config.commit_lint().imperative);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_values_name_the_setting() {
+        let err = XgitConfig::default()
+            .apply_toml("[ui]\nemoji = \"sometimes\"\n", Layer::User)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "ui.emoji must be true or false");
+
+        let err = XgitConfig::default()
+            .apply_toml("[ai]\nprovider = \"clippy\"\n", Layer::User)
+            .unwrap_err();
+        assert!(err.to_string().contains("ai.provider"), "{err}");
+    }
+}
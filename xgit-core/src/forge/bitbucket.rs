@@ -0,0 +1,606 @@
+//! Pull requests on Bitbucket Cloud through the REST API (2.0). Requests are signed in with
+//! an access token in `BITBUCKET_TOKEN`, an app password in `BITBUCKET_USERNAME` and
+//! `BITBUCKET_APP_PASSWORD`, or the login a git credential helper stores for bitbucket.org,
+//! and are anonymous otherwise.
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::forge::remote::{RepoUrl, BITBUCKET_HOST};
+use crate::forge::ForgeProvider;
+use crate::git::GitError;
+use crate::github::auth;
+use crate::github::types::{
+    parse_timestamp, ChecksState, MergeMethod, PrListFilter, PullRequestChecks, PullRequestListing,
+    PullRequestRecord, PullRequestSnapshot, PullRequestStatus, ReviewDecision,
+};
+
+const API_BASE_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// How a client signs in
+enum Credentials {
+    /// A repository, project or workspace access token
+    Token(String),
+    /// A user name with an app password
+    Login(String, String),
+}
+
+pub struct BitbucketClient {
+    octocrab: Octocrab,
+    /// Route prefix of the repository, `/repositories/<workspace>/<repo>`
+    repo_route: String,
+    repo_slug: String,
+    signed_in: bool,
+}
+
+impl BitbucketClient {
+    /// Client for the repository at `repo`, signed in when credentials are found and anonymous
+    /// otherwise, which is enough for public repositories
+    pub fn new(repo: &RepoUrl) -> Result<Self, Error> {
+        Self::build(repo, resolve_credentials())
+    }
+
+    /// Client that must be signed in, for opening and merging pull requests
+    pub fn authenticated(repo: &RepoUrl) -> Result<Self, Error> {
+        let credentials = resolve_credentials().ok_or_else(|| {
+            Error::from(GitError::AuthRequired(anyhow::anyhow!(
+                "No Bitbucket credentials found; set BITBUCKET_TOKEN, or BITBUCKET_USERNAME and BITBUCKET_APP_PASSWORD"
+            )))
+        })?;
+        Self::build(repo, Some(credentials))
+    }
+
+    fn build(repo: &RepoUrl, credentials: Option<Credentials>) -> Result<Self, Error> {
+        let signed_in = credentials.is_some();
+        let builder = Octocrab::builder().base_uri(API_BASE_URL)?;
+        let builder = match credentials {
+            Some(Credentials::Token(token)) => builder.personal_token(token),
+            Some(Credentials::Login(username, password)) => builder.basic_auth(username, password),
+            None => builder,
+        };
+        Ok(Self {
+            octocrab: builder
+                .build()
+                .context("Failed to create Bitbucket client")?,
+            repo_route: format!("/repositories/{}/{}", repo.owner, repo.repo),
+            repo_slug: repo.slug(),
+            signed_in,
+        })
+    }
+
+    /// `message` for a failed request, saying how to sign in when the client is anonymous,
+    /// since Bitbucket answers anonymous requests for private repositories with 404
+    fn failed(&self, message: &str) -> String {
+        if self.signed_in {
+            message.to_string()
+        } else {
+            format!(
+                "{message} (not signed in to Bitbucket; set BITBUCKET_TOKEN for private repositories)"
+            )
+        }
+    }
+
+    fn record(&self, pull_request: &PullRequest) -> PullRequestRecord {
+        to_pull_request_record(&self.repo_slug, pull_request)
+    }
+
+    /// Pull requests matching the `query` filter in any state, newest first
+    async fn search(&self, query: String) -> Result<Vec<PullRequest>, Error> {
+        let params = [
+            ("q", query),
+            ("sort", "-created_on".to_string()),
+            ("state", "OPEN".to_string()),
+            ("state", "MERGED".to_string()),
+            ("state", "DECLINED".to_string()),
+            ("state", "SUPERSEDED".to_string()),
+        ];
+        let page: Page<PullRequest> = self
+            .octocrab
+            .get(format!("{}/pullrequests", self.repo_route), Some(&params))
+            .await
+            .with_context(|| self.failed("Failed to fetch pull requests"))?;
+        Ok(page.values)
+    }
+
+    async fn pull_request(&self, id: u64) -> Result<PullRequest, Error> {
+        self.octocrab
+            .get(
+                format!("{}/pullrequests/{id}", self.repo_route),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch pull request"))
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for BitbucketClient {
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_default_branch(&self) -> Result<String, Error> {
+        let repository: Repository = self
+            .octocrab
+            .get(&self.repo_route, None::<&()>)
+            .await
+            .with_context(|| self.failed("Failed to fetch repository metadata"))?;
+        repository
+            .mainbranch
+            .map(|branch| branch.name)
+            .ok_or_else(|| anyhow::anyhow!("Repository main branch is not available"))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        Ok(self.record(&self.pull_request(pr_number).await?))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error> {
+        Ok(self
+            .pull_request(pr_number)
+            .await?
+            .description
+            .unwrap_or_default())
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn find_pr_by_head(&self, head_branch: &str) -> Result<Option<PullRequestRecord>, Error> {
+        let found = self
+            .search(format!("source.branch.name = {}", quote(head_branch)))
+            .await?;
+        Ok(found.first().map(|pull_request| self.record(pull_request)))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let found = self
+            .search(format!(
+                "source.branch.name = {} AND source.repository.full_name ~ {}",
+                quote(head_branch),
+                quote(&format!("{owner}/"))
+            ))
+            .await?;
+        Ok(found.first().map(|pull_request| self.record(pull_request)))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let page: Page<PullRequest> = self
+            .octocrab
+            .get(
+                format!("{}/commit/{commit_sha}/pullrequests", self.repo_route),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch pull requests for commit"))?;
+        let pull_request = page
+            .values
+            .iter()
+            .find(|pull_request| pull_request.state == "MERGED")
+            .or_else(|| page.values.first());
+        Ok(pull_request.map(|pull_request| self.record(pull_request)))
+    }
+
+    /// Build statuses on `head_sha`, and the decision from each participant's approval or
+    /// change request
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        head_sha: &str,
+    ) -> Result<PullRequestChecks, Error> {
+        let statuses: Page<BuildStatus> = self
+            .octocrab
+            .get(
+                format!("{}/commit/{head_sha}/statuses", self.repo_route),
+                Some(&json!({ "pagelen": 100 })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch build statuses"))?;
+        let pull_request = self.pull_request(pr_number).await?;
+        Ok(PullRequestChecks {
+            checks: ChecksState::combine(
+                statuses
+                    .values
+                    .iter()
+                    .map(|status| build_state(&status.state)),
+            ),
+            review_decision: review_decision(&pull_request.participants),
+        })
+    }
+
+    /// The first 50 open pull requests passing `filter`, newest first, each costing two more
+    /// requests for its checks
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn list_open_prs(&self, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error> {
+        let login = if filter.mine || filter.review_requested {
+            let user: User = self
+                .octocrab
+                .get("/user", None::<&()>)
+                .await
+                .with_context(|| self.failed("Failed to look up the signed-in Bitbucket user"))?;
+            Some(user.nickname)
+        } else {
+            None
+        };
+        let page: Page<PullRequest> = self
+            .octocrab
+            .get(
+                format!("{}/pullrequests", self.repo_route),
+                Some(&json!({ "state": "OPEN", "sort": "-created_on", "pagelen": 50 })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to list pull requests"))?;
+
+        let mut listings = Vec::new();
+        for pull_request in &page.values {
+            let mut listing = to_listing(&self.repo_slug, pull_request);
+            if !filter.matches(&listing, login.as_deref()) {
+                continue;
+            }
+            let checks = self
+                .get_pr_checks(pull_request.id, &pull_request.source.commit.hash)
+                .await?;
+            listing.record.set_checks(checks);
+            listings.push(listing);
+        }
+        Ok(listings)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, title, body))]
+    async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        let pull_request: PullRequest = self
+            .octocrab
+            .post(
+                format!("{}/pullrequests", self.repo_route),
+                Some(&json!({
+                    "title": title,
+                    "description": body.unwrap_or_default(),
+                    "source": { "branch": { "name": head } },
+                    "destination": { "branch": { "name": base } },
+                    "draft": draft,
+                })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to create pull request"))?;
+        Ok(self.record(&pull_request))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, title, body))]
+    async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        // Bitbucket requires the title on every update
+        let current = self.pull_request(pr_number).await?;
+        let mut changes = serde_json::Map::new();
+        changes.insert("title".to_string(), json!(title.unwrap_or(&current.title)));
+        if let Some(base) = base {
+            changes.insert(
+                "destination".to_string(),
+                json!({ "branch": { "name": base } }),
+            );
+        }
+        if let Some(body) = body {
+            changes.insert("description".to_string(), json!(body));
+        }
+        let pull_request: PullRequest = self
+            .octocrab
+            .put(
+                format!("{}/pullrequests/{pr_number}", self.repo_route),
+                Some(&changes),
+            )
+            .await
+            .with_context(|| self.failed("Failed to update pull request"))?;
+        Ok(self.record(&pull_request))
+    }
+
+    /// Bitbucket's merge endpoint cannot pin the head commit, so the head is checked just
+    /// before merging instead
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error> {
+        let current = self.pull_request(pr_number).await?;
+        if current.source.commit.hash.is_empty()
+            || !head_sha.starts_with(&current.source.commit.hash)
+        {
+            return Err(anyhow::anyhow!(
+                "PR #{pr_number} moved to {} on Bitbucket; pull and try again",
+                current.source.commit.hash
+            ));
+        }
+        let strategy = match method {
+            MergeMethod::Merge => "merge_commit",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase_fast_forward",
+        };
+        let merged: PullRequest = self
+            .octocrab
+            .post(
+                format!("{}/pullrequests/{pr_number}/merge", self.repo_route),
+                Some(&json!({ "merge_strategy": strategy })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to merge pull request"))?;
+
+        if merged.state != "MERGED" {
+            return Err(anyhow::anyhow!(
+                "Bitbucket did not merge PR #{pr_number}; it is {}",
+                merged.state.to_ascii_lowercase()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `BITBUCKET_TOKEN`, then `BITBUCKET_USERNAME` with `BITBUCKET_APP_PASSWORD`, then the git
+/// credential helper's login for bitbucket.org
+fn resolve_credentials() -> Option<Credentials> {
+    let var = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if let Some(token) = var("BITBUCKET_TOKEN") {
+        return Some(Credentials::Token(token));
+    }
+    if let (Some(username), Some(password)) =
+        (var("BITBUCKET_USERNAME"), var("BITBUCKET_APP_PASSWORD"))
+    {
+        return Some(Credentials::Login(username, password));
+    }
+    match auth::credential_helper_login(BITBUCKET_HOST)? {
+        (username, password) if username.is_empty() => Some(Credentials::Token(password)),
+        (username, password) => Some(Credentials::Login(username, password)),
+    }
+}
+
+/// `value` as a string literal in Bitbucket's query language
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    values: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct Repository {
+    mainbranch: Option<Branch>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    #[serde(default)]
+    nickname: String,
+}
+
+#[derive(Deserialize)]
+struct PullRequest {
+    id: u64,
+    title: String,
+    description: Option<String>,
+    /// `OPEN`, `MERGED`, `DECLINED` or `SUPERSEDED`
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    source: Endpoint,
+    destination: Endpoint,
+    links: Links,
+    author: Option<User>,
+    created_on: Option<String>,
+    updated_on: Option<String>,
+    #[serde(default)]
+    reviewers: Vec<User>,
+    /// Only on a single pull request
+    #[serde(default)]
+    participants: Vec<Participant>,
+}
+
+#[derive(Deserialize)]
+struct Endpoint {
+    branch: Branch,
+    #[serde(default)]
+    commit: Commit,
+    repository: Option<RepositoryName>,
+}
+
+#[derive(Deserialize)]
+struct Branch {
+    name: String,
+}
+
+#[derive(Default, Deserialize)]
+struct Commit {
+    /// Abbreviated to 12 characters
+    hash: String,
+}
+
+#[derive(Deserialize, PartialEq, Eq)]
+struct RepositoryName {
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct Links {
+    html: Link,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct Participant {
+    user: Option<User>,
+    /// `approved`, `changes_requested` or none
+    state: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BuildStatus {
+    /// `SUCCESSFUL`, `FAILED`, `INPROGRESS` or `STOPPED`
+    state: String,
+}
+
+/// A build status's `state`; stopped builds count as failed, like GitHub's cancelled runs
+fn build_state(state: &str) -> ChecksState {
+    match state {
+        "SUCCESSFUL" => ChecksState::Passing,
+        "INPROGRESS" => ChecksState::Pending,
+        _ => ChecksState::Failing,
+    }
+}
+
+fn review_decision(participants: &[Participant]) -> Option<ReviewDecision> {
+    ReviewDecision::from_reviews(participants.iter().filter_map(|participant| {
+        let state = match participant.state.as_deref()? {
+            "approved" => "APPROVED",
+            "changes_requested" => "CHANGES_REQUESTED",
+            _ => return None,
+        };
+        Some((participant.user.as_ref()?.nickname.as_str(), state))
+    }))
+}
+
+fn to_listing(repo_slug: &str, pull_request: &PullRequest) -> PullRequestListing {
+    PullRequestListing {
+        record: to_pull_request_record(repo_slug, pull_request),
+        author: pull_request
+            .author
+            .as_ref()
+            .map(|author| author.nickname.clone())
+            .unwrap_or_default(),
+        // Bitbucket pull requests have no labels
+        labels: Vec::new(),
+        requested_reviewers: pull_request
+            .reviewers
+            .iter()
+            .map(|reviewer| reviewer.nickname.clone())
+            .collect(),
+        is_cross_repository: pull_request.source.repository != pull_request.destination.repository,
+    }
+}
+
+fn to_pull_request_record(repo_slug: &str, pull_request: &PullRequest) -> PullRequestRecord {
+    let status = match pull_request.state.as_str() {
+        "MERGED" => PullRequestStatus::Merged,
+        "DECLINED" | "SUPERSEDED" => PullRequestStatus::Closed,
+        _ => PullRequestStatus::Open,
+    };
+    PullRequestRecord::from_snapshot(PullRequestSnapshot {
+        repo_slug: repo_slug.to_string(),
+        pr_number: pull_request.id,
+        title: pull_request.title.clone(),
+        url: pull_request.links.html.href.clone(),
+        base_ref: pull_request.destination.branch.name.clone(),
+        head_ref: pull_request.source.branch.name.clone(),
+        head_sha: pull_request.source.commit.hash.clone(),
+        draft: pull_request.draft,
+        created_at: pull_request.created_on.as_deref().and_then(parse_timestamp),
+        // Bitbucket has no merge time; a merged pull request was last updated by its merge
+        merged_at: (status == PullRequestStatus::Merged)
+            .then(|| pull_request.updated_on.as_deref().and_then(parse_timestamp))
+            .flatten(),
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quote, review_decision, to_listing, Participant, PullRequest};
+    use crate::github::types::{PullRequestStatus, ReviewDecision};
+
+    #[test]
+    fn pull_requests_become_records() -> Result<(), serde_json::Error> {
+        let pull_request: PullRequest = serde_json::from_str(
+            r#"{
+                "id": 12,
+                "title": "Add login",
+                "state": "MERGED",
+                "source": {
+                    "branch": { "name": "feature/login" },
+                    "commit": { "hash": "abc123def456" },
+                    "repository": { "full_name": "me/app" }
+                },
+                "destination": {
+                    "branch": { "name": "main" },
+                    "commit": { "hash": "0123456789ab" },
+                    "repository": { "full_name": "team/app" }
+                },
+                "links": { "html": { "href": "https://bitbucket.org/team/app/pull-requests/12" } },
+                "author": { "nickname": "octo" },
+                "created_on": "2024-05-01T10:00:00.123456+00:00",
+                "updated_on": "2024-05-02T08:00:00.000000+00:00",
+                "reviewers": [{ "nickname": "reviewer" }]
+            }"#,
+        )?;
+        let listing = to_listing("team/app", &pull_request);
+        let record = &listing.record;
+        assert_eq!(record.pr_number, 12);
+        assert_eq!(record.head_sha, "abc123def456");
+        assert_eq!(
+            (record.head_ref.as_str(), record.base_ref.as_str()),
+            ("feature/login", "main")
+        );
+        assert_eq!(record.status, PullRequestStatus::Merged);
+        assert_eq!(record.created_at, Some(1_714_557_600));
+        assert_eq!(record.merged_at, Some(1_714_636_800));
+        assert_eq!(listing.author, "octo");
+        assert_eq!(listing.requested_reviewers, ["reviewer"]);
+        assert!(listing.is_cross_repository);
+        Ok(())
+    }
+
+    #[test]
+    fn participants_decide_the_review_state() -> Result<(), serde_json::Error> {
+        let participants: Vec<Participant> = serde_json::from_str(
+            r#"[
+                { "user": { "nickname": "a" }, "state": "approved" },
+                { "user": { "nickname": "b" }, "state": null },
+                { "user": { "nickname": "c" }, "state": "changes_requested" }
+            ]"#,
+        )?;
+        assert_eq!(
+            review_decision(&participants),
+            Some(ReviewDecision::ChangesRequested)
+        );
+        assert_eq!(
+            review_decision(&participants[..2]),
+            Some(ReviewDecision::Approved)
+        );
+        assert_eq!(review_decision(&[]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn quote_escapes_query_strings() {
+        assert_eq!(quote(r#"fix/"quoted""#), r#""fix/\"quoted\"""#);
+    }
+}
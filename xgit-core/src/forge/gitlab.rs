@@ -0,0 +1,534 @@
+//! Merge requests on gitlab.com or a self-managed GitLab through the REST API (v4). Requests
+//! are signed in with `GITLAB_TOKEN` or `GL_TOKEN`, or the password a git credential helper
+//! stores for the host, and are anonymous otherwise.
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::forge::remote::{encode_component, RepoUrl};
+use crate::forge::ForgeProvider;
+use crate::git::GitError;
+use crate::github::auth;
+use crate::github::types::{
+    parse_timestamp, ChecksState, MergeMethod, PrListFilter, PullRequestChecks, PullRequestListing,
+    PullRequestRecord, PullRequestSnapshot, PullRequestStatus, ReviewDecision,
+};
+
+/// Host of gitlab.com's web and git URLs
+pub const GITLAB_HOST: &str = "gitlab.com";
+
+/// GitLab marks a merge request as a draft by this title prefix
+const DRAFT_PREFIX: &str = "Draft: ";
+
+pub struct GitLabClient {
+    octocrab: Octocrab,
+    /// Route prefix of the project, `/projects/<url-encoded path>`
+    project_route: String,
+    repo_slug: String,
+    signed_in: bool,
+}
+
+impl GitLabClient {
+    /// Client for the project at `repo` (on gitlab.com or a self-managed host), signed in
+    /// when a token is found and anonymous otherwise, which is enough for public projects
+    pub fn new(repo: &RepoUrl) -> Result<Self, Error> {
+        Self::build(repo, resolve_token(&repo.host))
+    }
+
+    /// Client that must be signed in, for opening and merging merge requests
+    pub fn authenticated(repo: &RepoUrl) -> Result<Self, Error> {
+        let token = resolve_token(&repo.host).ok_or_else(|| {
+            Error::from(GitError::AuthRequired(anyhow::anyhow!(
+                "No GitLab credentials found for {host}; set GITLAB_TOKEN or store a token for https://{host} in a git credential helper",
+                host = repo.host
+            )))
+        })?;
+        Self::build(repo, Some(token))
+    }
+
+    fn build(repo: &RepoUrl, token: Option<String>) -> Result<Self, Error> {
+        let signed_in = token.is_some();
+        let mut builder = Octocrab::builder().base_uri(format!("https://{}/api/v4", repo.host))?;
+        if let Some(token) = token {
+            builder = builder.personal_token(token);
+        }
+        Ok(Self {
+            octocrab: builder.build().context("Failed to create GitLab client")?,
+            project_route: format!("/projects/{}", encode_component(&repo.slug())),
+            repo_slug: repo.slug(),
+            signed_in,
+        })
+    }
+
+    /// `message` for a failed request, saying how to sign in when the client is anonymous,
+    /// since GitLab answers anonymous requests for private projects with 404
+    fn failed(&self, message: &str) -> String {
+        if self.signed_in {
+            message.to_string()
+        } else {
+            format!("{message} (not signed in to GitLab; set GITLAB_TOKEN for private projects)")
+        }
+    }
+
+    fn record(&self, request: &MergeRequest) -> PullRequestRecord {
+        to_pull_request_record(&self.repo_slug, request)
+    }
+
+    async fn merge_requests(&self, params: serde_json::Value) -> Result<Vec<MergeRequest>, Error> {
+        self.octocrab
+            .get(
+                format!("{}/merge_requests", self.project_route),
+                Some(&params),
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch merge requests"))
+    }
+
+    async fn merge_request(&self, iid: u64) -> Result<MergeRequest, Error> {
+        self.octocrab
+            .get(
+                format!("{}/merge_requests/{iid}", self.project_route),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch merge request"))
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GitLabClient {
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_default_branch(&self) -> Result<String, Error> {
+        let project: Project = self
+            .octocrab
+            .get(&self.project_route, None::<&()>)
+            .await
+            .with_context(|| self.failed("Failed to fetch project metadata"))?;
+        project
+            .default_branch
+            .ok_or_else(|| anyhow::anyhow!("Project default branch is not available"))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        Ok(self.record(&self.merge_request(pr_number).await?))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error> {
+        Ok(self
+            .merge_request(pr_number)
+            .await?
+            .description
+            .unwrap_or_default())
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn find_pr_by_head(&self, head_branch: &str) -> Result<Option<PullRequestRecord>, Error> {
+        let requests = self
+            .merge_requests(json!({ "source_branch": head_branch, "state": "all" }))
+            .await?;
+        Ok(requests.first().map(|request| self.record(request)))
+    }
+
+    /// GitLab lists a fork's merge requests by source branch like any other, so `owner` only
+    /// picks between same-named branches of the project and its forks
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let requests = self
+            .merge_requests(json!({ "source_branch": head_branch, "state": "all" }))
+            .await?;
+        let from_owner = requests.iter().find(|request| {
+            request
+                .author
+                .as_ref()
+                .map_or(false, |author| author.username.eq_ignore_ascii_case(owner))
+        });
+        Ok(from_owner
+            .or_else(|| requests.first())
+            .map(|request| self.record(request)))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let requests: Vec<MergeRequest> = self
+            .octocrab
+            .get(
+                format!(
+                    "{}/repository/commits/{commit_sha}/merge_requests",
+                    self.project_route
+                ),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch merge requests for commit"))?;
+        let request = requests
+            .iter()
+            .find(|request| request.state == "merged")
+            .or_else(|| requests.first());
+        Ok(request.map(|request| self.record(request)))
+    }
+
+    /// The head pipeline's status, when it ran on `head_sha`, and the approval rules. GitLab
+    /// has no change requests outside its paid tiers, so the decision is approved or waiting
+    /// on approvals.
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        head_sha: &str,
+    ) -> Result<PullRequestChecks, Error> {
+        let request = self.merge_request(pr_number).await?;
+        let approvals: Approvals = self
+            .octocrab
+            .get(
+                format!(
+                    "{}/merge_requests/{pr_number}/approvals",
+                    self.project_route
+                ),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch merge request approvals"))?;
+        Ok(PullRequestChecks {
+            checks: request
+                .head_pipeline
+                .filter(|pipeline| pipeline.sha == head_sha)
+                .map(|pipeline| pipeline_state(&pipeline.status)),
+            review_decision: approvals.decision(),
+        })
+    }
+
+    /// The first 100 open merge requests passing `filter`, newest first, each costing two more
+    /// requests for its checks
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn list_open_prs(&self, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error> {
+        let login = if filter.mine || filter.review_requested {
+            let user: User = self
+                .octocrab
+                .get("/user", None::<&()>)
+                .await
+                .with_context(|| self.failed("Failed to look up the signed-in GitLab user"))?;
+            Some(user.username)
+        } else {
+            None
+        };
+        let requests = self
+            .merge_requests(json!({
+                "state": "opened",
+                "order_by": "created_at",
+                "sort": "desc",
+                "per_page": 100,
+            }))
+            .await?;
+
+        let mut listings = Vec::new();
+        for request in &requests {
+            let mut listing = to_listing(&self.repo_slug, request);
+            if !filter.matches(&listing, login.as_deref()) {
+                continue;
+            }
+            let checks = self.get_pr_checks(request.iid, &request.sha).await?;
+            listing.record.set_checks(checks);
+            listings.push(listing);
+        }
+        Ok(listings)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, title, body))]
+    async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        let title = if draft {
+            format!("{DRAFT_PREFIX}{title}")
+        } else {
+            title.to_string()
+        };
+        let request: MergeRequest = self
+            .octocrab
+            .post(
+                format!("{}/merge_requests", self.project_route),
+                Some(&json!({
+                    "source_branch": head,
+                    "target_branch": base,
+                    "title": title,
+                    "description": body.unwrap_or_default(),
+                })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to create merge request"))?;
+        Ok(self.record(&request))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, title, body))]
+    async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        let mut changes = serde_json::Map::new();
+        if let Some(base) = base {
+            changes.insert("target_branch".to_string(), json!(base));
+        }
+        if let Some(title) = title {
+            changes.insert("title".to_string(), json!(title));
+        }
+        if let Some(body) = body {
+            changes.insert("description".to_string(), json!(body));
+        }
+        let request: MergeRequest = self
+            .octocrab
+            .put(
+                format!("{}/merge_requests/{pr_number}", self.project_route),
+                Some(&changes),
+            )
+            .await
+            .with_context(|| self.failed("Failed to update merge request"))?;
+        Ok(self.record(&request))
+    }
+
+    /// Merge or squash; whether GitLab rebases first is a project setting, not a choice per
+    /// merge request
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error> {
+        if method == MergeMethod::Rebase {
+            return Err(anyhow::anyhow!(
+                "GitLab rebases merge requests by project setting; merge with --strategy merge or squash"
+            ));
+        }
+        let request: MergeRequest = self
+            .octocrab
+            .put(
+                format!("{}/merge_requests/{pr_number}/merge", self.project_route),
+                Some(&json!({
+                    "sha": head_sha,
+                    "squash": method == MergeMethod::Squash,
+                })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to merge merge request"))?;
+
+        if request.state != "merged" {
+            return Err(anyhow::anyhow!(
+                "GitLab did not merge !{pr_number}; it is {}",
+                request.state
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `GITLAB_TOKEN`, then `GL_TOKEN`, then the git credential helper's password for `host`.
+///
+/// Clients are only made for gitlab.com and the hosts in the user's own `gitlab.hosts` (a
+/// repository's `.xgit.toml` cannot add any), so the environment's token stays with them.
+fn resolve_token(host: &str) -> Option<String> {
+    ["GITLAB_TOKEN", "GL_TOKEN"]
+        .iter()
+        .find_map(|name| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        })
+        .or_else(|| auth::credential_helper_token(host))
+}
+
+#[derive(Deserialize)]
+struct Project {
+    default_branch: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    web_url: String,
+    source_branch: String,
+    target_branch: String,
+    #[serde(default)]
+    sha: String,
+    /// `opened`, `closed`, `locked` or `merged`
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    created_at: Option<String>,
+    merged_at: Option<String>,
+    author: Option<User>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    reviewers: Vec<User>,
+    source_project_id: Option<u64>,
+    target_project_id: Option<u64>,
+    /// Only on a single merge request
+    head_pipeline: Option<Pipeline>,
+}
+
+#[derive(Deserialize)]
+struct Pipeline {
+    sha: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct Approvals {
+    #[serde(default)]
+    approved: bool,
+    #[serde(default)]
+    approvals_left: u64,
+    #[serde(default)]
+    approved_by: Vec<serde_json::Value>,
+}
+
+impl Approvals {
+    fn decision(&self) -> Option<ReviewDecision> {
+        if self.approvals_left > 0 {
+            Some(ReviewDecision::ReviewRequired)
+        } else if self.approved && !self.approved_by.is_empty() {
+            Some(ReviewDecision::Approved)
+        } else {
+            None
+        }
+    }
+}
+
+/// A pipeline's `status`: running ones are pending, and skipped or manual ones do not block
+fn pipeline_state(status: &str) -> ChecksState {
+    match status {
+        "success" | "skipped" | "manual" => ChecksState::Passing,
+        "failed" | "canceled" => ChecksState::Failing,
+        _ => ChecksState::Pending,
+    }
+}
+
+fn to_listing(repo_slug: &str, request: &MergeRequest) -> PullRequestListing {
+    PullRequestListing {
+        record: to_pull_request_record(repo_slug, request),
+        author: request
+            .author
+            .as_ref()
+            .map(|author| author.username.clone())
+            .unwrap_or_default(),
+        labels: request.labels.clone(),
+        requested_reviewers: request
+            .reviewers
+            .iter()
+            .map(|reviewer| reviewer.username.clone())
+            .collect(),
+        is_cross_repository: request.source_project_id != request.target_project_id,
+    }
+}
+
+fn to_pull_request_record(repo_slug: &str, request: &MergeRequest) -> PullRequestRecord {
+    PullRequestRecord::from_snapshot(PullRequestSnapshot {
+        repo_slug: repo_slug.to_string(),
+        pr_number: request.iid,
+        title: request.title.clone(),
+        url: request.web_url.clone(),
+        base_ref: request.target_branch.clone(),
+        head_ref: request.source_branch.clone(),
+        head_sha: request.sha.clone(),
+        draft: request.draft,
+        status: match request.state.as_str() {
+            "merged" => PullRequestStatus::Merged,
+            "closed" | "locked" => PullRequestStatus::Closed,
+            _ => PullRequestStatus::Open,
+        },
+        created_at: request.created_at.as_deref().and_then(parse_timestamp),
+        merged_at: request.merged_at.as_deref().and_then(parse_timestamp),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_listing, Approvals, MergeRequest};
+    use crate::github::types::{PullRequestStatus, ReviewDecision};
+
+    #[test]
+    fn merge_requests_become_pull_request_records() -> Result<(), serde_json::Error> {
+        let request: MergeRequest = serde_json::from_str(
+            r#"{
+                "iid": 42,
+                "title": "Draft: Add login",
+                "web_url": "https://gitlab.com/group/sub/app/-/merge_requests/42",
+                "source_branch": "feature/login",
+                "target_branch": "main",
+                "sha": "abc123",
+                "state": "merged",
+                "draft": true,
+                "created_at": "2024-05-01T10:00:00.000Z",
+                "merged_at": "2024-05-02T10:00:00.000+02:00",
+                "author": { "username": "octo" },
+                "labels": ["backend"],
+                "reviewers": [{ "username": "reviewer" }],
+                "source_project_id": 7,
+                "target_project_id": 9
+            }"#,
+        )?;
+        let listing = to_listing("group/sub/app", &request);
+        let record = &listing.record;
+        assert_eq!(record.pr_number, 42);
+        assert_eq!(record.repo_slug, "group/sub/app");
+        assert_eq!(
+            (record.head_ref.as_str(), record.base_ref.as_str()),
+            ("feature/login", "main")
+        );
+        assert_eq!(record.status, PullRequestStatus::Merged);
+        assert!(record.draft);
+        assert_eq!(record.created_at, Some(1_714_557_600));
+        assert_eq!(record.merged_at, Some(1_714_636_800));
+        assert_eq!(listing.author, "octo");
+        assert_eq!(listing.requested_reviewers, ["reviewer"]);
+        assert!(listing.is_cross_repository);
+        Ok(())
+    }
+
+    #[test]
+    fn approvals_decide_the_review_state() -> Result<(), serde_json::Error> {
+        let decision = |json: &str| {
+            serde_json::from_str::<Approvals>(json).map(|approvals| approvals.decision())
+        };
+        assert_eq!(
+            decision(r#"{ "approved": false, "approvals_left": 1, "approved_by": [] }"#)?,
+            Some(ReviewDecision::ReviewRequired)
+        );
+        assert_eq!(
+            decision(r#"{ "approved": true, "approvals_left": 0, "approved_by": [{}] }"#)?,
+            Some(ReviewDecision::Approved)
+        );
+        assert_eq!(
+            decision(r#"{ "approved": true, "approved_by": [] }"#)?,
+            None
+        );
+        Ok(())
+    }
+}
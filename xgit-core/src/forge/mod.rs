@@ -0,0 +1,130 @@
+//! The code forges xgit finds pull requests on. A repository's forge is chosen by its remote
+//! URL ([`GitRepo::forge_remote`](crate::GitRepo::forge_remote)): github.com and GitHub
+//! Enterprise go through [`github`](crate::github), gitlab.com and self-managed GitLab
+//! through [`gitlab`], and bitbucket.org through [`bitbucket`]. Commands work with whichever
+//! one through the [`ForgeProvider`] trait.
+
+pub mod bitbucket;
+pub mod gitlab;
+pub mod remote;
+
+pub use remote::{ForgeKind, ForgeRemote, RepoUrl};
+
+use crate::github::client::GitHubClient;
+use crate::github::pr_service::GhCli;
+use crate::github::types::{
+    MergeMethod, PrListFilter, PullRequestChecks, PullRequestListing, PullRequestRecord,
+};
+use anyhow::Error;
+use async_trait::async_trait;
+use bitbucket::BitbucketClient;
+use gitlab::GitLabClient;
+use std::env;
+use std::path::Path;
+
+/// The pull request operations xgit needs from a code forge.
+///
+/// [`GitHubPrService`](crate::github::pr_service::GitHubPrService) talks to GitHub (via the
+/// `gh` CLI or the REST API), GitLab or Bitbucket through this trait, so PR matching can be
+/// tested against an in-memory forge instead of the network. GitLab calls pull requests merge
+/// requests; here both are PRs, numbered by their `iid` on GitLab.
+#[async_trait]
+pub trait ForgeProvider: Send + Sync {
+    /// Check that the forge can be used, for example that a required CLI is installed
+    fn ensure_ready(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn get_default_branch(&self) -> Result<String, Error>;
+
+    async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error>;
+
+    /// The description of PR `pr_number`, empty when it has none
+    async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error>;
+
+    async fn find_pr_by_head(&self, head_branch: &str) -> Result<Option<PullRequestRecord>, Error>;
+
+    /// Find a PR whose head branch lives in `owner`'s fork
+    async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error>;
+
+    /// The PR that brought `commit_sha` in: a merged one when the forge links the commit to
+    /// several
+    async fn find_pr_by_commit(&self, commit_sha: &str)
+        -> Result<Option<PullRequestRecord>, Error>;
+
+    /// CI results on `head_sha` and the review decision of PR `pr_number`
+    async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        head_sha: &str,
+    ) -> Result<PullRequestChecks, Error>;
+
+    /// Open PRs passing `filter`, newest first, with their checks
+    async fn list_open_prs(&self, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error>;
+
+    async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error>;
+
+    async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error>;
+
+    /// Merge PR `pr_number` with `method`, refusing when its head is no longer `head_sha`
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error>;
+}
+
+/// The forge behind `remote`, for reading pull requests. GitHub goes through the `gh` CLI,
+/// or the REST API when `XGIT_GITHUB_BACKEND=api`; the others go through their REST APIs,
+/// signed in when credentials are found.
+pub fn provider_for(
+    repo_path: &Path,
+    remote: &ForgeRemote,
+) -> Result<Box<dyn ForgeProvider>, Error> {
+    let repo = &remote.repo;
+    Ok(match remote.kind {
+        ForgeKind::GitHub => match env::var("XGIT_GITHUB_BACKEND").ok().as_deref() {
+            Some("api") => Box::new(GitHubClient::new(
+                &repo.host,
+                repo.owner.clone(),
+                repo.repo.clone(),
+            )?),
+            _ => Box::new(GhCli::new(repo_path, &repo.host, repo.slug())),
+        },
+        ForgeKind::GitLab => Box::new(GitLabClient::new(repo)?),
+        ForgeKind::Bitbucket => Box::new(BitbucketClient::new(repo)?),
+    })
+}
+
+/// The forge behind `remote` through its REST API, signed in, for opening and merging pull
+/// requests; fails with an auth error explaining how to sign in when no credentials are found
+pub fn authenticated_provider(remote: &ForgeRemote) -> Result<Box<dyn ForgeProvider>, Error> {
+    let repo = &remote.repo;
+    Ok(match remote.kind {
+        ForgeKind::GitHub => Box::new(GitHubClient::authenticated(
+            &repo.host,
+            repo.owner.clone(),
+            repo.repo.clone(),
+        )?),
+        ForgeKind::GitLab => Box::new(GitLabClient::authenticated(repo)?),
+        ForgeKind::Bitbucket => Box::new(BitbucketClient::authenticated(repo)?),
+    })
+}
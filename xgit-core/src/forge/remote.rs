@@ -0,0 +1,317 @@
+use anyhow::Error;
+use std::fmt;
+
+use crate::config::XgitConfig;
+use crate::git::GitRepo;
+
+/// Host of Bitbucket Cloud's web and git URLs
+pub const BITBUCKET_HOST: &str = "bitbucket.org";
+
+/// The forges xgit can talk to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    /// github.com or a GitHub Enterprise host in `github.hosts`
+    GitHub,
+    /// gitlab.com or a self-managed GitLab host in `gitlab.hosts`
+    GitLab,
+    /// Bitbucket Cloud
+    Bitbucket,
+}
+
+impl ForgeKind {
+    /// The forge serving `host`, going by the hosts `config` knows
+    pub fn for_host(host: &str, config: &XgitConfig) -> Option<Self> {
+        let known = |hosts: &[String]| hosts.iter().any(|known| known.eq_ignore_ascii_case(host));
+        if known(config.github_hosts()) {
+            Some(ForgeKind::GitHub)
+        } else if known(config.gitlab_hosts()) {
+            Some(ForgeKind::GitLab)
+        } else if host.eq_ignore_ascii_case(BITBUCKET_HOST) {
+            Some(ForgeKind::Bitbucket)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ForgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Bitbucket => "Bitbucket",
+        })
+    }
+}
+
+/// A repository on a forge, read from a remote URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoUrl {
+    pub host: String,
+    /// User, organization or Bitbucket workspace; GitLab groups may nest, as `group/subgroup`
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RepoUrl {
+    /// Read `git@host:owner/repo.git`, `ssh://git@host[:port]/owner/repo.git` or
+    /// `https://[user@]host[:port]/owner/repo.git`
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (authority, path) = match url.split_once("://") {
+            Some(("https" | "http" | "ssh" | "git", rest)) => rest.split_once('/')?,
+            Some(_) => return None,
+            // scp-like syntax
+            None => url.split_once(':')?,
+        };
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?.to_ascii_lowercase();
+        if host.is_empty() {
+            return None;
+        }
+
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let (owner, repo) = path.rsplit_once('/')?;
+        if owner.is_empty() || repo.is_empty() || owner.split('/').any(str::is_empty) {
+            return None;
+        }
+        Some(Self {
+            host,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    /// `owner/repo`
+    pub fn slug(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+}
+
+/// A remote that points at a repository on a forge xgit knows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRemote {
+    pub name: String,
+    pub url: String,
+    pub kind: ForgeKind,
+    pub repo: RepoUrl,
+}
+
+impl ForgeRemote {
+    /// `url` as a remote of a known forge. GitHub and Bitbucket repositories sit directly
+    /// under their owner; GitLab's may sit in nested groups.
+    pub fn parse(name: &str, url: &str, config: &XgitConfig) -> Option<Self> {
+        let repo = RepoUrl::parse(url)?;
+        let kind = ForgeKind::for_host(&repo.host, config)?;
+        if kind != ForgeKind::GitLab && repo.owner.contains('/') {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            kind,
+            repo,
+        })
+    }
+
+    /// The repository's page, `https://host/owner/repo`
+    pub fn web_url(&self) -> String {
+        format!("https://{}/{}", self.repo.host, self.repo.slug())
+    }
+
+    /// The page for `branch`'s files
+    pub fn branch_url(&self, branch: &str) -> String {
+        let route = match self.kind {
+            ForgeKind::GitHub => "tree",
+            ForgeKind::GitLab => "-/tree",
+            ForgeKind::Bitbucket => "src",
+        };
+        format!("{}/{route}/{}", self.web_url(), encode_path(branch))
+    }
+
+    /// The page for `path` (relative to the repository root) as of `commit`, highlighting
+    /// `lines` (first and last, counting from 1) when given
+    pub fn blob_url(&self, commit: &str, path: &str, lines: Option<(u32, u32)>) -> String {
+        let (route, anchor) = match self.kind {
+            ForgeKind::GitHub => ("blob", line_anchor(lines, "#L", "-L")),
+            ForgeKind::GitLab => ("-/blob", line_anchor(lines, "#L", "-")),
+            ForgeKind::Bitbucket => ("src", line_anchor(lines, "#lines-", ":")),
+        };
+        format!(
+            "{}/{route}/{commit}/{}{anchor}",
+            self.web_url(),
+            encode_path(path)
+        )
+    }
+}
+
+/// `#L10`, or `#L10-L20` for a range, in a forge's own syntax
+fn line_anchor(lines: Option<(u32, u32)>, prefix: &str, separator: &str) -> String {
+    match lines {
+        Some((first, last)) if last > first => format!("{prefix}{first}{separator}{last}"),
+        Some((first, _)) => format!("{prefix}{first}"),
+        None => String::new(),
+    }
+}
+
+/// `path` with every byte outside unreserved URL characters and `/` percent-encoded
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `value` with every byte outside unreserved URL characters percent-encoded, for one segment
+/// of a URL path
+pub(crate) fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+impl GitRepo {
+    /// The remote to use for pull requests: the configured default remote, `origin` or
+    /// `upstream` when one points at a known forge, otherwise the first remote that does
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn forge_remote(&self) -> Result<ForgeRemote, Error> {
+        self.find_forge_remote(|_| true).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No GitHub, GitLab or Bitbucket remote found; add self-hosted hosts to `github.hosts` or `gitlab.hosts` in ~/.config/xgit/config.toml"
+            )
+        })
+    }
+
+    /// Like [`forge_remote`](GitRepo::forge_remote), considering only remotes on `kind`
+    pub(crate) fn find_forge_remote(
+        &self,
+        accept: impl Fn(ForgeKind) -> bool,
+    ) -> Option<ForgeRemote> {
+        let config = self.xgit_config().ok()?;
+        let forge_remote = |name: &str| {
+            let url = self.get_remote_url(name).ok()?;
+            ForgeRemote::parse(name, &url, &config).filter(|remote| accept(remote.kind))
+        };
+
+        let preferred = [config.default_remote(), "origin", "upstream"]
+            .into_iter()
+            .find_map(forge_remote);
+        preferred.or_else(|| {
+            self.get_remotes()
+                .ok()?
+                .iter()
+                .find_map(|remote| forge_remote(&remote.name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ForgeKind, ForgeRemote, RepoUrl};
+    use crate::config::XgitConfig;
+    use crate::test_utils::create_test_repo;
+
+    #[test]
+    fn parse_reads_ssh_and_https_urls() {
+        let parsed =
+            |url: &str| RepoUrl::parse(url).map(|repo| format!("{}:{}", repo.host, repo.slug()));
+        assert_eq!(
+            parsed("git@github.com:octo/repo.git").as_deref(),
+            Some("github.com:octo/repo")
+        );
+        assert_eq!(
+            parsed("ssh://git@git.corp.example:2222/team/app.git").as_deref(),
+            Some("git.corp.example:team/app")
+        );
+        assert_eq!(
+            parsed("https://me@GitLab.com/group/sub/app.git/").as_deref(),
+            Some("gitlab.com:group/sub/app")
+        );
+        assert_eq!(parsed("https://github.com/octo"), None);
+        assert_eq!(parsed("file:///github.com/octo/repo"), None);
+    }
+
+    #[test]
+    fn remotes_are_matched_to_their_forge() {
+        let config = XgitConfig::default();
+        let kind = |url: &str| ForgeRemote::parse("origin", url, &config).map(|remote| remote.kind);
+        assert_eq!(
+            kind("git@github.com:octo/repo.git"),
+            Some(ForgeKind::GitHub)
+        );
+        assert_eq!(
+            kind("https://gitlab.com/group/sub/app.git"),
+            Some(ForgeKind::GitLab)
+        );
+        assert_eq!(
+            kind("git@bitbucket.org:team/app.git"),
+            Some(ForgeKind::Bitbucket)
+        );
+        // Only GitLab nests repositories in groups
+        assert_eq!(kind("https://github.com/octo/sub/repo"), None);
+        assert_eq!(kind("git@git.corp.example:team/app.git"), None);
+    }
+
+    #[test]
+    fn web_urls_use_each_forges_routes() {
+        let config = XgitConfig::default();
+        let remote = |url: &str| ForgeRemote::parse("origin", url, &config).unwrap();
+
+        let github = remote("git@github.com:octo/repo.git");
+        assert_eq!(
+            github.branch_url("feature/søk#2"),
+            "https://github.com/octo/repo/tree/feature/s%C3%B8k%232"
+        );
+        assert_eq!(
+            github.blob_url("abc123", "docs/read me.md", Some((42, 42))),
+            "https://github.com/octo/repo/blob/abc123/docs/read%20me.md#L42"
+        );
+        assert_eq!(
+            github.blob_url("abc123", "src/lib.rs", Some((10, 20))),
+            "https://github.com/octo/repo/blob/abc123/src/lib.rs#L10-L20"
+        );
+
+        let gitlab = remote("git@gitlab.com:group/sub/app.git");
+        assert_eq!(
+            gitlab.branch_url("main"),
+            "https://gitlab.com/group/sub/app/-/tree/main"
+        );
+        assert_eq!(
+            gitlab.blob_url("abc123", "src/lib.rs", Some((10, 20))),
+            "https://gitlab.com/group/sub/app/-/blob/abc123/src/lib.rs#L10-20"
+        );
+
+        let bitbucket = remote("git@bitbucket.org:team/app.git");
+        assert_eq!(
+            bitbucket.blob_url("abc123", "src/lib.rs", Some((10, 20))),
+            "https://bitbucket.org/team/app/src/abc123/src/lib.rs#lines-10:20"
+        );
+        assert_eq!(
+            bitbucket.blob_url("abc123", "src/lib.rs", None),
+            "https://bitbucket.org/team/app/src/abc123/src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn forge_remote_prefers_origin() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        assert!(repo.forge_remote().is_err());
+
+        repo.add_remote("mirror", "https://example.com/octo/repo.git")?;
+        repo.add_remote("fork", "git@gitlab.com:me/repo.git")?;
+        assert_eq!(repo.forge_remote()?.kind, ForgeKind::GitLab);
+
+        repo.add_remote("origin", "git@bitbucket.org:team/repo.git")?;
+        let remote = repo.forge_remote()?;
+        assert_eq!(remote.name, "origin");
+        assert_eq!(remote.kind, ForgeKind::Bitbucket);
+        Ok(())
+    }
+}
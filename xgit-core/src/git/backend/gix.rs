@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::Context;
+use gix::bstr::{BString, ByteSlice};
+use gix::revision::walk::Sorting;
+use gix::status::index_worktree::iter::Summary;
+
+use crate::git::backend::ReadBackend;
+use crate::git::commits::staging::ChangeCounts;
+use crate::git::error::GitError;
+use crate::git::repository::core::CommitInfo;
+
+/// [`ReadBackend`] implemented with gitoxide
+pub struct GixBackend {
+    repo: gix::Repository,
+}
+
+impl GixBackend {
+    /// Open the repository whose git directory is `git_dir`
+    pub fn open(git_dir: &Path) -> Result<Self, GitError> {
+        let repo = gix::open(git_dir).context("Cannot open git repo with gix")?;
+        Ok(Self { repo })
+    }
+}
+
+impl ReadBackend for GixBackend {
+    fn name(&self) -> &'static str {
+        "gix"
+    }
+
+    fn list_branches(&self) -> Result<Vec<String>, GitError> {
+        let references = self
+            .repo
+            .references()
+            .context("Failed to read references")?;
+        let mut branches = Vec::new();
+        for reference in references
+            .local_branches()
+            .context("Failed to list local branches")?
+        {
+            let reference =
+                reference.map_err(|e| anyhow::anyhow!("Failed to read branch reference: {e}"))?;
+            branches.push(reference.name().shorten().to_string());
+        }
+        Ok(branches)
+    }
+
+    fn log(
+        &self,
+        range: &str,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        let head = || -> Result<gix::ObjectId, GitError> {
+            Ok(self
+                .repo
+                .head_id()
+                .context("Failed to resolve HEAD")?
+                .detach())
+        };
+        let spec = self
+            .repo
+            .rev_parse(range)
+            .context(format!("Failed to resolve '{range}'"))?
+            .detach();
+
+        let (tips, hidden) = match spec {
+            gix::revision::plumbing::Spec::Include(id) => (vec![id], Vec::new()),
+            gix::revision::plumbing::Spec::Range { from, to } => (vec![to], vec![from]),
+            gix::revision::plumbing::Spec::Merge { theirs, ours } => {
+                let base = self
+                    .repo
+                    .merge_base(theirs, ours)
+                    .ok()
+                    .flatten()
+                    .map(|base| vec![base.detach()])
+                    .unwrap_or_default();
+                (vec![theirs, ours], base)
+            }
+            // `A..` and `A...` compare against HEAD, like git
+            gix::revision::plumbing::Spec::Exclude(from) => (vec![head()?], vec![from]),
+            other => {
+                return Err(
+                    anyhow::anyhow!("Unsupported revision range '{range}': {other:?}").into(),
+                )
+            }
+        };
+
+        let walk = self
+            .repo
+            .rev_walk(tips)
+            .with_hidden(hidden)
+            .sorting(Sorting::ByCommitTime(Default::default()))
+            .all()
+            .context("Failed to walk commits")?;
+
+        let mut commits = Vec::new();
+        for info in walk.skip(skip).take(limit.unwrap_or(usize::MAX)) {
+            let info = info.context("Failed to walk commits")?;
+            let commit = info.object().context("Failed to find commit")?;
+            let author = commit.author().context("Failed to read commit author")?;
+            let time = author.time().context("Failed to read commit time")?;
+            commits.push(CommitInfo {
+                hash: info.id.to_string(),
+                message: commit
+                    .message_raw()
+                    .context("Failed to read commit message")?
+                    .to_str_lossy()
+                    .to_string(),
+                author_name: author.name.to_str_lossy().to_string(),
+                author_email: author.email.to_str_lossy().to_string(),
+                time: time.seconds,
+                offset_minutes: time.offset / 60,
+                parent_ids: info.parent_ids().map(|id| id.to_string()).collect(),
+            });
+        }
+        Ok(commits)
+    }
+
+    fn change_counts(&self) -> Result<ChangeCounts, GitError> {
+        let status = self
+            .repo
+            .status(gix::progress::Discard)
+            .context("Failed to read repository status")?
+            .untracked_files(gix::status::UntrackedFiles::Files)
+            .into_iter(Vec::<BString>::new())
+            .context("Failed to read repository status")?;
+
+        let mut staged = HashSet::new();
+        let mut unstaged = HashSet::new();
+        let mut untracked = HashSet::new();
+        let mut conflicted = HashSet::new();
+        for item in status {
+            match item.context("Failed to read repository status")? {
+                gix::status::Item::TreeIndex(change) => {
+                    staged.insert(change.location().to_owned());
+                }
+                gix::status::Item::IndexWorktree(item) => {
+                    let path = item.rela_path().to_owned();
+                    match item.summary() {
+                        Some(Summary::Conflict) => {
+                            conflicted.insert(path);
+                        }
+                        Some(Summary::Added) => {
+                            untracked.insert(path);
+                        }
+                        Some(_) => {
+                            unstaged.insert(path);
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        // Conflicted files are only counted as conflicted, like libgit2's status
+        Ok(ChangeCounts {
+            staged: staged.difference(&conflicted).count(),
+            unstaged: unstaged.difference(&conflicted).count(),
+            untracked: untracked.len(),
+            conflicted: conflicted.len(),
+        })
+    }
+}
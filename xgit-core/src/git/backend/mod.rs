@@ -0,0 +1,175 @@
+//! Read-only repository operations behind a swappable backend.
+//!
+//! [`GitRepo`] itself implements [`ReadBackend`] with libgit2. With the `gix` cargo feature,
+//! [`GixBackend`](gix::GixBackend) implements the same operations with gitoxide, which needs
+//! no C toolchain and is faster on very large repositories. `git config xgit.backend gix`
+//! selects it through [`GitRepo::read_backend`].
+
+#[cfg(feature = "gix")]
+pub mod gix;
+
+use crate::git::commits::staging::ChangeCounts;
+use crate::git::error::GitError;
+use crate::git::repository::core::{CommitInfo, GitRepo};
+
+/// Operations that only read the repository, shared by every backend
+pub trait ReadBackend {
+    /// Short name of the backend, as accepted by `xgit.backend`
+    fn name(&self) -> &'static str;
+
+    /// Names of all local branches
+    fn list_branches(&self) -> Result<Vec<String>, GitError>;
+
+    /// Commits selected by `range` (a revision, `A..B` or `A...B`), newest first, skipping the
+    /// first `skip` and returning at most `limit`
+    fn log(
+        &self,
+        range: &str,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommitInfo>, GitError>;
+
+    /// Count staged, unstaged, untracked and conflicted files
+    fn change_counts(&self) -> Result<ChangeCounts, GitError>;
+}
+
+impl ReadBackend for GitRepo {
+    fn name(&self) -> &'static str {
+        "git2"
+    }
+
+    fn list_branches(&self) -> Result<Vec<String>, GitError> {
+        self.get_all_branches()
+    }
+
+    fn log(
+        &self,
+        range: &str,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        self.list_commits_range(range, skip, limit)
+    }
+
+    fn change_counts(&self) -> Result<ChangeCounts, GitError> {
+        GitRepo::change_counts(self)
+    }
+}
+
+impl GitRepo {
+    /// The backend chosen by `xgit.backend` (`git2`, the default, or `gix`)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn read_backend(&self) -> Result<Box<dyn ReadBackend + '_>, GitError> {
+        let backend = self.get_config_string("xgit.backend")?;
+        match backend.as_deref().map(str::trim) {
+            None | Some("git2") => Ok(Box::new(self)),
+            #[cfg(feature = "gix")]
+            Some("gix") => Ok(Box::new(gix::GixBackend::open(self.git_dir())?)),
+            #[cfg(not(feature = "gix"))]
+            Some("gix") => Err(anyhow::anyhow!(
+                "xgit.backend is 'gix' but this build does not include the gix backend"
+            )
+            .into()),
+            Some(other) => Err(anyhow::anyhow!(
+                "Unknown xgit.backend '{other}' (expected 'git2' or 'gix')"
+            )
+            .into()),
+        }
+    }
+}
+
+impl<T: ReadBackend + ?Sized> ReadBackend for &T {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn list_branches(&self) -> Result<Vec<String>, GitError> {
+        (**self).list_branches()
+    }
+
+    fn log(
+        &self,
+        range: &str,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        (**self).log(range, skip, limit)
+    }
+
+    fn change_counts(&self) -> Result<ChangeCounts, GitError> {
+        (**self).change_counts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadBackend;
+    use crate::git::repository::core::{CommitInfo, GitRepo};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn read_backend_follows_config() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+        assert_eq!(repo.read_backend()?.name(), "git2");
+
+        repo.repo().config()?.set_str("xgit.backend", "svn")?;
+        assert!(repo.read_backend().is_err());
+        Ok(())
+    }
+
+    /// Every backend compiled in must agree with libgit2
+    #[test]
+    fn backends_agree_on_read_operations() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?
+            .add_file_and_commit("b.txt", "b\n", "Second commit")?;
+        repo.create_and_checkout_branch("feature")?;
+        repo.add_file_and_commit("c.txt", "c\n", "Feature commit")?;
+        repo.append_to_file("a.txt", "more\n")?;
+        repo.add_file("staged.txt", "s\n")?;
+        repo.add(&["staged.txt"])?;
+        std::fs::create_dir_all(repo.path().join("untracked"))?;
+        repo.add_file("untracked/new.txt", "u\n")?;
+
+        #[allow(unused_mut)]
+        let mut backends: Vec<Box<dyn ReadBackend>> = vec![Box::new(&repo)];
+        #[cfg(feature = "gix")]
+        backends.push(Box::new(super::gix::GixBackend::open(repo.git_dir())?));
+
+        let summary = |commits: Vec<CommitInfo>| {
+            commits
+                .into_iter()
+                .map(|commit| (commit.hash, commit.message, commit.time))
+                .collect::<Vec<_>>()
+        };
+        let git2: &GitRepo = &repo;
+        for backend in &backends {
+            assert_eq!(
+                backend.list_branches()?,
+                git2.get_all_branches()?,
+                "{}",
+                backend.name()
+            );
+            for (range, skip, limit) in [
+                ("HEAD", 0, None),
+                ("master..feature", 0, None),
+                ("HEAD", 1, Some(1)),
+            ] {
+                assert_eq!(
+                    summary(backend.log(range, skip, limit)?),
+                    summary(git2.list_commits_range(range, skip, limit)?),
+                    "{} {range}",
+                    backend.name()
+                );
+            }
+            assert_eq!(
+                backend.change_counts()?,
+                git2.change_counts()?,
+                "{}",
+                backend.name()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use git2::Oid;
+use serde::{Deserialize, Serialize};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+const CURRENT_SCHEMA_VERSION: u32 = 1;
+/// Drop the on-disk cache rather than let it grow without bound
+const MAX_CACHED_ENTRIES: usize = 10_000;
+
+/// Memoised merge bases, ahead/behind counts and merged-into-main results.
+///
+/// Everything is keyed by commit SHAs, so entries never go stale: a branch that moves simply
+/// looks up a different key. Main's tip is resolved once per cache, so one cache should live
+/// for a single command invocation. `Send + Sync`, so it can be shared with a
+/// [`GitRepoPool`](crate::git::GitRepoPool)'s workers.
+#[derive(Debug, Default)]
+pub struct MergeBaseCache {
+    path: Option<PathBuf>,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheState {
+    version: u32,
+    /// `"<a>:<b>"` → merge base
+    merge_bases: HashMap<String, String>,
+    /// `"<local>:<upstream>"` → (ahead, behind)
+    ahead_behind: HashMap<String, (usize, usize)>,
+    /// `"<branch tip>:<main tip>"` → whether the tip is reachable from main
+    merged: HashMap<String, bool>,
+    #[serde(skip)]
+    main_tip: Option<Oid>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl CacheState {
+    fn len(&self) -> usize {
+        self.merge_bases.len() + self.ahead_behind.len() + self.merged.len()
+    }
+}
+
+impl MergeBaseCache {
+    /// A cache that lives only as long as this value
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// A cache backed by the JSON file at `path`; an unreadable or outdated file starts empty
+    pub fn load(path: PathBuf) -> Self {
+        let state = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CacheState>(&contents).ok())
+            .filter(|state| state.version == CURRENT_SCHEMA_VERSION)
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Write new entries back to the backing file, if there is one
+    pub fn save(&self) -> Result<(), GitError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let Ok(mut state) = self.state.lock() else {
+            return Ok(());
+        };
+        if !state.dirty {
+            return Ok(());
+        }
+        if state.len() > MAX_CACHED_ENTRIES {
+            state.merge_bases.clear();
+            state.ahead_behind.clear();
+            state.merged.clear();
+        }
+        state.version = CURRENT_SCHEMA_VERSION;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create cache directory '{}'",
+                parent.display()
+            ))?;
+        }
+        let payload =
+            serde_json::to_vec(&*state).context("Failed to serialize merge-base cache")?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, payload).context(format!(
+            "Failed to write merge-base cache '{}'",
+            temp_path.display()
+        ))?;
+        fs::rename(&temp_path, path).context(format!(
+            "Failed to replace merge-base cache '{}'",
+            path.display()
+        ))?;
+        state.dirty = false;
+        Ok(())
+    }
+
+    fn get<T: Clone>(&self, select: impl FnOnce(&CacheState) -> Option<T>) -> Option<T> {
+        self.state.lock().ok().and_then(|state| select(&state))
+    }
+
+    fn update(&self, update: impl FnOnce(&mut CacheState)) {
+        if let Ok(mut state) = self.state.lock() {
+            update(&mut state);
+            state.dirty = true;
+        }
+    }
+}
+
+fn key(a: Oid, b: Oid) -> String {
+    format!("{a}:{b}")
+}
+
+impl GitRepo {
+    /// Open the merge-base cache for one invocation.
+    ///
+    /// With `xgit.cache.mergeBases` enabled the cache is also persisted to
+    /// `.git/xgit/merge-base-cache.json`, so later invocations skip work for unchanged SHAs.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn merge_base_cache(&self) -> Result<MergeBaseCache, GitError> {
+        let persistent = self
+            .get_config_bool("xgit.cache.mergeBases")?
+            .unwrap_or(false);
+        Ok(if persistent {
+            MergeBaseCache::load(self.git_dir().join("xgit").join("merge-base-cache.json"))
+        } else {
+            MergeBaseCache::in_memory()
+        })
+    }
+
+    /// Merge base of two commits, computed at most once per pair
+    #[tracing::instrument(level = "debug", skip(self, cache))]
+    pub fn merge_base_cached(
+        &self,
+        cache: &MergeBaseCache,
+        a: Oid,
+        b: Oid,
+    ) -> Result<Oid, GitError> {
+        let key = key(a, b);
+        if let Some(base) = cache
+            .get(|state| state.merge_bases.get(&key).cloned())
+            .and_then(|base| Oid::from_str(&base).ok())
+        {
+            return Ok(base);
+        }
+
+        let base = self
+            .repo()
+            .merge_base(a, b)
+            .context("Failed to find merge base")?;
+        cache.update(|state| {
+            state.merge_bases.insert(key, base.to_string());
+        });
+        Ok(base)
+    }
+
+    /// [`ahead_behind`](GitRepo::ahead_behind), memoised by the SHAs of both sides
+    #[tracing::instrument(level = "debug", skip(self, cache))]
+    pub fn ahead_behind_cached(
+        &self,
+        cache: &MergeBaseCache,
+        branch: &str,
+        upstream: &str,
+    ) -> Result<(usize, usize), GitError> {
+        let local = self.peel_to_oid(&format!("refs/heads/{branch}"))?;
+        let upstream_oid = self.peel_to_oid(upstream)?;
+        let key = key(local, upstream_oid);
+        if let Some(counts) = cache.get(|state| state.ahead_behind.get(&key).copied()) {
+            return Ok(counts);
+        }
+
+        let counts = self
+            .repo()
+            .graph_ahead_behind(local, upstream_oid)
+            .context(format!("Failed to compare '{branch}' with '{upstream}'"))?;
+        cache.update(|state| {
+            state.ahead_behind.insert(key, counts);
+        });
+        Ok(counts)
+    }
+
+    /// Whether each branch is merged into main/master, using a single walk of main's history.
+    ///
+    /// Branches that do not exist are left out of the result. Fails when there is no main or
+    /// master branch.
+    #[tracing::instrument(level = "debug", skip(self, cache, branches))]
+    pub fn branches_merged_to_main(
+        &self,
+        cache: &MergeBaseCache,
+        branches: &[String],
+    ) -> Result<HashMap<String, bool>, GitError> {
+        let main_tip = self.cached_main_tip(cache)?;
+        let mut merged = HashMap::new();
+        let mut pending: HashMap<Oid, Vec<&String>> = HashMap::new();
+
+        for branch in branches {
+            let Ok(tip) = self.peel_to_oid(&format!("refs/heads/{branch}")) else {
+                continue;
+            };
+            let known = if tip == main_tip {
+                Some(true)
+            } else {
+                cache.get(|state| state.merged.get(&key(tip, main_tip)).copied())
+            };
+            match known {
+                Some(is_merged) => {
+                    merged.insert(branch.clone(), is_merged);
+                }
+                None => pending.entry(tip).or_default().push(branch),
+            }
+        }
+
+        if !pending.is_empty() {
+            let mut walk = self.repo().revwalk().context("Failed to create revwalk")?;
+            walk.push(main_tip).context("Failed to walk main")?;
+            for oid in walk {
+                let oid = oid.context("Failed to walk main")?;
+                if let Some(names) = pending.remove(&oid) {
+                    cache.update(|state| {
+                        state.merged.insert(key(oid, main_tip), true);
+                    });
+                    merged.extend(names.into_iter().map(|name| (name.clone(), true)));
+                    if pending.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Whatever the walk did not reach is not in main
+        for (tip, names) in pending {
+            cache.update(|state| {
+                state.merged.insert(key(tip, main_tip), false);
+            });
+            merged.extend(names.into_iter().map(|name| (name.clone(), false)));
+        }
+        Ok(merged)
+    }
+
+    fn cached_main_tip(&self, cache: &MergeBaseCache) -> Result<Oid, GitError> {
+        if let Some(tip) = cache.get(|state| state.main_tip) {
+            return Ok(tip);
+        }
+        let tip = self.peel_to_oid(&format!("refs/heads/{}", self.trunk_branch()?))?;
+        if let Ok(mut state) = cache.state.lock() {
+            state.main_tip = Some(tip);
+        }
+        Ok(tip)
+    }
+
+    fn peel_to_oid(&self, revision: &str) -> Result<Oid, GitError> {
+        Ok(self
+            .repo()
+            .revparse_single(revision)
+            .context(format!("Failed to find '{revision}'"))?
+            .peel_to_commit()
+            .context(format!("Failed to get commit for '{revision}'"))?
+            .id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MergeBaseCache;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn branches_merged_to_main_matches_per_branch_checks() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+        repo.create_and_checkout_branch("merged")?;
+        repo.create_and_checkout_branch("open")?;
+        repo.add_file_and_commit("b.txt", "b", "Unmerged work")?;
+        repo.checkout_branch("master")?;
+        repo.add_file_and_commit("c.txt", "c", "Main moves on")?;
+
+        let cache = MergeBaseCache::in_memory();
+        let branches = ["merged", "open", "gone"].map(String::from);
+        let merged = repo.branches_merged_to_main(&cache, &branches)?;
+        assert_eq!(merged.get("merged"), Some(&true));
+        assert_eq!(merged.get("open"), Some(&false));
+        assert_eq!(merged.get("gone"), None);
+        for branch in ["merged", "open"] {
+            assert_eq!(merged[branch], repo.is_branch_merged_to_main(branch)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn persisted_cache_is_reused_across_invocations() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+        repo.create_and_checkout_branch("feature")?;
+        repo.add_file_and_commit("b.txt", "b", "Feature work")?;
+        let path = temp_dir.path().join("cache.json");
+
+        let cache = MergeBaseCache::load(path.clone());
+        assert_eq!(
+            repo.ahead_behind_cached(&cache, "feature", "master")?,
+            (1, 0)
+        );
+        repo.branches_merged_to_main(&cache, &["feature".to_string()])?;
+        cache.save()?;
+
+        let reloaded = MergeBaseCache::load(path);
+        let state = reloaded.state.lock().unwrap();
+        assert_eq!(state.ahead_behind.values().collect::<Vec<_>>(), [&(1, 0)]);
+        assert_eq!(state.merged.values().collect::<Vec<_>>(), [&false]);
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+pub mod merge_cache;
+pub mod naming;
+pub mod operations;
+pub mod protected;
+pub mod stack;
+pub mod switch;
+pub mod tracking;
@@ -0,0 +1,110 @@
+use crate::git::error::GitError;
+
+/// Branch types, after the Conventional Commits ones
+pub const BRANCH_TYPES: &[&str] = &["feat", "fix", "docs", "refactor", "perf", "test", "chore"];
+
+/// Pattern new branch names follow unless `branches.pattern` says otherwise
+pub const DEFAULT_BRANCH_PATTERN: &str = "{type}/{description}";
+
+/// Longest description, in words, that [`slugify`] keeps
+const MAX_SLUG_WORDS: usize = 6;
+
+/// `text` in kebab-case: lowercase ASCII letters and digits, words joined by `-`, cut to a
+/// few words so branch names stay short
+pub fn slugify(text: &str) -> String {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(MAX_SLUG_WORDS)
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Split a task description into a branch type and the rest, when it starts with one
+/// ("fix login redirect" gives `fix` and "login redirect"); `feat` otherwise
+pub fn infer_branch_type(task: &str) -> (&'static str, &str) {
+    let task = task.trim();
+    let (first, rest) = task
+        .split_once(|c: char| c.is_whitespace() || c == ':' || c == '/')
+        .unwrap_or((task, ""));
+    let rest = rest.trim();
+    match first.to_ascii_lowercase().as_str() {
+        // "add" names the change rather than labelling it, so it stays in the description
+        "add" => ("feat", task),
+        "feature" => ("feat", rest),
+        "bug" | "bugfix" | "hotfix" => ("fix", rest),
+        "doc" => ("docs", rest),
+        "tests" => ("test", rest),
+        first => match BRANCH_TYPES.iter().find(|kind| **kind == first) {
+            Some(kind) => (kind, rest),
+            None => ("feat", task),
+        },
+    }
+}
+
+/// Fill the `{type}` and `{description}` placeholders of `pattern`, failing when the result is
+/// not a valid branch name
+pub fn render_branch_name(
+    pattern: &str,
+    kind: &str,
+    description: &str,
+) -> Result<String, GitError> {
+    let name = pattern
+        .replace("{type}", kind)
+        .replace("{description}", description);
+    if description.is_empty() || !git2::Reference::is_valid_name(&format!("refs/heads/{name}")) {
+        return Err(GitError::Other(anyhow::anyhow!(
+            "'{name}' is not a valid branch name; check the branches.pattern setting"
+        )));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer_branch_type, render_branch_name, slugify, DEFAULT_BRANCH_PATTERN};
+
+    #[test]
+    fn slugify_makes_short_kebab_case() {
+        assert_eq!(
+            slugify("Fix the OAuth login redirect!"),
+            "fix-the-oauth-login-redirect"
+        );
+        assert_eq!(
+            slugify("one two three four five six seven"),
+            "one-two-three-four-five-six"
+        );
+        assert_eq!(slugify("  --  "), "");
+    }
+
+    #[test]
+    fn branch_type_comes_from_the_first_word() {
+        assert_eq!(
+            infer_branch_type("fix: login redirect"),
+            ("fix", "login redirect")
+        );
+        assert_eq!(
+            infer_branch_type("bugfix crash on start"),
+            ("fix", "crash on start")
+        );
+        assert_eq!(
+            infer_branch_type("add dark mode"),
+            ("feat", "add dark mode")
+        );
+        assert_eq!(infer_branch_type("dark mode"), ("feat", "dark mode"));
+    }
+
+    #[test]
+    fn patterns_fill_placeholders_and_reject_invalid_names() {
+        assert_eq!(
+            render_branch_name(DEFAULT_BRANCH_PATTERN, "fix", "login-redirect").unwrap(),
+            "fix/login-redirect"
+        );
+        assert_eq!(
+            render_branch_name("lx/{type}-{description}", "feat", "dark-mode").unwrap(),
+            "lx/feat-dark-mode"
+        );
+        assert!(render_branch_name("{type}//{description}", "feat", "x").is_err());
+        assert!(render_branch_name(DEFAULT_BRANCH_PATTERN, "feat", "").is_err());
+    }
+}
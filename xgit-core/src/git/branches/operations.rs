@@ -1,10 +1,13 @@
-use anyhow::{Context, Error};
+use anyhow::Context;
 use git2::BranchType;
 
+use crate::git::error::GitError;
 use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
 
 impl GitRepo {
-    pub fn get_all_branches(&self) -> Result<Vec<String>, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_all_branches(&self) -> Result<Vec<String>, GitError> {
         let mut branches = Vec::new();
 
         let branch_iter = self.repo().branches(Some(BranchType::Local))?;
@@ -20,7 +23,8 @@ impl GitRepo {
     }
 
     /// Create a new branch from the current HEAD and switch to it
-    pub fn create_and_checkout_branch(&self, branch_name: &str) -> Result<&Self, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn create_and_checkout_branch(&self, branch_name: &str) -> Result<&Self, GitError> {
         match self.repo().head() {
             Ok(head) => {
                 // Repository has commits, create branch from HEAD
@@ -35,6 +39,10 @@ impl GitRepo {
                     .branch(branch_name, &commit, false)
                     .context("Failed to create branch")?;
 
+                self.emit(GitEvent::BranchCreated {
+                    name: branch_name.to_string(),
+                });
+
                 // Switch to the new branch
                 self.repo()
                     .set_head(&format!("refs/heads/{branch_name}"))
@@ -48,11 +56,15 @@ impl GitRepo {
                     .context("Failed to set HEAD to new branch")?;
             }
         }
+        self.emit(GitEvent::BranchSwitched {
+            branch: branch_name.to_string(),
+        });
 
         Ok(self)
     }
 
-    pub fn checkout_branch(&self, branch_name: &str) -> Result<&Self, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn checkout_branch(&self, branch_name: &str) -> Result<&Self, GitError> {
         // Get the branch reference
         let branch_ref = format!("refs/heads/{branch_name}");
         let obj = self.repo().revparse_single(&branch_ref)?;
@@ -64,11 +76,15 @@ impl GitRepo {
 
         // Set HEAD to point to the branch
         self.repo().set_head(&branch_ref)?;
+        self.emit(GitEvent::BranchSwitched {
+            branch: branch_name.to_string(),
+        });
 
         Ok(self)
     }
 
-    pub fn get_head_symbolic_target(&self) -> Result<String, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_head_symbolic_target(&self) -> Result<String, GitError> {
         let head_ref = self
             .repo()
             .find_reference("HEAD")
@@ -76,12 +92,13 @@ impl GitRepo {
 
         match head_ref.symbolic_target() {
             Some(target) => Ok(target.to_string()),
-            None => Err(anyhow::anyhow!("HEAD is not a symbolic reference")),
+            None => Err(anyhow::anyhow!("HEAD is not a symbolic reference").into()),
         }
     }
 
     /// Get the current branch name
-    pub fn get_current_branch(&self) -> Result<String, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_current_branch(&self) -> Result<String, GitError> {
         let head_target = self
             .get_head_symbolic_target()
             .context("Failed to get current branch from HEAD")?;
@@ -94,8 +111,29 @@ impl GitRepo {
         Ok(branch_name.to_string())
     }
 
+    /// The local trunk branch: the configured
+    /// [`default_branch`](crate::config::XgitConfig::default_branch) when it exists, then
+    /// `main`, then `master`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn trunk_branch(&self) -> Result<String, GitError> {
+        let configured = self.xgit_config()?.default_branch().map(str::to_string);
+        for candidate in configured.as_deref().into_iter().chain(["main", "master"]) {
+            if self
+                .repo()
+                .find_branch(candidate, BranchType::Local)
+                .is_ok()
+            {
+                return Ok(candidate.to_string());
+            }
+        }
+        Err(GitError::NotFound(anyhow::anyhow!(
+            "Neither main nor master branch found"
+        )))
+    }
+
     /// Check if a specific branch is merged to main
-    pub fn is_branch_merged_to_main(&self, branch_name: &str) -> Result<bool, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn is_branch_merged_to_main(&self, branch_name: &str) -> Result<bool, GitError> {
         let branch_ref = self
             .repo()
             .find_reference(&format!("refs/heads/{branch_name}"))
@@ -104,9 +142,8 @@ impl GitRepo {
 
         let main_ref = self
             .repo()
-            .find_reference("refs/heads/main")
-            .or_else(|_| self.repo().find_reference("refs/heads/master"))
-            .context("Failed to find main/master branch")?;
+            .find_reference(&format!("refs/heads/{}", self.trunk_branch()?))
+            .context("Failed to find the trunk branch")?;
         let main_oid = main_ref.target().context("Failed to get main target")?;
 
         let merge_base = self
@@ -117,21 +154,66 @@ impl GitRepo {
         Ok(merge_base == branch_oid)
     }
 
-    /// Delete a local branch
-    pub fn delete_branch(&self, branch_name: &str) -> Result<(), Error> {
-        use anyhow::Context;
+    /// Delete a local branch, like `git branch -d`: refuses unless the branch is merged into
+    /// HEAD or into its upstream
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn delete_branch(&self, branch_name: &str) -> Result<(), GitError> {
+        self.remove_branch(branch_name, false)
+    }
+
+    /// Delete a local branch whatever its merge status, like `git branch -D`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn delete_branch_forced(&self, branch_name: &str) -> Result<(), GitError> {
+        self.remove_branch(branch_name, true)
+    }
 
+    fn remove_branch(&self, branch_name: &str, force: bool) -> Result<(), GitError> {
         let mut branch = self
             .repo()
-            .find_branch(branch_name, git2::BranchType::Local)
+            .find_branch(branch_name, BranchType::Local)
             .context(format!("Failed to find branch '{branch_name}'"))?;
 
+        if !force && !self.is_fully_merged(&branch)? {
+            return Err(anyhow::anyhow!(
+                "Branch '{branch_name}' is not fully merged into HEAD or its upstream"
+            )
+            .into());
+        }
+
         branch
             .delete()
             .context(format!("Failed to delete branch '{branch_name}'"))?;
+        self.emit(GitEvent::BranchDeleted {
+            name: branch_name.to_string(),
+        });
 
         Ok(())
     }
+
+    /// Whether `branch`'s tip is reachable from HEAD or from the branch's upstream
+    fn is_fully_merged(&self, branch: &git2::Branch) -> Result<bool, GitError> {
+        let tip = branch
+            .get()
+            .peel_to_commit()
+            .context("Failed to get branch commit")?
+            .id();
+        let head = self.repo().head().ok().and_then(|head| head.target());
+        let upstream = branch
+            .upstream()
+            .ok()
+            .and_then(|upstream| upstream.get().target());
+        for target in head.into_iter().chain(upstream) {
+            if target == tip
+                || self
+                    .repo()
+                    .graph_descendant_of(target, tip)
+                    .context("Failed to compare commits")?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +243,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn delete_branch_refuses_unmerged_branches_unless_forced(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?
+            .create_and_checkout_branch("merged")?
+            .checkout_branch("master")?
+            .create_and_checkout_branch("unmerged")?
+            .add_file_and_commit("a.txt", "a", "Unmerged work")?
+            .checkout_branch("master")?;
+
+        repo.delete_branch("merged")?;
+        assert!(repo.delete_branch("unmerged").is_err());
+        assert!(repo.get_all_branches()?.contains(&"unmerged".to_string()));
+
+        repo.delete_branch_forced("unmerged")?;
+        assert_eq!(repo.get_all_branches()?, vec!["master"]);
+        Ok(())
+    }
+
     #[test]
     fn create_branch_works_when_no_commit() -> Result<(), Box<dyn std::error::Error>> {
         let (_temp_dir, repo) = create_test_repo();
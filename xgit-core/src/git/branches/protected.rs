@@ -0,0 +1,96 @@
+use crate::git::error::GitError;
+use crate::git::repository::config::wildmatch;
+use crate::git::repository::core::GitRepo;
+
+/// Branches that are protected whatever else is configured
+pub const DEFAULT_PROTECTED_BRANCHES: &[&str] = &["main", "master", "develop"];
+
+/// Branch patterns that cleanup commands must never delete
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedBranches {
+    patterns: Vec<String>,
+}
+
+impl ProtectedBranches {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self { patterns }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether `branch` matches a pattern: `*` matches within a path segment, `**` across
+    /// segments and `?` any single character except `/`, so `release/*` protects
+    /// `release/1.0` but not `release/1.0/hotfix`
+    pub fn is_protected(&self, branch: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| wildmatch(pattern, branch))
+    }
+}
+
+impl GitRepo {
+    /// The protected branches: those of [`XgitConfig`](crate::config::XgitConfig), which start
+    /// from [`DEFAULT_PROTECTED_BRANCHES`], plus every value of the `xgit.protectedBranches`
+    /// git config (each may list several patterns separated by commas or whitespace)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn protected_branches(&self) -> Result<ProtectedBranches, GitError> {
+        let mut patterns = self.xgit_config()?.protected_branches().to_vec();
+        for (name, value) in self.get_config_entries("xgit.")? {
+            if !name.eq_ignore_ascii_case("xgit.protectedBranches") {
+                continue;
+            }
+            for pattern in value
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|pattern| !pattern.is_empty())
+            {
+                if !patterns.iter().any(|existing| existing == pattern) {
+                    patterns.push(pattern.to_string());
+                }
+            }
+        }
+        Ok(ProtectedBranches::new(patterns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::REPO_CONFIG_FILE;
+    use crate::test_utils::create_test_repo;
+
+    #[test]
+    fn protected_branches_combine_defaults_git_config_and_repo_file(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let protected = repo.protected_branches()?;
+        assert!(protected.is_protected("main"));
+        assert!(!protected.is_protected("release/1.0"));
+
+        let mut config = repo.repo().config()?;
+        config.set_str("xgit.protectedBranches", "release/*, staging")?;
+        std::fs::write(
+            repo.path().join(REPO_CONFIG_FILE),
+            "[branches]\nprotected = [\"hotfix/**\", \"main\"]\n",
+        )?;
+
+        let protected = repo.protected_branches()?;
+        assert_eq!(
+            protected.patterns(),
+            [
+                "main",
+                "master",
+                "develop",
+                "hotfix/**",
+                "release/*",
+                "staging"
+            ]
+        );
+        assert!(protected.is_protected("release/1.0"));
+        assert!(!protected.is_protected("release/1.0/rc"));
+        assert!(protected.is_protected("hotfix/2024/login"));
+        assert!(protected.is_protected("staging"));
+        assert!(!protected.is_protected("feature/staging"));
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+//! Stacked branches: chains of branches where each one builds on the one below it, so a large
+//! change can be reviewed as a series of small pull requests.
+//!
+//! A stacked branch records the branch it builds on as `branch.<name>.xgitParent` in the
+//! repository's config, and the parent commit it was last based on as
+//! `branch.<name>.xgitParentBase`. When the parent is rewritten (amended, rebased or
+//! restacked itself), the recorded base tells which of the branch's commits are its own, so
+//! restacking replays only those onto the parent's new tip.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Context;
+use git2::{BranchType, Config, ConfigLevel, Oid};
+
+use crate::git::error::GitError;
+use crate::git::rebase::operations::RebaseOutcome;
+use crate::git::repository::core::GitRepo;
+
+const PARENT_KEY: &str = "xgitParent";
+const PARENT_BASE_KEY: &str = "xgitParentBase";
+
+/// A branch in a stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackBranch {
+    pub name: String,
+    /// The branch it builds on
+    pub parent: String,
+    /// 1 for a branch built directly on the stack's base, 2 for its children and so on
+    pub depth: usize,
+    /// Whether the parent has moved on since the branch was last rebased onto it
+    pub needs_restack: bool,
+}
+
+/// Branches built on one base branch, usually the trunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack {
+    pub base: String,
+    /// Every parent before its children, children in name order
+    pub branches: Vec<StackBranch>,
+}
+
+impl Stack {
+    pub fn contains(&self, branch: &str) -> bool {
+        self.base == branch || self.branches.iter().any(|b| b.name == branch)
+    }
+
+    pub fn get(&self, branch: &str) -> Option<&StackBranch> {
+        self.branches.iter().find(|b| b.name == branch)
+    }
+
+    /// Branches recorded as building directly on `branch`
+    pub fn children(&self, branch: &str) -> Vec<&StackBranch> {
+        self.branches
+            .iter()
+            .filter(|b| b.parent == branch)
+            .collect()
+    }
+}
+
+impl GitRepo {
+    /// Create `name` at HEAD on top of the current branch, switch to it and record the current
+    /// branch as its parent
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn create_stacked_branch(&self, name: &str) -> Result<(), GitError> {
+        let parent = self.get_current_branch()?;
+        if self.repo().find_branch(name, BranchType::Local).is_ok() {
+            return Err(anyhow::anyhow!("Branch '{name}' already exists").into());
+        }
+        self.create_and_checkout_branch(name)?;
+        self.set_stack_parent(name, &parent)
+    }
+
+    /// Record `branch` as building on `parent` as it is now
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn set_stack_parent(&self, branch: &str, parent: &str) -> Result<(), GitError> {
+        let base = self.branch_tip(parent)?;
+        let mut config = self.local_config()?;
+        config
+            .set_str(&stack_key(branch, PARENT_KEY), parent)
+            .context(format!("Failed to record the parent of '{branch}'"))?;
+        config
+            .set_str(&stack_key(branch, PARENT_BASE_KEY), &base.to_string())
+            .context(format!("Failed to record the parent of '{branch}'"))?;
+        Ok(())
+    }
+
+    /// The branch `branch` is recorded as building on, if it is stacked
+    pub fn stack_parent(&self, branch: &str) -> Result<Option<String>, GitError> {
+        self.local_config_string(&stack_key(branch, PARENT_KEY))
+    }
+
+    /// Whether `branch`'s parent has commits `branch` is not based on, or no longer exists
+    pub fn needs_restack(&self, branch: &str) -> Result<bool, GitError> {
+        let Some(parent) = self.stack_parent(branch)? else {
+            return Ok(false);
+        };
+        let Ok(parent_tip) = self.branch_tip(&parent) else {
+            return Ok(true);
+        };
+        let tip = self.branch_tip(branch)?;
+        Ok(tip != parent_tip
+            && !self
+                .repo()
+                .graph_descendant_of(tip, parent_tip)
+                .context(format!("Failed to compare '{branch}' with '{parent}'"))?)
+    }
+
+    /// Every stack in the repository, by base name. Branches that no longer exist are left
+    /// out; a branch whose parent was deleted becomes the base of its own stack.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stacks(&self) -> Result<Vec<Stack>, GitError> {
+        let local: HashSet<String> = self.get_all_branches()?.into_iter().collect();
+        let mut children: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for branch in &local {
+            if let Some(parent) = self.stack_parent(branch)? {
+                children.entry(parent).or_default().push(branch.clone());
+            }
+        }
+        for names in children.values_mut() {
+            names.sort();
+        }
+
+        let stacked = |name: &String| {
+            local.contains(name)
+                && self
+                    .stack_parent(name)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |parent| local.contains(&parent))
+        };
+        let mut stacks = Vec::new();
+        let mut seen = HashSet::new();
+        for base in children.keys().filter(|name| !stacked(name)) {
+            let mut branches = Vec::new();
+            self.collect_stack(base, 1, &children, &mut seen, &mut branches)?;
+            stacks.push(Stack {
+                base: base.clone(),
+                branches,
+            });
+        }
+        Ok(stacks)
+    }
+
+    /// The stack `branch` is the base of or a member of
+    pub fn stack_containing(&self, branch: &str) -> Result<Option<Stack>, GitError> {
+        Ok(self
+            .stacks()?
+            .into_iter()
+            .find(|stack| stack.contains(branch)))
+    }
+
+    /// Rebase `branch` onto its parent's current tip, replaying only the commits made on
+    /// `branch` since it was last based on the parent, and check it out. A branch whose parent
+    /// was deleted (typically after its pull request was merged) moves onto the trunk, leaving
+    /// the parent's commits behind.
+    ///
+    /// Stops with [`GitError::Conflict`] like [`rebase`](GitRepo::rebase); once the rebase is
+    /// continued, restacking again records the new base.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn restack_branch(&self, branch: &str) -> Result<RebaseOutcome, GitError> {
+        let Some(mut parent) = self.stack_parent(branch)? else {
+            return Err(GitError::NotFound(anyhow::anyhow!(
+                "'{branch}' is not part of a stack"
+            )));
+        };
+        if self.is_rebase_in_progress() {
+            return Err(anyhow::anyhow!(
+                "A rebase is already in progress; continue, skip or abort it first"
+            )
+            .into());
+        }
+        if self.has_uncommitted_changes()? {
+            return Err(GitError::DirtyWorktree(format!(
+                "Uncommitted changes would be overwritten by restacking '{branch}'. Commit or stash them first."
+            )));
+        }
+        if self.branch_tip(&parent).is_err() {
+            let trunk = self.trunk_branch()?;
+            let base = self.local_config_string(&stack_key(branch, PARENT_BASE_KEY))?;
+            let mut config = self.local_config()?;
+            config
+                .set_str(&stack_key(branch, PARENT_KEY), &trunk)
+                .context(format!("Failed to record the parent of '{branch}'"))?;
+            if base.is_none() {
+                config
+                    .set_str(
+                        &stack_key(branch, PARENT_BASE_KEY),
+                        &self.branch_tip(&trunk)?.to_string(),
+                    )
+                    .context(format!("Failed to record the parent of '{branch}'"))?;
+            }
+            parent = trunk;
+        }
+
+        self.checkout_branch(branch)?;
+        if !self.needs_restack(branch)? {
+            self.set_stack_parent(branch, &parent)?;
+            return Ok(RebaseOutcome::UpToDate);
+        }
+
+        // Only the commits after the recorded base are the branch's own; without a usable
+        // base, fall back to everything not already in the parent
+        let tip = self.branch_tip(branch)?;
+        let upstream = self
+            .local_config_string(&stack_key(branch, PARENT_BASE_KEY))?
+            .and_then(|base| Oid::from_str(&base).ok())
+            .filter(|base| self.repo().graph_descendant_of(tip, *base).unwrap_or(false))
+            .map(|base| base.to_string())
+            .unwrap_or_else(|| parent.clone());
+        let outcome = self.rebase_onto(&parent, &upstream)?;
+        self.set_stack_parent(branch, &parent)?;
+        Ok(outcome)
+    }
+
+    fn collect_stack(
+        &self,
+        parent: &str,
+        depth: usize,
+        children: &BTreeMap<String, Vec<String>>,
+        seen: &mut HashSet<String>,
+        branches: &mut Vec<StackBranch>,
+    ) -> Result<(), GitError> {
+        for name in children.get(parent).into_iter().flatten() {
+            // A parent recorded by hand could close a loop
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            branches.push(StackBranch {
+                name: name.clone(),
+                parent: parent.to_string(),
+                depth,
+                needs_restack: self.needs_restack(name)?,
+            });
+            self.collect_stack(name, depth + 1, children, seen, branches)?;
+        }
+        Ok(())
+    }
+
+    fn branch_tip(&self, branch: &str) -> Result<Oid, GitError> {
+        self.repo()
+            .find_branch(branch, BranchType::Local)
+            .ok()
+            .and_then(|branch| branch.get().target())
+            .ok_or_else(|| GitError::NotFound(anyhow::anyhow!("Branch '{branch}' not found")))
+    }
+
+    /// The repository's own `.git/config`, where stack metadata lives; global and system
+    /// config are left out so a stray `branch.<name>.xgitParent` there cannot join a stack
+    fn local_config(&self) -> Result<Config, GitError> {
+        self.repo()
+            .config()
+            .and_then(|config| config.open_level(ConfigLevel::Local))
+            .context("Failed to open the repository's git config")
+            .map_err(GitError::from)
+    }
+
+    /// `name` from [`local_config`](Self::local_config)
+    fn local_config_string(&self, name: &str) -> Result<Option<String>, GitError> {
+        match self.local_config()?.get_string(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(anyhow::Error::new(err)
+                .context(format!("Failed to read '{name}'"))
+                .into()),
+        }
+    }
+}
+
+fn stack_key(branch: &str, key: &str) -> String {
+    format!("branch.{branch}.{key}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::rebase::operations::RebaseOutcome;
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    #[test]
+    fn stacked_branches_form_a_tree_on_their_base() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?;
+        repo.create_stacked_branch("api")?;
+        repo.add_file_and_commit("api.txt", "api\n", "Add api")?;
+        repo.create_stacked_branch("ui")?;
+        repo.add_file_and_commit("ui.txt", "ui\n", "Add ui")?;
+        repo.checkout_branch("api")?;
+        repo.create_stacked_branch("docs")?;
+
+        assert_eq!(repo.stack_parent("ui")?.as_deref(), Some("api"));
+        assert_eq!(repo.stack_parent("master")?, None);
+        assert!(repo.create_stacked_branch("ui").is_err());
+
+        let stacks = repo.stacks()?;
+        assert_eq!(stacks.len(), 1);
+        let stack = &stacks[0];
+        assert_eq!(stack.base, "master");
+        let names: Vec<_> = stack
+            .branches
+            .iter()
+            .map(|b| (b.name.as_str(), b.depth))
+            .collect();
+        assert_eq!(names, [("api", 1), ("docs", 2), ("ui", 2)]);
+        assert_eq!(stack.children("api").len(), 2);
+        assert!(stack.branches.iter().all(|b| !b.needs_restack));
+        assert_eq!(repo.stack_containing("master")?.as_ref(), Some(stack));
+        Ok(())
+    }
+
+    #[test]
+    fn restack_moves_children_onto_a_rewritten_parent() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?;
+        repo.create_stacked_branch("api")?;
+        repo.add_file_and_commit("api.txt", "api\n", "Add api")?;
+        repo.create_stacked_branch("ui")?;
+        repo.add_file_and_commit("ui.txt", "ui\n", "Add ui")?;
+
+        // Rewrite api's commit, as an amend after review would
+        repo.checkout_branch("master")?;
+        repo.repo()
+            .branch("api", &repo.repo().head()?.peel_to_commit()?, true)?;
+        repo.checkout_branch("api")?;
+        repo.add_file_and_commit("api.txt", "api v2\n", "Add api (reviewed)")?;
+        assert!(repo.needs_restack("ui")?);
+
+        assert_eq!(repo.restack_branch("api")?, RebaseOutcome::UpToDate);
+        let outcome = repo.restack_branch("ui")?;
+        assert!(matches!(
+            outcome,
+            RebaseOutcome::Completed { applied: 1, .. }
+        ));
+        repo.assert_current_branch("ui");
+        // The old api commit is left behind instead of conflicting with the new one
+        repo.assert_commit_messages(&["Add ui", "Add api (reviewed)", "Initial commit"]);
+        assert!(!repo.needs_restack("ui")?);
+        Ok(())
+    }
+
+    #[test]
+    fn restack_moves_orphans_onto_the_trunk() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?;
+        repo.create_stacked_branch("api")?;
+        repo.add_file_and_commit("api.txt", "api\n", "Add api")?;
+        repo.create_stacked_branch("ui")?;
+        repo.add_file_and_commit("ui.txt", "ui\n", "Add ui")?;
+
+        // api was squash-merged into master and deleted
+        repo.checkout_branch("master")?;
+        repo.add_file_and_commit("api.txt", "api\n", "Add api (#1)")?;
+        repo.delete_branch_forced("api")?;
+        assert!(repo.needs_restack("ui")?);
+        assert_eq!(repo.stacks()?[0].base, "api");
+
+        repo.restack_branch("ui")?;
+        assert_eq!(repo.stack_parent("ui")?.as_deref(), Some("master"));
+        repo.assert_commit_messages(&["Add ui", "Add api (#1)", "Initial commit"]);
+        assert_eq!(repo.stacks()?[0].base, "master");
+        Ok(())
+    }
+}
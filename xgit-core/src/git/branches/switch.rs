@@ -0,0 +1,357 @@
+use anyhow::Context;
+use git2::{build::CheckoutBuilder, BranchType, StashFlags, StatusOptions};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+
+/// What to do with uncommitted changes when switching branches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DirtyWorktreePolicy {
+    /// Refuse to switch while tracked files have uncommitted changes
+    #[default]
+    Refuse,
+    /// Discard uncommitted changes to tracked files
+    Force,
+    /// Stash changes before switching and re-apply them afterwards
+    Autostash,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SwitchOptions {
+    /// Create the branch (`git switch -c`)
+    pub create: bool,
+    /// Commit or branch to create the new branch from; defaults to HEAD
+    pub start_point: Option<String>,
+    pub dirty: DirtyWorktreePolicy,
+}
+
+/// Result of a branch switch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwitchOutcome {
+    pub branch: String,
+    pub created: bool,
+    /// Remote-tracking branch the new branch was set up to track, e.g. `origin/feature`
+    pub upstream: Option<String>,
+    pub stashed: bool,
+    /// Re-applying the autostash conflicted; the changes are kept in the stash
+    pub stash_conflicted: bool,
+}
+
+impl GitRepo {
+    /// Switch to `branch`, optionally creating it.
+    ///
+    /// Like `git switch`, an unknown branch name that exists on exactly one remote is created
+    /// tracking that remote branch, and `create` with a remote-tracking start point sets up
+    /// tracking too.
+    #[tracing::instrument(level = "debug", skip(self, options))]
+    pub fn switch_branch(
+        &self,
+        branch: &str,
+        options: &SwitchOptions,
+    ) -> Result<SwitchOutcome, GitError> {
+        self.require_worktree("switch branches")?;
+        let local_exists = self.repo().find_branch(branch, BranchType::Local).is_ok();
+        if options.create && local_exists {
+            return Err(anyhow::anyhow!("A branch named '{branch}' already exists").into());
+        }
+
+        let start_point = match (&options.start_point, options.create || local_exists) {
+            (Some(start_point), _) => Some(start_point.clone()),
+            (None, true) => None,
+            (None, false) => Some(self.find_unique_remote_branch(branch)?),
+        };
+        let create = options.create || !local_exists;
+        let upstream = start_point
+            .as_deref()
+            .filter(|start| create && self.is_remote_tracking_branch(start))
+            .map(str::to_string);
+
+        let target = match &start_point {
+            Some(start) => self
+                .repo()
+                .revparse_single(start)
+                .context(format!("Failed to resolve '{start}'"))?
+                .peel_to_commit()
+                .context(format!("'{start}' does not point to a commit"))?,
+            None if create => self
+                .repo()
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .context("Cannot create a branch before the first commit")?,
+            None => self
+                .repo()
+                .find_branch(branch, BranchType::Local)?
+                .get()
+                .peel_to_commit()
+                .context(format!("Failed to get commit for branch '{branch}'"))?,
+        };
+
+        // Changes are simply carried over when the tree does not change (e.g. `-c` from HEAD)
+        let same_tree = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_tree())
+            .map_or(false, |tree| tree.id() == target.tree_id());
+        let dirty = !same_tree && self.has_uncommitted_changes()?;
+        if dirty && options.dirty == DirtyWorktreePolicy::Refuse {
+            return Err(GitError::DirtyWorktree(format!(
+                "Uncommitted changes would be affected by switching to '{branch}'. Commit them, or use --autostash or --force."
+            )));
+        }
+
+        let stashed = dirty && options.dirty == DirtyWorktreePolicy::Autostash;
+        if stashed {
+            let signature = self
+                .create_signature()
+                .context("Failed to create signature")?;
+            self.open_mut()?
+                .stash_save(
+                    &signature,
+                    &format!("xg autostash before switching to {branch}"),
+                    Some(StashFlags::DEFAULT),
+                )
+                .context("Failed to stash uncommitted changes")?;
+        }
+
+        if create {
+            let mut new_branch = self
+                .repo()
+                .branch(branch, &target, false)
+                .context(format!("Failed to create branch '{branch}'"))?;
+            if let Some(upstream) = &upstream {
+                new_branch.set_upstream(Some(upstream)).context(format!(
+                    "Failed to set upstream of '{branch}' to '{upstream}'"
+                ))?;
+            }
+        }
+
+        let mut checkout = CheckoutBuilder::new();
+        if options.dirty == DirtyWorktreePolicy::Force {
+            checkout.force();
+        } else {
+            checkout.safe();
+        }
+        let switched = self
+            .repo()
+            .checkout_tree(target.as_object(), Some(&mut checkout))
+            .context(format!("Failed to check out '{branch}'"))
+            .and_then(|_| {
+                self.repo()
+                    .set_head(&format!("refs/heads/{branch}"))
+                    .context(format!("Failed to set HEAD to '{branch}'"))
+            });
+        if let Err(err) = switched {
+            // Give the user their changes back on the branch they started from
+            if stashed {
+                let _ = self
+                    .open_mut()
+                    .and_then(|mut repo| Ok(repo.stash_pop(0, None)?));
+            }
+            return Err(err.into());
+        }
+
+        if create {
+            self.emit(GitEvent::BranchCreated {
+                name: branch.to_string(),
+            });
+        }
+        self.emit(GitEvent::BranchSwitched {
+            branch: branch.to_string(),
+        });
+
+        let stash_conflicted = stashed
+            && self
+                .open_mut()
+                .and_then(|mut repo| Ok(repo.stash_pop(0, None)?))
+                .is_err();
+
+        Ok(SwitchOutcome {
+            branch: branch.to_string(),
+            created: create,
+            upstream,
+            stashed,
+            stash_conflicted,
+        })
+    }
+
+    /// Whether tracked files have staged or unstaged changes (untracked files do not count)
+    pub(crate) fn has_uncommitted_changes(&self) -> Result<bool, GitError> {
+        let mut options = StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+        let statuses = self
+            .repo()
+            .statuses(Some(&mut options))
+            .context("Failed to read repository status")?;
+        Ok(!statuses.is_empty())
+    }
+
+    fn is_remote_tracking_branch(&self, name: &str) -> bool {
+        self.repo().find_branch(name, BranchType::Remote).is_ok()
+    }
+
+    /// Find `<remote>/<branch>` for a branch that exists on exactly one remote
+    fn find_unique_remote_branch(&self, branch: &str) -> Result<String, GitError> {
+        let mut candidates = Vec::new();
+        for remote_branch in self.repo().branches(Some(BranchType::Remote))? {
+            let (remote_branch, _) = remote_branch?;
+            if let Some(name) = remote_branch.name()? {
+                if name.split_once('/').map(|(_, rest)| rest) == Some(branch) {
+                    candidates.push(name.to_string());
+                }
+            }
+        }
+
+        match candidates.len() {
+            0 => Err(GitError::NotFound(anyhow::anyhow!(
+                "Branch '{branch}' not found. Use -c to create it."
+            ))),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(anyhow::anyhow!(
+                "Branch '{branch}' exists on several remotes ({}); use -c {branch} <remote>/{branch}",
+                candidates.join(", ")
+            ).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DirtyWorktreePolicy, SwitchOptions};
+    use crate::git::GitError;
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    fn add_remote_branch(
+        repo: &crate::git::GitRepo,
+        name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let head = repo.repo().head()?.peel_to_commit()?;
+        repo.repo().reference(
+            &format!("refs/remotes/origin/{name}"),
+            head.id(),
+            false,
+            "test remote branch",
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn switch_branch_creates_and_switches() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+
+        let outcome = repo.switch_branch(
+            "feature",
+            &SwitchOptions {
+                create: true,
+                ..SwitchOptions::default()
+            },
+        )?;
+        assert!(outcome.created);
+        assert_eq!(outcome.upstream, None);
+        repo.assert_current_branch("feature");
+
+        repo.switch_branch("master", &SwitchOptions::default())?;
+        repo.assert_current_branch("master");
+        assert!(repo
+            .switch_branch(
+                "feature",
+                &SwitchOptions {
+                    create: true,
+                    ..SwitchOptions::default()
+                }
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn switch_branch_tracks_remote_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+        repo.repo()
+            .remote("origin", "https://example.com/repo.git")?;
+        add_remote_branch(&repo, "remote-only")?;
+        add_remote_branch(&repo, "explicit")?;
+
+        // Guessed from the only remote that has it
+        let outcome = repo.switch_branch("remote-only", &SwitchOptions::default())?;
+        assert!(outcome.created);
+        assert_eq!(outcome.upstream.as_deref(), Some("origin/remote-only"));
+        assert_eq!(
+            repo.get_remote_tracking_info("remote-only")?,
+            "origin/remote-only"
+        );
+
+        // `switch -c explicit origin/explicit`
+        let outcome = repo.switch_branch(
+            "explicit",
+            &SwitchOptions {
+                create: true,
+                start_point: Some("origin/explicit".to_string()),
+                ..SwitchOptions::default()
+            },
+        )?;
+        assert_eq!(outcome.upstream.as_deref(), Some("origin/explicit"));
+        repo.assert_current_branch("explicit");
+
+        assert!(repo
+            .switch_branch("missing", &SwitchOptions::default())
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn switch_branch_refuses_dirty_worktree_unless_autostash(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("b.txt", "b\n", "Feature commit")?
+            .checkout_branch("master")?;
+        repo.append_to_file("a.txt", "local edit\n")?;
+
+        let err = repo
+            .switch_branch("feature", &SwitchOptions::default())
+            .unwrap_err();
+        assert!(matches!(err, GitError::DirtyWorktree(_)), "got {err:?}");
+        repo.assert_current_branch("master");
+
+        let outcome = repo.switch_branch(
+            "feature",
+            &SwitchOptions {
+                dirty: DirtyWorktreePolicy::Autostash,
+                ..SwitchOptions::default()
+            },
+        )?;
+        assert!(outcome.stashed);
+        assert!(!outcome.stash_conflicted);
+        repo.assert_current_branch("feature");
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("a.txt"))?,
+            "a\nlocal edit\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn switch_branch_force_discards_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("b.txt", "b\n", "Feature commit")?
+            .checkout_branch("master")?;
+        repo.append_to_file("a.txt", "local edit\n")?;
+
+        repo.switch_branch(
+            "feature",
+            &SwitchOptions {
+                dirty: DirtyWorktreePolicy::Force,
+                ..SwitchOptions::default()
+            },
+        )?;
+        repo.assert_current_branch("feature");
+        assert_eq!(std::fs::read_to_string(repo.path().join("a.txt"))?, "a\n");
+        Ok(())
+    }
+}
@@ -1,11 +1,12 @@
-use anyhow::{Context, Error};
-use git2::BranchType;
+use anyhow::Context;
 
+use crate::git::error::GitError;
 use crate::git::repository::core::GitRepo;
 
 impl GitRepo {
     /// Get remote tracking info for a specific branch
-    pub fn get_remote_tracking_info(&self, branch: &str) -> Result<String, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_remote_tracking_info(&self, branch: &str) -> Result<String, GitError> {
         let branch_ref = format!("refs/heads/{branch}");
 
         // Try to get the upstream branch
@@ -26,16 +27,64 @@ impl GitRepo {
         Ok(tracking_branch.to_string())
     }
 
-    /// Check if all commits in the given branch are already in main/master
-    pub fn is_branch_merged_into_main(&self, branch: &str) -> Result<bool, Error> {
-        // Try to find main or master branch
-        let main_branch = if self.repo().find_branch("main", BranchType::Local).is_ok() {
-            "main"
-        } else if self.repo().find_branch("master", BranchType::Local).is_ok() {
-            "master"
-        } else {
-            return Err(anyhow::anyhow!("Neither main nor master branch found"));
+    /// The remote and the branch on it that `branch` tracks, e.g. `("origin", "feature")`, or
+    /// `None` when it has no upstream on a remote
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_upstream_remote_branch(
+        &self,
+        branch: &str,
+    ) -> Result<Option<(String, String)>, GitError> {
+        let branch_ref = format!("refs/heads/{branch}");
+        let (Ok(remote), Ok(merge)) = (
+            self.repo().branch_upstream_remote(&branch_ref),
+            self.repo().branch_upstream_merge(&branch_ref),
+        ) else {
+            return Ok(None);
+        };
+        let (Some(remote), Some(merge)) = (remote.as_str(), merge.as_str()) else {
+            return Err(anyhow::anyhow!("Upstream of '{branch}' is not valid UTF-8").into());
         };
+        // A local upstream is configured with remote "."
+        if remote == "." {
+            return Ok(None);
+        }
+        Ok(Some((
+            remote.to_string(),
+            merge
+                .strip_prefix("refs/heads/")
+                .unwrap_or(merge)
+                .to_string(),
+        )))
+    }
+
+    /// Count commits on `branch` not in `upstream` (ahead) and on `upstream` not in `branch` (behind).
+    ///
+    /// `upstream` is any revision, typically a remote-tracking branch such as `origin/main`.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn ahead_behind(&self, branch: &str, upstream: &str) -> Result<(usize, usize), GitError> {
+        let local = self
+            .repo()
+            .revparse_single(&format!("refs/heads/{branch}"))
+            .context(format!("Failed to find branch '{branch}'"))?
+            .peel_to_commit()
+            .context("Failed to get branch commit")?;
+        let upstream_commit = self
+            .repo()
+            .revparse_single(upstream)
+            .context(format!("Failed to find upstream '{upstream}'"))?
+            .peel_to_commit()
+            .context("Failed to get upstream commit")?;
+
+        self.repo()
+            .graph_ahead_behind(local.id(), upstream_commit.id())
+            .context(format!("Failed to compare '{branch}' with '{upstream}'"))
+            .map_err(GitError::from)
+    }
+
+    /// Check if all commits in the given branch are already in main/master
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn is_branch_merged_into_main(&self, branch: &str) -> Result<bool, GitError> {
+        let main_branch = self.trunk_branch()?;
 
         // Get the commit for the branch
         let branch_ref = format!("refs/heads/{branch}");
@@ -121,6 +170,43 @@ mod tests {
         assert!(master_result.is_err());
     }
 
+    #[test]
+    fn get_upstream_remote_branch_splits_remote_and_branch(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_remote_dir, remote_repo) = create_test_bare_repo();
+        let (_local_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?
+            .add_local_remote("origin", &remote_repo)?;
+        repo.create_and_checkout_branch("feature")?;
+        assert_eq!(repo.get_upstream_remote_branch("feature")?, None);
+
+        repo.push("origin", "feature")?;
+        repo.repo()
+            .find_branch("feature", git2::BranchType::Local)?
+            .set_upstream(Some("origin/feature"))?;
+        assert_eq!(
+            repo.get_upstream_remote_branch("feature")?,
+            Some(("origin".to_string(), "feature".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ahead_behind_counts_diverged_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("a.txt", "a", "Feature 1")?
+            .add_file_and_commit("b.txt", "b", "Feature 2")?
+            .checkout_branch("master")?
+            .add_file_and_commit("c.txt", "c", "Master 1")?;
+
+        assert_eq!(repo.ahead_behind("feature", "master")?, (2, 1));
+        assert_eq!(repo.ahead_behind("master", "feature")?, (1, 2));
+        assert!(repo.ahead_behind("feature", "missing").is_err());
+        Ok(())
+    }
+
     #[test]
     fn is_branch_merged_into_main_works() {
         let (_remote_dir, remote_repo) = create_test_bare_repo();
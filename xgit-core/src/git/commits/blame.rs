@@ -0,0 +1,126 @@
+use anyhow::Context;
+use git2::BlameOptions;
+use std::path::Path;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// One line of a blamed file and the commit that last changed it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// 1-based line number in the blamed version of the file
+    pub line: usize,
+    pub content: String,
+    pub commit_id: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author time of the commit in seconds since the Unix epoch
+    pub time: i64,
+    /// Author timezone offset in minutes
+    pub offset_minutes: i32,
+    /// The file's path in that commit when it has been renamed since
+    pub original_path: Option<String>,
+}
+
+impl GitRepo {
+    /// Every line of `path` (relative to the repository root) as of `rev`, default HEAD, with
+    /// the commit that last changed it, like `git blame`.
+    ///
+    /// Authors are mapped through `.mailmap`. Binary files are an error.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn blame(&self, path: &str, rev: Option<&str>) -> Result<Vec<BlameLine>, GitError> {
+        let rev = rev.unwrap_or("HEAD");
+        let commit = self
+            .repo()
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .context(format!("Failed to resolve '{rev}'"))?;
+        let blob = commit
+            .tree()
+            .and_then(|tree| tree.get_path(Path::new(path)))
+            .and_then(|entry| entry.to_object(self.repo()))
+            .and_then(|object| object.peel_to_blob())
+            .map_err(|_| GitError::NotFound(anyhow::anyhow!("'{path}' does not exist in {rev}")))?;
+        if blob.is_binary() {
+            return Err(anyhow::anyhow!("'{path}' is a binary file").into());
+        }
+
+        let mut options = BlameOptions::new();
+        options.newest_commit(commit.id()).use_mailmap(true);
+        let blame = self
+            .repo()
+            .blame_file(Path::new(path), Some(&mut options))
+            .context(format!("Failed to blame '{path}'"))?;
+
+        let content = String::from_utf8_lossy(blob.content());
+        let mut lines = Vec::new();
+        for (index, text) in content.lines().enumerate() {
+            let line = index + 1;
+            let hunk = blame
+                .get_line(line)
+                .ok_or_else(|| anyhow::anyhow!("No blame for line {line} of '{path}'"))?;
+            let signature = hunk.final_signature();
+            let original_path = hunk
+                .path()
+                .map(|original| original.to_string_lossy().into_owned())
+                .filter(|original| original != path);
+            lines.push(BlameLine {
+                line,
+                content: text.to_string(),
+                commit_id: hunk.final_commit_id().to_string(),
+                author_name: String::from_utf8_lossy(signature.name_bytes()).into_owned(),
+                author_email: String::from_utf8_lossy(signature.email_bytes()).into_owned(),
+                time: signature.when().seconds(),
+                offset_minutes: signature.when().offset_minutes(),
+                original_path,
+            });
+        }
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::GitError;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn blame_attributes_each_line_to_its_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("notes.txt", "one\ntwo\n", "Add notes")?;
+        let first = repo.list_commits()?[0].hash.clone();
+        repo.set_user_config("Other Person", "other@example.com")?;
+        repo.add_file_and_commit("notes.txt", "one\n2\nthree\n", "Edit notes")?;
+        let second = repo.list_commits()?[0].hash.clone();
+
+        let lines = repo.blame("notes.txt", None)?;
+        let summary: Vec<(usize, &str, &str, &str)> = lines
+            .iter()
+            .map(|line| {
+                (
+                    line.line,
+                    line.content.as_str(),
+                    line.commit_id.as_str(),
+                    line.author_name.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "one", first.as_str(), "Test User"),
+                (2, "2", second.as_str(), "Other Person"),
+                (3, "three", second.as_str(), "Other Person"),
+            ]
+        );
+
+        let old = repo.blame("notes.txt", Some(&first))?;
+        assert_eq!(old.len(), 2);
+        assert!(old.iter().all(|line| line.commit_id == first));
+        assert!(matches!(
+            repo.blame("missing.txt", None),
+            Err(GitError::NotFound(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,597 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+use git2::Oid;
+
+use crate::git::commits::date::parse_git_date;
+use crate::git::error::GitError;
+use crate::git::hooks::runner::MessageSource;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// A name and email to record as the author or committer of a commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+/// A point in time as git stores it: seconds since the Unix epoch plus a timezone offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitTime {
+    pub seconds: i64,
+    pub offset_minutes: i32,
+}
+
+impl CommitTime {
+    /// Parse a date in any format `GIT_AUTHOR_DATE` accepts: `<seconds> <offset>`,
+    /// `@<seconds>`, RFC 2822 or ISO 8601
+    pub fn parse(value: &str) -> Option<Self> {
+        parse_git_date(value)
+    }
+}
+
+/// An identity and time used for every commit a [`GitRepo`] creates; see
+/// [`GitRepo::set_fixed_signature`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedSignature {
+    pub identity: Identity,
+    pub time: CommitTime,
+}
+
+/// Everything `git commit` lets you control that [`GitRepo::commit_with`] supports.
+///
+/// Identities fall back to `GIT_AUTHOR_NAME`/`GIT_COMMITTER_NAME` (and the matching email and
+/// date variables), then to `user.name`/`user.email`, and dates to the current time, so
+/// `CommitOptions::new(message)` behaves like a plain `git commit -m --no-verify` that also
+/// skips `prepare-commit-msg` and `post-commit`; set `run_hooks` for the full `git commit`.
+#[derive(Debug, Clone, Default)]
+pub struct CommitOptions {
+    pub message: String,
+    pub author: Option<Identity>,
+    pub committer: Option<Identity>,
+    pub author_date: Option<CommitTime>,
+    pub committer_date: Option<CommitTime>,
+    /// Create the commit even when its tree matches the parent's (`--allow-empty`)
+    pub allow_empty: bool,
+    /// Run the repository's `pre-commit`, `prepare-commit-msg`, `commit-msg` and
+    /// `post-commit` hooks, as `git commit` does. Off by default, so the message committed is
+    /// the one given.
+    pub run_hooks: bool,
+    /// With `run_hooks`, skip the `pre-commit` and `commit-msg` hooks (`--no-verify`)
+    pub no_verify: bool,
+    /// Add a `Signed-off-by` trailer for the committer (`--signoff`)
+    pub signoff: bool,
+    /// Extra `Token: value` trailers appended to the message (`--trailer`)
+    pub trailers: Vec<(String, String)>,
+    /// Sign the commit with gpg (`-S`)
+    pub gpg_sign: bool,
+    /// Key to sign with; defaults to `user.signingKey`, then the committer identity
+    pub signing_key: Option<String>,
+}
+
+impl CommitOptions {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl GitRepo {
+    /// Commit the index to the current branch (or detached HEAD)
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn commit(&self, message: &str) -> Result<String, GitError> {
+        self.commit_with(&CommitOptions::new(message))
+    }
+
+    /// Commit the index to the current branch (or detached HEAD) with full control over
+    /// identities, dates, trailers, hooks and signing
+    #[tracing::instrument(level = "debug", skip(self, options))]
+    pub fn commit_with(&self, options: &CommitOptions) -> Result<String, GitError> {
+        self.require_worktree("commit the index")?;
+        let verify = options.run_hooks && !options.no_verify;
+        if verify {
+            self.run_hook("pre-commit", &[])?;
+        }
+
+        // Commit what is on disk once any concurrent git process has finished with the index
+        self.wait_for_index_lock()?;
+        let mut index = self
+            .repo()
+            .index()
+            .context("Failed to get repository index")?;
+        // A pre-commit hook may have re-staged files
+        index.read(false).context("Failed to read index")?;
+        let tree_id = index
+            .write_tree()
+            .context("Failed to write tree from index")?;
+        let tree = self
+            .repo()
+            .find_tree(tree_id)
+            .context("Failed to find tree")?;
+
+        let parent = match self.repo().head() {
+            Ok(head) => Some(
+                head.peel_to_commit()
+                    .context("Failed to find parent commit")?,
+            ),
+            Err(_) => None, // First commit, no parent
+        };
+        if !options.allow_empty {
+            let unchanged = match &parent {
+                Some(parent) => parent.tree_id() == tree_id,
+                None => tree.is_empty(),
+            };
+            if unchanged {
+                return Err(anyhow::anyhow!("Nothing to commit").into());
+            }
+        }
+
+        let author =
+            self.commit_signature(Role::Author, options.author.as_ref(), options.author_date)?;
+        let committer = self.commit_signature(
+            Role::Committer,
+            options.committer.as_ref(),
+            options.committer_date,
+        )?;
+
+        let mut trailers = options.trailers.clone();
+        if options.signoff {
+            trailers.push((
+                "Signed-off-by".to_string(),
+                format!(
+                    "{} <{}>",
+                    committer.name().unwrap_or_default(),
+                    committer.email().unwrap_or_default()
+                ),
+            ));
+        }
+        let mut message = append_trailers(&options.message, &trailers);
+        if options.run_hooks {
+            message = self.run_message_hooks(&message, &MessageSource::Message, verify)?;
+        }
+
+        let parents: Vec<_> = parent.iter().collect();
+        let commit_id = if options.gpg_sign {
+            let buffer = self
+                .repo()
+                .commit_create_buffer(&author, &committer, &message, &tree, &parents)
+                .context("Failed to create commit buffer")?;
+            let buffer = std::str::from_utf8(&buffer).context("Commit buffer is not UTF-8")?;
+            let key = match &options.signing_key {
+                Some(key) => key.clone(),
+                None => match self.get_config_string("user.signingKey")? {
+                    Some(key) => key,
+                    None => committer.to_string(),
+                },
+            };
+            let signature = self.gpg_sign(buffer, &key)?;
+            let commit_id = self
+                .repo()
+                .commit_signed(buffer, &signature, Some("gpgsig"))
+                .context("Failed to create signed commit")?;
+            self.advance_head(commit_id, &message, parent.is_none())?;
+            commit_id
+        } else {
+            self.repo()
+                .commit(Some("HEAD"), &author, &committer, &message, &tree, &parents)
+                .context("Failed to create commit")?
+        };
+
+        if options.run_hooks {
+            self.run_post_commit_hook();
+        }
+        let id = commit_id.to_string();
+        self.emit(GitEvent::CommitCreated {
+            id: id.clone(),
+            summary: message.lines().next().unwrap_or_default().to_string(),
+        });
+        Ok(id)
+    }
+
+    /// Replace the tip commit with one holding the current index, like `git commit --amend`.
+    ///
+    /// The author, parents and (when `message` is `None`) the message are kept; the committer
+    /// and commit date are updated. With `run_hooks`, the hooks run as for
+    /// [`CommitOptions::run_hooks`]. Returns the new commit's id.
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn commit_amend(&self, message: Option<&str>, run_hooks: bool) -> Result<String, GitError> {
+        self.require_worktree("amend a commit")?;
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("No commit to amend")?;
+        if run_hooks {
+            self.run_hook("pre-commit", &[])?;
+        }
+
+        self.wait_for_index_lock()?;
+        let mut index = self
+            .repo()
+            .index()
+            .context("Failed to get repository index")?;
+        index.read(false).context("Failed to read index")?;
+        let tree_id = index
+            .write_tree()
+            .context("Failed to write tree from index")?;
+        let tree = self
+            .repo()
+            .find_tree(tree_id)
+            .context("Failed to find tree")?;
+
+        let (message, source) = match message {
+            Some(message) => (message.to_string(), MessageSource::Message),
+            None => (
+                String::from_utf8_lossy(head.message_bytes()).into_owned(),
+                MessageSource::Commit(head.id().to_string()),
+            ),
+        };
+        let message = if run_hooks {
+            self.run_message_hooks(&message, &source, true)?
+        } else {
+            message
+        };
+        let committer = self.commit_signature(Role::Committer, None, None)?;
+        let commit_id = head
+            .amend(
+                Some("HEAD"),
+                None,
+                Some(&committer),
+                None,
+                Some(&message),
+                Some(&tree),
+            )
+            .context("Failed to amend commit")?;
+        if run_hooks {
+            self.run_post_commit_hook();
+        }
+
+        let id = commit_id.to_string();
+        self.emit(GitEvent::CommitCreated {
+            id: id.clone(),
+            summary: message.lines().next().unwrap_or_default().to_string(),
+        });
+        Ok(id)
+    }
+
+    /// The message template `commit.template` names, without its `#` comment lines, or `None`
+    /// when none is configured or it is empty. A relative path is taken from the worktree root.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn commit_template(&self) -> Result<Option<String>, GitError> {
+        let Some(path) = self.get_config_string("commit.template")? else {
+            return Ok(None);
+        };
+        let path = match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+            (Some(rest), Some(home)) => std::path::PathBuf::from(home).join(rest),
+            _ => self.path().join(&path),
+        };
+        let contents = std::fs::read_to_string(&path).context(format!(
+            "Failed to read commit template '{}'",
+            path.display()
+        ))?;
+        let template: Vec<&str> = contents
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        let template = template.join("\n").trim().to_string();
+        Ok((!template.is_empty()).then(|| template))
+    }
+
+    /// Point the current branch (or detached HEAD) at a commit created without `update_ref`
+    fn advance_head(&self, commit_id: Oid, message: &str, initial: bool) -> Result<(), GitError> {
+        let summary = message.lines().next().unwrap_or_default();
+        let reflog = if initial {
+            format!("commit (initial): {summary}")
+        } else {
+            format!("commit: {summary}")
+        };
+        match self.get_head_symbolic_target() {
+            Ok(branch_ref) => {
+                self.repo()
+                    .reference(&branch_ref, commit_id, true, &reflog)
+                    .context(format!("Failed to update '{branch_ref}'"))?;
+            }
+            Err(_) => self
+                .repo()
+                .set_head_detached(commit_id)
+                .context("Failed to update HEAD")?,
+        }
+        Ok(())
+    }
+
+    fn gpg_sign(&self, buffer: &str, key: &str) -> Result<String, GitError> {
+        let program = self
+            .get_config_string("gpg.program")?
+            .unwrap_or_else(|| "gpg".to_string());
+        let mut child = Command::new(&program)
+            .args(["--status-fd=2", "-bsau", key])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context(format!("Failed to run '{program}'"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(buffer.as_bytes())
+                .context("Failed to send commit to gpg")?;
+        }
+        let output = child.wait_with_output().context("Failed to wait for gpg")?;
+
+        let status = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() || !status.contains("[GNUPG:] SIG_CREATED ") {
+            return Err(anyhow::anyhow!("gpg failed to sign the commit: {}", status.trim()).into());
+        }
+        String::from_utf8(output.stdout)
+            .context("gpg produced a non-UTF-8 signature")
+            .map_err(GitError::from)
+    }
+}
+
+/// Append `Token: value` trailers, joining an existing trailer block when the message ends
+/// with one and skipping trailers that are already present
+fn append_trailers(message: &str, trailers: &[(String, String)]) -> String {
+    let body = message.trim_end();
+    let mut result = body.to_string();
+    let mut added = false;
+
+    for (token, value) in trailers {
+        let line = format!("{token}: {value}");
+        if result.lines().any(|existing| existing == line) {
+            continue;
+        }
+        if !added {
+            result.push_str(if ends_with_trailer_block(body) {
+                "\n"
+            } else {
+                "\n\n"
+            });
+            added = true;
+        } else {
+            result.push('\n');
+        }
+        result.push_str(&line);
+    }
+
+    if added || message.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+fn ends_with_trailer_block(body: &str) -> bool {
+    let Some((_, last_paragraph)) = body.rsplit_once("\n\n") else {
+        return false;
+    };
+    last_paragraph.lines().all(|line| {
+        line.split_once(": ").map_or(false, |(token, _)| {
+            !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_trailers, CommitOptions, CommitTime, Identity};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    fn trailer(token: &str, value: &str) -> (String, String) {
+        (token.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn append_trailers_starts_or_extends_the_trailer_block() {
+        assert_eq!(
+            append_trailers("Fix bug", &[trailer("Refs", "#12")]),
+            "Fix bug\n\nRefs: #12\n"
+        );
+        assert_eq!(
+            append_trailers(
+                "Fix bug\n\nLonger body.\n\nRefs: #12\n",
+                &[trailer("Signed-off-by", "A <a@x>"), trailer("Refs", "#12")]
+            ),
+            "Fix bug\n\nLonger body.\n\nRefs: #12\nSigned-off-by: A <a@x>\n"
+        );
+        assert_eq!(append_trailers("Fix bug", &[]), "Fix bug");
+    }
+
+    #[test]
+    fn commit_with_overrides_identities_dates_and_trailers(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+
+        let options = CommitOptions {
+            author: Some(Identity {
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+            }),
+            author_date: Some(CommitTime {
+                seconds: 1_000_000_000,
+                offset_minutes: 60,
+            }),
+            committer_date: Some(CommitTime {
+                seconds: 1_000_000_100,
+                offset_minutes: 0,
+            }),
+            signoff: true,
+            trailers: vec![trailer("Refs", "#7")],
+            ..CommitOptions::new("Import history")
+        };
+        let sha = repo.commit_with(&options)?;
+
+        let commit = repo.repo().find_commit(git2::Oid::from_str(&sha)?)?;
+        assert_eq!(commit.author().name(), Some("Ada"));
+        assert_eq!(commit.author().when().seconds(), 1_000_000_000);
+        assert_eq!(commit.author().when().offset_minutes(), 60);
+        assert_eq!(commit.committer().name(), Some("Test User"));
+        assert_eq!(commit.committer().when().seconds(), 1_000_000_100);
+        assert_eq!(
+            commit.message(),
+            Some("Import history\n\nRefs: #7\nSigned-off-by: Test User <test@example.com>\n")
+        );
+
+        // Nothing is staged on top of the previous commit
+        assert!(repo.commit_with(&options).is_err());
+        let again = repo.commit_with(&CommitOptions {
+            allow_empty: true,
+            ..options.clone()
+        })?;
+        assert_ne!(again, sha);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_refuses_empty_commits_unless_allowed() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert!(repo.commit("Nothing").is_err());
+
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        assert!(repo.commit("Nothing").is_err());
+        repo.commit_with(&CommitOptions {
+            allow_empty: true,
+            ..CommitOptions::new("Empty")
+        })?;
+        assert_eq!(repo.list_commits()?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_amend_keeps_the_author_and_replaces_the_tip() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = create_test_repo();
+        assert!(repo.commit_amend(None, false).is_err());
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+        let original = repo.commit_with(&CommitOptions {
+            author: Some(Identity {
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+            }),
+            author_date: Some(CommitTime {
+                seconds: 1_000_000_000,
+                offset_minutes: 0,
+            }),
+            ..CommitOptions::new("Add a\n\nWith a body.")
+        })?;
+
+        repo.add_file("b.txt", "b\n")?;
+        repo.add(&["b.txt"])?;
+        let combined = repo.diff_stats(&repo.get_amend_diff()?)?;
+        assert_eq!(combined.files_changed, 2);
+        let amended = repo.commit_amend(None, false)?;
+        assert_ne!(amended, original);
+        let commits = repo.list_commits()?;
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].hash, amended);
+        let commit = repo.repo().find_commit(git2::Oid::from_str(&amended)?)?;
+        assert_eq!(commit.author().name(), Some("Ada"));
+        assert_eq!(commit.author().when().seconds(), 1_000_000_000);
+        assert_eq!(commit.committer().name(), Some("Test User"));
+        assert_eq!(commit.message(), Some("Add a\n\nWith a body."));
+        assert!(commit.tree()?.get_name("b.txt").is_some());
+
+        let renamed = repo.commit_amend(Some("Add a and b"), false)?;
+        assert_eq!(repo.get_commit_message(&renamed)?, "Add a and b");
+        assert_eq!(repo.list_commits()?.len(), 1);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn install_script(path: &std::path::Path, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, script).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commit_runs_hooks_only_when_asked() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let hooks = repo.git_dir().join("hooks");
+        std::fs::create_dir_all(&hooks)?;
+        install_script(
+            &hooks.join("commit-msg"),
+            "#!/bin/sh\necho 'Checked-by: hook' >> \"$1\"\n",
+        );
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+        let sha = repo.commit("Add a")?;
+        assert_eq!(repo.get_commit_message(&sha)?, "Add a");
+
+        repo.add_file("b.txt", "b\n")?;
+        repo.add(&["b.txt"])?;
+        let sha = repo.commit_with(&CommitOptions {
+            run_hooks: true,
+            ..CommitOptions::new("Add b")
+        })?;
+        assert_eq!(repo.get_commit_message(&sha)?, "Add b\nChecked-by: hook\n");
+
+        install_script(&hooks.join("pre-commit"), "#!/bin/sh\nexit 1\n");
+        repo.add_file("c.txt", "c\n")?;
+        repo.add(&["c.txt"])?;
+        assert!(repo
+            .commit_with(&CommitOptions {
+                run_hooks: true,
+                ..CommitOptions::new("Add c")
+            })
+            .is_err());
+        let sha = repo.commit_with(&CommitOptions {
+            run_hooks: true,
+            no_verify: true,
+            ..CommitOptions::new("Add c")
+        })?;
+        assert_eq!(repo.get_commit_message(&sha)?, "Add c");
+        Ok(())
+    }
+
+    #[test]
+    fn commit_template_drops_comment_lines() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert_eq!(repo.commit_template()?, None);
+
+        repo.add_file(
+            ".gitmessage",
+            "# Summary in the imperative\nfeat: \n\n# Why?\n",
+        )?;
+        repo.repo()
+            .config()?
+            .set_str("commit.template", ".gitmessage")?;
+        assert_eq!(repo.commit_template()?.as_deref(), Some("feat:"));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commit_signs_with_the_configured_gpg_program() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo();
+        let fake_gpg = temp_dir.path().join("fake-gpg");
+        install_script(
+            &fake_gpg,
+            "#!/bin/sh\ncat > /dev/null\necho '[GNUPG:] SIG_CREATED D' >&2\nprintf -- '-----BEGIN PGP SIGNATURE-----\\nfake\\n-----END PGP SIGNATURE-----\\n'\n",
+        );
+        repo.repo()
+            .config()?
+            .set_str("gpg.program", fake_gpg.to_str().unwrap())?;
+
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+        let sha = repo.commit_with(&CommitOptions {
+            gpg_sign: true,
+            signing_key: Some("ABCD".to_string()),
+            ..CommitOptions::new("Signed commit")
+        })?;
+
+        assert_eq!(repo.list_commits()?[0].hash, sha);
+        let (signature, _) = repo
+            .repo()
+            .extract_signature(&git2::Oid::from_str(&sha)?, None)?;
+        assert!(std::str::from_utf8(&signature)?.contains("fake"));
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::Context;
+
+use crate::git::error::GitError;
+use crate::git::rebase::operations::conflicted_paths;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// Result of [`GitRepo::cherry_pick`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CherryPickOutcome {
+    /// The commit applied cleanly and `id` was committed on top of HEAD
+    Picked {
+        id: String,
+        original: String,
+        summary: String,
+    },
+    /// The commit conflicts with HEAD. The working tree holds conflict markers in `paths` and
+    /// `CHERRY_PICK_HEAD` is set, so `git commit` finishes the pick and
+    /// `git cherry-pick --abort` undoes it.
+    Conflicted {
+        original: String,
+        summary: String,
+        paths: Vec<String>,
+    },
+}
+
+impl GitRepo {
+    /// Apply the changes of `commit_ish` on top of HEAD and commit them with the original
+    /// author and message.
+    ///
+    /// A conflict is reported as [`CherryPickOutcome::Conflicted`] rather than an error.
+    /// Merge commits and commits whose changes are already on HEAD are refused.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn cherry_pick(&self, commit_ish: &str) -> Result<CherryPickOutcome, GitError> {
+        self.require_worktree("cherry-pick")?;
+        if self.has_uncommitted_changes()? {
+            return Err(GitError::DirtyWorktree(
+                "Uncommitted changes would be overwritten by the cherry-pick. Commit or stash them first."
+                    .to_string(),
+            ));
+        }
+        let id = self.resolve_commit(commit_ish)?;
+        let commit = self
+            .repo()
+            .find_commit(id)
+            .context(format!("Failed to find commit {id}"))?;
+        let original = id.to_string();
+        let summary = commit.summary().unwrap_or_default().to_string();
+        if commit.parent_count() > 1 {
+            return Err(
+                anyhow::anyhow!("Cannot cherry-pick merge commit {}", &original[..7]).into(),
+            );
+        }
+
+        self.wait_for_index_lock()?;
+        self.repo()
+            .cherrypick(&commit, None)
+            .context(format!("Failed to cherry-pick {}", &original[..7]))?;
+
+        let mut index = self.repo().index().context("Failed to read the index")?;
+        if index.has_conflicts() {
+            return Ok(CherryPickOutcome::Conflicted {
+                original,
+                summary,
+                paths: conflicted_paths(&index),
+            });
+        }
+
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Failed to read HEAD")?;
+        let tree_id = index
+            .write_tree()
+            .context("Failed to write the picked tree")?;
+        if tree_id == head.tree_id() {
+            self.repo()
+                .cleanup_state()
+                .context("Failed to clear the cherry-pick state")?;
+            return Err(
+                anyhow::anyhow!("{} {summary} is already applied on HEAD", &original[..7]).into(),
+            );
+        }
+        let tree = self
+            .repo()
+            .find_tree(tree_id)
+            .context("Failed to find the picked tree")?;
+
+        let committer = self.commit_signature(Role::Committer, None, None)?;
+        let message = commit.message().unwrap_or_default();
+        let new_id = self
+            .repo()
+            .commit(
+                Some("HEAD"),
+                &commit.author(),
+                &committer,
+                message,
+                &tree,
+                &[&head],
+            )
+            .context("Failed to create the picked commit")?;
+        self.repo()
+            .cleanup_state()
+            .context("Failed to clear the cherry-pick state")?;
+
+        let new_id = new_id.to_string();
+        self.emit(GitEvent::CommitCreated {
+            id: new_id.clone(),
+            summary: summary.clone(),
+        });
+        Ok(CherryPickOutcome::Picked {
+            id: new_id,
+            original,
+            summary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CherryPickOutcome;
+    use crate::git::{GitError, GitRepo};
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    /// `feature` adds one file and edits `shared.txt`; master edits `shared.txt` differently
+    fn repo_with_feature() -> Result<(assert_fs::TempDir, GitRepo), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("shared.txt", "base\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?;
+        repo.set_user_config("Feature Author", "feature@example.com")?;
+        repo.add_file_and_commit("feature.txt", "feature\n", "Add feature")?
+            .add_file_and_commit("shared.txt", "feature\n", "Edit shared on feature")?;
+        repo.set_user_config("Test User", "test@example.com")?;
+        repo.checkout_branch("master")?.add_file_and_commit(
+            "shared.txt",
+            "master\n",
+            "Edit shared on master",
+        )?;
+        Ok((temp_dir, repo))
+    }
+
+    #[test]
+    fn cherry_pick_keeps_original_author_and_message() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = repo_with_feature()?;
+
+        let outcome = repo.cherry_pick("feature~1")?;
+        let CherryPickOutcome::Picked { id, summary, .. } = outcome else {
+            panic!("expected a clean pick, got {outcome:?}");
+        };
+        assert_eq!(summary, "Add feature");
+        repo.assert_commit_messages(&["Add feature", "Edit shared on master", "Initial commit"]);
+        repo.assert_file_exists("feature.txt");
+
+        let commit = repo.repo().find_commit(git2::Oid::from_str(&id)?)?;
+        assert_eq!(commit.author().name(), Some("Feature Author"));
+        assert_eq!(commit.committer().name(), Some("Test User"));
+        assert_eq!(repo.repo().state(), git2::RepositoryState::Clean);
+
+        let err = repo.cherry_pick("feature~1").unwrap_err();
+        assert!(err.to_string().contains("already applied"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_cherry_pick_reports_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = repo_with_feature()?;
+
+        let outcome = repo.cherry_pick("feature")?;
+        let CherryPickOutcome::Conflicted { summary, paths, .. } = outcome else {
+            panic!("expected a conflict, got {outcome:?}");
+        };
+        assert_eq!(summary, "Edit shared on feature");
+        assert_eq!(paths, vec!["shared.txt".to_string()]);
+        repo.assert_conflicted_files(&["shared.txt"]);
+        assert_eq!(repo.repo().state(), git2::RepositoryState::CherryPick);
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_refuses_dirty_worktree() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = repo_with_feature()?;
+        std::fs::write(repo.path().join("shared.txt"), "edited\n")?;
+        assert!(matches!(
+            repo.cherry_pick("feature~1"),
+            Err(GitError::DirtyWorktree(_))
+        ));
+        Ok(())
+    }
+}
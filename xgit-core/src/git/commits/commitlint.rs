@@ -0,0 +1,292 @@
+use super::conventional::{
+    is_generated_message, ConventionalCommit, ConventionalCommitError, COMMIT_TYPES,
+};
+
+/// Header length `xg commit` and `xg lint-commits` allow unless `commitlint.max-subject-length`
+/// says otherwise
+pub const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+/// Verbs commit descriptions commonly start with, used to spot `Added` or `Fixes` where the
+/// imperative `Add` or `Fix` is expected
+const IMPERATIVE_VERBS: &[&str] = &[
+    "add",
+    "allow",
+    "avoid",
+    "bump",
+    "change",
+    "clean",
+    "configure",
+    "create",
+    "delete",
+    "deprecate",
+    "disable",
+    "document",
+    "drop",
+    "enable",
+    "ensure",
+    "extract",
+    "fix",
+    "handle",
+    "implement",
+    "improve",
+    "introduce",
+    "make",
+    "merge",
+    "move",
+    "optimize",
+    "prevent",
+    "refactor",
+    "reduce",
+    "remove",
+    "rename",
+    "replace",
+    "restore",
+    "revert",
+    "rewrite",
+    "run",
+    "set",
+    "show",
+    "simplify",
+    "skip",
+    "split",
+    "support",
+    "switch",
+    "test",
+    "update",
+    "upgrade",
+    "use",
+    "validate",
+];
+
+/// What a commit message is checked against; see the `[commitlint]` table in
+/// [`XgitConfig`](crate::config::XgitConfig)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintRules {
+    /// Types the header may start with
+    pub types: Vec<String>,
+    /// Scopes the header may name; any scope when empty
+    pub scopes: Vec<String>,
+    /// Whether the header must name a scope
+    pub require_scope: bool,
+    /// Longest header allowed, in characters
+    pub max_subject_length: usize,
+    /// Whether to warn about descriptions that do not start with an imperative verb
+    pub imperative: bool,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        Self {
+            types: COMMIT_TYPES.iter().map(|kind| kind.to_string()).collect(),
+            scopes: Vec::new(),
+            require_scope: false,
+            max_subject_length: DEFAULT_MAX_SUBJECT_LENGTH,
+            imperative: true,
+        }
+    }
+}
+
+/// Whether a problem rejects the message or is only reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Error,
+    /// Heuristic checks, which can be wrong
+    Warning,
+}
+
+/// One way a message breaks the rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintProblem {
+    /// The rule broken: `header`, `type`, `scope`, `subject-length` or `imperative`
+    pub rule: &'static str,
+    pub level: LintLevel,
+    pub message: String,
+}
+
+impl LintProblem {
+    fn error(rule: &'static str, message: String) -> Self {
+        Self {
+            rule,
+            level: LintLevel::Error,
+            message,
+        }
+    }
+}
+
+/// Check `message` against `rules`. Messages git writes itself (merges, reverts, `fixup!`
+/// commits) and empty ones pass as they are.
+pub fn lint_message(message: &str, rules: &LintRules) -> Vec<LintProblem> {
+    let message = message.trim();
+    if message.is_empty() || is_generated_message(message) {
+        return Vec::new();
+    }
+
+    let mut problems = Vec::new();
+    let header = message.lines().next().unwrap_or_default().trim_end();
+    let length = header.chars().count();
+    if length > rules.max_subject_length {
+        problems.push(LintProblem::error(
+            "subject-length",
+            format!(
+                "header is {length} characters; keep it to {}",
+                rules.max_subject_length
+            ),
+        ));
+    }
+
+    let commit = match ConventionalCommit::parse_with_types(message, &rules.types) {
+        Ok(commit) => commit,
+        Err(ConventionalCommitError::UnknownType(kind)) => {
+            problems.push(LintProblem::error(
+                "type",
+                format!(
+                    "unknown type '{kind}' (expected one of {})",
+                    rules.types.join(", ")
+                ),
+            ));
+            return problems;
+        }
+        Err(err) => {
+            problems.push(LintProblem::error("header", err.to_string()));
+            return problems;
+        }
+    };
+
+    match &commit.scope {
+        None if rules.require_scope => problems.push(LintProblem::error(
+            "scope",
+            format!(
+                "a scope is required, as in '{}(<scope>): {}'",
+                commit.commit_type, commit.description
+            ),
+        )),
+        Some(scope) if !rules.scopes.is_empty() && !rules.scopes.contains(scope) => {
+            problems.push(LintProblem::error(
+                "scope",
+                format!(
+                    "unknown scope '{scope}' (expected one of {})",
+                    rules.scopes.join(", ")
+                ),
+            ))
+        }
+        _ => {}
+    }
+
+    if rules.imperative {
+        let word = commit
+            .description
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        if let Some(verb) = imperative_form(word) {
+            problems.push(LintProblem {
+                rule: "imperative",
+                level: LintLevel::Warning,
+                message: format!("use the imperative mood: '{verb}' rather than '{word}'"),
+            });
+        }
+    }
+    problems
+}
+
+/// The imperative form of `word` when it is a known verb in another form, as `Add` for
+/// `Added` or `Adds`, keeping a leading capital
+fn imperative_form(word: &str) -> Option<String> {
+    let lower = word.to_ascii_lowercase();
+    if !lower.chars().all(|c| c.is_ascii_alphabetic()) || IMPERATIVE_VERBS.contains(&&*lower) {
+        return None;
+    }
+
+    let is_verb = |stem: &str| IMPERATIVE_VERBS.contains(&stem);
+    let verb = ["ing", "ied", "ies", "ed", "es", "s", "d"]
+        .iter()
+        .filter_map(|suffix| lower.strip_suffix(suffix))
+        .find_map(|stem| {
+            let mut candidates = vec![stem.to_string(), format!("{stem}e"), format!("{stem}y")];
+            // Doubled final consonant, as in `dropped` or `running`
+            let bytes = stem.as_bytes();
+            if bytes.len() > 2 && bytes[bytes.len() - 1] == bytes[bytes.len() - 2] {
+                candidates.push(stem[..stem.len() - 1].to_string());
+            }
+            candidates.into_iter().find(|candidate| is_verb(candidate))
+        })?;
+
+    Some(if word.starts_with(|c: char| c.is_ascii_uppercase()) {
+        verb[..1].to_ascii_uppercase() + &verb[1..]
+    } else {
+        verb
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{imperative_form, lint_message, LintLevel, LintRules};
+
+    fn rules_broken(message: &str, rules: &LintRules) -> Vec<&'static str> {
+        lint_message(message, rules)
+            .iter()
+            .map(|problem| problem.rule)
+            .collect()
+    }
+
+    #[test]
+    fn lint_message_checks_type_scope_and_length() {
+        let rules = LintRules::default();
+        assert!(rules_broken("feat(cli): Add lint-commits", &rules).is_empty());
+        assert!(rules_broken("Merge branch 'main' into topic", &rules).is_empty());
+        assert_eq!(rules_broken("Add lint-commits", &rules), ["header"]);
+        assert_eq!(rules_broken("feature: Add lint-commits", &rules), ["type"]);
+        assert_eq!(
+            rules_broken(&format!("feat: {}", "x".repeat(70)), &rules),
+            ["subject-length"]
+        );
+
+        let rules = LintRules {
+            types: vec!["feature".to_string()],
+            scopes: vec!["cli".to_string(), "core".to_string()],
+            require_scope: true,
+            ..LintRules::default()
+        };
+        assert!(rules_broken("feature(core): Add config", &rules).is_empty());
+        assert_eq!(rules_broken("feat(core): Add config", &rules), ["type"]);
+        assert_eq!(rules_broken("feature: Add config", &rules), ["scope"]);
+        assert_eq!(rules_broken("feature(ui): Add config", &rules), ["scope"]);
+    }
+
+    #[test]
+    fn lint_message_warns_about_non_imperative_descriptions() {
+        let problems = lint_message("fix: Fixed empty diffs", &LintRules::default());
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].level, LintLevel::Warning);
+        assert_eq!(
+            problems[0].message,
+            "use the imperative mood: 'Fix' rather than 'Fixed'"
+        );
+
+        let rules = LintRules {
+            imperative: false,
+            ..LintRules::default()
+        };
+        assert!(lint_message("fix: Fixed empty diffs", &rules).is_empty());
+    }
+
+    #[test]
+    fn imperative_form_recognises_common_verb_forms() {
+        let forms = [
+            ("Added", Some("Add")),
+            ("adds", Some("add")),
+            ("updating", Some("update")),
+            ("Updates", Some("Update")),
+            ("simplified", Some("simplify")),
+            ("dropped", Some("drop")),
+            ("running", Some("run")),
+            ("Add", None),
+            ("tests", Some("test")),
+            ("Initial", None),
+            ("embed", None),
+        ];
+        for (word, expected) in forms {
+            assert_eq!(imperative_form(word).as_deref(), expected, "{word}");
+        }
+    }
+}
@@ -0,0 +1,176 @@
+/// Types a Conventional Commits header may start with
+pub const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// A [Conventional Commits](https://www.conventionalcommits.org) message:
+/// `<type>[(scope)][!]: <description>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    /// Marked with `!` in the header or a `BREAKING CHANGE:` footer
+    pub breaking: bool,
+    pub description: String,
+    /// Everything after the header's blank line, if any
+    pub body: Option<String>,
+}
+
+/// Why a message is not a Conventional Commit
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConventionalCommitError {
+    #[error("commit message is empty")]
+    Empty,
+    #[error("header '{0}' does not match '<type>[(scope)][!]: <description>'")]
+    InvalidHeader(String),
+    #[error("unknown type '{0}' (expected one of {types})", types = COMMIT_TYPES.join(", "))]
+    UnknownType(String),
+    #[error("description after '<type>:' is empty")]
+    EmptyDescription,
+    #[error("the header must be followed by a blank line")]
+    MissingBlankLine,
+}
+
+impl ConventionalCommit {
+    /// Parse a commit message; surrounding blank lines are ignored
+    pub fn parse(message: &str) -> Result<Self, ConventionalCommitError> {
+        Self::parse_with_types(message, COMMIT_TYPES)
+    }
+
+    /// Parse a commit message whose type must be one of `types` rather than [`COMMIT_TYPES`]
+    pub fn parse_with_types<T: AsRef<str>>(
+        message: &str,
+        types: &[T],
+    ) -> Result<Self, ConventionalCommitError> {
+        let message = message.trim();
+        let mut lines = message.lines();
+        let header = lines.next().ok_or(ConventionalCommitError::Empty)?.trim();
+        let invalid = || ConventionalCommitError::InvalidHeader(header.to_string());
+
+        let (prefix, description) = header.split_once(':').ok_or_else(invalid)?;
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (commit_type, scope) = match prefix.split_once('(') {
+            Some((commit_type, scope)) => {
+                let scope = scope.strip_suffix(')').ok_or_else(invalid)?;
+                if scope.trim().is_empty() || scope.contains(['(', ')']) {
+                    return Err(invalid());
+                }
+                (commit_type, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+        if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(invalid());
+        }
+        if !types
+            .iter()
+            .any(|allowed| allowed.as_ref().eq_ignore_ascii_case(commit_type))
+        {
+            return Err(ConventionalCommitError::UnknownType(
+                commit_type.to_string(),
+            ));
+        }
+        if description.trim().is_empty() {
+            return Err(ConventionalCommitError::EmptyDescription);
+        }
+        if !description.starts_with(' ') {
+            return Err(invalid());
+        }
+        let description = description.trim();
+
+        let body = match lines.next() {
+            Some(line) if !line.trim().is_empty() => {
+                return Err(ConventionalCommitError::MissingBlankLine)
+            }
+            Some(_) => Some(lines.collect::<Vec<_>>().join("\n").trim().to_string())
+                .filter(|body| !body.is_empty()),
+            None => None,
+        };
+        let breaking_footer = body.as_deref().map_or(false, |body| {
+            body.lines().any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            })
+        });
+
+        Ok(ConventionalCommit {
+            commit_type: commit_type.to_lowercase(),
+            scope,
+            breaking: bang || breaking_footer,
+            description: description.to_string(),
+            body,
+        })
+    }
+}
+
+/// Messages git writes itself, which are not expected to follow the convention: merges,
+/// `git revert` messages and `fixup!`/`squash!`/`amend!` commits
+pub fn is_generated_message(message: &str) -> bool {
+    let header = message.trim_start();
+    ["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "]
+        .iter()
+        .any(|prefix| header.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_generated_message, ConventionalCommit, ConventionalCommitError};
+
+    #[test]
+    fn parse_reads_type_scope_and_breaking_marker() {
+        let commit = ConventionalCommit::parse("feat(cli)!: Add hooks command\n").unwrap();
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("cli"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "Add hooks command");
+        assert_eq!(commit.body, None);
+
+        let commit = ConventionalCommit::parse(
+            "fix: Handle empty diffs\n\nSkip generation.\n\nBREAKING CHANGE: no message",
+        )
+        .unwrap();
+        assert_eq!(commit.scope, None);
+        assert!(commit.breaking);
+        assert_eq!(
+            commit.body.as_deref(),
+            Some("Skip generation.\n\nBREAKING CHANGE: no message")
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_headers() {
+        assert_eq!(
+            ConventionalCommit::parse("  \n"),
+            Err(ConventionalCommitError::Empty)
+        );
+        for header in ["Add hooks", "feat:missing space", "feat(): x", "fe at: x"] {
+            assert_eq!(
+                ConventionalCommit::parse(header),
+                Err(ConventionalCommitError::InvalidHeader(header.to_string())),
+                "{header}"
+            );
+        }
+        assert_eq!(
+            ConventionalCommit::parse("feature: Add hooks"),
+            Err(ConventionalCommitError::UnknownType("feature".to_string()))
+        );
+        assert_eq!(
+            ConventionalCommit::parse("feat:  "),
+            Err(ConventionalCommitError::EmptyDescription)
+        );
+        assert_eq!(
+            ConventionalCommit::parse("feat: Add hooks\nbody"),
+            Err(ConventionalCommitError::MissingBlankLine)
+        );
+    }
+
+    #[test]
+    fn is_generated_message_recognises_git_messages() {
+        assert!(is_generated_message("Merge branch 'main' into topic"));
+        assert!(is_generated_message("Revert \"feat: Add hooks\""));
+        assert!(is_generated_message("fixup! feat: Add hooks"));
+        assert!(!is_generated_message("feat: Add hooks"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Parsing of the date formats git accepts in `GIT_AUTHOR_DATE` and `GIT_COMMITTER_DATE`.
+
+use crate::git::commits::builder::CommitTime;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Parse git's internal format (`<seconds> <offset>`, `@<seconds> [<offset>]`), RFC 2822
+/// (`Thu, 07 Apr 2005 22:13:13 +0200`) or ISO 8601 (`2005-04-07T22:13:13+02:00`).
+///
+/// Dates without a timezone are taken as UTC.
+pub(crate) fn parse_git_date(value: &str) -> Option<CommitTime> {
+    let value = value.trim();
+    parse_raw(value)
+        .or_else(|| parse_rfc2822(value))
+        .or_else(|| parse_iso8601(value))
+}
+
+fn parse_raw(value: &str) -> Option<CommitTime> {
+    let (value, offset_optional) = match value.strip_prefix('@') {
+        Some(rest) => (rest, true),
+        None => (value, false),
+    };
+    let mut parts = value.split_whitespace();
+    let seconds = parts.next()?;
+    if !seconds.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let offset_minutes = match parts.next() {
+        Some(offset) => parse_offset(offset)?,
+        None if offset_optional => 0,
+        None => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(CommitTime {
+        seconds: seconds.parse().ok()?,
+        offset_minutes,
+    })
+}
+
+fn parse_rfc2822(value: &str) -> Option<CommitTime> {
+    // The day of the week is optional and carries no information
+    let value = match value.split_once(',') {
+        Some((_, rest)) => rest,
+        None => value,
+    };
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [day, month, year, time, offset] = parts.as_slice() else {
+        return None;
+    };
+    let month = MONTHS
+        .iter()
+        .position(|name| month.eq_ignore_ascii_case(name))?
+        + 1;
+    to_commit_time(
+        year.parse().ok()?,
+        month as u32,
+        day.parse().ok()?,
+        time,
+        parse_offset(offset)?,
+    )
+}
+
+fn parse_iso8601(value: &str) -> Option<CommitTime> {
+    let (date, rest) = (value.get(..10)?, value.get(10..)?);
+    let rest = rest.strip_prefix(['T', ' '])?;
+    let mut fields = date.split('-');
+    let (year, month, day) = (fields.next()?, fields.next()?, fields.next()?);
+
+    let time_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+        .unwrap_or(rest.len());
+    let (time, offset) = rest.split_at(time_len);
+    let time = time.split('.').next()?;
+    let offset = match offset.trim() {
+        "" => 0,
+        offset => parse_offset(offset)?,
+    };
+    to_commit_time(
+        year.parse().ok()?,
+        month.parse().ok()?,
+        day.parse().ok()?,
+        time,
+        offset,
+    )
+}
+
+/// `Z`, `+hhmm`, `+hh:mm` or `+hh` as minutes east of UTC
+fn parse_offset(offset: &str) -> Option<i32> {
+    if offset.eq_ignore_ascii_case("z") {
+        return Some(0);
+    }
+    let (sign, digits) = match offset.as_bytes().first()? {
+        b'+' => (1, &offset[1..]),
+        b'-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let digits = digits.replace(':', "");
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    if minutes >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+fn to_commit_time(
+    year: i64,
+    month: u32,
+    day: u32,
+    time: &str,
+    offset_minutes: i32,
+) -> Option<CommitTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut fields = time.split(':');
+    let hours: i64 = fields.next()?.parse().ok()?;
+    let minutes: i64 = fields.next()?.parse().ok()?;
+    let seconds: i64 = match fields.next() {
+        Some(seconds) => seconds.parse().ok()?,
+        None => 0,
+    };
+    if fields.next().is_some() || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    let local = days_from_civil(year, month, day) * 86_400 + hours * 3600 + minutes * 60 + seconds;
+    Some(CommitTime {
+        seconds: local - i64::from(offset_minutes) * 60,
+        offset_minutes,
+    })
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_git_date;
+    use crate::git::commits::builder::CommitTime;
+
+    fn time(seconds: i64, offset_minutes: i32) -> Option<CommitTime> {
+        Some(CommitTime {
+            seconds,
+            offset_minutes,
+        })
+    }
+
+    #[test]
+    fn parses_the_formats_git_accepts() {
+        // 2005-04-07 22:13:13 +0200, the example from git-commit(1)
+        let expected = time(1_112_904_793, 120);
+        assert_eq!(parse_git_date("1112904793 +0200"), expected);
+        assert_eq!(parse_git_date("@1112904793 +0200"), expected);
+        assert_eq!(parse_git_date("Thu, 07 Apr 2005 22:13:13 +0200"), expected);
+        assert_eq!(parse_git_date("07 Apr 2005 22:13:13 +0200"), expected);
+        assert_eq!(parse_git_date("2005-04-07T22:13:13+02:00"), expected);
+        assert_eq!(parse_git_date("2005-04-07 22:13:13 +0200"), expected);
+
+        assert_eq!(parse_git_date("@0"), time(0, 0));
+        assert_eq!(
+            parse_git_date("2005-04-07T20:13:13Z"),
+            time(1_112_904_793, 0)
+        );
+        assert_eq!(
+            parse_git_date("2005-04-07T20:13:13"),
+            time(1_112_904_793, 0)
+        );
+        assert_eq!(
+            parse_git_date("2000-02-29T00:00:00-0130"),
+            time(951_787_800, -90)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_dates() {
+        for value in [
+            "",
+            "yesterday",
+            "1112904793",
+            "2005-13-07T22:13:13",
+            "@12 +02:99",
+        ] {
+            assert_eq!(parse_git_date(value), None, "{value}");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// Lines added and removed in one file of a diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiffStat {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Binary files have no line counts
+    pub binary: bool,
+}
+
+/// Totals and per-file counts of a diff, as shown by `git diff --stat`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<FileDiffStat>,
+}
+
+impl DiffStats {
+    /// The closing line of `git diff --stat`, e.g. `2 files changed, 3 insertions(+), 1 deletion(-)`
+    pub fn summary(&self) -> String {
+        let plural = |count: usize, singular: &str, plural: &str| {
+            if count == 1 {
+                format!("{count} {singular}")
+            } else {
+                format!("{count} {plural}")
+            }
+        };
+
+        let mut summary = plural(self.files_changed, "file changed", "files changed");
+        if self.insertions > 0 || self.deletions == 0 {
+            summary.push_str(&format!(
+                ", {}",
+                plural(self.insertions, "insertion(+)", "insertions(+)")
+            ));
+        }
+        if self.deletions > 0 || self.insertions == 0 {
+            summary.push_str(&format!(
+                ", {}",
+                plural(self.deletions, "deletion(-)", "deletions(-)")
+            ));
+        }
+        summary
+    }
+}
+
+impl GitRepo {
+    /// Count files changed, insertions and deletions of `diff`, overall and per file
+    #[tracing::instrument(level = "debug", skip(self, diff))]
+    pub fn diff_stats(&self, diff: &git2::Diff) -> Result<DiffStats, GitError> {
+        Ok(self.diff_summary(diff)?.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffStats, FileDiffStat};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn diff_stats_counts_totals_and_files() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "one\ntwo\n", "Initial commit")?;
+        repo.add_file("a.txt", "one\nTWO\nthree\n")?;
+        repo.add_file("b.bin", "\0\u{1}binary")?;
+        repo.add(&["a.txt", "b.bin"])?;
+
+        let stats = repo.diff_stats(&repo.get_staged_diff()?)?;
+        assert_eq!(stats.files_changed, 2);
+        assert_eq!(stats.insertions, 2);
+        assert_eq!(stats.deletions, 1);
+        assert_eq!(
+            stats.files,
+            vec![
+                FileDiffStat {
+                    path: "a.txt".to_string(),
+                    insertions: 2,
+                    deletions: 1,
+                    binary: false,
+                },
+                FileDiffStat {
+                    path: "b.bin".to_string(),
+                    insertions: 0,
+                    deletions: 0,
+                    binary: true,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn summary_matches_git_wording() {
+        let stats = |files_changed, insertions, deletions| DiffStats {
+            files_changed,
+            insertions,
+            deletions,
+            files: Vec::new(),
+        };
+        assert_eq!(
+            stats(2, 3, 1).summary(),
+            "2 files changed, 3 insertions(+), 1 deletion(-)"
+        );
+        assert_eq!(stats(1, 1, 0).summary(), "1 file changed, 1 insertion(+)");
+        assert_eq!(stats(1, 0, 4).summary(), "1 file changed, 4 deletions(-)");
+    }
+}
@@ -0,0 +1,296 @@
+use anyhow::Context;
+use git2::{Delta, Patch};
+
+use crate::git::commits::diff_stats::{DiffStats, FileDiffStat};
+use crate::git::commits::status::FileChangeKind;
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// The line ranges of one hunk, as in its `@@ -old_start,old_lines +new_start,new_lines @@`
+/// header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HunkRange {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+}
+
+/// One file of a [`DiffSummary`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiffSummary {
+    /// The path after the change; the old path for a deleted file
+    pub path: String,
+    /// Where a renamed or copied file came from
+    pub old_path: Option<String>,
+    pub kind: FileChangeKind,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Binary files have no line counts or hunks
+    pub binary: bool,
+    pub hunks: Vec<HunkRange>,
+}
+
+impl FileDiffSummary {
+    /// The path as `git diff --stat` shows it: `old => new` for renames
+    pub fn display_path(&self) -> String {
+        match &self.old_path {
+            Some(old_path) => format!("{old_path} => {}", self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// A diff as data: what changed in each file and where, without the patch text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffSummary {
+    pub files: Vec<FileDiffSummary>,
+}
+
+impl DiffSummary {
+    pub fn additions(&self) -> usize {
+        self.files.iter().map(|file| file.additions).sum()
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(|file| file.deletions).sum()
+    }
+
+    /// Totals and per-file counts
+    pub fn stats(&self) -> DiffStats {
+        DiffStats {
+            files_changed: self.files.len(),
+            insertions: self.additions(),
+            deletions: self.deletions(),
+            files: self
+                .files
+                .iter()
+                .map(|file| FileDiffStat {
+                    path: file.path.clone(),
+                    insertions: file.additions,
+                    deletions: file.deletions,
+                    binary: file.binary,
+                })
+                .collect(),
+        }
+    }
+
+    /// The text of `git diff --stat` for a terminal `width` columns wide: one
+    /// ` path | 12 +++---` line per file, with the bars scaled to fit, then the totals
+    pub fn render_stat(&self, width: usize) -> String {
+        let paths: Vec<String> = self
+            .files
+            .iter()
+            .map(FileDiffSummary::display_path)
+            .collect();
+        let path_width = paths
+            .iter()
+            .map(|path| path.chars().count())
+            .max()
+            .unwrap_or(0);
+        let max_changes = self
+            .files
+            .iter()
+            .map(|file| file.additions + file.deletions)
+            .max()
+            .unwrap_or(0);
+        let count_width = max_changes.to_string().len().max(3);
+        let bar_width = width.saturating_sub(path_width + count_width + 5).max(1);
+        let scale = |changes: usize| {
+            if max_changes <= bar_width || changes == 0 {
+                changes
+            } else {
+                (changes * bar_width / max_changes).max(1)
+            }
+        };
+
+        let mut lines = Vec::new();
+        for (file, path) in self.files.iter().zip(&paths) {
+            let changes = if file.binary {
+                format!("{:>count_width$}", "Bin")
+            } else {
+                let total = scale(file.additions + file.deletions);
+                let plus = scale(file.additions).min(total);
+                format!(
+                    "{:>count_width$} {}{}",
+                    file.additions + file.deletions,
+                    "+".repeat(plus),
+                    "-".repeat(total - plus)
+                )
+            };
+            lines.push(
+                format!(" {path:<path_width$} | {changes}")
+                    .trim_end()
+                    .to_string(),
+            );
+        }
+        lines.push(format!(" {}", self.stats().summary()));
+        lines.join("\n")
+    }
+}
+
+impl GitRepo {
+    /// Break `diff` down into files with their change kind, line counts and hunk ranges.
+    ///
+    /// Renames show up only when `diff` had rename detection run on it, e.g. with
+    /// [`git2::Diff::find_similar`].
+    #[tracing::instrument(level = "debug", skip(self, diff))]
+    pub fn diff_summary(&self, diff: &git2::Diff) -> Result<DiffSummary, GitError> {
+        let path_of =
+            |file: git2::DiffFile<'_>| file.path().map(|path| path.to_string_lossy().into_owned());
+
+        let mut files = Vec::new();
+        for (idx, delta) in diff.deltas().enumerate() {
+            let kind = match delta.status() {
+                Delta::Added | Delta::Untracked | Delta::Copied => FileChangeKind::Added,
+                Delta::Deleted => FileChangeKind::Deleted,
+                Delta::Renamed => FileChangeKind::Renamed,
+                Delta::Typechange => FileChangeKind::Typechange,
+                _ => FileChangeKind::Modified,
+            };
+            let path = path_of(delta.new_file())
+                .or_else(|| path_of(delta.old_file()))
+                .unwrap_or_default();
+            let old_path = matches!(delta.status(), Delta::Renamed | Delta::Copied)
+                .then(|| path_of(delta.old_file()))
+                .flatten();
+
+            let patch = Patch::from_diff(diff, idx).context("Failed to build patch for diff")?;
+            let binary = delta.flags().is_binary() || patch.is_none();
+            let mut file = FileDiffSummary {
+                path,
+                old_path,
+                kind,
+                additions: 0,
+                deletions: 0,
+                binary,
+                hunks: Vec::new(),
+            };
+            if let Some(patch) = patch.filter(|_| !binary) {
+                let (_, additions, deletions) = patch
+                    .line_stats()
+                    .context("Failed to count changed lines")?;
+                file.additions = additions;
+                file.deletions = deletions;
+                for hunk_idx in 0..patch.num_hunks() {
+                    let (hunk, _) = patch.hunk(hunk_idx).context("Failed to read hunk")?;
+                    file.hunks.push(HunkRange {
+                        old_start: hunk.old_start(),
+                        old_lines: hunk.old_lines(),
+                        new_start: hunk.new_start(),
+                        new_lines: hunk.new_lines(),
+                    });
+                }
+            }
+            files.push(file);
+        }
+        Ok(DiffSummary { files })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffSummary, FileDiffSummary, HunkRange};
+    use crate::git::commits::status::FileChangeKind;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn diff_summary_lists_kinds_counts_and_hunks() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let numbers: String = (1..=20).map(|n| format!("{n}\n")).collect();
+        repo.add_file_and_commit("numbers.txt", &numbers, "Initial commit")?
+            .add_file_and_commit(
+                "old.txt",
+                "a file long enough to be recognised\n",
+                "Add old",
+            )?;
+        let edited: String = (1..=20)
+            .filter(|n| *n != 19)
+            .map(|n| match n {
+                2 => "two\n".to_string(),
+                n => format!("{n}\n"),
+            })
+            .collect();
+        repo.add_file("numbers.txt", &edited)?;
+        std::fs::rename(repo.path().join("old.txt"), repo.path().join("new.txt"))?;
+        repo.add_file("image.bin", "\0\u{1}binary")?;
+        repo.add(&["."])?;
+
+        let mut diff = repo.get_staged_diff()?;
+        diff.find_similar(None)?;
+        let summary = repo.diff_summary(&diff)?;
+
+        let files: Vec<(&str, FileChangeKind)> = summary
+            .files
+            .iter()
+            .map(|file| (file.path.as_str(), file.kind))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("image.bin", FileChangeKind::Added),
+                ("new.txt", FileChangeKind::Renamed),
+                ("numbers.txt", FileChangeKind::Modified),
+            ]
+        );
+        assert!(summary.files[0].binary);
+        assert_eq!(summary.files[1].old_path.as_deref(), Some("old.txt"));
+        assert_eq!(summary.files[1].display_path(), "old.txt => new.txt");
+
+        let numbers = &summary.files[2];
+        assert_eq!((numbers.additions, numbers.deletions), (1, 2));
+        assert_eq!(
+            numbers.hunks,
+            vec![
+                HunkRange {
+                    old_start: 1,
+                    old_lines: 5,
+                    new_start: 1,
+                    new_lines: 5,
+                },
+                HunkRange {
+                    old_start: 16,
+                    old_lines: 5,
+                    new_start: 16,
+                    new_lines: 4,
+                },
+            ]
+        );
+        assert_eq!(
+            summary.stats().summary(),
+            "3 files changed, 1 insertion(+), 2 deletions(-)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn render_stat_scales_bars_to_the_width() {
+        let file = |path: &str, additions, deletions, binary| FileDiffSummary {
+            path: path.to_string(),
+            old_path: None,
+            kind: FileChangeKind::Modified,
+            additions,
+            deletions,
+            binary,
+            hunks: Vec::new(),
+        };
+        let summary = DiffSummary {
+            files: vec![
+                file("src/lib.rs", 3, 1, false),
+                file("logo.png", 0, 0, true),
+            ],
+        };
+        assert_eq!(
+            summary.render_stat(80),
+            " src/lib.rs |   4 +++-\n logo.png   | Bin\n 2 files changed, 3 insertions(+), 1 deletion(-)"
+        );
+
+        let wide = DiffSummary {
+            files: vec![file("a.txt", 150, 50, false)],
+        };
+        let line = wide.render_stat(30);
+        let line = line.lines().next().unwrap();
+        assert_eq!(line, " a.txt | 200 ++++++++++++-----");
+        assert_eq!(line.chars().count(), 30);
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::Context;
+use git2::{Delta, DiffDelta, Patch};
+
+use crate::git::commits::word_diff::render_word_diff;
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// How [`GitRepo::diff_to_string_with`] renders a diff as patch text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffTextOptions {
+    /// Decode non-UTF-8 lines with replacement characters; otherwise files with non-UTF-8
+    /// content are shown as binary
+    pub lossy: bool,
+    /// Stop once the text would exceed this many bytes, noting how many files were left out
+    pub max_bytes: Option<usize>,
+    /// Replace the hunks of any single file larger than this many bytes with a marker
+    pub max_file_bytes: Option<usize>,
+    /// Render a modified line once, as context with `[-removed-]{+added+}` markers, instead
+    /// of as a removed/added pair, like `git diff --word-diff=plain`
+    pub word_diff: bool,
+}
+
+impl Default for DiffTextOptions {
+    fn default() -> Self {
+        Self {
+            lossy: true,
+            max_bytes: None,
+            max_file_bytes: None,
+            word_diff: false,
+        }
+    }
+}
+
+/// Patch text of one file, split so the hunks can be dropped while keeping the header
+#[derive(Default)]
+struct FileText {
+    header: String,
+    lines: Vec<(char, String)>,
+    binary_marker: Option<String>,
+    non_utf8: bool,
+}
+
+impl GitRepo {
+    /// Convert a diff to patch text, decoding non-UTF-8 content lossily
+    #[tracing::instrument(level = "debug", skip(self, diff))]
+    pub fn diff_to_string(&self, diff: &git2::Diff) -> Result<String, GitError> {
+        self.diff_to_string_with(diff, &DiffTextOptions::default())
+    }
+
+    /// Convert a diff to patch text, marking binary files and applying size limits
+    #[tracing::instrument(level = "debug", skip(self, diff, options))]
+    pub fn diff_to_string_with(
+        &self,
+        diff: &git2::Diff,
+        options: &DiffTextOptions,
+    ) -> Result<String, GitError> {
+        let file_count = diff.deltas().len();
+        let mut diff_text = String::new();
+
+        for idx in 0..file_count {
+            let Some(mut patch) =
+                Patch::from_diff(diff, idx).context("Failed to build patch for diff")?
+            else {
+                // libgit2 gives no patch for binary files it did not load
+                if let Some(delta) = diff.get_delta(idx) {
+                    diff_text.push_str(&binary_marker(&delta));
+                }
+                continue;
+            };
+
+            let mut file = FileText::default();
+            patch
+                .print(&mut |_delta, _hunk, line| {
+                    let content = match std::str::from_utf8(line.content()) {
+                        Ok(content) => content.to_string(),
+                        Err(_) => {
+                            file.non_utf8 = true;
+                            String::from_utf8_lossy(line.content()).to_string()
+                        }
+                    };
+                    match line.origin() {
+                        'F' => file.header.push_str(&content),
+                        'B' => file.binary_marker = Some(content),
+                        // Hunk headers and "\ No newline at end of file" carry their own text
+                        origin => file.lines.push((origin, content)),
+                    }
+                    true
+                })
+                .context("Failed to print patch")?;
+
+            let delta = patch.delta();
+            let body = render_body(&file.lines, options.word_diff);
+            let mut text = file.header;
+            if delta.flags().is_binary() || file.binary_marker.is_some() {
+                text.push_str(
+                    &file
+                        .binary_marker
+                        .unwrap_or_else(|| binary_files_differ(&delta)),
+                );
+            } else if file.non_utf8 && !options.lossy {
+                text.push_str(&binary_files_differ(&delta));
+            } else if options
+                .max_file_bytes
+                .map_or(false, |limit| body.len() > limit)
+            {
+                text.push_str(&format!("[{} bytes of changes omitted]\n", body.len()));
+            } else {
+                text.push_str(&body);
+            }
+
+            if options
+                .max_bytes
+                .map_or(false, |limit| diff_text.len() + text.len() > limit)
+            {
+                diff_text.push_str(&format!(
+                    "[diff truncated: {} more files omitted]\n",
+                    file_count - idx
+                ));
+                break;
+            }
+            diff_text.push_str(&text);
+        }
+
+        Ok(diff_text)
+    }
+}
+
+/// Prefix content lines with their origin; with `word_diff`, a run of removed lines followed
+/// by added lines is rendered pairwise as single word-diffed lines
+fn render_body(lines: &[(char, String)], word_diff: bool) -> String {
+    let mut body = String::new();
+    let mut idx = 0;
+    while idx < lines.len() {
+        let (origin, content) = &lines[idx];
+        if !(word_diff && *origin == '-') {
+            if matches!(origin, '+' | '-' | ' ') {
+                body.push(*origin);
+            }
+            body.push_str(content);
+            idx += 1;
+            continue;
+        }
+
+        let removed_end = idx + lines[idx..].iter().take_while(|(o, _)| *o == '-').count();
+        let added_end = removed_end
+            + lines[removed_end..]
+                .iter()
+                .take_while(|(o, _)| *o == '+')
+                .count();
+        let removed = &lines[idx..removed_end];
+        let added = &lines[removed_end..added_end];
+
+        for pair in 0..removed.len().max(added.len()) {
+            let rendered = match (removed.get(pair), added.get(pair)) {
+                (Some((_, old)), Some((_, new))) => {
+                    render_word_diff(old.trim_end_matches('\n'), new.trim_end_matches('\n'))
+                        .map(|line| format!(" {line}\n"))
+                }
+                _ => None,
+            };
+            match rendered {
+                Some(line) => body.push_str(&line),
+                None => {
+                    for (origin, content) in removed.get(pair).into_iter().chain(added.get(pair)) {
+                        body.push(*origin);
+                        body.push_str(content);
+                    }
+                }
+            }
+        }
+        idx = added_end;
+    }
+    body
+}
+
+/// Header and marker for a binary file, for files libgit2 produced no patch for
+fn binary_marker(delta: &DiffDelta) -> String {
+    let (old, new) = delta_paths(delta);
+    format!("diff --git a/{old} b/{new}\n{}", binary_files_differ(delta))
+}
+
+fn binary_files_differ(delta: &DiffDelta) -> String {
+    let (old, new) = delta_paths(delta);
+    let old = match delta.status() {
+        Delta::Added | Delta::Untracked => "/dev/null".to_string(),
+        _ => format!("a/{old}"),
+    };
+    let new = match delta.status() {
+        Delta::Deleted => "/dev/null".to_string(),
+        _ => format!("b/{new}"),
+    };
+    format!("Binary files {old} and {new} differ\n")
+}
+
+fn delta_paths(delta: &DiffDelta) -> (String, String) {
+    let path = |file: git2::DiffFile| {
+        file.path()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let old = path(delta.old_file());
+    let new = path(delta.new_file());
+    match (old.is_empty(), new.is_empty()) {
+        (true, false) => (new.clone(), new),
+        (false, true) => (old.clone(), old),
+        _ => (old, new),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiffTextOptions;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn diff_to_string_keeps_headers_unprefixed() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "one\n", "Initial commit")?;
+        repo.add_file("a.txt", "one\ntwo\n")?;
+        repo.add(&["a.txt"])?;
+
+        let text = repo.diff_staged()?;
+        assert!(text.starts_with("diff --git a/a.txt b/a.txt\n"), "{text}");
+        assert!(text.contains("\n@@ -1 +1,2 @@\n one\n+two\n"), "{text}");
+        Ok(())
+    }
+
+    #[test]
+    fn binary_and_non_utf8_files_get_markers() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("keep.txt", "keep\n", "Initial commit")?;
+        std::fs::write(repo.path().join("image.bin"), b"\x00\x01\x02binary")?;
+        std::fs::write(repo.path().join("latin1.txt"), b"caf\xe9\n")?;
+        repo.add(&["image.bin", "latin1.txt"])?;
+        let diff = repo.get_staged_diff()?;
+
+        let lossy = repo.diff_to_string(&diff)?;
+        assert!(lossy.contains("Binary files /dev/null and b/image.bin differ\n"));
+        assert!(lossy.contains("+caf\u{fffd}\n"));
+
+        let strict = repo.diff_to_string_with(
+            &diff,
+            &DiffTextOptions {
+                lossy: false,
+                ..DiffTextOptions::default()
+            },
+        )?;
+        assert!(strict.contains("Binary files /dev/null and b/latin1.txt differ\n"));
+        assert!(!strict.contains("caf"));
+        Ok(())
+    }
+
+    #[test]
+    fn size_limits_truncate_files_and_the_whole_diff() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file("big.txt", &"line\n".repeat(100))?;
+        repo.add_file("small.txt", "small\n")?;
+        repo.add(&["big.txt", "small.txt"])?;
+        let diff = repo.get_staged_diff()?;
+
+        let per_file = repo.diff_to_string_with(
+            &diff,
+            &DiffTextOptions {
+                max_file_bytes: Some(100),
+                ..DiffTextOptions::default()
+            },
+        )?;
+        assert!(per_file.contains("+++ b/big.txt\n["));
+        assert!(per_file.contains("bytes of changes omitted]\n"));
+        assert!(per_file.contains("+small\n"));
+
+        let total = repo.diff_to_string_with(
+            &diff,
+            &DiffTextOptions {
+                max_bytes: Some(200),
+                ..DiffTextOptions::default()
+            },
+        )?;
+        assert_eq!(
+            total, "[diff truncated: 2 more files omitted]\n",
+            "the first file alone exceeds the limit"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn word_diff_marks_changes_within_modified_lines() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.rs", "let value = 1;\nlet name = \"a\";\n", "Initial")?;
+        repo.add_file(
+            "a.rs",
+            "let value = 42;\nlet name = \"a\";\nlet extra = 2;\n",
+        )?;
+        repo.add(&["a.rs"])?;
+        let diff = repo.get_staged_diff()?;
+
+        let text = repo.diff_to_string_with(
+            &diff,
+            &DiffTextOptions {
+                word_diff: true,
+                ..DiffTextOptions::default()
+            },
+        )?;
+        assert!(
+            text.contains("\n let value = [-1-]{+42+};\n let name = \"a\";\n+let extra = 2;\n"),
+            "{text}"
+        );
+        assert!(repo
+            .diff_to_string(&diff)?
+            .contains("-let value = 1;\n+let value = 42;\n"));
+        Ok(())
+    }
+}
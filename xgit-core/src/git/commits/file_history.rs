@@ -0,0 +1,195 @@
+use anyhow::Context;
+use git2::{Delta, DiffFindOptions, DiffOptions, Oid, Sort};
+use std::path::Path;
+
+use crate::git::commits::log::commit_info;
+use crate::git::error::GitError;
+use crate::git::repository::core::{CommitInfo, GitRepo};
+
+/// A commit that changed the file passed to [`GitRepo::file_history`]
+#[derive(Debug, Clone)]
+pub struct FileHistoryEntry {
+    pub commit: CommitInfo,
+    /// Path of the file after this commit (before it, for a deletion)
+    pub path: String,
+    /// Path the file had before this commit renamed it
+    pub previous_path: Option<String>,
+}
+
+impl GitRepo {
+    /// Commits reachable from HEAD that changed `path`, newest first, following renames like
+    /// `git log --follow`.
+    ///
+    /// `path` is relative to the repository root. Each commit is compared with its first
+    /// parent and merge commits are skipped, so on histories where both sides of a merge
+    /// renamed the file only the path seen most recently is followed.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn file_history(&self, path: &str) -> Result<Vec<FileHistoryEntry>, GitError> {
+        if self.repo().head().is_err() {
+            return Ok(Vec::new());
+        }
+
+        let mut revwalk = self.repo().revwalk().context("Failed to create revwalk")?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .context("Failed to set sorting")?;
+        revwalk.push_head().context("Failed to push HEAD")?;
+
+        let mut current_path = path.to_string();
+        let mut entries = Vec::new();
+
+        for oid in revwalk {
+            let oid = oid.context("Failed to get commit OID")?;
+            let commit = self
+                .repo()
+                .find_commit(oid)
+                .context("Failed to find commit")?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+
+            let tree = commit.tree().context("Failed to get commit tree")?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+                Err(_) => None,
+            };
+            let blob_at = |tree: Option<&git2::Tree>| -> Option<Oid> {
+                tree?
+                    .get_path(Path::new(&current_path))
+                    .ok()
+                    .map(|e| e.id())
+            };
+            let (before, after) = (blob_at(parent_tree.as_ref()), blob_at(Some(&tree)));
+            if before == after {
+                continue;
+            }
+
+            // A file that appears in this commit may have been renamed from another path
+            let previous_path = if before.is_none() {
+                let mut diff = self
+                    .repo()
+                    .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+                    .context("Failed to diff commit against parent")?;
+                diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+                    .context("Failed to detect renames")?;
+                diff.deltas()
+                    .find(|delta| {
+                        delta.status() == Delta::Renamed
+                            && delta.new_file().path() == Some(Path::new(&current_path))
+                    })
+                    .and_then(|delta| delta.old_file().path())
+                    .map(|old| old.to_string_lossy().to_string())
+            } else {
+                None
+            };
+
+            entries.push(FileHistoryEntry {
+                commit: commit_info(&commit),
+                path: current_path.clone(),
+                previous_path: previous_path.clone(),
+            });
+
+            match previous_path {
+                Some(previous_path) => current_path = previous_path,
+                // Added here without a rename: nothing older to follow
+                None if before.is_none() => break,
+                None => {}
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Changes `entry`'s commit made to the file, shown as a rename when it moved the file
+    #[tracing::instrument(level = "debug", skip(self, entry), fields(commit = %entry.commit.hash))]
+    pub fn get_file_history_diff(
+        &self,
+        entry: &FileHistoryEntry,
+    ) -> Result<git2::Diff<'_>, GitError> {
+        let oid = Oid::from_str(&entry.commit.hash).context("Invalid commit hash")?;
+        let commit = self
+            .repo()
+            .find_commit(oid)
+            .context("Failed to find commit")?;
+        let tree = commit.tree().context("Failed to get commit tree")?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+            Err(_) => None,
+        };
+
+        let mut options = DiffOptions::new();
+        options.pathspec(&entry.path).disable_pathspec_match(true);
+        if let Some(previous_path) = &entry.previous_path {
+            options.pathspec(previous_path);
+        }
+        let mut diff = self
+            .repo()
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))
+            .context(format!("Failed to diff '{}'", entry.path))?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+            .context("Failed to detect renames")?;
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn file_history_follows_renames() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("old.txt", "one\ntwo\nthree\nfour\n", "Add old")?
+            .add_file_and_commit("other.txt", "unrelated\n", "Add other")?
+            .append_to_file_and_commit("old.txt", "five\n", "Extend old")?;
+        std::fs::rename(repo.path().join("old.txt"), repo.path().join("new.txt"))?;
+        repo.add(&["old.txt", "new.txt"])?;
+        repo.commit("Rename old to new")?;
+        repo.append_to_file_and_commit("new.txt", "six\n", "Extend new")?;
+
+        let history = repo.file_history("new.txt")?;
+        let summary: Vec<_> = history
+            .iter()
+            .map(|entry| {
+                (
+                    entry.commit.message.trim(),
+                    entry.path.as_str(),
+                    entry.previous_path.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Extend new", "new.txt", None),
+                ("Rename old to new", "new.txt", Some("old.txt")),
+                ("Extend old", "old.txt", None),
+                ("Add old", "old.txt", None),
+            ]
+        );
+
+        let rename = repo.get_file_history_diff(&history[1])?;
+        assert_eq!(rename.deltas().len(), 1);
+        assert_eq!(rename.get_delta(0).unwrap().status(), git2::Delta::Renamed);
+        let extend = repo.diff_to_string(&repo.get_file_history_diff(&history[2])?)?;
+        assert!(extend.contains("+five"), "{extend}");
+        assert!(!extend.contains("other.txt"), "{extend}");
+        Ok(())
+    }
+
+    #[test]
+    fn file_history_includes_deletion_and_ignores_unknown_paths(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("gone.txt", "bye\n", "Add gone")?;
+        std::fs::remove_file(repo.path().join("gone.txt"))?;
+        repo.add(&["gone.txt"])?;
+        repo.commit("Remove gone")?;
+
+        let history = repo.file_history("gone.txt")?;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].commit.message.trim(), "Remove gone");
+        assert!(repo.file_history("missing.txt")?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::Context;
+use git2::Sort;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// Size and timing of a single commit, used for local activity statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitActivity {
+    pub hash: String,
+    /// Author time in seconds since the Unix epoch
+    pub time: i64,
+    /// Author timezone offset in minutes
+    pub offset_minutes: i32,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<String>,
+}
+
+/// A commit in the repository-wide history, with its author resolved through `.mailmap`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoCommit {
+    pub hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author time in seconds since the Unix epoch
+    pub time: i64,
+    /// Author timezone offset in minutes
+    pub offset_minutes: i32,
+    /// Per-file line changes against the first parent; empty for merge commits
+    pub files: Vec<FileChurn>,
+}
+
+/// Lines added and removed in one file by one commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChurn {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl GitRepo {
+    /// Collect activity for every commit reachable from local branches whose author email
+    /// matches `author_email` (case-insensitive), newest first
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_author_activity(&self, author_email: &str) -> Result<Vec<CommitActivity>, GitError> {
+        let mut revwalk = self.repo().revwalk().context("Failed to create revwalk")?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .context("Failed to set sorting")?;
+        revwalk
+            .push_glob("refs/heads")
+            .context("Failed to push local branches")?;
+
+        let mut activity = Vec::new();
+
+        for oid in revwalk {
+            let oid = oid.context("Failed to get commit OID")?;
+            let commit = self
+                .repo()
+                .find_commit(oid)
+                .context("Failed to find commit")?;
+
+            let author = commit.author();
+            let matches_author = author
+                .email()
+                .map_or(false, |email| email.eq_ignore_ascii_case(author_email));
+            if !matches_author {
+                continue;
+            }
+
+            let tree = commit.tree().context("Failed to get commit tree")?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+                Err(_) => None,
+            };
+            let diff = self
+                .repo()
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+                .context("Failed to diff commit against parent")?;
+            let stats = diff.stats().context("Failed to compute diff stats")?;
+            let files = diff
+                .deltas()
+                .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+
+            activity.push(CommitActivity {
+                hash: oid.to_string(),
+                time: author.when().seconds(),
+                offset_minutes: author.when().offset_minutes(),
+                insertions: stats.insertions(),
+                deletions: stats.deletions(),
+                files,
+            });
+        }
+
+        Ok(activity)
+    }
+
+    /// Collect every commit reachable from local branches, newest first.
+    ///
+    /// Authors are mapped through the repository's `.mailmap`, so one contributor who
+    /// committed under several names or emails shows up once. Merge commits carry no file
+    /// changes, since those are already counted on the commits being merged.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_repo_activity(&self) -> Result<Vec<RepoCommit>, GitError> {
+        let mut revwalk = self.repo().revwalk().context("Failed to create revwalk")?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .context("Failed to set sorting")?;
+        revwalk
+            .push_glob("refs/heads")
+            .context("Failed to push local branches")?;
+        let mailmap = self.repo().mailmap().context("Failed to load mailmap")?;
+
+        let mut commits = Vec::new();
+
+        for oid in revwalk {
+            let oid = oid.context("Failed to get commit OID")?;
+            let commit = self
+                .repo()
+                .find_commit(oid)
+                .context("Failed to find commit")?;
+            let author = commit
+                .author_with_mailmap(&mailmap)
+                .context("Failed to resolve author through mailmap")?;
+
+            let files = if commit.parent_count() > 1 {
+                Vec::new()
+            } else {
+                self.file_churn(&commit)?
+            };
+
+            commits.push(RepoCommit {
+                hash: oid.to_string(),
+                author_name: String::from_utf8_lossy(author.name_bytes()).to_string(),
+                author_email: String::from_utf8_lossy(author.email_bytes()).to_string(),
+                time: author.when().seconds(),
+                offset_minutes: author.when().offset_minutes(),
+                files,
+            });
+        }
+
+        Ok(commits)
+    }
+
+    fn file_churn(&self, commit: &git2::Commit) -> Result<Vec<FileChurn>, GitError> {
+        let tree = commit.tree().context("Failed to get commit tree")?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+            Err(_) => None,
+        };
+        let diff = self
+            .repo()
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .context("Failed to diff commit against parent")?;
+
+        let mut files = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(patch) =
+                git2::Patch::from_diff(&diff, index).context("Failed to build file patch")?
+            else {
+                continue;
+            };
+            let delta = patch.delta();
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+            let (_, insertions, deletions) = patch
+                .line_stats()
+                .context("Failed to count changed lines")?;
+            files.push(FileChurn {
+                path: path.to_string_lossy().to_string(),
+                insertions,
+                deletions,
+            });
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn get_author_activity_filters_by_author_and_counts_changes(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "one\ntwo\n", "Add a")?
+            .append_to_file_and_commit("a.txt", "three\n", "Extend a")?;
+
+        repo.set_user_config("Someone Else", "else@example.com")?;
+        repo.add_file_and_commit("b.txt", "other\n", "Add b")?;
+
+        let activity = repo.get_author_activity("TEST@example.com")?;
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[0].insertions, 1);
+        assert_eq!(activity[0].files, vec!["a.txt".to_string()]);
+        assert_eq!(activity[1].insertions, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn get_repo_activity_applies_mailmap_and_counts_lines_per_file(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "one\ntwo\n", "Add a")?;
+        repo.set_user_config("Old Name", "old@example.com")?;
+        repo.add_file_and_commit(
+            ".mailmap",
+            "Test User <test@example.com> Old Name <old@example.com>\n",
+            "Add mailmap",
+        )?;
+        repo.append_to_file_and_commit("a.txt", "three\n", "Extend a")?;
+
+        let commits = repo.get_repo_activity()?;
+        assert_eq!(commits.len(), 3);
+        assert!(commits
+            .iter()
+            .all(|commit| commit.author_email == "test@example.com"
+                && commit.author_name == "Test User"));
+        assert_eq!(commits[0].files.len(), 1);
+        assert_eq!(commits[0].files[0].path, "a.txt");
+        assert_eq!(
+            (
+                commits[0].files[0].insertions,
+                commits[0].files[0].deletions
+            ),
+            (1, 0)
+        );
+        assert_eq!(commits[2].files[0].insertions, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn get_author_activity_is_empty_without_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert!(repo.get_author_activity("test@example.com")?.is_empty());
+        Ok(())
+    }
+}
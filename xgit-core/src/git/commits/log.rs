@@ -0,0 +1,276 @@
+use anyhow::Context;
+use git2::{DiffOptions, Sort};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::{CommitInfo, GitRepo};
+
+/// Which commits [`GitRepo::list_commits_with`] returns, named after the `git log` options
+/// they mirror
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    /// Revision or range (`A..B`, `A...B`) to walk; defaults to HEAD
+    pub rev: Option<String>,
+    /// Return at most this many commits (`-n`)
+    pub max_count: Option<usize>,
+    /// Leave out this many of the matching commits first (`--skip`)
+    pub skip: usize,
+    /// Keep commits whose author name or email contains this, ignoring case (`--author`)
+    pub author: Option<String>,
+    /// Keep commits that changed a file matching one of these pathspecs (`-- <path>...`)
+    pub paths: Vec<String>,
+    /// Keep commits committed at or after this many seconds since the Unix epoch (`--since`)
+    pub since: Option<i64>,
+    /// Keep commits committed at or before this many seconds since the Unix epoch (`--until`)
+    pub until: Option<i64>,
+}
+
+impl GitRepo {
+    /// Commits selected by `options`, newest first.
+    ///
+    /// `skip` and `max_count` count only the commits the filters keep, so they page through
+    /// the filtered history. Like `git log -- <path>`, a merge commit matches a path filter only
+    /// when it differs from every parent there. Without a `rev`, a repository with no commits
+    /// yet has an empty history.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn list_commits_with(&self, options: &LogOptions) -> Result<Vec<CommitInfo>, GitError> {
+        let range = match options.rev.as_deref() {
+            Some(rev) => rev,
+            None if self.repo().head().is_err() => return Ok(Vec::new()),
+            None => "HEAD",
+        };
+        let spec = self
+            .repo()
+            .revparse(range)
+            .context(format!("Failed to resolve '{range}'"))?;
+        let from = spec
+            .from()
+            .map(|object| peel_commit_id(object, range))
+            .transpose()?;
+        let mut to = spec
+            .to()
+            .map(|object| peel_commit_id(object, range))
+            .transpose()?;
+        // `A..` and `A...` compare against HEAD, like git
+        if to.is_none() && !spec.mode().contains(git2::RevparseMode::SINGLE) {
+            to = Some(
+                self.repo()
+                    .head()
+                    .and_then(|head| head.peel_to_commit())
+                    .context("Failed to resolve HEAD")?
+                    .id(),
+            );
+        }
+
+        let mut revwalk = self.repo().revwalk().context("Failed to create revwalk")?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .context("Failed to set sorting")?;
+
+        match (from, to) {
+            (Some(from), Some(to)) if spec.mode().contains(git2::RevparseMode::MERGE_BASE) => {
+                revwalk.push(from).context("Failed to push range start")?;
+                revwalk.push(to).context("Failed to push range end")?;
+                if let Ok(base) = self.repo().merge_base(from, to) {
+                    revwalk.hide(base).context("Failed to hide merge base")?;
+                }
+            }
+            (Some(from), Some(to)) => {
+                revwalk.push(to).context("Failed to push range end")?;
+                revwalk.hide(from).context("Failed to hide range start")?;
+            }
+            (Some(start), None) | (None, Some(start)) => {
+                revwalk.push(start).context("Failed to push start commit")?;
+            }
+            (None, None) => return Ok(Vec::new()),
+        }
+
+        let author = options.author.as_deref().map(str::to_lowercase);
+        let limit = options.max_count.unwrap_or(usize::MAX);
+        let mut skipped = 0;
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            if commits.len() >= limit {
+                break;
+            }
+            let oid = oid.context("Failed to get commit OID")?;
+            let commit = self
+                .repo()
+                .find_commit(oid)
+                .context("Failed to find commit")?;
+
+            let committed = commit.committer().when().seconds();
+            if options.since.map_or(false, |since| committed < since)
+                || options.until.map_or(false, |until| committed > until)
+            {
+                continue;
+            }
+            if let Some(author) = &author {
+                let signature = commit.author();
+                let identity = format!(
+                    "{} <{}>",
+                    signature.name().unwrap_or_default(),
+                    signature.email().unwrap_or_default()
+                );
+                if !identity.to_lowercase().contains(author.as_str()) {
+                    continue;
+                }
+            }
+            if !options.paths.is_empty() && !self.touches_paths(&commit, &options.paths)? {
+                continue;
+            }
+
+            if skipped < options.skip {
+                skipped += 1;
+                continue;
+            }
+            commits.push(commit_info(&commit));
+        }
+
+        Ok(commits)
+    }
+
+    /// Whether `commit` changed anything matching `paths` compared with every one of its
+    /// parents, or added something matching for a root commit
+    fn touches_paths(&self, commit: &git2::Commit<'_>, paths: &[String]) -> Result<bool, GitError> {
+        let mut diff_options = DiffOptions::new();
+        for path in paths {
+            diff_options.pathspec(path);
+        }
+        let tree = commit.tree().context("Failed to get commit tree")?;
+        let changed_since = |parent: Option<&git2::Tree<'_>>, diff_options: &mut DiffOptions| {
+            self.repo()
+                .diff_tree_to_tree(parent, Some(&tree), Some(diff_options))
+                .map(|diff| diff.deltas().len() > 0)
+                .context("Failed to diff commit against parent")
+        };
+
+        if commit.parent_count() == 0 {
+            return Ok(changed_since(None, &mut diff_options)?);
+        }
+        for parent in commit.parents() {
+            let parent_tree = parent.tree().context("Failed to get parent tree")?;
+            if !changed_since(Some(&parent_tree), &mut diff_options)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// The fields of `commit` a listing needs, with author identity and time
+pub(crate) fn commit_info(commit: &git2::Commit<'_>) -> CommitInfo {
+    let author = commit.author();
+    CommitInfo {
+        hash: commit.id().to_string(),
+        message: commit.message().unwrap_or("").to_string(),
+        author_name: author.name().unwrap_or("").to_string(),
+        author_email: author.email().unwrap_or("").to_string(),
+        time: author.when().seconds(),
+        offset_minutes: author.when().offset_minutes(),
+        parent_ids: commit.parent_ids().map(|id| id.to_string()).collect(),
+    }
+}
+
+fn peel_commit_id(object: &git2::Object, range: &str) -> Result<git2::Oid, GitError> {
+    Ok(object
+        .peel_to_commit()
+        .context(format!("'{range}' does not point to a commit"))?
+        .id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogOptions;
+    use crate::git::commits::builder::{CommitOptions, CommitTime, Identity};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    fn subjects(commits: &[crate::CommitInfo]) -> Vec<&str> {
+        commits.iter().map(|commit| commit.message.trim()).collect()
+    }
+
+    #[test]
+    fn list_commits_with_filters_by_author_and_date() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        for (name, day, message) in [
+            ("Ada", 1, "First"),
+            ("Grace", 2, "Second"),
+            ("Ada", 3, "Third"),
+            ("Grace", 4, "Fourth"),
+        ] {
+            repo.add_file(&format!("{message}.txt"), message)?;
+            repo.add(&["."])?;
+            let time = CommitTime {
+                seconds: day * 86_400,
+                offset_minutes: 0,
+            };
+            repo.commit_with(&CommitOptions {
+                author: Some(Identity {
+                    name: name.to_string(),
+                    email: format!("{}@example.com", name.to_lowercase()),
+                }),
+                author_date: Some(time),
+                committer_date: Some(time),
+                ..CommitOptions::new(message)
+            })?;
+        }
+
+        let ada = repo.list_commits_with(&LogOptions {
+            author: Some("ADA@EXAMPLE".to_string()),
+            ..LogOptions::default()
+        })?;
+        assert_eq!(subjects(&ada), vec!["Third", "First"]);
+
+        let middle = repo.list_commits_with(&LogOptions {
+            since: Some(2 * 86_400),
+            until: Some(3 * 86_400),
+            ..LogOptions::default()
+        })?;
+        assert_eq!(subjects(&middle), vec!["Third", "Second"]);
+
+        let page = repo.list_commits_with(&LogOptions {
+            author: Some("grace".to_string()),
+            skip: 1,
+            max_count: Some(1),
+            ..LogOptions::default()
+        })?;
+        assert_eq!(subjects(&page), vec!["Second"]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_commits_with_filters_by_path_and_reports_parents(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("lib.rs", "lib", "Add lib")?
+            .checkout_branch("master")?
+            .add_file_and_commit("guide.md", "guide", "Add guide")?;
+        repo.merge("feature", None)?;
+
+        let lib = repo.list_commits_with(&LogOptions {
+            paths: vec!["lib.rs".to_string()],
+            ..LogOptions::default()
+        })?;
+        assert_eq!(subjects(&lib), vec!["Add lib"]);
+
+        let all = repo.list_commits_with(&LogOptions {
+            rev: Some("master".to_string()),
+            ..LogOptions::default()
+        })?;
+        let merge = &all[0];
+        assert_eq!(merge.message.trim(), "Merge branch 'feature'");
+        let parents: Vec<&str> = ["Add guide", "Add lib"]
+            .iter()
+            .map(|subject| {
+                all.iter()
+                    .find(|commit| commit.message.trim() == *subject)
+                    .map(|commit| commit.hash.as_str())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(merge.parent_ids, parents);
+        assert!(all.last().unwrap().parent_ids.is_empty());
+        Ok(())
+    }
+}
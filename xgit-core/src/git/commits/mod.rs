@@ -0,0 +1,17 @@
+pub mod blame;
+pub mod builder;
+pub mod cherry_pick;
+pub mod commitlint;
+pub mod conventional;
+pub mod date;
+pub mod diff_stats;
+pub mod diff_summary;
+pub mod diff_text;
+pub mod file_history;
+pub mod history;
+pub mod log;
+pub mod operations;
+pub mod ownership;
+pub mod staging;
+pub mod status;
+pub mod word_diff;
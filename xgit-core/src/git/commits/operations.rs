@@ -0,0 +1,811 @@
+use std::path::Path;
+
+use anyhow::Context;
+use git2::{DiffOptions, Sort};
+
+use crate::git::commits::log::LogOptions;
+use crate::git::error::GitError;
+use crate::git::repository::core::{CommitInfo, GitRepo};
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+impl GitRepo {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn list_commits(&self) -> Result<Vec<CommitInfo>, GitError> {
+        // Check if repository has any commits
+        if self.repo().head().is_err() {
+            // No commits in repository, return empty vec
+            return Ok(Vec::new());
+        }
+
+        self.list_commits_from("HEAD")
+    }
+
+    /// List commits reachable from `rev`, newest first
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn list_commits_from(&self, rev: &str) -> Result<Vec<CommitInfo>, GitError> {
+        self.list_commits_range(rev, 0, None)
+    }
+
+    /// List commits selected by `range`, newest first, skipping the first `skip` and
+    /// returning at most `limit`.
+    ///
+    /// `range` is a single revision, `A..B` (reachable from B but not A) or `A...B`
+    /// (reachable from either but not both). The walk stops once `limit` commits are found.
+    #[tracing::instrument(level = "debug", skip(self, limit))]
+    pub fn list_commits_range(
+        &self,
+        range: &str,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        self.list_commits_with(&LogOptions {
+            rev: Some(range.to_string()),
+            skip,
+            max_count: limit,
+            ..LogOptions::default()
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn add(&self, pathspecs: &[&str]) -> Result<&Self, GitError> {
+        self.require_worktree("add files")?;
+        self.update_index(|index| {
+            index
+                .add_all(pathspecs, git2::IndexAddOption::DEFAULT, None)
+                .context("Failed to add files to index")?;
+            // add_all only picks up files that exist; update_all stages matching deletions
+            index
+                .update_all(pathspecs, None)
+                .context("Failed to stage deleted files")?;
+            Ok(())
+        })?;
+
+        Ok(self)
+    }
+
+    /// Stage the files at `paths`, relative to the worktree root, like `git add -- <path>...`
+    /// with every path taken literally: `*` and `?` are part of the name. A path that is gone
+    /// from disk stages its deletion; a path neither on disk nor in the index is an error.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn add_paths(&self, paths: &[&str]) -> Result<&Self, GitError> {
+        self.require_worktree("add files")?;
+        let workdir = self.path().to_path_buf();
+        self.update_index(|index| {
+            for path in paths {
+                if workdir.join(path).is_file() {
+                    index
+                        .add_path(Path::new(path))
+                        .context(format!("Failed to add '{path}'"))?;
+                } else if index.get_path(Path::new(path), 0).is_some() {
+                    index
+                        .remove_path(Path::new(path))
+                        .context(format!("Failed to stage the deletion of '{path}'"))?;
+                } else {
+                    return Err(GitError::NotFound(anyhow::anyhow!(
+                        "'{path}' is not a file in the working tree or the index"
+                    )));
+                }
+            }
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    /// Delete the tracked files at `paths` from the index and the working tree, like
+    /// `git rm -- <path>...`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn remove_paths(&self, paths: &[&str]) -> Result<&Self, GitError> {
+        self.require_worktree("remove files")?;
+        let workdir = self.path().to_path_buf();
+        self.update_index(|index| {
+            for path in paths {
+                if index.get_path(Path::new(path), 0).is_none() {
+                    return Err(GitError::NotFound(anyhow::anyhow!(
+                        "'{path}' is not tracked"
+                    )));
+                }
+                index
+                    .remove_path(Path::new(path))
+                    .context(format!("Failed to remove '{path}' from the index"))?;
+            }
+            Ok(())
+        })?;
+        for path in paths {
+            match std::fs::remove_file(workdir.join(path)) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(anyhow::Error::new(err)
+                        .context(format!("Failed to delete '{path}'"))
+                        .into())
+                }
+                _ => {}
+            }
+        }
+        Ok(self)
+    }
+
+    /// Put the index entries for `paths` back to how HEAD has them, keeping the working tree,
+    /// like `git restore --staged -- <path>...`. Before the first commit the paths are
+    /// dropped from the index.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn unstage_paths(&self, paths: &[&str]) -> Result<&Self, GitError> {
+        self.require_worktree("unstage files")?;
+        let head = self
+            .repo()
+            .head()
+            .ok()
+            .and_then(|head| head.peel(git2::ObjectType::Commit).ok());
+        match head {
+            Some(head) => {
+                self.wait_for_index_lock()?;
+                self.repo()
+                    .reset_default(Some(&head), paths)
+                    .context("Failed to unstage files")?;
+                self.emit(GitEvent::IndexUpdated);
+            }
+            None => {
+                self.update_index(|index| {
+                    for path in paths {
+                        index
+                            .remove_path(Path::new(path))
+                            .context(format!("Failed to unstage '{path}'"))?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Stage modifications and deletions of every tracked file, leaving untracked files
+    /// alone, like `git add -u`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stage_all_tracked(&self) -> Result<&Self, GitError> {
+        self.require_worktree("stage tracked files")?;
+        self.update_index(|index| {
+            index
+                .update_all(Vec::<&str>::new(), None)
+                .context("Failed to stage tracked files")?;
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_branch_commit_info(&self, branch: &str) -> Result<String, GitError> {
+        // Get the commit that the branch points to
+        let branch_ref = format!("refs/heads/{branch}");
+        let reference = self
+            .repo()
+            .find_reference(&branch_ref)
+            .context(format!("Failed to find branch reference: {branch_ref}"))?;
+        let commit_oid = reference
+            .target()
+            .ok_or_else(|| anyhow::anyhow!("Branch reference has no target"))?;
+        let commit = self
+            .repo()
+            .find_commit(commit_oid)
+            .context("Failed to find commit")?;
+
+        // Get short hash (first 7 characters)
+        let short_hash = commit.id().to_string()[..7].to_string();
+
+        // Get commit message (first line only)
+        let message = commit.message().unwrap_or("No commit message");
+        let first_line = message.lines().next().unwrap_or("No commit message");
+
+        Ok(format!("{short_hash} {first_line}"))
+    }
+
+    /// Check if there are any staged files in the index.
+    ///
+    /// Compares HEAD's tree with the index entry by entry instead of writing the index out as
+    /// a tree, so nothing is written to the object database and no file contents are read.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn has_staged_changes(&self) -> Result<bool, GitError> {
+        self.require_worktree("inspect staged changes")?;
+        let index = self
+            .repo()
+            .index()
+            .context("Failed to get repository index")?;
+
+        // If repository has no commits yet, any files in index are staged
+        let Ok(head) = self.repo().head() else {
+            return Ok(!index.is_empty());
+        };
+        let head_tree = head.peel_to_tree().context("Failed to get HEAD tree")?;
+
+        let mut options = DiffOptions::new();
+        options.skip_binary_check(true);
+        let diff = self
+            .repo()
+            .diff_tree_to_index(Some(&head_tree), Some(&index), Some(&mut options))
+            .context("Failed to compare HEAD with the index")?;
+
+        Ok(diff.deltas().len() > 0)
+    }
+
+    /// Get diff object of staged changes
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_staged_diff(&self) -> Result<git2::Diff<'_>, GitError> {
+        self.require_worktree("diff staged changes")?;
+        let index = self
+            .repo()
+            .index()
+            .context("Failed to get repository index")?;
+
+        let diff = if self.repo().head().is_err() {
+            // No commits yet, diff against empty tree
+            let empty_tree = self
+                .repo()
+                .treebuilder(None)?
+                .write()
+                .context("Failed to create empty tree")?;
+            let empty_tree = self.repo().find_tree(empty_tree)?;
+
+            self.repo()
+                .diff_tree_to_index(Some(&empty_tree), Some(&index), None)
+                .context("Failed to create diff from empty tree to index")?
+        } else {
+            // Compare HEAD tree with index
+            let head = self.repo().head().context("Failed to get HEAD")?;
+            let head_commit = head
+                .peel_to_commit()
+                .context("Failed to peel HEAD to commit")?;
+            let head_tree = head_commit.tree().context("Failed to get HEAD tree")?;
+
+            self.repo()
+                .diff_tree_to_index(Some(&head_tree), Some(&index), None)
+                .context("Failed to create diff from HEAD to index")?
+        };
+
+        Ok(diff)
+    }
+
+    /// Diff of what the tip commit would hold if amended with the index: HEAD's first parent
+    /// (or the empty tree for a root commit) against the index
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_amend_diff(&self) -> Result<git2::Diff<'_>, GitError> {
+        self.require_worktree("diff changes to amend")?;
+        let index = self
+            .repo()
+            .index()
+            .context("Failed to get repository index")?;
+        let head_commit = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("No commit to amend")?;
+        let parent_tree = match head_commit.parent(0) {
+            Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+            Err(_) => None,
+        };
+
+        self.repo()
+            .diff_tree_to_index(parent_tree.as_ref(), Some(&index), None)
+            .context("Failed to create diff from HEAD's parent to index")
+            .map_err(GitError::from)
+    }
+
+    /// Get diff object of the changes introduced by a commit (against its first parent)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_commit_diff(&self, rev: &str) -> Result<git2::Diff<'_>, GitError> {
+        let commit = self
+            .repo()
+            .revparse_single(rev)
+            .context(format!("Failed to resolve revision '{rev}'"))?
+            .peel_to_commit()
+            .context("Failed to peel revision to commit")?;
+        let tree = commit.tree().context("Failed to get commit tree")?;
+
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().context("Failed to get parent tree")?),
+            Err(_) => None, // Root commit, diff against empty tree
+        };
+
+        self.repo()
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .context("Failed to create diff from parent to commit")
+            .map_err(GitError::from)
+    }
+
+    /// Get diff object of the changes `head` makes since it branched off `base`, like
+    /// `git diff base...head`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_range_diff(&self, base: &str, head: &str) -> Result<git2::Diff<'_>, GitError> {
+        let resolve = |rev: &str| {
+            self.repo()
+                .revparse_single(rev)
+                .context(format!("Failed to resolve revision '{rev}'"))?
+                .peel_to_commit()
+                .context("Failed to peel revision to commit")
+        };
+        let base = resolve(base)?;
+        let head = resolve(head)?;
+        let merge_base = self
+            .repo()
+            .merge_base(base.id(), head.id())
+            .context("Failed to find the merge base")?;
+        let base_tree = self
+            .repo()
+            .find_commit(merge_base)
+            .and_then(|commit| commit.tree())
+            .context("Failed to get merge base tree")?;
+        let head_tree = head.tree().context("Failed to get commit tree")?;
+
+        self.repo()
+            .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)
+            .context("Failed to create diff from merge base to commit")
+            .map_err(GitError::from)
+    }
+
+    /// Generate diff string of staged changes (convenience method)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn diff_staged(&self) -> Result<String, GitError> {
+        let diff = self.get_staged_diff()?;
+        self.diff_to_string(&diff)
+    }
+
+    /// Return true when the working tree and index are both clean.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn is_working_tree_clean(&self) -> Result<bool, GitError> {
+        self.require_worktree("check working tree status")?;
+        let statuses = self
+            .repo()
+            .statuses(None)
+            .context("Failed to read repository status")?;
+        Ok(statuses.is_empty())
+    }
+
+    /// List commits in (base, head] order from oldest to newest.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn list_commits_between(&self, base: &str, head: &str) -> Result<Vec<String>, GitError> {
+        let mut revwalk = self.repo().revwalk().context("Failed to create revwalk")?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)
+            .context("Failed to set revwalk sorting")?;
+        revwalk
+            .push_range(&format!("{base}..{head}"))
+            .context("Failed to walk commit range")?;
+
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let oid = oid.context("Failed to read commit from range")?;
+            commits.push(oid.to_string());
+        }
+        Ok(commits)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_commit_message(&self, commit_sha: &str) -> Result<String, GitError> {
+        let oid = git2::Oid::from_str(commit_sha).context("Invalid commit SHA")?;
+        let commit = self
+            .repo()
+            .find_commit(oid)
+            .context("Failed to find commit")?;
+        Ok(commit.message().unwrap_or_default().to_string())
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_commit_subject(&self, commit_sha: &str) -> Result<String, GitError> {
+        let oid = git2::Oid::from_str(commit_sha).context("Invalid commit SHA")?;
+        let commit = self
+            .repo()
+            .find_commit(oid)
+            .context("Failed to find commit")?;
+        Ok(commit.summary().unwrap_or_default().to_string())
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_commit_parent_count(&self, commit_sha: &str) -> Result<usize, GitError> {
+        let oid = git2::Oid::from_str(commit_sha).context("Invalid commit SHA")?;
+        let commit = self
+            .repo()
+            .find_commit(oid)
+            .context("Failed to find commit")?;
+        Ok(commit.parent_count())
+    }
+
+    /// Create a synthetic child commit:
+    /// - parent is `parent_sha`
+    /// - tree/content is copied from `tree_source_sha`
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn create_synthetic_child_commit(
+        &self,
+        parent_sha: &str,
+        tree_source_sha: &str,
+        message: &str,
+    ) -> Result<String, GitError> {
+        let parent_oid = git2::Oid::from_str(parent_sha).context("Invalid parent commit SHA")?;
+        let parent = self
+            .repo()
+            .find_commit(parent_oid)
+            .context("Failed to find parent commit")?;
+
+        let source_oid =
+            git2::Oid::from_str(tree_source_sha).context("Invalid tree-source commit SHA")?;
+        let source_commit = self
+            .repo()
+            .find_commit(source_oid)
+            .context("Failed to find tree-source commit")?;
+        let source_tree = source_commit
+            .tree()
+            .context("Failed to get source commit tree")?;
+
+        let author = self
+            .commit_signature(Role::Author, None, None)
+            .context("Failed to create commit signature")?;
+        let committer = self
+            .commit_signature(Role::Committer, None, None)
+            .context("Failed to create commit signature")?;
+
+        let commit_id = self
+            .repo()
+            .commit(None, &author, &committer, message, &source_tree, &[&parent])
+            .context("Failed to create synthetic child commit")?;
+
+        Ok(commit_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    #[test]
+    fn add_and_remove_paths_take_names_literally() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("keep.txt", "keep\n", "Initial commit")?;
+        repo.add_file("a*.txt", "star\n")?;
+        repo.add_file("ab.txt", "ab\n")?;
+
+        repo.add_paths(&["a*.txt"])?;
+        let staged: Vec<String> = repo.status()?.staged.into_iter().map(|e| e.path).collect();
+        assert_eq!(staged, vec!["a*.txt"]);
+        assert!(repo.add_paths(&["missing.txt"]).is_err());
+
+        std::fs::remove_file(repo.path().join("keep.txt"))?;
+        repo.add_paths(&["keep.txt"])?;
+        assert_eq!(repo.status()?.counts().staged, 2);
+
+        repo.add_file_and_commit("gone.txt", "gone\n", "Add gone")?;
+        repo.remove_paths(&["gone.txt"])?;
+        assert!(!repo.path().join("gone.txt").exists());
+        assert!(repo
+            .status()?
+            .staged
+            .iter()
+            .any(|entry| entry.path == "gone.txt"));
+        assert!(repo.remove_paths(&["ab.txt"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn unstage_paths_restores_head_and_keeps_the_file() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file("first.txt", "first\n")?;
+        repo.add_paths(&["first.txt"])?;
+        repo.unstage_paths(&["first.txt"])?;
+        assert_eq!(repo.status()?.untracked, vec!["first.txt"]);
+
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        repo.append_to_file("a.txt", "more\n")?;
+        repo.add_file("new.txt", "new\n")?;
+        repo.add_paths(&["a.txt", "new.txt"])?;
+        repo.unstage_paths(&["a.txt", "new.txt"])?;
+
+        let status = repo.status()?;
+        assert!(status.staged.is_empty());
+        assert_eq!(status.unstaged.len(), 1);
+        assert!(status.untracked.contains(&"new.txt".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn stage_all_tracked_leaves_untracked_files() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?
+            .add_file_and_commit("b.txt", "b\n", "Add b")?;
+        repo.append_to_file("a.txt", "more\n")?;
+        std::fs::remove_file(repo.path().join("b.txt"))?;
+        repo.add_file("new.txt", "new\n")?;
+
+        repo.stage_all_tracked()?;
+        let status = repo.status()?;
+        assert_eq!(status.staged.len(), 2);
+        assert!(status.unstaged.is_empty());
+        assert_eq!(status.untracked, vec!["new.txt"]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_commits_works_in_repo_without_any_commit() {
+        let (_temp_dir, repo) = create_test_repo();
+
+        let commits = repo.list_commits().unwrap();
+
+        assert_eq!(commits.len(), 0);
+    }
+
+    #[test]
+    fn list_commits_works() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        let commits = repo.list_commits().unwrap();
+        assert_eq!(commits.len(), 0);
+
+        repo.add_file_and_commit("test_file_1.txt", "foo", "Test commit 1")?
+            .add_file_and_commit("test_file_2.txt", "foo", "Test commit 2")?
+            .add_file_and_commit("test_file_3.txt", "foo", "Test commit 3")?
+            .assert_commit_messages(&["Test commit 3", "Test commit 2", "Test commit 1"]);
+
+        let commits = repo.list_commits().unwrap();
+        assert_eq!(commits.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_commits_from_starts_at_revision() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Commit A")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("b.txt", "b", "Commit B")?
+            .checkout_branch("master")?;
+
+        let commits = repo.list_commits_from("feature")?;
+        let messages: Vec<&str> = commits.iter().map(|c| c.message.trim()).collect();
+        assert_eq!(messages, vec!["Commit B", "Commit A"]);
+        assert_eq!(commits[0].author_email, "test@example.com");
+        assert_eq!(repo.list_commits()?.len(), 1);
+        assert!(repo.list_commits_from("missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn list_commits_range_supports_ranges_skip_and_limit() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Commit A")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("b.txt", "b", "Commit B")?
+            .add_file_and_commit("c.txt", "c", "Commit C")?
+            .checkout_branch("master")?
+            .add_file_and_commit("d.txt", "d", "Commit D")?;
+
+        let subjects = |range: &str, skip: usize, limit: Option<usize>| {
+            repo.list_commits_range(range, skip, limit).map(|commits| {
+                commits
+                    .iter()
+                    .map(|c| c.message.trim().to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            subjects("master..feature", 0, None)?,
+            vec!["Commit C", "Commit B"]
+        );
+        assert_eq!(subjects("feature..", 0, None)?, vec!["Commit D"]);
+        let mut symmetric = subjects("master...feature", 0, None)?;
+        symmetric.sort();
+        assert_eq!(symmetric, vec!["Commit B", "Commit C", "Commit D"]);
+        assert_eq!(subjects("feature", 1, Some(1))?, vec!["Commit B"]);
+        assert_eq!(subjects("feature", 5, None)?, Vec::<String>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn add_works_for_single_file_path() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        let file_name = "test_file.txt";
+        repo.add_file(file_name, "foo")?.add(&[file_name])?;
+
+        // Verify the file is staged
+        let index = repo.repo().index().unwrap();
+        let entry = index.get_path(std::path::Path::new(file_name), 0);
+        assert!(entry.is_some(), "File should be in the index after adding");
+        Ok(())
+    }
+
+    #[test]
+    fn add_works_for_glob_patterns() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        repo.add_file("test_file_1.txt", "foo")?
+            .add_file("test_file_2.txt", "foo")?
+            .add_file("test_file_non_text.rs", "foo")?
+            .add(&["*.txt"])?;
+
+        // Verify the file is staged
+        let index = repo.repo().index().unwrap();
+        assert_eq!(index.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn add_stages_deleted_files() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("gone.txt", "foo", "Initial commit")?;
+
+        std::fs::remove_file(repo.path().join("gone.txt"))?;
+        repo.add(&["gone.txt"])?;
+
+        let index = repo.repo().index().unwrap();
+        assert!(index
+            .get_path(std::path::Path::new("gone.txt"), 0)
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn add_works_for_all_files() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        repo.add_file("test_file_1.txt", "foo")?
+            .add_file("test_file_2.txt", "foo")?
+            .add_file("test_file_non_text.rs", "foo")?
+            .add(&["."])?;
+
+        // Verify the file is staged
+        let index = repo.repo().index().unwrap();
+        assert_eq!(index.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn has_staged_changes_works() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        // Initially no staged changes
+        assert!(!repo.has_staged_changes().unwrap());
+
+        // Add a file without staging
+        repo.add_file("test.txt", "content")?;
+        assert!(!repo.has_staged_changes().unwrap());
+
+        // Stage the file
+        repo.add(&["test.txt"])?;
+        assert!(repo.has_staged_changes().unwrap());
+
+        // Commit the file
+        repo.commit("Initial commit")?;
+        assert!(!repo.has_staged_changes().unwrap());
+
+        // Add another file and stage it
+        repo.add_file("test2.txt", "content2")?
+            .add(&["test2.txt"])?;
+        assert!(repo.has_staged_changes().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn has_staged_changes_sees_deletions_and_reverted_edits(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "one\n", "Initial commit")?;
+
+        std::fs::remove_file(repo.path().join("a.txt"))?;
+        repo.add(&["a.txt"])?;
+        assert!(repo.has_staged_changes()?);
+
+        // Staging the original content again leaves nothing to commit
+        repo.add_file("a.txt", "one\n")?.add(&["a.txt"])?;
+        assert!(!repo.has_staged_changes()?);
+        Ok(())
+    }
+
+    #[test]
+    fn diff_staged_works() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        // No staged changes initially
+        let diff = repo.diff_staged().unwrap();
+        assert!(diff.is_empty());
+
+        // Add and stage a file
+        repo.add_file("test.txt", "Hello World")?
+            .add(&["test.txt"])?;
+
+        let diff = repo.diff_staged().unwrap();
+        assert!(diff.contains("Hello World"));
+        assert!(diff.contains("+Hello World"));
+
+        // Commit the file
+        repo.commit("Add test file")?;
+
+        // No staged changes after commit
+        let diff = repo.diff_staged().unwrap();
+        assert!(diff.is_empty());
+
+        // Modify and stage the file
+        repo.add_file("test.txt", "Hello World\nSecond line")?
+            .add(&["test.txt"])?;
+
+        let diff = repo.diff_staged().unwrap();
+        assert!(diff.contains("+Second line"));
+        Ok(())
+    }
+
+    #[test]
+    fn get_staged_diff_and_diff_to_string_work() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        // Add and stage a file
+        repo.add_file("test.txt", "Hello World")?
+            .add(&["test.txt"])?;
+
+        // Test get_staged_diff returns a Diff object
+        let diff_obj = repo.get_staged_diff().unwrap();
+        assert_eq!(diff_obj.deltas().len(), 1);
+
+        // Test diff_to_string converts the Diff to string
+        let diff_string = repo.diff_to_string(&diff_obj).unwrap();
+        assert!(diff_string.contains("Hello World"));
+
+        // Should be same as calling diff_staged directly
+        let direct_diff = repo.diff_staged().unwrap();
+        assert_eq!(diff_string, direct_diff);
+        Ok(())
+    }
+
+    #[test]
+    fn get_commit_diff_works() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        repo.add_file_and_commit("test.txt", "first", "Initial commit")?
+            .add_file_and_commit("test.txt", "second", "Update test file")?;
+
+        // Root commit diffs against the empty tree
+        let root_diff = repo.get_commit_diff("HEAD~1").unwrap();
+        assert!(repo.diff_to_string(&root_diff)?.contains("+first"));
+
+        let diff = repo.get_commit_diff("HEAD").unwrap();
+        let diff_text = repo.diff_to_string(&diff)?;
+        assert!(diff_text.contains("-first"));
+        assert!(diff_text.contains("+second"));
+
+        assert!(repo.get_commit_diff("nonexistent").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn get_range_diff_ignores_changes_made_on_base() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        repo.add_file_and_commit("base.txt", "base", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature", "Add feature")?
+            .checkout_branch("master")?
+            .add_file_and_commit("base.txt", "moved on", "Update base")?;
+
+        let diff_text = repo.diff_to_string(&repo.get_range_diff("master", "feature")?)?;
+        assert!(diff_text.contains("+feature"));
+        assert!(!diff_text.contains("moved on"));
+        Ok(())
+    }
+
+    #[test]
+    fn get_branch_commit_info_works() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        // Add initial commit
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?;
+
+        let commit_info = repo.get_branch_commit_info("master").unwrap();
+        assert!(commit_info.contains("Initial commit"));
+        assert!(commit_info.len() > 7); // Should have short hash + message
+
+        // Test with feature branch
+        repo.create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature content", "Add feature")?;
+
+        let feature_commit_info = repo.get_branch_commit_info("feature").unwrap();
+        assert!(feature_commit_info.contains("Add feature"));
+
+        // Test with non-existent branch
+        let result = repo.get_branch_commit_info("nonexistent");
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Context;
+use git2::{BlameOptions, ObjectType, TreeWalkMode, TreeWalkResult};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// Current lines of one file, grouped by the author who last changed them
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileOwnership {
+    pub path: String,
+    /// Authors with their line counts, most lines first
+    pub authors: Vec<AuthorLines>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorLines {
+    pub name: String,
+    pub email: String,
+    pub lines: usize,
+}
+
+impl GitRepo {
+    /// Blame every text file at HEAD under `path` (a file or directory relative to the
+    /// repository root; empty for the whole tree), sorted by path.
+    ///
+    /// Authors are mapped through `.mailmap` and grouped by email, case-insensitively.
+    /// Binary files are skipped.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_line_ownership(&self, path: &str) -> Result<Vec<FileOwnership>, GitError> {
+        let tree = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_tree())
+            .context("Failed to resolve HEAD tree")?;
+        let prefix = path.trim_matches('/');
+
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |parent, entry| {
+            if entry.kind() != Some(ObjectType::Blob) {
+                return TreeWalkResult::Ok;
+            }
+            let file = format!("{parent}{}", entry.name().unwrap_or_default());
+            let under_prefix = prefix.is_empty()
+                || file == prefix
+                || file
+                    .strip_prefix(prefix)
+                    .map_or(false, |rest| rest.starts_with('/'));
+            if under_prefix {
+                files.push((file, entry.id()));
+            }
+            TreeWalkResult::Ok
+        })
+        .context("Failed to walk HEAD tree")?;
+
+        let mut ownership = Vec::new();
+        for (file, blob_id) in files {
+            let blob = self
+                .repo()
+                .find_blob(blob_id)
+                .context(format!("Failed to read '{file}'"))?;
+            if blob.is_binary() || blob.content().is_empty() {
+                continue;
+            }
+
+            let blame = self
+                .repo()
+                .blame_file(
+                    Path::new(&file),
+                    Some(BlameOptions::new().use_mailmap(true)),
+                )
+                .context(format!("Failed to blame '{file}'"))?;
+
+            let mut authors: Vec<AuthorLines> = Vec::new();
+            let mut author_index: HashMap<String, usize> = HashMap::new();
+            for hunk in blame.iter() {
+                let signature = hunk.final_signature();
+                let email = String::from_utf8_lossy(signature.email_bytes()).to_string();
+                let index = *author_index.entry(email.to_lowercase()).or_insert_with(|| {
+                    authors.push(AuthorLines {
+                        name: String::from_utf8_lossy(signature.name_bytes()).to_string(),
+                        email,
+                        lines: 0,
+                    });
+                    authors.len() - 1
+                });
+                authors[index].lines += hunk.lines_in_hunk();
+            }
+            authors.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.name.cmp(&b.name)));
+
+            ownership.push(FileOwnership {
+                path: file,
+                authors,
+            });
+        }
+
+        ownership.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(ownership)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn get_line_ownership_counts_lines_per_author_under_a_directory(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        std::fs::create_dir_all(repo.path().join("src"))?;
+        repo.add_file_and_commit("src/lib.rs", "one\ntwo\nthree\n", "Add lib")?
+            .add_file_and_commit("README.md", "readme\n", "Add readme")?;
+        repo.set_user_config("Other Person", "other@example.com")?;
+        repo.append_to_file_and_commit("src/lib.rs", "four\n", "Extend lib")?;
+
+        let ownership = repo.get_line_ownership("src")?;
+        assert_eq!(ownership.len(), 1);
+        assert_eq!(ownership[0].path, "src/lib.rs");
+        let authors: Vec<_> = ownership[0]
+            .authors
+            .iter()
+            .map(|author| (author.email.as_str(), author.lines))
+            .collect();
+        assert_eq!(
+            authors,
+            vec![("test@example.com", 3), ("other@example.com", 1)]
+        );
+
+        assert_eq!(repo.get_line_ownership("")?.len(), 2);
+        assert!(repo.get_line_ownership("sr")?.is_empty());
+        Ok(())
+    }
+}
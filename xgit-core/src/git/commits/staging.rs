@@ -0,0 +1,380 @@
+use anyhow::Context;
+use git2::{Delta, DiffOptions, Patch};
+use std::path::Path;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// Kind of change a working tree file has relative to the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkdirChangeKind {
+    Modified,
+    Untracked,
+    Deleted,
+    Typechange,
+}
+
+/// A file with changes that are not yet staged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkdirChange {
+    pub path: String,
+    pub kind: WorkdirChangeKind,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// A file as it is staged in the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFile {
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+/// Size of a staged file, without reading its content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFileSize {
+    pub path: String,
+    pub size: u64,
+    /// Whether the file is new in this commit rather than a modification
+    pub added: bool,
+}
+
+/// Number of files in each state, as summarised by `git status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeCounts {
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+}
+
+impl ChangeCounts {
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl GitRepo {
+    /// Count staged, unstaged, untracked and conflicted files (a file can be both staged and unstaged)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn change_counts(&self) -> Result<ChangeCounts, GitError> {
+        Ok(self.status()?.counts())
+    }
+
+    /// List modified, deleted and untracked files that differ from the index, sorted by path
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_unstaged_changes(&self) -> Result<Vec<WorkdirChange>, GitError> {
+        self.require_worktree("list unstaged changes")?;
+        let mut options = DiffOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+
+        let diff = self
+            .repo()
+            .diff_index_to_workdir(None, Some(&mut options))
+            .context("Failed to diff index against working tree")?;
+
+        let mut changes = Vec::new();
+        for (idx, delta) in diff.deltas().enumerate() {
+            let kind = match delta.status() {
+                Delta::Modified => WorkdirChangeKind::Modified,
+                Delta::Untracked | Delta::Added => WorkdirChangeKind::Untracked,
+                Delta::Deleted => WorkdirChangeKind::Deleted,
+                Delta::Typechange => WorkdirChangeKind::Typechange,
+                _ => continue,
+            };
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+
+            let (insertions, deletions) = match Patch::from_diff(&diff, idx)
+                .context("Failed to build patch for working tree change")?
+            {
+                Some(patch) => {
+                    let (_, insertions, deletions) = patch
+                        .line_stats()
+                        .context("Failed to count changed lines")?;
+                    (insertions, deletions)
+                }
+                None => (0, 0),
+            };
+
+            changes.push(WorkdirChange {
+                path: path.to_string_lossy().to_string(),
+                kind,
+                insertions,
+                deletions,
+            });
+        }
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(changes)
+    }
+
+    /// Diff of a single working tree file against the index, including untracked files
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_unstaged_file_diff(&self, path: &str) -> Result<git2::Diff<'_>, GitError> {
+        self.require_worktree("diff unstaged changes")?;
+        let mut options = DiffOptions::new();
+        options
+            .pathspec(path)
+            .disable_pathspec_match(true)
+            .include_untracked(true)
+            .show_untracked_content(true);
+
+        self.repo()
+            .diff_index_to_workdir(None, Some(&mut options))
+            .context(format!("Failed to diff '{path}' against the index"))
+            .map_err(GitError::from)
+    }
+
+    /// Added and modified files in the index with their staged content, sorted by path.
+    ///
+    /// Deleted files are left out since there is nothing left to check in them.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_staged_files(&self) -> Result<Vec<StagedFile>, GitError> {
+        let diff = self.get_staged_diff()?;
+        let mut files = Vec::new();
+        for delta in diff.deltas() {
+            if delta.status() == Delta::Deleted {
+                continue;
+            }
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+            let blob = self
+                .repo()
+                .find_blob(delta.new_file().id())
+                .context(format!("Failed to read staged '{}'", path.display()))?;
+            files.push(StagedFile {
+                path: path.to_string_lossy().to_string(),
+                content: blob.content().to_vec(),
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Added and modified files in the index with their staged size in bytes, sorted by path
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_staged_file_sizes(&self) -> Result<Vec<StagedFileSize>, GitError> {
+        let diff = self.get_staged_diff()?;
+        let odb = self
+            .repo()
+            .odb()
+            .context("Failed to open object database")?;
+        let mut files = Vec::new();
+        for delta in diff.deltas() {
+            if delta.status() == Delta::Deleted {
+                continue;
+            }
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+            let (size, _) = odb
+                .read_header(delta.new_file().id())
+                .context(format!("Failed to read staged '{}'", path.display()))?;
+            files.push(StagedFileSize {
+                path: path.to_string_lossy().to_string(),
+                size: size as u64,
+                added: delta.status() == Delta::Added,
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Tracked files that the current ignore rules would ignore, sorted by path
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_ignored_tracked_files(&self) -> Result<Vec<String>, GitError> {
+        self.require_worktree("check ignored files")?;
+        let index = self
+            .repo()
+            .index()
+            .context("Failed to get repository index")?;
+        let mut ignored = Vec::new();
+        for entry in index.iter() {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            if self
+                .repo()
+                .status_should_ignore(Path::new(&path))
+                .unwrap_or(false)
+                && !ignored.contains(&path)
+            {
+                ignored.push(path);
+            }
+        }
+        ignored.sort();
+        Ok(ignored)
+    }
+
+    /// Remove `paths` from the index but keep them on disk, like `git rm --cached`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn untrack(&self, paths: &[&str]) -> Result<&Self, GitError> {
+        self.require_worktree("untrack files")?;
+        self.update_index(|index| {
+            for path in paths {
+                index
+                    .remove_path(Path::new(path))
+                    .context(format!("Failed to untrack '{path}'"))?;
+            }
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    /// Whether a working tree path (absolute or relative to the repository root) is gitignored
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn is_path_ignored(&self, path: &Path) -> bool {
+        let Some(workdir) = self.workdir() else {
+            return false;
+        };
+        let relative = path.strip_prefix(workdir).unwrap_or(path);
+        if relative.is_absolute() {
+            return false;
+        }
+        self.repo().status_should_ignore(relative).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeCounts, WorkdirChangeKind};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn get_unstaged_changes_reports_kinds_and_line_counts() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("tracked.txt", "one\ntwo\n", "Initial commit")?
+            .add_file_and_commit("gone.txt", "bye\n", "Add file to delete")?;
+
+        repo.append_to_file("tracked.txt", "three\n")?;
+        std::fs::create_dir_all(repo.path().join("new"))?;
+        repo.add_file("new/untracked.txt", "hello\nworld\n")?;
+        std::fs::remove_file(repo.path().join("gone.txt"))?;
+
+        let changes = repo.get_unstaged_changes()?;
+        let summary: Vec<(&str, WorkdirChangeKind, usize, usize)> = changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind, c.insertions, c.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("gone.txt", WorkdirChangeKind::Deleted, 0, 1),
+                ("new/untracked.txt", WorkdirChangeKind::Untracked, 2, 0),
+                ("tracked.txt", WorkdirChangeKind::Modified, 1, 0),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn change_counts_summarises_status() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert!(repo.change_counts()?.is_clean());
+
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?
+            .add_file_and_commit("b.txt", "b\n", "Add b")?;
+        repo.add_file("new.txt", "new\n")?;
+        repo.add(&["new.txt"])?;
+        repo.append_to_file("a.txt", "more\n")?;
+        repo.add_file("untracked.txt", "?\n")?;
+
+        assert_eq!(
+            repo.change_counts()?,
+            ChangeCounts {
+                staged: 1,
+                unstaged: 1,
+                untracked: 1,
+                conflicted: 0,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_unstaged_file_diff_only_includes_requested_path(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        repo.append_to_file("a.txt", "more\n")?;
+        repo.add_file("b.txt", "b\n")?;
+
+        let diff = repo.get_unstaged_file_diff("b.txt")?;
+        assert_eq!(diff.deltas().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn get_staged_files_reads_index_content_and_skips_deletions(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("kept.txt", "one\n", "Initial commit")?
+            .add_file_and_commit("gone.txt", "bye\n", "Add file to delete")?;
+
+        repo.add_file("kept.txt", "staged\n")?;
+        repo.add_file("new.txt", "new\n")?;
+        repo.add(&["kept.txt", "new.txt"])?;
+        std::fs::remove_file(repo.path().join("gone.txt"))?;
+        repo.add(&["gone.txt"])?;
+        // Unstaged edits do not show up
+        repo.add_file("kept.txt", "unstaged\n")?;
+
+        let files = repo.get_staged_files()?;
+        let summary: Vec<(&str, &[u8])> = files
+            .iter()
+            .map(|file| (file.path.as_str(), file.content.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("kept.txt", &b"staged\n"[..]), ("new.txt", &b"new\n"[..])]
+        );
+
+        let sizes: Vec<_> = repo
+            .get_staged_file_sizes()?
+            .into_iter()
+            .map(|file| (file.path, file.size, file.added))
+            .collect();
+        assert_eq!(
+            sizes,
+            vec![
+                ("kept.txt".to_string(), 7, false),
+                ("new.txt".to_string(), 4, true)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn untrack_keeps_ignored_files_on_disk() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        std::fs::create_dir_all(repo.path().join("build"))?;
+        repo.add_file_and_commit("build/out.o", "binary", "Commit build output")?
+            .add_file_and_commit("src.c", "int main;\n", "Add source")?;
+        assert!(repo.get_ignored_tracked_files()?.is_empty());
+
+        repo.add_file(".gitignore", "build/\n")?;
+        assert_eq!(repo.get_ignored_tracked_files()?, vec!["build/out.o"]);
+
+        repo.untrack(&["build/out.o"])?;
+        assert!(repo.get_ignored_tracked_files()?.is_empty());
+        assert!(repo.path().join("build/out.o").exists());
+        assert!(repo.has_staged_changes()?);
+        Ok(())
+    }
+
+    #[test]
+    fn is_path_ignored_respects_gitignore() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file(".gitignore", "target/\n")?;
+
+        assert!(repo.is_path_ignored(&repo.path().join("target/debug/xg")));
+        assert!(repo.is_path_ignored(std::path::Path::new("target/debug/xg")));
+        assert!(!repo.is_path_ignored(&repo.path().join("src/main.rs")));
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::Context;
+use git2::{Delta, DiffDelta, Status, StatusOptions};
+
+use crate::git::commits::staging::ChangeCounts;
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// How a file changed, between HEAD and the index or between the index and the working tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Typechange,
+}
+
+/// One changed file in a [`RepoStatus`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    pub path: String,
+    pub kind: FileChangeKind,
+    /// Where the file was before a rename
+    pub old_path: Option<String>,
+}
+
+/// The working tree and index compared with HEAD, as `git status` shows them. A file with
+/// staged changes and further unstaged edits is listed in both `staged` and `unstaged`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoStatus {
+    /// Changes in the index relative to HEAD
+    pub staged: Vec<StatusEntry>,
+    /// Changes to tracked files in the working tree relative to the index
+    pub unstaged: Vec<StatusEntry>,
+    /// Files git does not track and does not ignore
+    pub untracked: Vec<String>,
+    /// Files with unresolved merge conflicts
+    pub conflicted: Vec<String>,
+}
+
+impl RepoStatus {
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Number of files in each section
+    pub fn counts(&self) -> ChangeCounts {
+        ChangeCounts {
+            staged: self.staged.len(),
+            unstaged: self.unstaged.len(),
+            untracked: self.untracked.len(),
+            conflicted: self.conflicted.len(),
+        }
+    }
+}
+
+impl GitRepo {
+    /// Staged, unstaged, untracked and conflicted files, each section sorted by path.
+    ///
+    /// Renames are detected between HEAD and the index, like `git status` does.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn status(&self) -> Result<RepoStatus, GitError> {
+        self.require_worktree("read working tree status")?;
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .renames_head_to_index(true);
+        let statuses = self
+            .repo()
+            .statuses(Some(&mut options))
+            .context("Failed to read repository status")?;
+
+        let mut status = RepoStatus::default();
+        for entry in statuses.iter() {
+            let flags = entry.status();
+            let path = entry.path().unwrap_or_default().to_string();
+            if flags.is_conflicted() {
+                status.conflicted.push(path);
+                continue;
+            }
+            if flags.is_wt_new() {
+                status.untracked.push(path.clone());
+            }
+            if let Some(kind) = staged_kind(flags) {
+                status
+                    .staged
+                    .push(status_entry(entry.head_to_index(), &path, kind));
+            }
+            if let Some(kind) = unstaged_kind(flags) {
+                status
+                    .unstaged
+                    .push(status_entry(entry.index_to_workdir(), &path, kind));
+            }
+        }
+
+        status.staged.sort_by(|a, b| a.path.cmp(&b.path));
+        status.unstaged.sort_by(|a, b| a.path.cmp(&b.path));
+        status.untracked.sort();
+        status.conflicted.sort();
+        Ok(status)
+    }
+}
+
+fn staged_kind(flags: Status) -> Option<FileChangeKind> {
+    if flags.is_index_renamed() {
+        Some(FileChangeKind::Renamed)
+    } else if flags.is_index_new() {
+        Some(FileChangeKind::Added)
+    } else if flags.is_index_deleted() {
+        Some(FileChangeKind::Deleted)
+    } else if flags.is_index_typechange() {
+        Some(FileChangeKind::Typechange)
+    } else if flags.is_index_modified() {
+        Some(FileChangeKind::Modified)
+    } else {
+        None
+    }
+}
+
+fn unstaged_kind(flags: Status) -> Option<FileChangeKind> {
+    if flags.is_wt_renamed() {
+        Some(FileChangeKind::Renamed)
+    } else if flags.is_wt_deleted() {
+        Some(FileChangeKind::Deleted)
+    } else if flags.is_wt_typechange() {
+        Some(FileChangeKind::Typechange)
+    } else if flags.is_wt_modified() {
+        Some(FileChangeKind::Modified)
+    } else {
+        None
+    }
+}
+
+/// The entry for `path`, taking the new path and the old one of a rename from `delta`
+fn status_entry(delta: Option<DiffDelta<'_>>, path: &str, kind: FileChangeKind) -> StatusEntry {
+    let file_path =
+        |file: git2::DiffFile<'_>| file.path().map(|path| path.to_string_lossy().into_owned());
+    match delta {
+        Some(delta) if delta.status() == Delta::Renamed => StatusEntry {
+            path: file_path(delta.new_file()).unwrap_or_else(|| path.to_string()),
+            kind,
+            old_path: file_path(delta.old_file()),
+        },
+        _ => StatusEntry {
+            path: path.to_string(),
+            kind,
+            old_path: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileChangeKind, StatusEntry};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::fs;
+
+    fn entry(path: &str, kind: FileChangeKind) -> StatusEntry {
+        StatusEntry {
+            path: path.to_string(),
+            kind,
+            old_path: None,
+        }
+    }
+
+    #[test]
+    fn status_sorts_changes_into_sections() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert!(repo.status()?.is_clean());
+
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?
+            .add_file_and_commit("b.txt", "b\n", "Add b")?
+            .add_file_and_commit(
+                "old.txt",
+                "a file long enough to be recognised\n",
+                "Add old",
+            )?;
+        repo.add_file("new.txt", "new\n")?;
+        repo.append_to_file("a.txt", "staged\n")?;
+        repo.add(&["new.txt", "a.txt"])?;
+        repo.append_to_file("a.txt", "unstaged\n")?;
+        fs::remove_file(repo.path().join("b.txt"))?;
+        fs::rename(repo.path().join("old.txt"), repo.path().join("moved.txt"))?;
+        repo.add(&["old.txt", "moved.txt"])?;
+        repo.add_file("untracked.txt", "?\n")?;
+
+        let status = repo.status()?;
+        assert_eq!(
+            status.staged,
+            vec![
+                entry("a.txt", FileChangeKind::Modified),
+                StatusEntry {
+                    old_path: Some("old.txt".to_string()),
+                    ..entry("moved.txt", FileChangeKind::Renamed)
+                },
+                entry("new.txt", FileChangeKind::Added),
+            ]
+        );
+        assert_eq!(
+            status.unstaged,
+            vec![
+                entry("a.txt", FileChangeKind::Modified),
+                entry("b.txt", FileChangeKind::Deleted),
+            ]
+        );
+        assert_eq!(status.untracked, vec!["untracked.txt".to_string()]);
+        assert!(status.conflicted.is_empty());
+        assert_eq!(status.counts().staged, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn status_lists_conflicted_files_once() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("shared.txt", "base\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("shared.txt", "feature\n", "Edit on feature")?
+            .checkout_branch("master")?
+            .add_file_and_commit("shared.txt", "master\n", "Edit on master")?;
+        assert!(matches!(
+            repo.merge("feature", None)?,
+            crate::MergeOutcome::Conflicts(_)
+        ));
+
+        let status = repo.status()?;
+        assert_eq!(status.conflicted, vec!["shared.txt".to_string()]);
+        assert!(status.staged.is_empty());
+        assert!(status.unstaged.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! Word-level changes within modified lines.
+//!
+//! Lines are split into word, whitespace and punctuation tokens and compared with a
+//! longest-common-subsequence, so `let value = 1;` → `let value = 42;` reports just `1`/`42`.
+
+use std::ops::Range;
+
+/// Lines with more tokens than this are treated as changed as a whole
+const MAX_WORD_DIFF_TOKENS: usize = 256;
+
+/// Compute the byte ranges that differ between two versions of a line.
+///
+/// Both results are empty when either line is too long to diff token by token.
+pub fn changed_ranges(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    // Very long lines are emphasized as a whole rather than diffed token by token
+    if old_tokens.len() > MAX_WORD_DIFF_TOKENS || new_tokens.len() > MAX_WORD_DIFF_TOKENS {
+        return (Vec::new(), Vec::new());
+    }
+
+    let (old_common, new_common) = longest_common_subsequence(&old_tokens, &new_tokens);
+    (
+        uncommon_ranges(&old_tokens, &old_common),
+        uncommon_ranges(&new_tokens, &new_common),
+    )
+}
+
+/// Render a modified line the way `git diff --word-diff=plain` does, e.g.
+/// `let value = [-1-]{+42+};`. Returns `None` for lines too long to diff by word.
+pub fn render_word_diff(old: &str, new: &str) -> Option<String> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > MAX_WORD_DIFF_TOKENS || new_tokens.len() > MAX_WORD_DIFF_TOKENS {
+        return None;
+    }
+    let (old_common, new_common) = longest_common_subsequence(&old_tokens, &new_tokens);
+
+    let mut rendered = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old_tokens.len() || j < new_tokens.len() {
+        let removed_start = i;
+        while i < old_tokens.len() && !old_common[i] {
+            i += 1;
+        }
+        let added_start = j;
+        while j < new_tokens.len() && !new_common[j] {
+            j += 1;
+        }
+        if i > removed_start {
+            rendered.push_str(&format!("[-{}-]", old_tokens[removed_start..i].concat()));
+        }
+        if j > added_start {
+            rendered.push_str(&format!("{{+{}+}}", new_tokens[added_start..j].concat()));
+        }
+        // Common tokens line up one-to-one
+        if i < old_tokens.len() && j < new_tokens.len() {
+            rendered.push_str(new_tokens[j]);
+            i += 1;
+            j += 1;
+        }
+    }
+    Some(rendered)
+}
+
+/// Split a line into word, whitespace and punctuation tokens
+pub fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((idx, ch)) = chars.next() {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let end = if is_word(ch) || ch.is_whitespace() {
+            let same_class = |c: char| {
+                if is_word(ch) {
+                    is_word(c)
+                } else {
+                    c.is_whitespace()
+                }
+            };
+            let mut end = idx + ch.len_utf8();
+            while let Some(&(next_idx, next)) = chars.peek() {
+                if !same_class(next) {
+                    break;
+                }
+                end = next_idx + next.len_utf8();
+                chars.next();
+            }
+            end
+        } else {
+            idx + ch.len_utf8()
+        };
+
+        tokens.push(&line[start..end]);
+        start = end;
+    }
+
+    tokens
+}
+
+fn longest_common_subsequence(old: &[&str], new: &[&str]) -> (Vec<bool>, Vec<bool>) {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_common = vec![false; old.len()];
+    let mut new_common = vec![false; new.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            old_common[i] = true;
+            new_common[j] = true;
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    (old_common, new_common)
+}
+
+fn uncommon_ranges(tokens: &[&str], common: &[bool]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut offset = 0;
+
+    for (token, is_common) in tokens.iter().zip(common) {
+        let end = offset + token.len();
+        if !is_common {
+            match ranges.last_mut() {
+                Some(last) if last.end == offset => last.end = end,
+                _ => ranges.push(offset..end),
+            }
+        }
+        offset = end;
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_ranges, render_word_diff, tokenize};
+
+    #[test]
+    fn tokenize_splits_words_whitespace_and_punctuation() {
+        assert_eq!(
+            tokenize("let foo_bar = baz(1);"),
+            vec!["let", " ", "foo_bar", " ", "=", " ", "baz", "(", "1", ")", ";"]
+        );
+    }
+
+    #[test]
+    fn changed_ranges_marks_only_modified_words() {
+        let (old, new) = changed_ranges("let value = 1;", "let value = 42;");
+        assert_eq!(old, vec![12..13]);
+        assert_eq!(new, vec![12..14]);
+    }
+
+    #[test]
+    fn render_word_diff_uses_git_plain_markers() {
+        assert_eq!(
+            render_word_diff("let value = 1;", "let value = 42;").unwrap(),
+            "let value = [-1-]{+42+};"
+        );
+        assert_eq!(
+            render_word_diff("call(old)", "call(old, new)").unwrap(),
+            "call(old{+, new+})"
+        );
+        assert_eq!(render_word_diff("a b", "a").unwrap(), "a[- b-]");
+        assert!(render_word_diff(&"x ".repeat(200), "y").is_none());
+    }
+}
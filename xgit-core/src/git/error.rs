@@ -0,0 +1,202 @@
+use thiserror::Error;
+
+/// Error returned by [`GitRepo`](crate::git::GitRepo) operations.
+///
+/// The variant says what kind of failure happened so callers can react to specific cases;
+/// the wrapped error keeps the full context chain, including the underlying [`git2::Error`]
+/// when there is one (see [`GitError::git2_code`]).
+#[derive(Debug, Error)]
+pub enum GitError {
+    /// The path is not inside a git repository
+    #[error("{0:#}")]
+    NotARepo(anyhow::Error),
+    /// A branch, commit, remote or other object does not exist
+    #[error("{0:#}")]
+    NotFound(anyhow::Error),
+    /// The operation stopped on merge or checkout conflicts
+    #[error("{0:#}")]
+    Conflict(anyhow::Error),
+    /// Uncommitted changes would be affected; the message explains how to proceed
+    #[error("{0}")]
+    DirtyWorktree(String),
+    /// HEAD points at a branch that has no commits yet
+    #[error("{0:#}")]
+    UnbornBranch(anyhow::Error),
+    /// Another git process holds the index or a ref lock
+    #[error("{0:#}")]
+    Locked(anyhow::Error),
+    /// The operation needs a working tree but the repository is bare
+    #[error("{0:#}")]
+    UnsupportedOnBareRepo(anyhow::Error),
+    /// The remote rejected or asked for credentials
+    #[error("{0:#}")]
+    AuthRequired(anyhow::Error),
+    /// The remote could not be reached
+    #[error("{0:#}")]
+    Network(anyhow::Error),
+    #[error("{0:#}")]
+    Other(anyhow::Error),
+}
+
+impl GitError {
+    /// The libgit2 error code behind this error, if it came from libgit2
+    pub fn git2_code(&self) -> Option<git2::ErrorCode> {
+        self.git2_error().map(git2::Error::code)
+    }
+
+    /// The libgit2 error behind this error, if it came from libgit2
+    pub fn git2_error(&self) -> Option<&git2::Error> {
+        let inner = match self {
+            GitError::DirtyWorktree(_) => return None,
+            GitError::NotARepo(err)
+            | GitError::NotFound(err)
+            | GitError::Conflict(err)
+            | GitError::UnbornBranch(err)
+            | GitError::Locked(err)
+            | GitError::UnsupportedOnBareRepo(err)
+            | GitError::AuthRequired(err)
+            | GitError::Network(err)
+            | GitError::Other(err) => err,
+        };
+        inner.chain().find_map(|cause| {
+            cause.downcast_ref::<git2::Error>().or_else(|| {
+                cause
+                    .downcast_ref::<GitError>()
+                    .and_then(GitError::git2_error)
+            })
+        })
+    }
+}
+
+impl From<anyhow::Error> for GitError {
+    fn from(err: anyhow::Error) -> Self {
+        // Context added on top of a GitError keeps the original kind
+        let kind = err.chain().find_map(|cause| {
+            if let Some(git_error) = cause.downcast_ref::<GitError>() {
+                return Some(Kind::of(git_error));
+            }
+            cause.downcast_ref::<git2::Error>().map(Kind::of_git2)
+        });
+
+        match kind {
+            Some(Kind::NotARepo) => GitError::NotARepo(err),
+            Some(Kind::NotFound) => GitError::NotFound(err),
+            Some(Kind::Conflict) => GitError::Conflict(err),
+            Some(Kind::DirtyWorktree) => GitError::DirtyWorktree(format!("{err:#}")),
+            Some(Kind::UnbornBranch) => GitError::UnbornBranch(err),
+            Some(Kind::Locked) => GitError::Locked(err),
+            Some(Kind::UnsupportedOnBareRepo) => GitError::UnsupportedOnBareRepo(err),
+            Some(Kind::AuthRequired) => GitError::AuthRequired(err),
+            Some(Kind::Network) => GitError::Network(err),
+            Some(Kind::Other) | None => GitError::Other(err),
+        }
+    }
+}
+
+impl From<git2::Error> for GitError {
+    fn from(err: git2::Error) -> Self {
+        anyhow::Error::new(err).into()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    NotARepo,
+    NotFound,
+    Conflict,
+    DirtyWorktree,
+    UnbornBranch,
+    Locked,
+    UnsupportedOnBareRepo,
+    AuthRequired,
+    Network,
+    Other,
+}
+
+impl Kind {
+    fn of(err: &GitError) -> Self {
+        match err {
+            GitError::NotARepo(_) => Kind::NotARepo,
+            GitError::NotFound(_) => Kind::NotFound,
+            GitError::Conflict(_) => Kind::Conflict,
+            GitError::DirtyWorktree(_) => Kind::DirtyWorktree,
+            GitError::UnbornBranch(_) => Kind::UnbornBranch,
+            GitError::Locked(_) => Kind::Locked,
+            GitError::UnsupportedOnBareRepo(_) => Kind::UnsupportedOnBareRepo,
+            GitError::AuthRequired(_) => Kind::AuthRequired,
+            GitError::Network(_) => Kind::Network,
+            GitError::Other(_) => Kind::Other,
+        }
+    }
+
+    fn of_git2(err: &git2::Error) -> Self {
+        use git2::{ErrorClass, ErrorCode};
+
+        match (err.code(), err.class()) {
+            (ErrorCode::Auth | ErrorCode::Certificate, _) => Kind::AuthRequired,
+            (ErrorCode::Conflict | ErrorCode::MergeConflict | ErrorCode::Unmerged, _) => {
+                Kind::Conflict
+            }
+            (ErrorCode::Uncommitted | ErrorCode::Modified | ErrorCode::IndexDirty, _) => {
+                Kind::DirtyWorktree
+            }
+            (ErrorCode::UnbornBranch, _) => Kind::UnbornBranch,
+            (ErrorCode::Locked, _) => Kind::Locked,
+            (ErrorCode::BareRepo, _) => Kind::UnsupportedOnBareRepo,
+            (ErrorCode::NotFound, ErrorClass::Repository) => Kind::NotARepo,
+            (ErrorCode::NotFound, _) => Kind::NotFound,
+            (_, ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssh | ErrorClass::Ssl) => {
+                Kind::Network
+            }
+            _ => Kind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitError;
+    use crate::git::GitRepo;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use anyhow::Context;
+
+    #[test]
+    fn open_outside_a_repo_is_not_a_repo() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let err = GitRepo::open(temp_dir.path()).err().unwrap();
+
+        assert!(matches!(err, GitError::NotARepo(_)));
+        assert_eq!(err.git2_code(), Some(git2::ErrorCode::NotFound));
+    }
+
+    #[test]
+    fn missing_objects_and_unborn_branches_are_distinguished() {
+        let (_dir, repo) = create_test_repo();
+
+        let err = repo.get_branch_commit_info("nope").err().unwrap();
+        assert!(matches!(err, GitError::NotFound(_)), "got {err:?}");
+
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+        repo.repo().set_head("refs/heads/orphan").unwrap();
+        let err = repo.merge("master", None).err().unwrap();
+        assert!(matches!(err, GitError::UnbornBranch(_)), "got {err:?}");
+    }
+
+    #[test]
+    fn context_keeps_the_kind_and_the_message_chain() {
+        let conflict = git2::Error::new(
+            git2::ErrorCode::MergeConflict,
+            git2::ErrorClass::Merge,
+            "conflicts",
+        );
+        let err: GitError = Err::<(), _>(GitError::from(conflict))
+            .context("Failed to merge")
+            .unwrap_err()
+            .into();
+
+        assert!(matches!(err, GitError::Conflict(_)));
+        assert!(err.to_string().starts_with("Failed to merge: conflicts"));
+        assert_eq!(err.git2_code(), Some(git2::ErrorCode::MergeConflict));
+    }
+}
@@ -0,0 +1,221 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Context;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// Where a commit's message came from, passed to `prepare-commit-msg` the way git does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MessageSource {
+    /// Given by the caller, like `git commit -m`
+    Message,
+    /// Reused from commit `id`, like `git commit --amend`
+    Commit(String),
+}
+
+impl MessageSource {
+    fn args(&self) -> Vec<&str> {
+        match self {
+            MessageSource::Message => vec!["message"],
+            MessageSource::Commit(id) => vec!["commit", id],
+        }
+    }
+}
+
+impl GitRepo {
+    /// Run hook `name` from [`GitRepo::hooks_dir`] if it is installed, failing when it exits
+    /// non-zero; returns whether it ran.
+    ///
+    /// Hooks run in the worktree root with `GIT_INDEX_FILE` set and `GIT_EDITOR=:`, as git
+    /// runs them for a commit that opens no editor.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn run_hook(&self, name: &str, args: &[&str]) -> Result<bool, GitError> {
+        let hook = self.hooks_dir().join(name);
+        if !is_executable(&hook) {
+            return Ok(false);
+        }
+        let status = Command::new(&hook)
+            .args(args)
+            .current_dir(self.path())
+            .env("GIT_INDEX_FILE", self.git_dir().join("index"))
+            .env("GIT_EDITOR", ":")
+            .status()
+            .context(format!("Failed to run {name} hook"))?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("{name} hook failed ({status})").into());
+        }
+        Ok(true)
+    }
+
+    /// Write `message` to `COMMIT_EDITMSG` and run `prepare-commit-msg` on it, then
+    /// `commit-msg` when `verify` is set; returns the message as the hooks left it, cleaned up
+    /// like `git commit -m` does (trailing whitespace and extra blank lines removed)
+    pub(crate) fn run_message_hooks(
+        &self,
+        message: &str,
+        source: &MessageSource,
+        verify: bool,
+    ) -> Result<String, GitError> {
+        let path = self.git_dir().join("COMMIT_EDITMSG");
+        let mut contents = message.to_string();
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        std::fs::write(&path, contents).context("Failed to write COMMIT_EDITMSG")?;
+
+        let file = path.to_string_lossy();
+        let mut args = vec![file.as_ref()];
+        args.extend(source.args());
+        let mut ran = self.run_hook("prepare-commit-msg", &args)?;
+        if verify {
+            ran |= self.run_hook("commit-msg", &[&file])?;
+        }
+        if !ran {
+            return Ok(message.to_string());
+        }
+        let edited = std::fs::read_to_string(&path).context("Failed to read COMMIT_EDITMSG")?;
+        git2::message_prettify(edited, None)
+            .context("Failed to clean up the commit message")
+            .map_err(GitError::from)
+    }
+
+    /// Run `post-commit`. The commit already exists, so like git a failing hook is only
+    /// logged.
+    pub(crate) fn run_post_commit_hook(&self) {
+        if let Err(err) = self.run_hook("post-commit", &[]) {
+            tracing::warn!(%err, "post-commit hook failed");
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().map_or(false, |metadata| {
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    })
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::git::commits::builder::CommitOptions;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    fn install_hook(repo_hooks: &Path, name: &str, script: &str) {
+        fs::create_dir_all(repo_hooks).unwrap();
+        let path = repo_hooks.join(name);
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn commit_runs_every_hook_with_git_arguments() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo();
+        let hooks = repo.hooks_dir();
+        let log = temp_dir.path().join("hooks.log");
+        let log = log.to_str().unwrap();
+        install_hook(
+            &hooks,
+            "pre-commit",
+            &format!("#!/bin/sh\necho \"pre-commit $GIT_EDITOR\" >> {log}\n"),
+        );
+        install_hook(
+            &hooks,
+            "prepare-commit-msg",
+            &format!("#!/bin/sh\necho \"prepare-commit-msg $2 $3\" >> {log}\necho 'Prepared: yes' >> \"$1\"\n"),
+        );
+        install_hook(
+            &hooks,
+            "commit-msg",
+            &format!("#!/bin/sh\necho commit-msg >> {log}\n"),
+        );
+        install_hook(
+            &hooks,
+            "post-commit",
+            &format!("#!/bin/sh\necho post-commit >> {log}\nexit 1\n"),
+        );
+
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+        let sha = repo.commit_with(&CommitOptions {
+            run_hooks: true,
+            ..CommitOptions::new("Add a")
+        })?;
+        assert_eq!(repo.get_commit_message(&sha)?, "Add a\nPrepared: yes\n");
+
+        repo.add_file("b.txt", "b\n")?;
+        repo.add(&["b.txt"])?;
+        let amended = repo.commit_amend(None, true)?;
+        assert_eq!(
+            fs::read_to_string(log)?,
+            format!(
+                "pre-commit :\nprepare-commit-msg message \ncommit-msg\npost-commit\n\
+                 pre-commit :\nprepare-commit-msg commit {sha}\ncommit-msg\npost-commit\n"
+            )
+        );
+        assert!(repo
+            .get_commit_message(&amended)?
+            .starts_with("Add a\nPrepared: yes\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn no_verify_keeps_prepare_commit_msg() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let hooks = repo.hooks_dir();
+        install_hook(&hooks, "pre-commit", "#!/bin/sh\nexit 1\n");
+        install_hook(
+            &hooks,
+            "prepare-commit-msg",
+            "#!/bin/sh\necho 'Prepared: yes' >> \"$1\"\n",
+        );
+
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+        assert!(repo
+            .commit_with(&CommitOptions {
+                run_hooks: true,
+                ..CommitOptions::new("Add a")
+            })
+            .is_err());
+        let sha = repo.commit_with(&CommitOptions {
+            run_hooks: true,
+            no_verify: true,
+            ..CommitOptions::new("Add a")
+        })?;
+        assert_eq!(repo.get_commit_message(&sha)?, "Add a\nPrepared: yes\n");
+        Ok(())
+    }
+
+    #[test]
+    fn hook_edited_messages_are_cleaned_up() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        install_hook(
+            &repo.hooks_dir(),
+            "commit-msg",
+            "#!/bin/sh\nprintf '\\n\\n\\nChecked-by: hook   \\n\\n\\n' >> \"$1\"\n",
+        );
+
+        repo.add_file("a.txt", "a\n")?;
+        repo.add(&["a.txt"])?;
+        let sha = repo.commit_with(&CommitOptions {
+            run_hooks: true,
+            ..CommitOptions::new("Add a")
+        })?;
+        assert_eq!(
+            repo.get_commit_message(&sha)?,
+            "Add a\n\nChecked-by: hook\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use anyhow::Context;
+use git2::{IndexEntry, RepositoryState, ResetType};
+
+use crate::git::error::GitError;
+use crate::git::rebase::operations::conflicted_paths;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// A path left unresolved by a merge, with the blob ids of each side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictEntry {
+    pub path: String,
+    /// The version in the merge base; `None` when both sides added the file
+    pub ancestor: Option<String>,
+    /// The version on the current branch; `None` when it deleted the file
+    pub ours: Option<String>,
+    /// The version being merged in; `None` when it deleted the file
+    pub theirs: Option<String>,
+}
+
+impl GitRepo {
+    /// Whether a merge stopped on conflicts and is waiting to be continued or aborted
+    pub fn is_merge_in_progress(&self) -> bool {
+        self.repo().state() == RepositoryState::Merge
+    }
+
+    /// The conflicted paths in the index, sorted by path. Empty once every conflict is
+    /// resolved and staged.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_conflicts(&self) -> Result<Vec<ConflictEntry>, GitError> {
+        let index = self.repo().index().context("Failed to read the index")?;
+        let blob_id = |entry: Option<IndexEntry>| entry.map(|entry| entry.id.to_string());
+        let mut conflicts: Vec<ConflictEntry> = index
+            .conflicts()
+            .context("Failed to read conflicts")?
+            .flatten()
+            .filter_map(|conflict| {
+                let path = conflict
+                    .our
+                    .as_ref()
+                    .or(conflict.their.as_ref())
+                    .or(conflict.ancestor.as_ref())
+                    .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())?;
+                Some(ConflictEntry {
+                    path,
+                    ancestor: blob_id(conflict.ancestor),
+                    ours: blob_id(conflict.our),
+                    theirs: blob_id(conflict.their),
+                })
+            })
+            .collect();
+        conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(conflicts)
+    }
+
+    /// Stop the merge and put the index and working tree back to HEAD, like
+    /// `git merge --abort`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn abort_merge(&self) -> Result<(), GitError> {
+        self.require_stopped_merge()?;
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel(git2::ObjectType::Commit))
+            .context("Failed to resolve HEAD")?;
+        self.wait_for_index_lock()?;
+        self.repo()
+            .reset(&head, ResetType::Hard, None)
+            .context("Failed to discard the merge")?;
+        self.repo()
+            .cleanup_state()
+            .context("Failed to cleanup merge state")?;
+        Ok(())
+    }
+
+    /// Commit the resolved merge with HEAD and the merged commits as parents, and return the
+    /// merge commit's id. `message` defaults to the one git prepared for the merge.
+    ///
+    /// Fails with [`GitError::Conflict`] while conflicts are left in the index.
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn continue_merge(&self, message: Option<&str>) -> Result<String, GitError> {
+        self.require_stopped_merge()?;
+        let mut index = self.repo().index().context("Failed to read the index")?;
+        if index.has_conflicts() {
+            return Err(GitError::Conflict(anyhow::anyhow!(
+                "Unresolved conflicts in {}; resolve and stage them first",
+                conflicted_paths(&index).join(", ")
+            )));
+        }
+
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Failed to resolve HEAD")?;
+        let mut merge_heads = Vec::new();
+        self.open_mut()?
+            .mergehead_foreach(|id| {
+                merge_heads.push(*id);
+                true
+            })
+            .context("Failed to read MERGE_HEAD")?;
+        let mut parents = vec![head];
+        for id in merge_heads {
+            parents.push(
+                self.repo()
+                    .find_commit(id)
+                    .context("Failed to find the merged commit")?,
+            );
+        }
+
+        let tree_id = index.write_tree().context("Failed to write merge tree")?;
+        let tree = self
+            .repo()
+            .find_tree(tree_id)
+            .context("Failed to find merge tree")?;
+        let message = match message {
+            Some(message) => message.to_string(),
+            None => self
+                .repo()
+                .message()
+                .map(|message| message.trim_end().to_string())
+                .unwrap_or_else(|_| "Merge".to_string()),
+        };
+        let author = self.commit_signature(Role::Author, None, None)?;
+        let committer = self.commit_signature(Role::Committer, None, None)?;
+        let parent_refs: Vec<&git2::Commit<'_>> = parents.iter().collect();
+        let id = self
+            .repo()
+            .commit(
+                Some("HEAD"),
+                &author,
+                &committer,
+                &message,
+                &tree,
+                &parent_refs,
+            )
+            .context("Failed to create merge commit")?;
+        self.repo()
+            .cleanup_state()
+            .context("Failed to cleanup merge state")?;
+
+        self.emit(GitEvent::CommitCreated {
+            id: id.to_string(),
+            summary: message.lines().next().unwrap_or_default().to_string(),
+        });
+        Ok(id.to_string())
+    }
+
+    fn require_stopped_merge(&self) -> Result<(), GitError> {
+        self.require_worktree("resolve a merge")?;
+        if !self.is_merge_in_progress() {
+            return Err(GitError::NotFound(anyhow::anyhow!("No merge in progress")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::merge::operations::MergeOutcome;
+    use crate::git::{GitError, GitEvent, GitRepo};
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+    use std::fs;
+
+    fn conflicted_repo() -> Result<(assert_fs::TempDir, GitRepo), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("shared.txt", "base\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("shared.txt", "feature\n", "Edit on feature")?
+            .add_file_and_commit("feature.txt", "feature\n", "Add feature file")?
+            .checkout_branch("master")?
+            .add_file_and_commit("shared.txt", "master\n", "Edit on master")?;
+        assert_eq!(
+            repo.merge("feature", None)?,
+            MergeOutcome::Conflicts(vec!["shared.txt".to_string()])
+        );
+        Ok((temp_dir, repo))
+    }
+
+    #[test]
+    fn get_conflicts_reports_each_side() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = conflicted_repo()?;
+        assert!(repo.is_merge_in_progress());
+
+        let conflicts = repo.get_conflicts()?;
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.path, "shared.txt");
+        let blob = |rev: &str| -> Result<Option<String>, git2::Error> {
+            Ok(Some(repo.repo().revparse_single(rev)?.id().to_string()))
+        };
+        assert_eq!(conflict.ancestor, blob("master~1:shared.txt")?);
+        assert_eq!(conflict.ours, blob("master:shared.txt")?);
+        assert_eq!(conflict.theirs, blob("feature:shared.txt")?);
+        Ok(())
+    }
+
+    #[test]
+    fn abort_merge_restores_head() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = conflicted_repo()?;
+
+        repo.abort_merge()?;
+        assert!(!repo.is_merge_in_progress());
+        assert!(repo.get_conflicts()?.is_empty());
+        assert!(repo.status()?.is_clean());
+        assert_eq!(
+            fs::read_to_string(repo.path().join("shared.txt"))?,
+            "master\n"
+        );
+        assert!(matches!(repo.abort_merge(), Err(GitError::NotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn continue_merge_commits_once_resolved() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = conflicted_repo()?;
+        assert!(matches!(
+            repo.continue_merge(None),
+            Err(GitError::Conflict(_))
+        ));
+
+        repo.add_file("shared.txt", "both\n")?;
+        repo.add(&["shared.txt"])?;
+        assert!(repo.get_conflicts()?.is_empty());
+        let events = repo.events();
+        let id = repo.continue_merge(None)?;
+
+        assert!(!repo.is_merge_in_progress());
+        let head = repo.repo().head()?.peel_to_commit()?;
+        assert_eq!(head.id().to_string(), id);
+        assert_eq!(head.parent_count(), 2);
+        assert_eq!(head.summary(), Some("Merge branch 'feature'"));
+        assert_eq!(
+            events.try_recv()?,
+            GitEvent::CommitCreated {
+                id,
+                summary: "Merge branch 'feature'".to_string()
+            }
+        );
+        repo.assert_file_exists("feature.txt");
+        assert!(repo.status()?.is_clean());
+        Ok(())
+    }
+}
@@ -1,2 +1,3 @@
+pub mod conflicts;
 pub mod operations;
 pub mod pull;
@@ -0,0 +1,353 @@
+use anyhow::Context;
+use std::fmt;
+
+use crate::git::commits::builder::{CommitTime, Identity};
+use crate::git::error::GitError;
+use crate::git::rebase::operations::conflicted_paths;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// Message, identities and dates for the commit [`GitRepo::merge_with`] creates when the merge
+/// is not a fast-forward.
+///
+/// Unset identities and dates fall back like [`CommitOptions`](crate::CommitOptions) does.
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// Defaults to `Merge branch '<branch>'`
+    pub message: Option<String>,
+    pub author: Option<Identity>,
+    pub committer: Option<Identity>,
+    pub author_date: Option<CommitTime>,
+    pub committer_date: Option<CommitTime>,
+}
+
+/// Result of [`GitRepo::merge`] and [`GitRepo::pull`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// The current branch moved forward to this commit without a merge commit
+    FastForward(String),
+    /// The histories diverged and this merge commit joins them
+    MergeCommit(String),
+    /// The histories diverged and the local commits were replayed onto `onto`;
+    /// `skipped` of them were dropped because their changes were already there
+    Rebased {
+        onto: String,
+        applied: usize,
+        skipped: usize,
+    },
+    /// The current branch already contains the merged commit; nothing changed
+    UpToDate,
+    /// The merge stopped on conflicts in these paths. The working tree holds conflict
+    /// markers and `MERGE_HEAD` is set, so [`GitRepo::continue_merge`] finishes the merge
+    /// once they are resolved and [`GitRepo::abort_merge`] undoes it.
+    Conflicts(Vec<String>),
+}
+
+impl MergeOutcome {
+    /// Whether the merge moved the current branch
+    pub fn updated_head(&self) -> bool {
+        matches!(
+            self,
+            MergeOutcome::FastForward(_)
+                | MergeOutcome::MergeCommit(_)
+                | MergeOutcome::Rebased { .. }
+        )
+    }
+}
+
+impl fmt::Display for MergeOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeOutcome::FastForward(id) => write!(f, "Fast-forward merge: {id}"),
+            MergeOutcome::MergeCommit(id) => write!(f, "Merge commit created: {id}"),
+            MergeOutcome::Rebased { onto, applied, .. } => write!(
+                f,
+                "Rebased {applied} commit{} onto {onto}",
+                if *applied == 1 { "" } else { "s" }
+            ),
+            MergeOutcome::UpToDate => write!(f, "Already up-to-date"),
+            MergeOutcome::Conflicts(paths) => {
+                write!(f, "Merge conflicts in {}", paths.join(", "))
+            }
+        }
+    }
+}
+
+impl GitRepo {
+    /// Merge a branch into the current branch.
+    ///
+    /// Conflicts are reported as [`MergeOutcome::Conflicts`] rather than an error.
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn merge(
+        &self,
+        branch_name: &str,
+        message: Option<&str>,
+    ) -> Result<MergeOutcome, GitError> {
+        self.merge_with(
+            branch_name,
+            &MergeOptions {
+                message: message.map(str::to_string),
+                ..MergeOptions::default()
+            },
+        )
+    }
+
+    /// Merge a branch into the current branch with control over the merge commit's
+    /// identities and dates
+    #[tracing::instrument(level = "debug", skip(self, options))]
+    pub fn merge_with(
+        &self,
+        branch_name: &str,
+        options: &MergeOptions,
+    ) -> Result<MergeOutcome, GitError> {
+        let outcome = self.merge_into_head(branch_name, options)?;
+        if outcome.updated_head() {
+            self.emit(GitEvent::Merged {
+                branch: branch_name.to_string(),
+            });
+        }
+        Ok(outcome)
+    }
+
+    fn merge_into_head(
+        &self,
+        branch_name: &str,
+        options: &MergeOptions,
+    ) -> Result<MergeOutcome, GitError> {
+        // Get the target branch to merge
+        let branch_ref = format!("refs/heads/{branch_name}");
+        let target_obj = self
+            .repo()
+            .revparse_single(&branch_ref)
+            .context(format!("Failed to find branch '{branch_name}'"))?;
+        let target_commit = target_obj
+            .peel_to_commit()
+            .context("Failed to get target commit")?;
+
+        // Get the current branch commit
+        let head_ref = self.repo().head().context("Failed to get HEAD")?;
+        let head_commit = head_ref
+            .peel_to_commit()
+            .context("Failed to get current commit")?;
+
+        // Check if already up-to-date
+        if head_commit.id() == target_commit.id() {
+            return Ok(MergeOutcome::UpToDate);
+        }
+
+        // Check if fast-forward is possible
+        let merge_base = self
+            .repo()
+            .merge_base(head_commit.id(), target_commit.id())
+            .context("Failed to find merge base")?;
+
+        if merge_base == head_commit.id() {
+            // Fast-forward merge: update branch reference to target commit
+            let current_branch_name = self
+                .get_current_branch()
+                .context("Failed to get current branch")?;
+
+            // Create a new reference for the branch pointing to target commit
+            let branch_ref_name = format!("refs/heads/{current_branch_name}");
+            self.repo()
+                .reference(
+                    &branch_ref_name,
+                    target_commit.id(),
+                    true,
+                    "Fast-forward merge",
+                )
+                .context("Failed to update branch reference")?;
+
+            // Update working directory if not bare
+            if !self.is_bare() {
+                let target_tree = target_commit.tree().context("Failed to get target tree")?;
+                let mut checkout_opts = git2::build::CheckoutBuilder::new();
+                checkout_opts.force();
+                self.repo()
+                    .checkout_tree(target_tree.as_object(), Some(&mut checkout_opts))
+                    .context("Failed to checkout target tree")?;
+            }
+
+            Ok(MergeOutcome::FastForward(target_commit.id().to_string()))
+        } else if merge_base == target_commit.id() {
+            // Already up to date
+            Ok(MergeOutcome::UpToDate)
+        } else {
+            // True merge required
+            let head_tree = head_commit.tree().context("Failed to get HEAD tree")?;
+
+            // Perform three-way merge
+            let mut index = self.repo().index().context("Failed to get index")?;
+            index
+                .read_tree(&head_tree)
+                .context("Failed to read head tree")?;
+
+            // Use git2's merge functionality through repository
+            let mut merge_options = git2::MergeOptions::new();
+            let mut checkout_opts = git2::build::CheckoutBuilder::new();
+            checkout_opts.conflict_style_merge(true);
+
+            // Annotating the branch rather than its commit makes MERGE_MSG read
+            // "Merge branch '<branch>'" for a merge continued after conflicts
+            let annotated_commit = self
+                .repo()
+                .find_reference(&branch_ref)
+                .and_then(|reference| self.repo().reference_to_annotated_commit(&reference))
+                .context("Failed to create annotated commit")?;
+
+            // Perform the merge analysis
+            let (analysis, _) = self
+                .repo()
+                .merge_analysis(&[&annotated_commit])
+                .context("Failed to analyze merge")?;
+
+            if analysis.is_up_to_date() {
+                Ok(MergeOutcome::UpToDate)
+            } else if analysis.is_fast_forward() {
+                // This shouldn't happen since we checked above, but handle it
+                self.repo()
+                    .set_head_detached(target_commit.id())
+                    .context("Failed to fast-forward merge")?;
+                Ok(MergeOutcome::FastForward(target_commit.id().to_string()))
+            } else if analysis.is_normal() {
+                // A true merge resolves conflicts in the working tree; only fast-forwards work bare
+                self.require_worktree("create a merge commit")?;
+                // Perform actual merge, which rewrites the index
+                self.wait_for_index_lock()?;
+                self.repo()
+                    .merge(
+                        &[&annotated_commit],
+                        Some(&mut merge_options),
+                        Some(&mut checkout_opts),
+                    )
+                    .context("Failed to perform merge")?;
+
+                // Check for conflicts
+                let mut index = self
+                    .repo()
+                    .index()
+                    .context("Failed to get index after merge")?;
+                if index.has_conflicts() {
+                    return Ok(MergeOutcome::Conflicts(conflicted_paths(&index)));
+                }
+
+                // Create merge commit
+                let tree_id = index.write_tree().context("Failed to write merge tree")?;
+                let tree = self
+                    .repo()
+                    .find_tree(tree_id)
+                    .context("Failed to find merge tree")?;
+
+                let default_message = format!("Merge branch '{branch_name}'");
+                let commit_message = options.message.as_deref().unwrap_or(&default_message);
+                let author = self
+                    .commit_signature(Role::Author, options.author.as_ref(), options.author_date)
+                    .context("Failed to create signature")?;
+                let committer = self
+                    .commit_signature(
+                        Role::Committer,
+                        options.committer.as_ref(),
+                        options.committer_date,
+                    )
+                    .context("Failed to create signature")?;
+
+                let merge_commit_id = self
+                    .repo()
+                    .commit(
+                        Some("HEAD"),
+                        &author,
+                        &committer,
+                        commit_message,
+                        &tree,
+                        &[&head_commit, &target_commit],
+                    )
+                    .context("Failed to create merge commit")?;
+
+                // Clean up merge state
+                self.repo()
+                    .cleanup_state()
+                    .context("Failed to cleanup merge state")?;
+
+                Ok(MergeOutcome::MergeCommit(merge_commit_id.to_string()))
+            } else {
+                Err(anyhow::anyhow!("Unsupported merge analysis result").into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeOptions, MergeOutcome};
+    use crate::git::commits::builder::{CommitTime, Identity};
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    #[test]
+    fn merge_works() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+
+        // Add initial commit to master
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature content", "Add feature")?
+            .checkout_branch("master")?;
+
+        // Merge the feature branch
+        let feature_head = repo.list_commits_from("feature")?[0].hash.clone();
+        let result = repo.merge("feature", None).unwrap();
+        assert_eq!(result, MergeOutcome::FastForward(feature_head.clone()));
+        assert_eq!(
+            result.to_string(),
+            format!("Fast-forward merge: {feature_head}")
+        );
+
+        // Verify the feature file exists on master after merge
+        repo.assert_file_exists("feature.txt");
+
+        // Test merging already merged branch
+        let result = repo.merge("feature", None).unwrap();
+        assert_eq!(result, MergeOutcome::UpToDate);
+        assert_eq!(result.to_string(), "Already up-to-date");
+
+        // Test merging non-existent branch
+        let result = repo.merge("nonexistent", None);
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_with_sets_identities_and_dates() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature", "Add feature")?
+            .checkout_branch("master")?
+            .add_file_and_commit("master.txt", "master", "Add master")?;
+
+        let options = MergeOptions {
+            message: Some("Import feature".to_string()),
+            author: Some(Identity {
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+            }),
+            author_date: Some(CommitTime {
+                seconds: 1_000_000_000,
+                offset_minutes: -300,
+            }),
+            committer_date: CommitTime::parse("2005-04-07T22:13:13+02:00"),
+            ..MergeOptions::default()
+        };
+        let result = repo.merge_with("feature", &options)?;
+
+        let head = repo.repo().head()?.peel_to_commit()?;
+        assert_eq!(result, MergeOutcome::MergeCommit(head.id().to_string()));
+        assert_eq!(head.message(), Some("Import feature"));
+        assert_eq!(head.author().name(), Some("Ada"));
+        assert_eq!(head.author().when().seconds(), 1_000_000_000);
+        assert_eq!(head.author().when().offset_minutes(), -300);
+        assert_eq!(head.committer().name(), Some("Test User"));
+        assert_eq!(head.committer().when().seconds(), 1_112_904_793);
+        Ok(())
+    }
+}
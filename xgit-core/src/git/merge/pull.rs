@@ -0,0 +1,60 @@
+// Pull itself is implemented in remotes/sync.rs, next to fetch
+// This module holds how a pull integrates the fetched commits
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// How [`GitRepo::pull_with`] brings fetched commits into a branch that has diverged from its
+/// remote. A branch that is only behind is fast-forwarded with every strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PullStrategy {
+    /// Join the histories with a merge commit, like `git pull --no-rebase`
+    #[default]
+    Merge,
+    /// Replay local commits on top of the remote branch, like `git pull --rebase`
+    Rebase,
+    /// Refuse anything but a fast-forward, like `git pull --ff-only`
+    FfOnly,
+}
+
+impl GitRepo {
+    /// The strategy `git pull` would use here: [`PullStrategy::Rebase`] when `pull.rebase`
+    /// is set to anything but false, [`PullStrategy::FfOnly`] when `pull.ff` is `only`, and
+    /// [`PullStrategy::Merge`] otherwise
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn pull_strategy(&self) -> Result<PullStrategy, GitError> {
+        if let Some(rebase) = self.get_config_string("pull.rebase")? {
+            if !matches!(
+                rebase.to_ascii_lowercase().as_str(),
+                "false" | "no" | "off" | "0"
+            ) {
+                return Ok(PullStrategy::Rebase);
+            }
+        }
+        if self.get_config_string("pull.ff")?.as_deref() == Some("only") {
+            return Ok(PullStrategy::FfOnly);
+        }
+        Ok(PullStrategy::Merge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PullStrategy;
+    use crate::test_utils::create_test_repo;
+
+    #[test]
+    fn pull_strategy_follows_git_config() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert_eq!(repo.pull_strategy()?, PullStrategy::Merge);
+
+        let mut config = repo.repo().config()?;
+        config.set_str("pull.ff", "only")?;
+        assert_eq!(repo.pull_strategy()?, PullStrategy::FfOnly);
+        config.set_str("pull.rebase", "false")?;
+        assert_eq!(repo.pull_strategy()?, PullStrategy::FfOnly);
+        config.set_str("pull.rebase", "merges")?;
+        assert_eq!(repo.pull_strategy()?, PullStrategy::Rebase);
+        Ok(())
+    }
+}
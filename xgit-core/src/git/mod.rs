@@ -0,0 +1,33 @@
+//! Git operations module
+//!
+//! This module provides a domain-driven structure for Git operations:
+//!
+//! - `backend`: [`ReadBackend`](backend::ReadBackend), read-only operations with a libgit2 or gix implementation
+//! - `repository`: Core repository operations (init, open, signatures, handle pools, events)
+//! - `branches`: Branch operations (create, checkout, list, tracking, merge-base cache)
+//! - `commits`: Commit operations (add, commit options, cherry-pick, diff, status, staged changes, Conventional Commits)
+//! - `hooks`: Client-side commit hooks (pre-commit, prepare-commit-msg, commit-msg, post-commit)
+//! - `remotes`: Remote operations (add, push, fetch, pull, credentials)
+//! - `merge`: Merge operations (merge strategies, pull merges, conflict inspection, abort and continue)
+//! - `rebase`: Rebases with conflict stops, continue, skip and abort, and interactive plans
+//! - `stash`: Stash save, apply, pop, drop and listing
+//! - `tags`: Lightweight and annotated tags (create, delete, list, push)
+//! - `error`: [`GitError`], the error every operation returns
+
+pub mod backend;
+pub mod branches;
+pub mod commits;
+pub mod error;
+pub mod hooks;
+pub mod merge;
+pub mod rebase;
+pub mod remotes;
+pub mod repository;
+pub mod stash;
+pub mod tags;
+
+// Re-export the main types
+pub use error::GitError;
+pub use repository::core::GitRepo;
+pub use repository::events::GitEvent;
+pub use repository::pool::GitRepoPool;
@@ -0,0 +1,342 @@
+//! Interactive rebases: reorder, reword, squash, fix up and drop the commits after a base.
+//!
+//! The plan is replayed in memory on top of the base, and the branch only moves once every
+//! step has applied, so a step that conflicts leaves the branch and working tree untouched.
+
+use anyhow::Context;
+use git2::{Oid, Sort};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// What to do with one commit of an interactive rebase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TodoAction {
+    /// Keep the commit as it is
+    Pick,
+    /// Keep the commit's changes under a new message
+    Reword(String),
+    /// Fold the commit into the one before it, keeping both messages
+    Squash,
+    /// Fold the commit into the one before it, keeping only that commit's message
+    Fixup,
+    /// Leave the commit out
+    Drop,
+}
+
+impl TodoAction {
+    /// The word `git rebase -i` uses for the action
+    pub fn name(&self) -> &'static str {
+        match self {
+            TodoAction::Pick => "pick",
+            TodoAction::Reword(_) => "reword",
+            TodoAction::Squash => "squash",
+            TodoAction::Fixup => "fixup",
+            TodoAction::Drop => "drop",
+        }
+    }
+}
+
+/// One line of an interactive rebase plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoItem {
+    pub id: String,
+    pub summary: String,
+    pub action: TodoAction,
+}
+
+/// Result of [`GitRepo::rebase_interactive`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteractiveRebaseOutcome {
+    /// Branch that was rewritten, or `HEAD` when it was detached
+    pub branch: String,
+    /// The rewritten branch's new tip
+    pub head: String,
+    /// Commits between the base and the new tip
+    pub commits: usize,
+}
+
+impl GitRepo {
+    /// The commits after `base` up to HEAD, oldest first, each set to [`TodoAction::Pick`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rebase_todo(&self, base: &str) -> Result<Vec<TodoItem>, GitError> {
+        let base = self.resolve_commit(base)?;
+        let mut revwalk = self.repo().revwalk().context("Failed to walk history")?;
+        revwalk.push_head().context("Failed to read HEAD")?;
+        revwalk
+            .hide(base)
+            .context("Failed to exclude the base from the walk")?;
+        revwalk
+            .set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)
+            .context("Failed to sort history")?;
+
+        let mut items = Vec::new();
+        for id in revwalk {
+            let id = id.context("Failed to walk history")?;
+            let commit = self
+                .repo()
+                .find_commit(id)
+                .context(format!("Failed to find commit {id}"))?;
+            if commit.parent_count() > 1 {
+                return Err(anyhow::anyhow!(
+                    "Cannot rebase merge commit {} interactively",
+                    &id.to_string()[..7]
+                )
+                .into());
+            }
+            items.push(TodoItem {
+                id: id.to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                action: TodoAction::Pick,
+            });
+        }
+        Ok(items)
+    }
+
+    /// Rewrite the commits after `base` following `plan`, in the plan's order, and move the
+    /// current branch to the result.
+    ///
+    /// Fails with [`GitError::Conflict`] when a commit does not apply on top of the ones
+    /// before it; nothing is changed in that case.
+    #[tracing::instrument(level = "debug", skip(self, plan))]
+    pub fn rebase_interactive(
+        &self,
+        base: &str,
+        plan: &[TodoItem],
+    ) -> Result<InteractiveRebaseOutcome, GitError> {
+        self.require_worktree("rebase")?;
+        if self.is_rebase_in_progress() {
+            return Err(anyhow::anyhow!(
+                "A rebase is already in progress; continue, skip or abort it first"
+            )
+            .into());
+        }
+        if self.has_uncommitted_changes()? {
+            return Err(GitError::DirtyWorktree(
+                "Uncommitted changes would be overwritten by the rebase. Commit or stash them first."
+                    .to_string(),
+            ));
+        }
+        let base = self.resolve_commit(base)?;
+
+        let committer = self.commit_signature(Role::Committer, None, None)?;
+        let mut tip = base;
+        let mut commits = 0;
+        for item in plan {
+            if item.action == TodoAction::Drop {
+                continue;
+            }
+            let id = Oid::from_str(&item.id).context(format!("Invalid commit id '{}'", item.id))?;
+            let commit = self
+                .repo()
+                .find_commit(id)
+                .context(format!("Failed to find commit {}", item.id))?;
+            let onto = self
+                .repo()
+                .find_commit(tip)
+                .context("Failed to find the rewritten commit")?;
+            let mut index = self
+                .repo()
+                .cherrypick_commit(&commit, &onto, 0, None)
+                .context(format!("Failed to apply {}", &item.id[..7]))?;
+            if index.has_conflicts() {
+                return Err(GitError::Conflict(anyhow::anyhow!(
+                    "{} {} conflicts with the commits before it in the plan",
+                    &item.id[..7],
+                    item.summary
+                )));
+            }
+            let tree_id = index
+                .write_tree_to(self.repo())
+                .context("Failed to write the rewritten tree")?;
+            let tree = self
+                .repo()
+                .find_tree(tree_id)
+                .context("Failed to find the rewritten tree")?;
+
+            let message = commit.message().unwrap_or_default();
+            tip = match &item.action {
+                TodoAction::Squash | TodoAction::Fixup => {
+                    if commits == 0 {
+                        return Err(anyhow::anyhow!(
+                            "Cannot {} {} into the base; pick a commit before it",
+                            item.action.name(),
+                            &item.id[..7]
+                        )
+                        .into());
+                    }
+                    let message = match item.action {
+                        TodoAction::Squash => format!(
+                            "{}\n\n{}",
+                            onto.message().unwrap_or_default().trim_end(),
+                            message.trim_end()
+                        ),
+                        _ => onto.message().unwrap_or_default().to_string(),
+                    };
+                    let parents: Vec<_> = onto.parents().collect();
+                    let parents: Vec<_> = parents.iter().collect();
+                    self.repo()
+                        .commit(None, &onto.author(), &committer, &message, &tree, &parents)
+                        .context("Failed to create the squashed commit")?
+                }
+                action => {
+                    let message = match action {
+                        TodoAction::Reword(message) => message.as_str(),
+                        _ => message,
+                    };
+                    commits += 1;
+                    self.repo()
+                        .commit(None, &commit.author(), &committer, message, &tree, &[&onto])
+                        .context(format!("Failed to rewrite {}", &item.id[..7]))?
+                }
+            };
+        }
+
+        let new_tip = self
+            .repo()
+            .find_commit(tip)
+            .context("Failed to find the rewritten branch tip")?;
+        self.wait_for_index_lock()?;
+        self.repo()
+            .checkout_tree(new_tip.as_object(), None)
+            .context("Failed to check out the rewritten branch")?;
+        let branch = match self.get_current_branch().ok() {
+            Some(branch) => {
+                self.repo()
+                    .reference(
+                        &format!("refs/heads/{branch}"),
+                        tip,
+                        true,
+                        "rebase -i (finish)",
+                    )
+                    .context(format!("Failed to update branch '{branch}'"))?;
+                branch
+            }
+            None => {
+                self.repo()
+                    .set_head_detached(tip)
+                    .context("Failed to move HEAD")?;
+                "HEAD".to_string()
+            }
+        };
+
+        self.emit(GitEvent::Rebased {
+            branch: branch.clone(),
+        });
+        Ok(InteractiveRebaseOutcome {
+            branch,
+            head: tip.to_string(),
+            commits,
+        })
+    }
+
+    pub(crate) fn resolve_commit(&self, revision: &str) -> Result<Oid, GitError> {
+        self.repo()
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id())
+            .context(format!("Failed to find '{revision}'"))
+            .map_err(GitError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TodoAction, TodoItem};
+    use crate::git::{GitError, GitRepo};
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    /// Four commits: the base, then `a`, `b` and `c` adding one file each
+    fn repo_with_commits() -> Result<(assert_fs::TempDir, GitRepo), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?
+            .add_file_and_commit("a.txt", "a\n", "Add a")?
+            .add_file_and_commit("b.txt", "b\n", "Add b")?
+            .add_file_and_commit("c.txt", "c\n", "Add c")?;
+        Ok((temp_dir, repo))
+    }
+
+    fn with_actions(todo: &[TodoItem], actions: &[TodoAction]) -> Vec<TodoItem> {
+        todo.iter()
+            .zip(actions)
+            .map(|(item, action)| TodoItem {
+                action: action.clone(),
+                ..item.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rebase_todo_lists_commits_after_base_oldest_first() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = repo_with_commits()?;
+        let todo = repo.rebase_todo("HEAD~3")?;
+        let summaries: Vec<&str> = todo.iter().map(|item| item.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["Add a", "Add b", "Add c"]);
+        assert!(todo.iter().all(|item| item.action == TodoAction::Pick));
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_interactive_reorders_rewords_squashes_and_drops(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = repo_with_commits()?;
+        repo.add_file_and_commit("d.txt", "d\n", "Add d")?;
+        let todo = repo.rebase_todo("HEAD~4")?;
+
+        // c, then a reworded with b squashed in, d dropped
+        let mut plan = with_actions(
+            &todo,
+            &[
+                TodoAction::Reword("Add a and b".to_string()),
+                TodoAction::Fixup,
+                TodoAction::Pick,
+                TodoAction::Drop,
+            ],
+        );
+        plan.swap(0, 2);
+        plan.swap(1, 2);
+        let outcome = repo.rebase_interactive("HEAD~4", &plan)?;
+
+        assert_eq!(outcome.branch, "master");
+        assert_eq!(outcome.commits, 2);
+        repo.assert_commit_messages(&["Add a and b", "Add c", "Initial commit"]);
+        repo.assert_file_exists("b.txt");
+        repo.assert_file_not_exists("d.txt");
+        assert!(repo.change_counts()?.is_clean());
+        Ok(())
+    }
+
+    #[test]
+    fn squash_keeps_both_messages() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = repo_with_commits()?;
+        let todo = repo.rebase_todo("HEAD~2")?;
+        let plan = with_actions(&todo, &[TodoAction::Pick, TodoAction::Squash]);
+
+        repo.rebase_interactive("HEAD~2", &plan)?;
+        repo.assert_commit_messages(&["Add b\n\nAdd c", "Add a", "Initial commit"]);
+
+        let plan = with_actions(&repo.rebase_todo("HEAD~1")?, &[TodoAction::Fixup]);
+        assert!(repo.rebase_interactive("HEAD~1", &plan).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_plan_leaves_the_branch_alone() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?
+            .add_file_and_commit("notes.txt", "one\n", "Add notes")?
+            .add_file_and_commit("notes.txt", "two\n", "Edit notes")?;
+        let head = repo.repo().head()?.target();
+
+        let mut plan = repo.rebase_todo("HEAD~2")?;
+        plan.swap(0, 1);
+        let err = repo.rebase_interactive("HEAD~2", &plan).unwrap_err();
+        assert!(matches!(err, GitError::Conflict(_)), "{err:?}");
+        assert_eq!(repo.repo().head()?.target(), head);
+        assert!(repo.change_counts()?.is_clean());
+        Ok(())
+    }
+}
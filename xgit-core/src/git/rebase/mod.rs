@@ -0,0 +1,2 @@
+pub mod interactive;
+pub mod operations;
@@ -0,0 +1,408 @@
+use anyhow::Context;
+use git2::{AnnotatedCommit, ErrorCode, Rebase, RepositoryState, ResetType};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// How a rebase run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebaseOutcome {
+    /// HEAD already contains the commit it was to be rebased onto; nothing changed
+    UpToDate,
+    /// Every remaining commit was replayed and the branch moved to the result
+    Completed {
+        /// Branch that was rebased, or `HEAD` when it was detached
+        branch: String,
+        /// Commits replayed in this run
+        applied: usize,
+        /// Commits dropped in this run because their changes were already upstream
+        skipped: usize,
+    },
+}
+
+impl GitRepo {
+    /// Whether a rebase stopped part-way and is waiting for `--continue`, `--skip` or `--abort`
+    pub fn is_rebase_in_progress(&self) -> bool {
+        matches!(
+            self.repo().state(),
+            RepositoryState::Rebase
+                | RepositoryState::RebaseInteractive
+                | RepositoryState::RebaseMerge
+        )
+    }
+
+    /// Replay the commits of the current branch that are not in `onto` on top of it, like
+    /// `git rebase <onto>`. `onto` is a branch or any other revision.
+    ///
+    /// Stops with [`GitError::Conflict`] at the first commit that does not apply cleanly,
+    /// leaving the conflicts in the working tree; resolve and stage them, then call
+    /// [`rebase_continue`](Self::rebase_continue), or [`rebase_skip`](Self::rebase_skip) or
+    /// [`rebase_abort`](Self::rebase_abort).
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rebase(&self, onto: &str) -> Result<RebaseOutcome, GitError> {
+        self.require_worktree("rebase")?;
+        if self.is_rebase_in_progress() {
+            return Err(anyhow::anyhow!(
+                "A rebase is already in progress; continue, skip or abort it first"
+            )
+            .into());
+        }
+
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Cannot rebase before the first commit")?;
+        let upstream = self.annotated_commit(onto)?;
+        let merge_base = self
+            .repo()
+            .merge_base(head.id(), upstream.id())
+            .context(format!("'{onto}' has no history in common with HEAD"))?;
+        if merge_base == upstream.id() {
+            return Ok(RebaseOutcome::UpToDate);
+        }
+        if self.has_uncommitted_changes()? {
+            return Err(GitError::DirtyWorktree(format!(
+                "Uncommitted changes would be overwritten by rebasing onto '{onto}'. Commit or stash them first."
+            )));
+        }
+
+        self.wait_for_index_lock()?;
+        let mut rebase = self
+            .repo()
+            .rebase(None, Some(&upstream), None, None)
+            .context(format!("Failed to start rebasing onto '{onto}'"))?;
+        self.run_rebase(&mut rebase, 0, 0)
+    }
+
+    /// Replay the commits of the current branch that are not in `upstream` on top of `onto`,
+    /// like `git rebase --onto <onto> <upstream>`. Used to move a branch off an old version of
+    /// the branch it was built on: commits that only the old version had are left behind
+    /// instead of being replayed.
+    ///
+    /// Stops on conflicts like [`rebase`](Self::rebase).
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rebase_onto(&self, onto: &str, upstream: &str) -> Result<RebaseOutcome, GitError> {
+        self.require_worktree("rebase")?;
+        if self.is_rebase_in_progress() {
+            return Err(anyhow::anyhow!(
+                "A rebase is already in progress; continue, skip or abort it first"
+            )
+            .into());
+        }
+
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .context("Cannot rebase before the first commit")?;
+        let onto_commit = self.annotated_commit(onto)?;
+        let upstream_commit = self.annotated_commit(upstream)?;
+        let merge_base = self
+            .repo()
+            .merge_base(head.id(), onto_commit.id())
+            .context(format!("'{onto}' has no history in common with HEAD"))?;
+        if merge_base == onto_commit.id() && upstream_commit.id() == onto_commit.id() {
+            return Ok(RebaseOutcome::UpToDate);
+        }
+        if self.has_uncommitted_changes()? {
+            return Err(GitError::DirtyWorktree(format!(
+                "Uncommitted changes would be overwritten by rebasing onto '{onto}'. Commit or stash them first."
+            )));
+        }
+
+        self.wait_for_index_lock()?;
+        let mut rebase = self
+            .repo()
+            .rebase(None, Some(&upstream_commit), Some(&onto_commit), None)
+            .context(format!("Failed to start rebasing onto '{onto}'"))?;
+        self.run_rebase(&mut rebase, 0, 0)
+    }
+
+    /// Commit the stopped commit with its conflicts resolved as staged, then replay the rest
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rebase_continue(&self) -> Result<RebaseOutcome, GitError> {
+        let mut rebase = self.open_stopped_rebase()?;
+        let (applied, skipped) = match self.commit_rebase_operation(&mut rebase)? {
+            true => (1, 0),
+            false => (0, 1),
+        };
+        self.run_rebase(&mut rebase, applied, skipped)
+    }
+
+    /// Drop the stopped commit, discarding its changes, then replay the rest
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rebase_skip(&self) -> Result<RebaseOutcome, GitError> {
+        let mut rebase = self.open_stopped_rebase()?;
+        let head = self
+            .repo()
+            .head()
+            .and_then(|head| head.peel(git2::ObjectType::Commit))
+            .context("Failed to resolve HEAD")?;
+        self.wait_for_index_lock()?;
+        self.repo()
+            .reset(&head, ResetType::Hard, None)
+            .context("Failed to discard the skipped commit's changes")?;
+        self.run_rebase(&mut rebase, 0, 1)
+    }
+
+    /// Stop the rebase and put the branch, index and working tree back as they were before it
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rebase_abort(&self) -> Result<(), GitError> {
+        let mut rebase = self.open_stopped_rebase()?;
+        self.wait_for_index_lock()?;
+        rebase.abort().context("Failed to abort the rebase")?;
+        Ok(())
+    }
+
+    /// `onto` as an annotated commit, keeping the branch name for the reflog when it is one
+    fn annotated_commit(&self, onto: &str) -> Result<AnnotatedCommit<'_>, GitError> {
+        if let Ok(reference) = self.repo().resolve_reference_from_short_name(onto) {
+            return self
+                .repo()
+                .reference_to_annotated_commit(&reference)
+                .context(format!("Failed to resolve '{onto}'"))
+                .map_err(GitError::from);
+        }
+        let commit = self
+            .repo()
+            .revparse_single(onto)
+            .and_then(|object| object.peel_to_commit())
+            .context(format!("Failed to find '{onto}'"))?;
+        self.repo()
+            .find_annotated_commit(commit.id())
+            .context(format!("Failed to resolve '{onto}'"))
+            .map_err(GitError::from)
+    }
+
+    fn open_stopped_rebase(&self) -> Result<Rebase<'_>, GitError> {
+        self.require_worktree("rebase")?;
+        if !self.is_rebase_in_progress() {
+            return Err(GitError::NotFound(anyhow::anyhow!("No rebase in progress")));
+        }
+        self.repo()
+            .open_rebase(None)
+            .context("Failed to open the rebase in progress")
+            .map_err(GitError::from)
+    }
+
+    /// Apply the remaining operations, committing each one, and finish the rebase
+    fn run_rebase(
+        &self,
+        rebase: &mut Rebase<'_>,
+        mut applied: usize,
+        mut skipped: usize,
+    ) -> Result<RebaseOutcome, GitError> {
+        while let Some(operation) = rebase.next() {
+            let operation = operation.context("Failed to apply the next commit")?;
+            let index = self.repo().index().context("Failed to read the index")?;
+            if index.has_conflicts() {
+                let commit = self
+                    .repo()
+                    .find_commit(operation.id())
+                    .context("Failed to find the commit being applied")?;
+                return Err(GitError::Conflict(anyhow::anyhow!(
+                    "Conflicts applying {} {}: {}",
+                    &operation.id().to_string()[..7],
+                    commit.summary().unwrap_or_default(),
+                    conflicted_paths(&index).join(", ")
+                )));
+            }
+            match self.commit_rebase_operation(rebase)? {
+                true => applied += 1,
+                false => skipped += 1,
+            }
+        }
+
+        let committer = self.commit_signature(Role::Committer, None, None)?;
+        rebase
+            .finish(Some(&committer))
+            .context("Failed to finish the rebase")?;
+        let branch = rebase
+            .orig_head_name()
+            .map(|name| name.strip_prefix("refs/heads/").unwrap_or(name))
+            .unwrap_or("HEAD")
+            .to_string();
+        self.emit(GitEvent::Rebased {
+            branch: branch.clone(),
+        });
+        Ok(RebaseOutcome::Completed {
+            branch,
+            applied,
+            skipped,
+        })
+    }
+
+    /// Commit the current operation with its original author and message; `false` when it
+    /// had nothing left to commit because its changes are already upstream
+    fn commit_rebase_operation(&self, rebase: &mut Rebase<'_>) -> Result<bool, GitError> {
+        let committer = self.commit_signature(Role::Committer, None, None)?;
+        match rebase.commit(None, &committer, None) {
+            Ok(_) => Ok(true),
+            Err(err) if err.code() == ErrorCode::Applied => Ok(false),
+            Err(err) => Err(anyhow::Error::new(err)
+                .context("Failed to commit the rebased change")
+                .into()),
+        }
+    }
+}
+
+/// Paths with unresolved conflicts in `index`, one per conflict
+pub(crate) fn conflicted_paths(index: &git2::Index) -> Vec<String> {
+    let Ok(conflicts) = index.conflicts() else {
+        return Vec::new();
+    };
+    conflicts
+        .flatten()
+        .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RebaseOutcome;
+    use crate::git::{GitError, GitEvent, GitRepo};
+    use crate::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    /// `feature` edits `shared.txt` one way and master the other way
+    fn diverged_with_conflict(repo: &GitRepo) -> Result<(), Box<dyn std::error::Error>> {
+        repo.add_file_and_commit("shared.txt", "base\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature\n", "Add feature")?
+            .add_file_and_commit("shared.txt", "feature\n", "Edit shared on feature")?
+            .checkout_branch("master")?
+            .add_file_and_commit("shared.txt", "master\n", "Edit shared on master")?
+            .checkout_branch("feature")?;
+        Ok(())
+    }
+
+    fn file(repo: &GitRepo, name: &str) -> String {
+        std::fs::read_to_string(repo.path().join(name)).unwrap()
+    }
+
+    #[test]
+    fn rebase_replays_branch_commits_onto_target() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "readme\n", "Initial commit")?
+            .create_and_checkout_branch("feature")?
+            .add_file_and_commit("feature.txt", "feature\n", "Add feature")?
+            .checkout_branch("master")?
+            .add_file_and_commit("master.txt", "master\n", "Add master")?
+            .checkout_branch("feature")?;
+        let events = repo.events();
+
+        let outcome = repo.rebase("master")?;
+        assert_eq!(
+            outcome,
+            RebaseOutcome::Completed {
+                branch: "feature".to_string(),
+                applied: 1,
+                skipped: 0
+            }
+        );
+        repo.assert_commit_messages(&["Add feature", "Add master", "Initial commit"]);
+        assert_eq!(repo.get_current_branch()?, "feature");
+        repo.assert_file_exists("master.txt");
+        assert!(!repo.is_rebase_in_progress());
+        assert_eq!(
+            events.try_iter().last(),
+            Some(GitEvent::Rebased {
+                branch: "feature".to_string()
+            })
+        );
+
+        assert_eq!(repo.rebase("master")?, RebaseOutcome::UpToDate);
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_stops_on_conflict_and_continues() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        diverged_with_conflict(&repo)?;
+
+        let err = repo.rebase("master").unwrap_err();
+        assert!(matches!(err, GitError::Conflict(_)), "{err:?}");
+        assert!(err.to_string().contains("Edit shared on feature"), "{err}");
+        assert!(err.to_string().contains("shared.txt"), "{err}");
+        assert!(repo.is_rebase_in_progress());
+        repo.assert_conflicted_files(&["shared.txt"]);
+        assert!(repo.rebase("master").is_err());
+
+        // Continuing with the conflict unresolved fails and keeps the rebase going
+        assert!(repo.rebase_continue().is_err());
+        assert!(repo.is_rebase_in_progress());
+
+        std::fs::write(repo.path().join("shared.txt"), "resolved\n")?;
+        repo.add(&["shared.txt"])?;
+        let outcome = repo.rebase_continue()?;
+        assert_eq!(
+            outcome,
+            RebaseOutcome::Completed {
+                branch: "feature".to_string(),
+                applied: 1,
+                skipped: 0
+            }
+        );
+        repo.assert_commit_messages(&[
+            "Edit shared on feature",
+            "Add feature",
+            "Edit shared on master",
+            "Initial commit",
+        ]);
+        assert_eq!(file(&repo, "shared.txt"), "resolved\n");
+        assert!(!repo.is_rebase_in_progress());
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_skip_drops_the_conflicting_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        diverged_with_conflict(&repo)?;
+
+        assert!(repo.rebase("master").is_err());
+        let outcome = repo.rebase_skip()?;
+        assert_eq!(
+            outcome,
+            RebaseOutcome::Completed {
+                branch: "feature".to_string(),
+                applied: 0,
+                skipped: 1
+            }
+        );
+        repo.assert_commit_messages(&["Add feature", "Edit shared on master", "Initial commit"]);
+        assert_eq!(file(&repo, "shared.txt"), "master\n");
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_abort_restores_the_branch() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        diverged_with_conflict(&repo)?;
+
+        assert!(repo.rebase("master").is_err());
+        repo.rebase_abort()?;
+        assert!(!repo.is_rebase_in_progress());
+        assert_eq!(repo.get_current_branch()?, "feature");
+        repo.assert_commit_messages(&["Edit shared on feature", "Add feature", "Initial commit"]);
+        assert_eq!(file(&repo, "shared.txt"), "feature\n");
+
+        let err = repo.rebase_abort().unwrap_err();
+        assert!(matches!(err, GitError::NotFound(_)), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn rebase_refuses_uncommitted_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        diverged_with_conflict(&repo)?;
+        std::fs::write(repo.path().join("feature.txt"), "dirty\n")?;
+
+        let err = repo.rebase("master").unwrap_err();
+        assert!(matches!(err, GitError::DirtyWorktree(_)), "{err:?}");
+        assert!(!repo.is_rebase_in_progress());
+        Ok(())
+    }
+}
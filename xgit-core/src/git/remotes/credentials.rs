@@ -0,0 +1,362 @@
+//! Credentials for remotes that need authentication.
+//!
+//! libgit2 asks for credentials again every time the previous ones are rejected, so the
+//! callback walks through the configured methods once each and then gives up:
+//!
+//! 1. ssh-agent (`xgit.auth.sshAgent`, on by default)
+//! 2. SSH key files: `xgit.auth.sshKey`, or `~/.ssh/id_ed25519`, `id_ecdsa` and `id_rsa`
+//! 3. git's `credential.helper` for HTTPS (`xgit.auth.credentialHelper`, on by default)
+//! 4. the platform's default credentials (NTLM/Negotiate)
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use git2::{Config, Cred, CredentialType, ErrorClass, ErrorCode, RemoteCallbacks};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// Keys tried when `xgit.auth.sshKey` is not set, in `~/.ssh`
+const DEFAULT_SSH_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// How to answer a remote's request for credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialConfig {
+    pub use_ssh_agent: bool,
+    /// Private keys to offer, in order; each key's `.pub` file is used when present
+    pub ssh_keys: Vec<PathBuf>,
+    pub use_credential_helper: bool,
+}
+
+impl CredentialConfig {
+    /// Build the config from the `xgit.auth.*` values, looking for default keys under `home`
+    fn from_values(
+        ssh_agent: Option<bool>,
+        ssh_key: Option<String>,
+        credential_helper: Option<bool>,
+        home: Option<&Path>,
+    ) -> Self {
+        let ssh_keys = match ssh_key {
+            Some(key) => vec![expand_home(&key, home)],
+            None => home
+                .map(|home| {
+                    DEFAULT_SSH_KEYS
+                        .iter()
+                        .map(|name| home.join(".ssh").join(name))
+                        .filter(|path| path.is_file())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+        CredentialConfig {
+            use_ssh_agent: ssh_agent.unwrap_or(true),
+            ssh_keys,
+            use_credential_helper: credential_helper.unwrap_or(true),
+        }
+    }
+
+    /// Read the settings from `config` directly, for operations without a repository such
+    /// as cloning
+    pub fn from_git_config(config: &Config) -> Result<Self, GitError> {
+        let bool_value = |name: &str| match config.get_bool(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.code() == ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err),
+        };
+        let ssh_key = match config.get_string("xgit.auth.sshKey") {
+            Ok(value) => Some(value),
+            Err(err) if err.code() == ErrorCode::NotFound => None,
+            Err(err) => return Err(anyhow::Error::new(err).into()),
+        };
+        Ok(Self::from_values(
+            bool_value("xgit.auth.sshAgent").context("Invalid xgit.auth.sshAgent")?,
+            ssh_key,
+            bool_value("xgit.auth.credentialHelper")
+                .context("Invalid xgit.auth.credentialHelper")?,
+            home_dir().as_deref(),
+        ))
+    }
+}
+
+/// One way of answering a credential request
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Attempt {
+    Username(String),
+    SshAgent(String),
+    SshKey(String, PathBuf),
+    CredentialHelper,
+    Default,
+}
+
+/// The methods not yet tried for one operation
+#[derive(Debug)]
+struct CredentialPlan {
+    config: CredentialConfig,
+    username_tried: bool,
+    agent_tried: bool,
+    keys_tried: usize,
+    helper_tried: bool,
+    default_tried: bool,
+}
+
+impl CredentialPlan {
+    fn new(config: CredentialConfig) -> Self {
+        CredentialPlan {
+            config,
+            username_tried: false,
+            agent_tried: false,
+            keys_tried: 0,
+            helper_tried: false,
+            default_tried: false,
+        }
+    }
+
+    /// The next method to try for a request allowing `allowed`, or `None` once everything
+    /// has been rejected
+    fn next(&mut self, username: Option<&str>, allowed: CredentialType) -> Option<Attempt> {
+        // SSH URLs without a user name ask for one before anything else
+        let username = username.unwrap_or("git").to_string();
+        if allowed.contains(CredentialType::USERNAME) && !self.username_tried {
+            self.username_tried = true;
+            return Some(Attempt::Username(username));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if self.config.use_ssh_agent && !self.agent_tried {
+                self.agent_tried = true;
+                return Some(Attempt::SshAgent(username));
+            }
+            if let Some(key) = self.config.ssh_keys.get(self.keys_tried) {
+                self.keys_tried += 1;
+                return Some(Attempt::SshKey(username, key.clone()));
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT)
+            && self.config.use_credential_helper
+            && !self.helper_tried
+        {
+            self.helper_tried = true;
+            return Some(Attempt::CredentialHelper);
+        }
+        if allowed.contains(CredentialType::DEFAULT) && !self.default_tried {
+            self.default_tried = true;
+            return Some(Attempt::Default);
+        }
+        None
+    }
+}
+
+/// Callbacks answering credential requests according to `config`, with the credential
+/// helpers taken from `git_config`
+pub fn credential_callbacks(
+    config: CredentialConfig,
+    git_config: Config,
+) -> RemoteCallbacks<'static> {
+    let mut plan = CredentialPlan::new(config);
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        // A method that cannot even produce credentials (no agent running, no helper
+        // configured) moves straight on to the next one
+        while let Some(attempt) = plan.next(username, allowed) {
+            tracing::debug!(?attempt, url, "Trying credentials");
+            let cred = match attempt {
+                Attempt::Username(username) => Cred::username(&username),
+                Attempt::SshAgent(username) => Cred::ssh_key_from_agent(&username),
+                Attempt::SshKey(username, key) => {
+                    let mut public_key = key.clone().into_os_string();
+                    public_key.push(".pub");
+                    let public_key = PathBuf::from(public_key);
+                    let public_key = public_key.is_file().then(|| public_key);
+                    Cred::ssh_key(&username, public_key.as_deref(), &key, None)
+                }
+                Attempt::CredentialHelper => Cred::credential_helper(&git_config, url, username),
+                Attempt::Default => Cred::default(),
+            };
+            match cred {
+                Ok(cred) => return Ok(cred),
+                Err(err) => tracing::debug!(%err, "Credentials unavailable"),
+            }
+        }
+        Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Callback,
+            format!(
+                "Authentication failed for '{url}': no ssh-agent identity, SSH key or credential helper was accepted"
+            ),
+        ))
+    });
+    callbacks
+}
+
+impl GitRepo {
+    /// The repository's `xgit.auth.*` settings
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn credential_config(&self) -> Result<CredentialConfig, GitError> {
+        Ok(CredentialConfig::from_values(
+            self.get_config_bool("xgit.auth.sshAgent")?,
+            self.get_config_string("xgit.auth.sshKey")?,
+            self.get_config_bool("xgit.auth.credentialHelper")?,
+            home_dir().as_deref(),
+        ))
+    }
+
+    /// Callbacks for a push or fetch that authenticate as configured for this repository
+    pub(crate) fn remote_callbacks(&self) -> Result<RemoteCallbacks<'static>, GitError> {
+        let git_config = self
+            .repo()
+            .config()
+            .context("Failed to get repository config")?;
+        Ok(credential_callbacks(self.credential_config()?, git_config))
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn expand_home(path: &str, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attempt, CredentialConfig, CredentialPlan};
+    use crate::git::GitError;
+    use crate::test_utils::{create_test_repo, GitHttpServer, RepoTestOperations};
+    use git2::CredentialType;
+    use std::path::PathBuf;
+
+    fn config(keys: &[&str]) -> CredentialConfig {
+        CredentialConfig {
+            use_ssh_agent: true,
+            ssh_keys: keys.iter().map(PathBuf::from).collect(),
+            use_credential_helper: true,
+        }
+    }
+
+    #[test]
+    fn plan_tries_each_ssh_method_once() {
+        let mut plan = CredentialPlan::new(config(&["/keys/a", "/keys/b"]));
+
+        assert_eq!(
+            plan.next(None, CredentialType::USERNAME),
+            Some(Attempt::Username("git".to_string()))
+        );
+        let ssh = CredentialType::SSH_KEY;
+        assert_eq!(
+            plan.next(Some("git"), ssh),
+            Some(Attempt::SshAgent("git".to_string()))
+        );
+        assert_eq!(
+            plan.next(Some("git"), ssh),
+            Some(Attempt::SshKey("git".to_string(), PathBuf::from("/keys/a")))
+        );
+        assert_eq!(
+            plan.next(Some("git"), ssh),
+            Some(Attempt::SshKey("git".to_string(), PathBuf::from("/keys/b")))
+        );
+        assert_eq!(plan.next(Some("git"), ssh), None);
+    }
+
+    #[test]
+    fn plan_uses_credential_helper_then_default_for_https() {
+        let mut plan = CredentialPlan::new(config(&["/keys/a"]));
+        let https = CredentialType::USER_PASS_PLAINTEXT | CredentialType::DEFAULT;
+
+        assert_eq!(plan.next(None, https), Some(Attempt::CredentialHelper));
+        assert_eq!(plan.next(None, https), Some(Attempt::Default));
+        assert_eq!(plan.next(None, https), None);
+
+        let mut plan = CredentialPlan::new(CredentialConfig {
+            use_credential_helper: false,
+            ..config(&[])
+        });
+        assert_eq!(plan.next(None, CredentialType::USER_PASS_PLAINTEXT), None);
+    }
+
+    #[test]
+    fn config_defaults_to_existing_keys_in_home() {
+        let home = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir(home.path().join(".ssh")).unwrap();
+        std::fs::write(home.path().join(".ssh/id_rsa"), "key").unwrap();
+
+        let config = CredentialConfig::from_values(None, None, None, Some(home.path()));
+        assert!(config.use_ssh_agent);
+        assert!(config.use_credential_helper);
+        assert_eq!(config.ssh_keys, vec![home.path().join(".ssh/id_rsa")]);
+
+        let config = CredentialConfig::from_values(
+            Some(false),
+            Some("~/keys/work".to_string()),
+            Some(false),
+            Some(home.path()),
+        );
+        assert!(!config.use_ssh_agent);
+        assert!(!config.use_credential_helper);
+        assert_eq!(config.ssh_keys, vec![home.path().join("keys/work")]);
+    }
+
+    #[test]
+    fn credential_config_reads_repository_settings() {
+        let (_temp_dir, repo) = create_test_repo();
+        let mut config = repo.repo().config().unwrap();
+        config.set_bool("xgit.auth.sshAgent", false).unwrap();
+        config.set_str("xgit.auth.sshKey", "/keys/deploy").unwrap();
+
+        let credentials = repo.credential_config().unwrap();
+        assert!(!credentials.use_ssh_agent);
+        assert_eq!(credentials.ssh_keys, vec![PathBuf::from("/keys/deploy")]);
+        assert!(credentials.use_credential_helper);
+    }
+
+    #[test]
+    fn push_and_fetch_authenticate_through_credential_helper() -> Result<(), anyhow::Error> {
+        let server = GitHttpServer::start_with_credentials("alice", "secret")?;
+        server.create_repo("private")?;
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+        repo.add_remote("origin", &server.url("private"))?;
+        let mut config = repo.repo().config()?;
+        config.set_bool("xgit.auth.credentialHelper", false)?;
+
+        let err = repo.push("origin", "master").unwrap_err();
+        assert!(matches!(err, GitError::AuthRequired(_)), "{err:?}");
+
+        config.set_bool("xgit.auth.credentialHelper", true)?;
+        config.set_str(
+            "credential.helper",
+            "!f() { echo username=alice; echo password=secret; }; f",
+        )?;
+        repo.push("origin", "master")?;
+        repo.fetch("origin", None)?;
+        assert!(repo.remote_tracking_branch_exists("origin/master"));
+        Ok(())
+    }
+
+    #[test]
+    fn ssh_and_https_remotes_reach_their_transport() -> Result<(), anyhow::Error> {
+        let version = git2::Version::get();
+        assert!(version.https(), "libgit2 was built without https");
+        assert!(version.ssh(), "libgit2 was built without ssh");
+
+        // A port nothing listens on: each fetch must get as far as connecting
+        let port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a", "Initial commit")?;
+        repo.add_remote("https", &format!("https://127.0.0.1:{port}/octo/repo.git"))?;
+        repo.add_remote("ssh", &format!("ssh://git@127.0.0.1:{port}/octo/repo.git"))?;
+
+        for remote in ["https", "ssh"] {
+            let err = format!("{:#}", repo.fetch(remote, None).unwrap_err());
+            assert!(!err.contains("unsupported URL protocol"), "{remote}: {err}");
+            assert!(!err.contains("no TLS stream"), "{remote}: {err}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod credentials;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+pub mod operations;
+pub mod sync;
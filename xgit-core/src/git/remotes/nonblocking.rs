@@ -0,0 +1,159 @@
+//! Async wrappers around the network-bound remote operations.
+//!
+//! libgit2 calls block the calling thread for the whole transfer, so each wrapper reopens the
+//! repository on tokio's blocking pool and awaits the result. The handle is not `Send`, which
+//! is why the work is done through a fresh `GitRepo` rather than by moving `self`.
+
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+
+use crate::git::error::GitError;
+use crate::git::merge::operations::MergeOutcome;
+use crate::git::merge::pull::PullStrategy;
+use crate::git::remotes::sync::FetchOutcome;
+use crate::git::repository::core::GitRepo;
+
+impl GitRepo {
+    /// Clone `url` into `path` without blocking the async runtime
+    #[tracing::instrument(level = "debug", skip(path), fields(path = %path.as_ref().display()))]
+    pub async fn clone_async(url: &str, path: impl AsRef<Path>) -> Result<GitRepo, GitError> {
+        let url = url.to_string();
+        let path = path.as_ref().to_path_buf();
+        tokio::task::spawn_blocking(move || GitRepo::clone(&url, &path))
+            .await
+            .context("Clone task panicked")?
+    }
+
+    /// Async counterpart of [`GitRepo::fetch`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn fetch_async(
+        &self,
+        remote_name: &str,
+        branch_name: Option<&str>,
+    ) -> Result<FetchOutcome, GitError> {
+        let remote_name = remote_name.to_string();
+        let branch_name = branch_name.map(str::to_string);
+        self.run_blocking(move |repo| repo.fetch(&remote_name, branch_name.as_deref()))
+            .await
+    }
+
+    /// Async counterpart of [`GitRepo::fetch_prune`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn fetch_prune_async(
+        &self,
+        remote_name: &str,
+        branch_name: Option<&str>,
+    ) -> Result<FetchOutcome, GitError> {
+        let remote_name = remote_name.to_string();
+        let branch_name = branch_name.map(str::to_string);
+        self.run_blocking(move |repo| repo.fetch_prune(&remote_name, branch_name.as_deref()))
+            .await
+    }
+
+    /// Async counterpart of [`GitRepo::pull`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn pull_async(
+        &self,
+        remote_name: &str,
+        branch_name: Option<&str>,
+    ) -> Result<MergeOutcome, GitError> {
+        let remote_name = remote_name.to_string();
+        let branch_name = branch_name.map(str::to_string);
+        self.run_blocking(move |repo| repo.pull(&remote_name, branch_name.as_deref()))
+            .await
+    }
+
+    /// Async counterpart of [`GitRepo::pull_with`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn pull_with_async(
+        &self,
+        remote_name: &str,
+        branch_name: Option<&str>,
+        strategy: PullStrategy,
+    ) -> Result<MergeOutcome, GitError> {
+        let remote_name = remote_name.to_string();
+        let branch_name = branch_name.map(str::to_string);
+        self.run_blocking(move |repo| {
+            repo.pull_with(&remote_name, branch_name.as_deref(), strategy)
+        })
+        .await
+    }
+
+    /// Async counterpart of [`GitRepo::push`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn push_async(&self, remote_name: &str, branch_name: &str) -> Result<(), GitError> {
+        let remote_name = remote_name.to_string();
+        let branch_name = branch_name.to_string();
+        self.run_blocking(move |repo| repo.push(&remote_name, &branch_name))
+            .await
+    }
+
+    /// Async counterpart of [`GitRepo::force_push_commit_to_branch`]
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn force_push_commit_to_branch_async(
+        &self,
+        remote_name: &str,
+        commit_sha: &str,
+        branch_name: &str,
+    ) -> Result<(), GitError> {
+        let remote_name = remote_name.to_string();
+        let commit_sha = commit_sha.to_string();
+        let branch_name = branch_name.to_string();
+        self.run_blocking(move |repo| {
+            repo.force_push_commit_to_branch(&remote_name, &commit_sha, &branch_name)
+        })
+        .await
+    }
+
+    async fn run_blocking<T, F>(&self, operation: F) -> Result<T, GitError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&GitRepo) -> Result<T, GitError> + Send + 'static,
+    {
+        let git_dir: PathBuf = self.git_dir().to_path_buf();
+        tokio::task::spawn_blocking(move || operation(&GitRepo::open(&git_dir)?))
+            .await
+            .context("Repository task panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::GitRepo;
+    use crate::test_utils::{create_test_bare_repo, create_test_repo, RepoTestOperations};
+
+    #[tokio::test]
+    async fn push_and_fetch_async_work() {
+        let (_remote_dir, remote_repo) = create_test_bare_repo();
+        let (_local_dir, local_repo) = create_test_repo();
+        local_repo
+            .add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+        local_repo.add_local_remote("origin", &remote_repo).unwrap();
+
+        local_repo.push_async("origin", "master").await.unwrap();
+        let result = local_repo.fetch_async("origin", None).await.unwrap();
+        assert!(result.pruned().is_empty());
+        assert!(local_repo.remote_tracking_branch_exists("origin/master"));
+        assert!(local_repo.fetch_async("nonexistent", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn clone_async_works() {
+        let (_remote_dir, remote_repo) = create_test_bare_repo();
+        let (_local_dir, local_repo) = create_test_repo();
+        local_repo
+            .add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+        local_repo.add_local_remote("origin", &remote_repo).unwrap();
+        local_repo.push("origin", "master").unwrap();
+
+        let target = assert_fs::TempDir::new().unwrap();
+        let url = remote_repo.path().to_str().unwrap();
+        let cloned = GitRepo::clone_async(url, target.path().join("clone"))
+            .await
+            .unwrap();
+        assert_eq!(cloned.get_current_branch().unwrap(), "master");
+        assert!(cloned.path().join("README.md").exists());
+    }
+}
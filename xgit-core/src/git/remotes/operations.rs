@@ -1,11 +1,15 @@
-use anyhow::{Context, Error};
+use anyhow::Context;
+use git2::{BranchType, PushOptions};
 use std::process::Command;
 
+use crate::git::error::GitError;
 use crate::git::repository::core::{GitRepo, RemoteInfo};
+use crate::git::repository::events::GitEvent;
 
 impl GitRepo {
     /// Add a remote repository
-    pub fn add_remote(&self, name: &str, url: &str) -> Result<(), Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn add_remote(&self, name: &str, url: &str) -> Result<(), GitError> {
         self.repo()
             .remote(name, url)
             .context(format!("Failed to add remote '{name}' with URL '{url}'"))?;
@@ -14,7 +18,8 @@ impl GitRepo {
     }
 
     /// Set the URL of an existing remote
-    pub fn set_remote_url(&self, name: &str, url: &str) -> Result<(), Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn set_remote_url(&self, name: &str, url: &str) -> Result<(), GitError> {
         self.repo()
             .remote_set_url(name, url)
             .context(format!("Failed to set URL for remote '{name}'"))?;
@@ -22,8 +27,43 @@ impl GitRepo {
         Ok(())
     }
 
+    /// Remove a remote along with its remote-tracking branches and the upstream settings of
+    /// local branches that track it
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn remove_remote(&self, name: &str) -> Result<(), GitError> {
+        self.repo()
+            .find_remote(name)
+            .context(format!("Failed to find remote '{name}'"))?;
+        self.repo()
+            .remote_delete(name)
+            .context(format!("Failed to remove remote '{name}'"))?;
+
+        Ok(())
+    }
+
+    /// Rename a remote, moving its remote-tracking branches, its default fetch refspec and the
+    /// upstream settings of branches that track it to the new name.
+    ///
+    /// Returns the fetch refspecs that are not in the default form and so were left as they
+    /// are; they need updating by hand.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<Vec<String>, GitError> {
+        self.repo()
+            .find_remote(old_name)
+            .context(format!("Failed to find remote '{old_name}'"))?;
+        let problems = self
+            .repo()
+            .remote_rename(old_name, new_name)
+            .context(format!(
+                "Failed to rename remote '{old_name}' to '{new_name}'"
+            ))?;
+
+        Ok(problems.iter().flatten().map(str::to_string).collect())
+    }
+
     /// List all remotes with their URLs
-    pub fn get_remotes(&self) -> Result<Vec<RemoteInfo>, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_remotes(&self) -> Result<Vec<RemoteInfo>, GitError> {
         let remotes = self
             .repo()
             .remotes()
@@ -50,13 +90,15 @@ impl GitRepo {
     }
 
     /// List all remote names only (for backward compatibility)
-    pub fn get_remote_names(&self) -> Result<Vec<String>, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_remote_names(&self) -> Result<Vec<String>, GitError> {
         let remotes = self.get_remotes()?;
         Ok(remotes.into_iter().map(|r| r.name).collect())
     }
 
     /// Get the URL of a specific remote
-    pub fn get_remote_url(&self, name: &str) -> Result<String, Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_remote_url(&self, name: &str) -> Result<String, GitError> {
         let remote = self
             .repo()
             .find_remote(name)
@@ -74,7 +116,8 @@ impl GitRepo {
     /// # Arguments
     /// * `remote_name` - The name of the remote (e.g., "origin")
     /// * `branch_name` - The name of the branch to push (e.g., "main", "master")
-    pub fn push(&self, remote_name: &str, branch_name: &str) -> Result<(), Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn push(&self, remote_name: &str, branch_name: &str) -> Result<(), GitError> {
         let mut remote = self
             .repo()
             .find_remote(remote_name)
@@ -82,18 +125,67 @@ impl GitRepo {
 
         let refspec = format!("refs/heads/{branch_name}:refs/heads/{branch_name}");
 
-        remote.push(&[&refspec], None).context(format!(
-            "Failed to push branch '{branch_name}' to remote '{remote_name}'"
-        ))?;
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(self.remote_callbacks()?);
+        remote
+            .push(&[&refspec], Some(&mut push_options))
+            .context(format!(
+                "Failed to push branch '{branch_name}' to remote '{remote_name}'"
+            ))?;
+        self.emit(GitEvent::Pushed {
+            remote: remote_name.to_string(),
+            refspec,
+        });
 
         Ok(())
     }
 
+    /// Push a branch and make the remote branch its upstream (equivalent to
+    /// `git push -u <remote> <branch>`)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn push_and_set_upstream(
+        &self,
+        remote_name: &str,
+        branch_name: &str,
+    ) -> Result<(), GitError> {
+        self.push(remote_name, branch_name)?;
+        self.set_upstream(branch_name, remote_name, branch_name)
+    }
+
+    /// Make `remote_branch` on `remote_name` the upstream of the local branch `branch_name` by
+    /// writing `branch.<name>.remote` and `branch.<name>.merge`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn set_upstream(
+        &self,
+        branch_name: &str,
+        remote_name: &str,
+        remote_branch: &str,
+    ) -> Result<(), GitError> {
+        self.repo()
+            .find_branch(branch_name, BranchType::Local)
+            .context(format!("Failed to find branch '{branch_name}'"))?;
+        let mut config = self
+            .repo()
+            .config()
+            .context("Failed to get repository config")?;
+        config
+            .set_str(&format!("branch.{branch_name}.remote"), remote_name)
+            .and_then(|_| {
+                config.set_str(
+                    &format!("branch.{branch_name}.merge"),
+                    &format!("refs/heads/{remote_branch}"),
+                )
+            })
+            .context(format!("Failed to set the upstream of '{branch_name}'"))?;
+        Ok(())
+    }
+
     /// Push current HEAD branch to remote (equivalent to `git push <remote>`)
     ///
     /// # Arguments
     /// * `remote_name` - The name of the remote (e.g., "origin")
-    pub fn push_current_branch(&self, remote_name: &str) -> Result<(), Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn push_current_branch(&self, remote_name: &str) -> Result<(), GitError> {
         // Get current branch name from HEAD
         let head_target = self
             .get_head_symbolic_target()
@@ -108,17 +200,19 @@ impl GitRepo {
     }
 
     /// Push current branch to origin remote (equivalent to `git push`)
-    pub fn push_to_origin(&self) -> Result<(), Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn push_to_origin(&self) -> Result<(), GitError> {
         self.push_current_branch("origin")
     }
 
     /// Force-push a specific commit SHA to a remote branch using --force-with-lease.
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn force_push_commit_to_branch(
         &self,
         remote_name: &str,
         commit_sha: &str,
         branch_name: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<(), GitError> {
         let status = Command::new("git")
             .arg("push")
             .arg("--force-with-lease")
@@ -131,19 +225,25 @@ impl GitRepo {
         if !status.success() {
             return Err(anyhow::anyhow!(
                 "Failed to force-push commit '{commit_sha}' to '{remote_name}/{branch_name}'"
-            ));
+            )
+            .into());
         }
+        self.emit(GitEvent::Pushed {
+            remote: remote_name.to_string(),
+            refspec: format!("{commit_sha}:refs/heads/{branch_name}"),
+        });
 
         Ok(())
     }
 
     /// Push a specific commit SHA to a remote branch (fast-forward only).
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn push_commit_to_branch(
         &self,
         remote_name: &str,
         commit_sha: &str,
         branch_name: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<(), GitError> {
         let status = Command::new("git")
             .arg("push")
             .arg(remote_name)
@@ -155,14 +255,24 @@ impl GitRepo {
         if !status.success() {
             return Err(anyhow::anyhow!(
                 "Failed to push commit '{commit_sha}' to '{remote_name}/{branch_name}'"
-            ));
+            )
+            .into());
         }
+        self.emit(GitEvent::Pushed {
+            remote: remote_name.to_string(),
+            refspec: format!("{commit_sha}:refs/heads/{branch_name}"),
+        });
 
         Ok(())
     }
 
     /// Delete a remote branch reference.
-    pub fn delete_remote_branch(&self, remote_name: &str, branch_name: &str) -> Result<(), Error> {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn delete_remote_branch(
+        &self,
+        remote_name: &str,
+        branch_name: &str,
+    ) -> Result<(), GitError> {
         let status = Command::new("git")
             .arg("push")
             .arg(remote_name)
@@ -174,8 +284,13 @@ impl GitRepo {
         if !status.success() {
             return Err(anyhow::anyhow!(
                 "Failed to delete remote branch '{remote_name}/{branch_name}'"
-            ));
+            )
+            .into());
         }
+        self.emit(GitEvent::RemoteBranchDeleted {
+            remote: remote_name.to_string(),
+            branch: branch_name.to_string(),
+        });
 
         Ok(())
     }
@@ -241,6 +356,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rename_and_remove_remote_move_tracking_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let (_remote_dir, remote_repo) = create_test_bare_repo();
+        let (_local_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("test.txt", "content", "Initial commit")?;
+        repo.add_local_remote("origin", &remote_repo)?;
+        repo.push_and_set_upstream("origin", "master")?;
+        repo.fetch("origin", None)?;
+        assert!(repo.remote_tracking_branch_exists("origin/master"));
+
+        assert!(repo.rename_remote("origin", "upstream")?.is_empty());
+        assert_eq!(repo.get_remote_names()?, vec!["upstream"]);
+        assert!(repo.remote_tracking_branch_exists("upstream/master"));
+        assert!(!repo.remote_tracking_branch_exists("origin/master"));
+        assert_eq!(
+            repo.get_upstream_remote_branch("master")?,
+            Some(("upstream".to_string(), "master".to_string()))
+        );
+
+        repo.remove_remote("upstream")?;
+        assert!(repo.get_remote_names()?.is_empty());
+        assert!(!repo.remote_tracking_branch_exists("upstream/master"));
+        assert_eq!(repo.get_upstream_remote_branch("master")?, None);
+        assert!(repo.remove_remote("upstream").is_err());
+        Ok(())
+    }
+
     #[test]
     fn get_remotes_works() {
         let temp_dir = assert_fs::TempDir::new().unwrap();
@@ -310,6 +452,30 @@ mod tests {
         assert_eq!(remote_branches, vec!["master"]);
     }
 
+    #[test]
+    fn push_and_set_upstream_configures_tracking() -> Result<(), Box<dyn std::error::Error>> {
+        let (_remote_dir, remote_repo) = create_test_bare_repo();
+        let (_local_dir, local_repo) = create_test_repo();
+        local_repo.add_file_and_commit("test.txt", "content", "Initial commit")?;
+        local_repo.add_local_remote("origin", &remote_repo)?;
+        local_repo.create_and_checkout_branch("feature")?;
+        assert!(local_repo.get_remote_tracking_info("feature").is_err());
+
+        local_repo.push_and_set_upstream("origin", "feature")?;
+        assert_eq!(remote_repo.get_all_branches()?, vec!["feature"]);
+        assert_eq!(
+            local_repo.get_remote_tracking_info("feature")?,
+            "origin/feature"
+        );
+        assert_eq!(
+            local_repo
+                .get_config_string("branch.feature.merge")?
+                .as_deref(),
+            Some("refs/heads/feature")
+        );
+        Ok(())
+    }
+
     #[test]
     fn push_current_branch_works() {
         let (_remote_dir, remote_repo) = create_test_bare_repo();
@@ -0,0 +1,279 @@
+//! Config lookups that follow the whole include chain.
+//!
+//! libgit2 resolves `include.path` and the `gitdir:`, `gitdir/i:` and `onbranch:` conditions
+//! of `includeIf` itself, but skips `includeIf "hasconfig:remote.*.url:..."` sections, the
+//! usual way to switch to a work identity for every clone of a work organisation. Those
+//! sections are evaluated here against the repository's remote URLs.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use git2::{Config, ConfigLevel, ErrorCode};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+const HASCONFIG_REMOTE_URL: &str = "hasconfig:remote.*.url:";
+
+/// The repository's config plus the files pulled in by matching `hasconfig` includes
+pub(crate) struct ResolvedConfig {
+    config: Config,
+    /// Included files with the level of the config file that included them
+    includes: Vec<(ConfigLevel, Config)>,
+}
+
+impl ResolvedConfig {
+    pub(crate) fn get_string(&self, name: &str) -> Result<Option<String>, git2::Error> {
+        match self.source_of(name)? {
+            Some(config) => config.get_string(name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn get_bool(&self, name: &str) -> Result<Option<bool>, git2::Error> {
+        match self.source_of(name)? {
+            Some(config) => config.get_bool(name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// All `(name, value)` entries, with included files after the ones that include them
+    pub(crate) fn entries(&self) -> Result<Vec<(String, String)>, git2::Error> {
+        let mut result = Vec::new();
+        for config in std::iter::once(&self.config).chain(self.includes.iter().map(|(_, c)| c)) {
+            let mut entries = config.entries(None)?;
+            while let Some(entry) = entries.next() {
+                let entry = entry?;
+                if let (Some(name), Some(value)) = (entry.name(), entry.value()) {
+                    result.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// The config whose value for `name` wins: an included file overrides values from the
+    /// same or a lower level, like the include itself would
+    fn source_of(&self, name: &str) -> Result<Option<&Config>, git2::Error> {
+        let mut winner = match self.config.get_entry(name) {
+            Ok(entry) => Some((level_rank(entry.level()), &self.config)),
+            Err(err) if err.code() == ErrorCode::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        for (level, config) in &self.includes {
+            if config.get_entry(name).is_err() {
+                continue;
+            }
+            let rank = level_rank(*level);
+            if winner.map_or(true, |(best, _)| rank >= best) {
+                winner = Some((rank, config));
+            }
+        }
+        Ok(winner.map(|(_, config)| config))
+    }
+}
+
+impl GitRepo {
+    /// Open the repository's config with every include resolved
+    pub(crate) fn resolved_config(&self) -> Result<ResolvedConfig, GitError> {
+        let config = self
+            .repo()
+            .config()
+            .context("Failed to get repository config")?;
+
+        let mut remote_urls = Vec::new();
+        let mut conditional = Vec::new();
+        let mut entries = config
+            .entries(None)
+            .context("Failed to read repository config entries")?;
+        while let Some(entry) = entries.next() {
+            let entry = entry.context("Failed to read config entry")?;
+            let (Some(name), Some(value)) = (entry.name(), entry.value()) else {
+                continue;
+            };
+            if name.starts_with("remote.") && name.ends_with(".url") {
+                remote_urls.push(value.to_string());
+            } else if let Some(pattern) = name
+                .strip_prefix("includeif.")
+                .and_then(|rest| rest.strip_suffix(".path"))
+                .and_then(|condition| condition.strip_prefix(HASCONFIG_REMOTE_URL))
+            {
+                conditional.push((entry.level(), pattern.to_string(), value.to_string()));
+            }
+        }
+        drop(entries);
+
+        let mut includes = Vec::new();
+        for (level, pattern, path) in conditional {
+            if !remote_urls.iter().any(|url| wildmatch(&pattern, url)) {
+                continue;
+            }
+            let path = self.include_path(level, &path);
+            // Like git, a missing include file is not an error
+            if !path.is_file() {
+                continue;
+            }
+            let included = Config::open(&path).context(format!(
+                "Failed to read included config '{}'",
+                path.display()
+            ))?;
+            includes.push((level, included));
+        }
+
+        Ok(ResolvedConfig { config, includes })
+    }
+
+    /// Resolve an include path: `~/` is the home directory and relative paths are relative
+    /// to the directory of the including config file
+    fn include_path(&self, level: ConfigLevel, path: &str) -> PathBuf {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        if let (Some(rest), Some(home)) = (path.strip_prefix("~/"), &home) {
+            return home.join(rest);
+        }
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        let base = match level {
+            ConfigLevel::Local | ConfigLevel::Worktree => {
+                Some(self.repo().commondir().to_path_buf())
+            }
+            ConfigLevel::Global => home,
+            ConfigLevel::XDG => std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.map(|home| home.join(".config")))
+                .map(|config| config.join("git")),
+            ConfigLevel::System => Some(PathBuf::from("/etc")),
+            _ => None,
+        };
+        match base {
+            Some(base) => base.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+/// Priority of a config level; higher levels override lower ones
+fn level_rank(level: ConfigLevel) -> u8 {
+    match level {
+        ConfigLevel::ProgramData => 0,
+        ConfigLevel::System => 1,
+        ConfigLevel::XDG => 2,
+        ConfigLevel::Global => 3,
+        ConfigLevel::Local => 4,
+        ConfigLevel::Worktree => 5,
+        ConfigLevel::App => 6,
+        ConfigLevel::Highest => 7,
+    }
+}
+
+/// Match a URL against a `hasconfig` pattern: `*` matches within a path segment, `**` across
+/// segments and `?` any single character except `/`
+pub(crate) fn wildmatch(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern {
+            [] => text.is_empty(),
+            [b'*', b'*', rest @ ..] => (0..=text.len()).any(|i| matches(rest, &text[i..])),
+            [b'*', rest @ ..] => (0..=text.len())
+                .take_while(|&i| i == 0 || text[i - 1] != b'/')
+                .any(|i| matches(rest, &text[i..])),
+            [b'?', rest @ ..] => {
+                matches!(text.first(), Some(&c) if c != b'/') && matches(rest, &text[1..])
+            }
+            [c, rest @ ..] => text.first() == Some(c) && matches(rest, &text[1..]),
+        }
+    }
+    matches(pattern.as_bytes(), text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wildmatch;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn hasconfig_includes_apply_when_a_remote_url_matches() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (dir, repo) = create_test_repo();
+        let work = dir.path().join("work.gitconfig");
+        std::fs::write(&work, "[user]\n\temail = me@acme.example\n")?;
+        let other = dir.path().join("other.gitconfig");
+        std::fs::write(&other, "[user]\n\tname = Someone Else\n")?;
+
+        let mut config = repo.repo().config()?;
+        config.set_str(
+            "includeIf.hasconfig:remote.*.url:https://github.com/acme/**.path",
+            work.to_str().unwrap(),
+        )?;
+        config.set_str(
+            "includeIf.hasconfig:remote.*.url:https://gitlab.com/**.path",
+            other.to_str().unwrap(),
+        )?;
+
+        // Without a matching remote the repository identity is used
+        assert_eq!(
+            repo.get_config_string("user.email")?.as_deref(),
+            Some("test@example.com")
+        );
+
+        repo.add_remote("origin", "https://github.com/acme/app.git")?;
+        assert_eq!(
+            repo.get_config_string("user.email")?.as_deref(),
+            Some("me@acme.example")
+        );
+        assert_eq!(
+            repo.get_config_string("user.name")?.as_deref(),
+            Some("Test User")
+        );
+
+        repo.add_file("README.md", "hello")?.add(&["README.md"])?;
+        let id = repo.commit("Initial commit")?;
+        let commit = repo.repo().find_commit(git2::Oid::from_str(&id)?)?;
+        assert_eq!(commit.author().email(), Some("me@acme.example"));
+        Ok(())
+    }
+
+    #[test]
+    fn conditional_includes_handled_by_libgit2_still_apply(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (dir, repo) = create_test_repo();
+        let branch = dir.path().join("branch.gitconfig");
+        std::fs::write(&branch, "[xgit]\n\tlocale = zh-CN\n")?;
+        repo.repo()
+            .config()?
+            .set_str("includeIf.onbranch:master.path", branch.to_str().unwrap())?;
+
+        assert_eq!(
+            repo.get_config_string("xgit.locale")?.as_deref(),
+            Some("zh-CN")
+        );
+        assert!(repo
+            .get_config_entries("xgit.")?
+            .contains(&("xgit.locale".to_string(), "zh-CN".to_string())));
+        Ok(())
+    }
+
+    #[test]
+    fn wildmatch_follows_git_glob_rules() {
+        assert!(wildmatch(
+            "https://github.com/acme/**",
+            "https://github.com/acme/app.git"
+        ));
+        assert!(wildmatch(
+            "https://github.com/*/app.git",
+            "https://github.com/acme/app.git"
+        ));
+        assert!(!wildmatch(
+            "https://github.com/*",
+            "https://github.com/acme/app.git"
+        ));
+        assert!(wildmatch(
+            "git@github.com:acme/?pp.git",
+            "git@github.com:acme/app.git"
+        ));
+        assert!(!wildmatch(
+            "https://github.com/acme/**",
+            "https://gitlab.com/acme/app.git"
+        ));
+    }
+}
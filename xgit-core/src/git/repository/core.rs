@@ -0,0 +1,533 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::git::commits::builder::FixedSignature;
+use crate::git::error::GitError;
+use crate::git::remotes::credentials::{credential_callbacks, CredentialConfig};
+use crate::git::repository::events::Observers;
+use git2::build::RepoBuilder;
+use git2::Repository;
+
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author time in seconds since the Unix epoch
+    pub time: i64,
+    /// Author timezone offset in minutes
+    pub offset_minutes: i32,
+    /// Ids of the parent commits, first parent first; empty for a root commit
+    pub parent_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: String,
+}
+
+pub struct GitRepo {
+    /// Working tree root, or the git directory for bare repositories
+    path: PathBuf,
+    workdir: Option<PathBuf>,
+    repo: Repository,
+    observers: Observers,
+    fixed_signature: RefCell<Option<FixedSignature>>,
+}
+
+impl GitRepo {
+    /// Open a git repository at the specified path
+    #[tracing::instrument(level = "debug", skip(path), fields(path = %path.as_ref().display()))]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        let repo = Repository::discover(path).context("Cannot open git repo at given path")?;
+        Ok(Self::from_repository(repo))
+    }
+
+    #[tracing::instrument(level = "debug", skip(path), fields(path = %path.as_ref().display()))]
+    pub fn init<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        let path_ref = path.as_ref();
+
+        // Check if it's already a git repository
+        if Repository::open(path_ref).is_ok() {
+            return Err(anyhow::anyhow!("Directory is already a git repository").into());
+        }
+
+        // Initialize a new git repository
+        let repo = Repository::init(path_ref).context("Failed to initialize git repository")?;
+        let git_repo = Self::from_repository(repo);
+
+        // TODO: init should respect config to create master/main
+
+        // Set HEAD to point to master (this is what git init does)
+        // The master branch will be created when the first commit is made
+        git_repo
+            .repo
+            .set_head("refs/heads/master")
+            .context("Failed to set HEAD to master")?;
+
+        Ok(git_repo)
+    }
+
+    /// Initialize a new bare git repository
+    #[tracing::instrument(level = "debug", skip(path), fields(path = %path.as_ref().display()))]
+    pub fn init_bare<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        let path_ref = path.as_ref();
+
+        // Check if it's already a git repository
+        if Repository::open(path_ref).is_ok() {
+            return Err(anyhow::anyhow!("Directory is already a git repository").into());
+        }
+
+        // Initialize a new bare git repository
+        let repo =
+            Repository::init_bare(path_ref).context("Failed to initialize bare git repository")?;
+        let git_repo = Self::from_repository(repo);
+
+        // Set HEAD to point to master (this is what git init --bare does)
+        git_repo
+            .repo
+            .set_head("refs/heads/master")
+            .context("Failed to set HEAD to master")?;
+
+        Ok(git_repo)
+    }
+
+    /// Clone `url` into `path` and open the result, authenticating with the global
+    /// `xgit.auth.*` settings
+    #[tracing::instrument(level = "debug", skip(path), fields(path = %path.as_ref().display()))]
+    pub fn clone<P: AsRef<Path>>(url: &str, path: P) -> Result<Self, GitError> {
+        let git_config = git2::Config::open_default().context("Failed to open git config")?;
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(credential_callbacks(
+            CredentialConfig::from_git_config(&git_config)?,
+            git_config,
+        ));
+        RepoBuilder::new()
+            .fetch_options(fetch_options)
+            .clone(url, path.as_ref())
+            .context(format!("Failed to clone '{url}'"))?;
+        Self::open(path)
+    }
+
+    /// Wrap an opened repository, resolving its working tree from the repository itself
+    /// rather than from the path it was opened with
+    fn from_repository(repo: Repository) -> Self {
+        let workdir = repo.workdir().map(normalize_repo_path);
+        let path = workdir
+            .clone()
+            .unwrap_or_else(|| normalize_repo_path(repo.path()));
+        Self {
+            path,
+            workdir,
+            repo,
+            observers: Observers::default(),
+            fixed_signature: RefCell::default(),
+        }
+    }
+
+    /// Get the path to the repository: the working tree root, or the git directory when the
+    /// repository is bare
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Root of the working tree, or `None` for bare repositories.
+    ///
+    /// This is the checkout a linked worktree was opened from and the parent of `.git` when
+    /// the repository was opened through its git directory.
+    pub fn workdir(&self) -> Option<&Path> {
+        self.workdir.as_deref()
+    }
+
+    /// Get the git directory for the repository (e.g. `.git` for non-bare repos, or
+    /// `.git/worktrees/<name>` for linked worktrees).
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
+    }
+
+    /// Directory git runs hooks from: `core.hooksPath` (relative to the worktree) or `hooks`
+    /// inside the git directory
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn hooks_dir(&self) -> PathBuf {
+        match self.get_config_string("core.hooksPath").ok().flatten() {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                if path.is_relative() {
+                    self.path().join(path)
+                } else {
+                    path
+                }
+            }
+            None => self.git_dir().join("hooks"),
+        }
+    }
+
+    /// Check if this is a bare repository
+    pub fn is_bare(&self) -> bool {
+        self.repo.is_bare()
+    }
+
+    /// Fail with [`GitError::UnsupportedOnBareRepo`] unless the repository has a working tree
+    pub(crate) fn require_worktree(&self, operation: &str) -> Result<(), GitError> {
+        if self.is_bare() {
+            return Err(GitError::UnsupportedOnBareRepo(anyhow::anyhow!(
+                "Cannot {operation} in a bare repository: it has no working tree"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get access to the internal git2 Repository
+    pub(crate) fn repo(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// A fresh handle for stash operations, which need `&mut Repository` and must see the
+    /// index as it is on disk after checkouts made through `self`
+    pub(crate) fn open_mut(&self) -> Result<Repository, GitError> {
+        Repository::open(self.git_dir())
+            .context("Failed to open repository")
+            .map_err(GitError::from)
+    }
+
+    pub(crate) fn observers(&self) -> &Observers {
+        &self.observers
+    }
+
+    pub(crate) fn fixed_signature(&self) -> &RefCell<Option<FixedSignature>> {
+        &self.fixed_signature
+    }
+
+    /// Set user configuration for commits
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn set_user_config(&self, name: &str, email: &str) -> Result<(), GitError> {
+        let mut config = self
+            .repo()
+            .config()
+            .context("Failed to get repository config")?;
+
+        config
+            .set_str("user.name", name)
+            .context("Failed to set user.name")?;
+
+        config
+            .set_str("user.email", email)
+            .context("Failed to set user.email")?;
+
+        Ok(())
+    }
+
+    /// Read a boolean config value, returning `None` when it is not set
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_config_bool(&self, name: &str) -> Result<Option<bool>, GitError> {
+        self.resolved_config()?.get_bool(name).map_err(|e| {
+            anyhow::Error::new(e)
+                .context(format!("Invalid boolean value for config '{name}'"))
+                .into()
+        })
+    }
+
+    /// Read a string config value, returning `None` when it is not set
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_config_string(&self, name: &str) -> Result<Option<String>, GitError> {
+        self.resolved_config()?.get_string(name).map_err(|e| {
+            anyhow::Error::new(e)
+                .context(format!("Failed to read config '{name}'"))
+                .into()
+        })
+    }
+
+    /// List config entries whose name starts with `prefix` (e.g. `xgit.`), in config order
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn get_config_entries(&self, prefix: &str) -> Result<Vec<(String, String)>, GitError> {
+        let entries = self
+            .resolved_config()?
+            .entries()
+            .context("Failed to read repository config entries")?;
+
+        Ok(entries
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .collect())
+    }
+}
+
+fn normalize_repo_path(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use git2::Repository;
+
+    use crate::git::branches::switch::SwitchOptions;
+    use crate::git::commits::builder::CommitOptions;
+    use crate::git::{GitError, GitRepo};
+    use crate::test_utils::{
+        create_test_bare_repo, create_test_repo, RepoAssertions, RepoTestOperations,
+    };
+
+    #[test]
+    fn open_works() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        Repository::init(path).unwrap();
+        let repo = GitRepo::open(path);
+
+        assert_eq!(
+            fs::canonicalize(repo.unwrap().path()).unwrap(),
+            fs::canonicalize(temp_dir.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn open_works_from_nested_directory() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        Repository::init(path).unwrap();
+
+        let nested_dir = path.join("foo").join("bar");
+        fs::create_dir_all(&nested_dir).unwrap();
+
+        let repo = GitRepo::open(&nested_dir).unwrap();
+
+        assert_eq!(
+            fs::canonicalize(repo.path()).unwrap(),
+            fs::canonicalize(temp_dir.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn open_fails_in_non_git_folder() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        let repo = GitRepo::open(path);
+
+        assert!(repo.is_err());
+    }
+
+    #[test]
+    fn init_works() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        let repo = GitRepo::init(path).unwrap();
+
+        assert_eq!(repo.path(), temp_dir.path());
+
+        // `git branch` shows nothing
+        let branches = repo.get_all_branches().unwrap();
+        assert_eq!(branches.len(), 0);
+        // Assert HEAD points to master branch (symbolic reference)
+        repo.assert_current_branch("master");
+    }
+
+    #[test]
+    fn init_fails_in_git_folder() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        Repository::init(path).unwrap();
+        let repo = GitRepo::init(path);
+
+        assert!(repo.is_err());
+    }
+
+    #[test]
+    fn init_bare_works() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        let repo = GitRepo::init_bare(path).unwrap();
+
+        assert_eq!(repo.path(), temp_dir.path());
+
+        // Verify it's a bare repository
+        assert!(repo.is_bare());
+
+        let branches = repo.get_all_branches().unwrap();
+        assert_eq!(branches.len(), 0);
+        // Assert HEAD points to master branch (symbolic reference)
+        repo.assert_current_branch("master");
+    }
+
+    #[test]
+    fn get_config_entries_filters_by_prefix() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let repo = GitRepo::init(temp_dir.path()).unwrap();
+        repo.set_user_config("Test User", "test@example.com")
+            .unwrap();
+
+        let entries = repo.get_config_entries("user.").unwrap();
+        assert!(entries.contains(&("user.name".to_string(), "Test User".to_string())));
+        assert!(entries.contains(&("user.email".to_string(), "test@example.com".to_string())));
+        assert!(entries.iter().all(|(name, _)| name.starts_with("user.")));
+    }
+
+    #[test]
+    fn get_config_bool_works() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let repo = GitRepo::init(temp_dir.path()).unwrap();
+
+        assert_eq!(repo.get_config_bool("xgit.assumeYes").unwrap(), None);
+
+        let mut config = repo.repo().config().unwrap();
+        config.set_str("xgit.assumeYes", "yes").unwrap();
+        assert_eq!(repo.get_config_bool("xgit.assumeYes").unwrap(), Some(true));
+
+        config.set_str("xgit.assumeYes", "maybe").unwrap();
+        assert!(repo.get_config_bool("xgit.assumeYes").is_err());
+    }
+
+    #[test]
+    fn get_config_string_works() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let repo = GitRepo::init(temp_dir.path()).unwrap();
+
+        assert_eq!(repo.get_config_string("xgit.locale").unwrap(), None);
+
+        let mut config = repo.repo().config().unwrap();
+        config.set_str("xgit.locale", "zh-CN").unwrap();
+        assert_eq!(
+            repo.get_config_string("xgit.locale").unwrap().as_deref(),
+            Some("zh-CN")
+        );
+    }
+
+    #[test]
+    fn init_bare_fails_in_git_folder() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let path = temp_dir.path();
+        Repository::init(path).unwrap();
+        let repo = GitRepo::init_bare(path);
+
+        assert!(repo.is_err());
+    }
+
+    fn bare_repo_with_history() -> (assert_fs::TempDir, assert_fs::TempDir, GitRepo) {
+        let (remote_dir, remote_repo) = create_test_bare_repo();
+        let (local_dir, local_repo) = create_test_repo();
+        local_repo
+            .add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap()
+            .create_and_checkout_branch("feature")
+            .unwrap()
+            .add_file_and_commit("feature.txt", "feature", "Add feature")
+            .unwrap();
+        local_repo.add_local_remote("origin", &remote_repo).unwrap();
+        local_repo.push("origin", "master").unwrap();
+        local_repo.push("origin", "feature").unwrap();
+        drop(local_repo);
+        (remote_dir, local_dir, remote_repo)
+    }
+
+    #[test]
+    fn bare_repos_support_history_and_ref_operations() {
+        let (_remote_dir, _local_dir, repo) = bare_repo_with_history();
+
+        let commits = repo.list_commits_from("feature").unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(repo.ahead_behind("feature", "master").unwrap(), (1, 0));
+        assert!(repo.get_commit_diff("feature").is_ok());
+
+        repo.checkout_branch("feature").unwrap();
+        repo.assert_current_branch("feature");
+        repo.checkout_branch("master").unwrap();
+        // A fast-forward only moves the branch, so it needs no working tree
+        let result = repo.merge("feature", None).unwrap();
+        assert!(
+            matches!(result, crate::MergeOutcome::FastForward(_)),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn bare_repos_reject_worktree_operations_with_a_typed_error() {
+        let (_remote_dir, _local_dir, repo) = bare_repo_with_history();
+
+        let errors = [
+            repo.add(&["README.md"]).err(),
+            repo.diff_staged().err(),
+            repo.has_staged_changes().err(),
+            repo.is_working_tree_clean().err(),
+            repo.change_counts().err(),
+            repo.get_unstaged_changes().err(),
+            repo.commit_with(&CommitOptions::new("Nothing")).err(),
+            repo.switch_branch("feature", &SwitchOptions::default())
+                .err(),
+        ];
+        for err in errors {
+            let err = err.expect("operation should fail on a bare repository");
+            assert!(
+                matches!(err, GitError::UnsupportedOnBareRepo(_)),
+                "got {err:?}"
+            );
+            assert!(err.to_string().contains("bare repository"), "{err}");
+        }
+    }
+
+    #[test]
+    fn worktree_operations_work_in_non_bare_repos() {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+
+        assert!(repo.is_working_tree_clean().unwrap());
+        assert!(repo.change_counts().unwrap().is_clean());
+        assert!(!repo.has_staged_changes().unwrap());
+        assert!(repo.diff_staged().unwrap().is_empty());
+    }
+
+    #[test]
+    fn open_via_git_dir_resolves_the_working_tree() {
+        let (temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+
+        let opened = GitRepo::open(repo.git_dir()).unwrap();
+
+        let workdir = fs::canonicalize(temp_dir.path()).unwrap();
+        assert_eq!(fs::canonicalize(opened.path()).unwrap(), workdir);
+        assert_eq!(
+            fs::canonicalize(opened.workdir().unwrap()).unwrap(),
+            workdir
+        );
+        opened.assert_file_exists("README.md");
+        assert!(opened.is_working_tree_clean().unwrap());
+    }
+
+    #[test]
+    fn linked_worktrees_keep_their_own_workdir_and_git_dir() {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+        let linked_dir = assert_fs::TempDir::new().unwrap();
+        let linked_path = linked_dir.path().join("linked");
+        repo.repo().worktree("linked", &linked_path, None).unwrap();
+
+        let linked = GitRepo::open(&linked_path).unwrap();
+
+        let linked_path = fs::canonicalize(&linked_path).unwrap();
+        assert_eq!(
+            fs::canonicalize(linked.workdir().unwrap()).unwrap(),
+            linked_path
+        );
+        assert!(linked.git_dir().ends_with("worktrees/linked"));
+        linked.assert_file_exists("README.md");
+        assert!(!linked.is_path_ignored(&linked_path.join("README.md")));
+    }
+
+    #[test]
+    fn bare_repos_have_no_workdir() {
+        let (_dir, repo) = create_test_bare_repo();
+
+        assert!(repo.workdir().is_none());
+        assert_eq!(
+            fs::canonicalize(repo.path()).unwrap(),
+            fs::canonicalize(repo.git_dir()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::cell::RefCell;
+use std::sync::mpsc::{self, Receiver};
+
+use crate::git::repository::core::GitRepo;
+
+/// Something a [`GitRepo`] operation changed, delivered to its observers after the change
+/// has succeeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GitEvent {
+    /// A commit was created and HEAD moved to it
+    CommitCreated { id: String, summary: String },
+    /// A local branch was created
+    BranchCreated { name: String },
+    /// A local branch was deleted
+    BranchDeleted { name: String },
+    /// HEAD now points at `branch`
+    BranchSwitched { branch: String },
+    /// The index was rewritten, e.g. by staging files
+    IndexUpdated,
+    /// `branch` (or HEAD) was merged into the current branch
+    Merged { branch: String },
+    /// A rebase of `branch` (or `HEAD` when detached) finished
+    Rebased { branch: String },
+    /// HEAD (and the branch it is on) was reset to commit `id`
+    Reset { id: String },
+    /// Remote-tracking refs were updated from `remote`
+    Fetched { remote: String },
+    /// `branch` was updated from `remote`
+    Pulled { remote: String, branch: String },
+    /// `refspec` was pushed to `remote`
+    Pushed { remote: String, refspec: String },
+    /// `branch` was deleted on `remote`
+    RemoteBranchDeleted { remote: String, branch: String },
+    /// A tag was created
+    TagCreated { name: String },
+    /// A local tag was deleted
+    TagDeleted { name: String },
+    /// Working tree changes were stashed as commit `id`
+    StashSaved { id: String },
+    /// The stash entry `id` was applied to the working tree
+    StashApplied { id: String },
+    /// The stash entry `id` was removed from the stash
+    StashDropped { id: String },
+}
+
+type Observer = Box<dyn Fn(&GitEvent) + Send>;
+
+/// Callbacks registered on one [`GitRepo`] handle
+#[derive(Default)]
+pub(crate) struct Observers(RefCell<Vec<Observer>>);
+
+impl GitRepo {
+    /// Call `observer` for every [`GitEvent`] raised through this handle.
+    ///
+    /// Observers run synchronously on the thread performing the operation, so they should
+    /// return quickly; they must not subscribe further observers from inside the callback.
+    pub fn subscribe(&self, observer: impl Fn(&GitEvent) + Send + 'static) {
+        self.observers().0.borrow_mut().push(Box::new(observer));
+    }
+
+    /// Receive every [`GitEvent`] raised through this handle on a channel, e.g. to react on
+    /// another thread. Events are dropped once the receiver is gone.
+    pub fn events(&self) -> Receiver<GitEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribe(move |event| {
+            let _ = sender.send(event.clone());
+        });
+        receiver
+    }
+
+    pub(crate) fn emit(&self, event: GitEvent) {
+        for observer in self.observers().0.borrow().iter() {
+            observer(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitEvent;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn operations_notify_subscribers_and_channels() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        repo.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+        let events = repo.events();
+
+        repo.add_file("a.txt", "a")?.add(&["a.txt"])?;
+        let id = repo.commit("Add a\n\nWith a body")?;
+        repo.create_and_checkout_branch("feature")?;
+        repo.checkout_branch("master")?;
+        repo.delete_branch("feature")?;
+
+        let expected = vec![
+            GitEvent::IndexUpdated,
+            GitEvent::CommitCreated {
+                id,
+                summary: "Add a".to_string(),
+            },
+            GitEvent::BranchCreated {
+                name: "feature".to_string(),
+            },
+            GitEvent::BranchSwitched {
+                branch: "feature".to_string(),
+            },
+            GitEvent::BranchSwitched {
+                branch: "master".to_string(),
+            },
+            GitEvent::BranchDeleted {
+                name: "feature".to_string(),
+            },
+        ];
+        assert_eq!(*seen.lock().unwrap(), expected);
+        assert_eq!(events.try_iter().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_operations_raise_no_events() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        let events = repo.events();
+        assert!(repo.commit("Nothing staged").is_err());
+        assert!(repo.delete_branch("missing").is_err());
+        assert_eq!(events.try_iter().count(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+
+/// How long to wait for another git process to release `index.lock`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LockRetry {
+    pub attempts: u32,
+    pub initial_delay: Duration,
+}
+
+impl Default for LockRetry {
+    /// Six attempts doubling from 20ms: about 1.3s in total, enough for a concurrent
+    /// `git status` or editor integration to finish
+    fn default() -> Self {
+        Self {
+            attempts: 6,
+            initial_delay: Duration::from_millis(20),
+        }
+    }
+}
+
+impl GitRepo {
+    /// Path of the lock file git holds while it rewrites the index
+    pub fn index_lock_path(&self) -> PathBuf {
+        self.git_dir().join("index.lock")
+    }
+
+    /// Wait with backoff while another process holds `index.lock`, failing with
+    /// [`GitError::Locked`] if it is not released in time
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn wait_for_index_lock(&self) -> Result<(), GitError> {
+        self.wait_for_index_lock_with(LockRetry::default())
+    }
+
+    /// Re-read the index from disk, apply `update` and write it back.
+    ///
+    /// Refreshing first means changes another process made since this handle last read the
+    /// index are kept rather than overwritten. A held `index.lock` is waited out with backoff;
+    /// `update` runs again on the refreshed index after each wait.
+    #[tracing::instrument(level = "debug", skip(self, update))]
+    pub fn update_index<T>(
+        &self,
+        update: impl FnMut(&mut git2::Index) -> Result<T, GitError>,
+    ) -> Result<T, GitError> {
+        self.update_index_with(LockRetry::default(), update)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, retry))]
+    pub(crate) fn wait_for_index_lock_with(&self, retry: LockRetry) -> Result<(), GitError> {
+        let lock = self.index_lock_path();
+        let mut delay = retry.initial_delay;
+        for _ in 0..retry.attempts {
+            if !lock.exists() {
+                return Ok(());
+            }
+            thread::sleep(delay);
+            delay *= 2;
+        }
+        if lock.exists() {
+            return Err(self.locked_error());
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, retry, update))]
+    pub(crate) fn update_index_with<T>(
+        &self,
+        retry: LockRetry,
+        mut update: impl FnMut(&mut git2::Index) -> Result<T, GitError>,
+    ) -> Result<T, GitError> {
+        let mut delay = retry.initial_delay;
+        for attempt in 0..=retry.attempts {
+            self.wait_for_index_lock_with(retry)?;
+
+            let mut index = self
+                .repo()
+                .index()
+                .context("Failed to get repository index")?;
+            index
+                .read(true)
+                .context("Failed to refresh index from disk")?;
+            let value = update(&mut index)?;
+
+            match index.write() {
+                Ok(()) => {
+                    self.emit(GitEvent::IndexUpdated);
+                    return Ok(value);
+                }
+                // Someone took the lock between our check and our write
+                Err(err) if err.code() == git2::ErrorCode::Locked && attempt < retry.attempts => {
+                    thread::sleep(delay);
+                    delay *= 2;
+                }
+                Err(err) if err.code() == git2::ErrorCode::Locked => {
+                    return Err(self.locked_error())
+                }
+                Err(err) => {
+                    return Err(anyhow::Error::new(err)
+                        .context("Failed to write index")
+                        .into())
+                }
+            }
+        }
+        Err(self.locked_error())
+    }
+
+    fn locked_error(&self) -> GitError {
+        GitError::Locked(anyhow::anyhow!(
+            "Another git process seems to be running in this repository: '{}' exists. \
+             If no other git process is running, remove the file and try again.",
+            self.index_lock_path().display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LockRetry;
+    use crate::git::GitError;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::time::Duration;
+
+    const QUICK: LockRetry = LockRetry {
+        attempts: 3,
+        initial_delay: Duration::from_millis(5),
+    };
+
+    #[test]
+    fn held_lock_fails_with_a_clear_error() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file("a.txt", "a")?;
+        std::fs::write(repo.index_lock_path(), "")?;
+
+        let err = repo
+            .update_index_with(QUICK, |index| Ok(index.len()))
+            .unwrap_err();
+        assert!(matches!(err, GitError::Locked(_)), "got {err:?}");
+        assert!(err.to_string().contains("Another git process"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn released_lock_is_waited_out() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file("a.txt", "a")?;
+        let lock = repo.index_lock_path();
+        std::fs::write(&lock, "")?;
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            std::fs::remove_file(lock).unwrap();
+        });
+
+        let retry = LockRetry {
+            attempts: 8,
+            initial_delay: Duration::from_millis(5),
+        };
+        repo.update_index_with(retry, |index| {
+            Ok(index.add_path(std::path::Path::new("a.txt"))?)
+        })?;
+        releaser.join().unwrap();
+        assert_eq!(repo.repo().index()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn update_index_keeps_changes_made_by_other_handles() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file("a.txt", "a")?.add_file("b.txt", "b")?;
+        // Load this handle's index before another process stages a.txt
+        repo.repo().index()?;
+        let other = git2::Repository::open(repo.path())?;
+        let mut other_index = other.index()?;
+        other_index.add_path(std::path::Path::new("a.txt"))?;
+        other_index.write()?;
+
+        repo.update_index(|index| Ok(index.add_path(std::path::Path::new("b.txt"))?))?;
+        assert_eq!(git2::Repository::open(repo.path())?.index()?.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod core;
+pub mod events;
+pub mod index_lock;
+pub mod pool;
+pub mod reflog;
+pub mod refs;
+pub mod signature;
@@ -0,0 +1,129 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::Context;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+
+/// A `Send + Sync` source of [`GitRepo`] handles for work spread across threads.
+///
+/// `git2::Repository` can move between threads but not be shared, so each task borrows its own
+/// handle. Handles are opened lazily and returned to the pool when dropped, so a pool used by
+/// N concurrent tasks opens at most N repositories.
+pub struct GitRepoPool {
+    git_dir: PathBuf,
+    idle: Mutex<Vec<GitRepo>>,
+}
+
+/// A handle borrowed from a [`GitRepoPool`], returned to it on drop
+pub struct PooledRepo<'a> {
+    pool: &'a GitRepoPool,
+    repo: Option<GitRepo>,
+}
+
+impl GitRepoPool {
+    /// Create a pool for the repository containing `path`
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        let repo = GitRepo::open(path)?;
+        Ok(Self {
+            git_dir: repo.git_dir().to_path_buf(),
+            idle: Mutex::new(vec![repo]),
+        })
+    }
+
+    /// Borrow a handle, opening a new one if every pooled handle is in use
+    pub fn get(&self) -> Result<PooledRepo<'_>, GitError> {
+        let idle = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+        let repo = match idle {
+            Some(repo) => repo,
+            None => GitRepo::open(&self.git_dir).context("Failed to open pooled repository")?,
+        };
+        Ok(PooledRepo {
+            pool: self,
+            repo: Some(repo),
+        })
+    }
+
+    /// Run `operation` with a borrowed handle
+    pub fn with<T>(
+        &self,
+        operation: impl FnOnce(&GitRepo) -> Result<T, GitError>,
+    ) -> Result<T, GitError> {
+        let repo = self.get()?;
+        operation(&repo)
+    }
+}
+
+impl Deref for PooledRepo<'_> {
+    type Target = GitRepo;
+
+    fn deref(&self) -> &GitRepo {
+        self.repo
+            .as_ref()
+            .expect("pooled repository is present until drop")
+    }
+}
+
+impl Drop for PooledRepo<'_> {
+    fn drop(&mut self) {
+        if let (Some(repo), Ok(mut idle)) = (self.repo.take(), self.pool.idle.lock()) {
+            idle.push(repo);
+        }
+    }
+}
+
+impl GitRepo {
+    /// Create a [`GitRepoPool`] for this repository, for handing to worker threads
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn pool(&self) -> Result<GitRepoPool, GitError> {
+        GitRepoPool::new(self.git_dir())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitRepoPool;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::sync::Arc;
+
+    #[test]
+    fn pool_is_shareable_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GitRepoPool>();
+
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")
+            .unwrap();
+        repo.create_and_checkout_branch("feature").unwrap();
+        let pool = Arc::new(repo.pool().unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                std::thread::spawn(move || pool.with(|repo| repo.get_all_branches()).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            let mut branches = handle.join().unwrap();
+            branches.sort();
+            assert_eq!(branches, vec!["feature", "master"]);
+        }
+    }
+
+    #[test]
+    fn dropped_handles_are_reused() {
+        let (_dir, repo) = create_test_repo();
+        let pool = repo.pool().unwrap();
+
+        let first = pool.get().unwrap();
+        let second = pool.get().unwrap();
+        drop(first);
+        drop(second);
+        assert_eq!(pool.idle.lock().unwrap().len(), 2);
+
+        let _again = pool.get().unwrap();
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::Context;
+use git2::{BranchType, ObjectType, ResetType};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+
+/// One entry of a reference's reflog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// Position in the reflog, newest first: `<ref>@{index}`
+    pub index: usize,
+    /// Where the reference pointed before; all zeros when it was created
+    pub old_id: String,
+    pub new_id: String,
+    /// Message as git records it, e.g. `checkout: moving from main to feature`
+    pub message: String,
+    pub committer_name: String,
+    /// When the reference moved, in seconds since the Unix epoch
+    pub time: i64,
+}
+
+impl ReflogEntry {
+    /// The operation part of the message, e.g. `commit (amend)` or `merge feature`
+    pub fn operation(&self) -> &str {
+        self.message
+            .split_once(':')
+            .map_or(self.message.as_str(), |(operation, _)| operation)
+    }
+}
+
+/// How to revert the last operation in the HEAD reflog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UndoPlan {
+    /// The last operation switched branches: switch back to `branch`
+    SwitchBack { branch: String, from: String },
+    /// The last operation moved `branch` (or a detached HEAD when `None`) from `from_id`:
+    /// move it back to `to_id`
+    Reset {
+        branch: Option<String>,
+        operation: String,
+        from_id: String,
+        to_id: String,
+    },
+}
+
+impl GitRepo {
+    /// The reflog of `refname` (e.g. `HEAD` or `refs/heads/main`), newest first
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn reflog(&self, refname: &str) -> Result<Vec<ReflogEntry>, GitError> {
+        let reflog = self
+            .repo()
+            .reflog(refname)
+            .context(format!("Failed to read the reflog of '{refname}'"))?;
+        Ok(reflog
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let committer = entry.committer();
+                ReflogEntry {
+                    index,
+                    old_id: entry.id_old().to_string(),
+                    new_id: entry.id_new().to_string(),
+                    message: entry.message().unwrap_or_default().to_string(),
+                    committer_name: String::from_utf8_lossy(committer.name_bytes()).into_owned(),
+                    time: committer.when().seconds(),
+                }
+            })
+            .collect())
+    }
+
+    /// The branch (or commit, when HEAD was detached) checked out before the current one, which
+    /// git calls `@{-1}`, or `None` when HEAD has never moved between branches
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn previous_checkout(&self) -> Result<Option<String>, GitError> {
+        Ok(self.reflog("HEAD")?.into_iter().find_map(|entry| {
+            let moves = entry.message.strip_prefix("checkout: moving from ")?;
+            moves.split_once(" to ").map(|(from, _)| from.to_string())
+        }))
+    }
+
+    /// Work out how to revert the last operation recorded in the HEAD reflog, or `None` when
+    /// there is nothing to revert.
+    ///
+    /// A checkout is reverted by switching back to the previous branch. Anything else that
+    /// moved the current branch (commit, merge, reset, rebase, pull) is reverted by moving the
+    /// branch back to where its own reflog says it was, so a rebase is undone as a whole
+    /// rather than one replayed commit at a time.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn plan_undo(&self) -> Result<Option<UndoPlan>, GitError> {
+        let Some(last) = self.reflog("HEAD")?.into_iter().next() else {
+            return Ok(None);
+        };
+
+        if let Some(moves) = last.message.strip_prefix("checkout: moving from ") {
+            let Some((from, to)) = moves.split_once(" to ") else {
+                return Err(anyhow::anyhow!("Unrecognized reflog entry '{}'", last.message).into());
+            };
+            if self.repo().find_branch(from, BranchType::Local).is_err() {
+                return Err(anyhow::anyhow!(
+                    "The last checkout left '{from}', which is not a local branch"
+                )
+                .into());
+            }
+            return Ok(Some(UndoPlan::SwitchBack {
+                branch: from.to_string(),
+                from: to.to_string(),
+            }));
+        }
+
+        let branch = self
+            .repo()
+            .head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.name().map(str::to_string));
+        let entry = match &branch {
+            Some(refname) => self.reflog(refname)?.into_iter().next().unwrap_or(last),
+            None => last,
+        };
+        if entry.old_id.bytes().all(|byte| byte == b'0') {
+            return Ok(None);
+        }
+        Ok(Some(UndoPlan::Reset {
+            branch: branch.map(|refname| {
+                refname
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(&refname)
+                    .to_string()
+            }),
+            operation: entry.operation().to_string(),
+            from_id: entry.new_id,
+            to_id: entry.old_id,
+        }))
+    }
+
+    /// Move the current branch (or a detached HEAD) to `target` and make the index and working
+    /// tree match it, like `git reset --hard`. Refuses while tracked files have uncommitted
+    /// changes, since those would be lost.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn reset_hard(&self, target: &str) -> Result<(), GitError> {
+        self.require_worktree("reset")?;
+        if self.has_uncommitted_changes()? {
+            return Err(GitError::DirtyWorktree(format!(
+                "Uncommitted changes would be lost by resetting to '{target}'. Commit or stash them first."
+            )));
+        }
+        let commit = self
+            .repo()
+            .revparse_single(target)
+            .and_then(|object| object.peel(ObjectType::Commit))
+            .context(format!("Failed to resolve '{target}'"))?;
+        self.wait_for_index_lock()?;
+        self.repo()
+            .reset(&commit, ResetType::Hard, None)
+            .context(format!("Failed to reset to '{target}'"))?;
+        self.emit(GitEvent::Reset {
+            id: commit.id().to_string(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndoPlan;
+    use crate::git::GitError;
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn reflog_lists_head_moves_newest_first() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "one", "Initial commit")?;
+        repo.add_file_and_commit("README.md", "two", "Second commit")?;
+
+        let reflog = repo.reflog("HEAD")?;
+        assert_eq!(reflog.len(), 2);
+        assert_eq!(reflog[0].index, 0);
+        assert_eq!(reflog[0].operation(), "commit");
+        assert_eq!(reflog[0].new_id, repo.list_commits()?[0].hash);
+        assert_eq!(reflog[0].old_id, reflog[1].new_id);
+        Ok(())
+    }
+
+    #[test]
+    fn undo_moves_the_branch_back_after_a_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "one", "Initial commit")?;
+        assert_eq!(repo.plan_undo()?, None);
+        let first = repo.list_commits()?[0].hash.clone();
+        repo.add_file_and_commit("README.md", "two", "Second commit")?;
+        let second = repo.list_commits()?[0].hash.clone();
+
+        let Some(UndoPlan::Reset {
+            branch,
+            from_id,
+            to_id,
+            ..
+        }) = repo.plan_undo()?
+        else {
+            panic!("expected a reset");
+        };
+        assert_eq!(branch.as_deref(), Some("master"));
+        assert_eq!((from_id, to_id.clone()), (second, first.clone()));
+
+        repo.reset_hard(&to_id)?;
+        assert_eq!(repo.list_commits()?[0].hash, first);
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("README.md"))?,
+            "one"
+        );
+
+        // The reset is itself an operation that can be undone
+        assert!(matches!(
+            repo.plan_undo()?,
+            Some(UndoPlan::Reset { operation, .. }) if operation == "reset"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn undo_switches_back_after_a_checkout() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "one", "Initial commit")?;
+        repo.create_and_checkout_branch("feature")?;
+
+        assert_eq!(
+            repo.plan_undo()?,
+            Some(UndoPlan::SwitchBack {
+                branch: "master".to_string(),
+                from: "feature".to_string(),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn previous_checkout_is_the_branch_left_last() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "one", "Initial commit")?;
+        assert_eq!(repo.previous_checkout()?, None);
+
+        repo.create_and_checkout_branch("feature")?;
+        repo.add_file_and_commit("README.md", "two", "Second commit")?;
+        assert_eq!(repo.previous_checkout()?.as_deref(), Some("master"));
+        Ok(())
+    }
+
+    #[test]
+    fn reset_hard_refuses_to_discard_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "one", "Initial commit")?;
+        repo.add_file_and_commit("README.md", "two", "Second commit")?;
+        std::fs::write(repo.path().join("README.md"), "edited")?;
+
+        assert!(matches!(
+            repo.reset_hard("HEAD~1"),
+            Err(GitError::DirtyWorktree(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Context;
+
+use crate::git::error::GitError;
+use std::collections::BTreeMap;
+
+use super::core::GitRepo;
+
+impl GitRepo {
+    /// Map every reference (plus `HEAD`) to the commit it currently points at
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn snapshot_refs(&self) -> Result<BTreeMap<String, String>, GitError> {
+        let mut refs = BTreeMap::new();
+
+        if let Some(head) = self.repo().head().ok().and_then(|head| head.target()) {
+            refs.insert("HEAD".to_string(), head.to_string());
+        }
+
+        let references = self
+            .repo()
+            .references()
+            .context("Failed to list references")?;
+        for reference in references {
+            let reference = reference.context("Failed to read reference")?;
+            let Ok(resolved) = reference.resolve() else {
+                continue;
+            };
+            if let (Some(name), Some(target)) = (reference.name(), resolved.target()) {
+                refs.insert(name.to_string(), target.to_string());
+            }
+        }
+
+        Ok(refs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn snapshot_refs_includes_head_and_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        assert!(repo.snapshot_refs()?.is_empty());
+
+        repo.add_file_and_commit("README.md", "initial", "Initial commit")?;
+        repo.create_and_checkout_branch("feature")?;
+
+        let refs = repo.snapshot_refs()?;
+        let head = refs.get("HEAD").cloned().unwrap();
+        assert_eq!(refs.get("refs/heads/feature"), Some(&head));
+        assert_eq!(refs.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::Context;
+
+use crate::git::commits::builder::{CommitTime, FixedSignature, Identity};
+use crate::git::commits::date::parse_git_date;
+use crate::git::error::GitError;
+use git2::{Signature, Time};
+
+use super::core::GitRepo;
+
+/// Which side of a commit a signature is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    Author,
+    Committer,
+}
+
+impl Role {
+    /// Prefix of the `GIT_*_NAME`, `GIT_*_EMAIL` and `GIT_*_DATE` overrides for this role
+    fn env_prefix(self) -> &'static str {
+        match self {
+            Role::Author => "GIT_AUTHOR",
+            Role::Committer => "GIT_COMMITTER",
+        }
+    }
+}
+
+impl GitRepo {
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) fn create_signature(&self) -> Result<Signature<'_>, GitError> {
+        // Includes matter here: work and personal identities are often split by includeIf
+        let config = self.resolved_config()?;
+
+        let author_name = config.get_string("user.name").ok().flatten().context(
+            "Failed to get user.name from git config. Run: git config user.name \"Your Name\"",
+        )?;
+
+        let author_email = config.get_string("user.email").ok().flatten()
+            .context("Failed to get user.email from git config. Run: git config user.email \"your@email.com\"")?;
+
+        Signature::now(&author_name, &author_email)
+            .context("Failed to create signature with git config values")
+            .map_err(GitError::from)
+    }
+
+    /// Stamp every commit this handle creates with `signature` instead of the environment,
+    /// config and clock, or go back to those with `None`.
+    ///
+    /// Commit hashes then depend only on content and history, so tests can assert on them.
+    /// Identities and dates passed explicitly, as in [`CommitOptions`], still win.
+    ///
+    /// [`CommitOptions`]: crate::CommitOptions
+    pub fn set_fixed_signature(&self, signature: Option<FixedSignature>) {
+        *self.fixed_signature().borrow_mut() = signature;
+    }
+
+    /// Signature for the author or committer of a new commit.
+    ///
+    /// An explicit identity or time wins, then a [fixed signature](Self::set_fixed_signature);
+    /// otherwise the `GIT_AUTHOR_*` / `GIT_COMMITTER_*` environment variables apply like they
+    /// do for `git commit`, then `user.name` and `user.email`, then the current time.
+    pub(crate) fn commit_signature(
+        &self,
+        role: Role,
+        identity: Option<&Identity>,
+        time: Option<CommitTime>,
+    ) -> Result<Signature<'static>, GitError> {
+        self.commit_signature_from_env(role, identity, time, |key| std::env::var(key).ok())
+    }
+
+    fn commit_signature_from_env(
+        &self,
+        role: Role,
+        identity: Option<&Identity>,
+        time: Option<CommitTime>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Signature<'static>, GitError> {
+        let fixed = self.fixed_signature().borrow().clone();
+        let prefix = role.env_prefix();
+        let identity = match (identity, &fixed) {
+            (Some(identity), _) => identity.clone(),
+            (None, Some(fixed)) => fixed.identity.clone(),
+            (None, None) => {
+                let name = env(&format!("{prefix}_NAME"));
+                let email = env(&format!("{prefix}_EMAIL"));
+                match (name, email) {
+                    (Some(name), Some(email)) => Identity { name, email },
+                    (name, email) => {
+                        let default = self.create_signature()?;
+                        Identity {
+                            name: name
+                                .unwrap_or_else(|| default.name().unwrap_or_default().to_string()),
+                            email: email
+                                .unwrap_or_else(|| default.email().unwrap_or_default().to_string()),
+                        }
+                    }
+                }
+            }
+        };
+        let time = match (
+            time.or(fixed.map(|fixed| fixed.time)),
+            env(&format!("{prefix}_DATE")),
+        ) {
+            (Some(time), _) => Some(time),
+            (None, Some(date)) => Some(
+                parse_git_date(&date)
+                    .with_context(|| format!("Invalid date in {prefix}_DATE: '{date}'"))?,
+            ),
+            (None, None) => None,
+        };
+
+        let Identity { name, email } = &identity;
+        let signature = match time {
+            Some(time) => {
+                Signature::new(name, email, &Time::new(time.seconds, time.offset_minutes))
+            }
+            None => Signature::now(name, email),
+        };
+        signature
+            .context(format!("Invalid identity '{name} <{email}>'"))
+            .map_err(GitError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::Role;
+    use crate::git::commits::builder::{CommitTime, FixedSignature, Identity};
+    use crate::test_utils::create_test_repo;
+
+    #[test]
+    fn environment_overrides_config_but_not_explicit_values() {
+        let (_dir, repo) = create_test_repo();
+        let env: HashMap<&str, &str> = [
+            ("GIT_AUTHOR_NAME", "Env Author"),
+            ("GIT_AUTHOR_DATE", "@1112904793 +0200"),
+            ("GIT_COMMITTER_EMAIL", "committer@example.com"),
+        ]
+        .into_iter()
+        .collect();
+        let lookup = |key: &str| env.get(key).map(|value| value.to_string());
+
+        let author = repo
+            .commit_signature_from_env(Role::Author, None, None, lookup)
+            .unwrap();
+        assert_eq!(author.name(), Some("Env Author"));
+        assert_eq!(author.email(), Some("test@example.com"));
+        assert_eq!(author.when().seconds(), 1_112_904_793);
+        assert_eq!(author.when().offset_minutes(), 120);
+
+        let committer = repo
+            .commit_signature_from_env(Role::Committer, None, None, lookup)
+            .unwrap();
+        assert_eq!(committer.name(), Some("Test User"));
+        assert_eq!(committer.email(), Some("committer@example.com"));
+
+        let explicit = Identity {
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+        };
+        let time = CommitTime {
+            seconds: 1_000_000_000,
+            offset_minutes: 0,
+        };
+        let author = repo
+            .commit_signature_from_env(Role::Author, Some(&explicit), Some(time), lookup)
+            .unwrap();
+        assert_eq!(author.name(), Some("Ada"));
+        assert_eq!(author.when().seconds(), 1_000_000_000);
+    }
+
+    #[test]
+    fn invalid_environment_dates_are_reported() {
+        let (_dir, repo) = create_test_repo();
+        let err = repo
+            .commit_signature_from_env(Role::Committer, None, None, |key| {
+                (key == "GIT_COMMITTER_DATE").then(|| "last tuesday".to_string())
+            })
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("GIT_COMMITTER_DATE"), "{err}");
+    }
+
+    #[test]
+    fn fixed_signature_overrides_environment_and_clock() {
+        let (_dir, repo) = create_test_repo();
+        let fixed = FixedSignature {
+            identity: Identity {
+                name: "Fixed".to_string(),
+                email: "fixed@example.com".to_string(),
+            },
+            time: CommitTime {
+                seconds: 1_234_567_890,
+                offset_minutes: -300,
+            },
+        };
+        repo.set_fixed_signature(Some(fixed));
+        let env = |key: &str| (key == "GIT_AUTHOR_NAME").then(|| "Env Author".to_string());
+
+        let author = repo
+            .commit_signature_from_env(Role::Author, None, None, env)
+            .unwrap();
+        assert_eq!(author.name(), Some("Fixed"));
+        assert_eq!(author.when().seconds(), 1_234_567_890);
+        assert_eq!(author.when().offset_minutes(), -300);
+
+        repo.set_fixed_signature(None);
+        let author = repo
+            .commit_signature_from_env(Role::Author, None, None, env)
+            .unwrap();
+        assert_eq!(author.name(), Some("Env Author"));
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::Context;
+use git2::{ErrorCode, Oid, StashApplyOptions, StashFlags};
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+
+/// One entry of the stash, newest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StashEntry {
+    /// Position in the stash: `stash@{index}`
+    pub index: usize,
+    /// Message as git shows it, e.g. `WIP on main: 1a2b3c4 Add notes`
+    pub message: String,
+    pub id: String,
+    /// When the changes were stashed, in seconds since the Unix epoch
+    pub time: i64,
+}
+
+impl GitRepo {
+    /// Stash staged and unstaged changes to tracked files (and untracked files with
+    /// `include_untracked`), leaving a clean working tree.
+    ///
+    /// Returns the stash commit's id, or `None` when there was nothing to stash.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stash_save(
+        &self,
+        message: Option<&str>,
+        include_untracked: bool,
+    ) -> Result<Option<String>, GitError> {
+        self.require_worktree("stash changes")?;
+        let signature = self.create_signature()?;
+        let flags = if include_untracked {
+            StashFlags::INCLUDE_UNTRACKED
+        } else {
+            StashFlags::DEFAULT
+        };
+        self.wait_for_index_lock()?;
+        let id = match self
+            .open_mut()?
+            .stash_save2(&signature, message, Some(flags))
+        {
+            Ok(id) => id,
+            Err(err) if err.code() == ErrorCode::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::Error::new(err)
+                    .context("Failed to stash changes")
+                    .into())
+            }
+        };
+        self.emit(GitEvent::StashSaved { id: id.to_string() });
+        Ok(Some(id.to_string()))
+    }
+
+    /// The stash entries, newest first
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stash_list(&self) -> Result<Vec<StashEntry>, GitError> {
+        let mut stashes = Vec::new();
+        self.open_mut()?
+            .stash_foreach(|index, message, id| {
+                stashes.push((index, message.to_string(), *id));
+                true
+            })
+            .context("Failed to list stashes")?;
+
+        stashes
+            .into_iter()
+            .map(|(index, message, id)| {
+                let time = self
+                    .repo()
+                    .find_commit(id)
+                    .context(format!("Failed to read stash@{{{index}}}"))?
+                    .time()
+                    .seconds();
+                Ok(StashEntry {
+                    index,
+                    message,
+                    id: id.to_string(),
+                    time,
+                })
+            })
+            .collect()
+    }
+
+    /// Re-apply `stash@{index}` to the working tree, keeping the entry.
+    ///
+    /// Fails with [`GitError::Conflict`] when the changes conflict with the working tree.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stash_apply(&self, index: usize) -> Result<(), GitError> {
+        let id = self.stash_id(index)?;
+        self.apply_stash(index, false)?;
+        self.emit(GitEvent::StashApplied { id: id.to_string() });
+        Ok(())
+    }
+
+    /// Re-apply `stash@{index}` and drop it once it applied cleanly; a conflicting pop keeps
+    /// the entry, like `git stash pop`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stash_pop(&self, index: usize) -> Result<(), GitError> {
+        let id = self.stash_id(index)?;
+        self.apply_stash(index, true)?;
+        self.emit(GitEvent::StashApplied { id: id.to_string() });
+        self.emit(GitEvent::StashDropped { id: id.to_string() });
+        Ok(())
+    }
+
+    /// Delete `stash@{index}` without applying it
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn stash_drop(&self, index: usize) -> Result<(), GitError> {
+        let id = self.stash_id(index)?;
+        self.open_mut()?
+            .stash_drop(index)
+            .context(format!("Failed to drop stash@{{{index}}}"))?;
+        self.emit(GitEvent::StashDropped { id: id.to_string() });
+        Ok(())
+    }
+
+    fn stash_id(&self, index: usize) -> Result<Oid, GitError> {
+        let mut found = None;
+        self.open_mut()?
+            .stash_foreach(|position, _, id| {
+                if position == index {
+                    found = Some(*id);
+                }
+                found.is_none()
+            })
+            .context("Failed to list stashes")?;
+        found.ok_or_else(|| GitError::NotFound(anyhow::anyhow!("No stash entry stash@{{{index}}}")))
+    }
+
+    /// Apply `stash@{index}`, dropping it afterwards when `drop` is set and the changes
+    /// applied without conflicts.
+    ///
+    /// libgit2's own pop drops the entry even when it leaves conflicts in the index, so the
+    /// drop is done here instead.
+    fn apply_stash(&self, index: usize, drop: bool) -> Result<(), GitError> {
+        self.require_worktree("apply a stash")?;
+        self.wait_for_index_lock()?;
+        let mut options = StashApplyOptions::new();
+        options.reinstantiate_index();
+        let mut repo = self.open_mut()?;
+        if let Err(err) = repo.stash_apply(index, Some(&mut options)) {
+            return Err(match err.code() {
+                ErrorCode::Conflict | ErrorCode::MergeConflict => {
+                    GitError::Conflict(anyhow::Error::new(err).context(format!(
+                        "stash@{{{index}}} would overwrite uncommitted changes"
+                    )))
+                }
+                _ => anyhow::Error::new(err)
+                    .context(format!("Failed to apply stash@{{{index}}}"))
+                    .into(),
+            });
+        }
+
+        let has_conflicts = repo
+            .index()
+            .context("Failed to read the index")?
+            .has_conflicts();
+        if has_conflicts {
+            return Err(GitError::Conflict(anyhow::anyhow!(
+                "Applying stash@{{{index}}} left conflicts; resolve them and stage the files"
+            )));
+        }
+        if drop {
+            repo.stash_drop(index)
+                .context(format!("Failed to drop stash@{{{index}}}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::{GitError, GitEvent};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+    use std::fs;
+
+    #[test]
+    fn stash_save_list_and_pop() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("notes.txt", "one\n", "Add notes")?;
+        assert_eq!(repo.stash_save(None, false)?, None);
+
+        fs::write(repo.path().join("notes.txt"), "two\n")?;
+        let events = repo.events();
+        let id = repo.stash_save(Some("half-done notes"), false)?.unwrap();
+        assert_eq!(events.try_recv()?, GitEvent::StashSaved { id: id.clone() });
+        assert_eq!(fs::read_to_string(repo.path().join("notes.txt"))?, "one\n");
+
+        let stashes = repo.stash_list()?;
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].index, 0);
+        assert_eq!(stashes[0].id, id);
+        assert_eq!(stashes[0].message, "On master: half-done notes");
+
+        repo.stash_pop(0)?;
+        assert_eq!(fs::read_to_string(repo.path().join("notes.txt"))?, "two\n");
+        assert!(repo.stash_list()?.is_empty());
+        assert!(matches!(repo.stash_pop(0), Err(GitError::NotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn stash_apply_keeps_entry_and_drop_removes_it() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("notes.txt", "one\n", "Add notes")?;
+        fs::write(repo.path().join("scratch.txt"), "untracked\n")?;
+
+        assert_eq!(repo.stash_save(None, false)?, None);
+        repo.stash_save(None, true)?.unwrap();
+        assert!(!repo.path().join("scratch.txt").exists());
+        assert!(repo.stash_list()?[0].message.starts_with("WIP on master: "));
+
+        repo.stash_apply(0)?;
+        assert!(repo.path().join("scratch.txt").exists());
+        assert_eq!(repo.stash_list()?.len(), 1);
+
+        repo.stash_drop(0)?;
+        assert!(repo.stash_list()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_pop_keeps_the_entry() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("notes.txt", "one\n", "Add notes")?;
+        fs::write(repo.path().join("notes.txt"), "stashed\n")?;
+        repo.stash_save(None, false)?;
+        repo.add_file_and_commit("notes.txt", "committed\n", "Edit notes")?;
+
+        let err = repo.stash_pop(0).unwrap_err();
+        assert!(matches!(err, GitError::Conflict(_)), "{err:?}");
+        assert_eq!(repo.stash_list()?.len(), 1);
+        Ok(())
+    }
+}
@@ -1,2 +1 @@
 pub mod operations;
-pub mod sync;
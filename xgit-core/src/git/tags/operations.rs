@@ -0,0 +1,262 @@
+use anyhow::Context;
+use git2::PushOptions;
+
+use crate::git::error::GitError;
+use crate::git::repository::core::GitRepo;
+use crate::git::repository::events::GitEvent;
+use crate::git::repository::signature::Role;
+
+/// A tag and the commit it points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagInfo {
+    pub name: String,
+    pub commit: String,
+    /// The tag message; `None` for lightweight tags
+    pub message: Option<String>,
+}
+
+impl GitRepo {
+    /// Every tag pointing at a commit, sorted by name. Tags of trees and blobs are left out.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn list_tags(&self) -> Result<Vec<TagInfo>, GitError> {
+        let names = self.repo().tag_names(None).context("Failed to list tags")?;
+        let mut tags: Vec<TagInfo> = names
+            .iter()
+            .flatten()
+            .filter_map(|name| self.tag_info(name))
+            .collect();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    /// Tags pointing at HEAD or one of its ancestors, sorted by name. Tags of trees and
+    /// blobs are left out.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn reachable_tags(&self) -> Result<Vec<TagInfo>, GitError> {
+        let Ok(head) = self.repo().head().and_then(|head| head.peel_to_commit()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut tags = Vec::new();
+        for tag in self.list_tags()? {
+            let commit = git2::Oid::from_str(&tag.commit).context("Invalid tag target")?;
+            let reachable = commit == head.id()
+                || self
+                    .repo()
+                    .graph_descendant_of(head.id(), commit)
+                    .context("Failed to compare tag with HEAD")?;
+            if reachable {
+                tags.push(tag);
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Create the annotated tag `name` at HEAD, tagged by the committer identity, and
+    /// return the tag object's id. Fails if the tag exists.
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn create_annotated_tag(&self, name: &str, message: &str) -> Result<String, GitError> {
+        self.create_tag(name, "HEAD", Some(message))
+    }
+
+    /// Create the tag `name` at the commit `target` resolves to: annotated when `message` is
+    /// given, lightweight otherwise. Returns the id of the tag object, or of the commit for a
+    /// lightweight tag. Fails if the tag exists.
+    #[tracing::instrument(level = "debug", skip(self, message))]
+    pub fn create_tag(
+        &self,
+        name: &str,
+        target: &str,
+        message: Option<&str>,
+    ) -> Result<String, GitError> {
+        let target_object = self
+            .repo()
+            .revparse_single(target)
+            .and_then(|object| object.peel(git2::ObjectType::Commit))
+            .context(format!("Failed to resolve '{target}'"))?;
+        let id = match message {
+            Some(message) => {
+                let tagger = self.commit_signature(Role::Committer, None, None)?;
+                self.repo()
+                    .tag(name, &target_object, &tagger, message, false)
+                    .context(format!("Failed to create tag '{name}'"))?
+            }
+            None => self
+                .repo()
+                .tag_lightweight(name, &target_object, false)
+                .context(format!("Failed to create tag '{name}'"))?,
+        };
+        self.emit(GitEvent::TagCreated {
+            name: name.to_string(),
+        });
+        Ok(id.to_string())
+    }
+
+    /// Delete the local tag `name`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn delete_tag(&self, name: &str) -> Result<(), GitError> {
+        if self
+            .repo()
+            .find_reference(&format!("refs/tags/{name}"))
+            .is_err()
+        {
+            return Err(GitError::NotFound(anyhow::anyhow!(
+                "Tag '{name}' not found"
+            )));
+        }
+        self.repo()
+            .tag_delete(name)
+            .context(format!("Failed to delete tag '{name}'"))?;
+        self.emit(GitEvent::TagDeleted {
+            name: name.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Push the tags `names` to `remote_name` (equivalent to `git push <remote> <tag>...`)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn push_tags(&self, remote_name: &str, names: &[&str]) -> Result<(), GitError> {
+        let mut remote = self
+            .repo()
+            .find_remote(remote_name)
+            .context(format!("Failed to find remote '{remote_name}'"))?;
+
+        let refspecs: Vec<String> = names
+            .iter()
+            .map(|name| format!("refs/tags/{name}:refs/tags/{name}"))
+            .collect();
+
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(self.remote_callbacks()?);
+        remote
+            .push(&refspecs, Some(&mut push_options))
+            .context(format!("Failed to push tags to remote '{remote_name}'"))?;
+        for refspec in refspecs {
+            self.emit(GitEvent::Pushed {
+                remote: remote_name.to_string(),
+                refspec,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The commit and message of tag `name`, or `None` when it does not point at a commit
+    fn tag_info(&self, name: &str) -> Option<TagInfo> {
+        let object = self
+            .repo()
+            .revparse_single(&format!("refs/tags/{name}"))
+            .ok()?;
+        let message = object
+            .as_tag()
+            .map(|tag| tag.message().unwrap_or_default().trim_end().to_string());
+        let commit = object.peel_to_commit().ok()?;
+        Some(TagInfo {
+            name: name.to_string(),
+            commit: commit.id().to_string(),
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::{GitError, GitEvent};
+    use crate::test_utils::{create_test_bare_repo, create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn create_annotated_tag_is_reachable_from_head() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        let events = repo.events();
+
+        repo.create_annotated_tag("v1.0.0", "Release v1.0.0")?;
+        assert_eq!(
+            events.try_recv()?,
+            GitEvent::TagCreated {
+                name: "v1.0.0".to_string()
+            }
+        );
+        assert!(repo.create_annotated_tag("v1.0.0", "Again").is_err());
+        let tag = repo
+            .repo()
+            .revparse_single("v1.0.0")?
+            .into_tag()
+            .expect("annotated tag");
+        assert_eq!(tag.message(), Some("Release v1.0.0"));
+
+        repo.add_file_and_commit("b.txt", "b\n", "Second commit")?;
+        repo.create_and_checkout_branch("side")?;
+        repo.add_file_and_commit("c.txt", "c\n", "Side commit")?;
+        repo.create_annotated_tag("side-only", "Not on master")?;
+        repo.checkout_branch("master")?;
+
+        let tags = repo.reachable_tags()?;
+        let names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, vec!["v1.0.0"]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_tags_includes_lightweight_tags_and_messages() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        let first = repo.repo().head()?.target().unwrap().to_string();
+        repo.add_file_and_commit("b.txt", "b\n", "Second commit")?;
+
+        assert_eq!(repo.create_tag("base", "HEAD~1", None)?, first);
+        repo.create_tag("v2.0.0", "HEAD", Some("Release v2.0.0\n"))?;
+
+        let tags = repo.list_tags()?;
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].name, "base");
+        assert_eq!(tags[0].commit, first);
+        assert_eq!(tags[0].message, None);
+        assert_eq!(tags[1].name, "v2.0.0");
+        assert_eq!(tags[1].message.as_deref(), Some("Release v2.0.0"));
+        Ok(())
+    }
+
+    #[test]
+    fn delete_tag_removes_it() -> Result<(), Box<dyn std::error::Error>> {
+        let (_temp_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        repo.create_tag("v1.0.0", "HEAD", Some("Release"))?;
+        let events = repo.events();
+
+        repo.delete_tag("v1.0.0")?;
+        assert_eq!(
+            events.try_recv()?,
+            GitEvent::TagDeleted {
+                name: "v1.0.0".to_string()
+            }
+        );
+        assert!(repo.list_tags()?.is_empty());
+        assert!(matches!(
+            repo.delete_tag("v1.0.0"),
+            Err(GitError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn push_tags_sends_them_to_the_remote() -> Result<(), Box<dyn std::error::Error>> {
+        let (_remote_dir, remote_repo) = create_test_bare_repo();
+        let (_local_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("a.txt", "a\n", "Initial commit")?;
+        repo.add_local_remote("origin", &remote_repo)?;
+        repo.push("origin", "master")?;
+        repo.create_tag("v1.0.0", "HEAD", Some("Release"))?;
+        repo.create_tag("local-only", "HEAD", None)?;
+
+        repo.push_tags("origin", &["v1.0.0"])?;
+        let names: Vec<String> = remote_repo
+            .list_tags()?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        assert_eq!(names, vec!["v1.0.0"]);
+        Ok(())
+    }
+}
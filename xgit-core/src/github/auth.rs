@@ -0,0 +1,226 @@
+//! Finding a token for the GitHub REST API: `GITHUB_TOKEN` or `GH_TOKEN` for github.com
+//! (`GH_ENTERPRISE_TOKEN` or `GITHUB_ENTERPRISE_TOKEN` for GitHub Enterprise hosts), then the
+//! `gh` CLI's login, then a git credential helper that stores the host's HTTPS password.
+//!
+//! Like `gh`, a github.com token is never sent to another host.
+
+use anyhow::Error;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::git::GitError;
+
+/// Host of github.com's web and git URLs
+pub const GITHUB_HOST: &str = "github.com";
+
+/// Where a token was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    /// The environment variable of this name
+    Env(&'static str),
+    /// `gh auth token`
+    GhCli,
+    /// `git credential fill`
+    CredentialHelper,
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenSource::Env(name) => write!(f, "${name}"),
+            TokenSource::GhCli => f.write_str("gh auth"),
+            TokenSource::CredentialHelper => f.write_str("git credential helper"),
+        }
+    }
+}
+
+/// A token for the GitHub API and where it came from
+#[derive(Clone, PartialEq, Eq)]
+pub struct GitHubToken {
+    pub token: String,
+    pub source: TokenSource,
+}
+
+// Keep the token itself out of logs and `tracing` fields
+impl fmt::Debug for GitHubToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitHubToken")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Look for a token for `host` in each source in turn
+pub fn resolve_token(host: &str) -> Option<GitHubToken> {
+    token_from_env(host)
+        .or_else(|| {
+            gh_auth_token(host).map(|token| GitHubToken {
+                token,
+                source: TokenSource::GhCli,
+            })
+        })
+        .or_else(|| {
+            credential_helper_token(host).map(|token| GitHubToken {
+                token,
+                source: TokenSource::CredentialHelper,
+            })
+        })
+}
+
+/// [`resolve_token`], or an auth error that says how to sign in
+pub fn require_token(host: &str) -> Result<GitHubToken, Error> {
+    resolve_token(host).ok_or_else(|| missing_token_error(host))
+}
+
+/// The error for when no source has a token for `host`
+pub fn missing_token_error(host: &str) -> Error {
+    let variable = if host.eq_ignore_ascii_case(GITHUB_HOST) {
+        "GITHUB_TOKEN"
+    } else {
+        "GH_ENTERPRISE_TOKEN"
+    };
+    GitError::AuthRequired(anyhow::anyhow!(
+        "No GitHub credentials found for {host}; set {variable}, run `gh auth login`, or store a token for https://{host} in a git credential helper"
+    ))
+    .into()
+}
+
+/// `GITHUB_TOKEN`, then `GH_TOKEN`, when set and not blank; for a GitHub Enterprise `host`
+/// `GH_ENTERPRISE_TOKEN`, then `GITHUB_ENTERPRISE_TOKEN` instead
+pub fn token_from_env(host: &str) -> Option<GitHubToken> {
+    token_from_vars(host, |name| std::env::var(name).ok())
+}
+
+fn token_from_vars(host: &str, var: impl Fn(&str) -> Option<String>) -> Option<GitHubToken> {
+    let names: &[&'static str] = if host.eq_ignore_ascii_case(GITHUB_HOST) {
+        &["GITHUB_TOKEN", "GH_TOKEN"]
+    } else {
+        &["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+    names.iter().find_map(|&name| {
+        var(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(|token| GitHubToken {
+                token,
+                source: TokenSource::Env(name),
+            })
+    })
+}
+
+/// The token `gh auth login` stored for `host`, if `gh` is installed and signed in
+fn gh_auth_token(host: &str) -> Option<String> {
+    let output = Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let token = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!token.is_empty()).then(|| token)
+}
+
+/// The password a git credential helper has for `https://host`, without prompting
+pub(crate) fn credential_helper_token(host: &str) -> Option<String> {
+    credential_helper_login(host).map(|(_, password)| password)
+}
+
+/// The user name (empty when the helper has none) and password a git credential helper has
+/// for `https://host`, without prompting
+pub(crate) fn credential_helper_login(host: &str) -> Option<(String, String)> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child
+        .stdin
+        .take()?
+        .write_all(format!("protocol=https\nhost={host}\n\n").as_bytes())
+        .ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let output = String::from_utf8_lossy(&output.stdout);
+    let password = parse_credential_password(&output)?;
+    let username = credential_field(&output, "username").unwrap_or_default();
+    Some((username, password))
+}
+
+/// The `password=` value of `git credential fill` output
+fn parse_credential_password(output: &str) -> Option<String> {
+    credential_field(output, "password")
+}
+
+/// The non-blank `name=` value of `git credential fill` output
+fn credential_field(output: &str, name: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_credential_password, token_from_vars, TokenSource, GITHUB_HOST};
+    use std::collections::HashMap;
+
+    #[test]
+    fn env_tokens_prefer_github_token_and_skip_blank_values() {
+        let vars = HashMap::from([("GITHUB_TOKEN", "  "), ("GH_TOKEN", "gho_abc\n")]);
+        let from =
+            |host| token_from_vars(host, |name| vars.get(name).map(|value| value.to_string()));
+        let token = from(GITHUB_HOST).unwrap();
+        assert_eq!(token.token, "gho_abc");
+        assert_eq!(token.source, TokenSource::Env("GH_TOKEN"));
+        assert_eq!(token.source.to_string(), "$GH_TOKEN");
+        assert!(!format!("{token:?}").contains("gho_abc"));
+
+        let vars = HashMap::from([
+            ("GITHUB_TOKEN", "ghp_1"),
+            ("GH_TOKEN", "gho_2"),
+            ("GH_ENTERPRISE_TOKEN", "ghe_3"),
+        ]);
+        let from =
+            |host| token_from_vars(host, |name| vars.get(name).map(|value| value.to_string()));
+        assert_eq!(
+            from(GITHUB_HOST).unwrap().source,
+            TokenSource::Env("GITHUB_TOKEN")
+        );
+        assert_eq!(from("git.corp.example").unwrap().token, "ghe_3");
+
+        // A github.com token never goes to another host
+        let vars = HashMap::from([("GITHUB_TOKEN", "ghp_1"), ("GH_TOKEN", "gho_2")]);
+        assert_eq!(
+            token_from_vars("evil.example", |name| vars
+                .get(name)
+                .map(|value| value.to_string())),
+            None
+        );
+
+        assert_eq!(token_from_vars(GITHUB_HOST, |_| None), None);
+    }
+
+    #[test]
+    fn parse_credential_password_reads_the_password_line() {
+        let output = "protocol=https\nhost=github.com\nusername=octocat\npassword=ghp_secret\n";
+        assert_eq!(
+            parse_credential_password(output).as_deref(),
+            Some("ghp_secret")
+        );
+        assert_eq!(
+            parse_credential_password("protocol=https\nhost=github.com\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::github::types::now_timestamp;
+use anyhow::{Context, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Which PR, if any, a branch had when it was last looked up.
+///
+/// Entries are keyed by branch and its head commit, so a branch that moves is looked up again
+/// straight away; otherwise an entry is trusted for the PR cache TTL. This is what lets
+/// `xg branch --stats` skip the forge for branches that have no PR, which the
+/// [`PrIndexStore`](crate::github::pr_index::PrIndexStore) cannot remember.
+#[derive(Debug, Clone)]
+pub struct PrLookupCache {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles between threads sharing the cache
+    lock: Arc<Mutex<()>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PrCacheFile {
+    version: u32,
+    /// `"<repo slug>:<branch>"` → last lookup
+    entries: HashMap<String, PrLookup>,
+}
+
+/// One cached lookup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrLookup {
+    /// Commit the branch pointed at when it was looked up
+    pub head_sha: String,
+    /// The PR found, or `None` when the branch had none
+    pub pr_number: Option<u64>,
+    /// When the lookup ran, in seconds since the Unix epoch
+    pub checked_at: u64,
+}
+
+impl PrLookupCache {
+    /// Cache backed by the JSON file at `path`, normally `.git/xgit/pr-cache.json`
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Arc::default(),
+        }
+    }
+
+    /// The lookup for `branch` of `repo_slug`, when it was made at `head_sha` no more than
+    /// `ttl_secs` ago
+    pub fn get(
+        &self,
+        repo_slug: &str,
+        branch: &str,
+        head_sha: &str,
+        ttl_secs: u64,
+    ) -> Option<PrLookup> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let now = now_timestamp();
+        self.load()
+            .entries
+            .remove(&key(repo_slug, branch))
+            .filter(|lookup| {
+                lookup.head_sha == head_sha && now.saturating_sub(lookup.checked_at) < ttl_secs
+            })
+    }
+
+    /// Remember that `branch` of `repo_slug` at `head_sha` has PR `pr_number`, or none
+    pub fn put(
+        &self,
+        repo_slug: &str,
+        branch: &str,
+        head_sha: &str,
+        pr_number: Option<u64>,
+    ) -> Result<(), Error> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut cache = self.load();
+        cache.version = CURRENT_SCHEMA_VERSION;
+        cache.entries.insert(
+            key(repo_slug, branch),
+            PrLookup {
+                head_sha: head_sha.to_string(),
+                pr_number,
+                checked_at: now_timestamp(),
+            },
+        );
+        self.save(&cache)
+    }
+
+    /// The cache file's contents; a missing, unreadable or outdated file reads as empty
+    fn load(&self) -> PrCacheFile {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<PrCacheFile>(&contents).ok())
+            .filter(|cache| cache.version == CURRENT_SCHEMA_VERSION)
+            .unwrap_or_default()
+    }
+
+    fn save(&self, cache: &PrCacheFile) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create PR cache directory '{}'",
+                parent.display()
+            ))?;
+        }
+        let payload = serde_json::to_vec(cache).context("Failed to serialize PR cache")?;
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, payload).context(format!(
+            "Failed to write PR cache '{}'",
+            temp_path.display()
+        ))?;
+        fs::rename(&temp_path, &self.path).context(format!(
+            "Failed to replace PR cache '{}'",
+            self.path.display()
+        ))?;
+        Ok(())
+    }
+}
+
+fn key(repo_slug: &str, branch: &str) -> String {
+    format!("{repo_slug}:{branch}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrLookupCache;
+    use assert_fs::TempDir;
+
+    #[test]
+    fn lookups_expire_when_the_branch_moves_or_the_ttl_passes() -> Result<(), anyhow::Error> {
+        let dir = TempDir::new()?;
+        let cache = PrLookupCache::new(dir.path().join("xgit").join("pr-cache.json"));
+        assert_eq!(cache.get("octo/repo", "feature", "aaa", 300), None);
+
+        cache.put("octo/repo", "feature", "aaa", None)?;
+        cache.put("octo/repo", "fix", "bbb", Some(7))?;
+
+        // A fresh handle reads what the first one wrote
+        let cache = PrLookupCache::new(dir.path().join("xgit").join("pr-cache.json"));
+        let lookup = cache.get("octo/repo", "feature", "aaa", 300).unwrap();
+        assert_eq!(lookup.pr_number, None);
+        assert_eq!(
+            cache
+                .get("octo/repo", "fix", "bbb", 300)
+                .and_then(|lookup| lookup.pr_number),
+            Some(7)
+        );
+
+        assert_eq!(cache.get("octo/repo", "feature", "ccc", 300), None);
+        assert_eq!(cache.get("octo/fork", "feature", "aaa", 300), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,602 @@
+use crate::forge::ForgeProvider;
+use crate::github::auth::{self, GitHubToken, TokenSource};
+use crate::github::issues::Issue;
+use crate::github::remote::api_base_url;
+use crate::github::types::{
+    ChecksState, MergeMethod, PrListFilter, PullRequestChecks, PullRequestListing,
+    PullRequestRecord, PullRequestSnapshot, PullRequestStatus, ReviewDecision,
+};
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde::Deserialize;
+use serde_json::json;
+
+pub struct GitHubClient {
+    octocrab: Octocrab,
+    owner: String,
+    repo: String,
+    /// Where the client's token came from; `None` for anonymous requests
+    token_source: Option<TokenSource>,
+}
+
+impl GitHubClient {
+    /// Client for `owner/repo` on `host` (github.com or a GitHub Enterprise host), signed in
+    /// with the first token [`auth::resolve_token`] finds, or anonymous when there is none,
+    /// which is enough to read public repositories
+    pub fn new(host: &str, owner: String, repo: String) -> Result<Self, Error> {
+        match auth::resolve_token(host) {
+            Some(token) => Self::with_token(host, owner, repo, token),
+            None => {
+                let octocrab = Octocrab::builder()
+                    .base_uri(api_base_url(host))?
+                    .build()
+                    .context("Failed to create GitHub client")?;
+                Ok(Self {
+                    octocrab,
+                    owner,
+                    repo,
+                    token_source: None,
+                })
+            }
+        }
+    }
+
+    /// Client that must be signed in, for creating and changing pull requests; fails with an
+    /// auth error explaining how to sign in when no token is found
+    pub fn authenticated(host: &str, owner: String, repo: String) -> Result<Self, Error> {
+        Self::with_token(host, owner, repo, auth::require_token(host)?)
+    }
+
+    pub fn with_token(
+        host: &str,
+        owner: String,
+        repo: String,
+        token: GitHubToken,
+    ) -> Result<Self, Error> {
+        let octocrab = Octocrab::builder()
+            .base_uri(api_base_url(host))?
+            .personal_token(token.token)
+            .build()
+            .context("Failed to create GitHub client")?;
+        Ok(Self {
+            octocrab,
+            owner,
+            repo,
+            token_source: Some(token.source),
+        })
+    }
+
+    /// Where the token came from, or `None` when requests are anonymous
+    pub fn token_source(&self) -> Option<TokenSource> {
+        self.token_source
+    }
+
+    /// `message` for a failed request, saying how to sign in when the client is anonymous,
+    /// since GitHub answers anonymous requests for private repositories with 404
+    fn failed(&self, message: &str) -> String {
+        match self.token_source {
+            Some(_) => message.to_string(),
+            None => format!(
+                "{message} (not signed in to GitHub; set GITHUB_TOKEN or run `gh auth login` for private repositories)"
+            ),
+        }
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_pr_by_head_branch(
+        &self,
+        branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let pulls = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .list()
+            .state(octocrab::params::State::All)
+            .head(format!("{}:{}", &self.owner, branch))
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to fetch pull requests"))?;
+
+        if let Some(pr) = pulls.items.first() {
+            let pr_info = to_pull_request_record(&self.owner, &self.repo, pr);
+            Ok(Some(pr_info))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_pr_by_head_branch_with_owner(
+        &self,
+        owner: &str,
+        branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let pulls = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .list()
+            .state(octocrab::params::State::All)
+            .head(format!("{owner}:{branch}"))
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to fetch pull requests"))?;
+
+        if let Some(pr) = pulls.items.first() {
+            let pr_info = to_pull_request_record(&self.owner, &self.repo, pr);
+            Ok(Some(pr_info))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_pr_by_number(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        let pr = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .get(pr_number)
+            .await
+            .with_context(|| self.failed("Failed to fetch pull request by number"))?;
+        Ok(to_pull_request_record(&self.owner, &self.repo, &pr))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let pulls: Vec<octocrab::models::pulls::PullRequest> = self
+            .octocrab
+            .get(
+                format!(
+                    "/repos/{}/{}/commits/{commit_sha}/pulls",
+                    self.owner, self.repo
+                ),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch pull requests for commit"))?;
+
+        let pr = pulls
+            .iter()
+            .find(|pr| pr.merged_at.is_some())
+            .or_else(|| pulls.first());
+        Ok(pr.map(|pr| to_pull_request_record(&self.owner, &self.repo, pr)))
+    }
+
+    /// Combined commit statuses and check runs on `head_sha`, and the review decision from
+    /// each reviewer's latest review. The REST API has no `reviewDecision`, so a PR waiting for
+    /// a required review has no decision rather than `ReviewRequired`.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        head_sha: &str,
+    ) -> Result<PullRequestChecks, Error> {
+        let repo_route = format!("/repos/{}/{}", self.owner, self.repo);
+        let status: CombinedStatus = self
+            .octocrab
+            .get(
+                format!("{repo_route}/commits/{head_sha}/status"),
+                None::<&()>,
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch commit statuses"))?;
+        let check_runs: CheckRuns = self
+            .octocrab
+            .get(
+                format!("{repo_route}/commits/{head_sha}/check-runs"),
+                Some(&json!({ "per_page": 100 })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch check runs"))?;
+        let reviews: Vec<Review> = self
+            .octocrab
+            .get(
+                format!("{repo_route}/pulls/{pr_number}/reviews"),
+                Some(&json!({ "per_page": 100 })),
+            )
+            .await
+            .with_context(|| self.failed("Failed to fetch pull request reviews"))?;
+
+        // Go by the individual statuses, since the combined state is `pending` when there are none
+        let statuses = status
+            .statuses
+            .iter()
+            .map(|status| ChecksState::from_status(&status.state));
+        let runs = check_runs
+            .check_runs
+            .iter()
+            .map(|run| ChecksState::from_check_run(&run.status, run.conclusion.as_deref()));
+        Ok(PullRequestChecks {
+            checks: ChecksState::combine(statuses.chain(runs)),
+            review_decision: ReviewDecision::from_reviews(reviews.iter().filter_map(|review| {
+                Some((review.user.as_ref()?.login.as_str(), review.state.as_str()))
+            })),
+        })
+    }
+
+    /// The first 100 open PRs passing `filter`, newest first. The REST API cannot filter by
+    /// author, reviewer or label, so that happens here, and each listed PR costs three more
+    /// requests for its checks.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_open_prs(
+        &self,
+        filter: &PrListFilter,
+    ) -> Result<Vec<PullRequestListing>, Error> {
+        let login =
+            if filter.mine || filter.review_requested {
+                let user =
+                    self.octocrab.current().user().await.with_context(|| {
+                        self.failed("Failed to look up the signed-in GitHub user")
+                    })?;
+                Some(user.login)
+            } else {
+                None
+            };
+        let pulls = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .list()
+            .state(octocrab::params::State::Open)
+            .per_page(100)
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to list pull requests"))?;
+
+        let mut listings = Vec::new();
+        for pr in &pulls.items {
+            let mut listing = PullRequestListing {
+                record: to_pull_request_record(&self.owner, &self.repo, pr),
+                author: pr
+                    .user
+                    .as_ref()
+                    .map(|user| user.login.clone())
+                    .unwrap_or_default(),
+                labels: pr
+                    .labels
+                    .iter()
+                    .flatten()
+                    .map(|label| label.name.clone())
+                    .collect(),
+                requested_reviewers: pr
+                    .requested_reviewers
+                    .iter()
+                    .flatten()
+                    .map(|reviewer| reviewer.login.clone())
+                    .collect(),
+                is_cross_repository: pr.head.repo.as_ref().map(|repo| repo.id)
+                    != pr.base.repo.as_ref().map(|repo| repo.id),
+            };
+            if !filter.matches(&listing, login.as_deref()) {
+                continue;
+            }
+            let checks = self.get_pr_checks(pr.number, &pr.head.sha).await?;
+            listing.record.set_checks(checks);
+            listings.push(listing);
+        }
+        Ok(listings)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_default_branch(&self) -> Result<String, Error> {
+        let repo = self
+            .octocrab
+            .repos(&self.owner, &self.repo)
+            .get()
+            .await
+            .with_context(|| self.failed("Failed to fetch repository metadata"))?;
+
+        repo.default_branch
+            .ok_or_else(|| anyhow::anyhow!("Repository default branch is not available"))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, title, body))]
+    pub async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        let pulls = self.octocrab.pulls(&self.owner, &self.repo);
+        let mut builder = pulls.create(title, head, base).draft(draft);
+        if let Some(body) = body {
+            builder = builder.body(body.to_string());
+        }
+
+        let pr = builder
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to create pull request"))?;
+
+        Ok(to_pull_request_record(&self.owner, &self.repo, &pr))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, title, body))]
+    pub async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        let pulls = self.octocrab.pulls(&self.owner, &self.repo);
+        let mut builder = pulls.update(pr_number);
+        if let Some(base) = base {
+            builder = builder.base(base);
+        }
+        if let Some(title) = title {
+            builder = builder.title(title);
+        }
+        if let Some(body) = body {
+            builder = builder.body(body.to_string());
+        }
+
+        let pr = builder
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to update pull request"))?;
+        Ok(to_pull_request_record(&self.owner, &self.repo, &pr))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error> {
+        let method = match method {
+            MergeMethod::Merge => octocrab::params::pulls::MergeMethod::Merge,
+            MergeMethod::Squash => octocrab::params::pulls::MergeMethod::Squash,
+            MergeMethod::Rebase => octocrab::params::pulls::MergeMethod::Rebase,
+        };
+        let merge = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .merge(pr_number)
+            .method(method)
+            .sha(head_sha)
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to merge pull request"))?;
+
+        if !merge.merged {
+            return Err(anyhow::anyhow!(
+                "GitHub did not merge PR #{pr_number}: {}",
+                merge.message.unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    /// The first 100 open issues assigned to the signed-in user, most recently updated first.
+    /// GitHub lists pull requests as issues too; those are left out.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_assigned_issues(&self) -> Result<Vec<Issue>, Error> {
+        let user = self
+            .octocrab
+            .current()
+            .user()
+            .await
+            .with_context(|| self.failed("Failed to look up the signed-in GitHub user"))?;
+        let issues = self
+            .octocrab
+            .issues(&self.owner, &self.repo)
+            .list()
+            .assignee(user.login.as_str())
+            .state(octocrab::params::State::Open)
+            .sort(octocrab::params::issues::Sort::Updated)
+            .direction(octocrab::params::Direction::Descending)
+            .per_page(100)
+            .send()
+            .await
+            .with_context(|| self.failed("Failed to list issues"))?;
+
+        Ok(issues
+            .items
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| Issue {
+                number: issue.number,
+                title: issue.title,
+                url: issue.html_url.to_string(),
+                labels: issue.labels.into_iter().map(|label| label.name).collect(),
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn rename_branch(&self, from: &str, to: &str) -> Result<(), Error> {
+        let route = format!(
+            "/repos/{owner}/{repo}/branches/{from}/rename",
+            owner = self.owner,
+            repo = self.repo,
+            from = from
+        );
+
+        self.octocrab
+            .post::<_, serde_json::Value>(route, Some(&json!({ "new_name": to })))
+            .await
+            .with_context(|| self.failed("Failed to rename branch on GitHub"))?;
+
+        Ok(())
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn repo(&self) -> &str {
+        &self.repo
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GitHubClient {
+    async fn get_default_branch(&self) -> Result<String, Error> {
+        GitHubClient::get_default_branch(self).await
+    }
+
+    async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        self.get_pr_by_number(pr_number).await
+    }
+
+    async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error> {
+        let pr = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .get(pr_number)
+            .await
+            .with_context(|| self.failed("Failed to fetch pull request by number"))?;
+        Ok(pr.body.unwrap_or_default())
+    }
+
+    async fn find_pr_by_head(&self, head_branch: &str) -> Result<Option<PullRequestRecord>, Error> {
+        self.find_pr_by_head_branch(head_branch).await
+    }
+
+    async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.find_pr_by_head_branch_with_owner(owner, head_branch)
+            .await
+    }
+
+    async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        GitHubClient::find_pr_by_commit(self, commit_sha).await
+    }
+
+    async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        head_sha: &str,
+    ) -> Result<PullRequestChecks, Error> {
+        GitHubClient::get_pr_checks(self, pr_number, head_sha).await
+    }
+
+    async fn list_open_prs(&self, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error> {
+        GitHubClient::list_open_prs(self, filter).await
+    }
+
+    async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        GitHubClient::create_pr(self, title, body, head, base, draft).await
+    }
+
+    async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        GitHubClient::update_pr(self, pr_number, base, title, body).await
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error> {
+        GitHubClient::merge_pr(self, pr_number, method, head_sha).await
+    }
+}
+
+#[derive(Deserialize)]
+struct CombinedStatus {
+    statuses: Vec<CommitStatus>,
+}
+
+#[derive(Deserialize)]
+struct CommitStatus {
+    state: String,
+}
+
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Review {
+    user: Option<ReviewUser>,
+    state: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewUser {
+    login: String,
+}
+
+/// Check `token` against `host`'s API, returning the login it belongs to
+pub async fn validate_token(host: &str, token: &GitHubToken) -> Result<String, Error> {
+    let octocrab = Octocrab::builder()
+        .base_uri(api_base_url(host))?
+        .personal_token(token.token.clone())
+        .build()
+        .context("Failed to create GitHub client")?;
+    let user = octocrab
+        .current()
+        .user()
+        .await
+        .context("GitHub rejected the token")?;
+    Ok(user.login)
+}
+
+fn to_pull_request_record(
+    owner: &str,
+    repo: &str,
+    pr: &octocrab::models::pulls::PullRequest,
+) -> PullRequestRecord {
+    PullRequestRecord::from_snapshot(PullRequestSnapshot {
+        repo_slug: format!("{owner}/{repo}"),
+        pr_number: pr.number,
+        title: pr.title.clone().unwrap_or_default(),
+        url: pr
+            .html_url
+            .as_ref()
+            .map(|u| u.to_string())
+            .unwrap_or_default(),
+        base_ref: pr.base.ref_field.clone(),
+        head_ref: pr.head.ref_field.clone(),
+        head_sha: pr.head.sha.clone(),
+        draft: pr.draft.unwrap_or(false),
+        status: to_pull_request_status(pr),
+        created_at: pr.created_at.map(|time| time.timestamp().max(0) as u64),
+        merged_at: pr.merged_at.map(|time| time.timestamp().max(0) as u64),
+    })
+}
+
+fn to_pull_request_status(pr: &octocrab::models::pulls::PullRequest) -> PullRequestStatus {
+    if pr.merged_at.is_some() {
+        return PullRequestStatus::Merged;
+    }
+
+    match pr.state.clone() {
+        Some(octocrab::models::IssueState::Closed) => PullRequestStatus::Closed,
+        Some(octocrab::models::IssueState::Open) => PullRequestStatus::Open,
+        Some(_) | None => PullRequestStatus::Open,
+    }
+}
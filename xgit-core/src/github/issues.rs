@@ -0,0 +1,252 @@
+use crate::github::types::now_timestamp;
+use anyhow::{Context, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// How long fetched issues are reused before GitHub is asked again
+pub const DEFAULT_ISSUE_CACHE_TTL_SECS: u64 = 300;
+
+/// An open issue assigned to the signed-in user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub labels: Vec<String>,
+}
+
+/// How a commit or pull request refers to an issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKeyword {
+    /// `Closes #N`: GitHub closes the issue when the change lands on the default branch
+    Closes,
+    /// `Refs #N`: mentions the issue without closing it
+    Refs,
+}
+
+impl IssueKeyword {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IssueKeyword::Closes => "Closes",
+            IssueKeyword::Refs => "Refs",
+        }
+    }
+
+    /// GitHub's closing keywords (`close`, `fix`, `resolve` and their forms) and `Refs`
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "close" | "closes" | "closed" | "fix" | "fixes" | "fixed" | "resolve" | "resolves"
+            | "resolved" => Some(IssueKeyword::Closes),
+            "ref" | "refs" | "references" => Some(IssueKeyword::Refs),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for IssueKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `Closes #N` or `Refs #N` line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IssueLink {
+    pub keyword: IssueKeyword,
+    pub number: u64,
+}
+
+impl fmt::Display for IssueLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} #{}", self.keyword, self.number)
+    }
+}
+
+impl IssueLink {
+    /// Read a line such as `Closes #12`, `fixes: #12` or `Refs #12`
+    pub fn parse(line: &str) -> Option<Self> {
+        let (keyword, number) = line.trim().split_once(char::is_whitespace)?;
+        let keyword = IssueKeyword::parse(keyword.trim_end_matches(':'))?;
+        let number = number.trim().strip_prefix('#')?.parse().ok()?;
+        Some(Self { keyword, number })
+    }
+}
+
+/// Every issue link in `message`, one per line, in order and without repeats. An issue both
+/// closed and referred to counts as closed.
+pub fn issue_links(message: &str) -> Vec<IssueLink> {
+    let mut links: Vec<IssueLink> = Vec::new();
+    for link in message.lines().filter_map(IssueLink::parse) {
+        match links.iter_mut().find(|known| known.number == link.number) {
+            Some(known) if link.keyword == IssueKeyword::Closes => known.keyword = link.keyword,
+            Some(_) => {}
+            None => links.push(link),
+        }
+    }
+    links
+}
+
+/// `message` with `link` on its last line. A closing trailer block (`Signed-off-by:` lines
+/// or other links) gets the link added; otherwise it starts a paragraph of its own. A message
+/// that already links the issue is left as it is.
+pub fn append_issue_link(message: &str, link: IssueLink) -> String {
+    let message = message.trim_end();
+    if issue_links(message)
+        .iter()
+        .any(|known| known.number == link.number && known.keyword == link.keyword)
+    {
+        return message.to_string();
+    }
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or_default();
+    let is_trailer = |line: &str| {
+        IssueLink::parse(line).is_some()
+            || line.split_once(": ").map_or(false, |(key, _)| {
+                !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '-')
+            })
+    };
+    let has_body = message.contains("\n\n");
+    if has_body && last_paragraph.lines().all(is_trailer) {
+        format!("{message}\n{link}")
+    } else {
+        format!("{message}\n\n{link}")
+    }
+}
+
+/// The assigned issues last fetched for each repository, so picking an issue for every commit
+/// does not cost a request each time
+#[derive(Debug, Clone)]
+pub struct IssueCache {
+    path: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IssueCacheFile {
+    version: u32,
+    /// Repository slug → issues
+    entries: HashMap<String, CachedIssues>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedIssues {
+    issues: Vec<Issue>,
+    /// When the issues were fetched, in seconds since the Unix epoch
+    fetched_at: u64,
+}
+
+impl IssueCache {
+    /// Cache backed by the JSON file at `path`, normally `.git/xgit/issue-cache.json`
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The issues of `repo_slug`, when fetched less than `ttl_secs` ago
+    pub fn get(&self, repo_slug: &str, ttl_secs: u64) -> Option<Vec<Issue>> {
+        let now = now_timestamp();
+        self.load()
+            .entries
+            .remove(repo_slug)
+            .filter(|cached| now.saturating_sub(cached.fetched_at) < ttl_secs)
+            .map(|cached| cached.issues)
+    }
+
+    /// Remember `issues` as the assigned issues of `repo_slug`
+    pub fn put(&self, repo_slug: &str, issues: &[Issue]) -> Result<(), Error> {
+        let mut cache = self.load();
+        cache.version = CURRENT_SCHEMA_VERSION;
+        cache.entries.insert(
+            repo_slug.to_string(),
+            CachedIssues {
+                issues: issues.to_vec(),
+                fetched_at: now_timestamp(),
+            },
+        );
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create issue cache directory '{}'",
+                parent.display()
+            ))?;
+        }
+        let payload = serde_json::to_vec(&cache).context("Failed to serialize issue cache")?;
+        fs::write(&self.path, payload).context(format!(
+            "Failed to write issue cache '{}'",
+            self.path.display()
+        ))
+    }
+
+    /// The cache file's contents; a missing, unreadable or outdated file reads as empty
+    fn load(&self) -> IssueCacheFile {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<IssueCacheFile>(&contents).ok())
+            .filter(|cache| cache.version == CURRENT_SCHEMA_VERSION)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_issue_link, issue_links, Issue, IssueCache, IssueKeyword, IssueLink};
+    use assert_fs::TempDir;
+
+    fn link(keyword: IssueKeyword, number: u64) -> IssueLink {
+        IssueLink { keyword, number }
+    }
+
+    #[test]
+    fn issue_links_read_closing_keywords_and_refs() {
+        let message = "fix: handle empty input\n\nFixes #12\nrefs: #7\nCloses #7\nSee #9\n";
+        assert_eq!(
+            issue_links(message),
+            [
+                link(IssueKeyword::Closes, 12),
+                link(IssueKeyword::Closes, 7)
+            ]
+        );
+        assert_eq!(IssueLink::parse("Closes #abc"), None);
+    }
+
+    #[test]
+    fn append_issue_link_joins_a_trailer_block() {
+        let closes = link(IssueKeyword::Closes, 4);
+        assert_eq!(
+            append_issue_link("feat: add login\n", closes),
+            "feat: add login\n\nCloses #4"
+        );
+        assert_eq!(
+            append_issue_link("feat: add login\n\nRefs #2", closes),
+            "feat: add login\n\nRefs #2\nCloses #4"
+        );
+        assert_eq!(
+            append_issue_link("feat: add login\n\nAdds a form.", closes),
+            "feat: add login\n\nAdds a form.\n\nCloses #4"
+        );
+        assert_eq!(
+            append_issue_link("feat: add login\n\nCloses #4", closes),
+            "feat: add login\n\nCloses #4"
+        );
+    }
+
+    #[test]
+    fn cached_issues_expire_after_the_ttl() -> Result<(), anyhow::Error> {
+        let dir = TempDir::new()?;
+        let cache = IssueCache::new(dir.path().join("xgit").join("issue-cache.json"));
+        assert_eq!(cache.get("octo/repo", 300), None);
+
+        let issues = vec![Issue {
+            number: 3,
+            title: "Crash on start".to_string(),
+            url: "https://github.com/octo/repo/issues/3".to_string(),
+            labels: vec!["bug".to_string()],
+        }];
+        cache.put("octo/repo", &issues)?;
+        assert_eq!(cache.get("octo/repo", 300), Some(issues));
+        assert_eq!(cache.get("octo/repo", 0), None);
+        assert_eq!(cache.get("octo/fork", 300), None);
+        Ok(())
+    }
+}
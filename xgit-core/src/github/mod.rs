@@ -1,7 +1,11 @@
+pub mod auth;
+pub mod cache;
 pub mod client;
+pub mod issues;
 pub mod pr_index;
 pub mod pr_matcher;
 pub mod pr_service;
+pub mod remote;
 pub mod types;
 
 pub use pr_matcher::GitHubPrMatcher;
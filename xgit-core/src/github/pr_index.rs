@@ -3,6 +3,7 @@ use anyhow::{Context, Error};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 const CURRENT_SCHEMA_VERSION: u32 = 1;
 
@@ -27,6 +28,8 @@ pub trait PrIndexStore: Send + Sync {
         repo_slug: &str,
         commit_sha: &str,
     ) -> Result<Option<PullRequestRecord>, Error>;
+    /// Every cached record for `repo_slug`, in the order they were first stored
+    fn list_records(&self, repo_slug: &str) -> Result<Vec<PullRequestRecord>, Error>;
     fn upsert_record(&self, record: &PullRequestRecord) -> Result<PullRequestRecord, Error>;
     fn attach_branch(
         &self,
@@ -56,6 +59,8 @@ pub trait PrIndexStore: Send + Sync {
 #[derive(Debug, Clone)]
 pub struct JsonPrIndexStore {
     path: PathBuf,
+    /// Serializes read-modify-write cycles between threads sharing the store
+    lock: Arc<Mutex<()>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -66,7 +71,10 @@ struct PrIndexFile {
 
 impl JsonPrIndexStore {
     pub fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            lock: Arc::default(),
+        }
     }
 
     fn load_index(&self) -> Result<PrIndexFile, Error> {
@@ -120,6 +128,7 @@ impl JsonPrIndexStore {
     where
         F: FnOnce(&mut PrIndexFile) -> Result<Option<PullRequestRecord>, Error>,
     {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
         let mut index = self.load_index()?;
         let result = mutator(&mut index)?;
         self.save_index(&index)?;
@@ -194,6 +203,15 @@ impl PrIndexStore for JsonPrIndexStore {
         }))
     }
 
+    fn list_records(&self, repo_slug: &str) -> Result<Vec<PullRequestRecord>, Error> {
+        let index = self.load_index()?;
+        Ok(index
+            .records
+            .into_iter()
+            .filter(|record| record.repo_slug == repo_slug)
+            .collect())
+    }
+
     fn upsert_record(&self, record: &PullRequestRecord) -> Result<PullRequestRecord, Error> {
         let record = record.clone();
         self.mutate(|index| {
@@ -320,6 +338,8 @@ mod tests {
             head_sha: "sha-1".to_string(),
             draft: false,
             status: PullRequestStatus::Open,
+            created_at: None,
+            merged_at: None,
         })
     }
 
@@ -351,6 +371,40 @@ mod tests {
             .is_some());
     }
 
+    #[test]
+    fn json_store_lists_records_for_one_repository() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let store = JsonPrIndexStore::new(temp_dir.path().join("pr-index.json"));
+
+        let mut other = sample_record();
+        other.repo_slug = "someone/else".to_string();
+        store.upsert_record(&sample_record()).unwrap();
+        store.upsert_record(&other).unwrap();
+
+        let records = store.list_records("owner/repo").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].pr_number, 42);
+    }
+
+    #[test]
+    fn json_store_reads_records_written_without_forge_timestamps() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let store = JsonPrIndexStore::new(temp_dir.path().join("pr-index.json"));
+
+        let mut payload = serde_json::to_value(PrIndexFile {
+            version: CURRENT_SCHEMA_VERSION,
+            records: vec![sample_record()],
+        })
+        .unwrap();
+        let record = payload["records"][0].as_object_mut().unwrap();
+        record.remove("created_at");
+        record.remove("merged_at");
+        std::fs::write(store.path(), serde_json::to_vec(&payload).unwrap()).unwrap();
+
+        let saved = store.get_by_pr("owner/repo", 42).unwrap().unwrap();
+        assert_eq!((saved.created_at, saved.merged_at), (None, None));
+    }
+
     #[test]
     fn json_store_rejects_unsupported_schema_version() {
         let temp_dir = assert_fs::TempDir::new().unwrap();
@@ -0,0 +1,364 @@
+use crate::{
+    forge::{ForgeProvider, ForgeRemote},
+    git::GitRepo,
+    github::{
+        cache::PrLookupCache,
+        pr_service::GitHubPrService,
+        types::{PullRequestRecord, ResolvedPullRequest},
+    },
+};
+use anyhow::Error;
+
+pub struct GitHubPrMatcher {
+    service: GitHubPrService,
+    remote: ForgeRemote,
+    lookup_cache: PrLookupCache,
+}
+
+impl GitHubPrMatcher {
+    pub fn new(repo: &GitRepo) -> Result<Self, Error> {
+        let remote = repo.forge_remote()?;
+        let service = GitHubPrService::new(repo.path(), &remote)?;
+
+        Ok(Self {
+            service,
+            remote,
+            lookup_cache: lookup_cache(repo),
+        })
+    }
+
+    /// Matcher for the repository's forge remote that queries `forge` instead of the forge
+    /// the remote points at
+    pub fn with_forge(repo: &GitRepo, forge: Box<dyn ForgeProvider>) -> Result<Self, Error> {
+        let remote = repo.forge_remote()?;
+        let service = GitHubPrService::with_forge(
+            repo.path(),
+            remote.repo.owner.clone(),
+            remote.repo.repo.clone(),
+            forge,
+        )?;
+
+        Ok(Self {
+            service,
+            remote,
+            lookup_cache: lookup_cache(repo),
+        })
+    }
+
+    /// Ask the forge again instead of trusting cached PRs, lookups and checks
+    pub fn refreshing(mut self) -> Self {
+        self.service = self.service.with_cache_ttl_secs(0);
+        self
+    }
+
+    pub fn service(&self) -> &GitHubPrService {
+        &self.service
+    }
+
+    pub fn remote_name(&self) -> &str {
+        &self.remote.name
+    }
+
+    /// The PR for `branch`, or `None` when it has none or the forge cannot be reached.
+    ///
+    /// Lookups are remembered in the [`PrLookupCache`] by the branch's head commit, so until
+    /// the branch moves or the cache TTL passes a branch without a PR costs no request.
+    pub async fn find_pr_for_branch(
+        &self,
+        repo: &GitRepo,
+        branch: &str,
+    ) -> Option<ResolvedPullRequest> {
+        let head_sha = repo
+            .repo()
+            .refname_to_id(&format!("refs/heads/{branch}"))
+            .ok()
+            .map(|oid| oid.to_string());
+        let repo_slug = self.service.repo_slug();
+        if let Some(head_sha) = &head_sha {
+            let ttl_secs = self.service.cache_ttl_secs();
+            if let Some(lookup) = self.lookup_cache.get(repo_slug, branch, head_sha, ttl_secs) {
+                // A branch known to have no PR stays that way until it moves
+                let pr_number = lookup.pr_number?;
+                if let Ok(Some(record)) = self.service.get_cached_pr(pr_number) {
+                    return Some(ResolvedPullRequest {
+                        record,
+                        is_stale: false,
+                    });
+                }
+            }
+        }
+
+        let found = self.lookup_branch(repo, branch).await.ok()?;
+        // A stale fallback means the forge could not be asked, so there is nothing new to keep
+        let is_stale = found.as_ref().map_or(false, |pr| pr.is_stale);
+        if let Some(head_sha) = head_sha.filter(|_| !is_stale) {
+            let pr_number = found.as_ref().map(|pr| pr.record.pr_number);
+            let _ = self
+                .lookup_cache
+                .put(repo_slug, branch, &head_sha, pr_number);
+        }
+        found
+    }
+
+    async fn lookup_branch(
+        &self,
+        repo: &GitRepo,
+        branch: &str,
+    ) -> Result<Option<ResolvedPullRequest>, Error> {
+        let remote_tracking = repo.get_remote_tracking_info(branch).ok();
+        let remote_branch = remote_tracking.as_deref().map(extract_branch_name);
+
+        if let Ok(Some(cached)) = self.service.get_cached_by_branch(branch) {
+            return self
+                .refresh_or_fallback(cached, branch, remote_branch.as_deref(), true)
+                .await;
+        }
+
+        if let Some(ref remote_branch_name) = remote_branch {
+            if let Ok(Some(cached)) = self.service.get_cached_by_remote_head(remote_branch_name) {
+                return self
+                    .refresh_or_fallback(cached, branch, Some(remote_branch_name), true)
+                    .await;
+            }
+        }
+
+        self.lookup_live(branch, remote_branch.as_deref(), true)
+            .await
+    }
+
+    pub async fn refresh_pr_for_branch(
+        &self,
+        repo: &GitRepo,
+        branch: &str,
+    ) -> Result<Option<ResolvedPullRequest>, Error> {
+        let remote_tracking = repo.get_remote_tracking_info(branch).ok();
+        let remote_branch = remote_tracking.as_deref().map(extract_branch_name);
+
+        if let Some(cached) = self.service.get_cached_by_branch(branch)?.or_else(|| {
+            remote_branch.as_deref().and_then(|remote_branch_name| {
+                self.service
+                    .get_cached_by_remote_head(remote_branch_name)
+                    .ok()
+                    .flatten()
+            })
+        }) {
+            return self
+                .refresh_or_fallback(cached, branch, remote_branch.as_deref(), false)
+                .await;
+        }
+
+        self.lookup_live(branch, remote_branch.as_deref(), false)
+            .await
+    }
+
+    async fn refresh_or_fallback(
+        &self,
+        cached: PullRequestRecord,
+        branch: &str,
+        remote_branch: Option<&str>,
+        allow_stale_on_error: bool,
+    ) -> Result<Option<ResolvedPullRequest>, Error> {
+        if cached.is_fresh(self.service.cache_ttl_secs()) {
+            let cached = self.attach_associations(cached, branch, remote_branch)?;
+            return Ok(Some(ResolvedPullRequest {
+                record: cached,
+                is_stale: false,
+            }));
+        }
+
+        match self.service.get_pr(cached.pr_number).await {
+            Ok(refreshed) => {
+                let refreshed = self.attach_associations(refreshed, branch, remote_branch)?;
+                Ok(Some(ResolvedPullRequest {
+                    record: refreshed,
+                    is_stale: false,
+                }))
+            }
+            Err(err) if allow_stale_on_error => {
+                let cached = self.attach_associations(cached, branch, remote_branch)?;
+                let _ = err;
+                Ok(Some(ResolvedPullRequest {
+                    record: cached,
+                    is_stale: true,
+                }))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn lookup_live(
+        &self,
+        branch: &str,
+        remote_branch: Option<&str>,
+        allow_stale_on_error: bool,
+    ) -> Result<Option<ResolvedPullRequest>, Error> {
+        if let Some(found) = self.service.find_pr_by_head(branch).await? {
+            let found = self.attach_associations(found, branch, remote_branch)?;
+            return Ok(Some(ResolvedPullRequest {
+                record: found,
+                is_stale: false,
+            }));
+        }
+
+        if let Some(remote_branch_name) = remote_branch {
+            if let Some(found) = self.service.find_pr_by_head(remote_branch_name).await? {
+                let found = self.attach_associations(found, branch, Some(remote_branch_name))?;
+                return Ok(Some(ResolvedPullRequest {
+                    record: found,
+                    is_stale: false,
+                }));
+            }
+        }
+
+        let fork_owner = &self.remote.repo.owner;
+        if let Some(found) = self
+            .service
+            .find_pr_by_head_with_owner(fork_owner, branch)
+            .await?
+        {
+            let found = self.attach_associations(found, branch, remote_branch)?;
+            return Ok(Some(ResolvedPullRequest {
+                record: found,
+                is_stale: false,
+            }));
+        }
+
+        if allow_stale_on_error {
+            return Ok(None);
+        }
+
+        Ok(None)
+    }
+
+    fn attach_associations(
+        &self,
+        record: PullRequestRecord,
+        branch: &str,
+        remote_branch: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        let _ = self.service.attach_branch(record.pr_number, branch)?;
+        if let Some(remote_branch) = remote_branch {
+            let _ = self
+                .service
+                .attach_remote_head(record.pr_number, remote_branch)?;
+        }
+
+        Ok(self
+            .service
+            .get_cached_pr(record.pr_number)?
+            .unwrap_or(record))
+    }
+}
+
+/// The lookup cache kept next to the PR index in `.git/xgit`
+fn lookup_cache(repo: &GitRepo) -> PrLookupCache {
+    PrLookupCache::new(repo.git_dir().join("xgit").join("pr-cache.json"))
+}
+
+fn extract_branch_name(remote_tracking: &str) -> String {
+    if let Some(slash_pos) = remote_tracking.find('/') {
+        remote_tracking[slash_pos + 1..].to_string()
+    } else {
+        remote_tracking.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitHubPrMatcher;
+    use crate::github::types::PullRequestStatus;
+    use crate::test_utils::{create_test_repo, MockForge, RepoTestOperations};
+
+    #[tokio::test]
+    async fn finds_prs_through_the_forge_and_then_the_cache() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?;
+        repo.add_remote("origin", "https://github.com/octo/repo.git")?;
+        let forge = MockForge::new().with_pr(MockForge::pull_request(
+            7,
+            "feature",
+            PullRequestStatus::Open,
+        ));
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(forge.clone()))?;
+
+        let found = matcher.find_pr_for_branch(&repo, "feature").await.unwrap();
+        assert_eq!(found.record.pr_number, 7);
+        assert!(!found.is_stale);
+        assert!(matcher.find_pr_for_branch(&repo, "master").await.is_none());
+
+        // The first lookup is cached, so asking again stays off the forge
+        let calls_before = forge.calls().len();
+        let cached = matcher.find_pr_for_branch(&repo, "feature").await.unwrap();
+        assert_eq!(cached.record.branch_names, vec!["feature"]);
+        assert_eq!(forge.calls().len(), calls_before);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn remembers_branches_without_prs_until_they_move() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_and_checkout_branch("feature")?;
+        repo.add_remote("origin", "https://github.com/octo/repo.git")?;
+        let forge = MockForge::new();
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(forge.clone()))?;
+
+        assert!(matcher.find_pr_for_branch(&repo, "feature").await.is_none());
+        let calls_before = forge.calls().len();
+        assert!(matcher.find_pr_for_branch(&repo, "feature").await.is_none());
+        assert_eq!(forge.calls().len(), calls_before);
+
+        // A new commit on the branch may come with a PR, so it is looked up again
+        repo.add_file_and_commit("feature.txt", "feature", "Add feature")?;
+        assert!(matcher.find_pr_for_branch(&repo, "feature").await.is_none());
+        let calls_after_commit = forge.calls().len();
+        assert!(calls_after_commit > calls_before);
+
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(forge.clone()))?.refreshing();
+        assert!(matcher.find_pr_for_branch(&repo, "feature").await.is_none());
+        assert!(forge.calls().len() > calls_after_commit);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn finds_the_pr_that_introduced_a_commit() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?;
+        repo.add_remote("origin", "https://github.com/octo/repo.git")?;
+        let mut record = MockForge::pull_request(12, "feature", PullRequestStatus::Merged);
+        record.commit_shas = vec!["abc123".to_string()];
+        let forge = MockForge::new().with_pr(record);
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(forge.clone()))?;
+
+        let found = matcher.service().find_pr_by_commit("abc123").await?;
+        assert_eq!(found.map(|pr| pr.pr_number), Some(12));
+        assert!(matcher
+            .service()
+            .find_pr_by_commit("def456")
+            .await?
+            .is_none());
+
+        // The commit is now linked in the cache
+        let calls_before = forge.calls().len();
+        let cached = matcher.service().find_pr_by_commit("abc123").await?;
+        assert_eq!(cached.map(|pr| pr.pr_number), Some(12));
+        assert_eq!(forge.calls().len(), calls_before);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unreachable_forge_means_no_pr_unless_refresh_is_forced() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?;
+        repo.add_remote("origin", "git@github.com:octo/repo.git")?;
+        let matcher = GitHubPrMatcher::with_forge(&repo, Box::new(MockForge::new().offline()))?;
+
+        assert!(matcher.find_pr_for_branch(&repo, "master").await.is_none());
+        assert!(matcher
+            .refresh_pr_for_branch(&repo, "master")
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,785 @@
+use crate::{
+    forge::{self, ForgeProvider, ForgeRemote},
+    git::GitRepo,
+    github::{
+        auth::GITHUB_HOST,
+        pr_index::{JsonPrIndexStore, PrIndexStore},
+        types::{
+            parse_timestamp, ChecksState, MergeMethod, PrListFilter, PullRequestChecks,
+            PullRequestListing, PullRequestRecord, PullRequestSnapshot, PullRequestStatus,
+            ReviewDecision,
+        },
+    },
+};
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+pub struct GitHubPrService {
+    forge: Box<dyn ForgeProvider>,
+    repo_slug: String,
+    store: Box<dyn PrIndexStore>,
+    cache_ttl_secs: u64,
+}
+
+impl GitHubPrService {
+    /// Service for the repository behind `remote`, on whichever forge hosts it; see
+    /// [`forge::provider_for`]
+    pub fn new(repo_path: &Path, remote: &ForgeRemote) -> Result<Self, Error> {
+        let forge = forge::provider_for(repo_path, remote)?;
+        Self::with_forge(
+            repo_path,
+            remote.repo.owner.clone(),
+            remote.repo.repo.clone(),
+            forge,
+        )
+    }
+
+    /// Service talking to `forge`, with the PR index kept in the repository at `repo_path`
+    pub fn with_forge(
+        repo_path: &Path,
+        owner: String,
+        repo: String,
+        forge: Box<dyn ForgeProvider>,
+    ) -> Result<Self, Error> {
+        let discovered_repo = git2::Repository::discover(repo_path)
+            .context("Failed to discover repository for PR index")?;
+        let index_path = discovered_repo.path().join("xgit").join("pr-index.json");
+
+        Ok(Self {
+            forge,
+            repo_slug: format!("{owner}/{repo}"),
+            store: Box::new(JsonPrIndexStore::new(index_path)),
+            cache_ttl_secs: DEFAULT_CACHE_TTL_SECS,
+        })
+    }
+
+    pub fn ensure_ready(&self) -> Result<(), Error> {
+        self.forge.ensure_ready()
+    }
+
+    pub fn repo_slug(&self) -> &str {
+        &self.repo_slug
+    }
+
+    /// Treat cached PRs and checks as fresh for `ttl_secs`; `0` always asks the forge again
+    pub fn with_cache_ttl_secs(mut self, ttl_secs: u64) -> Self {
+        self.cache_ttl_secs = ttl_secs;
+        self
+    }
+
+    pub fn cache_ttl_secs(&self) -> u64 {
+        self.cache_ttl_secs
+    }
+
+    pub async fn get_default_branch(&self) -> Result<String, Error> {
+        self.forge.get_default_branch().await
+    }
+
+    pub async fn resolve_trunk_base_branch(&self, repo: &GitRepo) -> Result<String, Error> {
+        if let Ok(default_branch) = self.get_default_branch().await {
+            return Ok(default_branch);
+        }
+
+        repo.trunk_branch().context(
+            "Unable to determine trunk branch from GitHub default branch or local main/master",
+        )
+    }
+
+    pub fn get_cached_pr(&self, pr_number: u64) -> Result<Option<PullRequestRecord>, Error> {
+        self.store.get_by_pr(&self.repo_slug, pr_number)
+    }
+
+    pub fn get_cached_by_branch(
+        &self,
+        branch_name: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.store.get_by_branch(&self.repo_slug, branch_name)
+    }
+
+    pub fn get_cached_by_remote_head(
+        &self,
+        remote_head_name: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.store
+            .get_by_remote_head(&self.repo_slug, remote_head_name)
+    }
+
+    pub fn get_cached_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.store.get_by_commit(&self.repo_slug, commit_sha)
+    }
+
+    /// Every PR cached for this repository, without contacting the forge
+    pub fn cached_pull_requests(&self) -> Result<Vec<PullRequestRecord>, Error> {
+        self.store.list_records(&self.repo_slug)
+    }
+
+    pub fn attach_branch(
+        &self,
+        pr_number: u64,
+        branch_name: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.store
+            .attach_branch(&self.repo_slug, pr_number, branch_name)
+    }
+
+    pub fn attach_remote_head(
+        &self,
+        pr_number: u64,
+        remote_head_name: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.store
+            .attach_remote_head(&self.repo_slug, pr_number, remote_head_name)
+    }
+
+    pub fn attach_commit(
+        &self,
+        pr_number: u64,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        self.store
+            .attach_commit(&self.repo_slug, pr_number, commit_sha)
+    }
+
+    pub async fn hydrate_pr_from_commit(
+        &self,
+        pr_number: u64,
+        commit_sha: &str,
+        branch_name: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        let record = match self.get_cached_pr(pr_number)? {
+            Some(record) => record,
+            None => self.get_pr(pr_number).await?,
+        };
+
+        self.attach_commit(pr_number, commit_sha)?;
+        if let Some(branch_name) = branch_name {
+            self.attach_branch(pr_number, branch_name)?;
+        }
+
+        Ok(self.get_cached_pr(pr_number)?.unwrap_or(record))
+    }
+
+    pub async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        let live = self.forge.get_pr(pr_number).await?;
+
+        self.persist_record(live)
+    }
+
+    /// The PR's description as it is on the forge now; descriptions are not cached
+    pub async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error> {
+        self.forge.get_pr_body(pr_number).await
+    }
+
+    pub async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        let live = self.forge.create_pr(title, body, head, base, draft).await?;
+
+        self.persist_record(live)
+    }
+
+    pub async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        let live = self.forge.update_pr(pr_number, base, title, body).await?;
+
+        self.persist_record(live)
+    }
+
+    /// Open PRs passing `filter`, which are cached along the way
+    pub async fn list_open_prs(
+        &self,
+        filter: &PrListFilter,
+    ) -> Result<Vec<PullRequestListing>, Error> {
+        let mut listings = self.forge.list_open_prs(filter).await?;
+        for listing in &mut listings {
+            listing.record = self.persist_record(listing.record.clone())?;
+        }
+        Ok(listings)
+    }
+
+    pub async fn find_pr_by_head(
+        &self,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let live = self.forge.find_pr_by_head(head_branch).await?;
+
+        live.map(|record| self.persist_record(record)).transpose()
+    }
+
+    pub async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let live = self
+            .forge
+            .find_pr_by_head_with_owner(owner, head_branch)
+            .await?;
+
+        live.map(|record| self.persist_record(record)).transpose()
+    }
+
+    /// The PR that brought `commit_sha` in, from the local index when a lookup already linked
+    /// the two, otherwise from the forge
+    pub async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        if let Some(cached) = self.get_cached_by_commit(commit_sha)? {
+            return Ok(Some(cached));
+        }
+        let Some(live) = self.forge.find_pr_by_commit(commit_sha).await? else {
+            return Ok(None);
+        };
+
+        let persisted = self.persist_record(live)?;
+        Ok(self
+            .attach_commit(persisted.pr_number, commit_sha)?
+            .or(Some(persisted)))
+    }
+
+    /// Merge PR `pr_number` at `head_sha` with `method`, returning the merged PR
+    pub async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<PullRequestRecord, Error> {
+        self.forge.merge_pr(pr_number, method, head_sha).await?;
+
+        self.get_pr(pr_number).await
+    }
+
+    /// `record` with the CI and review state of its head commit, fetched from the forge unless
+    /// the cached state is younger than the cache TTL
+    pub async fn load_checks(
+        &self,
+        record: &PullRequestRecord,
+    ) -> Result<PullRequestRecord, Error> {
+        if record.has_fresh_checks(self.cache_ttl_secs) {
+            return Ok(record.clone());
+        }
+        let checks = self
+            .forge
+            .get_pr_checks(record.pr_number, &record.head_sha)
+            .await?;
+
+        let mut record = record.clone();
+        record.set_checks(checks);
+        self.store.upsert_record(&record)
+    }
+
+    pub fn mark_refreshed(&self, pr_number: u64) -> Result<Option<PullRequestRecord>, Error> {
+        self.store.mark_refreshed(&self.repo_slug, pr_number)
+    }
+
+    fn persist_record(&self, record: PullRequestRecord) -> Result<PullRequestRecord, Error> {
+        let persisted = self.store.upsert_record(&record)?;
+        let _ = self
+            .store
+            .mark_refreshed(&self.repo_slug, persisted.pr_number)?;
+        Ok(self
+            .get_cached_pr(persisted.pr_number)?
+            .unwrap_or(persisted))
+    }
+}
+
+/// [`ForgeProvider`] implemented by shelling out to the GitHub CLI
+pub(crate) struct GhCli {
+    repo_path: PathBuf,
+    repo_slug: String,
+    /// GitHub Enterprise host, passed to `gh` as `GH_HOST`; `None` for github.com
+    host: Option<String>,
+}
+
+impl GhCli {
+    pub(crate) fn new(repo_path: &Path, host: &str, repo_slug: String) -> Self {
+        Self {
+            repo_path: repo_path.to_path_buf(),
+            repo_slug,
+            host: (host != GITHUB_HOST).then(|| host.to_string()),
+        }
+    }
+
+    fn output(&self, args: &[&str]) -> Result<String, Error> {
+        let mut command = Command::new("gh");
+        command.args(args).current_dir(&self.repo_path);
+        if let Some(host) = &self.host {
+            command.env("GH_HOST", host);
+        }
+        let output = command.output().context("Failed to execute gh command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!(
+                "gh {:?} failed (code {:?}): {}",
+                args,
+                output.status.code(),
+                stderr.trim()
+            ));
+        }
+
+        String::from_utf8(output.stdout).context("Invalid UTF-8 gh output")
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GhCli {
+    fn ensure_ready(&self) -> Result<(), Error> {
+        let version = Command::new("gh")
+            .arg("--version")
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to execute gh --version. Please install GitHub CLI (`gh`)")?;
+        if !version.status.success() {
+            return Err(anyhow::anyhow!(
+                "GitHub CLI (`gh`) is required for xg GitHub operations"
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn get_default_branch(&self) -> Result<String, Error> {
+        let output = self.output(&[
+            "api",
+            &format!("repos/{}", self.repo_slug),
+            "--jq",
+            ".default_branch",
+        ])?;
+        Ok(output.trim().to_string())
+    }
+
+    async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        gh_pr_view(self, pr_number)
+    }
+
+    async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error> {
+        let output = self.output(&[
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--repo",
+            &self.repo_slug,
+            "--json",
+            "body",
+            "--jq",
+            ".body",
+        ])?;
+        Ok(output.strip_suffix('\n').unwrap_or(&output).to_string())
+    }
+
+    async fn find_pr_by_head(&self, head_branch: &str) -> Result<Option<PullRequestRecord>, Error> {
+        gh_pr_find_by_head(self, head_branch)
+    }
+
+    async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        gh_pr_find_by_head_with_owner(self, owner, head_branch)
+    }
+
+    async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        gh_pr_find_by_commit(self, commit_sha)
+    }
+
+    async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        _head_sha: &str,
+    ) -> Result<PullRequestChecks, Error> {
+        gh_pr_checks(self, pr_number)
+    }
+
+    async fn list_open_prs(&self, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error> {
+        gh_pr_list_open(self, filter)
+    }
+
+    async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        gh_pr_create(self, title, body, head, base, draft)
+    }
+
+    async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        gh_pr_edit(self, pr_number, base, title, body)?;
+        gh_pr_view(self, pr_number)
+    }
+
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error> {
+        self.output(&[
+            "pr",
+            "merge",
+            &pr_number.to_string(),
+            "--repo",
+            &self.repo_slug,
+            &format!("--{}", method.as_str()),
+            "--match-head-commit",
+            head_sha,
+        ])
+        .context("`gh pr merge` failed")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GhPrViewResponse {
+    number: u64,
+    title: String,
+    state: String,
+    url: String,
+    #[serde(rename = "isDraft")]
+    is_draft: bool,
+    #[serde(rename = "baseRefName")]
+    base_ref_name: String,
+    #[serde(rename = "headRefName")]
+    head_ref_name: String,
+    #[serde(rename = "headRefOid")]
+    head_ref_oid: String,
+    #[serde(rename = "createdAt", default)]
+    created_at: Option<String>,
+    #[serde(rename = "mergedAt")]
+    merged_at: Option<String>,
+}
+
+fn gh_pr_view(gh: &GhCli, pr_number: u64) -> Result<PullRequestRecord, Error> {
+    let output = gh.output(&[
+        "pr",
+        "view",
+        &pr_number.to_string(),
+        "--repo",
+        &gh.repo_slug,
+        "--json",
+        "number,title,state,url,isDraft,baseRefName,headRefName,headRefOid,createdAt,mergedAt",
+    ])?;
+    let parsed: GhPrViewResponse =
+        serde_json::from_str(&output).context("Failed to parse `gh pr view` JSON output")?;
+    Ok(gh_response_to_record(&gh.repo_slug, parsed))
+}
+
+#[derive(Debug, Deserialize)]
+struct GhPrChecksResponse {
+    #[serde(rename = "statusCheckRollup", default)]
+    status_check_rollup: Vec<GhCheck>,
+    #[serde(rename = "reviewDecision", default)]
+    review_decision: Option<String>,
+}
+
+/// A check run (`status` and `conclusion`) or a commit status (`state`) in the rollup
+#[derive(Debug, Deserialize)]
+struct GhCheck {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+fn gh_pr_checks(gh: &GhCli, pr_number: u64) -> Result<PullRequestChecks, Error> {
+    let output = gh.output(&[
+        "pr",
+        "view",
+        &pr_number.to_string(),
+        "--repo",
+        &gh.repo_slug,
+        "--json",
+        "statusCheckRollup,reviewDecision",
+    ])?;
+    let parsed: GhPrChecksResponse =
+        serde_json::from_str(&output).context("Failed to parse `gh pr view` JSON output")?;
+    Ok(gh_checks(parsed))
+}
+
+fn gh_checks(response: GhPrChecksResponse) -> PullRequestChecks {
+    let states =
+        response
+            .status_check_rollup
+            .iter()
+            .map(|check| match (&check.state, &check.status) {
+                (Some(state), _) => ChecksState::from_status(state),
+                (None, status) => ChecksState::from_check_run(
+                    status.as_deref().unwrap_or_default(),
+                    check.conclusion.as_deref(),
+                ),
+            });
+    PullRequestChecks {
+        checks: ChecksState::combine(states),
+        review_decision: response
+            .review_decision
+            .as_deref()
+            .and_then(ReviewDecision::parse),
+    }
+}
+
+/// A PR in `gh pr list --json` output, with the fields `xg pr list` shows
+#[derive(Debug, Deserialize)]
+struct GhPrListItem {
+    #[serde(flatten)]
+    pr: GhPrViewResponse,
+    #[serde(flatten)]
+    checks: GhPrChecksResponse,
+    #[serde(default)]
+    author: Option<GhUser>,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+    #[serde(rename = "reviewRequests", default)]
+    review_requests: Vec<GhUser>,
+    #[serde(rename = "isCrossRepository", default)]
+    is_cross_repository: bool,
+}
+
+/// A user, or in `reviewRequests` possibly a team, which has no login
+#[derive(Debug, Deserialize)]
+struct GhUser {
+    #[serde(default)]
+    login: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhLabel {
+    name: String,
+}
+
+/// Open PRs through `gh pr list`, which filters on the server with `@me` standing for the
+/// signed-in user
+fn gh_pr_list_open(gh: &GhCli, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error> {
+    let mut args = vec![
+        "pr",
+        "list",
+        "--repo",
+        &gh.repo_slug,
+        "--state",
+        "open",
+        "--limit",
+        "100",
+        "--json",
+        "number,title,state,url,isDraft,baseRefName,headRefName,headRefOid,createdAt,mergedAt,author,labels,reviewRequests,isCrossRepository,statusCheckRollup,reviewDecision",
+    ];
+    if filter.mine {
+        args.extend(["--author", "@me"]);
+    }
+    if filter.review_requested {
+        args.extend(["--search", "review-requested:@me"]);
+    }
+    for label in &filter.labels {
+        args.extend(["--label", label]);
+    }
+
+    let output = gh.output(&args)?;
+    let parsed: Vec<GhPrListItem> =
+        serde_json::from_str(&output).context("Failed to parse `gh pr list` JSON output")?;
+    Ok(parsed
+        .into_iter()
+        .map(|item| {
+            let mut record = gh_response_to_record(&gh.repo_slug, item.pr);
+            record.set_checks(gh_checks(item.checks));
+            PullRequestListing {
+                record,
+                author: item
+                    .author
+                    .and_then(|author| author.login)
+                    .unwrap_or_default(),
+                labels: item.labels.into_iter().map(|label| label.name).collect(),
+                requested_reviewers: item
+                    .review_requests
+                    .into_iter()
+                    .filter_map(|reviewer| reviewer.login)
+                    .collect(),
+                is_cross_repository: item.is_cross_repository,
+            }
+        })
+        .collect())
+}
+
+fn gh_pr_create(
+    gh: &GhCli,
+    title: &str,
+    body: Option<&str>,
+    head: &str,
+    base: &str,
+    draft: bool,
+) -> Result<PullRequestRecord, Error> {
+    let mut args = vec![
+        "pr".to_string(),
+        "create".to_string(),
+        "--repo".to_string(),
+        gh.repo_slug.clone(),
+        "--title".to_string(),
+        title.to_string(),
+        "--head".to_string(),
+        head.to_string(),
+        "--base".to_string(),
+        base.to_string(),
+    ];
+
+    if let Some(body) = body {
+        args.push("--body".to_string());
+        args.push(body.to_string());
+    } else {
+        args.push("--body".to_string());
+        args.push(String::new());
+    }
+
+    if draft {
+        args.push("--draft".to_string());
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|value| value.as_str()).collect();
+    gh.output(&arg_refs).context("`gh pr create` failed")?;
+
+    gh_pr_find_by_head(gh, head)?
+        .ok_or_else(|| anyhow::anyhow!("PR was created but could not be resolved by head branch"))
+}
+
+fn gh_pr_edit(
+    gh: &GhCli,
+    pr_number: u64,
+    base: Option<&str>,
+    title: Option<&str>,
+    body: Option<&str>,
+) -> Result<(), Error> {
+    let mut args = vec![
+        "pr".to_string(),
+        "edit".to_string(),
+        pr_number.to_string(),
+        "--repo".to_string(),
+        gh.repo_slug.clone(),
+    ];
+
+    if let Some(base) = base {
+        args.push("--base".to_string());
+        args.push(base.to_string());
+    }
+    if let Some(title) = title {
+        args.push("--title".to_string());
+        args.push(title.to_string());
+    }
+    if let Some(body) = body {
+        args.push("--body".to_string());
+        args.push(body.to_string());
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|value| value.as_str()).collect();
+    gh.output(&arg_refs).context("`gh pr edit` failed")?;
+    Ok(())
+}
+
+fn gh_pr_find_by_head(gh: &GhCli, head_branch: &str) -> Result<Option<PullRequestRecord>, Error> {
+    gh_pr_list(gh, head_branch)
+}
+
+fn gh_pr_find_by_head_with_owner(
+    gh: &GhCli,
+    owner: &str,
+    head_branch: &str,
+) -> Result<Option<PullRequestRecord>, Error> {
+    gh_pr_list(gh, &format!("{owner}:{head_branch}"))
+}
+
+fn gh_pr_find_by_commit(gh: &GhCli, commit_sha: &str) -> Result<Option<PullRequestRecord>, Error> {
+    let output = gh.output(&[
+        "api",
+        &format!("repos/{}/commits/{commit_sha}/pulls", gh.repo_slug),
+        "--jq",
+        "(map(select(.merged_at != null)) + .)[0].number // empty",
+    ])?;
+    match output.trim() {
+        "" => Ok(None),
+        number => {
+            let pr_number = number
+                .parse::<u64>()
+                .context("Failed to parse PR number from `gh api` output")?;
+            gh_pr_view(gh, pr_number).map(Some)
+        }
+    }
+}
+
+fn gh_pr_list(gh: &GhCli, head_selector: &str) -> Result<Option<PullRequestRecord>, Error> {
+    let output = gh.output(&[
+        "pr",
+        "list",
+        "--repo",
+        &gh.repo_slug,
+        "--head",
+        head_selector,
+        "--state",
+        "all",
+        "--limit",
+        "1",
+        "--json",
+        "number,title,state,url,isDraft,baseRefName,headRefName,headRefOid,createdAt,mergedAt",
+    ])?;
+
+    let parsed: Vec<GhPrViewResponse> =
+        serde_json::from_str(&output).context("Failed to parse `gh pr list` JSON output")?;
+    Ok(parsed
+        .into_iter()
+        .next()
+        .map(|response| gh_response_to_record(&gh.repo_slug, response)))
+}
+
+fn gh_response_to_record(repo_slug: &str, response: GhPrViewResponse) -> PullRequestRecord {
+    PullRequestRecord::from_snapshot(PullRequestSnapshot {
+        repo_slug: repo_slug.to_string(),
+        pr_number: response.number,
+        title: response.title,
+        url: response.url,
+        base_ref: response.base_ref_name,
+        head_ref: response.head_ref_name,
+        head_sha: response.head_ref_oid,
+        draft: response.is_draft,
+        status: gh_state_to_pull_request_status(&response.state, response.merged_at.as_deref()),
+        created_at: response.created_at.as_deref().and_then(parse_timestamp),
+        merged_at: response.merged_at.as_deref().and_then(parse_timestamp),
+    })
+}
+
+fn gh_state_to_pull_request_status(state: &str, merged_at: Option<&str>) -> PullRequestStatus {
+    if merged_at.is_some() {
+        PullRequestStatus::Merged
+    } else if state.eq_ignore_ascii_case("closed") {
+        PullRequestStatus::Closed
+    } else {
+        PullRequestStatus::Open
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Error;
+
+use crate::forge::{ForgeKind, ForgeRemote};
+use crate::git::GitRepo;
+use crate::github::auth::GITHUB_HOST;
+
+/// Root of the REST API for `host`: `https://api.github.com`, or `https://<host>/api/v3` for
+/// GitHub Enterprise Server
+pub fn api_base_url(host: &str) -> String {
+    if host.eq_ignore_ascii_case(GITHUB_HOST) {
+        "https://api.github.com".to_string()
+    } else {
+        format!("https://{host}/api/v3")
+    }
+}
+
+impl GitRepo {
+    /// The remote to use for GitHub: the configured default remote, `origin` or `upstream`
+    /// when one points at a GitHub host (github.com or one in `github.hosts`), otherwise the
+    /// first remote that does
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn github_remote(&self) -> Result<ForgeRemote, Error> {
+        self.find_forge_remote(|kind| kind == ForgeKind::GitHub)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No GitHub remote found; add GitHub Enterprise hosts to `github.hosts` in ~/.config/xgit/config.toml"
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::api_base_url;
+    use crate::test_utils::create_test_repo;
+
+    #[test]
+    fn api_base_url_uses_the_enterprise_api_path() {
+        assert_eq!(api_base_url("github.com"), "https://api.github.com");
+        assert_eq!(
+            api_base_url("git.corp.example"),
+            "https://git.corp.example/api/v3"
+        );
+    }
+
+    #[test]
+    fn github_remote_prefers_origin_and_skips_other_hosts() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        assert!(repo.github_remote().is_err());
+
+        repo.add_remote("mirror", "git@gitlab.com:octo/repo.git")?;
+        repo.add_remote("fork", "https://github.com/me/repo.git")?;
+        assert_eq!(repo.github_remote()?.name, "fork");
+
+        repo.add_remote("origin", "git@github.com:octo/repo.git")?;
+        let remote = repo.github_remote()?;
+        assert_eq!(remote.name, "origin");
+        assert_eq!(remote.repo.slug(), "octo/repo");
+        Ok(())
+    }
+}
@@ -0,0 +1,561 @@
+use crate::git::commits::builder::CommitTime;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PullRequestStatus {
+    Open,
+    Closed,
+    Merged,
+}
+
+/// Combined result of the commit statuses and check runs on a PR's head commit
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ChecksState {
+    Passing,
+    Failing,
+    Pending,
+}
+
+impl ChecksState {
+    /// One check run, from its `status` and `conclusion` in either the REST API's lowercase or
+    /// GraphQL's uppercase
+    pub fn from_check_run(status: &str, conclusion: Option<&str>) -> Self {
+        if !status.eq_ignore_ascii_case("completed") {
+            return ChecksState::Pending;
+        }
+        match conclusion.unwrap_or_default().to_ascii_lowercase().as_str() {
+            "success" | "neutral" | "skipped" => ChecksState::Passing,
+            "" => ChecksState::Pending,
+            _ => ChecksState::Failing,
+        }
+    }
+
+    /// One commit status: `success`, `pending` (or GraphQL's `expected`), `failure` or `error`
+    pub fn from_status(state: &str) -> Self {
+        match state.to_ascii_lowercase().as_str() {
+            "success" => ChecksState::Passing,
+            "pending" | "expected" => ChecksState::Pending,
+            _ => ChecksState::Failing,
+        }
+    }
+
+    /// Failing when any check fails, else pending when any is still running; `None` without
+    /// checks
+    pub fn combine(states: impl IntoIterator<Item = ChecksState>) -> Option<Self> {
+        states.into_iter().fold(None, |combined, state| {
+            Some(match (combined, state) {
+                (Some(ChecksState::Failing), _) | (_, ChecksState::Failing) => ChecksState::Failing,
+                (Some(ChecksState::Pending), _) | (_, ChecksState::Pending) => ChecksState::Pending,
+                _ => ChecksState::Passing,
+            })
+        })
+    }
+}
+
+/// Where a PR's reviews stand
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    /// The base branch requires a review the PR does not have yet
+    ReviewRequired,
+}
+
+impl ReviewDecision {
+    /// GraphQL's `reviewDecision`: `APPROVED`, `CHANGES_REQUESTED` or `REVIEW_REQUIRED`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "APPROVED" => Some(ReviewDecision::Approved),
+            "CHANGES_REQUESTED" => Some(ReviewDecision::ChangesRequested),
+            "REVIEW_REQUIRED" => Some(ReviewDecision::ReviewRequired),
+            _ => None,
+        }
+    }
+
+    /// The decision from `(reviewer, state)` reviews, oldest first, where each reviewer's
+    /// latest approval or change request counts and any change request outweighs approvals
+    pub fn from_reviews<'a>(reviews: impl IntoIterator<Item = (&'a str, &'a str)>) -> Option<Self> {
+        let mut latest: Vec<(&str, ReviewDecision)> = Vec::new();
+        for (reviewer, state) in reviews {
+            let decision = match state.to_ascii_uppercase().as_str() {
+                "APPROVED" => ReviewDecision::Approved,
+                "CHANGES_REQUESTED" => ReviewDecision::ChangesRequested,
+                // A dismissed review no longer counts; comments do not change a decision
+                "DISMISSED" => {
+                    latest.retain(|(existing, _)| *existing != reviewer);
+                    continue;
+                }
+                _ => continue,
+            };
+            latest.retain(|(existing, _)| *existing != reviewer);
+            latest.push((reviewer, decision));
+        }
+
+        let decisions = latest.iter().map(|(_, decision)| *decision);
+        if decisions
+            .clone()
+            .any(|decision| decision == ReviewDecision::ChangesRequested)
+        {
+            Some(ReviewDecision::ChangesRequested)
+        } else if decisions
+            .clone()
+            .any(|decision| decision == ReviewDecision::Approved)
+        {
+            Some(ReviewDecision::Approved)
+        } else {
+            None
+        }
+    }
+}
+
+/// CI and review state of a PR, fetched separately from the PR itself
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PullRequestChecks {
+    pub checks: Option<ChecksState>,
+    pub review_decision: Option<ReviewDecision>,
+}
+
+/// How a PR's commits land on its base branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMethod {
+    /// A merge commit
+    Merge,
+    /// One commit with all of the PR's changes
+    Squash,
+    /// The PR's commits replayed one by one
+    Rebase,
+}
+
+impl MergeMethod {
+    /// `merge`, `squash` or `rebase`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "merge" => Some(MergeMethod::Merge),
+            "squash" => Some(MergeMethod::Squash),
+            "rebase" => Some(MergeMethod::Rebase),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestRecord {
+    pub repo_slug: String,
+    pub pr_number: u64,
+    pub title: String,
+    pub url: String,
+    pub base_ref: String,
+    pub head_ref: String,
+    pub head_sha: String,
+    pub draft: bool,
+    pub status: PullRequestStatus,
+    pub branch_names: Vec<String>,
+    pub remote_head_names: Vec<String>,
+    pub commit_shas: Vec<String>,
+    pub last_refreshed_at: Option<u64>,
+    pub updated_at: u64,
+    /// When the PR was opened on the forge, in seconds since the Unix epoch
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    /// When the PR was merged on the forge, in seconds since the Unix epoch
+    #[serde(default)]
+    pub merged_at: Option<u64>,
+    /// Checks on `head_sha`, once [`PullRequestChecks`] have been fetched
+    #[serde(default)]
+    pub checks: Option<ChecksState>,
+    #[serde(default)]
+    pub review_decision: Option<ReviewDecision>,
+    /// When `checks` and `review_decision` were last fetched, in seconds since the Unix epoch
+    #[serde(default)]
+    pub checks_refreshed_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestSnapshot {
+    pub repo_slug: String,
+    pub pr_number: u64,
+    pub title: String,
+    pub url: String,
+    pub base_ref: String,
+    pub head_ref: String,
+    pub head_sha: String,
+    pub draft: bool,
+    pub status: PullRequestStatus,
+    pub created_at: Option<u64>,
+    pub merged_at: Option<u64>,
+}
+
+impl PullRequestRecord {
+    pub fn from_snapshot(snapshot: PullRequestSnapshot) -> Self {
+        let now = now_timestamp();
+        Self {
+            repo_slug: snapshot.repo_slug,
+            pr_number: snapshot.pr_number,
+            title: snapshot.title,
+            url: snapshot.url,
+            base_ref: snapshot.base_ref,
+            head_ref: snapshot.head_ref,
+            head_sha: snapshot.head_sha,
+            draft: snapshot.draft,
+            status: snapshot.status,
+            branch_names: Vec::new(),
+            remote_head_names: Vec::new(),
+            commit_shas: Vec::new(),
+            last_refreshed_at: Some(now),
+            updated_at: now,
+            created_at: snapshot.created_at,
+            merged_at: snapshot.merged_at,
+            checks: None,
+            review_decision: None,
+            checks_refreshed_at: None,
+        }
+    }
+
+    pub fn set_checks(&mut self, checks: PullRequestChecks) {
+        self.checks = checks.checks;
+        self.review_decision = checks.review_decision;
+        self.checks_refreshed_at = Some(now_timestamp());
+    }
+
+    /// Whether `checks` were fetched within `ttl_secs`
+    pub fn has_fresh_checks(&self, ttl_secs: u64) -> bool {
+        self.checks_refreshed_at
+            .map(|refreshed_at| now_timestamp().saturating_sub(refreshed_at) < ttl_secs)
+            .unwrap_or(false)
+    }
+
+    pub fn is_closed_or_merged(&self) -> bool {
+        matches!(
+            self.status,
+            PullRequestStatus::Closed | PullRequestStatus::Merged
+        )
+    }
+
+    pub fn is_merged(&self) -> bool {
+        matches!(self.status, PullRequestStatus::Merged)
+    }
+
+    pub fn is_fresh(&self, ttl_secs: u64) -> bool {
+        let now = now_timestamp();
+        self.last_refreshed_at
+            .map(|refreshed_at| now.saturating_sub(refreshed_at) < ttl_secs)
+            .unwrap_or(false)
+    }
+
+    pub fn merge_with(&self, newer: &Self) -> Self {
+        let mut merged = newer.clone();
+        merged.branch_names = union_strings(&self.branch_names, &newer.branch_names);
+        merged.remote_head_names = union_strings(&self.remote_head_names, &newer.remote_head_names);
+        merged.commit_shas = union_strings(&self.commit_shas, &newer.commit_shas);
+        merged.last_refreshed_at = newer.last_refreshed_at.or(self.last_refreshed_at);
+        merged.updated_at = newer.updated_at.max(self.updated_at);
+        merged.created_at = newer.created_at.or(self.created_at);
+        merged.merged_at = newer.merged_at.or(self.merged_at);
+        // Checks belong to a head commit, so only carry them over while it is unchanged
+        if newer.checks_refreshed_at.is_none() && self.head_sha == newer.head_sha {
+            merged.checks = self.checks;
+            merged.review_decision = self.review_decision;
+            merged.checks_refreshed_at = self.checks_refreshed_at;
+        }
+        merged
+    }
+
+    pub fn attach_branch_name(&mut self, branch_name: &str) -> bool {
+        push_unique(&mut self.branch_names, branch_name)
+    }
+
+    pub fn attach_remote_head_name(&mut self, remote_head_name: &str) -> bool {
+        push_unique(&mut self.remote_head_names, remote_head_name)
+    }
+
+    pub fn attach_commit_sha(&mut self, commit_sha: &str) -> bool {
+        push_unique(&mut self.commit_shas, commit_sha)
+    }
+
+    pub fn mark_refreshed(&mut self) {
+        let now = now_timestamp();
+        self.last_refreshed_at = Some(now);
+        self.updated_at = now;
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = now_timestamp();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedPullRequest {
+    pub record: PullRequestRecord,
+    pub is_stale: bool,
+}
+
+/// Which open PRs to list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrListFilter {
+    /// Only PRs opened by the signed-in user
+    pub mine: bool,
+    /// Only PRs waiting on the signed-in user's review
+    pub review_requested: bool,
+    /// Only PRs carrying every one of these labels
+    pub labels: Vec<String>,
+}
+
+impl PrListFilter {
+    /// Whether `listing` passes, with `login` the signed-in user
+    pub fn matches(&self, listing: &PullRequestListing, login: Option<&str>) -> bool {
+        let is_login =
+            |user: &String| login.map_or(false, |login| user.eq_ignore_ascii_case(login));
+        (!self.mine || is_login(&listing.author))
+            && (!self.review_requested || listing.requested_reviewers.iter().any(is_login))
+            && self.labels.iter().all(|label| {
+                listing
+                    .labels
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(label))
+            })
+    }
+}
+
+/// An open PR with the details `xg pr list` shows; `record` includes its checks
+#[derive(Debug, Clone)]
+pub struct PullRequestListing {
+    pub record: PullRequestRecord,
+    /// Login of the PR's author
+    pub author: String,
+    pub labels: Vec<String>,
+    /// Logins of users asked to review; team requests are left out
+    pub requested_reviewers: Vec<String>,
+    /// Whether the head branch lives in a fork rather than the repository itself
+    pub is_cross_repository: bool,
+}
+
+/// Seconds since the Unix epoch for an ISO 8601 timestamp from a forge's API or `gh --json`
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    CommitTime::parse(value).map(|time| time.seconds.max(0) as u64)
+}
+
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn push_unique(values: &mut Vec<String>, value: &str) -> bool {
+    if values.iter().any(|existing| existing == value) {
+        return false;
+    }
+
+    values.push(value.to_string());
+    true
+}
+
+fn union_strings(existing: &[String], newer: &[String]) -> Vec<String> {
+    let mut merged = existing.to_vec();
+    for value in newer {
+        push_unique(&mut merged, value);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChecksState, MergeMethod, PrListFilter, PullRequestChecks, PullRequestListing,
+        PullRequestRecord, PullRequestSnapshot, PullRequestStatus, ReviewDecision,
+    };
+
+    fn record(head_sha: &str) -> PullRequestRecord {
+        PullRequestRecord::from_snapshot(PullRequestSnapshot {
+            repo_slug: "owner/repo".to_string(),
+            pr_number: 12,
+            title: "First".to_string(),
+            url: "https://example.com/12".to_string(),
+            base_ref: "main".to_string(),
+            head_ref: "feature".to_string(),
+            head_sha: head_sha.to_string(),
+            draft: false,
+            status: PullRequestStatus::Open,
+            created_at: None,
+            merged_at: None,
+        })
+    }
+
+    #[test]
+    fn merge_with_preserves_associations() {
+        let mut existing = PullRequestRecord::from_snapshot(PullRequestSnapshot {
+            repo_slug: "owner/repo".to_string(),
+            pr_number: 12,
+            title: "First".to_string(),
+            url: "https://example.com/12".to_string(),
+            base_ref: "main".to_string(),
+            head_ref: "feature".to_string(),
+            head_sha: "aaa".to_string(),
+            draft: false,
+            status: PullRequestStatus::Open,
+            created_at: None,
+            merged_at: None,
+        });
+        existing.attach_branch_name("feature");
+        existing.attach_commit_sha("sha-1");
+
+        let mut newer = PullRequestRecord::from_snapshot(PullRequestSnapshot {
+            repo_slug: "owner/repo".to_string(),
+            pr_number: 12,
+            title: "Second".to_string(),
+            url: "https://example.com/12".to_string(),
+            base_ref: "main".to_string(),
+            head_ref: "feature-renamed".to_string(),
+            head_sha: "bbb".to_string(),
+            draft: false,
+            status: PullRequestStatus::Merged,
+            created_at: None,
+            merged_at: None,
+        });
+        newer.attach_remote_head_name("feature-renamed");
+
+        let merged = existing.merge_with(&newer);
+        assert_eq!(merged.title, "Second");
+        assert_eq!(merged.status, PullRequestStatus::Merged);
+        assert!(merged.branch_names.iter().any(|branch| branch == "feature"));
+        assert!(merged.commit_shas.iter().any(|sha| sha == "sha-1"));
+        assert!(merged
+            .remote_head_names
+            .iter()
+            .any(|remote_head| remote_head == "feature-renamed"));
+    }
+
+    #[test]
+    fn merge_with_keeps_checks_only_for_the_same_head() {
+        let mut existing = record("aaa");
+        existing.set_checks(PullRequestChecks {
+            checks: Some(ChecksState::Failing),
+            review_decision: Some(ReviewDecision::Approved),
+        });
+        assert!(existing.has_fresh_checks(60));
+
+        let merged = existing.merge_with(&record("aaa"));
+        assert_eq!(merged.checks, Some(ChecksState::Failing));
+        assert_eq!(merged.review_decision, Some(ReviewDecision::Approved));
+
+        let merged = existing.merge_with(&record("bbb"));
+        assert_eq!(merged.checks, None);
+        assert!(!merged.has_fresh_checks(60));
+
+        let mut rechecked = record("aaa");
+        rechecked.set_checks(PullRequestChecks::default());
+        assert_eq!(existing.merge_with(&rechecked).checks, None);
+    }
+
+    #[test]
+    fn list_filter_matches_author_reviewers_and_labels() {
+        let listing = PullRequestListing {
+            record: record("aaa"),
+            author: "Octocat".to_string(),
+            labels: vec!["bug".to_string(), "UI".to_string()],
+            requested_reviewers: vec!["hubot".to_string()],
+            is_cross_repository: false,
+        };
+        let matches = |filter: PrListFilter, login| filter.matches(&listing, login);
+
+        assert!(matches(PrListFilter::default(), None));
+        assert!(matches(
+            PrListFilter {
+                mine: true,
+                labels: vec!["ui".to_string()],
+                ..PrListFilter::default()
+            },
+            Some("octocat")
+        ));
+        assert!(!matches(
+            PrListFilter {
+                mine: true,
+                ..PrListFilter::default()
+            },
+            Some("hubot")
+        ));
+        assert!(matches(
+            PrListFilter {
+                review_requested: true,
+                ..PrListFilter::default()
+            },
+            Some("hubot")
+        ));
+        assert!(!matches(
+            PrListFilter {
+                labels: vec!["bug".to_string(), "docs".to_string()],
+                ..PrListFilter::default()
+            },
+            None
+        ));
+    }
+
+    #[test]
+    fn checks_combine_to_the_worst_state() {
+        use ChecksState::{Failing, Passing, Pending};
+        assert_eq!(ChecksState::combine([]), None);
+        assert_eq!(ChecksState::combine([Passing, Passing]), Some(Passing));
+        assert_eq!(ChecksState::combine([Passing, Pending]), Some(Pending));
+        assert_eq!(ChecksState::combine([Failing, Pending]), Some(Failing));
+
+        assert_eq!(ChecksState::from_check_run("in_progress", None), Pending);
+        assert_eq!(
+            ChecksState::from_check_run("COMPLETED", Some("SKIPPED")),
+            Passing
+        );
+        assert_eq!(
+            ChecksState::from_check_run("completed", Some("timed_out")),
+            Failing
+        );
+        assert_eq!(ChecksState::from_status("error"), Failing);
+        assert_eq!(ChecksState::from_status("EXPECTED"), Pending);
+    }
+
+    #[test]
+    fn review_decision_counts_each_reviewers_latest_review() {
+        assert_eq!(ReviewDecision::from_reviews([]), None);
+        assert_eq!(
+            ReviewDecision::from_reviews([("ana", "COMMENTED"), ("bo", "APPROVED")]),
+            Some(ReviewDecision::Approved)
+        );
+        assert_eq!(
+            ReviewDecision::from_reviews([
+                ("ana", "CHANGES_REQUESTED"),
+                ("bo", "APPROVED"),
+                ("ana", "APPROVED"),
+            ]),
+            Some(ReviewDecision::Approved)
+        );
+        assert_eq!(
+            ReviewDecision::from_reviews([("bo", "APPROVED"), ("ana", "CHANGES_REQUESTED")]),
+            Some(ReviewDecision::ChangesRequested)
+        );
+        assert_eq!(
+            ReviewDecision::from_reviews([("ana", "APPROVED"), ("ana", "DISMISSED")]),
+            None
+        );
+        assert_eq!(
+            ReviewDecision::parse("REVIEW_REQUIRED"),
+            Some(ReviewDecision::ReviewRequired)
+        );
+        assert_eq!(ReviewDecision::parse(""), None);
+    }
+
+    #[test]
+    fn merge_method_parses_github_names() {
+        for method in [MergeMethod::Merge, MergeMethod::Squash, MergeMethod::Rebase] {
+            assert_eq!(MergeMethod::parse(method.as_str()), Some(method));
+        }
+        assert_eq!(MergeMethod::parse("Squash"), Some(MergeMethod::Squash));
+        assert_eq!(MergeMethod::parse("fast-forward"), None);
+    }
+}
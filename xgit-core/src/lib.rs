@@ -0,0 +1,76 @@
+//! Repository, GitHub and AI layers of [xgit](https://github.com/LiXuanqi/xgit), usable
+//! without the `xg` command line interface.
+//!
+//! - [`git`]: a [`GitRepo`] handle built on libgit2 for branches, commits, diffs, remotes
+//!   and merges
+//! - [`github`]: pull request lookup and caching for stacked-PR workflows
+//! - [`forge`]: the [`ForgeProvider`] trait with GitHub, GitLab and Bitbucket behind it,
+//!   chosen by remote URL
+//! - [`ai`]: commit message generation through the `claude` CLI
+//! - [`config`]: xgit's layered settings from `~/.config/xgit/config.toml`, `.xgit.toml`
+//!   and `XGIT_*` environment variables
+//!
+//! ```no_run
+//! use xgit_core::GitRepo;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let repo = GitRepo::open(".")?;
+//! println!("on {}", repo.get_current_branch()?);
+//! for commit in repo.list_commits()?.iter().take(5) {
+//!     println!("{} {}", &commit.hash[..7], commit.message.lines().next().unwrap_or(""));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Repository operations return [`GitError`], whose variant tells apart missing objects,
+//! conflicts, dirty worktrees, unborn branches and authentication or network failures. Each
+//! variant keeps the context chain describing what failed, and [`GitError::git2_code`]
+//! exposes the underlying libgit2 code when there is one.
+//!
+//! With the `async` feature, network operations also come as `*_async` methods (for example
+//! `GitRepo::fetch_async`) that run on tokio's blocking pool instead of the calling thread.
+//! With the `gix` feature, read-only operations can also run on gitoxide through
+//! [`GitRepo::read_backend`].
+//!
+//! Operations that change the repository raise a [`GitEvent`] to callbacks registered
+//! with [`GitRepo::subscribe`], or to a channel from [`GitRepo::events`], so consumers can
+//! react without polling.
+//!
+//! A [`GitRepo`] can move between threads but not be shared; use [`GitRepoPool`] to hand
+//! handles to parallel workers.
+//!
+//! With the `test-utils` feature, `test_utils` provides the throwaway repositories and fluent
+//! helpers xgit's own tests are written with, for use in the tests of crates built on this one.
+
+pub mod ai;
+pub mod config;
+pub mod forge;
+pub mod git;
+pub mod github;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+pub use config::{AiProvider, XgitConfig};
+pub use forge::ForgeProvider;
+pub use git::branches::merge_cache::MergeBaseCache;
+pub use git::branches::protected::ProtectedBranches;
+pub use git::commits::blame::BlameLine;
+pub use git::commits::builder::{CommitOptions, CommitTime, FixedSignature, Identity};
+pub use git::commits::cherry_pick::CherryPickOutcome;
+pub use git::commits::diff_summary::{DiffSummary, FileDiffSummary, HunkRange};
+pub use git::commits::file_history::FileHistoryEntry;
+pub use git::commits::log::LogOptions;
+pub use git::commits::status::{FileChangeKind, RepoStatus, StatusEntry};
+pub use git::merge::conflicts::ConflictEntry;
+pub use git::merge::operations::{MergeOptions, MergeOutcome};
+pub use git::merge::pull::PullStrategy;
+pub use git::rebase::operations::RebaseOutcome;
+pub use git::remotes::sync::FetchOutcome;
+pub use git::repository::core::{CommitInfo, RemoteInfo};
+pub use git::repository::reflog::{ReflogEntry, UndoPlan};
+pub use git::stash::operations::StashEntry;
+pub use git::tags::operations::TagInfo;
+pub use git::{GitError, GitEvent, GitRepo, GitRepoPool};
+pub use github::GitHubPrMatcher;
@@ -1,4 +1,6 @@
+use crate::git::merge::operations::MergeOutcome;
 use crate::git::GitRepo;
+use crate::test_utils::repo_extensions::worktree_file;
 use anyhow::{Context, Error};
 use std::ops::Deref;
 use std::result::Result::Ok;
@@ -9,19 +11,21 @@ pub struct GitRepoTestDecorator {
 }
 
 impl GitRepoTestDecorator {
+    /// Wrap a repository; the decorator derefs to it
     pub fn new(git_repo: GitRepo) -> Self {
         Self { inner: git_repo }
     }
 
+    /// Write a file in the working tree, replacing any existing content
     pub fn add_file(&self, filename: &str, content: &str) -> Result<&Self, Error> {
-        let file_path = self.inner.path().join(filename);
-        std::fs::write(file_path, content).unwrap();
+        let file_path = worktree_file(&self.inner, filename);
+        std::fs::write(file_path, content)?;
         Ok(self)
     }
 
     /// Append a new line to an existing file
     pub fn append_to_file(&self, filename: &str, content: &str) -> Result<&Self, Error> {
-        let file_path = self.inner.path().join(filename);
+        let file_path = worktree_file(&self.inner, filename);
 
         // Read existing content
         let mut existing_content = std::fs::read_to_string(&file_path)
@@ -42,6 +46,7 @@ impl GitRepoTestDecorator {
         Ok(self)
     }
 
+    /// Write a file, stage it and commit it
     pub fn add_file_and_commit(
         &self,
         filename: &str,
@@ -76,11 +81,12 @@ impl GitRepoTestDecorator {
             .to_str()
             .context("Failed to convert remote repository path to string")?;
 
-        self.add_remote(name, remote_path)
+        Ok(self.add_remote(name, remote_path)?)
     }
 
     // ===================== Assert functions ==================
 
+    /// Assert that commit messages match the expected order (newest first)
     pub fn assert_commit_messages(&self, expected_messages: &[&str]) -> &Self {
         let commits = self.list_commits().unwrap_or_else(|_| Vec::new());
 
@@ -122,6 +128,7 @@ impl GitRepoTestDecorator {
         self
     }
 
+    /// Assert that the current branch matches the expected branch name
     pub fn assert_current_branch(&self, branch_name: &str) -> &Self {
         let expected_target = format!("refs/heads/{branch_name}");
         self.assert_head_symbolic_target(&expected_target);
@@ -131,7 +138,7 @@ impl GitRepoTestDecorator {
 
     /// Assert that a file exists in the repository
     pub fn assert_file_exists(&self, filename: &str) -> &Self {
-        let file_path = self.inner.path().join(filename);
+        let file_path = worktree_file(&self.inner, filename);
         if !file_path.exists() {
             panic!("Expected file '{filename}' to exist at path: {file_path:?}");
         }
@@ -140,7 +147,7 @@ impl GitRepoTestDecorator {
 
     /// Assert that a file does not exist in the repository
     pub fn assert_file_not_exists(&self, filename: &str) -> &Self {
-        let file_path = self.inner.path().join(filename);
+        let file_path = worktree_file(&self.inner, filename);
         if file_path.exists() {
             panic!("Expected file '{filename}' to not exist at path: {file_path:?}");
         }
@@ -175,7 +182,12 @@ impl GitRepoTestDecorator {
 
     /// Merge branch (fluent wrapper)
     pub fn merge(&self, branch_name: &str, message: Option<&str>) -> Result<&Self, Error> {
-        self.inner.merge(branch_name, message)?;
+        if let MergeOutcome::Conflicts(paths) = self.inner.merge(branch_name, message)? {
+            return Err(anyhow::anyhow!(
+                "Merging '{branch_name}' conflicted in {}",
+                paths.join(", ")
+            ));
+        }
         Ok(self)
     }
 }
@@ -0,0 +1,474 @@
+use crate::git::GitRepo;
+use anyhow::{Context, Error};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Smart-HTTP git server on a local port, serving bare repositories through
+/// `git http-backend`.
+///
+/// Lets fetch, push and clone run over a real network transport in tests, optionally behind
+/// basic authentication and with injected failures for retry logic. The server and its
+/// repositories go away when it is dropped.
+///
+/// ```
+/// use xgit_core::test_utils::GitHttpServer;
+/// use xgit_core::GitRepo;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let server = GitHttpServer::start()?;
+/// server.create_repo("project")?;
+///
+/// let dir = assert_fs::TempDir::new()?;
+/// let clone = GitRepo::clone(&server.url("project"), dir.path())?;
+/// assert!(clone.get_all_branches()?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub struct GitHttpServer {
+    address: SocketAddr,
+    state: Arc<ServerState>,
+    _root: assert_fs::TempDir,
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+struct ServerState {
+    root: PathBuf,
+    credentials: Option<(String, String)>,
+    requests: Mutex<Vec<String>>,
+    failures_left: AtomicUsize,
+    shutting_down: AtomicBool,
+}
+
+impl GitHttpServer {
+    /// Serve without authentication
+    pub fn start() -> Result<Self, Error> {
+        Self::start_with(None)
+    }
+
+    /// Answer `401 Unauthorized` to any request that does not carry these basic-auth
+    /// credentials
+    pub fn start_with_credentials(username: &str, password: &str) -> Result<Self, Error> {
+        Self::start_with(Some((username.to_string(), password.to_string())))
+    }
+
+    fn start_with(credentials: Option<(String, String)>) -> Result<Self, Error> {
+        let root = assert_fs::TempDir::new()?;
+        let listener = TcpListener::bind("127.0.0.1:0").context("Failed to bind test server")?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(ServerState {
+            root: root.path().to_path_buf(),
+            credentials,
+            requests: Mutex::new(Vec::new()),
+            failures_left: AtomicUsize::new(0),
+            shutting_down: AtomicBool::new(false),
+        });
+
+        let accept_state = Arc::clone(&state);
+        let accept_thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accept_state.shutting_down.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let state = Arc::clone(&accept_state);
+                std::thread::spawn(move || {
+                    // A client hanging up mid-request is not the test's concern
+                    let _ = handle_connection(&state, stream);
+                });
+            }
+        });
+
+        Ok(Self {
+            address,
+            state,
+            _root: root,
+            accept_thread: Some(accept_thread),
+        })
+    }
+
+    /// Create an empty bare repository served at [`url(name)`](Self::url) that accepts pushes
+    pub fn create_repo(&self, name: &str) -> Result<GitRepo, Error> {
+        let repo = GitRepo::init_bare(self.state.root.join(format!("{name}.git")))?;
+        repo.repo()
+            .config()?
+            .set_bool("http.receivepack", true)
+            .context("Failed to enable pushes over HTTP")?;
+        Ok(repo)
+    }
+
+    /// `http://` URL of repository `name`
+    pub fn url(&self, name: &str) -> String {
+        format!("http://{}/{name}.git", self.address)
+    }
+
+    /// `http://` URL of repository `name` with `username:password@` embedded
+    pub fn url_with_credentials(&self, name: &str, username: &str, password: &str) -> String {
+        format!("http://{username}:{password}@{}/{name}.git", self.address)
+    }
+
+    /// Answer the next `count` requests with `503 Service Unavailable`
+    pub fn fail_next_requests(&self, count: usize) {
+        self.state.failures_left.store(count, Ordering::SeqCst);
+    }
+
+    /// Requests received so far as `"<METHOD> <path> <status>"`, oldest first
+    pub fn requests(&self) -> Vec<String> {
+        self.state
+            .requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for GitHttpServer {
+    fn drop(&mut self) {
+        self.state.shutting_down.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.accept_thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn handle_connection(state: &ServerState, stream: TcpStream) -> Result<(), Error> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let request = read_request(&mut reader)?;
+    let mut stream = stream;
+
+    let response = if state
+        .failures_left
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok()
+    {
+        Response {
+            status: 503,
+            headers: Vec::new(),
+            body: b"Injected failure\n".to_vec(),
+        }
+    } else if !is_authorized(state, &request) {
+        Response {
+            status: 401,
+            headers: vec![(
+                "WWW-Authenticate".to_string(),
+                "Basic realm=\"xgit test\"".to_string(),
+            )],
+            body: Vec::new(),
+        }
+    } else {
+        run_http_backend(state, &request)?
+    };
+    let Response {
+        status,
+        headers,
+        body,
+    } = response;
+
+    state
+        .requests
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(format!("{} {} {status}", request.method, request.path));
+
+    write!(stream, "HTTP/1.1 {status} {}\r\n", reason(status))?;
+    for (name, value) in &headers {
+        write!(stream, "{name}: {value}\r\n")?;
+    }
+    write!(
+        stream,
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Error> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().context("Empty request")?.to_string();
+    let target = parts.next().context("Request has no target")?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), query.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let mut request = Request {
+        method,
+        path,
+        query,
+        headers,
+        body: Vec::new(),
+    };
+    if request
+        .header("Transfer-Encoding")
+        .map_or(false, |value| value.eq_ignore_ascii_case("chunked"))
+    {
+        request.body = read_chunked_body(reader)?;
+    } else if let Some(length) = request.header("Content-Length") {
+        let mut body = vec![0; length.parse()?];
+        reader.read_exact(&mut body)?;
+        request.body = body;
+    }
+    Ok(request)
+}
+
+fn read_chunked_body(reader: &mut impl BufRead) -> Result<Vec<u8>, Error> {
+    let mut body = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let size_field = line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size_field, 16).context("Invalid chunk size")?;
+        if size == 0 {
+            // Skip trailers up to the blank line ending the body
+            loop {
+                line.clear();
+                reader.read_line(&mut line)?;
+                if line.trim_end().is_empty() {
+                    return Ok(body);
+                }
+            }
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        line.clear();
+        reader.read_line(&mut line)?;
+    }
+}
+
+fn is_authorized(state: &ServerState, request: &Request) -> bool {
+    let Some((username, password)) = &state.credentials else {
+        return true;
+    };
+    let expected = format!(
+        "Basic {}",
+        base64(format!("{username}:{password}").as_bytes())
+    );
+    request.header("Authorization") == Some(expected.as_str())
+}
+
+/// Hand the request to `git http-backend` as a CGI script
+fn run_http_backend(state: &ServerState, request: &Request) -> Result<Response, Error> {
+    let mut command = Command::new("git");
+    command
+        .arg("http-backend")
+        .env("GIT_PROJECT_ROOT", &state.root)
+        .env("GIT_HTTP_EXPORT_ALL", "1")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("REQUEST_METHOD", &request.method)
+        .env("PATH_INFO", &request.path)
+        .env("QUERY_STRING", &request.query)
+        .env("CONTENT_LENGTH", request.body.len().to_string())
+        .env("REMOTE_ADDR", "127.0.0.1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    if let Some(content_type) = request.header("Content-Type") {
+        command.env("CONTENT_TYPE", content_type);
+    }
+    if let Some(protocol) = request.header("Git-Protocol") {
+        command.env("GIT_PROTOCOL", protocol);
+    }
+    if let Some((username, _)) = &state.credentials {
+        command.env("REMOTE_USER", username);
+    }
+
+    let mut child = command
+        .spawn()
+        .context("Failed to run `git http-backend`")?;
+    let mut stdin = child.stdin.take().context("No stdin for http-backend")?;
+    let body = request.body.clone();
+    // Write on another thread so a large response cannot block on a full stdout pipe
+    let writer = std::thread::spawn(move || stdin.write_all(&body));
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+
+    parse_cgi_response(&output.stdout)
+}
+
+fn parse_cgi_response(output: &[u8]) -> Result<Response, Error> {
+    let (head, body) = [&b"\r\n\r\n"[..], b"\n\n"]
+        .iter()
+        .find_map(|separator| {
+            output
+                .windows(separator.len())
+                .position(|window| window == *separator)
+                .map(|at| (&output[..at], &output[at + separator.len()..]))
+        })
+        .context("`git http-backend` returned no headers")?;
+
+    let mut status = 200;
+    let mut headers = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("Status") {
+            status = value
+                .split_whitespace()
+                .next()
+                .and_then(|code| code.parse().ok())
+                .unwrap_or(500);
+        } else {
+            headers.push((name.trim().to_string(), value.to_string()));
+        }
+    }
+    Ok(Response {
+        status,
+        headers,
+        body: body.to_vec(),
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((input.len() + 2) / 3 * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, GitHttpServer};
+    use crate::git::{GitError, GitRepo};
+    use crate::test_utils::{create_test_repo, RepoTestOperations};
+
+    #[test]
+    fn base64_pads_partial_groups() {
+        assert_eq!(base64(b"user:pass"), "dXNlcjpwYXNz");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+    }
+
+    #[test]
+    fn push_fetch_and_clone_over_http() -> Result<(), anyhow::Error> {
+        let server = GitHttpServer::start()?;
+        server.create_repo("project")?;
+
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?;
+        repo.add_remote("origin", &server.url("project"))?;
+        repo.push("origin", "master")?;
+
+        let clone_dir = assert_fs::TempDir::new()?;
+        let clone = GitRepo::clone(&server.url("project"), clone_dir.path())?;
+        assert_eq!(clone.list_commits()?[0].message, "Initial commit");
+
+        repo.add_file_and_commit("README.md", "hello again", "Update readme")?;
+        repo.push("origin", "master")?;
+        clone.fetch("origin", Some("master"))?;
+        assert_eq!(
+            clone.list_commits_from("origin/master")?[0].message,
+            "Update readme"
+        );
+
+        let requests = server.requests();
+        assert!(
+            requests
+                .iter()
+                .any(|r| r == "POST /project.git/git-receive-pack 200"),
+            "{requests:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn credentials_are_required_when_configured() -> Result<(), anyhow::Error> {
+        let server = GitHttpServer::start_with_credentials("alice", "secret")?;
+        server.create_repo("private")?;
+        let clone_dir = assert_fs::TempDir::new()?;
+
+        let err = GitRepo::clone(&server.url("private"), clone_dir.path().join("anonymous"))
+            .err()
+            .unwrap();
+        assert!(matches!(err, GitError::AuthRequired(_)), "{err:?}");
+
+        let url = server.url_with_credentials("private", "alice", "secret");
+        GitRepo::clone(&url, clone_dir.path().join("authorized"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn injected_failures_answer_with_503() -> Result<(), anyhow::Error> {
+        let server = GitHttpServer::start()?;
+        server.create_repo("flaky")?;
+        server.fail_next_requests(1);
+        let clone_dir = assert_fs::TempDir::new()?;
+
+        assert!(GitRepo::clone(&server.url("flaky"), clone_dir.path().join("first")).is_err());
+        GitRepo::clone(&server.url("flaky"), clone_dir.path().join("second"))?;
+        assert!(server.requests()[0].ends_with(" 503"));
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use crate::forge::ForgeProvider;
+use crate::github::types::{
+    now_timestamp, MergeMethod, PrListFilter, PullRequestChecks, PullRequestListing,
+    PullRequestRecord, PullRequestSnapshot, PullRequestStatus,
+};
+use anyhow::Error;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// In-memory [`ForgeProvider`] that serves canned pull requests and records every call.
+///
+/// Clones share state, so a test can hand one clone to the code under test and keep another
+/// to inspect [`calls`](MockForge::calls) afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct MockForge {
+    state: Arc<Mutex<MockForgeState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockForgeState {
+    default_branch: Option<String>,
+    pull_requests: Vec<PullRequestRecord>,
+    /// PR number → description
+    bodies: HashMap<u64, String>,
+    calls: Vec<String>,
+    offline: bool,
+}
+
+impl MockForge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `record` from lookups by number, head branch, and its head or `commit_shas`, and
+    /// its `checks` and `review_decision` from check lookups
+    pub fn with_pr(self, record: PullRequestRecord) -> Self {
+        self.state().pull_requests.push(record);
+        self
+    }
+
+    /// Give PR `pr_number` the description `body`
+    pub fn with_pr_body(self, pr_number: u64, body: &str) -> Self {
+        self.state().bodies.insert(pr_number, body.to_string());
+        self
+    }
+
+    pub fn with_default_branch(self, branch: &str) -> Self {
+        self.state().default_branch = Some(branch.to_string());
+        self
+    }
+
+    /// Fail every call as if the forge were unreachable
+    pub fn offline(self) -> Self {
+        self.state().offline = true;
+        self
+    }
+
+    /// Calls made so far, one `"<method> <args>"` entry each
+    pub fn calls(&self) -> Vec<String> {
+        self.state().calls.clone()
+    }
+
+    /// Pull requests as the forge currently has them, including created and updated ones
+    pub fn pull_requests(&self) -> Vec<PullRequestRecord> {
+        self.state().pull_requests.clone()
+    }
+
+    /// The description PR `pr_number` has now, empty when it has none
+    pub fn pr_body(&self, pr_number: u64) -> String {
+        self.state()
+            .bodies
+            .get(&pr_number)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// A `octo/repo` pull request from `head` into `main`
+    pub fn pull_request(
+        pr_number: u64,
+        head: &str,
+        status: PullRequestStatus,
+    ) -> PullRequestRecord {
+        PullRequestRecord::from_snapshot(PullRequestSnapshot {
+            repo_slug: "octo/repo".to_string(),
+            pr_number,
+            title: format!("PR for {head}"),
+            url: format!("https://github.com/octo/repo/pull/{pr_number}"),
+            base_ref: "main".to_string(),
+            head_ref: head.to_string(),
+            head_sha: String::new(),
+            draft: false,
+            status,
+            created_at: None,
+            merged_at: None,
+        })
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockForgeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a call and fail it when offline
+    fn record(&self, call: String) -> Result<MutexGuard<'_, MockForgeState>, Error> {
+        let mut state = self.state();
+        state.calls.push(call.clone());
+        if state.offline {
+            return Err(anyhow::anyhow!("Mock forge is offline: {call}"));
+        }
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for MockForge {
+    async fn get_default_branch(&self) -> Result<String, Error> {
+        let state = self.record("get_default_branch".to_string())?;
+        Ok(state
+            .default_branch
+            .clone()
+            .unwrap_or_else(|| "main".to_string()))
+    }
+
+    async fn get_pr(&self, pr_number: u64) -> Result<PullRequestRecord, Error> {
+        let state = self.record(format!("get_pr {pr_number}"))?;
+        state
+            .pull_requests
+            .iter()
+            .find(|pr| pr.pr_number == pr_number)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Mock forge has no PR #{pr_number}"))
+    }
+
+    async fn get_pr_body(&self, pr_number: u64) -> Result<String, Error> {
+        let state = self.record(format!("get_pr_body {pr_number}"))?;
+        Ok(state.bodies.get(&pr_number).cloned().unwrap_or_default())
+    }
+
+    async fn find_pr_by_head(&self, head_branch: &str) -> Result<Option<PullRequestRecord>, Error> {
+        let state = self.record(format!("find_pr_by_head {head_branch}"))?;
+        Ok(state
+            .pull_requests
+            .iter()
+            .find(|pr| pr.head_ref == head_branch)
+            .cloned())
+    }
+
+    async fn find_pr_by_head_with_owner(
+        &self,
+        owner: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let state = self.record(format!("find_pr_by_head_with_owner {owner}:{head_branch}"))?;
+        Ok(state
+            .pull_requests
+            .iter()
+            .find(|pr| pr.head_ref == head_branch)
+            .cloned())
+    }
+
+    async fn find_pr_by_commit(
+        &self,
+        commit_sha: &str,
+    ) -> Result<Option<PullRequestRecord>, Error> {
+        let state = self.record(format!("find_pr_by_commit {commit_sha}"))?;
+        Ok(state
+            .pull_requests
+            .iter()
+            .find(|pr| {
+                pr.head_sha == commit_sha || pr.commit_shas.iter().any(|sha| sha == commit_sha)
+            })
+            .cloned())
+    }
+
+    async fn get_pr_checks(
+        &self,
+        pr_number: u64,
+        head_sha: &str,
+    ) -> Result<PullRequestChecks, Error> {
+        let state = self.record(format!("get_pr_checks {pr_number} {head_sha}"))?;
+        let record = state
+            .pull_requests
+            .iter()
+            .find(|pr| pr.pr_number == pr_number)
+            .ok_or_else(|| anyhow::anyhow!("Mock forge has no PR #{pr_number}"))?;
+        Ok(PullRequestChecks {
+            checks: record.checks,
+            review_decision: record.review_decision,
+        })
+    }
+
+    /// Open PRs as authored by `octocat` with no labels or review requests, newest first
+    async fn list_open_prs(&self, filter: &PrListFilter) -> Result<Vec<PullRequestListing>, Error> {
+        let state = self.record("list_open_prs".to_string())?;
+        Ok(state
+            .pull_requests
+            .iter()
+            .rev()
+            .filter(|pr| pr.status == PullRequestStatus::Open)
+            .map(|pr| PullRequestListing {
+                record: pr.clone(),
+                author: "octocat".to_string(),
+                labels: Vec::new(),
+                requested_reviewers: Vec::new(),
+                is_cross_repository: false,
+            })
+            .filter(|listing| filter.matches(listing, Some("octocat")))
+            .collect())
+    }
+
+    async fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        head: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequestRecord, Error> {
+        let mut state = self.record(format!("create_pr {head} -> {base}"))?;
+        let pr_number = state
+            .pull_requests
+            .iter()
+            .map(|pr| pr.pr_number)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mut record = Self::pull_request(pr_number, head, PullRequestStatus::Open);
+        record.title = title.to_string();
+        record.base_ref = base.to_string();
+        record.draft = draft;
+        if let Some(body) = body {
+            state.bodies.insert(pr_number, body.to_string());
+        }
+        state.pull_requests.push(record.clone());
+        Ok(record)
+    }
+
+    async fn update_pr(
+        &self,
+        pr_number: u64,
+        base: Option<&str>,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<PullRequestRecord, Error> {
+        let mut state = self.record(format!("update_pr {pr_number}"))?;
+        if let Some(body) = body {
+            state.bodies.insert(pr_number, body.to_string());
+        }
+        let record = state
+            .pull_requests
+            .iter_mut()
+            .find(|pr| pr.pr_number == pr_number)
+            .ok_or_else(|| anyhow::anyhow!("Mock forge has no PR #{pr_number}"))?;
+        if let Some(base) = base {
+            record.base_ref = base.to_string();
+        }
+        if let Some(title) = title {
+            record.title = title.to_string();
+        }
+        record.mark_refreshed();
+        Ok(record.clone())
+    }
+
+    /// Merges open PRs whose head is still `head_sha`
+    async fn merge_pr(
+        &self,
+        pr_number: u64,
+        method: MergeMethod,
+        head_sha: &str,
+    ) -> Result<(), Error> {
+        let mut state = self.record(format!(
+            "merge_pr {pr_number} {} {head_sha}",
+            method.as_str()
+        ))?;
+        let record = state
+            .pull_requests
+            .iter_mut()
+            .find(|pr| pr.pr_number == pr_number)
+            .ok_or_else(|| anyhow::anyhow!("Mock forge has no PR #{pr_number}"))?;
+        if record.status != PullRequestStatus::Open {
+            return Err(anyhow::anyhow!("PR #{pr_number} is not open"));
+        }
+        if record.head_sha != head_sha {
+            return Err(anyhow::anyhow!("Head of PR #{pr_number} was modified"));
+        }
+        record.status = PullRequestStatus::Merged;
+        record.merged_at = Some(now_timestamp());
+        record.mark_refreshed();
+        Ok(())
+    }
+}
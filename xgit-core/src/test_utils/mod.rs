@@ -0,0 +1,48 @@
+//! Helpers for building throwaway repositories in tests (`test-utils` feature).
+//!
+//! [`create_test_repo`] and [`create_test_bare_repo`] make a repository in a temporary
+//! directory that is removed when the returned [`TempDir`](assert_fs::TempDir) is dropped,
+//! with a test identity configured. [`RepoTestOperations`] adds fluent file and commit
+//! helpers to [`GitRepo`](crate::GitRepo) and [`RepoAssertions`] adds assertions that panic
+//! with a descriptive message. [`MockForge`] stands in for GitHub when testing pull request
+//! lookups, and [`RepoBuilder`] lays out branches, merges, tags and remotes declaratively
+//! when a test needs more than a linear history. [`GitHttpServer`] serves repositories over
+//! smart HTTP for tests of fetch, push and clone over a real transport.
+//!
+//! ```
+//! use xgit_core::test_utils::{create_test_repo, RepoAssertions, RepoTestOperations};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let (_dir, repo) = create_test_repo();
+//! repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+//!     .create_and_checkout_branch("feature")?
+//!     .add_file_and_commit("feature.txt", "feature", "Add feature")?;
+//!
+//! repo.assert_current_branch("feature")
+//!     .assert_file_exists("feature.txt")
+//!     .assert_commit_messages(&["Add feature", "Initial commit"]);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod git_repo_test_decorator;
+
+pub mod http_server;
+
+pub mod mock_forge;
+
+pub mod repo_builder;
+
+pub mod repo_extensions;
+
+pub use git_repo_test_decorator::GitRepoTestDecorator;
+
+pub use http_server::GitHttpServer;
+
+pub use mock_forge::MockForge;
+
+pub use repo_builder::RepoBuilder;
+
+pub use repo_extensions::{
+    create_test_bare_repo, create_test_repo, test_signature, RepoAssertions, RepoTestOperations,
+};
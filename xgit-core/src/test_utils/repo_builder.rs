@@ -0,0 +1,295 @@
+use crate::git::merge::operations::MergeOutcome;
+use crate::git::GitRepo;
+use crate::test_utils::{test_signature, RepoTestOperations};
+use anyhow::{Context, Error};
+use git2::BranchType;
+
+/// Declarative builder for repositories with a realistic history.
+///
+/// Steps run in order against a fresh repository when [`build`](RepoBuilder::build) is
+/// called. [`branch`](RepoBuilder::branch) runs its steps on the named branch and then
+/// returns to the branch it started from, so a history reads top to bottom. Commits are
+/// stamped with [`test_signature`], so the same steps always produce the same hashes:
+///
+/// ```
+/// use xgit_core::test_utils::RepoBuilder;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let (_dir, repo) = RepoBuilder::new()
+///     .commit("README.md", "hello", "Initial commit")
+///     .remote("origin")
+///     .push("origin", "master")
+///     .branch("feature", |b| b.commit("feature.txt", "feature", "Add feature"))
+///     .merge("feature")
+///     .tag("v1.0")
+///     .build()?;
+///
+/// assert_eq!(repo.get_remote_tracking_info("master")?, "origin/master");
+/// assert_eq!(repo.ahead_behind("master", "origin/master")?, (1, 0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+#[must_use]
+pub struct RepoBuilder {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug)]
+enum Step {
+    Commit {
+        path: String,
+        content: String,
+        message: String,
+    },
+    Branch {
+        name: String,
+        steps: Vec<Step>,
+    },
+    Checkout(String),
+    Merge {
+        branch: String,
+        expect_conflicts: bool,
+    },
+    Tag(String),
+    Remote(String),
+    Push {
+        remote: String,
+        branch: String,
+    },
+}
+
+impl RepoBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write `content` to `path` and commit it on the current branch
+    pub fn commit(mut self, path: &str, content: &str, message: &str) -> Self {
+        self.steps.push(Step::Commit {
+            path: path.to_string(),
+            content: content.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
+    /// Run `steps` on branch `name`, creating it from the current branch if needed, then
+    /// switch back
+    pub fn branch(mut self, name: &str, steps: impl FnOnce(RepoBuilder) -> RepoBuilder) -> Self {
+        self.steps.push(Step::Branch {
+            name: name.to_string(),
+            steps: steps(RepoBuilder::new()).steps,
+        });
+        self
+    }
+
+    /// Switch to an existing branch for the steps that follow
+    pub fn checkout(mut self, name: &str) -> Self {
+        self.steps.push(Step::Checkout(name.to_string()));
+        self
+    }
+
+    /// Merge `branch` into the current branch, fast-forwarding when possible
+    pub fn merge(mut self, branch: &str) -> Self {
+        self.steps.push(Step::Merge {
+            branch: branch.to_string(),
+            expect_conflicts: false,
+        });
+        self
+    }
+
+    /// Start merging `branch` and leave the conflicts it produces in the working tree.
+    ///
+    /// Building fails if the merge does not conflict.
+    pub fn merge_with_conflicts(mut self, branch: &str) -> Self {
+        self.steps.push(Step::Merge {
+            branch: branch.to_string(),
+            expect_conflicts: true,
+        });
+        self
+    }
+
+    /// Create a lightweight tag at HEAD
+    pub fn tag(mut self, name: &str) -> Self {
+        self.steps.push(Step::Tag(name.to_string()));
+        self
+    }
+
+    /// Add a remote backed by a bare repository in the same temporary directory
+    pub fn remote(mut self, name: &str) -> Self {
+        self.steps.push(Step::Remote(name.to_string()));
+        self
+    }
+
+    /// Push `branch` to `remote` and make it the branch's upstream
+    pub fn push(mut self, remote: &str, branch: &str) -> Self {
+        self.steps.push(Step::Push {
+            remote: remote.to_string(),
+            branch: branch.to_string(),
+        });
+        self
+    }
+
+    /// Create the repository and run every step.
+    ///
+    /// The repository lives in `repo/` under the returned directory, next to the bare
+    /// repositories of any remotes in `remotes/`.
+    pub fn build(self) -> Result<(assert_fs::TempDir, GitRepo), Error> {
+        let temp_dir = assert_fs::TempDir::new()?;
+        let repo = GitRepo::init(temp_dir.path().join("repo"))?;
+        repo.set_user_config("Test User", "test@example.com")?;
+        repo.set_fixed_signature(Some(test_signature()));
+        for step in self.steps {
+            run_step(&temp_dir, &repo, step)?;
+        }
+        Ok((temp_dir, repo))
+    }
+}
+
+fn run_step(temp_dir: &assert_fs::TempDir, repo: &GitRepo, step: Step) -> Result<(), Error> {
+    match step {
+        Step::Commit {
+            path,
+            content,
+            message,
+        } => {
+            if let Some(parent) = std::path::Path::new(&path).parent() {
+                std::fs::create_dir_all(repo.path().join(parent))?;
+            }
+            repo.add_file_and_commit(&path, &content, &message)?;
+        }
+        Step::Branch { name, steps } => {
+            let previous = repo
+                .get_current_branch()
+                .context("Branch steps need a checked-out branch with a commit")?;
+            if repo.get_all_branches()?.contains(&name) {
+                repo.checkout_branch(&name)?;
+            } else {
+                repo.create_and_checkout_branch(&name)?;
+            }
+            for step in steps {
+                run_step(temp_dir, repo, step)?;
+            }
+            repo.checkout_branch(&previous)?;
+        }
+        Step::Checkout(name) => {
+            repo.checkout_branch(&name)?;
+        }
+        Step::Merge {
+            branch,
+            expect_conflicts,
+        } => match (repo.merge(&branch, None)?, expect_conflicts) {
+            (MergeOutcome::Conflicts(_), true) => {}
+            (MergeOutcome::Conflicts(paths), false) => {
+                return Err(anyhow::anyhow!(
+                    "Merging '{branch}' conflicted in {}",
+                    paths.join(", ")
+                ))
+            }
+            (_, true) => {
+                return Err(anyhow::anyhow!(
+                    "Expected merging '{branch}' to conflict, but it merged cleanly"
+                ))
+            }
+            (_, false) => {}
+        },
+        Step::Tag(name) => {
+            let head = repo.repo().head()?.peel(git2::ObjectType::Commit)?;
+            repo.repo()
+                .tag_lightweight(&name, &head, false)
+                .context(format!("Failed to create tag '{name}'"))?;
+        }
+        Step::Remote(name) => {
+            let remote_path = temp_dir.path().join("remotes").join(format!("{name}.git"));
+            GitRepo::init_bare(&remote_path)?;
+            let remote_url = remote_path
+                .to_str()
+                .context("Remote path is not valid UTF-8")?;
+            repo.add_remote(&name, remote_url)?;
+        }
+        Step::Push { remote, branch } => {
+            repo.push(&remote, &branch)?;
+            repo.repo()
+                .find_branch(&branch, BranchType::Local)?
+                .set_upstream(Some(&format!("{remote}/{branch}")))
+                .context(format!("Failed to track '{remote}/{branch}'"))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RepoBuilder;
+    use crate::test_utils::RepoAssertions;
+
+    #[test]
+    fn builds_branches_merges_tags_and_remotes() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = RepoBuilder::new()
+            .commit("README.md", "hello", "Initial commit")
+            .remote("origin")
+            .push("origin", "master")
+            .branch("feature", |b| {
+                b.commit("src/feature.txt", "one", "Start feature").commit(
+                    "src/feature.txt",
+                    "two",
+                    "Finish feature",
+                )
+            })
+            .commit("README.md", "hello again", "Update readme")
+            .merge("feature")
+            .tag("v1.0")
+            .build()?;
+
+        repo.assert_current_branch("master")
+            .assert_file_exists("src/feature.txt");
+        let head = repo.repo().head()?.target().unwrap();
+        assert_eq!(repo.get_commit_parent_count(&head.to_string())?, 2);
+        assert_eq!(repo.get_remote_tracking_info("master")?, "origin/master");
+        assert_eq!(repo.ahead_behind("master", "origin/master")?, (4, 0));
+        assert_eq!(repo.repo().revparse_single("v1.0")?.id(), head);
+        Ok(())
+    }
+
+    #[test]
+    fn leaves_conflicting_edits_unresolved() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = RepoBuilder::new()
+            .commit("shared.txt", "base", "Initial commit")
+            .branch("left", |b| b.commit("shared.txt", "left", "Left edit"))
+            .commit("shared.txt", "right", "Right edit")
+            .merge_with_conflicts("left")
+            .build()?;
+
+        assert!(repo.repo().index()?.has_conflicts());
+
+        let clean_merge = RepoBuilder::new()
+            .commit("a.txt", "a", "Initial commit")
+            .branch("other", |b| b.commit("b.txt", "b", "Other file"))
+            .merge_with_conflicts("other")
+            .build();
+        assert!(clean_merge.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn same_steps_give_the_same_hashes() -> Result<(), anyhow::Error> {
+        let build = || {
+            RepoBuilder::new()
+                .commit("a.txt", "a", "Initial commit")
+                .branch("feature", |b| b.commit("b.txt", "b", "Add b"))
+                .commit("c.txt", "c", "Add c")
+                .merge("feature")
+                .build()
+        };
+        let (_first_dir, first) = build()?;
+        let (_second_dir, second) = build()?;
+
+        let hashes = |repo: &crate::GitRepo| -> Result<Vec<String>, anyhow::Error> {
+            Ok(repo.list_commits()?.into_iter().map(|c| c.hash).collect())
+        };
+        assert_eq!(hashes(&first)?, hashes(&second)?);
+        assert_eq!(first.list_commits()?[0].time, 1_112_904_793);
+        Ok(())
+    }
+}
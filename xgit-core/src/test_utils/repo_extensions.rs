@@ -1,8 +1,29 @@
+use std::path::PathBuf;
+
+use crate::git::commits::builder::{CommitTime, FixedSignature, Identity};
+use crate::git::merge::operations::MergeOutcome;
 use crate::git::GitRepo;
 use anyhow::{Context, Error};
 
-/// Create a new temporary repository for testing with user config set up
-#[cfg(test)]
+/// The identity and time test repositories stamp on their commits: `Test User
+/// <test@example.com>` at 2005-04-07 22:13:13 +0200, git's first commit
+pub fn test_signature() -> FixedSignature {
+    FixedSignature {
+        identity: Identity {
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+        },
+        time: CommitTime {
+            seconds: 1_112_904_793,
+            offset_minutes: 120,
+        },
+    }
+}
+
+/// Create a new temporary repository for testing with user config set up.
+///
+/// Commits take their time from the clock; call `repo.set_fixed_signature(Some(test_signature()))`
+/// when a test needs reproducible hashes.
 pub fn create_test_repo() -> (assert_fs::TempDir, GitRepo) {
     let temp_dir = assert_fs::TempDir::new().unwrap();
     let path = temp_dir.path();
@@ -13,7 +34,6 @@ pub fn create_test_repo() -> (assert_fs::TempDir, GitRepo) {
 }
 
 /// Create a new temporary bare repository for testing
-#[cfg(test)]
 pub fn create_test_bare_repo() -> (assert_fs::TempDir, GitRepo) {
     let temp_dir = assert_fs::TempDir::new().unwrap();
     let path = temp_dir.path();
@@ -23,8 +43,14 @@ pub fn create_test_bare_repo() -> (assert_fs::TempDir, GitRepo) {
     (temp_dir, repo)
 }
 
-/// Test-only trait that adds assertion methods to GitRepo
-#[cfg(test)]
+/// Path of `filename` in the repository's working tree
+pub(crate) fn worktree_file(repo: &GitRepo, filename: &str) -> PathBuf {
+    repo.workdir()
+        .expect("file helpers need a repository with a working tree")
+        .join(filename)
+}
+
+/// Assertions on a [`GitRepo`] that panic with a descriptive message when they fail
 pub trait RepoAssertions {
     /// Assert that HEAD's symbolic target matches the expected value
     fn assert_head_symbolic_target(&self, expected_target: &str) -> &Self;
@@ -40,10 +66,12 @@ pub trait RepoAssertions {
 
     /// Assert that commit messages match the expected order (newest first)
     fn assert_commit_messages(&self, expected_messages: &[&str]) -> &Self;
+
+    /// Assert that exactly these paths have unresolved conflicts in the index
+    fn assert_conflicted_files(&self, expected_files: &[&str]) -> &Self;
 }
 
-/// Test-only trait that adds test helper operations to GitRepo
-#[cfg(test)]
+/// Fluent helpers for setting up repository state in tests
 pub trait RepoTestOperations {
     /// Add a file with content (fluent)
     fn add_file(&self, filename: &str, content: &str) -> Result<&Self, Error>;
@@ -75,9 +103,22 @@ pub trait RepoTestOperations {
 
     /// Merge branch (fluent wrapper that ignores return value)
     fn merge_fluent(&self, branch_name: &str, message: Option<&str>) -> Result<&Self, Error>;
+
+    /// Commit `file` with base content, then diverge: a new `theirs` branch sets it to
+    /// `theirs` and the current branch sets it to `ours`, so merging, rebasing or
+    /// cherry-picking between the two conflicts. The current branch stays checked out.
+    fn create_conflicting_branches(
+        &self,
+        file: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<&Self, Error>;
+
+    /// [`create_conflicting_branches`](Self::create_conflicting_branches), then merge
+    /// `theirs` and leave the conflict unresolved in the index and working tree
+    fn create_merge_conflict(&self, file: &str, ours: &str, theirs: &str) -> Result<&Self, Error>;
 }
 
-#[cfg(test)]
 impl RepoAssertions for GitRepo {
     fn assert_head_symbolic_target(&self, expected_target: &str) -> &Self {
         match self.get_head_symbolic_target() {
@@ -102,7 +143,7 @@ impl RepoAssertions for GitRepo {
     }
 
     fn assert_file_exists(&self, filename: &str) -> &Self {
-        let file_path = self.path().join(filename);
+        let file_path = worktree_file(self, filename);
         if !file_path.exists() {
             panic!("Expected file '{filename}' to exist at path: {file_path:?}");
         }
@@ -110,7 +151,7 @@ impl RepoAssertions for GitRepo {
     }
 
     fn assert_file_not_exists(&self, filename: &str) -> &Self {
-        let file_path = self.path().join(filename);
+        let file_path = worktree_file(self, filename);
         if file_path.exists() {
             panic!("Expected file '{filename}' to not exist at path: {file_path:?}");
         }
@@ -140,18 +181,39 @@ impl RepoAssertions for GitRepo {
 
         self
     }
+
+    fn assert_conflicted_files(&self, expected_files: &[&str]) -> &Self {
+        let index = self.repo().index().expect("Failed to read index");
+        let mut conflicted: Vec<String> = index
+            .conflicts()
+            .expect("Failed to read index conflicts")
+            .filter_map(|conflict| {
+                let conflict = conflict.ok()?;
+                let entry = conflict.our.or(conflict.their).or(conflict.ancestor)?;
+                Some(String::from_utf8_lossy(&entry.path).into_owned())
+            })
+            .collect();
+        conflicted.sort();
+        let mut expected: Vec<&str> = expected_files.to_vec();
+        expected.sort();
+
+        if conflicted != expected {
+            panic!("Conflicted files mismatch. Expected: {expected:?}, Found: {conflicted:?}");
+        }
+
+        self
+    }
 }
 
-#[cfg(test)]
 impl RepoTestOperations for GitRepo {
     fn add_file(&self, filename: &str, content: &str) -> Result<&Self, Error> {
-        let file_path = self.path().join(filename);
+        let file_path = worktree_file(self, filename);
         std::fs::write(file_path, content)?;
         Ok(self)
     }
 
     fn append_to_file(&self, filename: &str, content: &str) -> Result<&Self, Error> {
-        let file_path = self.path().join(filename);
+        let file_path = worktree_file(self, filename);
 
         // Read existing content
         let mut existing_content = std::fs::read_to_string(&file_path)
@@ -204,7 +266,7 @@ impl RepoTestOperations for GitRepo {
             .to_str()
             .context("Failed to convert remote repository path to string")?;
 
-        self.add_remote(name, remote_path)
+        Ok(self.add_remote(name, remote_path)?)
     }
 
     fn commit_fluent(&self, message: &str) -> Result<&Self, Error> {
@@ -213,7 +275,83 @@ impl RepoTestOperations for GitRepo {
     }
 
     fn merge_fluent(&self, branch_name: &str, message: Option<&str>) -> Result<&Self, Error> {
-        self.merge(branch_name, message)?;
+        if let MergeOutcome::Conflicts(paths) = self.merge(branch_name, message)? {
+            return Err(anyhow::anyhow!(
+                "Merging '{branch_name}' conflicted in {}",
+                paths.join(", ")
+            ));
+        }
+        Ok(self)
+    }
+
+    fn create_conflicting_branches(
+        &self,
+        file: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<&Self, Error> {
+        if ours == theirs {
+            return Err(anyhow::anyhow!(
+                "'{file}' cannot conflict when both sides have the same content"
+            ));
+        }
+
+        self.add_file(file, &format!("base version of {file}\n"))?
+            .add(&[file])?;
+        if self.has_staged_changes()? {
+            self.commit_fluent(&format!("Add base version of {file}"))?;
+        }
+
+        let current = self
+            .get_current_branch()
+            .context("Conflicting branches need a checked-out branch")?;
+        self.create_and_checkout_branch("theirs")?;
+        self.add_file_and_commit(file, theirs, &format!("Change {file} on theirs"))?;
+        self.checkout_branch(&current)?;
+        self.add_file_and_commit(file, ours, &format!("Change {file} on {current}"))?;
+
         Ok(self)
     }
+
+    fn create_merge_conflict(&self, file: &str, ours: &str, theirs: &str) -> Result<&Self, Error> {
+        self.create_conflicting_branches(file, ours, theirs)?;
+        match self.merge("theirs", None)? {
+            MergeOutcome::Conflicts(_) => Ok(self),
+            _ => Err(anyhow::anyhow!("Merging 'theirs' did not conflict")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_test_repo, RepoAssertions, RepoTestOperations};
+
+    #[test]
+    fn conflicting_branches_diverge_from_a_common_base() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.create_conflicting_branches("notes.txt", "ours\n", "theirs\n")?;
+
+        repo.assert_current_branch("master")
+            .assert_commit_messages(&[
+                "Change notes.txt on master",
+                "Add base version of notes.txt",
+            ]);
+        assert_eq!(
+            repo.list_commits_from("theirs")?[0].message,
+            "Change notes.txt on theirs"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merge_conflict_is_left_unresolved() -> Result<(), anyhow::Error> {
+        let (_dir, repo) = create_test_repo();
+        repo.add_file_and_commit("README.md", "hello", "Initial commit")?
+            .create_merge_conflict("notes.txt", "ours\n", "theirs\n")?
+            .assert_conflicted_files(&["notes.txt"]);
+
+        let content = std::fs::read_to_string(repo.path().join("notes.txt"))?;
+        assert!(content.contains("<<<<<<<"), "{content}");
+        Ok(())
+    }
 }